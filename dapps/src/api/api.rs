@@ -17,24 +17,40 @@
 use std::sync::Arc;
 use hyper::{server, net, Decoder, Encoder, Next, Control};
 use api::types::{App, ApiError};
-use api::response::{as_json, as_json_error, ping_response};
+use api::response::{as_json, as_json_error, ping_response, health_response};
+use api::settings::{self, SettingsStore, SettingsHandler, SettingsError, DEFAULT_IDENTITY};
 use handlers::extract_url;
 use endpoint::{Endpoint, Endpoints, Handler, EndpointPath};
 use apps::fetcher::ContentFetcher;
+use tokens::{DappTokens, extract_token, is_dapp_endpoint};
+use ethcore_rpc::HealthProvider;
 
 #[derive(Clone)]
 pub struct RestApi {
 	local_domain: String,
 	endpoints: Arc<Endpoints>,
 	fetcher: Arc<ContentFetcher>,
+	health: Option<Arc<HealthProvider>>,
+	settings: Option<Arc<SettingsStore>>,
+	tokens: Arc<DappTokens>,
 }
 
 impl RestApi {
-	pub fn new(local_domain: String, endpoints: Arc<Endpoints>, fetcher: Arc<ContentFetcher>) -> Box<Endpoint> {
+	pub fn new(
+		local_domain: String,
+		endpoints: Arc<Endpoints>,
+		fetcher: Arc<ContentFetcher>,
+		health: Option<Arc<HealthProvider>>,
+		settings: Option<Arc<SettingsStore>>,
+		tokens: Arc<DappTokens>,
+	) -> Box<Endpoint> {
 		Box::new(RestApi {
 			local_domain: local_domain,
 			endpoints: endpoints,
 			fetcher: fetcher,
+			health: health,
+			settings: settings,
+			tokens: tokens,
 		})
 	}
 
@@ -80,6 +96,30 @@ impl RestApiRouter {
 			_ => None
 		}
 	}
+
+	/// Settings are only given to the dapp which sent the request.
+	fn resolve_settings(&self, dapp: Option<&str>, resource: Option<&str>, identity: Option<String>, sender: Option<String>) -> Option<Box<Handler>> {
+		match (self.api.settings.as_ref(), dapp, resource) {
+			(Some(store), Some(dapp), Some("settings")) => match sender {
+				Some(ref sender) if sender == dapp => {
+					let identity = identity.unwrap_or_else(|| DEFAULT_IDENTITY.into());
+					Some(Box::new(SettingsHandler::new(store.clone(), dapp.into(), identity)))
+				},
+				_ => Some(Box::new(settings::error_handler(SettingsError::Unauthorized))),
+			},
+			_ => None
+		}
+	}
+
+	/// Dapp which sent the request, as identified by its token.
+	fn sender(&self, request: &server::Request<net::HttpStream>, path: &EndpointPath) -> Option<String> {
+		extract_token(request)
+			.and_then(|token| self.api.tokens.dapp(&token))
+			.and_then(|dapp| match is_dapp_endpoint(&dapp, path) {
+				true => Some(dapp),
+				false => None,
+			})
+	}
 }
 
 impl server::Handler<net::HttpStream> for RestApiRouter {
@@ -97,11 +137,18 @@ impl server::Handler<net::HttpStream> for RestApiRouter {
 
 		let endpoint = url.path.get(1).map(|v| v.as_str());
 		let hash = url.path.get(2).map(|v| v.as_str());
+		let resource = url.path.get(3).map(|v| v.as_str());
+		let identity = url.raw.query_pairs()
+			.find(|&(ref key, _)| &**key == "identity")
+			.map(|(_, value)| value.into_owned());
+		let sender = self.sender(&request, &path);
 
 		let handler = endpoint.and_then(|v| match v {
 			"apps" => Some(as_json(&self.api.list_apps())),
 			"ping" => Some(ping_response(&self.api.local_domain)),
+			"health" => self.api.health.as_ref().map(|health| health_response(&health.health())),
 			"content" => self.resolve_content(hash, path, control),
+			"dapps" => self.resolve_settings(hash, resource, identity, sender),
 			_ => None
 		});
 
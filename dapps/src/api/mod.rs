@@ -21,7 +21,9 @@
 
 mod api;
 mod response;
+mod settings;
 mod types;
 
 pub use self::api::RestApi;
+pub use self::settings::SettingsStore;
 pub use self::types::App;
@@ -16,8 +16,10 @@
 
 use serde::Serialize;
 use serde_json;
+use hyper::status::StatusCode;
 use endpoint::Handler;
 use handlers::{ContentHandler, EchoHandler};
+use ethcore_rpc::v1::types::Health;
 
 pub fn as_json<T : Serialize>(val: &T) -> Box<Handler> {
 	Box::new(ContentHandler::ok(serde_json::to_string(val).unwrap(), "application/json".to_owned()))
@@ -27,6 +29,14 @@ pub fn as_json_error<T : Serialize>(val: &T) -> Box<Handler> {
 	Box::new(ContentHandler::not_found(serde_json::to_string(val).unwrap(), "application/json".to_owned()))
 }
 
+pub fn health_response(health: &Health) -> Box<Handler> {
+	let code = match health.is_healthy() {
+		true => StatusCode::Ok,
+		false => StatusCode::ServiceUnavailable,
+	};
+	Box::new(ContentHandler::new(code, serde_json::to_string(health).unwrap(), "application/json".to_owned()))
+}
+
 pub fn ping_response(local_domain: &str) -> Box<Handler> {
 	Box::new(EchoHandler::cors(vec![
 		format!("http://{}", local_domain),
@@ -0,0 +1,346 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-dapp settings storage.
+//!
+//! Settings are only accessible to the dapp itself: requests have to carry the dapp's token
+//! and be sent from one of its pages.
+
+use std::{fs, io, mem};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use hyper::{server, Decoder, Encoder, Next};
+use hyper::method::Method;
+use hyper::net::HttpStream;
+use hyper::status::StatusCode;
+use serde_json;
+use util::Mutex;
+use api::types::ApiError;
+use handlers::ContentHandler;
+
+/// Maximal size (in bytes) of settings stored for single dapp and identity.
+pub const MAX_SETTINGS_SIZE: usize = 64 * 1024;
+/// Maximal number of identities that can store settings of single dapp.
+pub const MAX_IDENTITIES: usize = 16;
+/// Maximal size (in bytes) of settings stored for all dapps together.
+pub const MAX_TOTAL_SIZE: u64 = 16 * 1024 * 1024;
+/// Identity used when request doesn't specify one.
+pub const DEFAULT_IDENTITY: &'static str = "default";
+
+#[derive(Debug)]
+pub enum SettingsError {
+	/// Dapp id or identity is not a valid file name.
+	InvalidName,
+	/// Settings are not a JSON object.
+	InvalidSettings,
+	/// Settings exceed `MAX_SETTINGS_SIZE`.
+	TooLarge,
+	/// Settings are already stored for `MAX_IDENTITIES` identities.
+	TooManyIdentities,
+	/// Settings of all dapps would exceed `MAX_TOTAL_SIZE`.
+	QuotaExceeded,
+	/// Request wasn't sent by the dapp.
+	Unauthorized,
+	/// Filesystem error.
+	Io(io::Error),
+}
+
+impl From<io::Error> for SettingsError {
+	fn from(err: io::Error) -> Self {
+		SettingsError::Io(err)
+	}
+}
+
+/// Stores settings as JSON files in `<path>/<dapp>/<identity>.json`.
+pub struct SettingsStore {
+	path: PathBuf,
+	// serializes writes, so the quota is checked against the stored settings.
+	write_lock: Mutex<()>,
+}
+
+impl SettingsStore {
+	pub fn new(path: PathBuf) -> Self {
+		SettingsStore {
+			path: path,
+			write_lock: Mutex::new(()),
+		}
+	}
+
+	fn is_valid_name(name: &str) -> bool {
+		!name.is_empty() && name.len() <= 128 && !name.starts_with('.') && name.chars().all(|c| match c {
+			'a'...'z' | 'A'...'Z' | '0'...'9' | '-' | '_' | '.' => true,
+			_ => false,
+		})
+	}
+
+	fn dapp_path(&self, dapp: &str) -> Result<PathBuf, SettingsError> {
+		if !Self::is_valid_name(dapp) {
+			return Err(SettingsError::InvalidName);
+		}
+		let mut path = self.path.clone();
+		path.push(dapp);
+		Ok(path)
+	}
+
+	fn settings_path(&self, dapp: &str, identity: &str) -> Result<PathBuf, SettingsError> {
+		if !Self::is_valid_name(identity) {
+			return Err(SettingsError::InvalidName);
+		}
+		let mut path = try!(self.dapp_path(dapp));
+		path.push(format!("{}.json", identity));
+		Ok(path)
+	}
+
+	/// Returns settings stored for given dapp and identity (empty object if there are none).
+	pub fn get(&self, dapp: &str, identity: &str) -> Result<String, SettingsError> {
+		let path = try!(self.settings_path(dapp, identity));
+		let mut file = match fs::File::open(&path) {
+			Ok(file) => file,
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok("{}".into()),
+			Err(e) => return Err(e.into()),
+		};
+		let mut settings = String::new();
+		try!(file.read_to_string(&mut settings));
+		Ok(settings)
+	}
+
+	/// Replaces settings stored for given dapp and identity.
+	pub fn set(&self, dapp: &str, identity: &str, settings: &str) -> Result<(), SettingsError> {
+		if settings.len() > MAX_SETTINGS_SIZE {
+			return Err(SettingsError::TooLarge);
+		}
+		match serde_json::from_str(settings) {
+			Ok(serde_json::Value::Object(_)) => {},
+			_ => return Err(SettingsError::InvalidSettings),
+		}
+
+		let dapp_path = try!(self.dapp_path(dapp));
+		let path = try!(self.settings_path(dapp, identity));
+		let _lock = self.write_lock.lock();
+		if !path.exists() && try!(settings_files(&dapp_path)).len() >= MAX_IDENTITIES {
+			return Err(SettingsError::TooManyIdentities);
+		}
+		let replaced = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+		if try!(self.total_size()) - replaced + settings.len() as u64 > MAX_TOTAL_SIZE {
+			return Err(SettingsError::QuotaExceeded);
+		}
+
+		// written to a temporary file first, so the settings are never left half-written.
+		try!(fs::create_dir_all(&dapp_path));
+		let mut temp_path = dapp_path.clone();
+		temp_path.push(format!(".{}.json.tmp", identity));
+		{
+			let mut file = try!(fs::File::create(&temp_path));
+			try!(file.write_all(settings.as_bytes()));
+			try!(file.sync_all());
+		}
+		try!(fs::rename(&temp_path, &path));
+		Ok(())
+	}
+
+	/// Size of settings stored for all dapps.
+	fn total_size(&self) -> Result<u64, SettingsError> {
+		let dapps = match fs::read_dir(&self.path) {
+			Ok(dapps) => dapps,
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+			Err(e) => return Err(e.into()),
+		};
+		let mut total = 0;
+		for dapp in dapps {
+			for file in try!(settings_files(&try!(dapp).path())) {
+				total += try!(fs::metadata(file)).len();
+			}
+		}
+		Ok(total)
+	}
+}
+
+/// Settings files stored in given dapp directory.
+fn settings_files(dapp_path: &Path) -> Result<Vec<PathBuf>, SettingsError> {
+	let entries = match fs::read_dir(dapp_path) {
+		Ok(entries) => entries,
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(e) => return Err(e.into()),
+	};
+	let mut files = Vec::new();
+	for entry in entries {
+		let path = try!(entry).path();
+		let is_settings = path.extension().map_or(false, |ext| ext == "json")
+			&& path.file_name().and_then(|name| name.to_str()).map_or(false, |name| !name.starts_with('.'));
+		if is_settings {
+			files.push(path);
+		}
+	}
+	Ok(files)
+}
+
+/// Response to a failed settings request.
+pub fn error_handler(err: SettingsError) -> ContentHandler {
+	let (code, detail) = match err {
+		SettingsError::InvalidName => (StatusCode::BadRequest, "Dapp id and identity may only contain letters, digits, `-`, `_` and `.`.".to_owned()),
+		SettingsError::InvalidSettings => (StatusCode::BadRequest, "Settings have to be a JSON object.".to_owned()),
+		SettingsError::TooLarge => (StatusCode::PayloadTooLarge, format!("Settings can't be larger than {} bytes.", MAX_SETTINGS_SIZE)),
+		SettingsError::TooManyIdentities => (StatusCode::Forbidden, format!("Settings can be stored for at most {} identities.", MAX_IDENTITIES)),
+		SettingsError::QuotaExceeded => (StatusCode::InsufficientStorage, format!("Settings of all dapps can't be larger than {} bytes.", MAX_TOTAL_SIZE)),
+		SettingsError::Unauthorized => (StatusCode::Forbidden, "Settings are only accessible from pages of the dapp.".to_owned()),
+		SettingsError::Io(e) => (StatusCode::InternalServerError, format!("Unable to access settings: {}", e)),
+	};
+	let error = ApiError {
+		code: format!("{}", code.to_u16()),
+		title: code.canonical_reason().unwrap_or("Error").into(),
+		detail: detail,
+	};
+	ContentHandler::new(code, serde_json::to_string(&error).unwrap(), "application/json".into())
+}
+
+/// Handles `GET` (read) and `PUT`/`POST` (replace) requests for settings of single dapp and identity.
+pub struct SettingsHandler {
+	store: Arc<SettingsStore>,
+	dapp: String,
+	identity: String,
+	content: Vec<u8>,
+	handler: Option<ContentHandler>,
+}
+
+impl SettingsHandler {
+	pub fn new(store: Arc<SettingsStore>, dapp: String, identity: String) -> Self {
+		SettingsHandler {
+			store: store,
+			dapp: dapp,
+			identity: identity,
+			content: Vec::new(),
+			handler: None,
+		}
+	}
+
+	fn respond(&mut self, result: Result<String, SettingsError>) {
+		self.handler = Some(match result {
+			Ok(settings) => ContentHandler::ok(settings, "application/json".into()),
+			Err(err) => error_handler(err),
+		});
+	}
+
+	fn store(&mut self) {
+		let content = mem::replace(&mut self.content, Vec::new());
+		let result = match String::from_utf8(content) {
+			Ok(settings) => self.store.set(&self.dapp, &self.identity, &settings).map(|_| settings),
+			Err(_) => Err(SettingsError::InvalidSettings),
+		};
+		self.respond(result);
+	}
+}
+
+impl server::Handler<HttpStream> for SettingsHandler {
+	fn on_request(&mut self, request: server::Request<HttpStream>) -> Next {
+		match *request.method() {
+			Method::Get => {
+				let result = self.store.get(&self.dapp, &self.identity);
+				self.respond(result);
+				Next::write()
+			},
+			Method::Put | Method::Post => Next::read(),
+			_ => {
+				self.handler = Some(ContentHandler::new(
+					StatusCode::MethodNotAllowed,
+					"Only GET, PUT and POST requests are supported.".into(),
+					"text/plain".into(),
+				));
+				Next::write()
+			},
+		}
+	}
+
+	fn on_request_readable(&mut self, decoder: &mut Decoder<HttpStream>) -> Next {
+		let mut buf = [0u8; 4096];
+		match decoder.read(&mut buf) {
+			Ok(0) => {
+				self.store();
+				Next::write()
+			},
+			Ok(read) => {
+				self.content.extend_from_slice(&buf[..read]);
+				// Don't buffer more than we would store anyway.
+				if self.content.len() > MAX_SETTINGS_SIZE {
+					self.respond(Err(SettingsError::TooLarge));
+					Next::write()
+				} else {
+					Next::read()
+				}
+			},
+			Err(e) => match e.kind() {
+				io::ErrorKind::WouldBlock => Next::read(),
+				_ => Next::end(),
+			}
+		}
+	}
+
+	fn on_response(&mut self, res: &mut server::Response) -> Next {
+		self.handler.as_mut().expect("handler is set before switching to write; qed").on_response(res)
+	}
+
+	fn on_response_writable(&mut self, encoder: &mut Encoder<HttpStream>) -> Next {
+		self.handler.as_mut().expect("handler is set before switching to write; qed").on_response_writable(encoder)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use devtools::RandomTempPath;
+	use super::{SettingsStore, MAX_IDENTITIES, MAX_SETTINGS_SIZE, MAX_TOTAL_SIZE};
+
+	#[test]
+	fn should_reject_invalid_names_and_settings() {
+		let path = RandomTempPath::new();
+		let store = SettingsStore::new(path.as_path().clone());
+
+		assert_eq!(format!("{:?}", store.get("..", "default")), "Err(InvalidName)");
+		assert_eq!(format!("{:?}", store.set("status", "../x", "{}")), "Err(InvalidName)");
+		assert_eq!(format!("{:?}", store.set("status", "default", "[]")), "Err(InvalidSettings)");
+		assert_eq!(store.get("status", "default").unwrap(), "{}");
+	}
+
+	#[test]
+	fn should_limit_identities_per_dapp() {
+		let path = RandomTempPath::new();
+		let store = SettingsStore::new(path.as_path().clone());
+
+		for i in 0..MAX_IDENTITIES {
+			store.set("status", &format!("id{}", i), "{}").unwrap();
+		}
+
+		assert_eq!(format!("{:?}", store.set("status", "another", "{}")), "Err(TooManyIdentities)");
+		assert!(store.set("status", "id0", r#"{"a":1}"#).is_ok());
+		assert!(store.set("wallet", "another", "{}").is_ok());
+	}
+
+	#[test]
+	fn should_limit_total_size() {
+		let path = RandomTempPath::new();
+		let store = SettingsStore::new(path.as_path().clone());
+		let value: String = ::std::iter::repeat('x').take(MAX_SETTINGS_SIZE - 8).collect();
+		let settings = format!(r#"{{"a":"{}"}}"#, value);
+		let count = MAX_TOTAL_SIZE as usize / settings.len();
+
+		for i in 0..count {
+			store.set(&format!("dapp{}", i / MAX_IDENTITIES), &format!("id{}", i % MAX_IDENTITIES), &settings).unwrap();
+		}
+
+		assert_eq!(format!("{:?}", store.set("another", "default", &settings)), "Err(QuotaExceeded)");
+		// replacing settings doesn't need more space
+		assert!(store.set("dapp0", "id0", &settings).is_ok());
+	}
+}
@@ -0,0 +1,339 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Archives with dapp bundles.
+//! Supports ZIP and gzipped TAR archives, detected by their magic bytes.
+
+use std::{fs, io, str};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use flate2::read::GzDecoder;
+use zip;
+
+const TAR_BLOCK: usize = 512;
+/// Maximal size of GNU long name and PAX extended header entries.
+const MAX_TAR_RECORD_SIZE: u64 = 4096;
+/// Maximal total size of files read from a single archive.
+pub const MAX_UNPACKED_SIZE: u64 = 128 * 1024 * 1024;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArchiveFormat {
+	Zip,
+	TarGz,
+}
+
+impl ArchiveFormat {
+	/// Detects format of the archive from its first bytes.
+	pub fn detect(header: &[u8]) -> Option<Self> {
+		if header.starts_with(b"PK\x03\x04") {
+			Some(ArchiveFormat::Zip)
+		} else if header.starts_with(&[0x1f, 0x8b]) {
+			Some(ArchiveFormat::TarGz)
+		} else {
+			None
+		}
+	}
+
+	/// Detects format of the archive stored in given file.
+	pub fn of_file(file: &mut fs::File) -> io::Result<Option<Self>> {
+		let mut header = [0u8; 4];
+		try!(file.seek(SeekFrom::Start(0)));
+		let read = try!(file.read(&mut header));
+		try!(file.seek(SeekFrom::Start(0)));
+		Ok(Self::detect(&header[..read]))
+	}
+}
+
+/// Converts name of archive entry into relative path.
+/// Returns `None` if the entry would be unpacked outside of target directory.
+pub fn entry_path(name: &str) -> Option<PathBuf> {
+	let mut path = PathBuf::new();
+	for component in Path::new(name).components() {
+		match component {
+			Component::Normal(part) => path.push(part),
+			Component::CurDir => {},
+			_ => return None,
+		}
+	}
+	Some(path)
+}
+
+/// Reader that fails once more than `remaining` bytes were read through all readers sharing it.
+struct SizeLimit<'a, R: Read + ?Sized + 'a> {
+	inner: &'a mut R,
+	remaining: &'a mut u64,
+}
+
+impl<'a, R: Read + ?Sized + 'a> Read for SizeLimit<'a, R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = try!(self.inner.read(buf));
+		if read as u64 > *self.remaining {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Archive content exceeds {} bytes.", MAX_UNPACKED_SIZE)));
+		}
+		*self.remaining -= read as u64;
+		Ok(read)
+	}
+}
+
+/// Calls `f` with name, directory flag and content of every file and directory in the archive.
+/// Other entries (like links) are skipped.
+/// Reading more than `MAX_UNPACKED_SIZE` bytes of content in total fails.
+pub fn for_each_entry<F, E>(file: fs::File, format: ArchiveFormat, mut f: F) -> Result<(), E> where
+	F: FnMut(&str, bool, &mut Read) -> Result<(), E>,
+	E: From<io::Error> + From<zip::result::ZipError>,
+{
+	let mut remaining = MAX_UNPACKED_SIZE;
+	match format {
+		ArchiveFormat::Zip => {
+			let mut zip = try!(zip::ZipArchive::new(file));
+			for i in 0..zip.len() {
+				let mut entry = try!(zip.by_index(i));
+				let name = entry.name().to_owned();
+				// TODO [todr] Check if it's consistent on windows.
+				let is_dir = name.ends_with('/');
+				try!(f(&name, is_dir, &mut SizeLimit { inner: &mut entry, remaining: &mut remaining }));
+			}
+			Ok(())
+		},
+		ArchiveFormat::TarGz => {
+			let decoder = try!(GzDecoder::new(io::BufReader::new(file)));
+			for_each_tar_entry(decoder, &mut remaining, f)
+		},
+	}
+}
+
+fn invalid_tar(details: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, format!("Invalid TAR archive: {}", details))
+}
+
+/// Reads NUL-terminated string from header field.
+fn header_str(field: &[u8]) -> io::Result<&str> {
+	let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+	str::from_utf8(&field[..end]).map_err(|_| invalid_tar("entry name is not valid UTF-8"))
+}
+
+/// Reads octal number from header field.
+fn header_octal(field: &[u8]) -> io::Result<u64> {
+	let value = try!(header_str(field));
+	let value = value.trim_matches(|c| c == ' ' || c == '\0');
+	if value.is_empty() {
+		return Ok(0);
+	}
+	u64::from_str_radix(value, 8).map_err(|_| invalid_tar("invalid number in header"))
+}
+
+/// Reads `path` record of PAX extended header.
+fn pax_path(records: &[u8]) -> Option<String> {
+	let mut records = records;
+	while !records.is_empty() {
+		let space = match records.iter().position(|b| *b == b' ') {
+			Some(space) => space,
+			None => return None,
+		};
+		let len = match str::from_utf8(&records[..space]).ok().and_then(|len| len.parse::<usize>().ok()) {
+			// `<len> ` and the trailing newline are part of the record.
+			Some(len) if len >= space + 2 && len <= records.len() => len,
+			_ => return None,
+		};
+		// Record is `<len> <key>=<value>\n`
+		let record = &records[space + 1..len - 1];
+		if record.starts_with(b"path=") {
+			return str::from_utf8(&record[5..]).ok().map(Into::into);
+		}
+		records = &records[len..];
+	}
+	None
+}
+
+fn for_each_tar_entry<R, F, E>(mut reader: R, remaining: &mut u64, mut f: F) -> Result<(), E> where
+	R: Read,
+	F: FnMut(&str, bool, &mut Read) -> Result<(), E>,
+	E: From<io::Error>,
+{
+	let mut header = [0u8; TAR_BLOCK];
+	// Name overriden by preceding GNU long name or PAX header.
+	let mut next_name: Option<String> = None;
+
+	loop {
+		try!(reader.read_exact(&mut header));
+		// Archive ends with empty blocks
+		if header.iter().all(|b| *b == 0) {
+			return Ok(());
+		}
+
+		let size = try!(header_octal(&header[124..136]));
+		let kind = header[156];
+		let name = match next_name.take() {
+			Some(name) => name,
+			None => {
+				let name = try!(header_str(&header[0..100]));
+				let is_ustar = &header[257..262] == b"ustar";
+				let prefix = if is_ustar { try!(header_str(&header[345..500])) } else { "" };
+				match prefix.is_empty() {
+					true => name.to_owned(),
+					false => format!("{}/{}", prefix, name),
+				}
+			},
+		};
+
+		{
+			let mut content = (&mut reader).take(size);
+			match kind {
+				b'0' | b'\0' | b'7' => try!(f(&name, false, &mut SizeLimit { inner: &mut content, remaining: &mut *remaining })),
+				b'5' => try!(f(&name, true, &mut SizeLimit { inner: &mut content, remaining: &mut *remaining })),
+				b'L' | b'x' if size > MAX_TAR_RECORD_SIZE => {
+					return Err(invalid_tar("extended header is too large").into());
+				},
+				b'L' | b'x' => {
+					let mut data = Vec::new();
+					try!(content.read_to_end(&mut data));
+					next_name = match kind {
+						b'L' => Some(try!(header_str(&data)).to_owned()),
+						_ => pax_path(&data),
+					};
+				},
+				_ => {},
+			}
+			// Skip whatever was not read by the callback
+			try!(io::copy(&mut content, &mut io::sink()));
+			if content.limit() > 0 {
+				return Err(invalid_tar("unexpected end of archive").into());
+			}
+		}
+
+		let padding = (TAR_BLOCK - (size as usize % TAR_BLOCK)) % TAR_BLOCK;
+		try!(reader.read_exact(&mut header[..padding]));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{fs, io, iter};
+	use std::io::{Read, Write};
+	use std::path::PathBuf;
+	use flate2::Compression;
+	use flate2::write::GzEncoder;
+	use zip;
+	use devtools::RandomTempPath;
+	use super::{ArchiveFormat, entry_path, for_each_entry, pax_path, TAR_BLOCK};
+
+	#[derive(Debug)]
+	struct Error;
+	impl From<io::Error> for Error {
+		fn from(_: io::Error) -> Self { Error }
+	}
+	impl From<zip::result::ZipError> for Error {
+		fn from(_: zip::result::ZipError) -> Self { Error }
+	}
+
+	fn tar_entry(tar: &mut Vec<u8>, name: &str, kind: u8, content: &[u8]) {
+		let mut header = [0u8; TAR_BLOCK];
+		header[..name.len()].copy_from_slice(name.as_bytes());
+		let size = format!("{:011o}\0", content.len());
+		header[124..136].copy_from_slice(size.as_bytes());
+		header[156] = kind;
+		header[257..263].copy_from_slice(b"ustar\0");
+		tar.extend_from_slice(&header);
+		tar.extend_from_slice(content);
+		let padding = (TAR_BLOCK - content.len() % TAR_BLOCK) % TAR_BLOCK;
+		tar.extend(vec![0u8; padding]);
+	}
+
+	#[test]
+	fn should_detect_archive_format() {
+		assert_eq!(ArchiveFormat::detect(b"PK\x03\x04rest"), Some(ArchiveFormat::Zip));
+		assert_eq!(ArchiveFormat::detect(&[0x1f, 0x8b, 0x08, 0x00]), Some(ArchiveFormat::TarGz));
+		assert_eq!(ArchiveFormat::detect(b"<html>"), None);
+	}
+
+	#[test]
+	fn should_reject_paths_outside_of_target() {
+		assert_eq!(entry_path("dapp/index.html"), Some(PathBuf::from("dapp/index.html")));
+		assert_eq!(entry_path("./dapp/"), Some(PathBuf::from("dapp")));
+		assert_eq!(entry_path("dapp/../../etc/passwd"), None);
+		assert_eq!(entry_path("/etc/passwd"), None);
+	}
+
+	#[test]
+	fn should_read_pax_path() {
+		assert_eq!(pax_path(b"20 path=dapp/a.html\n"), Some("dapp/a.html".into()));
+		assert_eq!(pax_path(b"12 mtime=10\n20 path=dapp/a.html\n"), Some("dapp/a.html".into()));
+		assert_eq!(pax_path(b"2 x"), None);
+		assert_eq!(pax_path(b"1 "), None);
+		assert_eq!(pax_path(b"30 path=dapp\n"), None);
+	}
+
+	fn write_tar_gz(tar: &[u8]) -> RandomTempPath {
+		let path = RandomTempPath::new();
+		{
+			let mut encoder = GzEncoder::new(fs::File::create(path.as_path()).unwrap(), Compression::Default);
+			encoder.write_all(tar).unwrap();
+			encoder.finish().unwrap();
+		}
+		path
+	}
+
+	#[test]
+	fn should_reject_too_large_extended_headers() {
+		// given
+		let mut tar = Vec::new();
+		tar_entry(&mut tar, "././@LongLink", b'L', &vec![b'a'; 8192]);
+		tar_entry(&mut tar, "dapp/aaaa", b'0', b"<html></html>");
+		tar.extend(vec![0u8; 2 * TAR_BLOCK]);
+		let path = write_tar_gz(&tar);
+
+		// when
+		let file = fs::File::open(path.as_path()).unwrap();
+		let res = for_each_entry(file, ArchiveFormat::TarGz, |_, _, _| -> Result<(), Error> { Ok(()) });
+
+		// then
+		assert!(res.is_err());
+	}
+
+	#[test]
+	fn should_read_tar_gz_entries() {
+		// given
+		let long_name = format!("dapp/{}/index.html", iter::repeat("a").take(120).collect::<String>());
+		let mut tar = Vec::new();
+		tar_entry(&mut tar, "dapp/", b'5', b"");
+		tar_entry(&mut tar, "pax_global_header", b'g', b"52 comment=ec4c1fe06c808fe3739858c347109b1f5f1ed4b5\n");
+		tar_entry(&mut tar, "dapp/manifest.json", b'0', b"{}");
+		tar_entry(&mut tar, "././@LongLink", b'L', long_name.as_bytes());
+		tar_entry(&mut tar, "dapp/aaaa", b'0', b"<html></html>");
+		tar.extend(vec![0u8; 2 * TAR_BLOCK]);
+		let path = write_tar_gz(&tar);
+
+		// when
+		let mut file = fs::File::open(path.as_path()).unwrap();
+		let format = ArchiveFormat::of_file(&mut file).unwrap().unwrap();
+		let mut entries = Vec::new();
+		for_each_entry(file, format, |name, is_dir, content| -> Result<(), Error> {
+			let mut data = String::new();
+			try!(content.read_to_string(&mut data));
+			entries.push((name.to_owned(), is_dir, data));
+			Ok(())
+		}).unwrap();
+
+		// then
+		assert_eq!(format, ArchiveFormat::TarGz);
+		assert_eq!(entries, vec![
+			("dapp/".to_owned(), true, "".to_owned()),
+			("dapp/manifest.json".to_owned(), false, "{}".to_owned()),
+			(long_name, false, "<html></html>".to_owned()),
+		]);
+	}
+}
@@ -20,6 +20,7 @@
 
 use zip;
 use std::{fs, env, fmt};
+use std::collections::HashMap;
 use std::io::{self, Read, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -30,13 +31,14 @@ use hyper::status::StatusCode;
 
 use random_filename;
 use SyncStatus;
-use util::{Mutex, H256};
+use util::{Mutex, Address, H256};
 use util::sha3::sha3;
 use page::LocalPageEndpoint;
 use handlers::{ContentHandler, ContentFetcherHandler, ContentValidator};
 use endpoint::{Endpoint, EndpointPath, Handler};
+use apps::archive::{self, ArchiveFormat, entry_path};
 use apps::cache::{ContentCache, ContentStatus};
-use apps::manifest::{MANIFEST_FILENAME, deserialize_manifest, serialize_manifest, Manifest};
+use apps::manifest::{MANIFEST_FILENAME, MANIFEST_SIGNATURE_FILENAME, deserialize_manifest, serialize_manifest, validate_manifest, manifest_signer, Manifest};
 use apps::urlhint::{URLHintContract, URLHint, URLHintResult};
 
 const MAX_CACHED_DAPPS: usize = 10;
@@ -46,6 +48,7 @@ pub struct ContentFetcher<R: URLHint = URLHintContract> {
 	resolver: R,
 	cache: Arc<Mutex<ContentCache>>,
 	sync: Arc<SyncStatus>,
+	require_signatures: bool,
 }
 
 impl<R: URLHint> Drop for ContentFetcher<R> {
@@ -57,7 +60,7 @@ impl<R: URLHint> Drop for ContentFetcher<R> {
 
 impl<R: URLHint> ContentFetcher<R> {
 
-	pub fn new(resolver: R, sync_status: Arc<SyncStatus>) -> Self {
+	pub fn new(resolver: R, sync_status: Arc<SyncStatus>, require_signatures: bool) -> Self {
 		let mut dapps_path = env::temp_dir();
 		dapps_path.push(random_filename());
 
@@ -65,6 +68,7 @@ impl<R: URLHint> ContentFetcher<R> {
 			dapps_path: dapps_path,
 			resolver: resolver,
 			sync: sync_status,
+			require_signatures: require_signatures,
 			cache: Arc::new(Mutex::new(ContentCache::default())),
 		}
 	}
@@ -149,6 +153,8 @@ impl<R: URLHint> ContentFetcher<R> {
 								DappInstaller {
 									id: content_id.clone(),
 									dapps_path: self.dapps_path.clone(),
+									owner: dapp.owner,
+									require_signature: self.require_signatures,
 									on_done: Box::new(on_done),
 								}
 							);
@@ -199,8 +205,14 @@ pub enum ValidationError {
 	Io(io::Error),
 	Zip(zip::result::ZipError),
 	InvalidContentId,
+	UnsupportedArchive,
+	UnsafePath(String),
 	ManifestNotFound,
+	ManifestInvalid(String),
 	ManifestSerialization(String),
+	SignatureMissing,
+	SignatureInvalid(String),
+	SignatureMismatch { expected: Address, got: Address, },
 	HashMismatch { expected: H256, got: H256, },
 }
 
@@ -210,13 +222,21 @@ impl fmt::Display for ValidationError {
 			ValidationError::Io(ref io) => write!(f, "Unexpected IO error occured: {:?}", io),
 			ValidationError::Zip(ref zip) => write!(f, "Unable to read ZIP archive: {:?}", zip),
 			ValidationError::InvalidContentId => write!(f, "ID is invalid. It should be 256 bits keccak hash of content."),
+			ValidationError::UnsupportedArchive => write!(f, "Downloaded Dapp bundle is neither ZIP nor gzipped TAR archive."),
+			ValidationError::UnsafePath(ref path) => write!(f, "Dapp bundle contains file outside of its directory: {}", path),
 			ValidationError::ManifestNotFound => write!(f, "Downloaded Dapp bundle did not contain valid manifest.json file."),
+			ValidationError::ManifestInvalid(ref err) => write!(f, "Dapp manifest.json is invalid: {}", err),
 			ValidationError::ManifestSerialization(ref err) => {
 				write!(f, "There was an error during Dapp Manifest serialization: {:?}", err)
 			},
 			ValidationError::HashMismatch { ref expected, ref got } => {
 				write!(f, "Hash of downloaded content did not match. Expected:{:?}, Got:{:?}.", expected, got)
 			},
+			ValidationError::SignatureMissing => write!(f, "Dapp manifest is not signed by its author."),
+			ValidationError::SignatureInvalid(ref err) => write!(f, "Dapp manifest signature is invalid: {}", err),
+			ValidationError::SignatureMismatch { ref expected, ref got } => {
+				write!(f, "Dapp manifest is not signed by the registered owner. Expected:{:?}, Got:{:?}.", expected, got)
+			},
 		}
 	}
 }
@@ -233,6 +253,27 @@ impl From<zip::result::ZipError> for ValidationError {
 	}
 }
 
+const DEFAULT_MIME: &'static str = "application/octet-stream";
+
+/// Guesses MIME type of single-file content from its magic bytes.
+fn sniff_mime(header: &[u8]) -> Option<&'static str> {
+	if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+		Some("image/png")
+	} else if header.starts_with(b"\xff\xd8\xff") {
+		Some("image/jpeg")
+	} else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+		Some("image/gif")
+	} else if header.starts_with(b"%PDF-") {
+		Some("application/pdf")
+	} else {
+		match ArchiveFormat::detect(header) {
+			Some(ArchiveFormat::Zip) => Some("application/zip"),
+			Some(ArchiveFormat::TarGz) => Some("application/gzip"),
+			None => None,
+		}
+	}
+}
+
 struct ContentInstaller {
 	id: String,
 	mime: String,
@@ -258,7 +299,17 @@ impl ContentValidator for ContentInstaller {
 
 		try!(fs::copy(&path, &content_path));
 
-		Ok((self.id.clone(), LocalPageEndpoint::single_file(content_path, self.mime.clone())))
+		// Registry entry doesn't say what the content is, so guess it from the first bytes
+		let mime = match self.mime.as_str() {
+			DEFAULT_MIME => {
+				let mut header = [0u8; 8];
+				let read = try!(try!(fs::File::open(&content_path)).read(&mut header));
+				sniff_mime(&header[..read]).unwrap_or(DEFAULT_MIME).to_owned()
+			},
+			mime => mime.to_owned(),
+		};
+
+		Ok((self.id.clone(), LocalPageEndpoint::single_file(content_path, mime)))
 	}
 
 	fn done(&self, endpoint: Option<LocalPageEndpoint>) {
@@ -270,32 +321,91 @@ impl ContentValidator for ContentInstaller {
 struct DappInstaller {
 	id: String,
 	dapps_path: PathBuf,
+	owner: Address,
+	require_signature: bool,
 	on_done: Box<Fn(String, Option<LocalPageEndpoint>) + Send>,
 }
 
+/// Manifest found in a dapp bundle.
+struct BundleManifest {
+	manifest: Manifest,
+	/// Manifest file as shipped by the author.
+	raw: Vec<u8>,
+	/// Content of the signature file next to the manifest.
+	signature: Option<String>,
+	/// Directory of the manifest inside the bundle.
+	dir: PathBuf,
+}
+
 impl DappInstaller {
-	fn find_manifest(zip: &mut zip::ZipArchive<fs::File>) -> Result<(Manifest, PathBuf), ValidationError> {
-		for i in 0..zip.len() {
-			let mut file = try!(zip.by_index(i));
+	fn find_manifest(file: fs::File, format: ArchiveFormat) -> Result<BundleManifest, ValidationError> {
+		let mut found: Option<(String, BundleManifest)> = None;
+		let mut signatures = HashMap::new();
+		let mut invalid = None;
+		try!(archive::for_each_entry(file, format, |name, is_dir, content| -> Result<(), ValidationError> {
+			if is_dir {
+				return Ok(());
+			}
 
-			if !file.name().ends_with(MANIFEST_FILENAME) {
-				continue;
+			// signature may come before or after the manifest, so it's matched by directory later
+			if name.ends_with(MANIFEST_SIGNATURE_FILENAME) {
+				let mut signature = String::new();
+				if content.read_to_string(&mut signature).is_ok() {
+					let dir = &name[..name.len() - MANIFEST_SIGNATURE_FILENAME.len()];
+					signatures.insert(dir.to_owned(), signature.trim().to_owned());
+				}
+				return Ok(());
+			}
+
+			if found.is_some() || !name.ends_with(MANIFEST_FILENAME) {
+				return Ok(());
 			}
 
 			// try to read manifest
-			let mut manifest = String::new();
-			let manifest = file
-				.read_to_string(&mut manifest).ok()
-				.and_then(|_| deserialize_manifest(manifest).ok());
-
-			if let Some(manifest) = manifest {
-				let mut manifest_location = PathBuf::from(file.name());
-				manifest_location.pop(); // get rid of filename
-				return Ok((manifest, manifest_location));
+			let mut raw = Vec::new();
+			let manifest = content
+				.read_to_end(&mut raw).map_err(|e| format!("{}", e))
+				.and_then(|_| String::from_utf8(raw.clone()).map_err(|e| format!("{}", e)))
+				.and_then(deserialize_manifest);
+
+			match manifest {
+				Ok(manifest) => {
+					let mut manifest_location = try!(entry_path(name).ok_or_else(|| ValidationError::UnsafePath(name.into())));
+					manifest_location.pop(); // get rid of filename
+					let dir = &name[..name.len() - MANIFEST_FILENAME.len()];
+					found = Some((dir.to_owned(), BundleManifest {
+						manifest: manifest,
+						raw: raw,
+						signature: None,
+						dir: manifest_location,
+					}));
+				},
+				Err(err) => if invalid.is_none() {
+					invalid = Some(err);
+				},
 			}
+			Ok(())
+		}));
+
+		match found {
+			Some((dir, mut bundle_manifest)) => {
+				bundle_manifest.signature = signatures.remove(&dir);
+				Ok(bundle_manifest)
+			},
+			None => Err(invalid.map_or(ValidationError::ManifestNotFound, ValidationError::ManifestInvalid)),
 		}
+	}
 
-		Err(ValidationError::ManifestNotFound)
+	fn verify_signature(&self, manifest: &BundleManifest) -> Result<(), ValidationError> {
+		let signature = manifest.signature.as_ref().map(|s| s.as_str());
+		match try!(manifest_signer(&manifest.raw, signature).map_err(ValidationError::SignatureInvalid)) {
+			Some(signer) if signer != self.owner => Err(ValidationError::SignatureMismatch {
+				expected: self.owner,
+				got: signer,
+			}),
+			None if self.require_signature => Err(ValidationError::SignatureMissing),
+			_ => Ok(()),
+		}
 	}
 
 	fn dapp_target_path(&self, manifest: &Manifest) -> PathBuf {
@@ -310,7 +420,7 @@ impl ContentValidator for DappInstaller {
 
 	fn validate_and_install(&self, app_path: PathBuf) -> Result<(String, LocalPageEndpoint), ValidationError> {
 		trace!(target: "dapps", "Opening dapp bundle at {:?}", app_path);
-		let mut file_reader = io::BufReader::new(try!(fs::File::open(app_path)));
+		let mut file_reader = io::BufReader::new(try!(fs::File::open(&app_path)));
 		let hash = try!(sha3(&mut file_reader));
 		let id = try!(self.id.as_str().parse().map_err(|_| ValidationError::InvalidContentId));
 		if id != hash {
@@ -319,11 +429,14 @@ impl ContentValidator for DappInstaller {
 				got: hash,
 			});
 		}
-		let file = file_reader.into_inner();
-		// Unpack archive
-		let mut zip = try!(zip::ZipArchive::new(file));
+		let mut file = file_reader.into_inner();
+		let format = try!(try!(ArchiveFormat::of_file(&mut file)).ok_or(ValidationError::UnsupportedArchive));
 		// First find manifest file
-		let (mut manifest, manifest_dir) = try!(Self::find_manifest(&mut zip));
+		let bundle_manifest = try!(Self::find_manifest(file, format));
+		try!(validate_manifest(&bundle_manifest.manifest).map_err(ValidationError::ManifestInvalid));
+		// Signature covers manifest as shipped by the author
+		try!(self.verify_signature(&bundle_manifest));
+		let (mut manifest, manifest_dir) = (bundle_manifest.manifest, bundle_manifest.dir);
 		// Overwrite id to match hash
 		manifest.id = self.id.clone();
 
@@ -335,26 +448,27 @@ impl ContentValidator for DappInstaller {
 			try!(fs::remove_dir_all(target.clone()));
 		}
 
-		// Unpack zip
-		for i in 0..zip.len() {
-			let mut file = try!(zip.by_index(i));
-			// TODO [todr] Check if it's consistent on windows.
-			let is_dir = file.name().chars().rev().next() == Some('/');
-
-			let file_path = PathBuf::from(file.name());
-			let location_in_manifest_base = file_path.strip_prefix(&manifest_dir);
+		// Unpack archive
+		let file = try!(fs::File::open(&app_path));
+		try!(archive::for_each_entry(file, format, |name, is_dir, content| -> Result<(), ValidationError> {
+			let file_path = try!(entry_path(name).ok_or_else(|| ValidationError::UnsafePath(name.into())));
 			// Create files that are inside manifest directory
-			if let Ok(location_in_manifest_base) = location_in_manifest_base {
+			if let Ok(location_in_manifest_base) = file_path.strip_prefix(&manifest_dir) {
 				let p = target.join(location_in_manifest_base);
 				// Check if it's a directory
 				if is_dir {
 					try!(fs::create_dir_all(p));
 				} else {
+					// Archives don't have to contain entries for directories
+					if let Some(parent) = p.parent() {
+						try!(fs::create_dir_all(parent));
+					}
 					let mut target = try!(fs::File::create(p));
-					try!(io::copy(&mut file, &mut target));
+					try!(io::copy(content, &mut target));
 				}
 			}
-		}
+			Ok(())
+		}));
 
 		// Write manifest
 		let manifest_str = try!(serialize_manifest(&manifest).map_err(ValidationError::ManifestSerialization));
@@ -383,7 +497,7 @@ mod tests {
 	use page::LocalPageEndpoint;
 	use apps::cache::ContentStatus;
 	use apps::urlhint::{URLHint, URLHintResult};
-	use super::ContentFetcher;
+	use super::{ContentFetcher, sniff_mime};
 
 	struct FakeResolver;
 	impl URLHint for FakeResolver {
@@ -396,7 +510,7 @@ mod tests {
 	fn should_true_if_contains_the_app() {
 		// given
 		let path = env::temp_dir();
-		let fetcher = ContentFetcher::new(FakeResolver, Arc::new(|| false));
+		let fetcher = ContentFetcher::new(FakeResolver, Arc::new(|| false), false);
 		let handler = LocalPageEndpoint::new(path, EndpointInfo {
 			name: "fake".into(),
 			description: "".into(),
@@ -414,5 +528,12 @@ mod tests {
 		assert_eq!(fetcher.contains("test2"), true);
 		assert_eq!(fetcher.contains("test3"), false);
 	}
-}
 
+	#[test]
+	fn should_sniff_mime_type_of_content() {
+		assert_eq!(sniff_mime(b"\x89PNG\r\n\x1a\n"), Some("image/png"));
+		assert_eq!(sniff_mime(b"GIF89a\x01\x00"), Some("image/gif"));
+		assert_eq!(sniff_mime(&[0x1f, 0x8b, 0x08, 0x00]), Some("application/gzip"));
+		assert_eq!(sniff_mime(b"hello"), None);
+	}
+}
@@ -15,9 +15,13 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use serde_json;
+use ethkey::{self, Signature, public_to_address};
+use util::{Address, H256, Hashable};
 pub use api::App as Manifest;
 
 pub const MANIFEST_FILENAME: &'static str = "manifest.json";
+/// File next to the manifest holding the author's signature of it (see `manifest_hash`).
+pub const MANIFEST_SIGNATURE_FILENAME: &'static str = "manifest.json.sig";
 
 pub fn deserialize_manifest(manifest: String) -> Result<Manifest, String> {
 	serde_json::from_str::<Manifest>(&manifest).map_err(|e| format!("{:?}", e))
@@ -27,3 +31,93 @@ pub fn deserialize_manifest(manifest: String) -> Result<Manifest, String> {
 pub fn serialize_manifest(manifest: &Manifest) -> Result<String, String> {
 	serde_json::to_string_pretty(manifest).map_err(|e| format!("{:?}", e))
 }
+
+/// Checks that manifest of a fetched dapp describes it well enough to be served.
+pub fn validate_manifest(manifest: &Manifest) -> Result<(), String> {
+	fn is_api_name(api: &str) -> bool {
+		!api.is_empty() && api.chars().all(|c| match c {
+			'a'...'z' | '0'...'9' | '_' => true,
+			_ => false,
+		})
+	}
+
+	if manifest.name.trim().is_empty() {
+		return Err("Dapp name is missing.".into());
+	}
+	if manifest.version.trim().is_empty() {
+		return Err("Dapp version is missing.".into());
+	}
+	if manifest.author.trim().is_empty() {
+		return Err("Dapp author is missing.".into());
+	}
+	if manifest.icon_url.starts_with('/') || manifest.icon_url.split('/').any(|part| part == "..") {
+		return Err(format!("Icon has to be located inside the dapp bundle, got: {}", manifest.icon_url));
+	}
+	if let Some(api) = manifest.apis.iter().find(|api| !is_api_name(api)) {
+		return Err(format!("Invalid API name: {}", api));
+	}
+	Ok(())
+}
+
+/// Hash signed by dapp author: Keccak of the manifest file exactly as shipped in the bundle.
+pub fn manifest_hash(raw_manifest: &[u8]) -> H256 {
+	raw_manifest.sha3()
+}
+
+/// Returns address of the author that signed the manifest or `None` if it's not signed.
+pub fn manifest_signer(raw_manifest: &[u8], signature: Option<&str>) -> Result<Option<Address>, String> {
+	let signature = match signature {
+		Some(signature) => try!(signature.parse::<Signature>().map_err(|e| format!("Invalid signature: {:?}", e))),
+		None => return Ok(None),
+	};
+	let public = try!(ethkey::recover(&signature, &manifest_hash(raw_manifest)).map_err(|e| format!("Invalid signature: {:?}", e)));
+	Ok(Some(public_to_address(&public)))
+}
+
+#[cfg(test)]
+mod tests {
+	use ethkey::{self, Generator, Random};
+	use super::{Manifest, validate_manifest, manifest_hash, manifest_signer};
+
+	fn manifest() -> Manifest {
+		Manifest {
+			id: "wallet".into(),
+			name: "Wallet".into(),
+			description: "Multisig wallet".into(),
+			version: "1.0.0".into(),
+			author: "Ethcore".into(),
+			icon_url: "icon.png".into(),
+			apis: vec!["eth".into(), "personal".into()],
+		}
+	}
+
+	#[test]
+	fn should_validate_manifest() {
+		let mut no_author = manifest();
+		no_author.author = "".into();
+		let mut outside_icon = manifest();
+		outside_icon.icon_url = "../icon.png".into();
+		let mut invalid_api = manifest();
+		invalid_api.apis.push("eth;rm".into());
+
+		assert_eq!(validate_manifest(&manifest()), Ok(()));
+		assert!(validate_manifest(&no_author).is_err());
+		assert!(validate_manifest(&outside_icon).is_err());
+		assert!(validate_manifest(&invalid_api).is_err());
+	}
+
+	#[test]
+	fn should_recover_manifest_signer() {
+		let author = Random.generate().unwrap();
+		let raw = br#"{"id":"wallet","name":"Wallet","version":"1.0.0"}"#;
+		let signature = format!("{}", ethkey::sign(author.secret(), &manifest_hash(raw)).unwrap());
+
+		assert_eq!(manifest_signer(raw, None), Ok(None));
+		assert_eq!(manifest_signer(raw, Some(&signature)), Ok(Some(author.address())));
+		assert!(manifest_signer(raw, Some("0x12")).is_err());
+
+		// any change of the file, even one not changing the manifest, breaks the signature
+		let reformatted = br#"{"id": "wallet", "name": "Wallet", "version": "1.0.0"}"#;
+		assert!(manifest_signer(reformatted, Some(&signature)).unwrap() != Some(author.address()));
+	}
+}
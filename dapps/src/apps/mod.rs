@@ -19,6 +19,7 @@ use page::PageEndpoint;
 use proxypac::ProxyPac;
 use parity_dapps::WebApp;
 
+mod archive;
 mod cache;
 mod fs;
 pub mod urlhint;
@@ -25,6 +25,8 @@ pub struct EndpointPath {
 	pub host: String,
 	pub port: u16,
 	pub using_dapps_domains: bool,
+	/// Token of the dapp to inject into its pages.
+	pub token: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -255,7 +255,7 @@ impl<H: ContentValidator> server::Handler<HttpStream> for ContentFetcherHandler<
 									StatusCode::BadGateway,
 									"Invalid Dapp",
 									"Downloaded bundle does not contain a valid content.",
-									Some(&format!("{:?}", e))
+									Some(&format!("{}", e))
 								))
 							},
 							Ok((id, result)) => {
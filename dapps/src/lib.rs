@@ -51,6 +51,7 @@ extern crate unicase;
 extern crate serde;
 extern crate serde_json;
 extern crate zip;
+extern crate flate2;
 extern crate rand;
 extern crate ethabi;
 extern crate jsonrpc_core;
@@ -60,6 +61,7 @@ extern crate rustc_serialize;
 extern crate parity_dapps;
 extern crate ethcore_rpc;
 extern crate ethcore_util as util;
+extern crate ethkey;
 extern crate linked_hash_map;
 extern crate fetch;
 #[cfg(test)]
@@ -71,6 +73,7 @@ mod page;
 mod router;
 mod handlers;
 mod rpc;
+mod tokens;
 mod api;
 mod proxypac;
 mod url;
@@ -78,6 +81,8 @@ mod url;
 mod tests;
 
 pub use self::apps::urlhint::ContractClient;
+pub use ethcore_rpc::HealthProvider;
+pub use ethcore_rpc::v1::types::{Health, HealthCheck};
 
 use std::sync::{Arc, Mutex};
 use std::net::SocketAddr;
@@ -105,6 +110,9 @@ pub struct ServerBuilder {
 	handler: Arc<IoHandler>,
 	registrar: Arc<ContractClient>,
 	sync_status: Arc<SyncStatus>,
+	health: Option<Arc<HealthProvider>>,
+	settings_path: Option<String>,
+	require_signatures: bool,
 }
 
 impl Extendable for ServerBuilder {
@@ -121,6 +129,9 @@ impl ServerBuilder {
 			handler: Arc::new(IoHandler::new()),
 			registrar: registrar,
 			sync_status: Arc::new(|| false),
+			health: None,
+			settings_path: None,
+			require_signatures: false,
 		}
 	}
 
@@ -129,6 +140,21 @@ impl ServerBuilder {
 		self.sync_status = status;
 	}
 
+	/// Serve node health checks under `/api/health`.
+	pub fn with_health(&mut self, health: Arc<HealthProvider>) {
+		self.health = Some(health);
+	}
+
+	/// Persist per-dapp settings served under `/api/dapps/<id>/settings` in given directory.
+	pub fn with_settings_path(&mut self, path: String) {
+		self.settings_path = Some(path);
+	}
+
+	/// Refuse to serve fetched dapps whose manifest is not signed by the registered owner.
+	pub fn with_required_signatures(&mut self, require: bool) {
+		self.require_signatures = require;
+	}
+
 	/// Asynchronously start server with no authentication,
 	/// returns result with `Server` handle on success or an error.
 	pub fn start_unsecured_http(&self, addr: &SocketAddr, hosts: Option<Vec<String>>) -> Result<Server, ServerError> {
@@ -140,6 +166,9 @@ impl ServerBuilder {
 			self.dapps_path.clone(),
 			self.registrar.clone(),
 			self.sync_status.clone(),
+			self.health.clone(),
+			self.settings_path.clone(),
+			self.require_signatures,
 		)
 	}
 
@@ -154,6 +183,9 @@ impl ServerBuilder {
 			self.dapps_path.clone(),
 			self.registrar.clone(),
 			self.sync_status.clone(),
+			self.health.clone(),
+			self.settings_path.clone(),
+			self.require_signatures,
 		)
 	}
 }
@@ -162,6 +194,9 @@ impl ServerBuilder {
 pub struct Server {
 	server: Option<hyper::server::Listening>,
 	panic_handler: Arc<Mutex<Option<Box<Fn() -> () + Send>>>>,
+	// read only by tests, to act as a dapp.
+	#[cfg_attr(not(test), allow(dead_code))]
+	tokens: Arc<tokens::DappTokens>,
 }
 
 impl Server {
@@ -188,22 +223,28 @@ impl Server {
 		dapps_path: String,
 		registrar: Arc<ContractClient>,
 		sync_status: Arc<SyncStatus>,
+		health: Option<Arc<HealthProvider>>,
+		settings_path: Option<String>,
+		require_signatures: bool,
 	) -> Result<Server, ServerError> {
 		let panic_handler = Arc::new(Mutex::new(None));
 		let authorization = Arc::new(authorization);
-		let content_fetcher = Arc::new(apps::fetcher::ContentFetcher::new(apps::urlhint::URLHintContract::new(registrar), sync_status));
+		let content_fetcher = Arc::new(apps::fetcher::ContentFetcher::new(apps::urlhint::URLHintContract::new(registrar), sync_status, require_signatures));
 		let endpoints = Arc::new(apps::all_endpoints(dapps_path));
+		let tokens = Arc::new(tokens::DappTokens::default());
+		let settings = settings_path.map(|path| Arc::new(api::SettingsStore::new(path.into())));
 		let special = Arc::new({
 			let mut special = HashMap::new();
-			special.insert(router::SpecialEndpoint::Rpc, rpc::rpc(handler, panic_handler.clone()));
+			special.insert(router::SpecialEndpoint::Rpc, rpc::rpc(handler, panic_handler.clone(), tokens.clone()));
 			special.insert(router::SpecialEndpoint::Utils, apps::utils());
 			special.insert(
 				router::SpecialEndpoint::Api,
-				api::RestApi::new(format!("{}", addr), endpoints.clone(), content_fetcher.clone())
+				api::RestApi::new(format!("{}", addr), endpoints.clone(), content_fetcher.clone(), health, settings, tokens.clone())
 			);
 			special
 		});
 		let hosts = Self::allowed_hosts(hosts, format!("{}", addr));
+		let server_tokens = tokens.clone();
 
 		try!(hyper::Server::http(addr))
 			.handle(move |ctrl| router::Router::new(
@@ -214,6 +255,7 @@ impl Server {
 				special.clone(),
 				authorization.clone(),
 				hosts.clone(),
+				tokens.clone(),
 			))
 			.map(|(l, srv)| {
 
@@ -224,6 +266,7 @@ impl Server {
 				Server {
 					server: Some(l),
 					panic_handler: panic_handler,
+					tokens: server_tokens,
 				}
 			})
 			.map_err(ServerError::from)
@@ -239,6 +282,12 @@ impl Server {
 	pub fn addr(&self) -> &SocketAddr {
 		self.server.as_ref().expect("server is always Some at the start; it's consumed only when object is dropped; qed").addr()
 	}
+
+	#[cfg(test)]
+	/// Returns token given to the dapp by this server.
+	pub fn dapp_token(&self, dapp: &str) -> String {
+		self.tokens.token(dapp)
+	}
 }
 
 impl Drop for Server {
@@ -23,6 +23,7 @@ use hyper::status::StatusCode;
 use hyper::{Decoder, Encoder, Next};
 use endpoint::EndpointPath;
 use handlers::ContentHandler;
+use tokens::TokenInjector;
 
 /// Represents a file that can be sent to client.
 /// Implementation should keep track of bytes already sent internally.
@@ -52,7 +53,7 @@ pub trait Dapp: Send + 'static {
 /// Currently served by `PageHandler` file
 pub enum ServedFile<T: Dapp> {
 	/// File from dapp
-	File(T::DappFile),
+	File(TokenInjector<T::DappFile>),
 	/// Error (404)
 	Error(ContentHandler),
 }
@@ -107,6 +108,7 @@ impl<T: Dapp> PageHandler<T> {
 
 impl<T: Dapp> server::Handler<HttpStream> for PageHandler<T> {
 	fn on_request(&mut self, req: server::Request<HttpStream>) -> Next {
+		let token = self.path.token.clone();
 		self.file = match *req.uri() {
 			RequestUri::AbsolutePath(ref path) => {
 				self.app.file(&self.extract_path(path))
@@ -115,7 +117,7 @@ impl<T: Dapp> server::Handler<HttpStream> for PageHandler<T> {
 				self.app.file(&self.extract_path(url.path()))
 			},
 			_ => None,
-		}.map_or_else(|| ServedFile::default(), |f| ServedFile::File(f));
+		}.map_or_else(|| ServedFile::default(), |f| ServedFile::File(TokenInjector::new(f, token.as_ref().map(|t| t.as_str()))));
 		Next::write()
 	}
 
@@ -211,6 +213,7 @@ fn should_extract_path_with_appid() {
 			host: "".to_owned(),
 			port: 8080,
 			using_dapps_domains: true,
+			token: None,
 		},
 		file: Default::default(),
 		safe_to_embed: true,
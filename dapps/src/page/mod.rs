@@ -17,7 +17,7 @@
 
 mod builtin;
 mod local;
-mod handler;
+pub mod handler;
 
 pub use self::local::LocalPageEndpoint;
 pub use self::builtin::PageEndpoint;
@@ -30,6 +30,7 @@ use apps;
 use apps::fetcher::ContentFetcher;
 use endpoint::{Endpoint, Endpoints, EndpointPath};
 use handlers::{Redirection, extract_url, ContentHandler};
+use tokens::DappTokens;
 use self::auth::{Authorization, Authorized};
 
 /// Special endpoints are accessible on every domain (every dapp)
@@ -49,6 +50,7 @@ pub struct Router<A: Authorization + 'static> {
 	special: Arc<HashMap<SpecialEndpoint, Box<Endpoint>>>,
 	authorization: Arc<A>,
 	allowed_hosts: Option<Vec<String>>,
+	tokens: Arc<DappTokens>,
 	handler: Box<server::Handler<HttpStream> + Send>,
 }
 
@@ -85,7 +87,7 @@ impl<A: Authorization + 'static> server::Handler<HttpStream> for Router<A> {
 			},
 			// Then delegate to dapp
 			(Some(ref path), _) if self.endpoints.contains_key(&path.app_id) => {
-				self.endpoints.get(&path.app_id).unwrap().to_async_handler(path.clone(), control)
+				self.endpoints.get(&path.app_id).unwrap().to_async_handler(self.with_token(path), control)
 			},
 			// Try to resolve and fetch the dapp
 			(Some(ref path), _) if self.fetch.contains(&path.app_id) => {
@@ -141,6 +143,7 @@ impl<A: Authorization> Router<A> {
 		special: Arc<HashMap<SpecialEndpoint, Box<Endpoint>>>,
 		authorization: Arc<A>,
 		allowed_hosts: Option<Vec<String>>,
+		tokens: Arc<DappTokens>,
 		) -> Self {
 
 		let handler = special.get(&SpecialEndpoint::Utils).unwrap().to_handler(EndpointPath::default());
@@ -152,9 +155,22 @@ impl<A: Authorization> Router<A> {
 			special: special,
 			authorization: authorization,
 			allowed_hosts: allowed_hosts,
+			tokens: tokens,
 			handler: handler,
 		}
 	}
+
+	/// Returns path with the token of the dapp to be injected into its pages.
+	/// Only builtin and local dapps served from their own domain are given a token.
+	fn with_token(&self, path: &EndpointPath) -> EndpointPath {
+		EndpointPath {
+			token: match path.using_dapps_domains {
+				true => Some(self.tokens.token(&path.app_id)),
+				false => None,
+			},
+			..path.clone()
+		}
+	}
 }
 
 fn extract_endpoint(url: &Option<Url>) -> (Option<EndpointPath>, SpecialEndpoint) {
@@ -182,6 +198,7 @@ fn extract_endpoint(url: &Option<Url>) -> (Option<EndpointPath>, SpecialEndpoint
 					host: domain.clone(),
 					port: url.port,
 					using_dapps_domains: true,
+					token: None,
 				}), special_endpoint(url))
 			},
 			_ if url.path.len() > 1 => {
@@ -191,6 +208,7 @@ fn extract_endpoint(url: &Option<Url>) -> (Option<EndpointPath>, SpecialEndpoint
 					host: format!("{}", url.host),
 					port: url.port,
 					using_dapps_domains: false,
+					token: None,
 				}), special_endpoint(url))
 			},
 			_ => (None, special_endpoint(url)),
@@ -211,6 +229,7 @@ fn should_extract_endpoint() {
 			host: "localhost".to_owned(),
 			port: 8080,
 			using_dapps_domains: false,
+			token: None,
 		}), SpecialEndpoint::None)
 	);
 
@@ -222,6 +241,7 @@ fn should_extract_endpoint() {
 			host: "localhost".to_owned(),
 			port: 8080,
 			using_dapps_domains: false,
+			token: None,
 		}), SpecialEndpoint::Rpc)
 	);
 
@@ -232,6 +252,7 @@ fn should_extract_endpoint() {
 			host: "my.status.parity".to_owned(),
 			port: 80,
 			using_dapps_domains: true,
+			token: None,
 		}), SpecialEndpoint::Utils)
 	);
 
@@ -243,6 +264,7 @@ fn should_extract_endpoint() {
 			host: "my.status.parity".to_owned(),
 			port: 80,
 			using_dapps_domains: true,
+			token: None,
 		}), SpecialEndpoint::None)
 	);
 
@@ -254,6 +276,7 @@ fn should_extract_endpoint() {
 			host: "my.status.parity".to_owned(),
 			port: 80,
 			using_dapps_domains: true,
+			token: None,
 		}), SpecialEndpoint::Rpc)
 	);
 
@@ -265,6 +288,7 @@ fn should_extract_endpoint() {
 			host: "my.status.parity".to_owned(),
 			port: 80,
 			using_dapps_domains: true,
+			token: None,
 		}), SpecialEndpoint::Api)
 	);
 }
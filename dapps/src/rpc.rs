@@ -15,14 +15,19 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::{Arc, Mutex};
-use hyper;
+use hyper::{self, server, Decoder, Encoder, Next};
+use hyper::net::HttpStream;
 use jsonrpc_core::IoHandler;
 use jsonrpc_http_server::{ServerHandler, PanicHandler, AccessControlAllowOrigin};
+use ethcore_rpc::v1::origin::{self, Origin};
+use ethcore_rpc::v1::request_id::{self, REQUEST_ID_HEADER};
 use endpoint::{Endpoint, EndpointPath, Handler};
+use tokens::{DappTokens, extract_token, is_dapp_endpoint};
 
-pub fn rpc(handler: Arc<IoHandler>, panic_handler: Arc<Mutex<Option<Box<Fn() -> () + Send>>>>) -> Box<Endpoint> {
+pub fn rpc(handler: Arc<IoHandler>, panic_handler: Arc<Mutex<Option<Box<Fn() -> () + Send>>>>, tokens: Arc<DappTokens>) -> Box<Endpoint> {
 	Box::new(RpcEndpoint {
 		handler: handler,
+		tokens: tokens,
 		panic_handler: panic_handler,
 		cors_domain: Some(vec![AccessControlAllowOrigin::Null]),
 		// NOTE [ToDr] We don't need to do any hosts validation here. It's already done in router.
@@ -32,20 +37,79 @@ pub fn rpc(handler: Arc<IoHandler>, panic_handler: Arc<Mutex<Option<Box<Fn() ->
 
 struct RpcEndpoint {
 	handler: Arc<IoHandler>,
+	tokens: Arc<DappTokens>,
 	panic_handler: Arc<Mutex<Option<Box<Fn() -> () + Send>>>>,
 	cors_domain: Option<Vec<AccessControlAllowOrigin>>,
 	allowed_hosts: Option<Vec<String>>,
 }
 
 impl Endpoint for RpcEndpoint {
-	fn to_async_handler(&self, _path: EndpointPath, control: hyper::Control) -> Box<Handler> {
+	fn to_async_handler(&self, path: EndpointPath, control: hyper::Control) -> Box<Handler> {
 		let panic_handler = PanicHandler { handler: self.panic_handler.clone() };
-		Box::new(ServerHandler::new(
+		Box::new(RequestIdHandler {
+			request_id: String::new(),
+			path: path,
+			tokens: self.tokens.clone(),
+			origin: Origin::Dapps,
+			handler: Box::new(ServerHandler::new(
 				self.handler.clone(),
 				self.cors_domain.clone(),
 				self.allowed_hosts.clone(),
 				panic_handler,
 				control,
-		))
+			)),
+		})
+	}
+}
+
+/// Tags every request with an id (taken from `X-Request-Id` or generated),
+/// logs it and echoes it back in the response headers.
+/// Requests with a dapp token sent to that dapp's own domain are handled with the dapp set
+/// as their origin, other requests are marked as coming from an unknown dapp.
+struct RequestIdHandler {
+	request_id: String,
+	path: EndpointPath,
+	tokens: Arc<DappTokens>,
+	origin: Origin,
+	handler: Box<Handler>,
+}
+
+impl server::Handler<HttpStream> for RequestIdHandler {
+	fn on_request(&mut self, request: server::Request<HttpStream>) -> Next {
+		let id = request.headers().get_raw(REQUEST_ID_HEADER)
+			.and_then(|list| list.get(0))
+			.and_then(|id| String::from_utf8(id.clone()).ok());
+		self.request_id = request_id::from_client(id.as_ref().map(|id| id.as_str()));
+
+		let dapp = extract_token(&request)
+			.and_then(|token| self.tokens.dapp(&token))
+			.and_then(|dapp| match is_dapp_endpoint(&dapp, &self.path) {
+				true => Some(dapp),
+				false => {
+					warn!(target: "rpc", "[{}] Ignoring token of {} sent to {}", self.request_id, dapp, self.path.host);
+					None
+				},
+			});
+		self.origin = dapp.map_or(Origin::Dapps, Origin::Dapp);
+
+		debug!(target: "rpc", "[{}] {} {} (origin: {:?})", self.request_id, request.method(), request.uri(), self.origin);
+		let (handler, origin) = (&mut self.handler, self.origin.clone());
+		request_id::with_request_id(self.request_id.clone(), move || origin::with_origin(origin, move || handler.on_request(request)))
+	}
+
+	fn on_request_readable(&mut self, decoder: &mut Decoder<HttpStream>) -> Next {
+		let (handler, origin) = (&mut self.handler, self.origin.clone());
+		request_id::with_request_id(self.request_id.clone(), || origin::with_origin(origin, || handler.on_request_readable(decoder)))
+	}
+
+	fn on_response(&mut self, res: &mut server::Response) -> Next {
+		let next = self.handler.on_response(res);
+		debug!(target: "rpc", "[{}] Responding with {}", self.request_id, res.status());
+		res.headers_mut().set_raw(REQUEST_ID_HEADER, vec![self.request_id.clone().into_bytes()]);
+		next
+	}
+
+	fn on_response_writable(&mut self, encoder: &mut Encoder<HttpStream>) -> Next {
+		self.handler.on_response_writable(encoder)
 	}
 }
@@ -14,7 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use tests::helpers::{serve, serve_with_registrar, request};
+use devtools::{http_client, RandomTempPath};
+use tests::helpers::{serve, serve_with_registrar, serve_with_health, serve_with_settings, request};
+use {Health, HealthCheck};
 
 #[test]
 fn should_return_error() {
@@ -103,3 +105,164 @@ fn should_try_to_resolve_dapp() {
 	assert_eq!(registrar.calls.lock().len(), 2);
 }
 
+fn health(synced: bool) -> Health {
+	Health {
+		peers: HealthCheck { healthy: true, details: "5 peers connected".into() },
+		sync: HealthCheck { healthy: synced, details: "0 blocks behind".into() },
+		tx_queue: HealthCheck { healthy: true, details: "0 of 1024 transactions queued".into() },
+	}
+}
+
+#[test]
+fn should_report_healthy_node() {
+	// given
+	let server = serve_with_health(health(true));
+
+	// when
+	let response = request(server,
+		"\
+			GET /api/health HTTP/1.1\r\n\
+			Host: 127.0.0.1:8080\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	assert_eq!(response.status, "HTTP/1.1 200 OK".to_owned());
+	assert_eq!(response.headers.get(0).unwrap(), "Content-Type: application/json");
+	assert!(response.body.contains(r#""txQueue":{"healthy":true,"details":"0 of 1024 transactions queued"}"#), response.body);
+}
+
+#[test]
+fn should_report_lagging_node_as_unavailable() {
+	// given
+	let server = serve_with_health(health(false));
+
+	// when
+	let response = request(server,
+		"\
+			GET /api/health HTTP/1.1\r\n\
+			Host: 127.0.0.1:8080\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	assert_eq!(response.status, "HTTP/1.1 503 Service Unavailable".to_owned());
+	assert!(response.body.contains(r#""sync":{"healthy":false,"details":"0 blocks behind"}"#), response.body);
+}
+
+#[test]
+fn should_store_dapp_settings_per_identity() {
+	// given
+	let path = RandomTempPath::new();
+
+	// when
+	let stored = request_as_dapp(path.as_str(), "status",
+		"\
+			PUT /api/dapps/status/settings?identity=0x01 HTTP/1.1\r\n\
+			Host: status.parity\r\n\
+			X-Parity-Dapp-Token: {token}\r\n\
+			Connection: close\r\n\
+			Content-Length: 16\r\n\
+			\r\n\
+			{\"theme\":\"dark\"}\
+		"
+	);
+	let read = request_as_dapp(path.as_str(), "status",
+		"\
+			GET /api/dapps/status/settings?identity=0x01 HTTP/1.1\r\n\
+			Host: status.parity\r\n\
+			X-Parity-Dapp-Token: {token}\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+	let other = request_as_dapp(path.as_str(), "status",
+		"\
+			GET /api/dapps/status/settings HTTP/1.1\r\n\
+			Host: status.parity\r\n\
+			X-Parity-Dapp-Token: {token}\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	assert_eq!(stored.status, "HTTP/1.1 200 OK".to_owned());
+	assert_eq!(read.status, "HTTP/1.1 200 OK".to_owned());
+	assert_eq!(read.headers.get(0).unwrap(), "Content-Type: application/json");
+	assert_eq!(read.body, "10\n{\"theme\":\"dark\"}\n0\n\n".to_owned());
+	assert_eq!(other.body, "2\n{}\n0\n\n".to_owned());
+}
+
+#[test]
+fn should_reject_invalid_dapp_settings() {
+	// given
+	let path = RandomTempPath::new();
+
+	// when
+	let response = request_as_dapp(path.as_str(), "status",
+		"\
+			PUT /api/dapps/status/settings HTTP/1.1\r\n\
+			Host: status.parity\r\n\
+			X-Parity-Dapp-Token: {token}\r\n\
+			Connection: close\r\n\
+			Content-Length: 7\r\n\
+			\r\n\
+			[1,2,3]\
+		"
+	);
+
+	// then
+	assert_eq!(response.status, "HTTP/1.1 400 Bad Request".to_owned());
+	assert!(response.body.contains("Settings have to be a JSON object."), response.body);
+}
+
+#[test]
+fn should_not_give_dapp_settings_without_token() {
+	// given
+	let path = RandomTempPath::new();
+
+	// when
+	let response = request(serve_with_settings(path.as_str()),
+		"\
+			GET /api/dapps/status/settings HTTP/1.1\r\n\
+			Host: 127.0.0.1:8080\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	assert_eq!(response.status, "HTTP/1.1 403 Forbidden".to_owned());
+}
+
+#[test]
+fn should_not_give_dapp_settings_to_other_dapps() {
+	// given
+	let path = RandomTempPath::new();
+
+	// when
+	let response = request_as_dapp(path.as_str(), "home",
+		"\
+			GET /api/dapps/status/settings HTTP/1.1\r\n\
+			Host: home.parity\r\n\
+			X-Parity-Dapp-Token: {token}\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	assert_eq!(response.status, "HTTP/1.1 403 Forbidden".to_owned());
+}
+
+// sends the request with the token `dapp` was given by the server.
+fn request_as_dapp(settings_path: &str, dapp: &str, req: &str) -> http_client::Response {
+	let server = serve_with_settings(settings_path);
+	let req = req.replace("{token}", &server.dapp_token(dapp));
+	request(server, &req)
+}
@@ -21,6 +21,7 @@ use rustc_serialize::hex::FromHex;
 
 use ServerBuilder;
 use Server;
+use Health;
 use apps::urlhint::ContractClient;
 use util::{Bytes, Address, Mutex, ToPretty};
 use devtools::http_client;
@@ -77,6 +78,24 @@ pub fn serve_with_auth(user: &str, pass: &str) -> Server {
 	builder.start_basic_auth_http(&"127.0.0.1:0".parse().unwrap(), None, user, pass).unwrap()
 }
 
+pub fn serve_with_health(health: Health) -> Server {
+	let registrar = Arc::new(FakeRegistrar::new());
+	let mut dapps_path = env::temp_dir();
+	dapps_path.push("non-existent-dir-to-prevent-fs-files-from-loading");
+	let mut builder = ServerBuilder::new(dapps_path.to_str().unwrap().into(), registrar);
+	builder.with_health(Arc::new(move || health.clone()));
+	builder.start_unsecured_http(&"127.0.0.1:0".parse().unwrap(), None).unwrap()
+}
+
+pub fn serve_with_settings(settings_path: &str) -> Server {
+	let registrar = Arc::new(FakeRegistrar::new());
+	let mut dapps_path = env::temp_dir();
+	dapps_path.push("non-existent-dir-to-prevent-fs-files-from-loading");
+	let mut builder = ServerBuilder::new(dapps_path.to_str().unwrap().into(), registrar);
+	builder.with_settings_path(settings_path.into());
+	builder.start_unsecured_http(&"127.0.0.1:0".parse().unwrap(), None).unwrap()
+}
+
 pub fn serve_hosts(hosts: Option<Vec<String>>) -> Server {
 	init_server(hosts).0
 }
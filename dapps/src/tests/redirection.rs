@@ -140,6 +140,91 @@ fn should_serve_rpc_at_slash_rpc() {
 	assert_eq!(response.body, format!("58\n{}\n\n0\n\n", r#"{"jsonrpc":"2.0","error":{"code":-32700,"message":"Parse error","data":null},"id":null}"#));
 }
 
+#[test]
+fn should_echo_rpc_request_id() {
+	// given
+	let server = serve();
+
+	// when
+	let response = request(server,
+		"\
+			POST /rpc HTTP/1.1\r\n\
+			Host: 127.0.0.1:8080\r\n\
+			Connection: close\r\n\
+			X-Request-Id: proxy-42\r\n\
+			Content-Type: application/json\r\n
+			\r\n\
+			{}
+		"
+	);
+
+	// then
+	assert_eq!(response.status, "HTTP/1.1 200 OK".to_owned());
+	assert!(response.headers.contains(&"X-Request-Id: proxy-42".to_owned()), "{:?}", response.headers);
+}
+
+#[test]
+fn should_generate_rpc_request_id() {
+	// given
+	let server = serve();
+
+	// when
+	let response = request(server,
+		"\
+			POST /rpc HTTP/1.1\r\n\
+			Host: 127.0.0.1:8080\r\n\
+			Connection: close\r\n\
+			Content-Type: application/json\r\n
+			\r\n\
+			{}
+		"
+	);
+
+	// then
+	assert!(response.headers.iter().any(|h| h.starts_with("X-Request-Id: ")), "{:?}", response.headers);
+}
+
+#[test]
+fn should_inject_dapp_token() {
+	// given
+	let server = serve();
+
+	// when
+	let response = request(server,
+		"\
+			GET / HTTP/1.1\r\n\
+			Host: home.parity\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	assert_eq!(response.status, "HTTP/1.1 200 OK".to_owned());
+	assert!(response.body.contains("window.PARITY_DAPP_TOKEN = "), "{:?}", response.body);
+	assert!(!response.headers.iter().any(|h| h.starts_with("Set-Cookie")), "{:?}", response.headers);
+}
+
+#[test]
+fn should_not_inject_dapp_token_on_shared_origin() {
+	// given
+	let server = serve();
+
+	// when
+	let response = request(server,
+		"\
+			GET /home/ HTTP/1.1\r\n\
+			Host: 127.0.0.1:8080\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	assert_eq!(response.status, "HTTP/1.1 200 OK".to_owned());
+	assert!(!response.body.contains("window.PARITY_DAPP_TOKEN"), "{:?}", response.body);
+}
+
 
 #[test]
 fn should_serve_proxy_pac() {
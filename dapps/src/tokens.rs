@@ -0,0 +1,249 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-dapp RPC tokens.
+//!
+//! Every HTML page of a builtin or local dapp served from the dapp's own domain gets the dapp's
+//! token injected as `window.PARITY_DAPP_TOKEN`. RPC requests carrying the token in
+//! `X-Parity-Dapp-Token` header are attributed to that dapp only if they are sent to the dapp's
+//! own domain. Pages of other dapps and websites have a different origin, so they cannot read
+//! the token. Dapps sharing the node's origin can read each other's pages and get no token.
+
+use std::ascii::AsciiExt;
+use std::collections::HashMap;
+use rand::{self, Rng};
+use hyper::server;
+use hyper::net::HttpStream;
+use util::RwLock;
+use endpoint::EndpointPath;
+use page::handler::DappFile;
+
+/// Header that dapps use to send their token with RPC requests.
+pub const TOKEN_HEADER: &'static str = "X-Parity-Dapp-Token";
+const TOKEN_LEN: usize = 32;
+
+/// Tokens of dapps served by this node, generated when dapp is served for the first time.
+#[derive(Default)]
+pub struct DappTokens {
+	tokens: RwLock<HashMap<String, String>>,
+}
+
+impl DappTokens {
+	/// Returns token of given dapp.
+	pub fn token(&self, dapp: &str) -> String {
+		if let Some(token) = self.tokens.read().get(dapp) {
+			return token.clone();
+		}
+		self.tokens.write()
+			.entry(dapp.to_owned())
+			.or_insert_with(|| rand::thread_rng().gen_ascii_chars().take(TOKEN_LEN).collect())
+			.clone()
+	}
+
+	/// Returns dapp that was given the token.
+	pub fn dapp(&self, token: &str) -> Option<String> {
+		self.tokens.read().iter()
+			.find(|&(_, t)| t == token)
+			.map(|(dapp, _)| dapp.clone())
+	}
+}
+
+// returns first value of given request header.
+fn raw_header(request: &server::Request<HttpStream>, name: &str) -> Option<String> {
+	request.headers().get_raw(name)
+		.and_then(|list| list.get(0))
+		.and_then(|value| String::from_utf8(value.clone()).ok())
+		.map(|value| value.trim().to_owned())
+}
+
+/// Extracts dapp token from request headers.
+pub fn extract_token(request: &server::Request<HttpStream>) -> Option<String> {
+	raw_header(request, TOKEN_HEADER)
+}
+
+/// Checks that a request carrying the token of `dapp` was sent to the dapp's own domain.
+/// `path` is the path of the endpoint serving the request.
+pub fn is_dapp_endpoint(dapp: &str, path: &EndpointPath) -> bool {
+	path.using_dapps_domains && path.app_id == dapp
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Injection {
+	Pending,
+	BeforeScript(usize),
+	Script(usize),
+	Done,
+}
+
+/// Injects dapp token into HTML documents, right after the opening `<head>` tag.
+/// Other files are served unchanged.
+pub struct TokenInjector<F: DappFile> {
+	file: F,
+	script: Vec<u8>,
+	state: Injection,
+}
+
+impl<F: DappFile> TokenInjector<F> {
+	pub fn new(file: F, token: Option<&str>) -> Self {
+		let state = match token {
+			Some(_) if file.content_type().starts_with("text/html") => Injection::Pending,
+			_ => Injection::Done,
+		};
+		TokenInjector {
+			file: file,
+			script: token.map(|token| format!("<script>window.PARITY_DAPP_TOKEN = \"{}\";</script>", token).into_bytes()).unwrap_or_else(Vec::new),
+			state: state,
+		}
+	}
+}
+
+// returns position right after the opening `<head>` tag.
+fn head_end(chunk: &[u8]) -> Option<usize> {
+	let lowercase = chunk.to_ascii_lowercase();
+	lowercase.windows(6).position(|w| &w[..5] == b"<head" && (w[5] == b'>' || w[5] == b' ' || w[5] == b'\t' || w[5] == b'\r' || w[5] == b'\n'))
+		.and_then(|start| lowercase[start..].iter().position(|c| *c == b'>').map(|end| start + end + 1))
+}
+
+impl<F: DappFile> DappFile for TokenInjector<F> {
+	fn content_type(&self) -> &str {
+		self.file.content_type()
+	}
+
+	fn is_drained(&self) -> bool {
+		match self.state {
+			Injection::BeforeScript(_) | Injection::Script(_) => false,
+			_ => self.file.is_drained(),
+		}
+	}
+
+	fn next_chunk(&mut self) -> &[u8] {
+		if let Injection::Pending = self.state {
+			// the tag needs to be in the first chunk, the token is not injected otherwise.
+			self.state = match head_end(self.file.next_chunk()) {
+				Some(end) => Injection::BeforeScript(end),
+				None => Injection::Done,
+			};
+		}
+		match self.state {
+			Injection::BeforeScript(remaining) => &self.file.next_chunk()[..remaining],
+			Injection::Script(written) => &self.script[written..],
+			_ => self.file.next_chunk(),
+		}
+	}
+
+	fn bytes_written(&mut self, bytes: usize) {
+		self.state = match self.state {
+			Injection::BeforeScript(remaining) => {
+				self.file.bytes_written(bytes);
+				match remaining - bytes {
+					0 => Injection::Script(0),
+					remaining => Injection::BeforeScript(remaining),
+				}
+			},
+			Injection::Script(written) if written + bytes == self.script.len() => Injection::Done,
+			Injection::Script(written) => Injection::Script(written + bytes),
+			Injection::Pending => {
+				self.file.bytes_written(bytes);
+				Injection::Pending
+			},
+			Injection::Done => {
+				self.file.bytes_written(bytes);
+				Injection::Done
+			},
+		};
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use endpoint::EndpointPath;
+	use page::handler::DappFile;
+	use super::{DappTokens, TokenInjector, is_dapp_endpoint};
+
+	struct TestFile {
+		content_type: &'static str,
+		content: Vec<u8>,
+		pos: usize,
+	}
+
+	impl DappFile for TestFile {
+		fn content_type(&self) -> &str { self.content_type }
+		fn is_drained(&self) -> bool { self.pos == self.content.len() }
+		fn next_chunk(&mut self) -> &[u8] { &self.content[self.pos..] }
+		fn bytes_written(&mut self, bytes: usize) { self.pos += bytes; }
+	}
+
+	fn serve(content_type: &'static str, content: &str, token: Option<&str>) -> String {
+		let file = TestFile { content_type: content_type, content: content.as_bytes().to_vec(), pos: 0 };
+		let mut injector = TokenInjector::new(file, token);
+		let mut result = Vec::new();
+		while !injector.is_drained() {
+			let chunk = injector.next_chunk().to_vec();
+			// write only part of the chunk
+			let len = ::std::cmp::min(chunk.len(), 5);
+			result.extend_from_slice(&chunk[..len]);
+			injector.bytes_written(len);
+		}
+		String::from_utf8(result).unwrap()
+	}
+
+	fn path(app_id: &str, using_dapps_domains: bool) -> EndpointPath {
+		EndpointPath {
+			app_id: app_id.into(),
+			host: "127.0.0.1".into(),
+			port: 8080,
+			using_dapps_domains: using_dapps_domains,
+			token: None,
+		}
+	}
+
+	#[test]
+	fn should_inject_token_into_html_head() {
+		assert_eq!(
+			serve("text/html", "<!DOCTYPE html><html><HEAD lang=\"en\"><title></title></head></html>", Some("abc")),
+			"<!DOCTYPE html><html><HEAD lang=\"en\"><script>window.PARITY_DAPP_TOKEN = \"abc\";</script><title></title></head></html>"
+		);
+		assert_eq!(serve("text/html", "<html><header></header></html>", Some("abc")), "<html><header></header></html>");
+		assert_eq!(serve("text/html", "<html><head></head></html>", None), "<html><head></head></html>");
+		assert_eq!(serve("text/css", "<head>", Some("abc")), "<head>");
+	}
+
+	#[test]
+	fn should_accept_token_only_on_dapp_domain() {
+		assert!(is_dapp_endpoint("wallet", &path("wallet", true)));
+		assert!(!is_dapp_endpoint("home", &path("wallet", true)));
+		// dapps sharing the node's origin cannot be told apart
+		assert!(!is_dapp_endpoint("wallet", &path("wallet", false)));
+		assert!(!is_dapp_endpoint("wallet", &path("rpc", false)));
+	}
+
+	#[test]
+	fn should_give_each_dapp_its_own_token() {
+		// given
+		let tokens = DappTokens::default();
+
+		// when
+		let wallet = tokens.token("wallet");
+		let home = tokens.token("home");
+
+		// then
+		assert!(wallet != home);
+		assert_eq!(tokens.token("wallet"), wallet);
+		assert_eq!(tokens.dapp(&wallet), Some("wallet".into()));
+		assert_eq!(tokens.dapp(&home), Some("home".into()));
+		assert_eq!(tokens.dapp("unknown"), None);
+	}
+}
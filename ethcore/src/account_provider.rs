@@ -24,9 +24,12 @@ use util::{Mutex, RwLock};
 use ethstore::{SecretStore, Error as SSError, SafeAccount, EthStore};
 use ethstore::dir::{KeyDirectory};
 use ethstore::ethkey::{Address, Message, Secret, Random, Generator};
-use ethjson::misc::AccountMeta;
+use ethjson::misc::{AccountMeta, DappsSettings};
 pub use ethstore::ethkey::Signature;
 
+/// Identifier of a dapp, as attributed to its RPC requests.
+pub type DappId = String;
+
 /// Type of unlock.
 #[derive(Clone)]
 enum Unlock {
@@ -46,6 +49,13 @@ struct AccountData {
 	password: String,
 }
 
+/// Key used by the consensus engine to seal blocks.
+struct EngineSigner {
+	store: Box<SecretStore>,
+	address: Address,
+	password: String,
+}
+
 /// `AccountProvider` errors.
 #[derive(Debug)]
 pub enum Error {
@@ -153,12 +163,107 @@ impl AddressBook {
 	}
 }
 
+/// Disk-backed map from dapp id to its settings. Uses JSON.
+struct DappsSettingsStore {
+	path: Option<PathBuf>,
+	cache: HashMap<DappId, DappsSettings>,
+}
+
+impl DappsSettingsStore {
+	pub fn new(path: String) -> Self {
+		let mut path: PathBuf = path.into();
+		path.push("dapps_accounts.json");
+		trace!(target: "dapps", "path={:?}", path);
+		let mut r = DappsSettingsStore {
+			path: Some(path),
+			cache: HashMap::new(),
+		};
+		r.revert();
+		r
+	}
+
+	/// Store which is never written to disk.
+	pub fn transient() -> Self {
+		DappsSettingsStore {
+			path: None,
+			cache: HashMap::new(),
+		}
+	}
+
+	/// Accounts exposed to the dapp, starting with its default one.
+	pub fn accounts(&self, dapp: &str) -> Vec<Address> {
+		let settings = match self.cache.get(dapp) {
+			Some(settings) => settings,
+			None => return Vec::new(),
+		};
+		settings.default.iter()
+			.chain(settings.accounts.iter().filter(|a| Some(*a) != settings.default.as_ref()))
+			.cloned()
+			.map(Into::into)
+			.collect()
+	}
+
+	/// Replaces accounts exposed to the dapp. Default account is dropped if it's not among them.
+	pub fn set_accounts(&mut self, dapp: DappId, accounts: Vec<Address>) {
+		{
+			let settings = self.cache.entry(dapp).or_insert_with(Default::default);
+			settings.accounts = accounts.into_iter().map(Into::into).collect();
+			if settings.default.as_ref().map_or(false, |d| !settings.accounts.contains(d)) {
+				settings.default = None;
+			}
+		}
+		self.save();
+	}
+
+	/// Sets the default account of the dapp, exposing it if it wasn't yet.
+	pub fn set_default(&mut self, dapp: DappId, account: Address) {
+		{
+			let settings = self.cache.entry(dapp).or_insert_with(Default::default);
+			let account = account.into();
+			if !settings.accounts.contains(&account) {
+				settings.accounts.push(account.clone());
+			}
+			settings.default = Some(account);
+		}
+		self.save();
+	}
+
+	fn revert(&mut self) {
+		let path = match self.path {
+			Some(ref path) => path.clone(),
+			None => return,
+		};
+		let _ = fs::File::open(path)
+			.map_err(|e| trace!(target: "dapps", "Couldn't open dapps settings: {}", e))
+			.and_then(|f| DappsSettings::read_dapps_settings(&f)
+				.map_err(|e| warn!(target: "dapps", "Couldn't read dapps settings: {}", e))
+				.and_then(|m| { self.cache = m; Ok(()) })
+			);
+	}
+
+	fn save(&mut self) {
+		let path = match self.path {
+			Some(ref path) => path.clone(),
+			None => return,
+		};
+		let _ = fs::File::create(path)
+			.map_err(|e| warn!(target: "dapps", "Couldn't open dapps settings for writing: {}", e))
+			.and_then(|mut f| DappsSettings::write_dapps_settings(&self.cache, &mut f)
+				.map_err(|e| warn!(target: "dapps", "Couldn't write to dapps settings: {}", e))
+			);
+	}
+}
+
 /// Account management.
 /// Responsible for unlocking accounts.
 pub struct AccountProvider {
 	unlocked: Mutex<HashMap<Address, AccountData>>,
 	sstore: Box<SecretStore>,
 	address_book: Mutex<AddressBook>,
+	/// Accounts each dapp is allowed to see.
+	dapps_settings: RwLock<DappsSettingsStore>,
+	/// Node key kept apart from user accounts; never exposed through account management.
+	engine_signer: RwLock<Option<EngineSigner>>,
 }
 
 impl AccountProvider {
@@ -167,7 +272,9 @@ impl AccountProvider {
 		AccountProvider {
 			unlocked: Mutex::new(HashMap::new()),
 			address_book: Mutex::new(AddressBook::new(sstore.local_path().into())),
+			dapps_settings: RwLock::new(DappsSettingsStore::new(sstore.local_path().into())),
 			sstore: sstore,
+			engine_signer: RwLock::new(None),
 		}
 	}
 
@@ -176,7 +283,9 @@ impl AccountProvider {
 		AccountProvider {
 			unlocked: Mutex::new(HashMap::new()),
 			address_book: Mutex::new(AddressBook::new(Default::default())),
-			sstore: Box::new(EthStore::open(Box::new(NullDir::default())).unwrap())
+			dapps_settings: RwLock::new(DappsSettingsStore::transient()),
+			sstore: Box::new(EthStore::open(Box::new(NullDir::default())).unwrap()),
+			engine_signer: RwLock::new(None),
 		}
 	}
 
@@ -212,6 +321,38 @@ impl AccountProvider {
 		Ok(accounts)
 	}
 
+	/// Returns addresses of accounts the given dapp is allowed to see.
+	/// Only accounts still present in the store are returned.
+	pub fn dapps_addresses(&self, dapp: &str) -> Result<Vec<Address>, Error> {
+		let accounts = try!(self.sstore.accounts());
+		Ok(self.dapps_settings.read().accounts(dapp)
+			.into_iter()
+			.filter(|address| accounts.contains(address))
+			.collect()
+		)
+	}
+
+	/// Sets addresses of accounts the given dapp is allowed to see, replacing previous ones.
+	pub fn set_dapps_addresses(&self, dapp: DappId, addresses: Vec<Address>) -> Result<(), Error> {
+		self.dapps_settings.write().set_accounts(dapp, addresses);
+		Ok(())
+	}
+
+	/// Returns the account the given dapp uses by default: the first of `dapps_addresses`.
+	pub fn dapps_default_address(&self, dapp: &str) -> Result<Option<Address>, Error> {
+		Ok(try!(self.dapps_addresses(dapp)).into_iter().next())
+	}
+
+	/// Sets the account the given dapp uses by default. It's exposed to the dapp if it wasn't yet.
+	/// Fails if the account is not in the key store.
+	pub fn set_dapps_default_address(&self, dapp: DappId, address: Address) -> Result<(), Error> {
+		if !try!(self.sstore.accounts()).contains(&address) {
+			return Err(SSError::InvalidAccount.into());
+		}
+		self.dapps_settings.write().set_default(dapp, address);
+		Ok(())
+	}
+
 	/// Returns each address along with metadata.
 	pub fn addresses_info(&self) -> Result<HashMap<Address, AccountMeta>, Error> {
 		Ok(self.address_book.lock().get())
@@ -257,6 +398,28 @@ impl AccountProvider {
 		Ok(())
 	}
 
+	/// Returns `true` if the password unlocks given account.
+	pub fn test_password(&self, account: &Address, password: &str) -> Result<bool, Error> {
+		let valid = try!(self.sstore.test_password(account, password));
+		Ok(valid)
+	}
+
+	/// Changes the password of given account.
+	pub fn change_password(&self, account: &Address, password: String, new_password: String) -> Result<(), Error> {
+		try!(self.sstore.change_password(account, &password, &new_password));
+		// keep unlocked account usable
+		if let Some(data) = self.unlocked.lock().get_mut(account) {
+			data.password = new_password;
+		}
+		Ok(())
+	}
+
+	/// Returns the encrypted key file of given account as JSON.
+	pub fn export_account(&self, account: &Address, password: String) -> Result<String, Error> {
+		let exported = try!(self.sstore.export_account(account, &password));
+		Ok(exported)
+	}
+
 	/// Helper method used for unlocking accounts.
 	fn unlock_account(&self, account: Address, password: String, unlock: Unlock) -> Result<(), Error> {
 		// verify password by signing dump message
@@ -301,6 +464,37 @@ impl AccountProvider {
 		unlocked.get(&account).is_some()
 	}
 
+	/// Sets the key used by the engine to seal blocks. `store` should be separate from the store
+	/// of user accounts, so that the key can't be used or exported through account management.
+	pub fn set_engine_signer(&self, store: Box<SecretStore>, address: Address, password: String) -> Result<(), Error> {
+		// verify password by signing dump message
+		let _ = try!(store.sign(&address, &password, &Default::default()));
+		*self.engine_signer.write() = Some(EngineSigner {
+			store: store,
+			address: address,
+			password: password,
+		});
+		Ok(())
+	}
+
+	/// Returns address of the engine signer if set.
+	pub fn engine_signer(&self) -> Option<Address> {
+		self.engine_signer.read().as_ref().map(|signer| signer.address)
+	}
+
+	/// Signs the message on behalf of the engine.
+	/// If the engine signer is set only its key is used, otherwise the account must be unlocked.
+	pub fn sign_as_engine(&self, account: Address, message: Message) -> Result<Signature, Error> {
+		match *self.engine_signer.read() {
+			Some(ref signer) if signer.address == account => {
+				let signature = try!(signer.store.sign(&account, &signer.password, &message));
+				Ok(signature)
+			},
+			Some(_) => Err(Error::NotUnlocked),
+			None => self.sign(account, message),
+		}
+	}
+
 	/// Signs the message. Account must be unlocked.
 	pub fn sign(&self, account: Address, message: Message) -> Result<Signature, Error> {
 		let data = {
@@ -361,10 +555,11 @@ impl AccountProvider {
 
 #[cfg(test)]
 mod tests {
-	use super::{AccountProvider, AddressBook};
+	use super::{AccountProvider, AddressBook, DappsSettingsStore, NullDir};
 	use std::collections::HashMap;
+	use ethstore::{EthStore, SecretStore};
 	use ethjson::misc::AccountMeta;
-	use ethstore::ethkey::{Generator, Random};
+	use ethstore::ethkey::{Address, Generator, Random};
 	use std::time::Duration;
 	use devtools::RandomTempPath;
 
@@ -379,6 +574,52 @@ mod tests {
 		assert_eq!(b.get(), hash_map![1.into() => AccountMeta{name: "One".to_owned(), meta: "{1:1}".to_owned(), uuid: None}]);
 	}
 
+	#[test]
+	fn should_save_and_reload_dapps_settings() {
+		let temp = RandomTempPath::create_dir();
+		let path = temp.as_str().to_owned();
+		let mut b = DappsSettingsStore::new(path.clone());
+		b.set_accounts("dappOne".into(), vec![1.into(), 2.into()]);
+		let b = DappsSettingsStore::new(path);
+		assert_eq!(b.accounts("dappOne"), vec![Address::from(1), Address::from(2)]);
+		assert!(b.accounts("dappTwo").is_empty());
+	}
+
+	#[test]
+	fn should_only_expose_existing_accounts_to_dapps() {
+		let kp = Random.generate().unwrap();
+		let ap = AccountProvider::transient_provider();
+		assert!(ap.insert_account(kp.secret().clone(), "test").is_ok());
+		assert!(ap.dapps_addresses("app1").unwrap().is_empty());
+
+		ap.set_dapps_addresses("app1".into(), vec![kp.address(), 5.into()]).unwrap();
+		assert_eq!(ap.dapps_addresses("app1").unwrap(), vec![kp.address()]);
+		assert!(ap.dapps_addresses("app2").unwrap().is_empty());
+	}
+
+	#[test]
+	fn should_list_dapps_default_address_first() {
+		let ap = AccountProvider::transient_provider();
+		let first = ap.new_account("test").unwrap();
+		let second = ap.new_account("test").unwrap();
+		let third = ap.new_account("test").unwrap();
+		ap.set_dapps_addresses("app1".into(), vec![first, second]).unwrap();
+		assert_eq!(ap.dapps_default_address("app1").unwrap(), Some(first));
+
+		ap.set_dapps_default_address("app1".into(), third).unwrap();
+		assert_eq!(ap.dapps_addresses("app1").unwrap(), vec![third, first, second]);
+		assert_eq!(ap.dapps_default_address("app1").unwrap(), Some(third));
+
+		// removing default from exposed accounts resets it
+		ap.set_dapps_addresses("app1".into(), vec![second, first]).unwrap();
+		assert_eq!(ap.dapps_default_address("app1").unwrap(), Some(second));
+		assert_eq!(ap.dapps_default_address("app2").unwrap(), None);
+
+		// only accounts from the key store can be set
+		assert!(ap.set_dapps_default_address("app1".into(), 5.into()).is_err());
+		assert_eq!(ap.dapps_default_address("app1").unwrap(), Some(second));
+	}
+
 	#[test]
 	fn unlock_account_temp() {
 		let kp = Random.generate().unwrap();
@@ -415,4 +656,39 @@ mod tests {
 		::std::thread::sleep(Duration::from_millis(2000));
 		assert!(ap.sign(kp.address(), Default::default()).is_err());
 	}
+
+	#[test]
+	fn should_sign_with_engine_signer_only() {
+		let kp = Random.generate().unwrap();
+		let ap = AccountProvider::transient_provider();
+		assert!(ap.insert_account(kp.secret().clone(), "test").is_ok());
+		assert!(ap.unlock_account_permanently(kp.address(), "test".into()).is_ok());
+		assert!(ap.sign_as_engine(kp.address(), Default::default()).is_ok());
+
+		let engine_kp = Random.generate().unwrap();
+		let store = EthStore::open(Box::new(NullDir::default())).unwrap();
+		store.insert_account(engine_kp.secret().clone(), "engine").unwrap();
+		assert!(ap.set_engine_signer(Box::new(store), engine_kp.address(), "wrong".into()).is_err());
+
+		let store = EthStore::open(Box::new(NullDir::default())).unwrap();
+		store.insert_account(engine_kp.secret().clone(), "engine").unwrap();
+		assert!(ap.set_engine_signer(Box::new(store), engine_kp.address(), "engine".into()).is_ok());
+		assert_eq!(ap.engine_signer(), Some(engine_kp.address()));
+		assert!(ap.sign_as_engine(engine_kp.address(), Default::default()).is_ok());
+		assert!(ap.sign_as_engine(kp.address(), Default::default()).is_err());
+		assert!(ap.accounts().unwrap().iter().all(|a| *a != engine_kp.address()));
+	}
+
+	#[test]
+	fn change_and_test_password() {
+		let kp = Random.generate().unwrap();
+		let ap = AccountProvider::transient_provider();
+		assert!(ap.insert_account(kp.secret().clone(), "test").is_ok());
+		assert!(ap.unlock_account_permanently(kp.address(), "test".into()).is_ok());
+		assert!(ap.change_password(&kp.address(), "test1".into(), "test2".into()).is_err());
+		assert!(ap.change_password(&kp.address(), "test".into(), "test2".into()).is_ok());
+		assert!(!ap.test_password(&kp.address(), "test").unwrap());
+		assert!(ap.test_password(&kp.address(), "test2").unwrap());
+		assert!(ap.sign(kp.address(), Default::default()).is_ok());
+	}
 }
@@ -333,6 +333,20 @@ impl<'x> OpenBlock<'x> {
 		}
 	}
 
+	/// Set the balance of account `a` directly, outside of any transaction.
+	///
+	/// Only meant for development chains: no other node could reproduce the resulting state root.
+	pub fn set_balance(&mut self, a: &Address, balance: &U256) -> Result<(), Error> {
+		let state = &mut self.block.state;
+		let current = state.balance(a);
+		if current > *balance {
+			state.sub_balance(a, &(current - *balance));
+		} else {
+			state.add_balance(a, &(*balance - current));
+		}
+		state.commit()
+	}
+
 	/// Push a transaction into the block.
 	///
 	/// If valid, it will be executed, and archived together with the receipt.
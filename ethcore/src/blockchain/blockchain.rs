@@ -36,6 +36,8 @@ use cache_manager::CacheManager;
 
 const LOG_BLOOMS_LEVELS: usize = 3;
 const LOG_BLOOMS_ELEMENTS_PER_INDEX: usize = 16;
+/// Maximal number of block bodies removed by a single `prune_ancient_bodies` call.
+const MAX_PRUNED_BODIES_PER_BATCH: BlockNumber = 1000;
 
 /// Interface for querying blocks by hash and by number.
 pub trait BlockProvider {
@@ -132,6 +134,11 @@ pub trait BlockProvider {
 	/// Returns logs matching given filter.
 	fn logs<F>(&self, mut blocks: Vec<BlockNumber>, matches: F, limit: Option<usize>) -> Vec<LocalizedLogEntry>
 		where F: Fn(&LogEntry) -> bool, Self: Sized;
+
+	/// Returns logs from given blocks, which don't have to be canonical. Blocks are expected
+	/// in descending order; logs are returned in ascending order.
+	fn logs_by_hash<F>(&self, blocks: Vec<H256>, matches: F, limit: Option<usize>) -> Vec<LocalizedLogEntry>
+		where F: Fn(&LogEntry) -> bool, Self: Sized;
 }
 
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
@@ -163,6 +170,8 @@ pub struct BlockChain {
 	first_block: H256,
 
 	best_block: RwLock<BestBlock>,
+	// canonical blocks below this number have had their bodies and receipts removed.
+	pruned_before: RwLock<BlockNumber>,
 
 	// block cache
 	block_headers: RwLock<HashMap<H256, Bytes>>,
@@ -326,9 +335,15 @@ impl BlockProvider for BlockChain {
 		// sort in reverse order
 		blocks.sort_by(|a, b| b.cmp(a));
 
+		let hashes = blocks.into_iter().filter_map(|number| self.block_hash(number)).collect();
+		self.logs_by_hash(hashes, matches, limit)
+	}
+
+	fn logs_by_hash<F>(&self, blocks: Vec<H256>, matches: F, limit: Option<usize>) -> Vec<LocalizedLogEntry>
+		where F: Fn(&LogEntry) -> bool, Self: Sized {
 		let mut log_index = 0;
 		let mut logs = blocks.into_iter()
-			.filter_map(|number| self.block_hash(number).map(|hash| (number, hash)))
+			.filter_map(|hash| self.block_number(&hash).map(|number| (number, hash)))
 			.filter_map(|(number, hash)| self.block_receipts(&hash).map(|r| (number, hash, r.receipts)))
 			.filter_map(|(number, hash, receipts)| self.block_body(&hash).map(|ref b| (number, hash, receipts, BodyView::new(b).transaction_hashes())))
 			.flat_map(|(number, hash, mut receipts, mut hashes)| {
@@ -399,6 +414,7 @@ impl BlockChain {
 			},
 			first_block: H256::zero(),
 			best_block: RwLock::new(BestBlock::default()),
+			pruned_before: RwLock::new(0),
 			block_headers: RwLock::new(HashMap::new()),
 			block_bodies: RwLock::new(HashMap::new()),
 			block_details: RwLock::new(HashMap::new()),
@@ -480,6 +496,10 @@ impl BlockChain {
 				bc.first_block = H256::from_slice(&raw_first);
 			}
 
+			if let Some(pruned) = bc.db.get(db::COL_EXTRA, b"pruned").unwrap() {
+				*bc.pruned_before.write() = decode(&pruned);
+			}
+
 			// and write them
 			let mut best_block = bc.best_block.write();
 			*best_block = BestBlock {
@@ -1133,6 +1153,35 @@ impl BlockChain {
 		self.best_block.read().number
 	}
 
+	/// Get the number of the first canonical block whose body and receipts were not pruned.
+	pub fn pruned_before(&self) -> BlockNumber {
+		*self.pruned_before.read()
+	}
+
+	/// Remove bodies and receipts of canonical blocks below `until`, keeping their headers.
+	/// Works through at most `MAX_PRUNED_BODIES_PER_BATCH` blocks per call.
+	pub fn prune_ancient_bodies(&self, batch: &mut DBTransaction, until: BlockNumber) {
+		let mut pruned_before = self.pruned_before.write();
+		let end = min(until, *pruned_before + MAX_PRUNED_BODIES_PER_BATCH);
+		if end <= *pruned_before {
+			return;
+		}
+
+		let hashes: Vec<H256> = (*pruned_before..end).filter_map(|n| self.block_hash(n)).collect();
+		let mut block_bodies = self.block_bodies.write();
+		let mut block_receipts = self.block_receipts.write();
+		for hash in hashes {
+			type ReceiptsKey = Key<BlockReceipts, Target=H264>;
+			batch.delete(db::COL_BODIES, &hash);
+			batch.delete(db::COL_EXTRA, &ReceiptsKey::key(&hash));
+			block_bodies.remove(&hash);
+			block_receipts.remove(&hash);
+		}
+
+		batch.put(db::COL_EXTRA, b"pruned", &encode(&end));
+		*pruned_before = end;
+	}
+
 	/// Get best block total difficulty.
 	pub fn best_block_total_difficulty(&self) -> U256 {
 		self.best_block.read().total_difficulty
@@ -1155,6 +1204,16 @@ impl BlockChain {
 		}
 	}
 
+	/// Changes the preferred and maximal size of the cache.
+	pub fn set_cache_limits(&self, pref_cache_size: usize, max_cache_size: usize) {
+		self.cache_man.lock().set_limits(pref_cache_size, max_cache_size);
+	}
+
+	/// Maximal size of the cache.
+	pub fn cache_limit(&self) -> usize {
+		self.cache_man.lock().max_cache_size()
+	}
+
 	/// Ticks our cache system and throws out any old data.
 	pub fn collect_garbage(&self) {
 		let current_size = self.cache_size().total();
@@ -1199,6 +1258,35 @@ impl BlockChain {
 		});
 	}
 
+	/// Drops all cached data (e.g. when the client goes to sleep).
+	pub fn clear_cache(&self) {
+		let mut block_headers = self.block_headers.write();
+		let mut block_bodies = self.block_bodies.write();
+		let mut block_details = self.block_details.write();
+		let mut block_hashes = self.block_hashes.write();
+		let mut transaction_addresses = self.transaction_addresses.write();
+		let mut blocks_blooms = self.blocks_blooms.write();
+		let mut block_receipts = self.block_receipts.write();
+
+		block_headers.clear();
+		block_bodies.clear();
+		block_details.clear();
+		block_hashes.clear();
+		transaction_addresses.clear();
+		blocks_blooms.clear();
+		block_receipts.clear();
+
+		block_headers.shrink_to_fit();
+		block_bodies.shrink_to_fit();
+		block_details.shrink_to_fit();
+		block_hashes.shrink_to_fit();
+		transaction_addresses.shrink_to_fit();
+		blocks_blooms.shrink_to_fit();
+		block_receipts.shrink_to_fit();
+
+		self.cache_man.lock().clear();
+	}
+
 	/// Create a block body from a block.
 	pub fn block_to_body(block: &[u8]) -> Bytes {
 		let mut body = RlpStream::new_list(2);
@@ -1289,6 +1377,38 @@ mod tests {
 		assert_eq!(bc.block_hash(2), None);
 	}
 
+	#[test]
+	fn prunes_ancient_bodies_and_receipts() {
+		let mut canon_chain = ChainGenerator::default();
+		let mut finalizer = BlockFinalizer::default();
+		let genesis = canon_chain.generate(&mut finalizer).unwrap();
+		let blocks: Vec<_> = (0..4).map(|_| canon_chain.generate(&mut finalizer).unwrap()).collect();
+
+		let temp = RandomTempPath::new();
+		{
+			let db = new_db(temp.as_str());
+			let bc = BlockChain::new(Config::default(), &genesis, db.clone());
+			for block in &blocks {
+				insert_block(&db, &bc, block, vec![]);
+			}
+
+			let mut batch = db.transaction();
+			bc.prune_ancient_bodies(&mut batch, 3);
+			db.write(batch).unwrap();
+
+			assert_eq!(bc.pruned_before(), 3);
+			let pruned = bc.block_hash(2).unwrap();
+			assert!(bc.block_header_data(&pruned).is_some());
+			assert!(bc.block_body(&pruned).is_none());
+			assert!(bc.block_receipts(&pruned).is_none());
+			assert!(bc.block_body(&bc.block_hash(3).unwrap()).is_some());
+		}
+
+		let db = new_db(temp.as_str());
+		let bc = BlockChain::new(Config::default(), &genesis, db.clone());
+		assert_eq!(bc.pruned_before(), 3);
+	}
+
 	#[test]
 	fn check_ancestry_iter() {
 		let mut canon_chain = ChainGenerator::default();
@@ -1822,6 +1942,7 @@ mod tests {
 		let block2 = BlockView::new(&b2);
 		let logs1 = bc.logs(vec![1, 2], |_| true, None);
 		let logs2 = bc.logs(vec![1, 2], |_| true, Some(1));
+		let logs3 = bc.logs_by_hash(vec![block2.hash()], |_| true, None);
 
 		// then
 		assert_eq!(logs1, vec![
@@ -1868,6 +1989,7 @@ mod tests {
 				log_index: 0,
 			}
 		]);
+		assert_eq!(logs3, logs2);
 	}
 
 	#[test]
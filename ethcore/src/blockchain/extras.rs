@@ -37,6 +37,8 @@ pub enum ExtrasIndex {
 	BlocksBlooms = 3,
 	/// Block receipts index
 	BlockReceipts = 4,
+	/// Transactions by address index
+	AddressTransactions = 5,
 }
 
 fn with_index(hash: &H256, i: ExtrasIndex) -> H264 {
@@ -46,10 +46,11 @@ impl Pricer for Linear {
 	}
 }
 
-/// Pricing scheme and execution definition for a built-in contract.
+/// Pricing scheme, execution definition and activation block for a built-in contract.
 pub struct Builtin {
 	pricer: Box<Pricer>,
 	native: Box<Impl>,
+	activate_at: u64,
 }
 
 impl Builtin {
@@ -58,6 +59,9 @@ impl Builtin {
 
 	/// Simple forwarder for execute.
 	pub fn execute(&self, input: &[u8], output: &mut BytesRef) { self.native.execute(input, output) }
+
+	/// Whether the builtin is activated at the given block number.
+	pub fn is_active(&self, at: u64) -> bool { at >= self.activate_at }
 }
 
 impl From<ethjson::spec::Builtin> for Builtin {
@@ -74,6 +78,7 @@ impl From<ethjson::spec::Builtin> for Builtin {
 		Builtin {
 			pricer: pricer,
 			native: ethereum_builtin(&b.name),
+			activate_at: b.activate_at.map_or(0, Into::into),
 		}
 	}
 }
@@ -307,6 +312,7 @@ mod tests {
 		let b = Builtin {
 			pricer: pricer as Box<Pricer>,
 			native: ethereum_builtin("identity"),
+			activate_at: 0,
 		};
 
 		assert_eq!(b.cost(0), U256::from(10));
@@ -327,7 +333,8 @@ mod tests {
 			pricing: ethjson::spec::Pricing::Linear(ethjson::spec::Linear {
 				base: 10,
 				word: 20,
-			})
+			}),
+			activate_at: None,
 		});
 
 		assert_eq!(b.cost(0), U256::from(10));
@@ -340,4 +347,21 @@ mod tests {
 		b.execute(&i[..], &mut BytesRef::Fixed(&mut o[..]));
 		assert_eq!(i, o);
 	}
+
+	#[test]
+	fn activation_block() {
+		let b = Builtin::from(ethjson::spec::Builtin {
+			name: "identity".to_owned(),
+			pricing: ethjson::spec::Pricing::Linear(ethjson::spec::Linear {
+				base: 10,
+				word: 20,
+			}),
+			activate_at: Some(ethjson::uint::Uint(U256::from(100))),
+		});
+
+		assert!(!b.is_active(0));
+		assert!(!b.is_active(99));
+		assert!(b.is_active(100));
+		assert!(b.is_active(101));
+	}
 }
@@ -63,6 +63,24 @@ impl<T> CacheManager<T> where T: Eq + Hash {
 		}
 	}
 
+	/// Changes the preferred and maximal size of the cache. Takes effect on next garbage collection.
+	pub fn set_limits(&mut self, pref_cache_size: usize, max_cache_size: usize) {
+		self.pref_cache_size = pref_cache_size;
+		self.max_cache_size = max_cache_size;
+	}
+
+	/// Maximal size of the cache.
+	pub fn max_cache_size(&self) -> usize {
+		self.max_cache_size
+	}
+
+	/// Forgets usage information about all objects.
+	pub fn clear(&mut self) {
+		for usage in self.cache_usage.iter_mut() {
+			usage.clear();
+		}
+	}
+
 	fn rotate_cache_if_needed(&mut self) {
 		if self.cache_usage[0].len() * self.bytes_per_cache_entry > self.pref_cache_size / COLLECTION_QUEUE_SIZE {
 			let cache = self.cache_usage.pop_back().unwrap();
@@ -0,0 +1,128 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Index of transactions by sender and recipient address.
+//!
+//! Entries are kept in the extras column under `[index, address, block number, transaction index, block hash]`
+//! keys, so transactions of an address are iterated in chain order. Transactions of every imported block
+//! are indexed, including non-canonical ones; readers are expected to skip entries of retracted blocks.
+
+use util::{H256, Address, FixedHash, DBTransaction, Database};
+use header::BlockNumber;
+use transaction::{SignedTransaction, Action};
+use executive::contract_address;
+use blockchain::extras::ExtrasIndex;
+use db::COL_EXTRA;
+
+const PREFIX_LEN: usize = 1 + 20;
+const KEY_LEN: usize = PREFIX_LEN + 8 + 4 + 32;
+
+/// Location of an indexed transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct IndexEntry {
+	/// Number of the block containing the transaction.
+	pub block_number: BlockNumber,
+	/// Hash of the block containing the transaction.
+	pub block_hash: H256,
+	/// Transaction index within the block.
+	pub index: usize,
+}
+
+fn first_indexed_key() -> [u8; 1] {
+	[ExtrasIndex::AddressTransactions as u8]
+}
+
+fn address_prefix(address: &Address) -> Vec<u8> {
+	let mut prefix = Vec::with_capacity(KEY_LEN);
+	prefix.push(ExtrasIndex::AddressTransactions as u8);
+	prefix.extend_from_slice(address);
+	prefix
+}
+
+fn entry_key(address: &Address, number: BlockNumber, index: usize, hash: &H256) -> Vec<u8> {
+	let mut key = address_prefix(address);
+	key.extend((0..8).rev().map(|i| (number >> (i * 8)) as u8));
+	key.extend((0..4).rev().map(|i| (index >> (i * 8)) as u8));
+	key.extend_from_slice(hash);
+	key
+}
+
+fn decode_entry(key: &[u8]) -> IndexEntry {
+	let be = |bytes: &[u8]| bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+	IndexEntry {
+		block_number: be(&key[PREFIX_LEN..PREFIX_LEN + 8]),
+		index: be(&key[PREFIX_LEN + 8..PREFIX_LEN + 12]) as usize,
+		block_hash: H256::from_slice(&key[PREFIX_LEN + 12..]),
+	}
+}
+
+/// Number of the lowest block from which on all transactions are indexed.
+/// `None` if the index has never been initialized in this database.
+pub fn first_indexed(db: &Database) -> Option<BlockNumber> {
+	db.get(COL_EXTRA, &first_indexed_key()).expect("low-level database error")
+		.map(|v| ::rlp::decode(&v))
+}
+
+/// Records that all transactions from block `number` on are indexed.
+pub fn set_first_indexed(batch: &mut DBTransaction, number: BlockNumber) {
+	batch.put(COL_EXTRA, &first_indexed_key(), &::rlp::encode(&number));
+}
+
+/// Indexes transactions of given block under their sender, recipient and created contract addresses.
+pub fn insert(batch: &mut DBTransaction, number: BlockNumber, hash: &H256, transactions: &[SignedTransaction]) {
+	for (index, tx) in transactions.iter().enumerate() {
+		let sender = match tx.sender() {
+			Ok(sender) => sender,
+			Err(_) => continue,
+		};
+		let other = match tx.action {
+			Action::Call(ref to) => to.clone(),
+			Action::Create => contract_address(&sender, &tx.nonce),
+		};
+		batch.put(COL_EXTRA, &entry_key(&sender, number, index, hash), &[]);
+		if other != sender {
+			batch.put(COL_EXTRA, &entry_key(&other, number, index, hash), &[]);
+		}
+	}
+}
+
+/// Iterates over indexed transactions of `address` in chain order. Only sees flushed data.
+pub fn iter(db: &Database, address: &Address) -> Box<Iterator<Item = IndexEntry>> {
+	let prefix = address_prefix(address);
+	match db.iter_from_prefix(COL_EXTRA, &prefix) {
+		Some(iter) => Box::new(iter
+			.take_while(move |&(ref key, _)| key.starts_with(&prefix))
+			.filter(|&(ref key, _)| key.len() == KEY_LEN)
+			.map(|(key, _)| decode_entry(&key))),
+		None => Box::new(::std::iter::empty()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::{H256, Address};
+	use super::{entry_key, decode_entry, IndexEntry};
+
+	#[test]
+	fn should_order_keys_by_block_number_and_index() {
+		let address = Address::from(1);
+		let a = entry_key(&address, 255, 7, &H256::from(9));
+		let b = entry_key(&address, 256, 0, &H256::from(1));
+		let c = entry_key(&address, 256, 1, &H256::from(0));
+		assert!(a < b && b < c);
+		assert_eq!(decode_entry(&b), IndexEntry { block_number: 256, block_hash: H256::from(1), index: 0 });
+	}
+}
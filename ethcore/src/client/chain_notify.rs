@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use ipc::IpcConfig;
-use util::H256;
+use util::{H256, U256};
 
 /// Represents what has to be handled by actor listening to chain events
 #[ipc]
@@ -31,6 +31,11 @@ pub trait ChainNotify : Send + Sync {
 		// does nothing by default
 	}
 
+	/// fires when a locally sealed block is about to be committed. It's not in the chain yet.
+	fn block_sealed(&self, _block: Vec<u8>, _total_difficulty: U256) {
+		// does nothing by default
+	}
+
 	/// fires when chain achieves active mode
 	fn start(&self) {
 		// does nothing by default
@@ -14,27 +14,30 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 use std::collections::{HashSet, HashMap, BTreeMap, VecDeque};
+use std::cmp::{min, max};
 use std::sync::{Arc, Weak};
 use std::path::{Path};
+use std::fs;
 use std::fmt;
+use std::thread;
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
 use std::time::{Instant};
-use time::precise_time_ns;
+use time::{precise_time_ns, get_time};
 
 // util
 use util::{Bytes, PerfTimer, Itertools, Mutex, RwLock};
 use util::{journaldb, TrieFactory, Trie};
 use util::trie::TrieSpec;
-use util::{U256, H256, Address, H2048, Uint, FixedHash};
+use util::{U256, H256, Address, H2048, Uint, FixedHash, Hashable};
 use util::kvdb::*;
 
 // other
 use io::*;
-use views::{HeaderView, BodyView};
+use views::{HeaderView, BodyView, BlockView};
 use error::{ImportError, ExecutionError, CallError, BlockError, ImportResult, Error as EthcoreError};
-use header::BlockNumber;
+use header::{BlockNumber, Header};
 use state::State;
-use spec::Spec;
+use spec::{self, Spec};
 use basic_types::Seal;
 use engines::Engine;
 use service::ClientIoMessage;
@@ -50,21 +53,28 @@ use verification::queue::{BlockQueue, QueueInfo as BlockQueueInfo};
 use blockchain::{BlockChain, BlockProvider, TreeRoute, ImportRoute};
 use client::{
 	BlockID, TransactionID, UncleID, TraceId, ClientConfig, BlockChainClient,
-	MiningBlockChainClient, TraceFilter, CallAnalytics, BlockImportError, Mode,
-	ChainNotify,
+	MiningBlockChainClient, TraceFilter, CallAnalytics, BlockImportError, Mode, IpcMode,
+	ChainNotify, IntegrityIssue, IntegrityReport, RepairError, ChainWarning,
 };
+use client::integrity;
+use client::address_index;
 use client::Error as ClientError;
 use env_info::EnvInfo;
 use executive::{Executive, Executed, TransactOptions, contract_address};
 use receipt::LocalizedReceipt;
-use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Database as TraceDatabase};
+use pod_account::{self, PodAccount};
+use pod_state::PodState;
+use types::account_diff::AccountDiff;
+use types::block_rewards::BlockRewards;
+use types::memory_usage::{CacheUsage, ClientMemoryUsage};
+use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Database as TraceDatabase, TransferTracer, NoopVMTracer};
 use trace;
 use trace::FlatTransactionTraces;
 use evm::Factory as EvmFactory;
 use miner::{Miner, MinerService};
-use snapshot::{self, io as snapshot_io};
+use snapshot::{self, io as snapshot_io, DatabaseRestore};
 use factory::Factories;
-use rlp::{View, UntrustedRlp};
+use rlp::{decode, View, UntrustedRlp};
 use state_db::StateDB;
 
 // re-export
@@ -74,6 +84,11 @@ pub use blockchain::CacheSize as BlockChainCacheSize;
 
 const MAX_TX_QUEUE_SIZE: usize = 4096;
 const MAX_QUEUE_SIZE_TO_SLEEP_ON: usize = 2;
+/// Number of most recent blocks checked for missing data on startup.
+const STARTUP_INTEGRITY_DEPTH: u64 = 64;
+const DEV_CHAIN_ONLY: &'static str = "Only available on development (instant seal) chains";
+/// Maximal number of blocks sealed by a single `dev_mine_blocks` call.
+const MAX_DEV_BLOCKS: u64 = 1024;
 
 impl fmt::Display for BlockChainInfo {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -92,6 +107,10 @@ pub struct ClientReport {
 	pub gas_processed: U256,
 	/// Memory used by state DB
 	pub state_db_mem: usize,
+	/// Number of state trie node lookups served from the cache
+	pub state_cache_hits: usize,
+	/// Number of state trie node lookups which missed the cache
+	pub state_cache_misses: usize,
 }
 
 impl ClientReport {
@@ -120,7 +139,8 @@ impl SleepState {
 /// Blockchain database client backed by a persistent database. Owns and manages a blockchain and a block queue.
 /// Call `import_block()` to import a block asynchronously; `flush_queue()` flushes the queue.
 pub struct Client {
-	mode: Mode,
+	mode: Mutex<Mode>,
+	on_mode_change: Mutex<Option<Box<FnMut(&Mode) + 'static + Send>>>,
 	chain: RwLock<Arc<BlockChain>>,
 	tracedb: RwLock<TraceDB<BlockChain>>,
 	engine: Arc<Engine>,
@@ -141,12 +161,39 @@ pub struct Client {
 	queue_transactions: AtomicUsize,
 	last_hashes: RwLock<VecDeque<H256>>,
 	factories: Factories,
+	chain_warnings: RwLock<VecDeque<ChainWarning>>,
+	quarantine: Mutex<Quarantine>,
+	dev_genesis: Option<(Bytes, PodState)>,
+	backup_in_progress: Arc<AtomicBool>,
+	/// Whether the state of the best block was found missing on startup and is yet to be repaired.
+	repair_pending: AtomicBool,
 }
 
 /// The pruning constant -- how old blocks must be before we
 /// assume finality of a given candidate.
 pub const HISTORY: u64 = 1200;
 
+/// Maximal number of chain warnings kept.
+const MAX_CHAIN_WARNINGS: usize = 64;
+
+/// Maximal number of blocks held back by the reorganization depth limit.
+const MAX_QUARANTINED_BLOCKS: usize = 1024;
+
+/// Blocks held back by the reorganization depth limit until released by the operator.
+#[derive(Default)]
+struct Quarantine {
+	/// Raw quarantined blocks and their descendants, with parent hashes.
+	blocks: HashMap<H256, (H256, Bytes)>,
+	/// Released blocks, imported regardless of the limit.
+	released: HashSet<H256>,
+}
+
+/// Number of old blocks added to the address index on each tick.
+const ADDRESS_INDEX_BACKFILL_BLOCKS: u64 = 1000;
+
+/// Maximal number of blocks replayed to find storage changed between two blocks.
+pub const MAX_REPLAYED_BLOCKS: u64 = 256;
+
 /// Append a path element to the given path and return the string.
 pub fn append_path<P>(path: P, item: &str) -> String where P: AsRef<Path> {
 	let mut p = path.as_ref().to_path_buf();
@@ -170,6 +217,9 @@ impl Client {
 		let db = Arc::new(try!(Database::open(&db_config, &path.to_str().unwrap()).map_err(ClientError::Database)));
 		let chain = Arc::new(BlockChain::new(config.blockchain.clone(), &gb, db.clone()));
 		let tracedb = RwLock::new(TraceDB::new(config.tracing.clone(), db.clone(), chain.clone()));
+		if config.address_index {
+			try!(Self::init_address_index(&db, &chain).map_err(ClientError::Database));
+		}
 
 		let trie_spec = match config.fat_db {
 			true => TrieSpec::Fat,
@@ -177,24 +227,26 @@ impl Client {
 		};
 
 		let journal_db = journaldb::new(db.clone(), config.pruning, ::db::COL_STATE);
-		let mut state_db = StateDB::new(journal_db);
+		let mut state_db = StateDB::new(journal_db, config.state_cache_size);
 		if state_db.journal_db().is_empty() && try!(spec.ensure_db_good(&mut state_db)) {
 			let mut batch = DBTransaction::new(&db);
 			try!(state_db.commit(&mut batch, 0, &spec.genesis_header().hash(), None));
 			try!(db.write(batch).map_err(ClientError::Database));
 		}
 
-		if !chain.block_header(&chain.best_block_hash()).map_or(true, |h| state_db.journal_db().contains(h.state_root())) {
-			warn!("State root not found for block #{} ({})", chain.best_block_number(), chain.best_block_hash().hex());
-		}
-
 		let engine = spec.engine.clone();
 
+		// development chains keep their genesis around so that they can be reset.
+		let dev_genesis = match engine.is_default_sealer() {
+			Some(true) => Some((gb.clone(), spec.genesis_state().clone())),
+			_ => None,
+		};
+
 		let block_queue = BlockQueue::new(config.queue.clone(), engine.clone(), message_channel.clone());
 		let panic_handler = PanicHandler::new_in_arc();
 		panic_handler.forward_from(&block_queue);
 
-		let awake = match config.mode { Mode::Dark(..) => false, _ => true };
+		let awake = match config.mode { Mode::Dark(..) | Mode::Off => false, _ => true };
 
 		let factories = Factories {
 			vm: EvmFactory::new(config.vm_type.clone()),
@@ -205,7 +257,8 @@ impl Client {
 		let client = Client {
 			sleep_state: Mutex::new(SleepState::new(awake)),
 			liveness: AtomicBool::new(awake),
-			mode: config.mode.clone(),
+			mode: Mutex::new(config.mode.clone()),
+			on_mode_change: Mutex::new(None),
 			chain: RwLock::new(chain),
 			tracedb: tracedb,
 			engine: engine,
@@ -224,10 +277,204 @@ impl Client {
 			queue_transactions: AtomicUsize::new(0),
 			last_hashes: RwLock::new(VecDeque::new()),
 			factories: factories,
+			chain_warnings: RwLock::new(VecDeque::new()),
+			quarantine: Mutex::new(Quarantine::default()),
+			dev_genesis: dev_genesis,
+			backup_in_progress: Arc::new(AtomicBool::new(false)),
+			repair_pending: AtomicBool::new(false),
 		};
+
+		let report = client.check_integrity(STARTUP_INTEGRITY_DEPTH, false);
+		for issue in &report.issues {
+			warn!("Database integrity check: {}", issue);
+		}
+		// re-executing blocks may take a while, the state is repaired on the first tick.
+		if report.is_state_missing() {
+			info!("State of block #{} is missing and will be restored in the background", report.best_block);
+			client.repair_pending.store(true, AtomicOrdering::Relaxed);
+		}
+
 		Ok(Arc::new(client))
 	}
 
+	/// Marks blocks imported from now on as indexed, unless the address index already exists.
+	/// Older blocks are indexed in the background by `tick`.
+	fn init_address_index(db: &Database, chain: &BlockChain) -> Result<(), String> {
+		if address_index::first_indexed(db).is_some() {
+			return Ok(());
+		}
+		let mut batch = DBTransaction::new(db);
+		address_index::set_first_indexed(&mut batch, chain.best_block_number() + 1);
+		db.write(batch)
+	}
+
+	/// Adds up to `ADDRESS_INDEX_BACKFILL_BLOCKS` blocks preceding the lowest indexed one to the address index.
+	/// Stops at the first block whose body is missing, e.g. not yet downloaded after a snapshot restoration.
+	fn backfill_address_index(&self) {
+		let db = self.db.read();
+		let first = match address_index::first_indexed(&db) {
+			Some(first) if first > 1 => first,
+			_ => return,
+		};
+
+		let chain = self.chain.read();
+		let mut batch = DBTransaction::new(&db);
+		let mut next = first;
+		while next > 1 && first - next < ADDRESS_INDEX_BACKFILL_BLOCKS {
+			let number = next - 1;
+			let (hash, body) = match chain.block_hash(number).and_then(|h| chain.block_body(&h).map(|b| (h, b))) {
+				Some(block) => block,
+				None => break,
+			};
+			address_index::insert(&mut batch, number, &hash, &BodyView::new(&body).transactions());
+			next = number;
+		}
+
+		if next != first {
+			trace!(target: "client", "Address index backfilled down to block #{}", next);
+			address_index::set_first_indexed(&mut batch, next);
+			db.write_buffered(batch);
+		}
+	}
+
+	/// Registers a callback invoked whenever the operating mode is changed with `set_mode`.
+	pub fn on_mode_change<F>(&self, f: F) where F: 'static + FnMut(&Mode) + Send {
+		*self.on_mode_change.lock() = Some(Box::new(f));
+	}
+
+	/// Prepares the client for shutdown: waits for the block import in progress to finish,
+	/// drops blocks which are still queued and flushes buffered database writes.
+	pub fn prepare_shutdown(&self) {
+		let _import_lock = self.import_lock.lock();
+		self.block_queue.clear();
+		if let Err(e) = self.db.read().flush() {
+			warn!("Failed to flush database on shutdown: {}", e);
+		}
+	}
+
+	/// Checks that chain data of `depth` most recent blocks and the best block state root are present.
+	/// If `walk_state` is set the whole best block state is traversed to find missing trie nodes and code.
+	pub fn check_integrity(&self, depth: u64, walk_state: bool) -> IntegrityReport {
+		let chain = self.chain.read().clone();
+		let best_hash = chain.best_block_hash();
+		let mut report = IntegrityReport {
+			best_block: chain.best_block_number(),
+			..Default::default()
+		};
+
+		// blocks preceding the snapshot a warp-synced node was restored from aren't stored,
+		// while bodies and receipts of ancient blocks may have been pruned.
+		let first_block = chain.first_block_number();
+		let pruned_before = chain.pruned_before();
+
+		let mut hash = best_hash.clone();
+		let mut number = report.best_block;
+		while report.blocks_checked < depth && number >= first_block {
+			report.blocks_checked += 1;
+			let header = match chain.block_header(&hash) {
+				Some(header) => header,
+				None => {
+					report.issues.push(IntegrityIssue::MissingBlockData { number: number, hash: hash, what: "header" });
+					break;
+				}
+			};
+
+			{
+				let mut missing = |what: &'static str| report.issues.push(IntegrityIssue::MissingBlockData { number: number, hash: hash.clone(), what: what });
+				if chain.block_details(&hash).is_none() {
+					missing("details");
+				}
+				if number >= pruned_before {
+					if chain.block_body(&hash).is_none() {
+						missing("body");
+					}
+					if number > 0 && chain.block_receipts(&hash).is_none() {
+						missing("receipts");
+					}
+				}
+			}
+
+			if number == 0 {
+				break;
+			}
+			hash = header.parent_hash().clone();
+			number -= 1;
+		}
+
+		if let Some(header) = chain.block_header(&best_hash) {
+			let state_db = self.state_db.lock().boxed_clone();
+			if !state_db.journal_db().contains(header.state_root()) {
+				report.issues.push(IntegrityIssue::MissingStateRoot {
+					number: report.best_block,
+					hash: best_hash,
+					root: header.state_root().clone(),
+				});
+			} else if walk_state {
+				report.accounts_checked = integrity::check_state(state_db.as_hashdb(), header.state_root(), &self.factories.accountdb, &mut report.issues);
+			}
+		}
+
+		report
+	}
+
+	/// Restores missing state of the best block by re-executing recent blocks on top of
+	/// the most recent ancestor with intact state. Returns number of re-executed blocks.
+	pub fn repair_state(&self) -> Result<u64, RepairError> {
+		let _import_lock = self.import_lock.lock();
+		let chain = self.chain.read().clone();
+		let best_hash = chain.best_block_hash();
+		let best_number = chain.best_block_number();
+
+		// collect blocks with missing state, newest first
+		let mut route = Vec::new();
+		let mut hash = best_hash.clone();
+		let ancestor = loop {
+			let header = match chain.block_header(&hash) {
+				Some(header) => header,
+				None => return Err(RepairError::MissingBlock(best_number - route.len() as u64)),
+			};
+			if self.state_db.lock().journal_db().contains(header.state_root()) {
+				break header;
+			}
+			if header.number() == 0 || route.len() as u64 >= HISTORY {
+				return Err(RepairError::NoIntactAncestor);
+			}
+			hash = header.parent_hash().clone();
+			route.push(header);
+		};
+
+		if route.is_empty() {
+			return Ok(0);
+		}
+
+		let count = route.len() as u64;
+		let mut db = self.state_db.lock().boxed_clone();
+		let mut parent = ancestor;
+		for header in route.into_iter().rev() {
+			let number = header.number();
+			let bytes = try!(chain.block(&header.hash()).ok_or(RepairError::MissingBlock(number)));
+			let view = BlockView::new(&bytes);
+			let last_hashes = self.build_last_hashes(parent.hash());
+			let locked = try!(enact(&header, &view.transactions(), &view.uncles(), &*self.engine, false, db, &parent, last_hashes, self.factories.clone())
+				.map_err(|e| RepairError::Execution(number, e)));
+			if locked.header().state_root() != header.state_root() {
+				return Err(RepairError::StateRootMismatch {
+					number: number,
+					expected: header.state_root().clone(),
+					got: locked.header().state_root().clone(),
+				});
+			}
+			db = locked.drain();
+			parent = header;
+		}
+
+		let mut batch = DBTransaction::new(&self.db.read());
+		try!(db.commit(&mut batch, best_number, &best_hash, None).map_err(|e| RepairError::Execution(best_number, e.into())));
+		try!(self.db.read().write(batch).map_err(RepairError::Database));
+		self.state_db.lock().clear_cache();
+		Ok(count)
+	}
+
 	/// Adds an actor to be notified on certain events
 	pub fn add_notify(&self, target: Arc<ChainNotify>) {
 		self.notify.write().push(Arc::downgrade(&target));
@@ -354,6 +601,7 @@ impl Client {
 		let (imported_blocks, import_results, invalid_blocks, imported, duration) = {
 			let mut imported_blocks = Vec::with_capacity(max_blocks_to_import);
 			let mut invalid_blocks = HashSet::new();
+			let mut held_blocks = Vec::new();
 			let mut import_results = Vec::with_capacity(max_blocks_to_import);
 
 			let _import_lock = self.import_lock.lock();
@@ -367,11 +615,21 @@ impl Client {
 					invalid_blocks.insert(header.hash());
 					continue;
 				}
+				if self.quarantine.lock().blocks.contains_key(header.parent_hash()) {
+					self.hold_block(header, &block.bytes);
+					held_blocks.push(header.hash());
+					continue;
+				}
 				let closed_block = self.check_and_close_block(&block);
 				if let Err(_) = closed_block {
 					invalid_blocks.insert(header.hash());
 					continue;
 				}
+				if self.exceeds_reorg_limit(header) {
+					self.hold_block(header, &block.bytes);
+					held_blocks.push(header.hash());
+					continue;
+				}
 
 				let closed_block = closed_block.unwrap();
 				imported_blocks.push(header.hash());
@@ -392,6 +650,7 @@ impl Client {
 				if !imported_blocks.is_empty() {
 					self.block_queue.mark_as_good(&imported_blocks);
 				}
+				self.block_queue.mark_as_good(&held_blocks);
 			}
 			let duration_ns = precise_time_ns() - start;
 			(imported_blocks, import_results, invalid_blocks, imported, duration_ns)
@@ -422,6 +681,57 @@ impl Client {
 		imported
 	}
 
+	/// Checks if the block would become the new best block by reverting more canonical blocks
+	/// than allowed. Such blocks are quarantined and reported as chain warnings.
+	fn exceeds_reorg_limit(&self, header: &Header) -> bool {
+		let max_depth = match self.config.max_reorg_depth {
+			Some(depth) => depth,
+			None => return false,
+		};
+		if self.quarantine.lock().released.remove(&header.hash()) {
+			return false;
+		}
+
+		let depth = {
+			let chain = self.chain.read();
+			let parent_details = match chain.block_details(header.parent_hash()) {
+				Some(details) => details,
+				None => return false,
+			};
+			if parent_details.total_difficulty + *header.difficulty() <= chain.best_block_total_difficulty() {
+				return false;
+			}
+			chain.tree_route(chain.best_block_hash(), header.parent_hash().clone()).index as u64
+		};
+
+		if depth <= max_depth {
+			return false;
+		}
+
+		warn!(target: "client", "Block #{} ({}) would revert {} blocks, more than the limit of {}. Block quarantined.", header.number(), header.hash(), depth, max_depth);
+		let mut warnings = self.chain_warnings.write();
+		if warnings.len() >= MAX_CHAIN_WARNINGS {
+			warnings.pop_front();
+		}
+		warnings.push_back(ChainWarning {
+			block_hash: header.hash(),
+			block_number: header.number(),
+			reorg_depth: depth,
+			timestamp: get_time().sec as u64,
+		});
+		true
+	}
+
+	/// Keeps the block in quarantine until it's released.
+	fn hold_block(&self, header: &Header, bytes: &[u8]) {
+		let mut quarantine = self.quarantine.lock();
+		if quarantine.blocks.len() >= MAX_QUARANTINED_BLOCKS {
+			warn!(target: "client", "Too many quarantined blocks. Dropping block #{} ({}).", header.number(), header.hash());
+			return;
+		}
+		quarantine.blocks.insert(header.hash(), (header.parent_hash().clone(), bytes.to_vec()));
+	}
+
 	fn commit_block<B>(&self, block: B, hash: &H256, block_data: &[u8]) -> ImportRoute where B: IsBlock + Drain {
 		let number = block.header().number();
 		let parent = block.header().parent_hash().clone();
@@ -444,6 +754,9 @@ impl Client {
 		//let traces = From::from(block.traces().clone().unwrap_or_else(Vec::new));
 
 		let mut batch = DBTransaction::new(&self.db.read());
+		if self.config.address_index {
+			address_index::insert(&mut batch, number, hash, block.transactions());
+		}
 		// CHECK! I *think* this is fine, even if the state_root is equal to another
 		// already-imported block of the same number.
 		// TODO: Prove it with a test.
@@ -459,6 +772,9 @@ impl Client {
 			retracted: route.retracted.len()
 		});
 		let is_canon = route.enacted.last().map_or(false, |h| h == hash);
+		if let (true, Some(keep)) = (is_canon, self.config.prune_ancient_bodies) {
+			chain.prune_ancient_bodies(&mut batch, number.saturating_sub(keep));
+		}
 		state.sync_cache(&route.enacted, &route.retracted, is_canon);
 		// Final commit to the DB
 		self.db.read().write_buffered(batch);
@@ -544,15 +860,57 @@ impl Client {
 		.expect("State root of best block header always valid.")
 	}
 
+	/// Executes the transaction on a copy of the latest state, in which the contract it calls
+	/// has given code and storage instead of its own. Returns the execution result together
+	/// with the storage the contract is left with. Used to run private contracts, whose code
+	/// and storage are only known to the parties allowed to decrypt them.
+	pub fn call_private(&self, t: &SignedTransaction, code: Bytes, storage: BTreeMap<H256, H256>) -> Result<(Executed, BTreeMap<H256, H256>), CallError> {
+		let contract = match t.action {
+			Action::Call(ref address) => address.clone(),
+			Action::Create => return Err(ExecutionError::TransactionMalformed("Private transaction must call a contract".into()).into()),
+		};
+		let env_info = try!(self.call_env_info(BlockID::Latest));
+		let mut state = self.state();
+
+		// a fresh account, so that storage of the public contract doesn't leak into execution.
+		let balance = state.balance(&contract);
+		state.new_contract(&contract, balance);
+		state.init_code(&contract, code);
+		for (key, value) in storage {
+			state.set_storage(&contract, key, value);
+		}
+
+		let executed = try!(self.call_on_state(&mut state, &env_info, t, Default::default()));
+		// the account is fresh, so all of its storage is held in the cache.
+		let storage = state.to_pod().get().get(&contract)
+			.map(|account| account.storage.iter()
+				.filter(|&(_, value)| !value.is_zero())
+				.map(|(key, value)| (key.clone(), value.clone()))
+				.collect())
+			.unwrap_or_else(BTreeMap::new);
+		Ok((executed, storage))
+	}
+
 	/// Get info on the cache.
 	pub fn blockchain_cache_info(&self) -> BlockChainCacheSize {
 		self.chain.read().cache_size()
 	}
 
+	/// Changes memory limits of the blockchain cache, state cache and block queue (in bytes).
+	pub fn set_cache_limits(&self, blockchain: usize, state: usize, block_queue: usize) {
+		self.chain.read().set_cache_limits(blockchain * 3 / 4, blockchain);
+		self.state_db.lock().set_node_cache_limit(state);
+		self.block_queue.set_max_mem_use(block_queue);
+	}
+
 	/// Get the report.
 	pub fn report(&self) -> ClientReport {
 		let mut report = self.report.read().clone();
-		report.state_db_mem = self.state_db.lock().mem_used();
+		let state_db = self.state_db.lock();
+		let node_cache_stats = state_db.node_cache_stats();
+		report.state_db_mem = state_db.mem_used();
+		report.state_cache_hits = node_cache_stats.hits;
+		report.state_cache_misses = node_cache_stats.misses;
 		report
 	}
 
@@ -562,8 +920,21 @@ impl Client {
 		self.chain.read().collect_garbage();
 		self.block_queue.collect_garbage();
 		self.tracedb.read().collect_garbage();
+		self.miner.expire_work_waiters();
+		if self.config.address_index {
+			self.backfill_address_index();
+		}
+		if self.repair_pending.swap(false, AtomicOrdering::Relaxed) {
+			let best_block = self.chain.read().best_block_number();
+			match self.repair_state() {
+				Ok(count) => info!("Restored state of block #{} by re-executing {} blocks", best_block, count),
+				Err(e) => warn!("Unable to restore state of block #{}: {}. Run `parity db check` for details.", best_block, e),
+			}
+		}
 
-		match self.mode {
+		let mode = self.mode.lock().clone();
+		match mode {
+			Mode::Off => self.sleep(),
 			Mode::Dark(timeout) => {
 				let mut ss = self.sleep_state.lock();
 				if let Some(t) = ss.last_activity {
@@ -595,6 +966,30 @@ impl Client {
 		}
 	}
 
+	/// Replays all transactions of the block and returns storage keys of `address` they accessed.
+	fn touched_storage(&self, id: BlockID, address: &Address) -> Result<Vec<H256>, CallError> {
+		let header_data = try!(self.block_header(id.clone()).ok_or(CallError::StatePruned));
+		let body_data = try!(self.block_body(id.clone()).ok_or(CallError::StatePruned));
+		let mut state = try!(self.state_at_beginning(id).ok_or(CallError::StatePruned));
+
+		let view = HeaderView::new(&header_data);
+		let mut env_info = EnvInfo {
+			number: view.number(),
+			author: view.author(),
+			timestamp: view.timestamp(),
+			difficulty: view.difficulty(),
+			last_hashes: self.build_last_hashes(view.hash()),
+			gas_used: U256::default(),
+			gas_limit: view.gas_limit(),
+		};
+		for t in &BodyView::new(&body_data).transactions() {
+			let executed = try!(Executive::new(&mut state, &env_info, &*self.engine, &self.factories.vm).transact(t, Default::default()));
+			env_info.gas_used = env_info.gas_used + executed.gas_used;
+		}
+
+		Ok(state.to_pod().get().get(address).map_or_else(Vec::new, |account| account.storage.keys().cloned().collect()))
+	}
+
 	/// Look up the block number for the given block ID.
 	pub fn block_number(&self, id: BlockID) -> Option<BlockNumber> {
 		match id {
@@ -647,6 +1042,52 @@ impl Client {
 		}
 	}
 
+	fn call_env_info(&self, block: BlockID) -> Result<EnvInfo, CallError> {
+		let header = try!(self.block_header(block).ok_or(CallError::StatePruned));
+		let view = HeaderView::new(&header);
+		Ok(EnvInfo {
+			number: view.number(),
+			author: view.author(),
+			timestamp: view.timestamp(),
+			difficulty: view.difficulty(),
+			last_hashes: self.build_last_hashes(view.hash()),
+			gas_used: U256::zero(),
+			gas_limit: U256::max_value(),
+		})
+	}
+
+	fn call_on_state(&self, state: &mut State, env_info: &EnvInfo, t: &SignedTransaction, analytics: CallAnalytics) -> Result<Executed, CallError> {
+		let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
+
+		let sender = try!(t.sender().map_err(|e| {
+			let message = format!("Transaction malformed: {:?}", e);
+			ExecutionError::TransactionMalformed(message)
+		}));
+		let balance = state.balance(&sender);
+		let needed_balance = t.value + t.gas * t.gas_price;
+		if balance < needed_balance {
+			// give the sender a sufficient balance
+			state.add_balance(&sender, &(needed_balance - balance));
+		}
+		let mut ret = try!(self.transact_with_analytics(state, env_info, t, analytics));
+
+		// TODO gav move this into Executive.
+		ret.state_diff = original_state.map(|original| state.diff_from(original));
+
+		Ok(ret)
+	}
+
+	// executes the transaction without checking its nonce, using the tracers requested by `analytics`.
+	fn transact_with_analytics(&self, state: &mut State, env_info: &EnvInfo, t: &SignedTransaction, analytics: CallAnalytics) -> Result<Executed, ExecutionError> {
+		if analytics.transfer_tracing {
+			return Executive::new(state, env_info, &*self.engine, &self.factories.vm)
+				.transact_with_tracer(t, false, TransferTracer::default(), NoopVMTracer);
+		}
+
+		let options = TransactOptions { tracing: analytics.transaction_tracing, vm_tracing: analytics.vm_tracing, check_nonce: false };
+		Executive::new(state, env_info, &*self.engine, &self.factories.vm).transact(t, options)
+	}
+
 	fn transaction_address(&self, id: TransactionID) -> Option<TransactionAddress> {
 		match id {
 			TransactionID::Hash(ref hash) => self.chain.read().transaction_address(hash),
@@ -671,6 +1112,9 @@ impl Client {
 			if self.queue_info().total_queue_size() <= MAX_QUEUE_SIZE_TO_SLEEP_ON {
 				self.liveness.store(false, AtomicOrdering::Relaxed);
 				self.notify(|n| n.stop());
+				// release caches; they will be repopulated on demand after waking up.
+				self.chain.read().clear_cache();
+				self.state_db.lock().clear_cache();
 				trace!(target: "mode", "sleep: Sleeping.");
 			} else {
 				trace!(target: "mode", "sleep: Cannot sleep - syncing ongoing.");
@@ -679,6 +1123,19 @@ impl Client {
 			}
 		}
 	}
+
+	/// Seal and import an empty block on top of the best block, altered by `f` beforehand.
+	fn seal_dev_block<F>(&self, f: F) -> Result<H256, String> where F: FnOnce(&mut OpenBlock) -> Result<(), EthcoreError> {
+		if self.dev_genesis.is_none() {
+			return Err(DEV_CHAIN_ONLY.into());
+		}
+
+		let gas_range_target = (self.miner.gas_floor_target(), self.miner.gas_ceil_target());
+		let mut block = self.prepare_open_block(self.miner.author(), gas_range_target, self.miner.extra_data());
+		try!(f(&mut block).map_err(|e| format!("{}", e)));
+		let sealed = try!(block.close_and_lock().seal(&*self.engine, Vec::new()).map_err(|e| format!("{}", e)));
+		self.import_sealed_block(sealed).map_err(|e| format!("{}", e))
+	}
 }
 
 impl snapshot::DatabaseRestore for Client {
@@ -694,9 +1151,12 @@ impl snapshot::DatabaseRestore for Client {
 		let db = self.db.write();
 		try!(db.restore(new_db));
 
-		*state_db = StateDB::new(journaldb::new(db.clone(), self.pruning, ::db::COL_STATE));
+		*state_db = StateDB::new(journaldb::new(db.clone(), self.pruning, ::db::COL_STATE), self.config.state_cache_size);
 		*chain = Arc::new(BlockChain::new(self.config.blockchain.clone(), &[], db.clone()));
 		*tracedb = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
+		if self.config.address_index {
+			try!(Self::init_address_index(&db, &chain).map_err(ClientError::Database));
+		}
 		Ok(())
 	}
 }
@@ -704,39 +1164,19 @@ impl snapshot::DatabaseRestore for Client {
 
 impl BlockChainClient for Client {
 	fn call(&self, t: &SignedTransaction, block: BlockID, analytics: CallAnalytics) -> Result<Executed, CallError> {
-		let header = try!(self.block_header(block).ok_or(CallError::StatePruned));
-		let view = HeaderView::new(&header);
-		let last_hashes = self.build_last_hashes(view.hash());
-		let env_info = EnvInfo {
-			number: view.number(),
-			author: view.author(),
-			timestamp: view.timestamp(),
-			difficulty: view.difficulty(),
-			last_hashes: last_hashes,
-			gas_used: U256::zero(),
-			gas_limit: U256::max_value(),
-		};
+		let env_info = try!(self.call_env_info(block.clone()));
 		// that's just a copy of the state.
 		let mut state = try!(self.state_at(block).ok_or(CallError::StatePruned));
-		let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
-
-		let sender = try!(t.sender().map_err(|e| {
-			let message = format!("Transaction malformed: {:?}", e);
-			ExecutionError::TransactionMalformed(message)
-		}));
-		let balance = state.balance(&sender);
-		let needed_balance = t.value + t.gas * t.gas_price;
-		if balance < needed_balance {
-			// give the sender a sufficient balance
-			state.add_balance(&sender, &(needed_balance - balance));
-		}
-		let options = TransactOptions { tracing: analytics.transaction_tracing, vm_tracing: analytics.vm_tracing, check_nonce: false };
-		let mut ret = try!(Executive::new(&mut state, &env_info, &*self.engine, &self.factories.vm).transact(t, options));
-
-		// TODO gav move this into Executive.
-		ret.state_diff = original_state.map(|original| state.diff_from(original));
+		self.call_on_state(&mut state, &env_info, t, analytics)
+	}
 
-		Ok(ret)
+	fn call_many(&self, transactions: &[SignedTransaction], block: BlockID, analytics: CallAnalytics) -> Result<Vec<Executed>, CallError> {
+		let env_info = try!(self.call_env_info(block.clone()));
+		// a single copy of the state shared by all calls.
+		let mut state = try!(self.state_at(block).ok_or(CallError::StatePruned));
+		transactions.iter()
+			.map(|t| self.call_on_state(&mut state, &env_info, t, analytics))
+			.collect()
 	}
 
 	fn replay(&self, id: TransactionID, analytics: CallAnalytics) -> Result<Executed, CallError> {
@@ -750,7 +1190,6 @@ impl BlockChainClient for Client {
 			return Err(CallError::TransactionNotFound);
 		}
 
-		let options = TransactOptions { tracing: analytics.transaction_tracing, vm_tracing: analytics.vm_tracing, check_nonce: false };
 		let view = HeaderView::new(&header_data);
 		let last_hashes = self.build_last_hashes(view.hash());
 		let mut env_info = EnvInfo {
@@ -771,19 +1210,195 @@ impl BlockChainClient for Client {
 		let t = &txs[address.index];
 
 		let original_state = if analytics.state_diffing { Some(state.clone()) } else { None };
-		let mut ret = try!(Executive::new(&mut state, &env_info, &*self.engine, &self.factories.vm).transact(t, options));
+		let mut ret = try!(self.transact_with_analytics(&mut state, &env_info, t, analytics));
 		ret.state_diff = original_state.map(|original| state.diff_from(original));
 
 		Ok(ret)
 	}
 
+	fn account_diff(&self, address: &Address, from: BlockID, to: BlockID) -> Result<Option<AccountDiff>, CallError> {
+		let from_number = try!(self.block_number(from.clone()).ok_or(CallError::StatePruned));
+		let to_number = try!(self.block_number(to.clone()).ok_or(CallError::StatePruned));
+		let pre_state = try!(self.state_at(from.clone()).ok_or(CallError::StatePruned));
+		let post_state = try!(self.state_at(to.clone()).ok_or(CallError::StatePruned));
+
+		let keys: HashSet<H256> = if self.factories.trie.is_fat() {
+			self.list_storage(from, address).unwrap_or_else(Vec::new).into_iter()
+				.chain(self.list_storage(to, address).unwrap_or_else(Vec::new))
+				.collect()
+		} else {
+			// without key preimages only storage touched by the intervening blocks can be found
+			let (first, last) = (min(from_number, to_number) + 1, max(from_number, to_number));
+			if last + 1 - first > MAX_REPLAYED_BLOCKS {
+				return Err(CallError::RangeTooLarge(MAX_REPLAYED_BLOCKS));
+			}
+			let mut keys = HashSet::new();
+			for number in first..(last + 1) {
+				keys.extend(try!(self.touched_storage(BlockID::Number(number), address)));
+			}
+			keys
+		};
+
+		let to_pod = |state: &State| if state.exists(address) {
+			Some(PodAccount {
+				balance: state.balance(address),
+				nonce: state.nonce(address),
+				code: Some(state.code(address).map_or_else(Vec::new, |code| (*code).clone())),
+				storage: keys.iter()
+					.map(|key| (key.clone(), state.storage_at(address, key)))
+					.filter(|&(_, ref value)| !value.is_zero())
+					.collect(),
+			})
+		} else {
+			None
+		};
+		Ok(pod_account::diff_pod(to_pod(&pre_state).as_ref(), to_pod(&post_state).as_ref()))
+	}
+
 	fn keep_alive(&self) {
-		if self.mode != Mode::Active {
+		let should_wake = match *self.mode.lock() {
+			Mode::Dark(..) | Mode::Passive(..) => true,
+			_ => false,
+		};
+		if should_wake {
 			self.wake_up();
 			(*self.sleep_state.lock()).last_activity = Some(Instant::now());
 		}
 	}
 
+	fn mode(&self) -> IpcMode {
+		self.mode.lock().clone().into()
+	}
+
+	fn chain_warnings(&self) -> Vec<ChainWarning> {
+		self.chain_warnings.read().iter().cloned().collect()
+	}
+
+	fn release_quarantined_block(&self, hash: H256) -> bool {
+		let blocks = {
+			let mut quarantine = self.quarantine.lock();
+			match quarantine.blocks.get(&hash) {
+				Some(&(ref parent, _)) if !quarantine.blocks.contains_key(parent) => {},
+				_ => return false,
+			}
+
+			// the block with all held descendants, parents first.
+			let mut released = vec![hash.clone()];
+			let mut i = 0;
+			while i < released.len() {
+				let children: Vec<H256> = quarantine.blocks.iter()
+					.filter(|&(_, &(ref parent, _))| *parent == released[i])
+					.map(|(child, _)| child.clone())
+					.collect();
+				released.extend(children);
+				i += 1;
+			}
+			quarantine.released.insert(hash.clone());
+			released.iter().filter_map(|h| quarantine.blocks.remove(h)).map(|(_, bytes)| bytes).collect::<Vec<_>>()
+		};
+
+		info!(target: "client", "Releasing quarantined block {} with {} descendants.", hash, blocks.len() - 1);
+		for bytes in blocks {
+			if let Err(e) = self.import_block(bytes) {
+				warn!(target: "client", "Cannot import released block: {:?}", e);
+			}
+		}
+		self.chain_warnings.write().retain(|warning| warning.block_hash != hash);
+		true
+	}
+
+	fn dev_mine_blocks(&self, count: u64) -> Result<BlockNumber, String> {
+		if count > MAX_DEV_BLOCKS {
+			return Err(format!("Cannot mine more than {} blocks at once", MAX_DEV_BLOCKS));
+		}
+		for _ in 0..count {
+			try!(self.seal_dev_block(|_| Ok(())));
+		}
+		Ok(self.chain.read().best_block_number())
+	}
+
+	fn dev_set_balance(&self, address: Address, balance: U256) -> Result<(), String> {
+		self.seal_dev_block(|block| block.set_balance(&address, &balance)).map(|_| ())
+	}
+
+	fn dev_reset(&self) -> Result<(), String> {
+		let &(ref genesis, ref genesis_state) = try!(self.dev_genesis.as_ref().ok_or(DEV_CHAIN_ONLY));
+		let reset_path = format!("{}-reset", self.db.read().path());
+		let _ = fs::remove_dir_all(&reset_path);
+
+		{
+			// build a fresh database holding nothing but the genesis block and state.
+			let db = Arc::new(try!(Database::open(&DatabaseConfig::with_columns(::db::NUM_COLUMNS), &reset_path)));
+			BlockChain::new(self.config.blockchain.clone(), genesis, db.clone());
+			let mut state_db = StateDB::new(journaldb::new(db.clone(), self.pruning, ::db::COL_STATE), 0);
+			try!(spec::populate_state(genesis_state, &mut state_db).map_err(|e| format!("{}", e)));
+			let mut batch = DBTransaction::new(&db);
+			try!(state_db.commit(&mut batch, 0, &BlockView::new(genesis).hash(), None).map_err(|e| format!("{}", e)));
+			try!(db.write(batch));
+		}
+
+		// every block but the genesis is retracted.
+		let retracted = {
+			let chain = self.chain.read();
+			(1..chain.best_block_number() + 1).filter_map(|n| chain.block_hash(n)).collect::<Vec<_>>()
+		};
+
+		info!("Resetting development chain to genesis");
+		try!(self.restore_db(&reset_path).map_err(|e| format!("{}", e)));
+
+		let genesis_hash = BlockView::new(genesis).hash();
+		self.notify(|notify| {
+			notify.new_blocks(vec![], vec![], vec![genesis_hash.clone()], retracted.clone(), vec![], 0);
+		});
+		Ok(())
+	}
+
+	fn backup_database(&self, path: String) -> Result<(), String> {
+		if fs::metadata(&path).is_ok() {
+			return Err(format!("Backup destination {} already exists", path));
+		}
+		if self.backup_in_progress.compare_and_swap(false, true, AtomicOrdering::SeqCst) {
+			return Err("Another database backup is in progress".into());
+		}
+
+		let db = self.db.read().clone();
+		let in_progress = self.backup_in_progress.clone();
+		let spawned = thread::Builder::new().name("DB Backup".into()).spawn(move || {
+			info!("Backing up database to {}", path);
+			match db.backup(&path) {
+				Ok(()) => info!("Database backup to {} finished", path),
+				Err(e) => warn!("Database backup to {} failed: {}", path, e),
+			}
+			in_progress.store(false, AtomicOrdering::SeqCst);
+		});
+
+		spawned.map(|_| ()).map_err(|e| {
+			self.backup_in_progress.store(false, AtomicOrdering::SeqCst);
+			format!("Cannot start database backup: {}", e)
+		})
+	}
+
+	fn set_mode(&self, new_mode: IpcMode) {
+		let mode: Mode = new_mode.into();
+		trace!(target: "mode", "set_mode: Switching to {}", mode);
+		*self.mode.lock() = mode.clone();
+		if let Some(ref mut f) = *self.on_mode_change.lock() {
+			f(&mode);
+		}
+		match mode {
+			Mode::Off => self.sleep(),
+			Mode::Active => self.wake_up(),
+			Mode::Dark(..) | Mode::Passive(..) => {
+				self.wake_up();
+				(*self.sleep_state.lock()).last_activity = Some(Instant::now());
+			},
+		}
+	}
+
+	fn is_hibernating(&self) -> bool {
+		!self.liveness.load(AtomicOrdering::Relaxed)
+	}
+
 	fn best_block_header(&self) -> Bytes {
 		self.chain.read().best_block_header()
 	}
@@ -800,7 +1415,8 @@ impl BlockChainClient for Client {
 
 	fn block(&self, id: BlockID) -> Option<Bytes> {
 		if let BlockID::Pending = id {
-			if let Some(block) = self.miner.pending_block() {
+			let best_block = self.chain.read().best_block_number();
+			if let Some(block) = self.miner.pending_block(best_block) {
 				return Some(block.rlp_bytes(Seal::Without));
 			}
 		}
@@ -821,7 +1437,8 @@ impl BlockChainClient for Client {
 
 	fn block_total_difficulty(&self, id: BlockID) -> Option<U256> {
 		if let BlockID::Pending = id {
-			if let Some(block) = self.miner.pending_block() {
+			let best_block = self.chain.read().best_block_number();
+			if let Some(block) = self.miner.pending_block(best_block) {
 				return Some(*block.header.difficulty() + self.block_total_difficulty(BlockID::Latest).expect("blocks in chain have details; qed"));
 			}
 		}
@@ -882,15 +1499,96 @@ impl BlockChainClient for Client {
 		Some(accounts)
 	}
 
+	fn address_transactions(&self, address: &Address, offset: u64, limit: u64) -> Option<Vec<LocalizedTransaction>> {
+		if !self.config.address_index {
+			return None;
+		}
+
+		let chain = self.chain.read();
+		let entries = address_index::iter(&self.db.read(), address);
+		Some(entries
+			.filter(|entry| chain.block_hash(entry.block_number).map_or(false, |h| h == entry.block_hash))
+			.skip(offset as usize)
+			.take(limit as usize)
+			.filter_map(|entry| chain.transaction(&TransactionAddress { block_hash: entry.block_hash, index: entry.index }))
+			.collect())
+	}
+
+	fn list_storage(&self, id: BlockID, account: &Address) -> Option<Vec<H256>> {
+		if !self.factories.trie.is_fat() {
+			trace!(target: "fatdb", "list_storage: Not a fat DB");
+			return None;
+		}
+
+		let state = match self.state_at(id) {
+			Some(state) => state,
+			_ => return None,
+		};
+
+		let root = match state.storage_root(account) {
+			Some(root) => root,
+			_ => return None,
+		};
+
+		let (_, db) = state.drop();
+		let account_db = self.factories.accountdb.readonly(db.as_hashdb(), account.sha3());
+		let trie = match self.factories.trie.readonly(account_db.as_hashdb(), &root) {
+			Ok(trie) => trie,
+			_ => {
+				trace!(target: "fatdb", "list_storage: Couldn't open the DB");
+				return None;
+			}
+		};
+
+		let iter = match trie.iter() {
+			Ok(iter) => iter,
+			_ => return None,
+		};
+
+		let keys = iter.filter_map(|item| {
+			item.ok().map(|(key, _)| H256::from_slice(&key))
+		}).collect();
+
+		Some(keys)
+	}
+
 	fn transaction(&self, id: TransactionID) -> Option<LocalizedTransaction> {
 		self.transaction_address(id).and_then(|address| self.chain.read().transaction(&address))
 	}
 
+	fn is_pruned(&self, id: BlockID) -> bool {
+		let chain = self.chain.read();
+		Self::block_hash(&chain, id)
+			.and_then(|hash| chain.block_number(&hash).map(|number| (hash, number)))
+			.map_or(false, |(hash, number)| number < chain.pruned_before() && chain.block_hash(number) == Some(hash))
+	}
+
+	fn is_transaction_pruned(&self, id: TransactionID) -> bool {
+		self.transaction_address(id).map_or(false, |address| self.is_pruned(BlockID::Hash(address.block_hash)))
+	}
+
 	fn uncle(&self, id: UncleID) -> Option<Bytes> {
 		let index = id.position;
 		self.block_body(id.block).and_then(|body| BodyView::new(&body).uncle_rlp_at(index))
 	}
 
+	fn block_extra_info(&self, id: BlockID) -> Option<BTreeMap<String, String>> {
+		self.block_header(id)
+			.map(|header| self.engine.extra_info(&decode(&header)).into_iter().collect())
+	}
+
+	fn uncle_extra_info(&self, id: UncleID) -> Option<BTreeMap<String, String>> {
+		self.uncle(id)
+			.map(|header| self.engine.extra_info(&decode(&header)).into_iter().collect())
+	}
+
+	fn block_rewards(&self, id: BlockID) -> Option<BlockRewards> {
+		self.block(id).map(|block| {
+			let block = BlockView::new(&block);
+			self.engine.block_rewards(&block.header(), &block.uncles())
+		})
+	}
+
 	fn transaction_receipt(&self, id: TransactionID) -> Option<LocalizedReceipt> {
 		let chain = self.chain.read();
 		self.transaction_address(id)
@@ -900,19 +1598,23 @@ impl BlockChainClient for Client {
 					BodyView::new(&block).localized_transaction_at(&address.block_hash, block_number, address.index)
 				});
 
-			match (t, chain.transaction_receipt(&address)) {
-				(Some(tx), Some(receipt)) => {
+			match (t, chain.block_receipts(&address.block_hash)) {
+				(Some(tx), Some(block_receipts)) => {
 					let block_hash = tx.block_hash.clone();
 					let block_number = tx.block_number.clone();
 					let transaction_hash = tx.hash();
 					let transaction_index = tx.transaction_index;
-					let prior_gas_used = match tx.transaction_index {
-						0 => U256::zero(),
-						i => {
-							let prior_address = TransactionAddress { block_hash: address.block_hash, index: i - 1 };
-							let prior_receipt = chain.transaction_receipt(&prior_address).expect("Transaction receipt at `address` exists; `prior_address` has lower index in same block; qed");
-							prior_receipt.gas_used
-						}
+					let receipts = &block_receipts.receipts;
+					let receipt = match receipts.get(transaction_index) {
+						Some(receipt) => receipt.clone(),
+						None => return None,
+					};
+					let (prior_gas_used, prior_logs) = match transaction_index {
+						0 => (U256::zero(), 0),
+						i => (
+							receipts[i - 1].gas_used,
+							receipts[..i].iter().fold(0, |logs, r| logs + r.logs.len()),
+						),
 					};
 					Some(LocalizedReceipt {
 						transaction_hash: tx.hash(),
@@ -925,13 +1627,14 @@ impl BlockChainClient for Client {
 							Action::Call(_) => None,
 							Action::Create => Some(contract_address(&tx.sender().unwrap(), &tx.nonce))
 						},
+						state_root: receipt.state_root,
 						logs: receipt.logs.into_iter().enumerate().map(|(i, log)| LocalizedLogEntry {
 							entry: log,
 							block_hash: block_hash.clone(),
 							block_number: block_number,
 							transaction_hash: transaction_hash.clone(),
 							transaction_index: transaction_index,
-							log_index: i
+							log_index: prior_logs + i,
 						}).collect()
 					})
 				},
@@ -982,6 +1685,17 @@ impl BlockChainClient for Client {
 		self.block_queue.queue_info()
 	}
 
+	fn memory_usage(&self) -> ClientMemoryUsage {
+		let chain = self.chain.read();
+		let state_db = self.state_db.lock();
+		let queue_info = self.block_queue.queue_info();
+		ClientMemoryUsage {
+			blockchain: CacheUsage { used: chain.cache_size().total(), limit: chain.cache_limit() },
+			state: CacheUsage { used: state_db.node_cache_size(), limit: state_db.node_cache_limit() },
+			block_queue: CacheUsage { used: queue_info.mem_used, limit: queue_info.max_mem_use },
+		}
+	}
+
 	fn clear_queue(&self) {
 		self.block_queue.clear();
 	}
@@ -1001,6 +1715,10 @@ impl BlockChainClient for Client {
 		self.engine.additional_params().into_iter().collect()
 	}
 
+	fn forks(&self) -> BTreeMap<String, Option<u64>> {
+		self.engine.forks().into_iter().map(|(name, block)| (name.to_owned(), block)).collect()
+	}
+
 	fn blocks_with_bloom(&self, bloom: &H2048, from_block: BlockID, to_block: BlockID) -> Option<Vec<BlockNumber>> {
 		match (self.block_number(from_block), self.block_number(to_block)) {
 			(Some(from), Some(to)) => Some(self.chain.read().blocks_with_bloom(bloom, from, to)),
@@ -1009,15 +1727,15 @@ impl BlockChainClient for Client {
 	}
 
 	fn logs(&self, filter: Filter) -> Vec<LocalizedLogEntry> {
-		let blocks = filter.bloom_possibilities().iter()
-			.filter_map(|bloom| self.blocks_with_bloom(bloom, filter.from_block.clone(), filter.to_block.clone()))
-			.flat_map(|m| m)
-			// remove duplicate elements
-			.collect::<HashSet<u64>>()
-			.into_iter()
-			.collect::<Vec<u64>>();
-
-		self.chain.read().logs(blocks, |entry| filter.matches(entry), filter.limit)
+		match (&filter.from_block, &filter.to_block) {
+			// logs of a single block, which doesn't have to be canonical
+			(&BlockID::Hash(ref from), &BlockID::Hash(ref to)) if from == to =>
+				self.chain.read().logs_by_hash(vec![from.clone()], |entry| filter.matches(entry), filter.limit),
+			_ => {
+				let blocks = self.blocks_with_filter(&filter);
+				self.chain.read().logs(blocks, |entry| filter.matches(entry), filter.limit)
+			},
+		}
 	}
 
 	fn filter_traces(&self, filter: TraceFilter) -> Option<Vec<LocalizedTrace>> {
@@ -1081,7 +1799,15 @@ impl BlockChainClient for Client {
 	}
 
 	fn pending_transactions(&self) -> Vec<SignedTransaction> {
-		self.miner.pending_transactions(self.chain.read().best_block_number())
+		let transactions = self.miner.pending_transactions(self.chain.read().best_block_number());
+		transactions.into_iter().filter(|tx| !self.miner.is_local_only_transaction(&tx.hash())).collect()
+	}
+
+	fn queued_transaction(&self, hash: &H256) -> Option<SignedTransaction> {
+		match self.miner.is_local_only_transaction(hash) {
+			true => None,
+			false => self.miner.queued_transaction(hash).map(|(tx, _)| tx),
+		}
 	}
 }
 
@@ -1128,7 +1854,22 @@ impl MiningBlockChainClient for Client {
 		let h = block.header().hash();
 		let number = block.header().number();
 
+		// only announce blocks that are certain to be committed.
+		let parent_td = {
+			let chain = self.chain.read();
+			if chain.is_known(&h) {
+				return Err(ImportError::AlreadyInChain.into());
+			}
+			match chain.block_details(block.header().parent_hash()) {
+				Some(details) => details.total_difficulty,
+				None => return Err(BlockError::UnknownParent(block.header().parent_hash().clone()).into()),
+			}
+		};
+
 		let block_data = block.rlp_bytes();
+		// the seal is already checked; peers needn't wait for the commit.
+		self.notify(|notify| notify.block_sealed(block_data.clone(), parent_td + *block.header().difficulty()));
+
 		let route = self.commit_block(block, &h, &block_data);
 		trace!(target: "client", "Imported sealed block #{} ({})", number, h);
 		self.state_db.lock().sync_cache(&route.enacted, &route.retracted, false);
@@ -15,6 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::str::FromStr;
+use std::fmt;
 pub use std::time::Duration;
 pub use blockchain::Config as BlockChainConfig;
 pub use trace::Config as TraceConfig;
@@ -71,6 +72,19 @@ pub enum Mode {
 	/// Goes offline after RLP is inactive for some (given) time and
 	/// stays inactive.
 	Dark(Duration),
+	/// Always off.
+	Off,
+}
+
+impl fmt::Display for Mode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Mode::Active => write!(f, "active"),
+			Mode::Passive(..) => write!(f, "passive"),
+			Mode::Dark(..) => write!(f, "dark"),
+			Mode::Off => write!(f, "offline"),
+		}
+	}
 }
 
 impl Default for Mode {
@@ -98,6 +112,8 @@ pub struct ClientConfig {
 	pub name: String,
 	/// State db cache-size if not default
 	pub db_cache_size: Option<usize>,
+	/// Size of the state trie node cache in bytes. Zero disables the cache.
+	pub state_cache_size: usize,
 	/// State db compaction profile
 	pub db_compaction: DatabaseCompactionProfile,
 	/// Should db have WAL enabled?
@@ -106,6 +122,15 @@ pub struct ClientConfig {
 	pub mode: Mode,
 	/// Type of block verifier used by client.
 	pub verifier_type: VerifierType,
+	/// Maximal number of canonical blocks a reorganization may revert. Unlimited if none.
+	pub max_reorg_depth: Option<u64>,
+	/// Maintain an index of transactions by sender and recipient address.
+	pub address_index: bool,
+	/// Remove bodies and receipts of canonical blocks older than this many blocks. Keep all if none.
+	pub prune_ancient_bodies: Option<u64>,
+	/// Number of IO worker threads handling client messages and timers.
+	/// Defaults to half the number of cores, between 2 and 8, when not set.
+	pub io_workers: Option<usize>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,138 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Client executing contract code against an ephemeral state, used to benchmark the EVM.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use util::{U256, Address, Bytes, BytesRef, Hashable, journaldb};
+use util::kvdb::{Database, DatabaseConfig};
+use action_params::{ActionParams, ActionValue};
+use env_info::EnvInfo;
+use evm::{self, CallType, Factory as EvmFactory, VMType};
+use executive::{Executive, contract_address};
+use factory::Factories;
+use spec::Spec;
+use state::{State, Substate};
+use state_db::StateDB;
+use trace::{NoopTracer, NoopVMTracer};
+
+/// Outcome of running code with `EvmTestClient`.
+#[derive(Debug, PartialEq)]
+pub struct EvmStats {
+	/// Gas consumed by the execution.
+	pub gas_used: U256,
+	/// Data returned by the code.
+	pub output: Bytes,
+	/// Time spent in the EVM.
+	pub time: Duration,
+}
+
+/// Runs contract code under the rules of a chain specification, against a state
+/// holding nothing but its genesis accounts.
+pub struct EvmTestClient<'a> {
+	spec: &'a Spec,
+	state: State,
+	vm_factory: EvmFactory,
+}
+
+impl<'a> EvmTestClient<'a> {
+	/// Create a new client keeping its state database at `path`, which should be an
+	/// empty temporary directory. It's up to the caller to remove it afterwards.
+	pub fn new(spec: &'a Spec, vm_type: VMType, path: &Path) -> Result<Self, String> {
+		let db = Arc::new(try!(Database::open(&DatabaseConfig::with_columns(::db::NUM_COLUMNS), &path.to_string_lossy())));
+		let mut state_db = StateDB::new(journaldb::new(db, journaldb::Algorithm::EarlyMerge, ::db::COL_STATE), 0);
+		try!(spec.ensure_db_good(&mut state_db).map_err(|e| format!("{}", e)));
+
+		let factories = Factories {
+			vm: EvmFactory::new(vm_type.clone()),
+			trie: Default::default(),
+			accountdb: Default::default(),
+		};
+		let state = try!(State::from_existing(state_db, spec.state_root(), spec.engine.account_start_nonce(), factories)
+			.map_err(|e| format!("{}", e)));
+
+		Ok(EvmTestClient {
+			spec: spec,
+			state: state,
+			vm_factory: EvmFactory::new(vm_type),
+		})
+	}
+
+	/// Run `code` with `input` as call data, as if called by a transaction sent from the zero address
+	/// in a block described by `info`, whose number selects the rules of the chain used.
+	/// Changes made to the state are kept for subsequent calls.
+	pub fn call(&mut self, info: &EnvInfo, code: Bytes, input: Bytes, gas: U256, gas_price: U256) -> evm::Result<EvmStats> {
+		let address = contract_address(&Address::zero(), &U256::zero());
+		let params = ActionParams {
+			code_address: address.clone(),
+			code_hash: code.sha3(),
+			address: address,
+			sender: Address::zero(),
+			origin: Address::zero(),
+			gas: gas,
+			gas_price: gas_price,
+			value: ActionValue::Apparent(U256::zero()),
+			code: Some(Arc::new(code)),
+			data: Some(input),
+			call_type: CallType::Call,
+		};
+
+		let mut substate = Substate::new();
+		let mut output = Vec::new();
+		let start = Instant::now();
+		let gas_left = {
+			let mut executive = Executive::new(&mut self.state, info, &*self.spec.engine, &self.vm_factory);
+			try!(executive.call(params, &mut substate, BytesRef::Flexible(&mut output), &mut NoopTracer, &mut NoopVMTracer))
+		};
+
+		Ok(EvmStats {
+			gas_used: gas - gas_left,
+			output: output,
+			time: start.elapsed(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use devtools::RandomTempPath;
+	use env_info::EnvInfo;
+	use evm::VMType;
+	use rustc_serialize::hex::FromHex;
+	use spec::Spec;
+	use super::EvmTestClient;
+
+	#[test]
+	fn runs_code_against_genesis_state() {
+		let spec = Spec::new_test();
+		let path = RandomTempPath::new();
+		let mut client = EvmTestClient::new(&spec, VMType::Interpreter, path.as_path()).unwrap();
+
+		// return CALLDATALOAD(0) + 1
+		let code = "60016000350160005260206000f3".from_hex().unwrap();
+		let input = vec![0u8; 31].into_iter().chain(Some(41)).collect::<Vec<_>>();
+		let mut info = EnvInfo::default();
+		info.gas_limit = 100_000.into();
+		let stats = client.call(&info, code, input, 100_000.into(), 0.into()).unwrap();
+
+		let mut expected = vec![0u8; 32];
+		expected[31] = 42;
+		assert_eq!(stats.output, expected);
+		assert_eq!(stats.gas_used, 27.into());
+	}
+}
@@ -0,0 +1,244 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Database integrity checks.
+
+use std::fmt;
+use util::{H256, FixedHash, SHA3_EMPTY};
+use util::hashdb::HashDB;
+use util::trie::{Trie, TrieDB, TrieError};
+use account_db::Factory as AccountDBFactory;
+use header::BlockNumber;
+use state::Account;
+use error::Error;
+
+/// Maximal number of missing nodes reported before the state check gives up.
+const MAX_REPORTED_NODES: usize = 64;
+
+/// Problem found by the database integrity check.
+#[derive(Debug, PartialEq, Clone)]
+pub enum IntegrityIssue {
+	/// Part of the block is missing from the chain database.
+	MissingBlockData {
+		/// Block number.
+		number: BlockNumber,
+		/// Block hash.
+		hash: H256,
+		/// Missing part of the block (header, body, details or receipts).
+		what: &'static str,
+	},
+	/// State root of the block is missing from the state database.
+	MissingStateRoot {
+		/// Block number.
+		number: BlockNumber,
+		/// Block hash.
+		hash: H256,
+		/// Missing state root.
+		root: H256,
+	},
+	/// Account or storage trie node is missing from the state database.
+	MissingTrieNode(H256),
+	/// Contract code is missing from the state database.
+	MissingCode(H256),
+}
+
+impl fmt::Display for IntegrityIssue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			IntegrityIssue::MissingBlockData { number, ref hash, what } =>
+				write!(f, "Block #{} ({}) is missing its {}", number, hash, what),
+			IntegrityIssue::MissingStateRoot { number, ref hash, ref root } =>
+				write!(f, "State root {} of block #{} ({}) is missing", root, number, hash),
+			IntegrityIssue::MissingTrieNode(ref hash) => write!(f, "State trie node {} is missing", hash),
+			IntegrityIssue::MissingCode(ref hash) => write!(f, "Contract code {} is missing", hash),
+		}
+	}
+}
+
+/// Result of the database integrity check.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct IntegrityReport {
+	/// Best block at the time of the check.
+	pub best_block: BlockNumber,
+	/// Number of most recent blocks checked.
+	pub blocks_checked: u64,
+	/// Number of accounts of the best block state checked. Zero unless the state was walked.
+	pub accounts_checked: usize,
+	/// Problems found.
+	pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+	/// Returns true if no problems were found.
+	pub fn is_ok(&self) -> bool {
+		self.issues.is_empty()
+	}
+
+	/// Returns true if the best block state root is missing.
+	pub fn is_state_missing(&self) -> bool {
+		self.issues.iter().any(|issue| match *issue {
+			IntegrityIssue::MissingStateRoot { .. } | IntegrityIssue::MissingTrieNode(_) | IntegrityIssue::MissingCode(_) => true,
+			_ => false,
+		})
+	}
+}
+
+impl fmt::Display for IntegrityReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		try!(writeln!(f, "Checked {} blocks up to #{} and {} accounts.", self.blocks_checked, self.best_block, self.accounts_checked));
+		match self.issues.len() {
+			0 => write!(f, "No problems found."),
+			n => {
+				try!(write!(f, "Found {} problems:", n));
+				for issue in &self.issues {
+					try!(write!(f, "\n  {}", issue));
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+/// State repair error.
+#[derive(Debug)]
+pub enum RepairError {
+	/// None of the recent blocks has complete state to re-execute from.
+	NoIntactAncestor,
+	/// Chain data needed to re-execute the block is missing.
+	MissingBlock(BlockNumber),
+	/// Block failed to re-execute.
+	Execution(BlockNumber, Error),
+	/// Re-executed block produced different state root.
+	StateRootMismatch {
+		/// Block number.
+		number: BlockNumber,
+		/// State root from the block header.
+		expected: H256,
+		/// State root produced by re-execution.
+		got: H256,
+	},
+	/// Repaired state could not be written to the database.
+	Database(String),
+}
+
+impl fmt::Display for RepairError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			RepairError::NoIntactAncestor => write!(f, "No recent block with complete state found"),
+			RepairError::MissingBlock(number) => write!(f, "Block #{} is missing from the chain database", number),
+			RepairError::Execution(number, ref e) => write!(f, "Failed to re-execute block #{}: {:?}", number, e),
+			RepairError::StateRootMismatch { number, ref expected, ref got } =>
+				write!(f, "Re-executed block #{} produced state root {}, expected {}", number, got, expected),
+			RepairError::Database(ref e) => write!(f, "Failed to write the restored state: {}", e),
+		}
+	}
+}
+
+fn note_missing(error: Box<TrieError>, issues: &mut Vec<IntegrityIssue>) {
+	match *error {
+		TrieError::InvalidStateRoot(hash) | TrieError::IncompleteDatabase(hash) => issues.push(IntegrityIssue::MissingTrieNode(hash)),
+	}
+}
+
+/// Walks the whole state trie under `root` including account storage and code,
+/// noting missing nodes. Returns number of accounts checked.
+pub fn check_state(db: &HashDB, root: &H256, accountdb: &AccountDBFactory, issues: &mut Vec<IntegrityIssue>) -> usize {
+	let trie = match TrieDB::new(db, root) {
+		Ok(trie) => trie,
+		Err(e) => { note_missing(e, issues); return 0; }
+	};
+	let iter = match trie.iter() {
+		Ok(iter) => iter,
+		Err(e) => { note_missing(e, issues); return 0; }
+	};
+
+	let mut accounts = 0;
+	for item in iter {
+		if issues.len() >= MAX_REPORTED_NODES {
+			break;
+		}
+
+		let (key, value) = match item {
+			Ok(item) => item,
+			Err(e) => { note_missing(e, issues); continue; }
+		};
+		accounts += 1;
+
+		let account = Account::from_rlp(value);
+		let account_db = accountdb.readonly(db, H256::from_slice(&key));
+		let code_hash = account.code_hash();
+		if code_hash != SHA3_EMPTY && !account_db.contains(&code_hash) {
+			issues.push(IntegrityIssue::MissingCode(code_hash));
+		}
+
+		let storage_root = account.storage_root().cloned().expect("account loaded from rlp has clean storage; qed");
+		let storage = match TrieDB::new(&*account_db, &storage_root) {
+			Ok(storage) => storage,
+			Err(e) => { note_missing(e, issues); continue; }
+		};
+		match storage.iter() {
+			Ok(iter) => for item in iter {
+				if let Err(e) = item {
+					note_missing(e, issues);
+				}
+			},
+			Err(e) => note_missing(e, issues),
+		}
+	}
+	accounts
+}
+
+#[cfg(test)]
+mod tests {
+	use util::{H256, FixedHash};
+	use util::hashdb::HashDB;
+	use util::memorydb::MemoryDB;
+	use util::trie::{TrieMut, SecTrieDBMut};
+	use account_db::Factory as AccountDBFactory;
+	use state::Account;
+	use super::{check_state, IntegrityIssue};
+
+	fn state_with_account(db: &mut MemoryDB) -> H256 {
+		let mut root = H256::new();
+		{
+			let mut trie = SecTrieDBMut::new(db, &mut root);
+			trie.insert(&[1u8; 20], &Account::new_basic(10.into(), 0.into()).rlp()).unwrap();
+		}
+		root
+	}
+
+	#[test]
+	fn should_find_no_issues_in_complete_state() {
+		let mut db = MemoryDB::new();
+		let root = state_with_account(&mut db);
+
+		let mut issues = Vec::new();
+		assert_eq!(check_state(&db, &root, &AccountDBFactory::Mangled, &mut issues), 1);
+		assert!(issues.is_empty());
+	}
+
+	#[test]
+	fn should_report_missing_state_root() {
+		let mut db = MemoryDB::new();
+		let root = state_with_account(&mut db);
+		db.remove(&root);
+		db.purge();
+
+		let mut issues = Vec::new();
+		assert_eq!(check_state(&db, &root, &AccountDBFactory::Mangled, &mut issues), 0);
+		assert_eq!(issues, vec![IntegrityIssue::MissingTrieNode(root)]);
+	}
+}
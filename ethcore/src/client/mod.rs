@@ -21,12 +21,20 @@ mod error;
 mod test_client;
 mod trace;
 mod client;
+mod integrity;
+mod address_index;
+mod evm_test_client;
 
 pub use self::client::*;
 pub use self::config::{Mode, ClientConfig, DatabaseCompactionProfile, BlockChainConfig, VMType};
 pub use self::error::Error;
+pub use self::integrity::{IntegrityIssue, IntegrityReport, RepairError};
 pub use types::ids::*;
+pub use types::mode::IpcMode;
+pub use types::chain_warning::ChainWarning;
+pub use types::memory_usage::{CacheUsage, ClientMemoryUsage};
 pub use self::test_client::{TestBlockChainClient, EachBlockWith};
+pub use self::evm_test_client::{EvmTestClient, EvmStats};
 pub use types::trace_filter::Filter as TraceFilter;
 pub use executive::{Executed, Executive, TransactOptions};
 pub use env_info::{LastHashes, EnvInfo};
@@ -23,9 +23,10 @@ use ethkey::{Generator, Random};
 use devtools::*;
 use transaction::{Transaction, LocalizedTransaction, SignedTransaction, Action};
 use blockchain::TreeRoute;
+use views::BlockView;
 use client::{
 	BlockChainClient, MiningBlockChainClient, BlockChainInfo, BlockStatus, BlockID,
-	TransactionID, UncleID, TraceId, TraceFilter, LastHashes, CallAnalytics, BlockImportError,
+	TransactionID, UncleID, TraceId, TraceFilter, LastHashes, CallAnalytics, BlockImportError, IpcMode, ChainWarning,
 };
 use db::{NUM_COLUMNS, COL_STATE};
 use header::{Header as BlockHeader, BlockNumber};
@@ -42,6 +43,9 @@ use verification::queue::QueueInfo;
 use block::{OpenBlock, SealedBlock};
 use executive::Executed;
 use error::CallError;
+use types::account_diff::AccountDiff;
+use types::block_rewards::BlockRewards;
+use types::memory_usage::ClientMemoryUsage;
 use trace::LocalizedTrace;
 use state_db::StateDB;
 
@@ -81,6 +85,16 @@ pub struct TestBlockChainClient {
 	pub vm_factory: EvmFactory,
 	/// Timestamp assigned to latest sealed block
 	pub latest_block_timestamp: RwLock<u64>,
+	/// Operating mode
+	pub mode: RwLock<IpcMode>,
+	/// Chain warnings
+	pub chain_warnings: RwLock<Vec<ChainWarning>>,
+	/// Paths database backups were requested at
+	pub backups: RwLock<Vec<String>>,
+	/// Blocks whose bodies and receipts were pruned
+	pub pruned: RwLock<HashSet<H256>>,
+	/// Reported memory usage of caches
+	pub memory_usage: RwLock<ClientMemoryUsage>,
 }
 
 #[derive(Clone)]
@@ -124,6 +138,11 @@ impl TestBlockChainClient {
 			spec: spec,
 			vm_factory: EvmFactory::new(VMType::Interpreter),
 			latest_block_timestamp: RwLock::new(10_000_000),
+			mode: RwLock::new(IpcMode::Active),
+			chain_warnings: RwLock::new(Vec::new()),
+			backups: RwLock::new(Vec::new()),
+			pruned: RwLock::new(HashSet::new()),
+			memory_usage: RwLock::new(ClientMemoryUsage::default()),
 		};
 		client.add_blocks(1, EachBlockWith::Nothing); // add genesis block
 		client.genesis_hash = client.last_hash.read().clone();
@@ -226,6 +245,29 @@ impl TestBlockChainClient {
 		}
 	}
 
+	/// Replaces the `depth` most recent blocks with `count` new ones, as a reorganisation would.
+	/// Retracted blocks are forgotten. Blocks only differ from the ones they replace if built
+	/// `with` something else. Returns hashes of the retracted and enacted blocks.
+	pub fn reorg(&self, depth: usize, count: usize, with: EachBlockWith) -> (Vec<H256>, Vec<H256>) {
+		let len = self.numbers.read().len();
+		assert!(depth < len, "The genesis block can't be retracted");
+		let fork_point = len - depth;
+
+		let retracted: Vec<H256> = (fork_point..len).map(|n| self.numbers.write().remove(&n).expect("n < len; qed")).collect();
+		{
+			let mut blocks = self.blocks.write();
+			for hash in &retracted {
+				blocks.remove(hash);
+			}
+		}
+		*self.last_hash.write() = self.numbers.read()[&(fork_point - 1)].clone();
+
+		self.add_blocks(count, with);
+		let numbers = self.numbers.read();
+		let enacted = (fork_point..fork_point + count).map(|n| numbers[&n].clone()).collect();
+		(retracted, enacted)
+	}
+
 	/// Make a bad block by setting invalid extra data.
 	pub fn corrupt_block(&mut self, n: BlockNumber) {
 		let hash = self.block_hash(BlockID::Number(n)).unwrap();
@@ -289,7 +331,7 @@ pub fn get_temp_state_db() -> GuardedTempResult<StateDB> {
 	let temp = RandomTempPath::new();
 	let db = Database::open(&DatabaseConfig::with_columns(NUM_COLUMNS), temp.as_str()).unwrap();
 	let journal_db = journaldb::new(Arc::new(db), journaldb::Algorithm::EarlyMerge, COL_STATE);
-	let state_db = StateDB::new(journal_db);
+	let state_db = StateDB::new(journal_db, 5 * 1024 * 1024);
 	GuardedTempResult {
 		_temp: temp,
 		result: Some(state_db)
@@ -331,10 +373,66 @@ impl MiningBlockChainClient for TestBlockChainClient {
 }
 
 impl BlockChainClient for TestBlockChainClient {
+	fn mode(&self) -> IpcMode {
+		self.mode.read().clone()
+	}
+
+	fn set_mode(&self, mode: IpcMode) {
+		*self.mode.write() = mode;
+	}
+
+	fn chain_warnings(&self) -> Vec<ChainWarning> {
+		self.chain_warnings.read().clone()
+	}
+
+	fn release_quarantined_block(&self, hash: H256) -> bool {
+		let mut warnings = self.chain_warnings.write();
+		let count = warnings.len();
+		warnings.retain(|warning| warning.block_hash != hash);
+		warnings.len() != count
+	}
+
+	fn dev_mine_blocks(&self, count: u64) -> Result<BlockNumber, String> {
+		self.add_blocks(count as usize, EachBlockWith::Nothing);
+		Ok(self.chain_info().best_block_number)
+	}
+
+	fn dev_set_balance(&self, address: Address, balance: U256) -> Result<(), String> {
+		self.set_balance(address, balance);
+		Ok(())
+	}
+
+	fn dev_reset(&self) -> Result<(), String> {
+		let genesis_hash = self.genesis_hash.clone();
+		let genesis = self.blocks.read()[&genesis_hash].clone();
+		let mut blocks = self.blocks.write();
+		blocks.clear();
+		blocks.insert(genesis_hash.clone(), genesis);
+		let mut numbers = self.numbers.write();
+		numbers.clear();
+		numbers.insert(0, genesis_hash.clone());
+		*self.last_hash.write() = genesis_hash;
+		self.balances.write().clear();
+		Ok(())
+	}
+
+	fn backup_database(&self, path: String) -> Result<(), String> {
+		self.backups.write().push(path);
+		Ok(())
+	}
+
 	fn call(&self, _t: &SignedTransaction, _block: BlockID, _analytics: CallAnalytics) -> Result<Executed, CallError> {
 		self.execution_result.read().clone().unwrap()
 	}
 
+	fn call_many(&self, transactions: &[SignedTransaction], _block: BlockID, _analytics: CallAnalytics) -> Result<Vec<Executed>, CallError> {
+		transactions.iter().map(|_| self.execution_result.read().clone().unwrap()).collect()
+	}
+
+	fn account_diff(&self, _address: &Address, _from: BlockID, _to: BlockID) -> Result<Option<AccountDiff>, CallError> {
+		Ok(None)
+	}
+
 	fn replay(&self, _id: TransactionID, _analytics: CallAnalytics) -> Result<Executed, CallError> {
 		self.execution_result.read().clone().unwrap()
 	}
@@ -389,6 +487,14 @@ impl BlockChainClient for TestBlockChainClient {
 		None
 	}
 
+	fn list_storage(&self, _id: BlockID, _account: &Address) -> Option<Vec<H256>> {
+		None
+	}
+
+	fn address_transactions(&self, _address: &Address, _offset: u64, _limit: u64) -> Option<Vec<LocalizedTransaction>> {
+		None
+	}
+
 	fn transaction(&self, _id: TransactionID) -> Option<LocalizedTransaction> {
 		None	// Simple default.
 	}
@@ -397,12 +503,44 @@ impl BlockChainClient for TestBlockChainClient {
 		None	// Simple default.
 	}
 
+	fn block_extra_info(&self, id: BlockID) -> Option<BTreeMap<String, String>> {
+		self.block(id)
+			.map(|block| BlockView::new(&block).header())
+			.map(|header| self.spec.engine.extra_info(&header).into_iter().collect())
+	}
+
+	fn block_rewards(&self, id: BlockID) -> Option<BlockRewards> {
+		self.block(id).map(|block| {
+			let block = BlockView::new(&block);
+			self.spec.engine.block_rewards(&block.header(), &block.uncles())
+		})
+	}
+
+	fn uncle_extra_info(&self, _id: UncleID) -> Option<BTreeMap<String, String>> {
+		None
+	}
+
 	fn transaction_receipt(&self, id: TransactionID) -> Option<LocalizedReceipt> {
 		self.receipts.read().get(&id).cloned()
 	}
 
-	fn blocks_with_bloom(&self, _bloom: &H2048, _from_block: BlockID, _to_block: BlockID) -> Option<Vec<BlockNumber>> {
-		unimplemented!();
+	fn is_pruned(&self, id: BlockID) -> bool {
+		self.block_hash(id).map_or(false, |hash| self.pruned.read().contains(&hash))
+	}
+
+	fn is_transaction_pruned(&self, id: TransactionID) -> bool {
+		match id {
+			TransactionID::Location(id, _) => self.is_pruned(id),
+			TransactionID::Hash(_) => false,
+		}
+	}
+
+	fn blocks_with_bloom(&self, _bloom: &H2048, from_block: BlockID, to_block: BlockID) -> Option<Vec<BlockNumber>> {
+		// every block is a candidate
+		match (self.block_number(from_block), self.block_number(to_block)) {
+			(Some(from), Some(to)) => Some((from..to + 1).collect()),
+			_ => None,
+		}
 	}
 
 	fn logs(&self, filter: Filter) -> Vec<LocalizedLogEntry> {
@@ -427,16 +565,17 @@ impl BlockChainClient for TestBlockChainClient {
 	}
 
 	fn block_body(&self, id: BlockID) -> Option<Bytes> {
-		self.block_hash(id).and_then(|hash| self.blocks.read().get(&hash).map(|r| {
+		self.block(id).map(|r| {
 			let mut stream = RlpStream::new_list(2);
-			stream.append_raw(Rlp::new(r).at(1).as_raw(), 1);
-			stream.append_raw(Rlp::new(r).at(2).as_raw(), 1);
+			stream.append_raw(Rlp::new(&r).at(1).as_raw(), 1);
+			stream.append_raw(Rlp::new(&r).at(2).as_raw(), 1);
 			stream.out()
-		}))
+		})
 	}
 
 	fn block(&self, id: BlockID) -> Option<Bytes> {
-		self.block_hash(id).and_then(|hash| self.blocks.read().get(&hash).cloned())
+		self.block_hash(id)
+			.and_then(|hash| if self.pruned.read().contains(&hash) { None } else { self.blocks.read().get(&hash).cloned() })
 	}
 
 	fn block_status(&self, id: BlockID) -> BlockStatus {
@@ -551,6 +690,10 @@ impl BlockChainClient for TestBlockChainClient {
 		Ok(h)
 	}
 
+	fn memory_usage(&self) -> ClientMemoryUsage {
+		self.memory_usage.read().clone()
+	}
+
 	fn queue_info(&self) -> QueueInfo {
 		QueueInfo {
 			verified_queue_size: self.queue_size.load(AtomicOrder::Relaxed),
@@ -559,6 +702,7 @@ impl BlockChainClient for TestBlockChainClient {
 			max_queue_size: 0,
 			max_mem_use: 0,
 			mem_used: 0,
+			verifier_count: 0,
 		}
 	}
 
@@ -569,6 +713,10 @@ impl BlockChainClient for TestBlockChainClient {
 		Default::default()
 	}
 
+	fn forks(&self) -> BTreeMap<String, Option<u64>> {
+		Default::default()
+	}
+
 	fn chain_info(&self) -> BlockChainInfo {
 		BlockChainInfo {
 			total_difficulty: *self.difficulty.read(),
@@ -602,6 +750,14 @@ impl BlockChainClient for TestBlockChainClient {
 	}
 
 	fn pending_transactions(&self) -> Vec<SignedTransaction> {
-		self.miner.pending_transactions(self.chain_info().best_block_number)
+		let transactions = self.miner.pending_transactions(self.chain_info().best_block_number);
+		transactions.into_iter().filter(|tx| !self.miner.is_local_only_transaction(&tx.hash())).collect()
+	}
+
+	fn queued_transaction(&self, hash: &H256) -> Option<SignedTransaction> {
+		match self.miner.is_local_only_transaction(hash) {
+			true => None,
+			false => self.miner.queued_transaction(hash).map(|(tx, _)| tx),
+		}
 	}
 }
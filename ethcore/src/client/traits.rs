@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use util::{U256, Address, H256, H2048, Bytes, Itertools};
 use blockchain::TreeRoute;
 use verification::queue::QueueInfo as BlockQueueInfo;
@@ -37,15 +37,51 @@ use block_import_error::BlockImportError;
 use ipc::IpcConfig;
 use types::blockchain_info::BlockChainInfo;
 use types::block_status::BlockStatus;
+use types::mode::IpcMode;
+use types::chain_warning::ChainWarning;
+use types::account_diff::AccountDiff;
+use types::block_rewards::BlockRewards;
+use types::memory_usage::ClientMemoryUsage;
 
 #[ipc(client_ident="RemoteClient")]
 /// Blockchain database client. Owns and manages a blockchain and a block queue.
 pub trait BlockChainClient : Sync + Send {
 
 	/// Should be called by any external-facing interface when actively using the client.
-	/// To minimise chatter, there's no need to call more than once every 30s.
+	/// Wakes the client up if it is hibernating and postpones hibernation otherwise.
 	fn keep_alive(&self) {}
 
+	/// Returns true if the client is hibernating, i.e. it has been inactive for longer than
+	/// the timeout of its operating mode and has released its caches and paused syncing.
+	fn is_hibernating(&self) -> bool { false }
+
+	/// Returns current operating mode of the client.
+	fn mode(&self) -> IpcMode;
+
+	/// Changes operating mode of the client.
+	fn set_mode(&self, mode: IpcMode);
+
+	/// Returns blocks quarantined by the reorganization depth limit, most recent last.
+	fn chain_warnings(&self) -> Vec<ChainWarning>;
+
+	/// Imports the quarantined block with given hash and its held descendants regardless
+	/// of the reorganization depth limit. Returns `false` if no such block is held.
+	fn release_quarantined_block(&self, hash: H256) -> bool;
+
+	/// Seals `count` empty blocks on a development chain, returning the new best block number.
+	/// Fails if `count` is too large.
+	fn dev_mine_blocks(&self, count: u64) -> Result<BlockNumber, String>;
+
+	/// Seals a block on a development chain which sets the balance of `address` to `balance`.
+	fn dev_set_balance(&self, address: Address, balance: U256) -> Result<(), String>;
+
+	/// Discards every block of a development chain, leaving only its genesis.
+	fn dev_reset(&self) -> Result<(), String>;
+
+	/// Starts writing a point-in-time copy of the client database to the given (not yet existing) directory
+	/// in the background. Progress and failures are logged.
+	fn backup_database(&self, path: String) -> Result<(), String>;
+
 	/// Get raw block header data by block id.
 	fn block_header(&self, id: BlockID) -> Option<Bytes>;
 
@@ -114,15 +150,37 @@ pub trait BlockChainClient : Sync + Send {
 	/// Get a list of all accounts in the block `id`, if fat DB is in operation, otherwise `None`.
 	fn list_accounts(&self, id: BlockID) -> Option<Vec<Address>>;
 
+	/// Get a list of all storage keys of `account` in the block `id`, if fat DB is in operation, otherwise `None`.
+	fn list_storage(&self, id: BlockID, account: &Address) -> Option<Vec<H256>>;
+
+	/// Get canonical transactions sent from or to `address` (or creating a contract at it) in chain order,
+	/// skipping the first `offset` of them. Returns `None` if the address index is disabled.
+	fn address_transactions(&self, address: &Address, offset: u64, limit: u64) -> Option<Vec<LocalizedTransaction>>;
+
 	/// Get transaction with given hash.
 	fn transaction(&self, id: TransactionID) -> Option<LocalizedTransaction>;
 
 	/// Get uncle with given id.
 	fn uncle(&self, id: UncleID) -> Option<Bytes>;
 
+	/// Returns engine-specific interpretation of the seal of block with given id.
+	fn block_extra_info(&self, id: BlockID) -> Option<BTreeMap<String, String>>;
+
+	/// Returns rewards issued by the engine for block with given id, excluding transaction fees.
+	fn block_rewards(&self, id: BlockID) -> Option<BlockRewards>;
+
+	/// Returns engine-specific interpretation of the seal of uncle with given id.
+	fn uncle_extra_info(&self, id: UncleID) -> Option<BTreeMap<String, String>>;
+
 	/// Get transaction receipt with given hash.
 	fn transaction_receipt(&self, id: TransactionID) -> Option<LocalizedReceipt>;
 
+	/// Returns true if the block is known but its body and receipts were pruned.
+	fn is_pruned(&self, id: BlockID) -> bool;
+
+	/// Returns true if the transaction is known but the body of its block was pruned.
+	fn is_transaction_pruned(&self, id: TransactionID) -> bool;
+
 	/// Get a tree route between `from` and `to`.
 	/// See `BlockChain::tree_route`.
 	fn tree_route(&self, from: &H256, to: &H256) -> Option<TreeRoute>;
@@ -142,6 +200,9 @@ pub trait BlockChainClient : Sync + Send {
 	/// Get block queue information.
 	fn queue_info(&self) -> BlockQueueInfo;
 
+	/// Get memory used by the caches and their limits.
+	fn memory_usage(&self) -> ClientMemoryUsage;
+
 	/// Clear block queue and abort all import activity.
 	fn clear_queue(&self);
 
@@ -151,21 +212,44 @@ pub trait BlockChainClient : Sync + Send {
 	/// Get the registrar address, if it exists.
 	fn additional_params(&self) -> BTreeMap<String, String>;
 
+	/// Get consensus forks supported by the engine with their transition blocks (`None` if not scheduled).
+	fn forks(&self) -> BTreeMap<String, Option<u64>>;
+
 	/// Get the best block header.
 	fn best_block_header(&self) -> Bytes;
 
 	/// Returns numbers of blocks containing given bloom.
 	fn blocks_with_bloom(&self, bloom: &H2048, from_block: BlockID, to_block: BlockID) -> Option<Vec<BlockNumber>>;
 
+	/// Returns numbers of blocks which may contain logs matching given filter, in ascending order.
+	/// Only block blooms are checked, so some of the blocks may not contain any matching logs.
+	fn blocks_with_filter(&self, filter: &Filter) -> Vec<BlockNumber> {
+		filter.bloom_possibilities().iter()
+			.filter_map(|bloom| self.blocks_with_bloom(bloom, filter.from_block.clone(), filter.to_block.clone()))
+			.flat_map(|m| m)
+			// remove duplicate elements
+			.collect::<BTreeSet<BlockNumber>>()
+			.into_iter()
+			.collect()
+	}
+
 	/// Returns logs matching given filter.
 	fn logs(&self, filter: Filter) -> Vec<LocalizedLogEntry>;
 
 	/// Makes a non-persistent transaction call.
 	fn call(&self, t: &SignedTransaction, block: BlockID, analytics: CallAnalytics) -> Result<Executed, CallError>;
 
+	/// Makes several non-persistent transaction calls in sequence on a single state,
+	/// each one seeing the effects of the previous ones.
+	fn call_many(&self, transactions: &[SignedTransaction], block: BlockID, analytics: CallAnalytics) -> Result<Vec<Executed>, CallError>;
+
 	/// Replays a given transaction for inspection.
 	fn replay(&self, t: TransactionID, analytics: CallAnalytics) -> Result<Executed, CallError>;
 
+	/// Returns changes of the account `address` between states of blocks `from` and `to`
+	/// or `None` if the account did not change.
+	fn account_diff(&self, address: &Address, from: BlockID, to: BlockID) -> Result<Option<AccountDiff>, CallError>;
+
 	/// Returns traces matching given filter.
 	fn filter_traces(&self, filter: TraceFilter) -> Option<Vec<LocalizedTrace>>;
 
@@ -184,9 +268,12 @@ pub trait BlockChainClient : Sync + Send {
 	/// Queue transactions for importing.
 	fn queue_transactions(&self, transactions: Vec<Bytes>);
 
-	/// list all transactions
+	/// list all transactions which can be propagated to peers
 	fn pending_transactions(&self) -> Vec<SignedTransaction>;
 
+	/// Get transaction with given hash from the queue if it can be propagated to peers.
+	fn queued_transaction(&self, hash: &H256) -> Option<SignedTransaction>;
+
 	/// Get the gas price distribution.
 	fn gas_price_statistics(&self, sample_size: usize, distribution_size: usize) -> Result<Vec<U256>, ()> {
 		let mut h = self.chain_info().best_block_hash;
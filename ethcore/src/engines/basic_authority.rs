@@ -75,10 +75,22 @@ impl Engine for BasicAuthority {
 	fn builtins(&self) -> &BTreeMap<Address, Builtin> { &self.builtins }
 
 	/// Additional engine-specific information for the user/developer concerning `header`.
-	fn extra_info(&self, _header: &Header) -> HashMap<String, String> { hash_map!["signature".to_owned() => "TODO".to_owned()] }
+	fn extra_info(&self, header: &Header) -> HashMap<String, String> {
+		use rlp::{UntrustedRlp, View};
+
+		let sig = match header.seal().get(0).map(|s| UntrustedRlp::new(s).as_val::<H520>()) {
+			Some(Ok(sig)) => sig,
+			_ => return HashMap::new(),
+		};
+		let mut info = hash_map!["signature".to_owned() => format!("0x{}", sig.hex())];
+		if let Ok(public) = recover(&sig.into(), &header.bare_hash()) {
+			info.insert("signer".to_owned(), format!("0x{}", public_to_address(&public).hex()));
+		}
+		info
+	}
 
-	fn schedule(&self, _env_info: &EnvInfo) -> Schedule {
-		Schedule::new_homestead()
+	fn schedule(&self, env_info: &EnvInfo) -> Schedule {
+		self.params.update_schedule(env_info.number, Schedule::new_homestead())
 	}
 
 	fn populate_from_parent(&self, header: &mut Header, parent: &Header, gas_floor_target: U256, _gas_ceil_target: U256) {
@@ -112,7 +124,7 @@ impl Engine for BasicAuthority {
 			let header = block.header();
 			let message = header.bare_hash();
 			// account should be pernamently unlocked, otherwise sealing will fail
-			if let Ok(signature) = ap.sign(*block.header().author(), message) {
+			if let Ok(signature) = ap.sign_as_engine(*block.header().author(), message) {
 				return Some(vec![::rlp::encode(&(&*signature as &[u8])).to_vec()]);
 			} else {
 				trace!(target: "basicauthority", "generate_seal: FAIL: accounts secret key unavailable");
@@ -243,6 +255,23 @@ mod tests {
 		assert!(verify_result.is_err());
 	}
 
+	#[test]
+	fn extra_info_contains_signer() {
+		let tap = AccountProvider::transient_provider();
+		let addr = tap.insert_account("".sha3(), "").unwrap();
+		tap.unlock_account_permanently(addr, "".into()).unwrap();
+
+		let engine = new_test_authority().engine;
+		let mut header: Header = Header::default();
+		let signature = tap.sign(addr, header.bare_hash()).unwrap();
+		header.set_seal(vec![::rlp::encode(&(&*signature as &[u8])).to_vec()]);
+
+		let info = engine.extra_info(&header);
+		assert_eq!(info.get("signer"), Some(&format!("0x{}", addr.hex())));
+		assert!(info.contains_key("signature"));
+		assert!(engine.extra_info(&Header::default()).is_empty());
+	}
+
 	#[test]
 	fn can_generate_seal() {
 		let tap = AccountProvider::transient_provider();
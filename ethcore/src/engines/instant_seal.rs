@@ -54,8 +54,8 @@ impl Engine for InstantSeal {
 		&self.builtins
 	}
 
-	fn schedule(&self, _env_info: &EnvInfo) -> Schedule {
-		Schedule::new_homestead()
+	fn schedule(&self, env_info: &EnvInfo) -> Schedule {
+		self.params.update_schedule(env_info.number, Schedule::new_homestead())
 	}
 
 	fn is_sealer(&self, _author: &Address) -> Option<bool> { Some(true) }
@@ -29,6 +29,7 @@ use account_provider::AccountProvider;
 use block::ExecutedBlock;
 use spec::CommonParams;
 use evm::Schedule;
+use types::block_rewards::BlockRewards;
 
 /// A consensus mechanism for the chain. Generally either proof-of-work or proof-of-stake-based.
 /// Provides hooks into each of the major parts of block import.
@@ -47,6 +48,10 @@ pub trait Engine : Sync + Send {
 	/// Additional information.
 	fn additional_params(&self) -> HashMap<String, String> { HashMap::new() }
 
+	/// Consensus forks implemented by this engine with their transition blocks on this chain.
+	/// Transition is `None` if the fork is not scheduled.
+	fn forks(&self) -> Vec<(&'static str, Option<BlockNumber>)> { Vec::new() }
+
 	/// Get the general parameters of the chain.
 	fn params(&self) -> &CommonParams;
 
@@ -71,6 +76,9 @@ pub trait Engine : Sync + Send {
 	/// Block transformation functions, after the transactions.
 	fn on_close_block(&self, _block: &mut ExecutedBlock) {}
 
+	/// Rewards issued for the block with given header and uncles, excluding transaction fees.
+	fn block_rewards(&self, _header: &Header, _uncles: &[Header]) -> BlockRewards { BlockRewards::default() }
+
 	/// If Some(true) this author is able to generate seals, generate_seal has to be implemented.
 	/// None indicates that this Engine never seals internally regardless of author (e.g. PoW).
 	fn is_sealer(&self, _author: &Address) -> Option<bool> { None }
@@ -119,8 +127,8 @@ pub trait Engine : Sync + Send {
 
 	// TODO: builtin contract routing - to do this properly, it will require removing the built-in configuration-reading logic
 	// from Spec into here and removing the Spec::builtins field.
-	/// Determine whether a particular address is a builtin contract.
-	fn is_builtin(&self, a: &Address) -> bool { self.builtins().contains_key(a) }
+	/// Determine whether a particular address is a builtin contract activated at block `at`.
+	fn is_builtin(&self, a: &Address, at: BlockNumber) -> bool { self.builtins().get(a).map_or(false, |b| b.is_active(at)) }
 	/// Determine the code execution cost of the builtin contract with address `a`.
 	/// Panics if `is_builtin(a)` is not true.
 	fn cost_of_builtin(&self, a: &Address, input: &[u8]) -> U256 { self.builtins().get(a).unwrap().cost(input.len()) }
@@ -51,7 +51,7 @@ impl Engine for NullEngine {
 		&self.builtins
 	}
 
-	fn schedule(&self, _env_info: &EnvInfo) -> Schedule {
-		Schedule::new_homestead()
+	fn schedule(&self, env_info: &EnvInfo) -> Schedule {
+		self.params.update_schedule(env_info.number, Schedule::new_homestead())
 	}
 }
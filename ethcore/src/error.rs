@@ -36,8 +36,13 @@ pub enum TransactionError {
 	/// Transaction is not valid anymore (state already has higher nonce)
 	Old,
 	/// Transaction has too low fee
-	/// (there is already a transaction with the same sender-nonce but higher gas price)
-	TooCheapToReplace,
+	/// (there is already a transaction with the same sender-nonce and not sufficiently lower gas price)
+	TooCheapToReplace {
+		/// Minimal gas price required to replace the transaction
+		minimal: U256,
+		/// Transaction gas price
+		got: U256,
+	},
 	/// Transaction was not imported to the queue because limit has been reached.
 	LimitReached,
 	/// Transaction's gas price is below threshold.
@@ -63,6 +68,14 @@ pub enum TransactionError {
 	},
 	/// Transaction's gas limit (aka gas) is invalid.
 	InvalidGasLimit(OutOfBounds<U256>),
+	/// Sender is temporarily banned.
+	SenderBanned,
+	/// Recipient is temporarily banned.
+	RecipientBanned,
+	/// Contract creation code is temporarily banned.
+	CodeBanned,
+	/// Transaction is not permitted by the chain's permissioning rules.
+	NotPermitted,
 }
 
 impl fmt::Display for TransactionError {
@@ -71,7 +84,8 @@ impl fmt::Display for TransactionError {
 		let msg = match *self {
 			AlreadyImported => "Already imported".into(),
 			Old => "No longer valid".into(),
-			TooCheapToReplace => "Gas price too low to replace".into(),
+			TooCheapToReplace { minimal, got } =>
+				format!("Gas price too low to replace. Min={}, Given={}", minimal, got),
 			LimitReached => "Transaction limit reached".into(),
 			InsufficientGasPrice { minimal, got } =>
 				format!("Insufficient gas price. Min={}, Given={}", minimal, got),
@@ -81,6 +95,10 @@ impl fmt::Display for TransactionError {
 			GasLimitExceeded { limit, got } =>
 				format!("Gas limit exceeded. Limit={}, Given={}", limit, got),
 			InvalidGasLimit(ref err) => format!("Invalid gas limit. {}", err),
+			SenderBanned => "Sender is temporarily banned.".into(),
+			RecipientBanned => "Recipient is temporarily banned.".into(),
+			CodeBanned => "Contract code is temporarily banned.".into(),
+			NotPermitted => "Transaction is not permitted.".into(),
 		};
 
 		f.write_fmt(format_args!("Transaction error ({})", msg))
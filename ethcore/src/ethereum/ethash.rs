@@ -20,6 +20,7 @@ use block::*;
 use spec::CommonParams;
 use engines::Engine;
 use evm::Schedule;
+use types::block_rewards::BlockRewards;
 use ethjson;
 use rlp::{self, UntrustedRlp, View};
 
@@ -54,6 +55,8 @@ pub struct EthashParams {
 	pub difficulty_hardfork_bound_divisor: U256,
 	/// Block on which there is no additional difficulty from the exponential bomb.
 	pub bomb_defuse_transition: u64,
+	/// EIP-150 (gas cost changes for IO-heavy operations) transition block.
+	pub eip150_transition: u64,
 }
 
 impl From<ethjson::spec::EthashParams> for EthashParams {
@@ -66,13 +69,14 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 			duration_limit: p.duration_limit.into(),
 			block_reward: p.block_reward.into(),
 			registrar: p.registrar.map_or_else(Address::new, Into::into),
-			frontier_compatibility_mode_limit: p.frontier_compatibility_mode_limit.map_or(0, Into::into),
+			frontier_compatibility_mode_limit: p.homestead_transition.or(p.frontier_compatibility_mode_limit).map_or(0, Into::into),
 			dao_hardfork_transition: p.dao_hardfork_transition.map_or(0x7fffffffffffffff, Into::into),
 			dao_hardfork_beneficiary: p.dao_hardfork_beneficiary.map_or_else(Address::new, Into::into),
 			dao_hardfork_accounts: p.dao_hardfork_accounts.unwrap_or_else(Vec::new).into_iter().map(Into::into).collect(),
 			difficulty_hardfork_transition: p.difficulty_hardfork_transition.map_or(0x7fffffffffffffff, Into::into),
 			difficulty_hardfork_bound_divisor: p.difficulty_hardfork_bound_divisor.map_or(p.difficulty_bound_divisor.into(), Into::into),
 			bomb_defuse_transition: p.bomb_defuse_transition.map_or(0x7fffffffffffffff, Into::into),
+			eip150_transition: p.eip150_transition.map_or(0x7fffffffffffffff, Into::into),
 		}
 	}
 }
@@ -107,6 +111,17 @@ impl Engine for Ethash {
 	fn params(&self) -> &CommonParams { &self.params }
 	fn additional_params(&self) -> HashMap<String, String> { hash_map!["registrar".to_owned() => self.ethash_params.registrar.hex()] }
 
+	fn forks(&self) -> Vec<(&'static str, Option<BlockNumber>)> {
+		let scheduled = |block: u64| if block == 0x7fffffffffffffff { None } else { Some(block) };
+		vec![
+			("homestead", Some(self.ethash_params.frontier_compatibility_mode_limit)),
+			("daoHardfork", scheduled(self.ethash_params.dao_hardfork_transition)),
+			("difficultyHardfork", scheduled(self.ethash_params.difficulty_hardfork_transition)),
+			("bombDefuse", scheduled(self.ethash_params.bomb_defuse_transition)),
+			("eip150", scheduled(self.ethash_params.eip150_transition)),
+		]
+	}
+
 	fn builtins(&self) -> &BTreeMap<Address, Builtin> {
 		&self.builtins
 	}
@@ -119,11 +134,14 @@ impl Engine for Ethash {
 	fn schedule(&self, env_info: &EnvInfo) -> Schedule {
 		trace!(target: "client", "Creating schedule. fCML={}", self.ethash_params.frontier_compatibility_mode_limit);
 
-		if env_info.number < self.ethash_params.frontier_compatibility_mode_limit {
+		let schedule = if env_info.number < self.ethash_params.frontier_compatibility_mode_limit {
 			Schedule::new_frontier()
-		} else {
+		} else if env_info.number < self.ethash_params.eip150_transition {
 			Schedule::new_homestead()
-		}
+		} else {
+			Schedule::new_post_eip150()
+		};
+		self.params.update_schedule(env_info.number, schedule)
 	}
 
 	fn populate_from_parent(&self, header: &mut Header, parent: &Header, gas_floor_target: U256, gas_ceil_target: U256) {
@@ -168,16 +186,15 @@ impl Engine for Ethash {
 	/// Apply the block reward on finalisation of the block.
 	/// This assumes that all uncles are valid uncles (i.e. of at least one generation before the current).
 	fn on_close_block(&self, block: &mut ExecutedBlock) {
-		let reward = self.ethash_params.block_reward;
 		let fields = block.fields_mut();
+		let rewards = self.block_rewards(&*fields.header, fields.uncles);
 
 		// Bestow block reward
-		fields.state.add_balance(fields.header.author(), &(reward + reward / U256::from(32) * U256::from(fields.uncles.len())));
+		fields.state.add_balance(fields.header.author(), &rewards.author);
 
 		// Bestow uncle rewards
-		let current_number = fields.header.number();
-		for u in fields.uncles.iter() {
-			fields.state.add_balance(u.author(), &(reward * U256::from(8 + u.number() - current_number) / U256::from(8)));
+		for (u, reward) in fields.uncles.iter().zip(rewards.uncles.iter()) {
+			fields.state.add_balance(u.author(), reward);
 		}
 
 		// Commit state so that we can actually figure out the state root.
@@ -186,6 +203,16 @@ impl Engine for Ethash {
 		}		
 	}
 
+	fn block_rewards(&self, header: &Header, uncles: &[Header]) -> BlockRewards {
+		let reward = self.ethash_params.block_reward;
+		BlockRewards {
+			author: reward + reward / U256::from(32) * U256::from(uncles.len()),
+			uncles: uncles.iter()
+				.map(|u| reward * U256::from(8 + u.number() - header.number()) / U256::from(8))
+				.collect(),
+		}
+	}
+
 	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> result::Result<(), Error> {
 		// check the seal fields.
 		if header.seal().len() != self.seal_fields() {
@@ -370,9 +397,30 @@ mod tests {
 	use block::*;
 	use tests::helpers::*;
 	use super::super::new_morden;
-	use super::Ethash;
+	use super::{Ethash, EthashParams};
+	use engines::Engine;
 	use rlp;
 
+	fn get_default_ethash_params() -> EthashParams {
+		EthashParams {
+			gas_limit_bound_divisor: U256::from(0x0400),
+			minimum_difficulty: U256::from(0x020000),
+			difficulty_bound_divisor: U256::from(0x0800),
+			difficulty_increment_divisor: 10,
+			duration_limit: 13,
+			block_reward: U256::zero(),
+			registrar: Address::new(),
+			frontier_compatibility_mode_limit: 0,
+			dao_hardfork_transition: 0x7fffffffffffffff,
+			dao_hardfork_beneficiary: Address::new(),
+			dao_hardfork_accounts: vec![],
+			difficulty_hardfork_transition: 0x7fffffffffffffff,
+			difficulty_hardfork_bound_divisor: U256::from(0x0800),
+			bomb_defuse_transition: 0x7fffffffffffffff,
+			eip150_transition: 0x7fffffffffffffff,
+		}
+	}
+
 	#[test]
 	fn on_close_block() {
 		let spec = new_morden();
@@ -592,5 +640,22 @@ mod tests {
 		assert_eq!(Ethash::difficulty_to_boundary(&U256::from(32)), H256::from_str("0800000000000000000000000000000000000000000000000000000000000000").unwrap());
 	}
 
+	#[test]
+	fn should_switch_schedule_at_fork_transitions() {
+		let spec = new_morden();
+		let mut params = get_default_ethash_params();
+		params.frontier_compatibility_mode_limit = 10;
+		params.eip150_transition = 20;
+		let ethash = Ethash::new(spec.params, params, BTreeMap::new());
+		let env_info = |number| EnvInfo { number: number, ..Default::default() };
+
+		assert!(!ethash.schedule(&env_info(5)).have_delegate_call);
+		assert_eq!(ethash.schedule(&env_info(15)).sload_gas, 50);
+		assert_eq!(ethash.schedule(&env_info(25)).sload_gas, 200);
+		assert_eq!(ethash.schedule(&env_info(25)).sub_gas_cap_divisor, Some(64));
+		assert!(ethash.forks().contains(&("eip150", Some(20))));
+		assert!(ethash.forks().contains(&("daoHardfork", None)));
+	}
+
 	// TODO: difficulty test
 }
@@ -121,6 +121,7 @@ fn result(r: evm::Result<evm::GasLeft>) -> U256 {
 	match r {
 		Ok(evm::GasLeft::Known(v)) => v,
 		Ok(evm::GasLeft::NeedsReturn(v, _)) => v,
+		Ok(evm::GasLeft::NeedsRevert(v, _)) => v,
 		_ => U256::zero(),
 	}
 }
@@ -59,6 +59,12 @@ pub enum Error {
 		/// What was the stack limit
 		limit: usize
 	},
+	/// `Reverted` is returned when execution hit a `REVERT` instruction.
+	/// State changes are reverted, but the remaining gas is given back.
+	Reverted {
+		/// Gas left at the time of reverting.
+		gas_left: U256,
+	},
 	/// Returned on evm internal error. Should never be ignored during development.
 	/// Likely to cause consensus issues.
 	#[allow(dead_code)] // created only by jit
@@ -74,6 +80,7 @@ impl fmt::Display for Error {
 			BadInstruction { .. } => "Bad instruction",
 			StackUnderflow { .. } => "Stack underflow",
 			OutOfStack { .. } => "Out of stack",
+			Reverted { .. } => "Reverted",
 			Internal => "Internal error",
 		};
 		message.fmt(f)
@@ -91,6 +98,8 @@ pub enum GasLeft<'a> {
 	Known(U256),
 	/// Return instruction must be processed.
 	NeedsReturn(U256, &'a [u8]),
+	/// Revert instruction must be processed.
+	NeedsRevert(U256, &'a [u8]),
 }
 
 /// Types that can be "finalized" using an EVM.
@@ -107,6 +116,7 @@ impl<'a> Finalize for Result<GasLeft<'a>> {
 		match self {
 			Ok(GasLeft::Known(gas)) => Ok(gas),
 			Ok(GasLeft::NeedsReturn(gas, ret_code)) => ext.ret(&gas, ret_code),
+			Ok(GasLeft::NeedsRevert(gas, data)) => ext.revert(&gas, data),
 			Err(err) => Err(err),
 		}
 	}
@@ -28,7 +28,10 @@ pub enum ContractCreateResult {
 	Created(Address, U256),
 	/// Returned when contract creation failed.
 	/// VM doesn't have to know the reason.
-	Failed
+	Failed,
+	/// Returned when the init code executed `REVERT`.
+	/// Contains gas left.
+	Reverted(U256),
 }
 
 /// Result of externalities call function.
@@ -38,7 +41,10 @@ pub enum MessageCallResult {
 	Success(U256),
 	/// Returned when message call failed.
 	/// VM doesn't have to know the reason.
-	Failed
+	Failed,
+	/// Returned when the callee executed `REVERT`.
+	/// Contains gas left.
+	Reverted(U256),
 }
 
 /// Externalities interface for EVMs
@@ -93,6 +99,10 @@ pub trait Ext {
 	/// Returns gas_left if cost of returning the data is not too high.
 	fn ret(self, gas: &U256, data: &[u8]) -> evm::Result<U256> where Self: Sized;
 
+	/// Should be called when transaction calls `REVERT` opcode.
+	/// Stores the data as call output and returns `evm::Error::Reverted`.
+	fn revert(self, gas: &U256, data: &[u8]) -> evm::Result<U256> where Self: Sized;
+
 	/// Should be called when contract commits suicide.
 	/// Address to which funds should be refunded.
 	fn suicide(&mut self, refund_address: &Address);
@@ -18,6 +18,7 @@
 //!
 //! TODO: consider spliting it into two separate files.
 use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
 use evm::Evm;
 use util::{U256, Uint};
@@ -55,6 +56,18 @@ impl Default for VMType {
 	}
 }
 
+impl FromStr for VMType {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"interpreter" | "int" => Ok(VMType::Interpreter),
+			"jit" => VMType::jit().ok_or_else(|| "Parity is built without the JIT EVM.".into()),
+			other => Err(format!("Invalid VM type: {}", other)),
+		}
+	}
+}
+
 impl VMType {
 	/// Return all possible VMs (JIT, Interpreter)
 	#[cfg(feature = "jit")]
@@ -156,6 +169,14 @@ fn test_create_vm() {
 	let _vm = Factory::default().create(U256::zero());
 }
 
+#[test]
+fn test_vm_type_from_str() {
+	assert_eq!("interpreter".parse::<VMType>(), Ok(VMType::Interpreter));
+	assert_eq!("int".parse::<VMType>(), Ok(VMType::Interpreter));
+	assert_eq!("jit".parse::<VMType>().ok(), VMType::jit());
+	assert!("foo".parse::<VMType>().is_err());
+}
+
 /// Create tests by injecting different VM factories
 #[macro_export]
 macro_rules! evm_test(
@@ -32,7 +32,9 @@ macro_rules! overflowing {
 enum InstructionCost<Cost: CostType> {
 	Gas(Cost),
 	GasMem(Cost, Cost),
-	GasMemCopy(Cost, Cost, Cost)
+	GasMemCopy(Cost, Cost, Cost),
+	/// Base gas, memory size and gas requested for a sub-call.
+	GasMemProvide(Cost, Cost, U256),
 }
 
 pub struct Gasometer<Gas: CostType> {
@@ -56,6 +58,23 @@ impl<Gas: CostType> Gasometer<Gas> {
 		}
 	}
 
+	/// Returns gas provided to a sub-call requesting `requested` gas, when `needed` gas is paid for the call itself.
+	fn gas_provided(&self, schedule: &evm::Schedule, needed: Gas, requested: U256) -> evm::Result<Gas> {
+		match schedule.sub_gas_cap_divisor {
+			Some(cap_divisor) if self.current_gas >= needed => {
+				let gas_remaining = self.current_gas - needed;
+				let max_gas_provided = gas_remaining - gas_remaining / Gas::from(cap_divisor);
+				match Gas::from_u256(requested) {
+					Ok(requested) => Ok(cmp::min(requested, max_gas_provided)),
+					Err(_) => Ok(max_gas_provided),
+				}
+			},
+			_ => Gas::from_u256(requested),
+		}
+	}
+
+	/// Returns gas cost of the instruction including gas provided to sub-calls, new memory gas,
+	/// new memory size and gas provided to the sub-call if any.
 	#[cfg_attr(feature="dev", allow(cyclomatic_complexity))]
 	pub fn get_gas_cost_mem(
 		&mut self,
@@ -64,7 +83,7 @@ impl<Gas: CostType> Gasometer<Gas> {
 		info: &InstructionInfo,
 		stack: &Stack<U256>,
 		current_mem_size: usize,
-	) -> evm::Result<(Gas, Gas, usize)> {
+	) -> evm::Result<(Gas, Gas, usize, Option<Gas>)> {
 		let schedule = ext.schedule();
 		let tier = instructions::get_tier_idx(info.tier);
 		let default_gas = Gas::from(schedule.tier_step_gas[tier]);
@@ -90,13 +109,27 @@ impl<Gas: CostType> Gasometer<Gas> {
 			instructions::SLOAD => {
 				InstructionCost::Gas(Gas::from(schedule.sload_gas))
 			},
+			instructions::BALANCE => {
+				InstructionCost::Gas(Gas::from(schedule.balance_gas))
+			},
+			instructions::EXTCODESIZE => {
+				InstructionCost::Gas(Gas::from(schedule.extcodesize_gas))
+			},
+			instructions::SUICIDE => {
+				let mut gas = Gas::from(schedule.suicide_gas);
+				let address = u256_to_address(stack.peek(0));
+				if schedule.suicide_to_new_account_cost > 0 && !ext.exists(&address) {
+					gas = overflowing!(gas.overflow_add(Gas::from(schedule.suicide_to_new_account_cost)));
+				}
+				InstructionCost::Gas(gas)
+			},
 			instructions::MSTORE | instructions::MLOAD => {
 				InstructionCost::GasMem(default_gas, try!(mem_needed_const(stack.peek(0), 32)))
 			},
 			instructions::MSTORE8 => {
 				InstructionCost::GasMem(default_gas, try!(mem_needed_const(stack.peek(0), 1)))
 			},
-			instructions::RETURN => {
+			instructions::RETURN | instructions::REVERT => {
 				InstructionCost::GasMem(default_gas, try!(mem_needed(stack.peek(0), stack.peek(1))))
 			},
 			instructions::SHA3 => {
@@ -109,7 +142,7 @@ impl<Gas: CostType> Gasometer<Gas> {
 				InstructionCost::GasMemCopy(default_gas, try!(mem_needed(stack.peek(0), stack.peek(2))), try!(Gas::from_u256(*stack.peek(2))))
 			},
 			instructions::EXTCODECOPY => {
-				InstructionCost::GasMemCopy(default_gas, try!(mem_needed(stack.peek(1), stack.peek(3))), try!(Gas::from_u256(*stack.peek(3))))
+				InstructionCost::GasMemCopy(Gas::from(schedule.extcodecopy_base_gas), try!(mem_needed(stack.peek(1), stack.peek(3))), try!(Gas::from_u256(*stack.peek(3))))
 			},
 			instructions::LOG0...instructions::LOG4 => {
 				let no_of_topics = instructions::get_log_topics(instruction);
@@ -120,7 +153,7 @@ impl<Gas: CostType> Gasometer<Gas> {
 				InstructionCost::GasMem(gas, try!(mem_needed(stack.peek(0), stack.peek(1))))
 			},
 			instructions::CALL | instructions::CALLCODE => {
-				let mut gas  = overflowing!(add_gas_usize(try!(Gas::from_u256(*stack.peek(0))), schedule.call_gas));
+				let mut gas = Gas::from(schedule.call_gas);
 				let mem = cmp::max(
 					try!(mem_needed(stack.peek(5), stack.peek(6))),
 					try!(mem_needed(stack.peek(3), stack.peek(4)))
@@ -136,15 +169,15 @@ impl<Gas: CostType> Gasometer<Gas> {
 					gas = overflowing!(gas.overflow_add(Gas::from(schedule.call_value_transfer_gas)));
 				};
 
-				InstructionCost::GasMem(gas,mem)
+				InstructionCost::GasMemProvide(gas, mem, *stack.peek(0))
 			},
 			instructions::DELEGATECALL => {
-				let gas = overflowing!(add_gas_usize(try!(Gas::from_u256(*stack.peek(0))), schedule.call_gas));
+				let gas = Gas::from(schedule.call_gas);
 				let mem = cmp::max(
 					try!(mem_needed(stack.peek(4), stack.peek(5))),
 					try!(mem_needed(stack.peek(2), stack.peek(3)))
 				);
-				InstructionCost::GasMem(gas, mem)
+				InstructionCost::GasMemProvide(gas, mem, *stack.peek(0))
 			},
 			instructions::CREATE => {
 				let gas = Gas::from(schedule.create_gas);
@@ -162,12 +195,19 @@ impl<Gas: CostType> Gasometer<Gas> {
 
 		match cost {
 			InstructionCost::Gas(gas) => {
-				Ok((gas, self.current_mem_gas, 0))
+				Ok((gas, self.current_mem_gas, 0, None))
 			},
 			InstructionCost::GasMem(gas, mem_size) => {
 				let (mem_gas_cost, new_mem_gas, new_mem_size) = try!(self.mem_gas_cost(schedule, current_mem_size, &mem_size));
 				let gas = overflowing!(gas.overflow_add(mem_gas_cost));
-				Ok((gas, new_mem_gas, new_mem_size))
+				Ok((gas, new_mem_gas, new_mem_size, None))
+			},
+			InstructionCost::GasMemProvide(gas, mem_size, requested) => {
+				let (mem_gas_cost, new_mem_gas, new_mem_size) = try!(self.mem_gas_cost(schedule, current_mem_size, &mem_size));
+				let gas = overflowing!(gas.overflow_add(mem_gas_cost));
+				let provided = try!(self.gas_provided(schedule, gas, requested));
+				let gas = overflowing!(gas.overflow_add(provided));
+				Ok((gas, new_mem_gas, new_mem_size, Some(provided)))
 			},
 			InstructionCost::GasMemCopy(gas, mem_size, copy) => {
 				let (mem_gas_cost, new_mem_gas, new_mem_size) = try!(self.mem_gas_cost(schedule, current_mem_size, &mem_size));
@@ -175,7 +215,7 @@ impl<Gas: CostType> Gasometer<Gas> {
 				let copy_gas = Gas::from(schedule.copy_gas) * copy;
 				let gas = overflowing!(gas.overflow_add(copy_gas));
 				let gas = overflowing!(gas.overflow_add(mem_gas_cost));
-				Ok((gas, new_mem_gas, new_mem_size))
+				Ok((gas, new_mem_gas, new_mem_size, None))
 			}
 		}
 	}
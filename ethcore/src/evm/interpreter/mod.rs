@@ -81,12 +81,13 @@ impl<'a> CodeReader<'a> {
 
 enum InstructionResult<Gas> {
 	Ok,
-	UseAllGas,
 	GasLeft(Gas),
 	UnusedGas(Gas),
 	JumpToPosition(U256),
 	// gas left, init_orf, init_size
 	StopExecutionNeedsReturn(Gas, U256, U256),
+	// gas left, output offset, output size
+	StopExecutionNeedsRevert(Gas, U256, U256),
 	StopExecution,
 }
 
@@ -120,7 +121,7 @@ impl<Cost: CostType> evm::Evm for Interpreter<Cost> {
 			try!(self.verify_instruction(ext, instruction, info, &stack));
 
 			// Calculate gas cost
-			let (gas_cost, mem_gas, mem_size) = try!(gasometer.get_gas_cost_mem(ext, instruction, info, &stack, self.mem.size()));
+			let (gas_cost, mem_gas, mem_size, provided) = try!(gasometer.get_gas_cost_mem(ext, instruction, info, &stack, self.mem.size()));
 			// TODO: make compile-time removable if too much of a performance hit.
 			let trace_executed = ext.trace_prepare_execute(reader.position - 1, instruction, &gas_cost.as_u256());
 
@@ -138,7 +139,7 @@ impl<Cost: CostType> evm::Evm for Interpreter<Cost> {
 
 			// Execute instruction
 			let result = try!(self.exec_instruction(
-				gasometer.current_gas, &params, ext, instruction, &mut reader, &mut stack
+				gasometer.current_gas, &params, ext, instruction, &mut reader, &mut stack, provided
 			));
 
 			evm_debug!({ informant.after_instruction(instruction) });
@@ -153,9 +154,6 @@ impl<Cost: CostType> evm::Evm for Interpreter<Cost> {
 				InstructionResult::UnusedGas(gas) => {
 					gasometer.current_gas = gasometer.current_gas + gas;
 				},
-				InstructionResult::UseAllGas => {
-					gasometer.current_gas = Cost::from(0);
-				},
 				InstructionResult::GasLeft(gas_left) => {
 					gasometer.current_gas = gas_left;
 				},
@@ -167,6 +165,10 @@ impl<Cost: CostType> evm::Evm for Interpreter<Cost> {
 					informant.done();
 					return Ok(GasLeft::NeedsReturn(gas.as_u256(), self.mem.read_slice(off, size)));
 				},
+				InstructionResult::StopExecutionNeedsRevert(gas, off, size) => {
+					informant.done();
+					return Ok(GasLeft::NeedsRevert(gas.as_u256(), self.mem.read_slice(off, size)));
+				},
 				InstructionResult::StopExecution => break,
 			}
 		}
@@ -194,6 +196,12 @@ impl<Cost: CostType> Interpreter<Cost> {
 			});
 		}
 
+		if !schedule.have_revert && instruction == instructions::REVERT {
+			return Err(evm::Error::BadInstruction {
+				instruction: instruction
+			});
+		}
+
 		if info.tier == instructions::GasPriceTier::Invalid {
 			return Err(evm::Error::BadInstruction {
 				instruction: instruction
@@ -250,7 +258,8 @@ impl<Cost: CostType> Interpreter<Cost> {
 		ext: &mut evm::Ext,
 		instruction: Instruction,
 		code: &mut CodeReader,
-		stack: &mut Stack<U256>
+		stack: &mut Stack<U256>,
+		provided: Option<Cost>
 	) -> evm::Result<InstructionResult<Cost>> {
 		match instruction {
 			instructions::JUMP => {
@@ -284,22 +293,37 @@ impl<Cost: CostType> Interpreter<Cost> {
 					return Ok(InstructionResult::Ok);
 				}
 
-				let create_result = ext.create(&gas.as_u256(), &endowment, contract_code);
+				// all but a part of remaining gas may be given to the created contract
+				let create_gas = match ext.schedule().sub_gas_cap_divisor {
+					Some(cap_divisor) => gas - gas / Cost::from(cap_divisor),
+					None => gas,
+				};
+				let kept_gas = gas - create_gas;
+
+				let create_result = ext.create(&create_gas.as_u256(), &endowment, contract_code);
 				return match create_result {
 					ContractCreateResult::Created(address, gas_left) => {
 						stack.push(address_to_u256(address));
-						Ok(InstructionResult::GasLeft(Cost::from_u256(gas_left).expect("Gas left cannot be greater.")))
+						let gas_left = Cost::from_u256(gas_left).expect("Gas left cannot be greater.");
+						Ok(InstructionResult::GasLeft(gas_left + kept_gas))
+					},
+					ContractCreateResult::Reverted(gas_left) => {
+						stack.push(U256::zero());
+						let gas_left = Cost::from_u256(gas_left).expect("Gas left cannot be greater.");
+						Ok(InstructionResult::GasLeft(gas_left + kept_gas))
 					},
 					ContractCreateResult::Failed => {
 						stack.push(U256::zero());
 						// TODO [todr] Should we just StopExecution here?
-						Ok(InstructionResult::UseAllGas)
+						Ok(InstructionResult::GasLeft(kept_gas))
 					}
 				};
 			},
 			instructions::CALL | instructions::CALLCODE | instructions::DELEGATECALL => {
 				assert!(ext.schedule().call_value_transfer_gas > ext.schedule().call_stipend, "overflow possible");
-				let call_gas = Cost::from_u256(stack.pop_back()).expect("Gas is already validated.");
+				// requested gas is capped by the gasometer
+				stack.pop_back();
+				let call_gas = provided.expect("`get_gas_cost_mem` always returns gas provided to calls; qed");
 				let code_address = stack.pop_back();
 				let code_address = u256_to_address(&code_address);
 
@@ -353,6 +377,10 @@ impl<Cost: CostType> Interpreter<Cost> {
 						stack.push(U256::one());
 						Ok(InstructionResult::UnusedGas(Cost::from_u256(gas_left).expect("Gas left cannot be greater then current one")))
 					},
+					MessageCallResult::Reverted(gas_left) => {
+						stack.push(U256::zero());
+						Ok(InstructionResult::UnusedGas(Cost::from_u256(gas_left).expect("Gas left cannot be greater then current one")))
+					},
 					MessageCallResult::Failed  => {
 						stack.push(U256::zero());
 						Ok(InstructionResult::Ok)
@@ -365,6 +393,12 @@ impl<Cost: CostType> Interpreter<Cost> {
 
 				return Ok(InstructionResult::StopExecutionNeedsReturn(gas, init_off, init_size))
 			},
+			instructions::REVERT => {
+				let off = stack.pop_back();
+				let size = stack.pop_back();
+
+				return Ok(InstructionResult::StopExecutionNeedsRevert(gas, off, size))
+			},
 			instructions::STOP => {
 				return Ok(InstructionResult::StopExecution);
 			},
@@ -179,7 +179,7 @@ impl<'a> evmjit::Ext for ExtAdapter<'a> {
 					*address = new_address.into_jit();
 					*io_gas = gas_left.low_u64();
 				},
-				evm::ContractCreateResult::Failed => unsafe {
+				evm::ContractCreateResult::Failed | evm::ContractCreateResult::Reverted(_) => unsafe {
 					*address = Address::new().into_jit();
 					*io_gas = 0;
 				}
@@ -264,7 +264,7 @@ impl<'a> evmjit::Ext for ExtAdapter<'a> {
 				*io_gas = (gas + gas_left).low_u64();
 				true
 			},
-			evm::MessageCallResult::Failed => unsafe {
+			evm::MessageCallResult::Failed | evm::MessageCallResult::Reverted(_) => unsafe {
 				*io_gas = gas.low_u64();
 				false
 			}
@@ -16,12 +16,16 @@
 
 //! Cost schedule and other parameterisations for the EVM.
 
+use ethjson;
+
 /// Definition of the cost schedule and other parameterisations for the EVM.
 pub struct Schedule {
 	/// Does it support exceptional failed code deposit
 	pub exceptional_failed_code_deposit: bool,
 	/// Does it have a delegate cal
 	pub have_delegate_call: bool,
+	/// Does it have a REVERT instruction
+	pub have_revert: bool,
 	/// VM stack limit
 	pub stack_limit: usize,
 	/// Max number of nested calls/creates
@@ -38,6 +42,12 @@ pub struct Schedule {
 	pub sha3_word_gas: usize,
 	/// Gas price for loading from storage
 	pub sload_gas: usize,
+	/// Gas price for `BALANCE` opcode
+	pub balance_gas: usize,
+	/// Gas price for `EXTCODESIZE` opcode
+	pub extcodesize_gas: usize,
+	/// Base gas price for `EXTCODECOPY` opcode
+	pub extcodecopy_base_gas: usize,
 	/// Gas price for setting new value to storage (`storage==0`, `new!=0`)
 	pub sstore_set_gas: usize,
 	/// Gas price for altering value in storage
@@ -64,6 +74,13 @@ pub struct Schedule {
 	pub call_new_account_gas: usize,
 	/// Refund for SUICIDE
 	pub suicide_refund_gas: usize,
+	/// Gas price for `SUICIDE` opcode
+	pub suicide_gas: usize,
+	/// Additional gas for `SUICIDE` sending funds to a non-existent account
+	pub suicide_to_new_account_cost: usize,
+	/// If Some(x): let limit = GAS * (x - 1) / x; let CALL's gas = min(requested, limit); let CREATE's gas = limit.
+	/// If None: let CALL's gas = requested; let CREATE's gas = GAS;
+	pub sub_gas_cap_divisor: Option<usize>,
 	/// Gas for used memory
 	pub memory_gas: usize,
 	/// Coefficient used to convert memory size to gas price for memory
@@ -93,10 +110,61 @@ impl Schedule {
 		Self::new(true, true, 53000)
 	}
 
+	/// Schedule for the post-EIP-150 era of the Ethereum main net.
+	pub fn new_post_eip150() -> Schedule {
+		let mut schedule = Self::new(true, true, 53000);
+		schedule.sload_gas = 200;
+		schedule.balance_gas = 400;
+		schedule.extcodesize_gas = 700;
+		schedule.extcodecopy_base_gas = 700;
+		schedule.call_gas = 700;
+		schedule.suicide_gas = 5000;
+		schedule.suicide_to_new_account_cost = 25000;
+		schedule.sub_gas_cap_divisor = Some(64);
+		schedule
+	}
+
+	/// Replace the costs given in the chain specification's gas schedule overrides.
+	pub fn with_overrides(self, overrides: &ethjson::spec::GasSchedule) -> Schedule {
+		let mut schedule = self;
+
+		macro_rules! apply {
+			($($field: ident),*) => {
+				$(
+					if let Some(value) = overrides.$field {
+						schedule.$field = value.into();
+					}
+				)*
+			}
+		}
+
+		apply!(
+			exp_gas, exp_byte_gas, sha3_gas, sha3_word_gas, sload_gas, balance_gas, extcodesize_gas,
+			extcodecopy_base_gas, sstore_set_gas, sstore_reset_gas, sstore_refund_gas, jumpdest_gas,
+			log_gas, log_data_gas, log_topic_gas, create_gas, call_gas, call_stipend, call_value_transfer_gas,
+			call_new_account_gas, suicide_refund_gas, suicide_gas, suicide_to_new_account_cost, memory_gas,
+			create_data_gas, tx_gas, tx_create_gas, tx_data_zero_gas, tx_data_non_zero_gas, copy_gas
+		);
+		schedule
+	}
+
+	/// Checks that the chain specification's gas schedule overrides keep the assumptions
+	/// the EVM implementations rely on, whichever schedule they're applied to.
+	pub fn check_overrides(overrides: &ethjson::spec::GasSchedule) -> Result<(), String> {
+		for schedule in vec![Self::new_frontier(), Self::new_homestead(), Self::new_post_eip150()] {
+			let schedule = schedule.with_overrides(overrides);
+			if schedule.call_stipend >= schedule.call_value_transfer_gas {
+				return Err(format!("Gas schedule callStipend ({}) must be lower than callValueTransferGas ({}).", schedule.call_stipend, schedule.call_value_transfer_gas));
+			}
+		}
+		Ok(())
+	}
+
 	fn new(efcd: bool, hdc: bool, tcg: usize) -> Schedule {
 		Schedule{
 			exceptional_failed_code_deposit: efcd,
 			have_delegate_call: hdc,
+			have_revert: false,
 			stack_limit: 1024,
 			max_depth: 1024,
 			tier_step_gas: [0, 2, 3, 5, 8, 10, 20, 0],
@@ -105,6 +173,9 @@ impl Schedule {
 			sha3_gas: 30,
 			sha3_word_gas: 6,
 			sload_gas: 50,
+			balance_gas: 20,
+			extcodesize_gas: 20,
+			extcodecopy_base_gas: 20,
 			sstore_set_gas: 20000,
 			sstore_reset_gas: 5000,
 			sstore_refund_gas: 15000,
@@ -118,6 +189,9 @@ impl Schedule {
 			call_value_transfer_gas: 9000,
 			call_new_account_gas: 25000,
 			suicide_refund_gas: 24000,
+			suicide_gas: 0,
+			suicide_to_new_account_cost: 0,
+			sub_gas_cap_divisor: None,
 			memory_gas: 3,
 			quad_coeff_div: 512,
 			create_data_gas: 200,
@@ -135,8 +209,47 @@ impl Schedule {
 fn schedule_evm_assumptions() {
 	let s1 = Schedule::new_frontier();
 	let s2 = Schedule::new_homestead();
+	let s3 = Schedule::new_post_eip150();
 
 	// To optimize division we assume 2**9 for quad_coeff_div
 	assert_eq!(s1.quad_coeff_div, 512);
 	assert_eq!(s2.quad_coeff_div, 512);
+	assert_eq!(s3.quad_coeff_div, 512);
+}
+
+#[test]
+fn schedule_overrides() {
+	use util::U256;
+	use ethjson::uint::Uint;
+
+	let overrides = ethjson::spec::GasSchedule {
+		sload_gas: Some(Uint(U256::from(100))),
+		call_stipend: Some(Uint(U256::from(0))),
+		..Default::default()
+	};
+	let s = Schedule::new_homestead().with_overrides(&overrides);
+
+	assert_eq!(s.sload_gas, 100);
+	assert_eq!(s.call_stipend, 0);
+	assert_eq!(s.call_gas, 40);
+	assert_eq!(s.tx_create_gas, 53000);
+}
+
+#[test]
+fn schedule_overrides_check() {
+	use util::U256;
+	use ethjson::uint::Uint;
+
+	let stipend = |value: u64| ethjson::spec::GasSchedule {
+		call_stipend: Some(Uint(U256::from(value))),
+		..Default::default()
+	};
+
+	assert!(Schedule::check_overrides(&Default::default()).is_ok());
+	assert!(Schedule::check_overrides(&stipend(8999)).is_ok());
+	assert!(Schedule::check_overrides(&stipend(9000)).is_err());
+	assert!(Schedule::check_overrides(&ethjson::spec::GasSchedule {
+		call_value_transfer_gas: Some(Uint(U256::from(2300))),
+		..Default::default()
+	}).is_err());
 }
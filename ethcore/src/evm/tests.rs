@@ -63,6 +63,7 @@ fn test_finalize(res: Result<GasLeft, evm::Error>) -> Result<U256, evm::Error> {
 	match res {
 		Ok(GasLeft::Known(gas)) => Ok(gas),
 		Ok(GasLeft::NeedsReturn(_, _)) => unimplemented!(), // since ret is unimplemented.
+		Ok(GasLeft::NeedsRevert(_, _)) => unimplemented!(), // since revert is unimplemented.
 		Err(e) => Err(e),
 	}
 }
@@ -155,6 +156,10 @@ impl Ext for FakeExt {
 		unimplemented!();
 	}
 
+	fn revert(self, _gas: &U256, _data: &[u8]) -> evm::Result<U256> {
+		unimplemented!();
+	}
+
 	fn suicide(&mut self, _refund_address: &Address) {
 		unimplemented!();
 	}
@@ -836,6 +841,37 @@ fn test_badinstruction_int() {
 	}
 }
 
+#[test]
+fn test_revert_int() {
+	let factory = super::Factory::new(VMType::Interpreter);
+	let code = "602a60005260206000fd".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+
+	let mut ext = FakeExt::new();
+	let err = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params.clone(), &mut ext)).unwrap_err()
+	};
+	match err {
+		evm::Error::BadInstruction { instruction: 0xfd } => (),
+		_ => assert!(false, "Expected bad instruction")
+	}
+
+	let mut ext = FakeExt::new();
+	ext.schedule.have_revert = true;
+	let mut vm = factory.create(params.gas);
+	match vm.exec(params, &mut ext) {
+		Ok(GasLeft::NeedsRevert(gas_left, data)) => {
+			assert_eq!(gas_left, U256::from(99_982));
+			assert_eq!(data, &*H256::from(42));
+		},
+		_ => assert!(false, "Expected revert"),
+	}
+}
+
 evm_test!{test_pop: test_pop_jit, test_pop_int}
 fn test_pop(factory: super::Factory) {
 	let code = "60f060aa50600055".from_hex().unwrap();
@@ -944,6 +980,76 @@ fn test_calls(factory: super::Factory) {
 	assert_eq!(ext.calls.len(), 2);
 }
 
+evm_test!{ignorejit => test_call_gas_cap: test_call_gas_cap_jit, test_call_gas_cap_int}
+fn test_call_gas_cap(factory: super::Factory) {
+	// CALL to a new account requesting more gas than available
+	let code = "6000600060006000600061099862fffffff100".from_hex().unwrap();
+
+	let address = Address::from(0x155);
+	let code_address = Address::from(0x998);
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	params.address = address.clone();
+	let mut ext = FakeExt::new();
+	ext.schedule = Schedule::new_post_eip150();
+
+	let gas_left = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	// 100_000 - 21 (pushes) - 700 (call) - 25_000 (new account) = 74_279 of which 1/64 is kept
+	assert_set_contains(&ext.calls, &FakeCall {
+		call_type: FakeCallType::Call,
+		gas: U256::from(73_119),
+		sender_address: Some(address.clone()),
+		receive_address: Some(code_address.clone()),
+		value: Some(U256::zero()),
+		data: vec!(),
+		code_address: Some(code_address.clone())
+	});
+	assert_eq!(gas_left, U256::from(74_279));
+}
+
+/// Runs the same code on every available VM and checks that they all agree on
+/// the gas left and the resulting storage.
+/// Only built with the JIT, since the interpreter alone has nothing to be compared with.
+#[cfg(feature = "jit")]
+fn assert_vms_agree(code: &str) {
+	let results: Vec<_> = VMType::all().into_iter().map(|vm_type| {
+		let mut params = ActionParams::default();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code.from_hex().unwrap()));
+		let mut ext = FakeExt::new();
+
+		let gas_left = {
+			let mut vm = Factory::new(vm_type.clone()).create(params.gas);
+			test_finalize(vm.exec(params, &mut ext)).ok()
+		};
+
+		(vm_type, gas_left, ext.store)
+	}).collect();
+
+	let (ref first_type, ref first_gas, ref first_store) = results[0];
+	for &(ref vm_type, ref gas_left, ref store) in &results[1..] {
+		assert_eq!(gas_left, first_gas, "{} and {} disagree on gas left", vm_type, first_type);
+		assert_eq!(store, first_store, "{} and {} disagree on storage", vm_type, first_type);
+	}
+}
+
+#[test]
+#[cfg(feature = "jit")]
+fn test_differential_execution() {
+	// add, sha3, mulmod/addmod, byte, signextend and an out-of-gas loop
+	assert_vms_agree("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff01600055");
+	assert_vms_agree("6000600020600055");
+	assert_vms_agree("60ff60f060108282820860005509600155600060f0601082828208196002550919600355");
+	assert_vms_agree("60f061ffff1a600055610fff601f1a600155");
+	assert_vms_agree("610fff60020b60005560ff60200b600155");
+	assert_vms_agree("5b600056");
+}
+
 fn assert_set_contains<T : Debug + Eq + PartialEq + Hash>(set: &HashSet<T>, val: &T) {
 	let contains = set.contains(val);
 	if !contains {
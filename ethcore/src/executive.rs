@@ -258,7 +258,7 @@ impl<'a> Executive<'a> {
 		}
 		trace!("Executive::call(params={:?}) self.env_info={:?}", params, self.info);
 
-		if self.engine.is_builtin(&params.code_address) {
+		if self.engine.is_builtin(&params.code_address, self.info.number) {
 			// if destination is builtin, try to execute it
 
 			let default = [];
@@ -416,7 +416,10 @@ impl<'a> Executive<'a> {
 		let refunds_bound = sstore_refunds + suicide_refunds;
 
 		// real ammount to refund
-		let gas_left_prerefund = match result { Ok(x) => x, _ => 0.into() };
+		let gas_left_prerefund = match result {
+			Ok(x) | Err(evm::Error::Reverted { gas_left: x }) => x,
+			_ => 0.into(),
+		};
 		let refunded = cmp::min(refunds_bound, (t.gas - gas_left_prerefund) >> 1);
 		let gas_left = gas_left_prerefund + refunded;
 
@@ -439,6 +442,21 @@ impl<'a> Executive<'a> {
 
 		match result {
 			Err(evm::Error::Internal) => Err(ExecutionError::Internal),
+			Err(evm::Error::Reverted { .. }) => {
+				Ok(Executed {
+					gas: t.gas,
+					gas_used: gas_used,
+					refunded: refunded,
+					cumulative_gas_used: self.info.gas_used + gas_used,
+					logs: vec![],
+					contracts_created: vec![],
+					output: output,
+					trace: trace,
+					vm_trace: vm_trace,
+					state_diff: None,
+					reverted: true,
+				})
+			},
 			Err(_) => {
 				Ok(Executed {
 					gas: t.gas,
@@ -451,6 +469,7 @@ impl<'a> Executive<'a> {
 					trace: trace,
 					vm_trace: vm_trace,
 					state_diff: None,
+					reverted: false,
 				})
 			},
 			_ => {
@@ -465,6 +484,7 @@ impl<'a> Executive<'a> {
 					trace: trace,
 					vm_trace: vm_trace,
 					state_diff: None,
+					reverted: false,
 				})
 			},
 		}
@@ -476,7 +496,8 @@ impl<'a> Executive<'a> {
 				| Err(evm::Error::BadJumpDestination {..})
 				| Err(evm::Error::BadInstruction {.. })
 				| Err(evm::Error::StackUnderflow {..})
-				| Err(evm::Error::OutOfStack {..}) => {
+				| Err(evm::Error::OutOfStack {..})
+				| Err(evm::Error::Reverted {..}) => {
 					self.state.revert_to_snapshot();
 			},
 			Ok(_) | Err(evm::Error::Internal) => {
@@ -497,7 +518,7 @@ mod tests {
 	use state::Substate;
 	use tests::helpers::*;
 	use trace::trace;
-	use trace::{FlatTrace, Tracer, NoopTracer, ExecutiveTracer};
+	use trace::{FlatTrace, Tracer, NoopTracer, ExecutiveTracer, TransferTracer};
 	use trace::{VMTrace, VMOperation, VMExecutedOperation, MemoryDiff, StorageDiff, VMTracer, NoopVMTracer, ExecutiveVMTracer};
 	use types::executed::CallType;
 
@@ -721,6 +742,73 @@ mod tests {
 		assert_eq!(vm_tracer.drain().unwrap(), expected_vm_trace);
 	}
 
+	#[test]
+	fn test_transfer_tracer() {
+		let factory = Factory::new(VMType::Interpreter);
+
+		// same code as in `test_call_to_create`: creates a contract endowed with 23 wei.
+		let code = "7c601080600c6000396000f3006000355415600957005b60203560003555600052601d60036017f0600055".from_hex().unwrap();
+
+		let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		let address = contract_address(&sender, &U256::zero());
+		let mut params = ActionParams::default();
+		params.address = address.clone();
+		params.code_address = address.clone();
+		params.sender = sender.clone();
+		params.origin = sender.clone();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code));
+		params.value = ActionValue::Transfer(U256::from(100));
+		params.call_type = CallType::Call;
+		let mut state_result = get_temp_state();
+		let mut state = state_result.reference_mut();
+		state.add_balance(&sender, &U256::from(100));
+		let info = EnvInfo::default();
+		let engine = TestEngine::new(5);
+		let mut substate = Substate::new();
+		let mut tracer = TransferTracer::default();
+		let mut vm_tracer = NoopVMTracer;
+
+		{
+			let mut ex = Executive::new(&mut state, &info, &engine, &factory);
+			let output = BytesRef::Fixed(&mut[0u8;0]);
+			ex.call(params, &mut substate, output, &mut tracer, &mut vm_tracer).unwrap();
+		}
+
+		let expected_transfers = vec![FlatTrace {
+			trace_address: Default::default(),
+			subtraces: 0,
+			action: trace::Action::Call(trace::Call {
+				from: sender,
+				to: address,
+				value: 100.into(),
+				gas: 100000.into(),
+				input: vec![],
+				call_type: CallType::Call,
+			}),
+			result: trace::Res::Call(trace::CallResult {
+				gas_used: U256::from(55_248),
+				output: vec![],
+			}),
+		}, FlatTrace {
+			trace_address: Default::default(),
+			subtraces: 0,
+			action: trace::Action::Create(trace::Create {
+				from: "b010143a42d5980c7e5ef0e4a4416dc098a4fed3".into(),
+				value: 23.into(),
+				gas: 67979.into(),
+				init: vec![],
+			}),
+			result: trace::Res::Create(trace::CreateResult {
+				gas_used: U256::from(3224),
+				address: Address::from_str("c6d80f262ae5e0f164e5fde365044d7ada2bfa34").unwrap(),
+				code: vec![],
+			}),
+		}];
+
+		assert_eq!(tracer.traces(), expected_transfers);
+	}
+
 	#[test]
 	fn test_create_contract() {
 		// Tracing is not supported in JIT
@@ -161,6 +161,7 @@ impl<'a, T, V> Ext for Externalities<'a, T, V> where T: 'a + Tracer, V: 'a + VMT
 				self.substate.contracts_created.push(address.clone());
 				ContractCreateResult::Created(address, gas_left)
 			},
+			Err(evm::Error::Reverted { gas_left }) => ContractCreateResult::Reverted(gas_left),
 			_ => ContractCreateResult::Failed
 		}
 	}
@@ -199,6 +200,7 @@ impl<'a, T, V> Ext for Externalities<'a, T, V> where T: 'a + Tracer, V: 'a + VMT
 
 		match ex.call(params, self.substate, BytesRef::Fixed(output), self.tracer, self.vm_tracer) {
 			Ok(gas_left) => MessageCallResult::Success(gas_left),
+			Err(evm::Error::Reverted { gas_left }) => MessageCallResult::Reverted(gas_left),
 			_ => MessageCallResult::Failed
 		}
 	}
@@ -252,6 +254,27 @@ impl<'a, T, V> Ext for Externalities<'a, T, V> where T: 'a + Tracer, V: 'a + VMT
 		}
 	}
 
+	#[cfg_attr(feature="dev", allow(match_ref_pats))]
+	fn revert(mut self, gas: &U256, data: &[u8]) -> evm::Result<U256>
+		where Self: Sized {
+		match self.output {
+			OutputPolicy::Return(BytesRef::Fixed(ref mut slice), ref mut copy) => {
+				copy.as_mut().map(|b| **b = data.to_owned());
+				let len = cmp::min(slice.len(), data.len());
+				(&mut slice[..len]).copy_from_slice(&data[..len]);
+			},
+			OutputPolicy::Return(BytesRef::Flexible(ref mut vec), ref mut copy) => {
+				copy.as_mut().map(|b| **b = data.to_owned());
+				vec.clear();
+				vec.extend_from_slice(data);
+			},
+			OutputPolicy::InitContract(ref mut copy) => {
+				copy.as_mut().map(|b| **b = data.to_owned());
+			},
+		}
+		Err(evm::Error::Reverted { gas_left: *gas })
+	}
+
 	fn log(&mut self, topics: Vec<H256>, data: &[u8]) {
 		let address = self.origin_info.address.clone();
 		self.substate.logs.push(LogEntry {
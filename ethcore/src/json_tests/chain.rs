@@ -74,7 +74,11 @@ pub fn json_chain_test(json_data: &[u8], era: ChainEra) -> Vec<String> {
 						client.import_verified_blocks();
 					}
 				}
-				fail_unless(client.chain_info().best_block_hash == blockchain.best_block.into());
+				let best_block_hash = client.chain_info().best_block_hash;
+				let expected: H256 = blockchain.best_block.into();
+				if fail_unless(best_block_hash == expected) {
+					println!("!!! {}: Best block mismatch (got: {}, expect: {})", name, best_block_hash, expected);
+				}
 			}
 		}
 
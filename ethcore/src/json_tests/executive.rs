@@ -143,6 +143,10 @@ impl<'a, T, V> Ext for TestExt<'a, T, V> where T: Tracer, V: VMTracer {
 		self.ext.ret(gas, data)
 	}
 
+	fn revert(self, gas: &U256, data: &[u8]) -> Result<U256, evm::Error> {
+		self.ext.revert(gas, data)
+	}
+
 	fn suicide(&mut self, refund_address: &Address) {
 		self.ext.suicide(refund_address)
 	}
@@ -164,7 +168,7 @@ impl<'a, T, V> Ext for TestExt<'a, T, V> where T: Tracer, V: VMTracer {
 	}
 }
 
-fn do_json_test(json_data: &[u8]) -> Vec<String> {
+pub fn do_json_test(json_data: &[u8]) -> Vec<String> {
 	let vms = VMType::all();
 	vms
 		.iter()
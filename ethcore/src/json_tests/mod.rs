@@ -24,3 +24,4 @@ mod chain;
 mod homestead_state;
 mod homestead_chain;
 mod trie;
+mod runner;
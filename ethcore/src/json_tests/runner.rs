@@ -0,0 +1,121 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runs consensus test vectors found in a directory given at run time, so that engine
+//! changes can be checked against a local checkout of the shared test suite:
+//!
+//! `ETHEREUM_TESTS=~/ethereum/tests cargo test --features json-tests external_tests -- --nocapture`
+//!
+//! The kind of each file is told by the directory it's in (`BlockchainTests`, `StateTests`,
+//! `TransactionTests` or `VMTests`) and the rules it runs under by a `Homestead` or
+//! `TestNetwork` (DAO hard-fork) directory on its path. Other files are skipped.
+
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::panic;
+use std::path::Path;
+use tests::helpers::ChainEra;
+use super::{chain, state, transaction, executive};
+
+/// Environment variable pointing at the test vectors directory.
+const TESTS_DIR_VAR: &'static str = "ETHEREUM_TESTS";
+
+fn has_component(path: &Path, name: &str) -> bool {
+	path.components().any(|c| c.as_os_str() == name)
+}
+
+fn era_of(path: &Path) -> ChainEra {
+	if has_component(path, "TestNetwork") {
+		ChainEra::DaoHardfork
+	} else if has_component(path, "Homestead") {
+		ChainEra::Homestead
+	} else {
+		ChainEra::Frontier
+	}
+}
+
+fn transaction_test(json: &[u8], _era: ChainEra) -> Vec<String> {
+	transaction::do_json_test(json)
+}
+
+fn vm_test(json: &[u8], _era: ChainEra) -> Vec<String> {
+	executive::do_json_test(json)
+}
+
+/// Runs a single test file, returning names of failed tests. `None` if the file isn't a known kind of test.
+fn run_file(path: &Path) -> Option<Vec<String>> {
+	let era = era_of(path);
+	let runner: fn(&[u8], ChainEra) -> Vec<String> = if has_component(path, "BlockchainTests") {
+		chain::json_chain_test
+	} else if has_component(path, "StateTests") {
+		state::json_chain_test
+	} else if has_component(path, "TransactionTests") {
+		transaction_test
+	} else if has_component(path, "VMTests") {
+		vm_test
+	} else {
+		return None;
+	};
+
+	let mut json = Vec::new();
+	if let Err(e) = File::open(path).and_then(|mut f| f.read_to_end(&mut json)) {
+		return Some(vec![format!("unreadable: {}", e)]);
+	}
+
+	println!("{}:", path.display());
+	match panic::catch_unwind(|| runner(&json, era)) {
+		Ok(failed) => Some(failed),
+		Err(_) => Some(vec!["harness panicked, the file is probably malformed".into()]),
+	}
+}
+
+/// Runs every test file under `dir`, returning failures prefixed with the path of their file.
+pub fn run_dir(dir: &Path) -> Vec<String> {
+	let mut entries: Vec<_> = match fs::read_dir(dir) {
+		Ok(entries) => entries.filter_map(Result::ok).map(|e| e.path()).collect(),
+		Err(e) => return vec![format!("{}: {}", dir.display(), e)],
+	};
+	entries.sort();
+
+	let mut failed = Vec::new();
+	for path in entries {
+		if path.is_dir() {
+			failed.extend(run_dir(&path));
+		} else if path.extension().map_or(false, |ext| ext == "json") {
+			let file_failed = match run_file(&path) {
+				Some(file_failed) => file_failed,
+				None => continue,
+			};
+			failed.extend(file_failed.into_iter().map(|name| format!("{}: {}", path.display(), name)));
+		}
+	}
+	failed
+}
+
+#[test]
+fn external_tests() {
+	let dir = match env::var(TESTS_DIR_VAR) {
+		Ok(dir) => dir,
+		Err(_) => {
+			println!("{} is not set, skipping external consensus tests.", TESTS_DIR_VAR);
+			return;
+		}
+	};
+
+	let failed = run_dir(Path::new(&dir));
+	assert!(failed.is_empty(), "{} consensus tests failed:\n{}", failed.len(), failed.join("\n"));
+}
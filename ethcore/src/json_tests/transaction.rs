@@ -19,7 +19,7 @@ use evm;
 use ethjson;
 use rlp::{UntrustedRlp, View};
 
-fn do_json_test(json_data: &[u8]) -> Vec<String> {
+pub fn do_json_test(json_data: &[u8]) -> Vec<String> {
 	let tests = ethjson::transaction::Test::load(json_data).unwrap();
 	let mut failed = Vec::new();
 	let old_schedule = evm::Schedule::new_frontier();
@@ -0,0 +1,197 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Temporary bans of senders, recipients and contract codes of transactions
+//! which repeatedly take too long to execute during block packing.
+
+use std::collections::HashMap;
+use std::time::{Instant, Duration};
+use util::{Address, H256, Hashable};
+use transaction::{Action, SignedTransaction};
+use error::{Error, TransactionError};
+
+/// Transaction banning configuration.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Banning {
+	/// Banning is disabled.
+	Disabled,
+	/// Banning is enabled.
+	Enabled {
+		/// Transactions executing longer than this are offending.
+		offend_threshold: Duration,
+		/// Number of offending transactions after which the party is banned.
+		min_offends: u16,
+		/// How long the ban lasts.
+		ban_duration: Duration,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Offender {
+	Sender(Address),
+	Recipient(Address),
+	Code(H256),
+}
+
+impl Offender {
+	/// Returns all parties of given transaction.
+	/// Fails if the sender cannot be recovered from the signature.
+	fn all(tx: &SignedTransaction) -> Result<Vec<Offender>, Error> {
+		let mut parties = vec![Offender::Sender(try!(tx.sender()))];
+		match tx.action {
+			Action::Call(ref address) => parties.push(Offender::Recipient(address.clone())),
+			Action::Create => parties.push(Offender::Code(tx.data.sha3())),
+		}
+		Ok(parties)
+	}
+}
+
+/// Counts offending transactions and keeps track of active bans.
+pub struct BanningQueue {
+	offend_threshold: Duration,
+	min_offends: u16,
+	ban_duration: Duration,
+	offends: HashMap<Offender, u16>,
+	bans: HashMap<Offender, Instant>,
+}
+
+impl BanningQueue {
+	/// Creates new banning queue.
+	pub fn new(offend_threshold: Duration, min_offends: u16, ban_duration: Duration) -> Self {
+		BanningQueue {
+			offend_threshold: offend_threshold,
+			min_offends: min_offends,
+			ban_duration: ban_duration,
+			offends: HashMap::new(),
+			bans: HashMap::new(),
+		}
+	}
+
+	/// Returns an error if the sender, recipient or code of given transaction is banned.
+	pub fn check(&mut self, tx: &SignedTransaction) -> Result<(), TransactionError> {
+		let now = Instant::now();
+		self.bans = self.bans.drain().filter(|&(_, until)| until > now).collect();
+
+		let parties = match Offender::all(tx) {
+			Ok(parties) => parties,
+			// transactions with invalid signatures are rejected by the queue anyway
+			Err(_) => return Ok(()),
+		};
+		for party in parties {
+			if self.bans.contains_key(&party) {
+				return Err(match party {
+					Offender::Sender(_) => TransactionError::SenderBanned,
+					Offender::Recipient(_) => TransactionError::RecipientBanned,
+					Offender::Code(_) => TransactionError::CodeBanned,
+				});
+			}
+		}
+		Ok(())
+	}
+
+	/// Notes how long given transaction took to execute.
+	/// Returns `true` if any of its parties got banned.
+	pub fn note_execution_time(&mut self, tx: &SignedTransaction, time: Duration) -> bool {
+		if time <= self.offend_threshold {
+			return false;
+		}
+
+		let parties = match Offender::all(tx) {
+			Ok(parties) => parties,
+			Err(_) => return false,
+		};
+		let mut banned = false;
+		for party in parties {
+			let offends = {
+				let offends = self.offends.entry(party.clone()).or_insert(0);
+				*offends = offends.saturating_add(1);
+				*offends
+			};
+			if offends >= self.min_offends {
+				debug!(target: "miner", "Banning {:?} for {}s after {} slow transactions", party, self.ban_duration.as_secs(), offends);
+				self.offends.remove(&party);
+				self.bans.insert(party, Instant::now() + self.ban_duration);
+				banned = true;
+			}
+		}
+		banned
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+	use util::U256;
+	use ethkey::{Random, Generator};
+	use transaction::{Transaction, Action, SignedTransaction};
+	use error::TransactionError;
+	use super::BanningQueue;
+
+	fn transaction(action: Action) -> SignedTransaction {
+		let keypair = Random.generate().unwrap();
+		Transaction {
+			action: action,
+			value: U256::from(100),
+			data: vec![1, 2, 3],
+			gas: U256::from(100_000),
+			gas_price: U256::from(1),
+			nonce: U256::zero(),
+		}.sign(keypair.secret())
+	}
+
+	#[test]
+	fn should_ban_parties_after_repeated_offends() {
+		// given
+		let mut queue = BanningQueue::new(Duration::from_millis(100), 2, Duration::from_secs(180));
+		let tx = transaction(Action::Call(5.into()));
+		assert!(!queue.note_execution_time(&tx, Duration::from_millis(200)));
+		assert_eq!(queue.check(&tx), Ok(()));
+
+		// when
+		assert!(queue.note_execution_time(&tx, Duration::from_millis(200)));
+
+		// then
+		assert_eq!(queue.check(&tx), Err(TransactionError::SenderBanned));
+		assert_eq!(queue.check(&transaction(Action::Call(5.into()))), Err(TransactionError::RecipientBanned));
+		assert_eq!(queue.check(&transaction(Action::Call(6.into()))), Ok(()));
+	}
+
+	#[test]
+	fn should_ban_contract_code() {
+		// given
+		let mut queue = BanningQueue::new(Duration::from_millis(100), 1, Duration::from_secs(180));
+
+		// when
+		assert!(!queue.note_execution_time(&transaction(Action::Create), Duration::from_millis(50)));
+		assert!(queue.note_execution_time(&transaction(Action::Create), Duration::from_millis(150)));
+
+		// then
+		assert_eq!(queue.check(&transaction(Action::Create)), Err(TransactionError::CodeBanned));
+	}
+
+	#[test]
+	fn should_lift_expired_bans() {
+		// given
+		let mut queue = BanningQueue::new(Duration::from_millis(100), 1, Duration::from_secs(0));
+		let tx = transaction(Action::Create);
+
+		// when
+		assert!(queue.note_execution_time(&tx, Duration::from_millis(150)));
+
+		// then
+		assert_eq!(queue.check(&tx), Ok(()));
+	}
+}
@@ -19,6 +19,30 @@ use std::sync::Arc;
 use std::time::{Instant, Duration};
 use util::{Mutex, U256, H256};
 
+/// Outcome of a proof-of-work solution submitted by external miner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareResult {
+	/// Solution was valid and the block has been imported.
+	Accepted,
+	/// Solution was for an unknown or outdated block.
+	Stale,
+	/// Solution was rejected.
+	Invalid,
+}
+
+/// Solutions submitted by single worker.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WorkerStatistics {
+	/// Number of accepted solutions.
+	pub accepted: u64,
+	/// Number of solutions for outdated work.
+	pub stale: u64,
+	/// Number of rejected solutions.
+	pub invalid: u64,
+	/// Last reported hashrate, zero if it has expired.
+	pub hashrate: U256,
+}
+
 /// External miner interface.
 pub trait ExternalMinerService: Send + Sync {
 	/// Submit hashrate for given miner.
@@ -26,18 +50,23 @@ pub trait ExternalMinerService: Send + Sync {
 
 	/// Total hashrate.
 	fn hashrate(&self) -> U256;
+
+	/// Record outcome of a solution submitted by given miner.
+	fn submit_share(&self, id: H256, result: ShareResult);
+
+	/// Solution statistics and current hashrate of known miners.
+	fn worker_statistics(&self) -> HashMap<H256, WorkerStatistics>;
 }
 
 /// External Miner.
 pub struct ExternalMiner {
 	hashrates: Arc<Mutex<HashMap<H256, (Instant, U256)>>>,
+	shares: Mutex<HashMap<H256, WorkerStatistics>>,
 }
 
 impl Default for ExternalMiner {
 	fn default() -> Self {
-		ExternalMiner {
-			hashrates: Arc::new(Mutex::new(HashMap::new())),
-		}
+		ExternalMiner::new(Arc::new(Mutex::new(HashMap::new())))
 	}
 }
 
@@ -46,11 +75,14 @@ impl ExternalMiner {
 	pub fn new(hashrates: Arc<Mutex<HashMap<H256, (Instant, U256)>>>) -> Self {
 		ExternalMiner {
 			hashrates: hashrates,
+			shares: Mutex::new(HashMap::new()),
 		}
 	}
 }
 
 const ENTRY_TIMEOUT: u64 = 2;
+/// Maximal number of workers to keep solution statistics for.
+const MAX_WORKERS: usize = 1024;
 
 impl ExternalMinerService for ExternalMiner {
 	fn submit_hashrate(&self, hashrate: U256, id: H256) {
@@ -63,6 +95,31 @@ impl ExternalMinerService for ExternalMiner {
 		*hashrates = h;
 		hashrates.iter().fold(U256::from(0), |sum, (_, &(_, v))| sum + v)
 	}
+
+	fn submit_share(&self, id: H256, result: ShareResult) {
+		let mut shares = self.shares.lock();
+		if !shares.contains_key(&id) && shares.len() >= MAX_WORKERS {
+			return;
+		}
+		let stats = shares.entry(id).or_insert_with(WorkerStatistics::default);
+		match result {
+			ShareResult::Accepted => stats.accepted += 1,
+			ShareResult::Stale => stats.stale += 1,
+			ShareResult::Invalid => stats.invalid += 1,
+		}
+	}
+
+	fn worker_statistics(&self) -> HashMap<H256, WorkerStatistics> {
+		let now = Instant::now();
+		let mut stats = self.shares.lock().clone();
+		for (id, &(t, hashrate)) in self.hashrates.lock().iter() {
+			let worker = stats.entry(id.clone()).or_insert_with(WorkerStatistics::default);
+			if t > now {
+				worker.hashrate = hashrate;
+			}
+		}
+		stats
+	}
 }
 
 #[cfg(test)]
@@ -107,4 +164,22 @@ mod tests {
 		// then
 		assert_eq!(m.hashrate(), U256::from(35));
 	}
+
+	#[test]
+	fn should_count_shares_per_worker() {
+		// given
+		let m = miner();
+		m.submit_hashrate(U256::from(10), H256::from(1));
+
+		// when
+		m.submit_share(H256::from(1), ShareResult::Accepted);
+		m.submit_share(H256::from(1), ShareResult::Stale);
+		m.submit_share(H256::from(2), ShareResult::Invalid);
+
+		// then
+		let stats = m.worker_statistics();
+		assert_eq!(stats.len(), 2);
+		assert_eq!(stats[&H256::from(1)], WorkerStatistics { accepted: 1, stale: 1, invalid: 0, hashrate: U256::from(10) });
+		assert_eq!(stats[&H256::from(2)], WorkerStatistics { accepted: 0, stale: 0, invalid: 1, hashrate: U256::from(0) });
+	}
 }
@@ -22,7 +22,7 @@ use util::using_queue::{UsingQueue, GetAction};
 use account_provider::AccountProvider;
 use views::{BlockView, HeaderView};
 use state::State;
-use client::{MiningBlockChainClient, Executive, Executed, EnvInfo, TransactOptions, BlockID, CallAnalytics};
+use client::{MiningBlockChainClient, Executive, Executed, EnvInfo, TransactOptions, BlockID, CallAnalytics, CacheUsage};
 use executive::contract_address;
 use block::{ClosedBlock, SealedBlock, IsBlock, Block};
 use error::*;
@@ -30,8 +30,10 @@ use transaction::{Action, SignedTransaction};
 use receipt::{Receipt, RichReceipt};
 use spec::Spec;
 use engines::Engine;
-use miner::{MinerService, MinerStatus, TransactionQueue, AccountDetails, TransactionOrigin};
+use miner::{MinerService, MinerStatus, SealingStatistics, ClockDrift, TransactionQueue, AccountDetails, TransactionOrigin, QueueStatus};
 use miner::work_notify::WorkPoster;
+use miner::banning::{Banning, BanningQueue};
+use miner::permissions::{TransactionPermissions, PermissionCache};
 use client::TransactionImportResult;
 use miner::price_info::PriceInfo;
 use header::BlockNumber;
@@ -65,12 +67,24 @@ pub struct MinerOptions {
 	pub tx_gas_limit: U256,
 	/// Maximum size of the transaction queue.
 	pub tx_queue_size: usize,
+	/// Minimal gas price increase (in percents) required to replace a queued transaction.
+	pub tx_replace_bump: usize,
+	/// Maximum number of non-local transactions from a single sender in the queue.
+	pub tx_queue_per_sender: usize,
+	/// Maximum memory used by transactions in the queue (in bytes).
+	pub tx_queue_memory_limit: Option<usize>,
+	/// Temporary banning of parties of transactions which take too long to execute.
+	pub tx_queue_banning: Banning,
+	/// Restrictions on who may deploy contracts or transact with certain accounts.
+	pub tx_permissions: TransactionPermissions,
 	/// Whether we should fallback to providing all the queue's transactions or just pending.
 	pub pending_set: PendingSet,
 	/// How many historical work packages can we store before running out?
 	pub work_queue_size: usize,
 	/// Can we submit two different solutions for the same block and expect both to result in an import?
 	pub enable_resubmission: bool,
+	/// Maximal offset of the local clock against NTP at which blocks are still sealed.
+	pub max_clock_drift: Option<Duration>,
 }
 
 impl Default for MinerOptions {
@@ -82,10 +96,16 @@ impl Default for MinerOptions {
 			reseal_on_own_tx: true,
 			tx_gas_limit: !U256::zero(),
 			tx_queue_size: 2048,
+			tx_replace_bump: 10,
+			tx_queue_per_sender: 64,
+			tx_queue_memory_limit: Some(4 * 1024 * 1024),
+			tx_queue_banning: Banning::Disabled,
+			tx_permissions: TransactionPermissions::Unrestricted,
 			pending_set: PendingSet::AlwaysQueue,
 			reseal_min_period: Duration::from_secs(2),
 			work_queue_size: 20,
 			enable_resubmission: true,
+			max_clock_drift: Some(Duration::from_secs(10)),
 		}
 	}
 }
@@ -165,12 +185,20 @@ struct SealingWork {
 	enabled: bool,
 }
 
+/// Callback waiting for new sealing work.
+struct WorkWaiter {
+	deadline: Instant,
+	on_change: Box<Fn(bool) + Send>,
+}
+
 /// Keeps track of transactions using priority queue and holds currently mined block.
 /// Handles preparing work for "work sealing" or seals "internally" if Engine does not require work.
 pub struct Miner {
 	// NOTE [ToDr]  When locking always lock in this order!
 	transaction_queue: Arc<Mutex<TransactionQueue>>,
 	sealing_work: Mutex<SealingWork>,
+	/// Waiting for new sealing work; always locked after `sealing_work`.
+	work_waiters: Mutex<Vec<WorkWaiter>>,
 	next_allowed_reseal: Mutex<Instant>,
 	sealing_block_last_request: Mutex<u64>,
 	// for sealing...
@@ -185,6 +213,12 @@ pub struct Miner {
 	accounts: Option<Arc<AccountProvider>>,
 	work_poster: Option<WorkPoster>,
 	gas_pricer: Mutex<GasPricer>,
+	local_only_transactions: RwLock<HashSet<H256>>,
+	banning_queue: Option<Mutex<BanningQueue>>,
+	permission_cache: Mutex<PermissionCache>,
+	sealing_statistics: Mutex<SealingStatistics>,
+	sealed_blocks: Mutex<VecDeque<H256>>,
+	clock_drift: RwLock<ClockDrift>,
 }
 
 impl Miner {
@@ -194,7 +228,18 @@ impl Miner {
 			true => None,
 			false => Some(WorkPoster::new(&options.new_work_notify))
 		};
-		let txq = Arc::new(Mutex::new(TransactionQueue::with_limits(options.tx_queue_size, options.tx_gas_limit)));
+		let mut txq = TransactionQueue::with_limits(options.tx_queue_size, options.tx_gas_limit);
+		txq.set_replace_gas_price_bump(options.tx_replace_bump);
+		txq.set_per_sender_limit(options.tx_queue_per_sender);
+		if let Some(limit) = options.tx_queue_memory_limit {
+			txq.set_memory_limit(limit);
+		}
+		let txq = Arc::new(Mutex::new(txq));
+		let banning_queue = match options.tx_queue_banning {
+			Banning::Disabled => None,
+			Banning::Enabled { offend_threshold, min_offends, ban_duration } =>
+				Some(Mutex::new(BanningQueue::new(offend_threshold, min_offends, ban_duration))),
+		};
 		Miner {
 			transaction_queue: txq,
 			next_allowed_reseal: Mutex::new(Instant::now()),
@@ -205,6 +250,7 @@ impl Miner {
 					|| !options.new_work_notify.is_empty()
 					|| spec.engine.is_default_sealer().unwrap_or(false)
 			}),
+			work_waiters: Mutex::new(Vec::new()),
 			seals_internally: spec.engine.is_default_sealer().is_some(),
 			gas_range_target: RwLock::new((U256::zero(), U256::zero())),
 			author: RwLock::new(Address::default()),
@@ -214,6 +260,12 @@ impl Miner {
 			engine: spec.engine.clone(),
 			work_poster: work_poster,
 			gas_pricer: Mutex::new(gas_pricer),
+			local_only_transactions: RwLock::new(HashSet::new()),
+			banning_queue: banning_queue,
+			permission_cache: Mutex::new(PermissionCache::default()),
+			sealing_statistics: Mutex::new(SealingStatistics::default()),
+			sealed_blocks: Mutex::new(VecDeque::new()),
+			clock_drift: RwLock::new(ClockDrift::default()),
 		}
 	}
 
@@ -241,11 +293,6 @@ impl Miner {
 		self.sealing_work.lock().queue.peek_last_ref().map(|b| b.block().fields().state.clone())
 	}
 
-	/// Get `Some` `clone()` of the current pending block's state or `None` if we're not sealing.
-	pub fn pending_block(&self) -> Option<Block> {
-		self.sealing_work.lock().queue.peek_last_ref().map(|b| b.base().clone())
-	}
-
 	#[cfg_attr(feature="dev", allow(match_same_arms))]
 	/// Prepares new block for sealing including top transactions from queue.
 	fn prepare_block(&self, chain: &MiningBlockChainClient) -> (ClosedBlock, Option<H256>) {
@@ -259,6 +306,7 @@ impl Miner {
 			trace!(target: "miner", "prepare_block: done recalibration.");
 		}
 
+		let selection_start = Instant::now();
 		let (transactions, mut open_block, original_work_hash) = {
 			let transactions = {self.transaction_queue.lock().top_transactions()};
 			let mut sealing_work = self.sealing_work.lock();
@@ -290,14 +338,37 @@ impl Miner {
 			};
 			(transactions, open_block, last_work_hash)
 		};
+		let selection_time = selection_start.elapsed();
 
+		let execution_start = Instant::now();
 		let mut invalid_transactions = HashSet::new();
 		let mut transactions_to_penalize = HashSet::new();
 		let block_number = open_block.block().fields().header.number();
 		// TODO: push new uncles, too.
 		for tx in transactions {
 			let hash = tx.hash();
-			match open_block.push_transaction(tx, None) {
+			if let Some(ref banning_queue) = self.banning_queue {
+				if let Err(e) = banning_queue.lock().check(&tx) {
+					debug!(target: "miner", "Skipping banned transaction {:?}: {}", hash, e);
+					invalid_transactions.insert(hash);
+					continue;
+				}
+			}
+			if let Err(e) = self.options.tx_permissions.check(chain, &tx, &self.permission_cache) {
+				debug!(target: "miner", "Skipping transaction {:?}: {}", hash, e);
+				invalid_transactions.insert(hash);
+				continue;
+			}
+			let start = Instant::now();
+			let result = open_block.push_transaction(tx.clone(), None);
+			if let Some(ref banning_queue) = self.banning_queue {
+				if banning_queue.lock().note_execution_time(&tx, start.elapsed()) {
+					// parties are banned now; the transaction is in the block already
+					// but drop it from the queue in case the block is not sealed
+					invalid_transactions.insert(hash);
+				}
+			}
+			match result {
 				Err(Error::Execution(ExecutionError::BlockGasLimitReached { gas_limit, gas_used, gas })) => {
 					debug!(target: "miner", "Skipping adding transaction to block because of gas limit: {:?} (limit: {:?}, used: {:?}, gas: {:?})", hash, gas_limit, gas_used, gas);
 
@@ -330,6 +401,13 @@ impl Miner {
 		}
 
 		let block = open_block.close();
+		let execution_time = execution_start.elapsed();
+		trace!(target: "miner", "prepare_block: #{} assembled (selection: {:?}, execution: {:?})", block_number, selection_time, execution_time);
+		{
+			let mut stats = self.sealing_statistics.lock();
+			stats.transaction_selection.note(selection_time);
+			stats.execution.note(execution_time);
+		}
 
 		let fetch_account = |a: &Address| AccountDetails {
 			nonce: chain.latest_nonce(a),
@@ -388,7 +466,10 @@ impl Miner {
 		});
 		if let Some(seal) = s {
 			trace!(target: "miner", "seal_block_internally: managed internal seal. importing...");
-			block.lock().try_seal(&*self.engine, seal).or_else(|_| {
+			let start = Instant::now();
+			let sealed = block.lock().try_seal(&*self.engine, seal);
+			self.sealing_statistics.lock().seal_check.note(start.elapsed());
+			sealed.or_else(|_| {
 				warn!("prepare_sealing: ERROR: try_seal failed when given internally generated seal. WTF?");
 				Err(None)
 			})
@@ -398,11 +479,30 @@ impl Miner {
 		}
 	}
 
+	/// Whether the local clock is too far off to produce blocks with valid timestamps.
+	fn clock_out_of_sync(&self) -> bool {
+		self.options.max_clock_drift.as_ref().map_or(false, |max| self.clock_drift.read().exceeds(max))
+	}
+
+	/// Remembers a block sealed by this node, forgetting the oldest one if the history is full.
+	fn note_sealed_block(&self, hash: H256) {
+		let mut sealed_blocks = self.sealed_blocks.lock();
+		if sealed_blocks.len() == SEALED_BLOCKS_HISTORY {
+			sealed_blocks.pop_front();
+		}
+		sealed_blocks.push_back(hash);
+	}
+
 	/// Uses Engine to seal the block internally and then imports it to chain.
 	fn seal_and_import_block_internally(&self, chain: &MiningBlockChainClient, block: ClosedBlock) -> bool {
 		if !block.transactions().is_empty() {
 			if let Ok(sealed) = self.seal_block_internally(block) {
-				if chain.import_block(sealed.rlp_bytes()).is_ok() {
+				let hash = sealed.header().hash();
+				let start = Instant::now();
+				let imported = chain.import_block(sealed.rlp_bytes()).is_ok();
+				self.sealing_statistics.lock().import.note(start.elapsed());
+				if imported {
+					self.note_sealed_block(hash);
 					return true
 				}
 			}
@@ -412,6 +512,7 @@ impl Miner {
 
 	/// Prepares work which has to be done to seal.
 	fn prepare_work(&self, block: ClosedBlock, original_work_hash: Option<H256>) {
+		let mut waiters = Vec::new();
 		let (work, is_new) = {
 			let mut sealing_work = self.sealing_work.lock();
 			let last_work_hash = sealing_work.queue.peek_last_ref().map(|pb| pb.block().fields().header.hash());
@@ -427,6 +528,7 @@ impl Miner {
 				if self.work_poster.is_some() && is_new {
 					sealing_work.queue.use_last_ref();
 				}
+				waiters = mem::replace(&mut *self.work_waiters.lock(), Vec::new());
 				(Some((pow_hash, difficulty, number)), is_new)
 			} else {
 				(None, false)
@@ -434,11 +536,29 @@ impl Miner {
 			trace!(target: "miner", "prepare_work: leaving (last={:?})", sealing_work.queue.peek_last_ref().map(|b| b.block().fields().header.hash()));
 			(work, is_new)
 		};
+		for waiter in waiters {
+			(waiter.on_change)(true);
+		}
 		if is_new {
 			work.map(|(pow_hash, difficulty, number)| self.work_poster.as_ref().map(|p| p.notify(pow_hash, difficulty, number)));
 		}
 	}
 
+	/// Notifies callbacks waiting for new sealing work longer than they asked for.
+	pub fn expire_work_waiters(&self) {
+		let now = Instant::now();
+		let expired = {
+			let mut waiters = self.work_waiters.lock();
+			let (expired, waiting) = mem::replace(&mut *waiters, Vec::new()).into_iter()
+				.partition::<Vec<_>, _>(|waiter| waiter.deadline <= now);
+			*waiters = waiting;
+			expired
+		};
+		for waiter in expired {
+			(waiter.on_change)(false);
+		}
+	}
+
 	fn update_gas_limit(&self, chain: &MiningBlockChainClient) {
 		let gas_limit = HeaderView::new(&chain.best_block_header()).gas_limit();
 		let mut queue = self.transaction_queue.lock();
@@ -478,7 +598,25 @@ impl Miner {
 		prepare_new
 	}
 
-	fn add_transactions_to_queue(&self, chain: &MiningBlockChainClient, transactions: Vec<SignedTransaction>, origin: TransactionOrigin, transaction_queue: &mut TransactionQueue) ->
+	/// Checks transactions against bans and permissions. Must be called without holding
+	/// the transaction queue lock, as the permissioning contract may have to be called.
+	fn check_transactions(&self, chain: &MiningBlockChainClient, transactions: Vec<SignedTransaction>, origin: TransactionOrigin) ->
+		Vec<Result<SignedTransaction, Error>> {
+
+		transactions.into_iter()
+			.map(|tx| -> Result<SignedTransaction, Error> {
+				if let Some(ref banning_queue) = self.banning_queue {
+					if origin == TransactionOrigin::External {
+						try!(banning_queue.lock().check(&tx));
+					}
+				}
+				try!(self.options.tx_permissions.check(chain, &tx, &self.permission_cache));
+				Ok(tx)
+			})
+			.collect()
+	}
+
+	fn add_transactions_to_queue(&self, chain: &MiningBlockChainClient, transactions: Vec<Result<SignedTransaction, Error>>, origin: TransactionOrigin, transaction_queue: &mut TransactionQueue) ->
 		Vec<Result<TransactionImportResult, Error>> {
 
 		let fetch_account = |a: &Address| AccountDetails {
@@ -487,7 +625,7 @@ impl Miner {
 		};
 
 		transactions.into_iter()
-			.map(|tx| transaction_queue.add(tx, &fetch_account, origin))
+			.map(|tx| transaction_queue.add(try!(tx), &fetch_account, origin))
 			.collect()
 	}
 
@@ -511,6 +649,8 @@ impl Miner {
 }
 
 const SEALING_TIMEOUT_IN_BLOCKS : u64 = 5;
+/// Number of recently sealed blocks remembered for orphan statistics.
+const SEALED_BLOCKS_HISTORY: usize = 256;
 
 impl MinerService for Miner {
 
@@ -533,6 +673,36 @@ impl MinerService for Miner {
 		}
 	}
 
+	fn sealing_statistics(&self) -> SealingStatistics {
+		self.sealing_statistics.lock().clone()
+	}
+
+	fn sealed_blocks(&self) -> Vec<H256> {
+		self.sealed_blocks.lock().iter().cloned().collect()
+	}
+
+	fn clock_drift(&self) -> ClockDrift {
+		self.clock_drift.read().clone()
+	}
+
+	fn transaction_queue_memory(&self) -> CacheUsage {
+		let queue = self.transaction_queue.lock();
+		CacheUsage {
+			used: queue.mem_usage(),
+			limit: queue.memory_limit(),
+		}
+	}
+
+	fn set_clock_drift(&self, drift: ClockDrift) {
+		let was_out_of_sync = self.clock_out_of_sync();
+		*self.clock_drift.write() = drift;
+		match (was_out_of_sync, self.clock_out_of_sync()) {
+			(false, true) => warn!("Local clock is off by {}ms according to NTP. Blocks will not be sealed until it is corrected.", self.clock_drift.read().ntp.unwrap_or(0)),
+			(true, false) => info!("Local clock is in sync again. Resuming sealing."),
+			_ => {},
+		}
+	}
+
 	fn call(&self, chain: &MiningBlockChainClient, t: &SignedTransaction, analytics: CallAnalytics) -> Result<Executed, CallError> {
 		let sealing_work = self.sealing_work.lock();
 		match sealing_work.queue.peek_last_ref() {
@@ -623,6 +793,10 @@ impl MinerService for Miner {
 		*self.extra_data.write() = extra_data;
 	}
 
+	fn maximum_extra_data_size(&self) -> usize {
+		self.engine.maximum_extra_data_size()
+	}
+
 	/// Set the gas limit we wish to target when sealing a new block.
 	fn set_gas_floor_target(&self, target: U256) {
 		self.gas_range_target.write().0 = target;
@@ -688,6 +862,7 @@ impl MinerService for Miner {
 	) -> Vec<Result<TransactionImportResult, Error>> {
 
 		let results = {
+			let transactions = self.check_transactions(chain, transactions, TransactionOrigin::External);
 			let mut transaction_queue = self.transaction_queue.lock();
 			self.add_transactions_to_queue(
 				chain, transactions, TransactionOrigin::External, &mut transaction_queue
@@ -715,10 +890,11 @@ impl MinerService for Miner {
 		trace!(target: "own_tx", "Importing transaction: {:?}", transaction);
 
 		let imported = {
+			let transactions = self.check_transactions(chain, vec![transaction], TransactionOrigin::Local);
 			// Be sure to release the lock before we call prepare_work_sealing
 			let mut transaction_queue = self.transaction_queue.lock();
 			let import = self.add_transactions_to_queue(
-				chain, vec![transaction], TransactionOrigin::Local, &mut transaction_queue
+				chain, transactions, TransactionOrigin::Local, &mut transaction_queue
 			).pop().unwrap();
 
 			match import {
@@ -753,11 +929,23 @@ impl MinerService for Miner {
 		imported
 	}
 
+	fn queued_transaction(&self, hash: &H256) -> Option<(SignedTransaction, QueueStatus)> {
+		self.transaction_queue.lock().find_with_status(hash)
+	}
+
 	fn all_transactions(&self) -> Vec<SignedTransaction> {
 		let queue = self.transaction_queue.lock();
 		queue.top_transactions()
 	}
 
+	fn pending_block(&self, best_block: BlockNumber) -> Option<Block> {
+		self.from_pending_block(
+			best_block,
+			|| None,
+			|sealing| Some(sealing.base().clone())
+		)
+	}
+
 	fn pending_transactions(&self, best_block: BlockNumber) -> Vec<SignedTransaction> {
 		let queue = self.transaction_queue.lock();
 		match self.options.pending_set {
@@ -779,6 +967,25 @@ impl MinerService for Miner {
 		}
 	}
 
+	fn import_local_only_transaction(
+		&self,
+		chain: &MiningBlockChainClient,
+		transaction: SignedTransaction,
+	) -> Result<TransactionImportResult, Error> {
+		let hash = transaction.hash();
+		// mark before import so that the transaction is never picked up for propagation
+		self.local_only_transactions.write().insert(hash.clone());
+		let imported = self.import_own_transaction(chain, transaction);
+		if imported.is_err() {
+			self.local_only_transactions.write().remove(&hash);
+		}
+		imported
+	}
+
+	fn is_local_only_transaction(&self, hash: &H256) -> bool {
+		self.local_only_transactions.read().contains(hash)
+	}
+
 	fn pending_transactions_hashes(&self, best_block: BlockNumber) -> Vec<H256> {
 		let queue = self.transaction_queue.lock();
 		match self.options.pending_set {
@@ -843,6 +1050,7 @@ impl MinerService for Miner {
 								Action::Call(_) => None,
 								Action::Create => Some(contract_address(&tx.sender().unwrap(), &tx.nonce)),
 							},
+							state_root: receipt.state_root.clone(),
 							logs: receipt.logs.clone(),
 						}
 					})
@@ -870,12 +1078,23 @@ impl MinerService for Miner {
 		self.transaction_queue.lock().last_nonce(address)
 	}
 
+	fn next_nonce(&self, chain: &MiningBlockChainClient, address: &Address) -> U256 {
+		let state_nonce = chain.latest_nonce(address);
+		self.transaction_queue.lock().highest_nonce(address)
+			.map_or(state_nonce, |nonce| cmp::max(state_nonce, nonce + U256::one()))
+	}
+
 
 	/// Update sealing if required.
 	/// Prepare the block and work if the Engine does not seal internally.
 	fn update_sealing(&self, chain: &MiningBlockChainClient) {
 		trace!(target: "miner", "update_sealing");
 
+		if self.clock_out_of_sync() {
+			trace!(target: "miner", "Not sealing: local clock is off by {}ms.", self.clock_drift.read().ntp.unwrap_or(0));
+			return;
+		}
+
 		if self.requires_reseal(chain.chain_info().best_block_number) {
 			// --------------------------------------------------------------------------
 			// | NOTE Code below requires transaction_queue and sealing_work locks.     |
@@ -899,7 +1118,13 @@ impl MinerService for Miner {
 
 	fn map_sealing_work<F, T>(&self, chain: &MiningBlockChainClient, f: F) -> Option<T> where F: FnOnce(&ClosedBlock) -> T {
 		trace!(target: "miner", "map_sealing_work: entering");
+		if self.clock_out_of_sync() {
+			trace!(target: "miner", "Not providing work: local clock is off by {}ms.", self.clock_drift.read().ntp.unwrap_or(0));
+			return None;
+		}
+		let start = Instant::now();
 		self.prepare_work_sealing(chain);
+		self.sealing_statistics.lock().work_preparation.note(start.elapsed());
 		trace!(target: "miner", "map_sealing_work: sealing prepared");
 		let mut sealing_work = self.sealing_work.lock();
 		let ret = sealing_work.queue.use_last_ref();
@@ -907,6 +1132,20 @@ impl MinerService for Miner {
 		ret.map(f)
 	}
 
+	fn on_work_change(&self, pow_hash: &H256, timeout: Duration, on_change: Box<Fn(bool) + Send>) {
+		{
+			let sealing_work = self.sealing_work.lock();
+			if sealing_work.queue.peek_last_ref().map_or(true, |b| &b.hash() == pow_hash) {
+				self.work_waiters.lock().push(WorkWaiter {
+					deadline: Instant::now() + timeout,
+					on_change: on_change,
+				});
+				return;
+			}
+		}
+		on_change(true);
+	}
+
 	fn submit_seal(&self, chain: &MiningBlockChainClient, pow_hash: H256, seal: Vec<Bytes>) -> Result<(), Error> {
 		let result =
 			if let Some(b) = self.sealing_work.lock().queue.get_used_if(
@@ -918,7 +1157,10 @@ impl MinerService for Miner {
 				|b| &b.hash() == &pow_hash
 			) {
 				trace!(target: "miner", "Sealing block {}={}={} with seal {:?}", pow_hash, b.hash(), b.header().bare_hash(), seal);
-				b.lock().try_seal(&*self.engine, seal).or_else(|(e, _)| {
+				let start = Instant::now();
+				let sealed = b.lock().try_seal(&*self.engine, seal);
+				self.sealing_statistics.lock().seal_check.note(start.elapsed());
+				sealed.or_else(|(e, _)| {
 					warn!(target: "miner", "Mined solution rejected: {}", e);
 					Err(Error::PowInvalid)
 				})
@@ -929,7 +1171,11 @@ impl MinerService for Miner {
 		result.and_then(|sealed| {
 			let n = sealed.header().number();
 			let h = sealed.header().hash();
-			try!(chain.import_sealed_block(sealed));
+			let start = Instant::now();
+			let imported = chain.import_sealed_block(sealed);
+			self.sealing_statistics.lock().import.note(start.elapsed());
+			try!(imported);
+			self.note_sealed_block(h.clone());
 			info!(target: "miner", "Mined block imported OK. #{}: {}", Colour::White.bold().paint(format!("{}", n)), Colour::White.bold().paint(h.hex()));
 			Ok(())
 		})
@@ -966,6 +1212,7 @@ impl MinerService for Miner {
 				.par_iter()
 				.map(|h| fetch_transactions(chain, h));
 			out_of_chain.for_each(|txs| {
+				let txs = self.check_transactions(chain, txs, TransactionOrigin::RetractedBlock);
 				let mut transaction_queue = self.transaction_queue.lock();
 				let _ = self.add_transactions_to_queue(
 					chain, txs, TransactionOrigin::RetractedBlock, &mut transaction_queue
@@ -993,6 +1240,14 @@ impl MinerService for Miner {
 			});
 		}
 
+		// Forget local-only transactions which are no longer in the queue
+		{
+			let transaction_queue = self.transaction_queue.lock();
+			let mut local_only = self.local_only_transactions.write();
+			let remaining = local_only.iter().filter(|hash| transaction_queue.find(hash).is_some()).cloned().collect();
+			*local_only = remaining;
+		}
+
 		if enacted.len() > 0 {
 			// --------------------------------------------------------------------------
 			// | NOTE Code below requires transaction_queue and sealing_work locks.     |
@@ -1007,7 +1262,7 @@ impl MinerService for Miner {
 mod tests {
 
 	use std::time::Duration;
-	use super::super::MinerService;
+	use super::super::{MinerService, ClockDrift};
 	use super::*;
 	use util::*;
 	use ethkey::{Generator, Random};
@@ -1029,6 +1284,41 @@ mod tests {
 		assert!(sealing_work.is_some(), "Expected closed block");
 	}
 
+	#[test]
+	fn should_not_seal_with_drifting_clock() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = Miner::with_spec(&Spec::new_test());
+		miner.set_clock_drift(ClockDrift { ntp: Some(-15_000), peers: None });
+
+		// when
+		let sealing_work = miner.map_sealing_work(&client, |_| ());
+
+		// then
+		assert!(sealing_work.is_none());
+		miner.set_clock_drift(ClockDrift { ntp: Some(500), peers: Some(-120_000) });
+		assert!(miner.map_sealing_work(&client, |_| ()).is_some());
+	}
+
+	#[test]
+	fn should_record_sealing_timings() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = Miner::with_spec(&Spec::new_test());
+
+		// when
+		miner.map_sealing_work(&client, |_| ());
+		miner.map_sealing_work(&client, |_| ());
+
+		// then
+		let stats = miner.sealing_statistics();
+		assert_eq!(stats.transaction_selection.count, 1);
+		assert_eq!(stats.execution.count, 1);
+		assert_eq!(stats.work_preparation.count, 2);
+		assert_eq!(stats.seal_check.count, 0);
+		assert!(stats.work_preparation.max >= stats.work_preparation.last);
+	}
+
 	#[test]
 	fn should_still_work_after_a_couple_of_blocks() {
 		// given
@@ -1060,9 +1350,15 @@ mod tests {
 				reseal_min_period: Duration::from_secs(5),
 				tx_gas_limit: !U256::zero(),
 				tx_queue_size: 1024,
+				tx_replace_bump: 10,
+				tx_queue_per_sender: 64,
+				tx_queue_memory_limit: None,
+				tx_queue_banning: Banning::Disabled,
+				tx_permissions: TransactionPermissions::Unrestricted,
 				pending_set: PendingSet::AlwaysSealing,
 				work_queue_size: 5,
 				enable_resubmission: true,
+				max_clock_drift: None,
 			},
 			GasPricer::new_fixed(0u64.into()),
 			&Spec::new_test(),
@@ -1082,6 +1378,30 @@ mod tests {
 		}.sign(keypair.secret())
 	}
 
+	#[test]
+	fn should_wake_up_work_waiters_when_work_changes() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let pow_hash = miner.map_sealing_work(&client, |b| b.hash()).unwrap();
+		let (tx, rx) = ::std::sync::mpsc::channel();
+		let notify = |tx: ::std::sync::mpsc::Sender<bool>| Box::new(move |changed| tx.send(changed).unwrap());
+		miner.on_work_change(&H256::zero(), Duration::from_secs(10), notify(tx.clone()));
+		assert_eq!(rx.try_recv(), Ok(true));
+		miner.on_work_change(&pow_hash, Duration::from_millis(0), notify(tx.clone()));
+		miner.expire_work_waiters();
+		assert_eq!(rx.try_recv(), Ok(false));
+
+		// when
+		miner.on_work_change(&pow_hash, Duration::from_secs(10), notify(tx.clone()));
+		assert!(rx.try_recv().is_err());
+		miner.import_own_transaction(&client, transaction()).unwrap();
+
+		// then
+		assert_eq!(rx.try_recv(), Ok(true));
+		assert!(miner.map_sealing_work(&client, |b| b.hash() != pow_hash).unwrap());
+	}
+
 	#[test]
 	fn should_make_pending_block_when_importing_own_transaction() {
 		// given
@@ -1102,6 +1422,24 @@ mod tests {
 		assert!(!miner.prepare_work_sealing(&client));
 	}
 
+	#[test]
+	fn should_mark_local_only_transaction() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let transaction = transaction();
+		let hash = transaction.hash();
+
+		// when
+		let res = miner.import_local_only_transaction(&client, transaction);
+
+		// then
+		assert_eq!(res.unwrap(), TransactionImportResult::Current);
+		assert_eq!(miner.pending_transactions(0).len(), 1);
+		assert!(miner.is_local_only_transaction(&hash));
+		assert!(!miner.is_local_only_transaction(&H256::default()));
+	}
+
 	#[test]
 	fn should_not_use_pending_block_if_best_block_is_higher() {
 		// given
@@ -1140,6 +1478,40 @@ mod tests {
 		assert!(miner.prepare_work_sealing(&client));
 	}
 
+	#[test]
+	fn should_reject_external_transaction_with_invalid_signature_when_banning() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = Arc::try_unwrap(Miner::new(
+			MinerOptions {
+				tx_queue_banning: Banning::Enabled {
+					offend_threshold: Duration::from_millis(100),
+					min_offends: 1,
+					ban_duration: Duration::from_secs(180),
+				},
+				..MinerOptions::default()
+			},
+			GasPricer::new_fixed(0u64.into()),
+			&Spec::new_test(),
+			None,
+		)).ok().expect("Miner was just created.");
+		let transaction = Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data: "3331600055".from_hex().unwrap(),
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero(),
+		}.invalid_sign();
+
+		// when
+		let res = miner.import_external_transactions(&client, vec![transaction]).pop().unwrap();
+
+		// then
+		assert!(res.is_err());
+		assert_eq!(miner.all_transactions().len(), 0);
+	}
+
 	#[test]
 	fn should_not_seal_unless_enabled() {
 		let miner = miner();
@@ -1164,14 +1536,14 @@ mod tests {
 
 		miner.update_sealing(client);
 		client.flush_queue();
-		assert!(miner.pending_block().is_none());
+		assert!(miner.pending_block(0).is_none());
 		assert_eq!(client.chain_info().best_block_number, 3 as BlockNumber);
 
 		assert_eq!(miner.import_own_transaction(client, transaction()).unwrap(), TransactionImportResult::Current);
 
 		miner.update_sealing(client);
 		client.flush_queue();
-		assert!(miner.pending_block().is_none());
+		assert!(miner.pending_block(0).is_none());
 		assert_eq!(client.chain_info().best_block_number, 4 as BlockNumber);
 	}
 }
@@ -42,20 +42,25 @@
 //! ```
 
 mod miner;
+mod banning;
+mod permissions;
 mod external;
 mod transaction_queue;
 mod work_notify;
 mod price_info;
 
-pub use self::transaction_queue::{TransactionQueue, AccountDetails, TransactionOrigin};
+pub use self::transaction_queue::{TransactionQueue, AccountDetails, TransactionOrigin, QueueStatus};
 pub use self::miner::{Miner, MinerOptions, PendingSet, GasPricer, GasPriceCalibratorOptions};
-pub use self::external::{ExternalMiner, ExternalMinerService};
+pub use self::banning::Banning;
+pub use self::permissions::{TransactionPermissions, PermissionRules, PermissionCache};
+pub use self::external::{ExternalMiner, ExternalMinerService, ShareResult, WorkerStatistics};
 pub use client::TransactionImportResult;
 
 use std::collections::BTreeMap;
+use std::time::Duration;
 use util::{H256, U256, Address, Bytes};
-use client::{MiningBlockChainClient, Executed, CallAnalytics};
-use block::ClosedBlock;
+use client::{MiningBlockChainClient, Executed, CallAnalytics, CacheUsage};
+use block::{Block, ClosedBlock};
 use header::BlockNumber;
 use receipt::{RichReceipt, Receipt};
 use error::{Error, CallError};
@@ -67,6 +72,21 @@ pub trait MinerService : Send + Sync {
 	/// Returns miner's status.
 	fn status(&self) -> MinerStatus;
 
+	/// Returns timings of the block production.
+	fn sealing_statistics(&self) -> SealingStatistics;
+
+	/// Returns hashes of the most recent blocks sealed and imported by this node, oldest first.
+	fn sealed_blocks(&self) -> Vec<H256>;
+
+	/// Returns memory used by transactions in the queue and its limit.
+	fn transaction_queue_memory(&self) -> CacheUsage;
+
+	/// Returns last measured offsets of the local clock.
+	fn clock_drift(&self) -> ClockDrift;
+
+	/// Sets measured offsets of the local clock. Sealing is suspended while the offset against NTP is too large.
+	fn set_clock_drift(&self, drift: ClockDrift);
+
 	/// Get the author that we will seal blocks as.
 	fn author(&self) -> Address;
 
@@ -79,6 +99,9 @@ pub trait MinerService : Send + Sync {
 	/// Set the extra_data that we will seal blocks with.
 	fn set_extra_data(&self, extra_data: Bytes);
 
+	/// Get the maximal extra_data size allowed by the engine.
+	fn maximum_extra_data_size(&self) -> usize;
+
 	/// Get current minimal gas price for transactions accepted to queue.
 	fn minimal_gas_price(&self) -> U256;
 
@@ -115,6 +138,14 @@ pub trait MinerService : Send + Sync {
 	fn import_own_transaction(&self, chain: &MiningBlockChainClient, transaction: SignedTransaction) ->
 		Result<TransactionImportResult, Error>;
 
+	/// Imports own transaction to queue. The transaction is never propagated to peers
+	/// and only gets included in blocks sealed by this node.
+	fn import_local_only_transaction(&self, chain: &MiningBlockChainClient, transaction: SignedTransaction) ->
+		Result<TransactionImportResult, Error>;
+
+	/// Returns true if given transaction must not be propagated to peers.
+	fn is_local_only_transaction(&self, hash: &H256) -> bool;
+
 	/// Returns hashes of transactions currently in pending
 	fn pending_transactions_hashes(&self, best_block: BlockNumber) -> Vec<H256>;
 
@@ -131,6 +162,10 @@ pub trait MinerService : Send + Sync {
 	/// Will check the seal, but not actually insert the block into the chain.
 	fn submit_seal(&self, chain: &MiningBlockChainClient, pow_hash: H256, seal: Vec<Bytes>) -> Result<(), Error>;
 
+	/// Invokes `on_change` once the latest sealing work differs from `pow_hash`, with `true`,
+	/// or once `timeout` passes, with `false`. Timeouts are checked on every client tick.
+	fn on_work_change(&self, pow_hash: &H256, timeout: Duration, on_change: Box<Fn(bool) + Send>);
+
 	/// Get the sealing work package and if `Some`, apply some transform.
 	fn map_sealing_work<F, T>(&self, chain: &MiningBlockChainClient, f: F) -> Option<T>
 		where F: FnOnce(&ClosedBlock) -> T, Self: Sized;
@@ -138,9 +173,15 @@ pub trait MinerService : Send + Sync {
 	/// Query pending transactions for hash.
 	fn transaction(&self, best_block: BlockNumber, hash: &H256) -> Option<SignedTransaction>;
 
+	/// Query the transaction queue (including `future` transactions) for hash.
+	fn queued_transaction(&self, hash: &H256) -> Option<(SignedTransaction, QueueStatus)>;
+
 	/// Get a list of all transactions.
 	fn all_transactions(&self) -> Vec<SignedTransaction>;
 
+	/// Get the pending block if it's built on top of `best_block`.
+	fn pending_block(&self, best_block: BlockNumber) -> Option<Block>;
+
 	/// Get a list of all pending transactions.
 	fn pending_transactions(&self, best_block: BlockNumber) -> Vec<SignedTransaction>;
 
@@ -153,6 +194,10 @@ pub trait MinerService : Send + Sync {
 	/// Returns highest transaction nonce for given address.
 	fn last_nonce(&self, address: &Address) -> Option<U256>;
 
+	/// Returns next nonce that can be used by given address without colliding
+	/// with any transaction in the queue (including `future` ones).
+	fn next_nonce(&self, chain: &MiningBlockChainClient, address: &Address) -> U256;
+
 	/// Is it currently sealing?
 	fn is_sealing(&self) -> bool;
 
@@ -179,7 +224,7 @@ pub trait MinerService : Send + Sync {
 }
 
 /// Mining status
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MinerStatus {
 	/// Number of transactions in queue with state `pending` (ready to be included in block)
 	pub transactions_in_pending_queue: usize,
@@ -188,3 +233,68 @@ pub struct MinerStatus {
 	/// Number of transactions included in currently mined block
 	pub transactions_in_pending_block: usize,
 }
+
+/// Duration of a single step of the block production.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Timing {
+	/// Duration of the most recent run.
+	pub last: Duration,
+	/// Longest run so far.
+	pub max: Duration,
+	/// Total time of all runs.
+	pub total: Duration,
+	/// Number of runs.
+	pub count: u64,
+}
+
+impl Timing {
+	/// Notes another run of the step.
+	pub fn note(&mut self, duration: Duration) {
+		self.last = duration;
+		if duration > self.max {
+			self.max = duration;
+		}
+		self.total = self.total + duration;
+		self.count += 1;
+	}
+
+	/// Average duration of a run.
+	pub fn average(&self) -> Duration {
+		match self.count {
+			0 => Duration::new(0, 0),
+			count => self.total / count as u32,
+		}
+	}
+}
+
+/// Offsets of the local clock against reference clocks, in milliseconds. Positive if the local clock is ahead.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClockDrift {
+	/// Offset against the NTP server.
+	pub ntp: Option<i64>,
+	/// Median offset against clocks of peers. Only accurate to tens of seconds.
+	pub peers: Option<i64>,
+}
+
+impl ClockDrift {
+	/// Whether the offset against NTP is larger than `max`. Peer estimates are too coarse to act upon.
+	pub fn exceeds(&self, max: &Duration) -> bool {
+		let max = max.as_secs() as i64 * 1000 + max.subsec_nanos() as i64 / 1_000_000;
+		self.ntp.map_or(false, |drift| drift.abs() > max)
+	}
+}
+
+/// Timing breakdown of the block production.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SealingStatistics {
+	/// Picking transactions from the queue and opening the block.
+	pub transaction_selection: Timing,
+	/// Executing transactions and closing the block.
+	pub execution: Timing,
+	/// Preparing the work package when it is requested (includes block assembly if needed).
+	pub work_preparation: Timing,
+	/// Verifying submitted or generated seal.
+	pub seal_check: Timing,
+	/// Importing the sealed block into the chain.
+	pub import: Timing,
+}
@@ -0,0 +1,226 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction permissioning for private chains. Restricts who may deploy contracts
+//! or transact with certain accounts, either with local rules or by asking an
+//! on-chain permissioning contract.
+
+use std::io::Read;
+use std::collections::{HashMap, HashSet};
+use util::{Address, H256, U256, Hashable, Mutex};
+use client::{MiningBlockChainClient, BlockID};
+use transaction::{Action, Transaction, SignedTransaction};
+use error::TransactionError;
+use ethjson;
+
+/// Gas given to calls of the permissioning contract.
+const PERMISSION_CALL_GAS: u64 = 100_000;
+/// Maximal number of cached answers of the permissioning contract.
+const MAX_CACHED_PERMISSIONS: usize = 4096;
+
+/// Transaction permissioning configuration.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TransactionPermissions {
+	/// Every transaction is permitted.
+	Unrestricted,
+	/// Transactions are checked against local rules.
+	Rules(PermissionRules),
+	/// Transactions are checked by the permissioning contract at given address.
+	/// It has to implement `allowed(address sender, address to, bool create) constant returns (bool)`.
+	Contract(Address),
+}
+
+impl Default for TransactionPermissions {
+	fn default() -> Self {
+		TransactionPermissions::Unrestricted
+	}
+}
+
+/// Local transaction permissioning rules.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PermissionRules {
+	/// Accounts allowed to deploy contracts. `None` lets everyone deploy.
+	pub deployers: Option<HashSet<Address>>,
+	/// Recipients which may only receive transactions from the listed senders.
+	pub restricted_recipients: HashMap<Address, HashSet<Address>>,
+}
+
+impl PermissionRules {
+	/// Loads rules from json.
+	pub fn load<R>(reader: R) -> Result<Self, String> where R: Read {
+		ethjson::misc::TransactionPermissions::load(reader)
+			.map(Into::into)
+			.map_err(|e| format!("Invalid transaction permissions: {}", e))
+	}
+
+	fn allows(&self, sender: &Address, action: &Action) -> bool {
+		match *action {
+			Action::Create => self.deployers.as_ref().map_or(true, |deployers| deployers.contains(sender)),
+			Action::Call(ref to) => self.restricted_recipients.get(to).map_or(true, |senders| senders.contains(sender)),
+		}
+	}
+}
+
+impl From<ethjson::misc::TransactionPermissions> for PermissionRules {
+	fn from(p: ethjson::misc::TransactionPermissions) -> Self {
+		PermissionRules {
+			deployers: p.deployers.map(|d| d.into_iter().map(Into::into).collect()),
+			restricted_recipients: p.restricted_recipients.unwrap_or_else(Default::default).into_iter()
+				.map(|(to, senders)| (to.into(), senders.into_iter().map(Into::into).collect()))
+				.collect(),
+		}
+	}
+}
+
+/// Answers of the permissioning contract as of a single best block.
+#[derive(Default)]
+pub struct PermissionCache {
+	block: H256,
+	allowed: HashMap<(Address, Address, bool), bool>,
+}
+
+impl PermissionCache {
+	/// Returns cached answer for given sender and action, if it was given as of `block`.
+	fn get(&self, block: &H256, sender: &Address, to: &Address, create: bool) -> Option<bool> {
+		match self.block == *block {
+			true => self.allowed.get(&(sender.clone(), to.clone(), create)).cloned(),
+			false => None,
+		}
+	}
+
+	/// Caches answer given as of `block`, forgetting answers given as of other blocks.
+	fn insert(&mut self, block: H256, sender: Address, to: Address, create: bool, allowed: bool) {
+		if self.block != block || self.allowed.len() >= MAX_CACHED_PERMISSIONS {
+			self.block = block;
+			self.allowed.clear();
+		}
+		self.allowed.insert((sender, to, create), allowed);
+	}
+}
+
+impl TransactionPermissions {
+	/// Returns an error if given transaction is not permitted as of the latest block of `chain`.
+	/// Answers of the permissioning contract are kept in `cache` until the best block changes.
+	pub fn check(&self, chain: &MiningBlockChainClient, tx: &SignedTransaction, cache: &Mutex<PermissionCache>) -> Result<(), TransactionError> {
+		let sender = match tx.sender() {
+			Ok(sender) => sender,
+			// transactions with invalid signatures are rejected by the queue anyway
+			Err(_) => return Ok(()),
+		};
+		let allowed = match *self {
+			TransactionPermissions::Unrestricted => true,
+			TransactionPermissions::Rules(ref rules) => rules.allows(&sender, &tx.action),
+			TransactionPermissions::Contract(ref contract) => {
+				let (to, create) = match tx.action {
+					Action::Create => (Address::default(), true),
+					Action::Call(ref to) => (to.clone(), false),
+				};
+				let block = chain.chain_info().best_block_hash;
+				let cached = cache.lock().get(&block, &sender, &to, create);
+				match cached {
+					Some(allowed) => allowed,
+					None => {
+						let allowed = Self::contract_allows(chain, contract, &block, &sender, &to, create);
+						cache.lock().insert(block, sender, to, create, allowed);
+						allowed
+					},
+				}
+			},
+		};
+		match allowed {
+			true => Ok(()),
+			false => Err(TransactionError::NotPermitted),
+		}
+	}
+
+	fn contract_allows(chain: &MiningBlockChainClient, contract: &Address, block: &H256, sender: &Address, to: &Address, create: bool) -> bool {
+		let mut data = "allowed(address,address,bool)".sha3()[..4].to_vec();
+		data.extend_from_slice(&H256::from(sender));
+		data.extend_from_slice(&H256::from(to));
+		data.extend_from_slice(&H256::from(create as u64));
+
+		let from = Address::default();
+		let call = Transaction {
+			nonce: chain.latest_nonce(&from),
+			action: Action::Call(contract.clone()),
+			gas: U256::from(PERMISSION_CALL_GAS),
+			gas_price: U256::default(),
+			value: U256::default(),
+			data: data,
+		}.fake_sign(from);
+
+		match chain.call(&call, BlockID::Hash(block.clone()), Default::default()) {
+			Ok(executed) => executed.output.len() >= 32 && !H256::from_slice(&executed.output[..32]).is_zero(),
+			Err(e) => {
+				warn!(target: "miner", "Calling permissioning contract failed: {:?}", e);
+				false
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::{HashMap, HashSet};
+	use util::{Address, H256};
+	use transaction::Action;
+	use super::{PermissionRules, PermissionCache};
+
+	#[test]
+	fn should_allow_everything_by_default() {
+		let rules = PermissionRules::default();
+		assert!(rules.allows(&Address::from(1), &Action::Create));
+		assert!(rules.allows(&Address::from(1), &Action::Call(Address::from(2))));
+	}
+
+	#[test]
+	fn should_restrict_deployers_and_recipients() {
+		let mut deployers = HashSet::new();
+		deployers.insert(Address::from(1));
+		let mut senders = HashSet::new();
+		senders.insert(Address::from(1));
+		let mut restricted = HashMap::new();
+		restricted.insert(Address::from(3), senders);
+		let rules = PermissionRules {
+			deployers: Some(deployers),
+			restricted_recipients: restricted,
+		};
+
+		assert!(rules.allows(&Address::from(1), &Action::Create));
+		assert!(!rules.allows(&Address::from(2), &Action::Create));
+		assert!(rules.allows(&Address::from(1), &Action::Call(Address::from(3))));
+		assert!(!rules.allows(&Address::from(2), &Action::Call(Address::from(3))));
+		assert!(rules.allows(&Address::from(2), &Action::Call(Address::from(4))));
+	}
+
+	#[test]
+	fn should_cache_permissions_until_best_block_changes() {
+		let mut cache = PermissionCache::default();
+		let (sender, to) = (Address::from(1), Address::from(2));
+		assert_eq!(cache.get(&H256::from(1), &sender, &to, false), None);
+
+		cache.insert(H256::from(1), sender, to, false, true);
+
+		assert_eq!(cache.get(&H256::from(1), &sender, &to, false), Some(true));
+		assert_eq!(cache.get(&H256::from(1), &sender, &to, true), None);
+		assert_eq!(cache.get(&H256::from(2), &sender, &to, false), None);
+
+		cache.insert(H256::from(2), sender, Address::from(3), false, false);
+
+		assert_eq!(cache.get(&H256::from(1), &sender, &to, false), None);
+		assert_eq!(cache.get(&H256::from(2), &sender, &Address::from(3), false), Some(false));
+	}
+}
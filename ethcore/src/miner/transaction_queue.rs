@@ -83,9 +83,9 @@
 
 use std::ops::Deref;
 use std::cmp::Ordering;
-use std::cmp;
+use std::{cmp, mem};
 use std::collections::{HashSet, HashMap, BTreeSet, BTreeMap};
-use util::{Address, H256, Uint, U256};
+use util::{Address, H256, Uint, U256, HeapSizeOf};
 use util::table::Table;
 use transaction::*;
 use error::{Error, TransactionError};
@@ -234,6 +234,53 @@ impl VerifiedTransaction {
 	fn sender(&self) -> Address {
 		self.transaction.sender().expect("Sender is verified in new; qed")
 	}
+
+	fn mem_usage(&self) -> usize {
+		mem::size_of::<VerifiedTransaction>() + self.transaction.heap_size_of_children()
+	}
+}
+
+/// All transactions managed by the queue indexed by hash, along with the memory they use.
+#[derive(Default)]
+struct TransactionsByHash {
+	transactions: HashMap<H256, VerifiedTransaction>,
+	mem_usage: usize,
+}
+
+impl TransactionsByHash {
+	fn insert(&mut self, hash: H256, tx: VerifiedTransaction) -> Option<VerifiedTransaction> {
+		self.mem_usage += tx.mem_usage();
+		let old = self.transactions.insert(hash, tx);
+		if let Some(ref old) = old {
+			self.mem_usage -= old.mem_usage();
+		}
+		old
+	}
+
+	fn remove(&mut self, hash: &H256) -> Option<VerifiedTransaction> {
+		let tx = self.transactions.remove(hash);
+		if let Some(ref tx) = tx {
+			self.mem_usage -= tx.mem_usage();
+		}
+		tx
+	}
+
+	fn clear(&mut self) {
+		self.transactions.clear();
+		self.mem_usage = 0;
+	}
+
+	fn mem_usage(&self) -> usize {
+		self.mem_usage
+	}
+}
+
+impl Deref for TransactionsByHash {
+	type Target = HashMap<H256, VerifiedTransaction>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.transactions
+	}
 }
 
 #[derive(Debug, Default)]
@@ -316,7 +363,7 @@ impl TransactionSet {
 	///
 	/// It drops transactions from this set but also removes associated `VerifiedTransaction`.
 	/// Returns addresses and lowest nonces of transactions removed because of limit.
-	fn enforce_limit(&mut self, by_hash: &mut HashMap<H256, VerifiedTransaction>) -> Option<HashMap<Address, U256>> {
+	fn enforce_limit(&mut self, by_hash: &mut TransactionsByHash) -> Option<HashMap<Address, U256>> {
 		let len = self.by_priority.len();
 		if len <= self.limit {
 			return None;
@@ -394,6 +441,15 @@ pub struct TransactionQueueStatus {
 	pub future: usize,
 }
 
+/// Status of a transaction held by the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueStatus {
+	/// Transaction is in `current` (ready to go to block)
+	Current,
+	/// Transaction is in `future` (waiting for transactions with lower nonces first)
+	Future,
+}
+
 /// Details of account
 pub struct AccountDetails {
 	/// Most recent account nonce
@@ -418,9 +474,15 @@ pub struct TransactionQueue {
 	/// Priority queue for transactions that has been received but are not yet valid to go to block
 	future: TransactionSet,
 	/// All transactions managed by queue indexed by hash
-	by_hash: HashMap<H256, VerifiedTransaction>,
+	by_hash: TransactionsByHash,
 	/// Last nonce of transaction in current (to quickly check next expected transaction)
 	last_nonces: HashMap<Address, U256>,
+	/// Minimal gas price increase (in percents) required to replace a transaction with the same sender and nonce
+	replace_gas_price_bump: usize,
+	/// Maximal number of non-local transactions from a single sender
+	per_sender_limit: usize,
+	/// Maximal memory used by all transactions in the queue (in bytes)
+	memory_limit: usize,
 }
 
 impl Default for TransactionQueue {
@@ -457,8 +519,11 @@ impl TransactionQueue {
 			gas_limit: !U256::zero(),
 			current: current,
 			future: future,
-			by_hash: HashMap::new(),
+			by_hash: TransactionsByHash::default(),
 			last_nonces: HashMap::new(),
+			replace_gas_price_bump: 0,
+			per_sender_limit: usize::max_value(),
+			memory_limit: usize::max_value(),
 		}
 	}
 
@@ -487,6 +552,34 @@ impl TransactionQueue {
 		self.minimal_gas_price = min_gas_price;
 	}
 
+	/// Sets minimal gas price increase (in percents) required to replace
+	/// a transaction with the same sender and nonce.
+	pub fn set_replace_gas_price_bump(&mut self, percent: usize) {
+		self.replace_gas_price_bump = percent;
+	}
+
+	/// Sets maximal number of transactions from a single sender. Local transactions are not limited.
+	/// Any transaction already imported to the queue is not affected.
+	pub fn set_per_sender_limit(&mut self, limit: usize) {
+		self.per_sender_limit = limit;
+	}
+
+	/// Sets maximal memory used by transactions in the queue (in bytes) and ensures the limit.
+	pub fn set_memory_limit(&mut self, limit: usize) {
+		self.memory_limit = limit;
+		self.enforce_memory_limit();
+	}
+
+	/// Returns maximal memory used by transactions in the queue (in bytes).
+	pub fn memory_limit(&self) -> usize {
+		self.memory_limit
+	}
+
+	/// Returns memory used by transactions in the queue (in bytes).
+	pub fn mem_usage(&self) -> usize {
+		self.by_hash.mem_usage()
+	}
+
 	/// Get one more than the lowest gas price in the queue iff the pool is
 	/// full, otherwise 0.
 	pub fn effective_minimum_gas_price(&self) -> U256 {
@@ -762,6 +855,17 @@ impl TransactionQueue {
 		match self.by_hash.get(hash) { Some(transaction_ref) => Some(transaction_ref.transaction.clone()), None => None }
 	}
 
+	/// Finds transaction in the queue by hash (if any) together with its status.
+	pub fn find_with_status(&self, hash: &H256) -> Option<(SignedTransaction, QueueStatus)> {
+		self.by_hash.get(hash).map(|tx| {
+			let status = match self.current.by_address.get(&tx.sender(), &tx.nonce()) {
+				Some(order) if order.hash == *hash => QueueStatus::Current,
+				_ => QueueStatus::Future,
+			};
+			(tx.transaction.clone(), status)
+		})
+	}
+
 	/// Removes all elements (in any state) from the queue
 	pub fn clear(&mut self) {
 		self.current.clear();
@@ -775,6 +879,13 @@ impl TransactionQueue {
 		self.last_nonces.get(address).cloned()
 	}
 
+	/// Returns highest nonce of any transaction (either `current` or `future`) from given address.
+	pub fn highest_nonce(&self, address: &Address) -> Option<U256> {
+		let current = self.current.by_address.row(address).and_then(|row| row.keys().max().cloned());
+		let future = self.future.by_address.row(address).and_then(|row| row.keys().max().cloned());
+		cmp::max(current, future)
+	}
+
 	/// Checks if there are any transactions in `future` that should actually be promoted to `current`
 	/// (because nonce matches).
 	fn move_matching_future_to_current(&mut self, address: Address, mut current_nonce: U256, first_nonce: U256) {
@@ -812,7 +923,7 @@ impl TransactionQueue {
 	/// this.
 	///
 	/// It ignores transactions that has already been imported (same `hash`) and replaces the transaction
-	/// iff `(address, nonce)` is the same but `gas_price` is higher.
+	/// iff `(address, nonce)` is the same but `gas_price` is higher by at least `replace_gas_price_bump` percent.
 	///
 	/// Returns `true` when transaction was imported successfuly
 	fn import_tx(&mut self, tx: VerifiedTransaction, state_nonce: U256) -> Result<TransactionImportResult, TransactionError> {
@@ -838,6 +949,18 @@ impl TransactionQueue {
 
 		// Update nonces of transactions in future (remove old transactions)
 		self.update_future(&address, state_nonce);
+
+		if tx.origin != TransactionOrigin::Local {
+			let is_replacement = self.current.by_address.get(&address, &nonce).is_some()
+				|| self.future.by_address.get(&address, &nonce).is_some();
+			let count = self.current.by_address.row(&address).map_or(0, |row| row.len())
+				+ self.future.by_address.row(&address).map_or(0, |row| row.len());
+			if !is_replacement && count >= self.per_sender_limit {
+				trace!(target: "txqueue", "Dropping transaction {:?}: sender {} has {} transactions in the queue", hash, address, count);
+				return Err(TransactionError::LimitReached);
+			}
+		}
+
 		// State nonce could be updated. Maybe there are some more items waiting in future?
 		self.move_matching_future_to_current(address, state_nonce, state_nonce);
 		// Check the next expected nonce (might be updated by move above)
@@ -850,11 +973,13 @@ impl TransactionQueue {
 		if nonce > next_nonce {
 			// We have a gap - put to future.
 			// Insert transaction (or replace old one with lower gas price)
-			try!(check_too_cheap(Self::replace_transaction(tx, state_nonce, &mut self.future, &mut self.by_hash)));
+			try!(Self::replace_transaction(tx, state_nonce, self.replace_gas_price_bump, &mut self.future, &mut self.by_hash));
 			// Enforce limit in Future
 			let removed = self.future.enforce_limit(&mut self.by_hash);
 			// Return an error if this transaction was not imported because of limit.
 			try!(check_if_removed(&address, &nonce, removed));
+			let removed = self.enforce_memory_limit();
+			try!(check_if_removed(&address, &nonce, removed));
 
 			debug!(target: "txqueue", "Importing transaction to future: {:?}", hash);
 			debug!(target: "txqueue", "status: {:?}", self.status());
@@ -866,7 +991,7 @@ impl TransactionQueue {
 		self.move_matching_future_to_current(address, nonce + U256::one(), state_nonce);
 
 		// Replace transaction if any
-		try!(check_too_cheap(Self::replace_transaction(tx, state_nonce, &mut self.current, &mut self.by_hash)));
+		try!(Self::replace_transaction(tx, state_nonce, self.replace_gas_price_bump, &mut self.current, &mut self.by_hash));
 		// Keep track of highest nonce stored in current
 		let new_max = self.last_nonces.get(&address).map_or(nonce, |n| cmp::max(nonce, *n));
 		self.last_nonces.insert(address, new_max);
@@ -877,12 +1002,54 @@ impl TransactionQueue {
 		self.update_last_nonces(&removed);
 		// Trigger error if the transaction we are importing was removed.
 		try!(check_if_removed(&address, &nonce, removed));
+		let removed = self.enforce_memory_limit();
+		try!(check_if_removed(&address, &nonce, removed));
 
 		debug!(target: "txqueue", "Imported transaction to current: {:?}", hash);
 		debug!(target: "txqueue", "status: {:?}", self.status());
 		Ok(TransactionImportResult::Current)
 	}
 
+	/// Removes transactions until the queue fits in `memory_limit`. Lowest priced future transactions are removed first,
+	/// then lowest priority current transactions.
+	///
+	/// Returns addresses and lowest nonces of removed transactions.
+	fn enforce_memory_limit(&mut self) -> Option<HashMap<Address, U256>> {
+		let mut mem_usage = self.mem_usage();
+		if mem_usage <= self.memory_limit {
+			return None;
+		}
+
+		let mut removed = HashMap::new();
+		let mut removed_current = HashMap::new();
+		while mem_usage > self.memory_limit {
+			let lowest_future = self.future.by_gas_price.values().next().and_then(|hashes| hashes.iter().next().cloned());
+			let (hash, is_future) = match lowest_future {
+				Some(hash) => (hash, true),
+				None => match self.current.by_priority.iter().next_back() {
+					Some(order) => (order.hash.clone(), false),
+					None => break,
+				},
+			};
+
+			let tx = self.by_hash.remove(&hash).expect("All transactions in `current` and `future` are also in `by_hash`; qed");
+			let (sender, nonce) = (tx.sender(), tx.nonce());
+			let set = if is_future { &mut self.future } else { &mut self.current };
+			set.drop(&sender, &nonce).expect("Transaction has just been found in the set; qed");
+			mem_usage -= tx.mem_usage();
+			trace!(target: "txqueue", "Removing transaction {:?} because of memory limit", hash);
+
+			let min = removed.get(&sender).map_or(nonce, |n| cmp::min(*n, nonce));
+			removed.insert(sender, min);
+			if !is_future {
+				let min = removed_current.get(&sender).map_or(nonce, |n| cmp::min(*n, nonce));
+				removed_current.insert(sender, min);
+			}
+		}
+		self.update_last_nonces(&Some(removed_current));
+		Some(removed)
+	}
+
 	/// Updates
 	fn update_last_nonces(&mut self, removed_min_nonces: &Option<HashMap<Address, U256>>) {
 		if let Some(ref min_nonces) = *removed_min_nonces {
@@ -898,29 +1065,41 @@ impl TransactionQueue {
 
 	/// Replaces transaction in given set (could be `future` or `current`).
 	///
-	/// If there is already transaction with same `(sender, nonce)` it will be replaced iff `gas_price` is higher.
-	/// One of the transactions is dropped from set and also removed from queue entirely (from `by_hash`).
+	/// If there is already transaction with same `(sender, nonce)` it will be replaced iff `gas_price` is higher
+	/// by at least `bump_percent`. The old transaction is dropped from set and also removed from queue entirely (from `by_hash`).
 	///
-	/// Returns `true` if transaction actually got to the queue (`false` if there was already a transaction with higher
-	/// gas_price)
-	fn replace_transaction(tx: VerifiedTransaction, base_nonce: U256, set: &mut TransactionSet, by_hash: &mut HashMap<H256, VerifiedTransaction>) -> bool {
+	/// Returns `TooCheapToReplace` error with the minimal acceptable gas price if the transaction was not imported.
+	fn replace_transaction(tx: VerifiedTransaction, base_nonce: U256, bump_percent: usize, set: &mut TransactionSet, by_hash: &mut TransactionsByHash) -> Result<(), TransactionError> {
 		let order = TransactionOrder::for_transaction(&tx, base_nonce);
 		let hash = tx.hash();
 		let address = tx.sender();
 		let nonce = tx.nonce();
 
+		if let Some(old) = set.by_address.get(&address, &nonce) {
+			let minimal = match old.gas_price.overflowing_mul(U256::from(100 + bump_percent)) {
+				(_, true) => !U256::zero(),
+				(bumped, false) => bumped / U256::from(100),
+			};
+			if order.gas_price < minimal {
+				trace!(target: "txqueue", "Dropping transaction {:?}: gas price {} too low to replace {:?} (minimal: {})", hash, order.gas_price, old.hash, minimal);
+				return Err(TransactionError::TooCheapToReplace {
+					minimal: minimal,
+					got: order.gas_price,
+				});
+			}
+		}
+
 		let old_hash = by_hash.insert(hash, tx);
 		assert!(old_hash.is_none(), "Each hash has to be inserted exactly once.");
 
-
 		if let Some(old) = set.insert(address, nonce, order.clone()) {
-			Self::replace_orders(address, nonce, old, order, set, by_hash)
-		} else {
-			true
+			// gas price has been checked above, so the new transaction always stays
+			Self::replace_orders(address, nonce, old, order, set, by_hash);
 		}
+		Ok(())
 	}
 
-	fn replace_orders(address: Address, nonce: U256, old: TransactionOrder, order: TransactionOrder, set: &mut TransactionSet, by_hash: &mut HashMap<H256, VerifiedTransaction>) -> bool {
+	fn replace_orders(address: Address, nonce: U256, old: TransactionOrder, order: TransactionOrder, set: &mut TransactionSet, by_hash: &mut TransactionsByHash) -> bool {
 		// There was already transaction in queue. Let's check which one should stay
 		let old_fee = old.gas_price;
 		let new_fee = order.gas_price;
@@ -938,14 +1117,6 @@ impl TransactionQueue {
 	}
 }
 
-fn check_too_cheap(is_in: bool) -> Result<(), TransactionError> {
-	if is_in {
-		Ok(())
-	} else {
-		Err(TransactionError::TooCheapToReplace)
-	}
-}
-
 fn check_if_removed(sender: &Address, nonce: &U256, dropped: Option<HashMap<Address, U256>>) -> Result<(), TransactionError> {
 	match dropped {
 		Some(ref dropped) => match dropped.get(sender) {
@@ -968,7 +1139,7 @@ mod test {
 	use transaction::*;
 	use error::{Error, TransactionError};
 	use super::*;
-	use super::{TransactionSet, TransactionOrder, VerifiedTransaction};
+	use super::{TransactionSet, TransactionOrder, VerifiedTransaction, TransactionsByHash};
 	use client::TransactionImportResult;
 
 	fn unwrap_tx_err(err: Result<TransactionImportResult, Error>) -> TransactionError {
@@ -1095,7 +1266,7 @@ mod test {
 		let tx1 = VerifiedTransaction::new(tx1, TransactionOrigin::External).unwrap();
 		let tx2 = VerifiedTransaction::new(tx2, TransactionOrigin::External).unwrap();
 		let mut by_hash = {
-			let mut x = HashMap::new();
+			let mut x = TransactionsByHash::default();
 			let tx1 = VerifiedTransaction::new(tx1.transaction.clone(), TransactionOrigin::External).unwrap();
 			let tx2 = VerifiedTransaction::new(tx2.transaction.clone(), TransactionOrigin::External).unwrap();
 			x.insert(tx1.hash(), tx1);
@@ -1728,6 +1899,67 @@ mod test {
 		assert_eq!(txq.status().future, 1);
 	}
 
+	#[test]
+	fn should_limit_transactions_per_sender() {
+		// given
+		let mut txq = TransactionQueue::new();
+		txq.set_per_sender_limit(1);
+		let (tx1, tx2) = new_tx_pair_default(1.into(), 0.into());
+		txq.add(tx1, &default_account_details, TransactionOrigin::External).unwrap();
+
+		// when
+		let res = txq.add(tx2.clone(), &default_account_details, TransactionOrigin::External);
+
+		// then
+		assert_eq!(unwrap_tx_err(res), TransactionError::LimitReached);
+		assert_eq!(txq.status().pending, 1);
+		txq.add(tx2, &default_account_details, TransactionOrigin::Local).unwrap();
+		assert_eq!(txq.status().pending, 2);
+	}
+
+	#[test]
+	fn should_drop_future_transactions_first_when_memory_limit_reached() {
+		// given
+		let mut txq = TransactionQueue::new();
+		let (tx1, tx2) = new_tx_pair_default(1.into(), 0.into());
+		let (tx3, tx4) = new_tx_pair_default(4.into(), 1.into());
+		for tx in vec![tx1, tx2, tx3, tx4] {
+			txq.add(tx, &default_account_details, TransactionOrigin::External).unwrap();
+		}
+		assert_eq!(txq.status().pending, 3);
+		assert_eq!(txq.status().future, 1);
+
+		// when
+		let limit = txq.mem_usage() - 1;
+		txq.set_memory_limit(limit);
+
+		// then
+		assert_eq!(txq.status().pending, 3);
+		assert_eq!(txq.status().future, 0);
+		assert!(txq.mem_usage() <= limit);
+	}
+
+	#[test]
+	fn should_track_memory_used_by_transactions() {
+		// given
+		let mut txq = TransactionQueue::new();
+		let (tx1, tx2) = new_tx_pair_default(1.into(), 0.into());
+		let hash = tx1.hash();
+		txq.add(tx1, &default_account_details, TransactionOrigin::External).unwrap();
+		txq.add(tx2, &default_account_details, TransactionOrigin::External).unwrap();
+		let total = txq.by_hash.values().fold(0, |sum, tx| sum + tx.mem_usage());
+		assert_eq!(txq.mem_usage(), total);
+
+		// when
+		txq.remove_invalid(&hash, &default_account_details);
+
+		// then
+		let total = txq.by_hash.values().fold(0, |sum, tx| sum + tx.mem_usage());
+		assert_eq!(txq.mem_usage(), total);
+		txq.clear();
+		assert_eq!(txq.mem_usage(), 0);
+	}
+
 	#[test]
 	fn should_drop_transactions_with_old_nonces() {
 		let mut txq = TransactionQueue::new();
@@ -1833,6 +2065,32 @@ mod test {
 		assert_eq!(txq.top_transactions()[0].gas_price, U256::from(200));
 	}
 
+	#[test]
+	fn should_require_gas_price_bump_to_replace_transaction() {
+		// given
+		let mut txq = TransactionQueue::new();
+		txq.set_replace_gas_price_bump(10);
+		let keypair = Random.generate().unwrap();
+		let tx = new_unsigned_tx(123.into(), 100.into()).sign(keypair.secret());
+		let with_gas_price = |gas_price: u64| {
+			let mut tx2 = (*tx).clone();
+			tx2.gas_price = U256::from(gas_price);
+			tx2.sign(keypair.secret())
+		};
+		let tx2 = with_gas_price(109);
+		let tx3 = with_gas_price(110);
+		txq.add(tx.clone(), &default_account_details, TransactionOrigin::External).unwrap();
+
+		// when
+		let res = txq.add(tx2, &default_account_details, TransactionOrigin::External);
+		txq.add(tx3, &default_account_details, TransactionOrigin::External).unwrap();
+
+		// then
+		assert_eq!(unwrap_tx_err(res), TransactionError::TooCheapToReplace { minimal: 110.into(), got: 109.into() });
+		assert_eq!(txq.status().pending, 1);
+		assert_eq!(txq.top_transactions()[0].gas_price, U256::from(110));
+	}
+
 	#[test]
 	fn should_replace_same_transaction_when_importing_to_futures() {
 		// given
@@ -1910,6 +2168,43 @@ mod test {
 		assert_eq!(txq.last_nonce(&from), Some(nonce));
 	}
 
+	#[test]
+	fn should_return_highest_nonce_including_future_transactions() {
+		// given
+		let mut txq = TransactionQueue::new();
+		let (tx1, tx2) = new_tx_pair_default(2.into(), 0.into());
+		let from = tx1.sender().unwrap();
+		let nonce = tx1.nonce;
+		let details = |_a: &Address| AccountDetails { nonce: nonce, balance: !U256::zero() };
+
+		// when
+		txq.add(tx1, &details, TransactionOrigin::External).unwrap();
+		txq.add(tx2, &details, TransactionOrigin::External).unwrap();
+
+		// then
+		assert_eq!(txq.status().future, 1);
+		assert_eq!(txq.last_nonce(&from), Some(nonce));
+		assert_eq!(txq.highest_nonce(&from), Some(nonce + 2.into()));
+		assert_eq!(txq.highest_nonce(&Address::default()), None);
+	}
+
+	#[test]
+	fn should_find_transactions_with_queue_status() {
+		// given
+		let mut txq = TransactionQueue::new();
+		let (tx1, tx2) = new_tx_pair_default(2.into(), 0.into());
+		let (hash1, hash2) = (tx1.hash(), tx2.hash());
+
+		// when
+		txq.add(tx1, &default_account_details, TransactionOrigin::External).unwrap();
+		txq.add(tx2, &default_account_details, TransactionOrigin::External).unwrap();
+
+		// then
+		assert_eq!(txq.find_with_status(&hash1).map(|t| t.1), Some(QueueStatus::Current));
+		assert_eq!(txq.find_with_status(&hash2).map(|t| t.1), Some(QueueStatus::Future));
+		assert_eq!(txq.find_with_status(&H256::default()), None);
+	}
+
 	#[test]
 	fn should_remove_old_transaction_even_if_newer_transaction_was_not_known() {
 		// given
@@ -24,7 +24,8 @@ use client::{Client, ClientConfig, ChainNotify};
 use miner::Miner;
 use snapshot::ManifestData;
 use snapshot::service::{Service as SnapshotService, ServiceParams as SnapServiceParams};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::thread::{self, JoinHandle};
 
 #[cfg(feature="ipc")]
 use nanoipc;
@@ -56,6 +57,7 @@ pub struct ClientService {
 	client: Arc<Client>,
 	snapshot: Arc<SnapshotService>,
 	panic_handler: Arc<PanicHandler>,
+	snapshot_thread: Arc<Mutex<Option<JoinHandle<()>>>>,
 	_stop_guard: ::devtools::StopGuard,
 }
 
@@ -71,7 +73,8 @@ impl ClientService {
 		) -> Result<ClientService, Error>
 	{
 		let panic_handler = PanicHandler::new_in_arc();
-		let io_service = try!(IoService::<ClientIoMessage>::start());
+		let io_workers = config.io_workers.unwrap_or_else(|| min(max(::num_cpus::get() / 2, 2), 8));
+		let io_service = try!(IoService::<ClientIoMessage>::start_with_workers(io_workers));
 		panic_handler.forward_from(&io_service);
 
 		info!("Configured for {} using {} engine", Colour::White.bold().paint(spec.name.clone()), Colour::Yellow.bold().paint(spec.engine.name()));
@@ -105,9 +108,12 @@ impl ClientService {
 		let snapshot = Arc::new(try!(SnapshotService::new(snapshot_params)));
 
 		panic_handler.forward_from(&*client);
+		let snapshot_thread = Arc::new(Mutex::new(None));
 		let client_io = Arc::new(ClientIoHandler {
 			client: client.clone(),
 			snapshot: snapshot.clone(),
+			snapshot_thread: snapshot_thread.clone(),
+			taking_snapshot: Arc::new(AtomicBool::new(false)),
 		});
 		try!(io_service.register_handler(client_io));
 
@@ -119,6 +125,7 @@ impl ClientService {
 			client: client,
 			snapshot: snapshot,
 			panic_handler: panic_handler,
+			snapshot_thread: snapshot_thread,
 			_stop_guard: stop_guard,
 		})
 	}
@@ -154,6 +161,18 @@ impl ClientService {
 	}
 }
 
+impl Drop for ClientService {
+	fn drop(&mut self) {
+		// the snapshot thread holds the client; let it finish before the client is closed.
+		if let Some(handle) = self.snapshot_thread.lock().take() {
+			trace!(target: "shutdown", "[ClientService] Waiting for snapshot to finish...");
+			if handle.join().is_err() {
+				warn!("Snapshot thread panicked");
+			}
+		}
+	}
+}
+
 impl MayPanic for ClientService {
 	fn on_panic<F>(&self, closure: F) where F: OnPanicListener {
 		self.panic_handler.on_panic(closure);
@@ -164,6 +183,9 @@ impl MayPanic for ClientService {
 struct ClientIoHandler {
 	client: Arc<Client>,
 	snapshot: Arc<SnapshotService>,
+	// thread taking the periodic snapshot, joined on shutdown.
+	snapshot_thread: Arc<Mutex<Option<JoinHandle<()>>>>,
+	taking_snapshot: Arc<AtomicBool>,
 }
 
 const CLIENT_TICK_TIMER: TimerToken = 0;
@@ -199,8 +221,33 @@ impl IoHandler<ClientIoMessage> for ClientIoHandler {
 			ClientIoMessage::FeedStateChunk(ref hash, ref chunk) => self.snapshot.feed_state_chunk(*hash, chunk),
 			ClientIoMessage::FeedBlockChunk(ref hash, ref chunk) => self.snapshot.feed_block_chunk(*hash, chunk),
 			ClientIoMessage::TakeSnapshot(num) => {
-				if let Err(e) = self.snapshot.take_snapshot(&*self.client, num) {
-					warn!("Failed to take snapshot at block #{}: {}", num, e);
+				let mut snapshot_thread = self.snapshot_thread.lock();
+				if self.taking_snapshot.swap(true, AtomicOrdering::SeqCst) {
+					info!("Skipping snapshot at #{} as another one is currently in-progress.", num);
+					return;
+				}
+				// the previous snapshot has finished already.
+				if let Some(handle) = snapshot_thread.take() {
+					let _ = handle.join();
+				}
+
+				// snapshots take a long time; keep them off the IO workers.
+				let client = self.client.clone();
+				let snapshot = self.snapshot.clone();
+				let taking_snapshot = self.taking_snapshot.clone();
+				let res = thread::Builder::new().name("Periodic Snapshot".into()).spawn(move || {
+					if let Err(e) = snapshot.take_snapshot(&*client, num) {
+						warn!("Failed to take snapshot at block #{}: {}", num, e);
+					}
+					taking_snapshot.store(false, AtomicOrdering::SeqCst);
+				});
+
+				match res {
+					Ok(handle) => *snapshot_thread = Some(handle),
+					Err(e) => {
+						warn!("Failed to spawn snapshot thread: {}", e);
+						self.taking_snapshot.store(false, AtomicOrdering::SeqCst);
+					},
 				}
 			}
 			_ => {} // ignore other messages
@@ -50,7 +50,7 @@ use rand::{Rng, OsRng};
 
 pub use self::error::Error;
 
-pub use self::service::{Service, DatabaseRestore};
+pub use self::service::{Service, DatabaseRestore, CreationStatus};
 pub use self::traits::SnapshotService;
 pub use self::watcher::Watcher;
 pub use types::snapshot_manifest::ManifestData;
@@ -81,8 +81,8 @@ mod traits {
 // Try to have chunks be around 4MB (before compression)
 const PREFERRED_CHUNK_SIZE: usize = 4 * 1024 * 1024;
 
-// How many blocks to include in a snapshot, starting from the head of the chain.
-const SNAPSHOT_BLOCKS: u64 = 30000;
+/// How many blocks to include in a snapshot, starting from the head of the chain.
+pub const SNAPSHOT_BLOCKS: u64 = 30000;
 
 /// A progress indicator for snapshots.
 #[derive(Debug, Default)]
@@ -17,9 +17,9 @@
 //! Snapshot network service implementation.
 
 use std::collections::HashSet;
-use std::io::ErrorKind;
+use std::io::{self, ErrorKind};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
@@ -40,6 +40,50 @@ use util::journaldb::Algorithm;
 use util::kvdb::{Database, DatabaseConfig};
 use util::snappy;
 
+/// Status of local snapshot creation.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CreationStatus {
+	/// Number of the block a snapshot is currently being taken at, if any.
+	pub in_progress: Option<u64>,
+	/// Accounts written by the snapshot in progress.
+	pub accounts: usize,
+	/// Blocks written by the snapshot in progress.
+	pub blocks: usize,
+	/// Bytes written by the snapshot in progress.
+	pub size: usize,
+	/// Block numbers of the snapshots kept on disk, most recent first.
+	pub snapshots: Vec<u64>,
+}
+
+// list the block numbers of snapshots stored in the history dir, most recent first.
+fn history_snapshots(dir: &Path) -> io::Result<Vec<u64>> {
+	let entries = match fs::read_dir(dir) {
+		Ok(entries) => entries,
+		Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(e) => return Err(e),
+	};
+
+	let mut numbers = Vec::new();
+	for entry in entries {
+		let entry = try!(entry);
+		if let Some(num) = entry.file_name().to_str().and_then(|name| name.parse::<u64>().ok()) {
+			numbers.push(num);
+		}
+	}
+
+	numbers.sort_by(|a, b| b.cmp(a));
+	Ok(numbers)
+}
+
+// remove all but the `keep` most recent snapshots from the history dir.
+fn prune_history(dir: &Path, keep: usize) -> io::Result<()> {
+	for num in try!(history_snapshots(dir)).into_iter().skip(keep) {
+		trace!(target: "snapshot", "removing old snapshot #{}", num);
+		try!(fs::remove_dir_all(dir.join(num.to_string())));
+	}
+	Ok(())
+}
+
 /// Helper for removing directories in case of error.
 struct Guard(bool, PathBuf);
 
@@ -215,6 +259,8 @@ pub struct Service {
 	db_restore: Arc<DatabaseRestore>,
 	progress: super::Progress,
 	taking_snapshot: AtomicBool,
+	taking_at: Mutex<Option<u64>>,
+	retain: AtomicUsize,
 }
 
 impl Service {
@@ -235,6 +281,8 @@ impl Service {
 			db_restore: params.db_restore,
 			progress: Default::default(),
 			taking_snapshot: AtomicBool::new(false),
+			taking_at: Mutex::new(None),
+			retain: AtomicUsize::new(1),
 		};
 
 		// create the root snapshot dir if it doesn't exist.
@@ -278,6 +326,13 @@ impl Service {
 		dir
 	}
 
+	// get the dir holding older snapshots retained alongside the current one.
+	fn history_dir(&self) -> PathBuf {
+		let mut dir = self.snapshot_root.clone();
+		dir.push("history");
+		dir
+	}
+
 	// get the restoration directory.
 	fn restoration_dir(&self) -> PathBuf {
 		let mut dir = self.snapshot_root.clone();
@@ -312,6 +367,32 @@ impl Service {
 		self.reader.read()
 	}
 
+	/// Set the number of most recent snapshots to keep on disk.
+	/// The current snapshot is always kept.
+	pub fn set_retain(&self, retain: usize) {
+		self.retain.store(::std::cmp::max(retain, 1), Ordering::SeqCst);
+	}
+
+	/// Get the status of local snapshot creation.
+	pub fn creation_status(&self) -> CreationStatus {
+		let in_progress = *self.taking_at.lock();
+		let (accounts, blocks, size) = match in_progress {
+			Some(_) => (self.progress.accounts(), self.progress.blocks(), self.progress.size()),
+			None => (0, 0, 0),
+		};
+
+		let mut snapshots: Vec<u64> = self.manifest().map(|m| m.block_number).into_iter().collect();
+		snapshots.extend(history_snapshots(&self.history_dir()).unwrap_or_else(|_| Vec::new()));
+
+		CreationStatus {
+			in_progress: in_progress,
+			accounts: accounts,
+			blocks: blocks,
+			size: size,
+			snapshots: snapshots,
+		}
+	}
+
 	/// Tick the snapshot service. This will log any active snapshot
 	/// being taken.
 	pub fn tick(&self) {
@@ -333,6 +414,7 @@ impl Service {
 
 		info!("Taking snapshot at #{}", num);
 		self.progress.reset();
+		*self.taking_at.lock() = Some(num);
 
 		let temp_dir = self.temp_snapshot_dir();
 		let snapshot_dir = self.snapshot_dir();
@@ -344,6 +426,7 @@ impl Service {
 		let guard = Guard::new(temp_dir.clone());
 		let res = client.take_snapshot(writer, BlockID::Number(num), &self.progress);
 
+		*self.taking_at.lock() = None;
 		self.taking_snapshot.store(false, Ordering::SeqCst);
 		if let Err(e) = res {
 			if client.chain_info().best_block_number >= num + ::client::HISTORY {
@@ -360,12 +443,21 @@ impl Service {
 		info!("Finished taking snapshot at #{}", num);
 
 		let mut reader = self.reader.write();
+		let retain = self.retain.load(Ordering::SeqCst);
+		let history_dir = self.history_dir();
 
 		// destroy the old snapshot reader.
-		*reader = None;
+		let previous = reader.take().map(|r| r.manifest().block_number);
 
 		if snapshot_dir.exists() {
-			try!(fs::remove_dir_all(&snapshot_dir));
+			match previous {
+				Some(prev) if retain > 1 && prev != num => {
+					trace!(target: "snapshot", "moving snapshot #{} to history", prev);
+					try!(fs::create_dir_all(&history_dir));
+					try!(fs::rename(&snapshot_dir, history_dir.join(prev.to_string())));
+				}
+				_ => try!(fs::remove_dir_all(&snapshot_dir)),
+			}
 		}
 
 		try!(fs::rename(temp_dir, &snapshot_dir));
@@ -373,6 +465,11 @@ impl Service {
 		*reader = Some(try!(LooseReader::new(snapshot_dir)));
 
 		guard.disarm();
+
+		if let Err(e) = prune_history(&history_dir, retain - 1) {
+			warn!("Failed to remove old snapshots: {}", e);
+		}
+
 		Ok(())
 	}
 
@@ -629,4 +726,21 @@ mod tests {
 		service.restore_state_chunk(Default::default(), vec![]);
 		service.restore_block_chunk(Default::default(), vec![]);
 	}
+
+	#[test]
+	fn prunes_snapshot_history() {
+		use std::fs;
+
+		let dir = RandomTempPath::new();
+		let dir = dir.as_path().to_owned();
+		for num in &[10000u64, 30000, 20000, 40000] {
+			fs::create_dir_all(dir.join(num.to_string())).unwrap();
+		}
+
+		assert_eq!(history_snapshots(&dir).unwrap(), vec![40000, 30000, 20000, 10000]);
+		prune_history(&dir, 2).unwrap();
+		assert_eq!(history_snapshots(&dir).unwrap(), vec![40000, 30000]);
+		prune_history(&dir, 0).unwrap();
+		assert!(history_snapshots(&dir).unwrap().is_empty());
+	}
 }
\ No newline at end of file
@@ -18,6 +18,7 @@
 
 use common::*;
 use engines::{Engine, NullEngine, InstantSeal, BasicAuthority};
+use evm::Schedule;
 use pod_state::*;
 use account_db::*;
 use state_db::StateDB;
@@ -25,7 +26,23 @@ use super::genesis::Genesis;
 use super::seal::Generic as GenericSeal;
 use ethereum;
 use ethjson;
-use rlp::{Rlp, RlpStream, View, Stream};
+use rlp::{Rlp, UntrustedRlp, RlpStream, View, Stream};
+
+/// Insert the trie nodes of given state into the state DB, returning its root.
+pub fn populate_state(state: &PodState, db: &mut StateDB) -> Result<H256, Box<TrieError>> {
+	let mut root = H256::new();
+	{
+		let mut t = SecTrieDBMut::new(db.as_hashdb_mut(), &mut root);
+		for (address, account) in state.get().iter() {
+			try!(t.insert(&**address, &account.rlp()));
+		}
+	}
+	for (address, account) in state.get().iter() {
+		db.note_account_bloom(address);
+		account.insert_additional(&mut AccountDBMut::new(db.as_hashdb_mut(), address));
+	}
+	Ok(root)
+}
 
 /// Parameters common to all engines.
 #[derive(Debug, PartialEq, Clone)]
@@ -43,6 +60,19 @@ pub struct CommonParams {
 	pub min_gas_limit: U256,
 	/// Fork block to check.
 	pub fork_block: Option<(BlockNumber, H256)>,
+	/// Number of first block where EIP-140 (REVERT instruction) rules begin.
+	pub eip140_transition: BlockNumber,
+	/// Overrides applied on top of the engine's EVM gas schedule.
+	pub gas_schedule: ethjson::spec::GasSchedule,
+}
+
+impl CommonParams {
+	/// Apply common spec rules to the engine's schedule for the given block.
+	pub fn update_schedule(&self, block_number: BlockNumber, schedule: Schedule) -> Schedule {
+		let mut schedule = schedule.with_overrides(&self.gas_schedule);
+		schedule.have_revert = block_number >= self.eip140_transition;
+		schedule
+	}
 }
 
 impl From<ethjson::spec::Params> for CommonParams {
@@ -54,6 +84,8 @@ impl From<ethjson::spec::Params> for CommonParams {
 			subprotocol_name: p.subprotocol_name.unwrap_or_else(|| "eth".to_owned()),
 			min_gas_limit: p.min_gas_limit.into(),
 			fork_block: if let (Some(n), Some(h)) = (p.fork_block, p.fork_hash) { Some((n.into(), h.into())) } else { None },
+			eip140_transition: p.eip140_transition.map_or(BlockNumber::max_value(), Into::into),
+			gas_schedule: p.gas_schedule.unwrap_or_else(Default::default),
 		}
 	}
 }
@@ -71,6 +103,9 @@ pub struct Spec {
 	/// Known nodes on the network in enode format.
 	pub nodes: Vec<String>,
 
+	/// Trusted recent header new nodes may start syncing from.
+	pub hardcoded_sync: Option<Header>,
+
 	/// Parameters common to all engines.
 	pub params: CommonParams,
 
@@ -116,6 +151,7 @@ impl From<ethjson::spec::Spec> for Spec {
 			engine: Spec::engine(s.engine, params, builtins),
 			fork_name: s.fork_name.map(Into::into),
 			nodes: s.nodes.unwrap_or_else(Vec::new),
+			hardcoded_sync: s.hardcoded_sync.and_then(|h| UntrustedRlp::new(&h.header).as_val().ok()),
 			parent_hash: g.parent_hash,
 			transactions_root: g.transactions_root,
 			receipts_root: g.receipts_root,
@@ -165,6 +201,11 @@ impl Spec {
 	/// Get the configured network fork block.
 	pub fn fork_block(&self) -> Option<(BlockNumber, H256)> { self.params.fork_block }
 
+	/// Get the number and hash of the trusted sync checkpoint.
+	pub fn checkpoint(&self) -> Option<(BlockNumber, H256)> {
+		self.hardcoded_sync.as_ref().map(|h| (h.number(), h.hash()))
+	}
+
 	/// Get the header of the genesis block.
 	pub fn genesis_header(&self) -> Header {
 		let mut header: Header = Default::default();
@@ -228,6 +269,9 @@ impl Spec {
 		*self.state_root_memo.write() = None;
 	}
 
+	/// Get the genesis state.
+	pub fn genesis_state(&self) -> &PodState { &self.genesis_state }
+
 	/// Returns `false` if the memoized state root is invalid. `true` otherwise.
 	pub fn is_state_root_valid(&self) -> bool {
 		self.state_root_memo.read().clone().map_or(true, |sr| sr == self.genesis_state.root())
@@ -237,19 +281,8 @@ impl Spec {
 	pub fn ensure_db_good(&self, db: &mut StateDB) -> Result<bool, Box<TrieError>> {
 		if !db.as_hashdb().contains(&self.state_root()) {
 			trace!(target: "spec", "ensure_db_good: Fresh database? Cannot find state root {}", self.state_root());
-			let mut root = H256::new();
-
-			{
-				let mut t = SecTrieDBMut::new(db.as_hashdb_mut(), &mut root);
-				for (address, account) in self.genesis_state.get().iter() {
-					try!(t.insert(&**address, &account.rlp()));
-				}
-			}
+			let root = try!(populate_state(&self.genesis_state, db));
 			trace!(target: "spec", "ensure_db_good: Populated sec trie; root is {}", root);
-			for (address, account) in self.genesis_state.get().iter() {
-				db.note_account_bloom(address);
-				account.insert_additional(&mut AccountDBMut::new(db.as_hashdb_mut(), address));
-			}
 			assert!(db.as_hashdb().contains(&self.state_root()));
 			Ok(true)
 		} else { Ok(false) }
@@ -257,10 +290,14 @@ impl Spec {
 
 	/// Loads spec from json file.
 	pub fn load<R>(reader: R) -> Result<Self, String> where R: Read {
-		match ethjson::spec::Spec::load(reader) {
-			Ok(spec) => Ok(spec.into()),
-			_ => Err("Spec json is invalid".into()),
+		let spec = try!(ethjson::spec::Spec::load(reader).map_err(|_| "Spec json is invalid".to_owned()));
+		if let Some(ref overrides) = spec.params.gas_schedule {
+			try!(Schedule::check_overrides(overrides));
+		}
+		if let Some(ref sync) = spec.hardcoded_sync {
+			try!(UntrustedRlp::new(&sync.header).as_val::<Header>().map_err(|e| format!("Invalid hardcoded sync header: {:?}", e)));
 		}
+		Ok(spec.into())
 	}
 
 	/// Create a new Spec which conforms to the Frontier-era Morden chain except that it's a NullEngine consensus.
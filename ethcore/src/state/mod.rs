@@ -349,6 +349,12 @@ impl State {
 			|a| a.as_ref().map_or(U256::zero(), |account| *account.balance()))
 	}
 
+	/// Get the storage root of account `a`.
+	pub fn storage_root(&self, a: &Address) -> Option<H256> {
+		self.ensure_cached(a, RequireCache::None,
+			|a| a.as_ref().and_then(|account| account.storage_root().cloned()))
+	}
+
 	/// Get the nonce of account `a`.
 	pub fn nonce(&self, a: &Address) -> U256 {
 		self.ensure_cached(a, RequireCache::None,
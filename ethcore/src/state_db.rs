@@ -14,14 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{VecDeque, HashSet};
+use std::collections::{VecDeque, HashSet, HashMap};
 use lru_cache::LruCache;
 use util::journaldb::JournalDB;
 use util::hash::{H256};
 use util::hashdb::HashDB;
+use util::memorydb::MemoryDB;
 use state::Account;
 use header::BlockNumber;
-use util::{Arc, Address, Database, DBTransaction, UtilError, Mutex, Hashable};
+use util::{Arc, Address, Bytes, Database, DBTransaction, UtilError, Mutex, Hashable};
 use bloom_journal::{Bloom, BloomJournal};
 use db::COL_ACCOUNT_BLOOM;
 use byteorder::{LittleEndian, ByteOrder};
@@ -29,6 +30,11 @@ use byteorder::{LittleEndian, ByteOrder};
 const STATE_CACHE_ITEMS: usize = 256000;
 const STATE_CACHE_BLOCKS: usize = 8;
 
+/// Estimated average size of a state trie node, used to turn cache size in bytes into a number of nodes.
+const STATE_NODE_AVERAGE_SIZE: usize = 256;
+/// Maximal memory used by nodes served from the node cache before the overlay holding them is cleared.
+const NODE_OVERLAY_SIZE: usize = 16 * 1024 * 1024;
+
 pub const ACCOUNT_BLOOM_SPACE: usize = 1048576;
 pub const DEFAULT_ACCOUNT_PRESET: usize = 1000000;
 
@@ -43,6 +49,98 @@ struct AccountCache {
 	modifications: VecDeque<BlockChanges>,
 }
 
+/// Shared cache of state trie nodes read from the backing database.
+struct NodeCache {
+	/// Trie nodes keyed by hash. Nodes are immutable, so entries never become stale.
+	nodes: LruCache<H256, Bytes>,
+	/// Number of lookups served from the cache.
+	hits: usize,
+	/// Number of lookups which had to hit the backing database.
+	misses: usize,
+}
+
+/// State trie node cache statistics.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct NodeCacheStats {
+	/// Number of lookups served from the cache.
+	pub hits: usize,
+	/// Number of lookups which had to hit the backing database.
+	pub misses: usize,
+}
+
+/// `HashDB` wrapping `JournalDB` which serves node reads from the shared node cache.
+struct CachingDB {
+	/// Backing database.
+	db: Box<JournalDB>,
+	/// Shared node cache.
+	cache: Arc<Mutex<NodeCache>>,
+	/// Nodes returned from the cache, kept so that references to them stay valid.
+	/// Cleared on commit and cache synchronization.
+	overlay: MemoryDB,
+	/// Memory the overlay may use. Once it's full, reads bypass the node cache.
+	overlay_limit: usize,
+}
+
+impl CachingDB {
+	fn boxed_clone(&self) -> CachingDB {
+		CachingDB {
+			db: self.db.boxed_clone(),
+			cache: self.cache.clone(),
+			overlay: MemoryDB::new(),
+			overlay_limit: self.overlay_limit,
+		}
+	}
+}
+
+impl HashDB for CachingDB {
+	fn keys(&self) -> HashMap<H256, i32> {
+		self.db.keys()
+	}
+
+	fn get(&self, key: &H256) -> Option<&[u8]> {
+		if let Some((node, _)) = self.overlay.raw(key) {
+			return Some(node);
+		}
+		if self.overlay.mem_used() >= self.overlay_limit {
+			return self.db.get(key);
+		}
+
+		let cached = {
+			let mut cache = self.cache.lock();
+			let node = cache.nodes.get_mut(key).cloned();
+			match node.is_some() {
+				true => cache.hits += 1,
+				false => cache.misses += 1,
+			}
+			node
+		};
+
+		match cached {
+			Some(node) => Some(self.overlay.denote(key, node).0),
+			None => self.db.get(key).map(|node| {
+				self.cache.lock().nodes.insert(key.clone(), node.to_vec());
+				node
+			}),
+		}
+	}
+
+	fn contains(&self, key: &H256) -> bool {
+		self.db.contains(key)
+	}
+
+	fn insert(&mut self, value: &[u8]) -> H256 {
+		self.db.insert(value)
+	}
+
+	fn emplace(&mut self, key: H256, value: Bytes) {
+		self.db.emplace(key, value)
+	}
+
+	fn remove(&mut self, key: &H256) {
+		self.db.remove(key)
+	}
+}
+
 /// Buffered account cache item.
 struct CacheQueueItem {
 	/// Account address.
@@ -84,8 +182,8 @@ struct BlockChanges {
 /// Then, after the block has been added to the chain the local cache in the
 /// `StateDB` is propagated into the global cache.
 pub struct StateDB {
-	/// Backing database.
-	db: Box<JournalDB>,
+	/// Backing database with trie node cache.
+	db: CachingDB,
 	/// Shared canonical state cache.
 	account_cache: Arc<Mutex<AccountCache>>,
 	/// Local dirty cache.
@@ -129,11 +227,20 @@ impl StateDB {
 		bloom
 	}
 
-	/// Create a new instance wrapping `JournalDB`
-	pub fn new(db: Box<JournalDB>) -> StateDB {
+	/// Create a new instance wrapping `JournalDB` with trie node cache of `node_cache_size` bytes.
+	pub fn new(db: Box<JournalDB>, node_cache_size: usize) -> StateDB {
 		let bloom = Self::load_bloom(db.backing());
 		StateDB {
-			db: db,
+			db: CachingDB {
+				db: db,
+				cache: Arc::new(Mutex::new(NodeCache {
+					nodes: LruCache::new(node_cache_size / STATE_NODE_AVERAGE_SIZE),
+					hits: 0,
+					misses: 0,
+				})),
+				overlay: MemoryDB::new(),
+				overlay_limit: NODE_OVERLAY_SIZE,
+			},
 			account_cache: Arc::new(Mutex::new(AccountCache {
 				accounts: LruCache::new(STATE_CACHE_ITEMS),
 				modifications: VecDeque::new(),
@@ -179,7 +286,8 @@ impl StateDB {
  			let mut bloom_lock = self.account_bloom.lock();
  			try!(Self::commit_bloom(batch, bloom_lock.drain_journal()));
  		}
-		let records = try!(self.db.commit(batch, now, id, end));
+		let records = try!(self.db.db.commit(batch, now, id, end));
+		self.db.overlay.clear();
 		self.commit_hash = Some(id.clone());
 		self.commit_number = Some(now);
 		Ok(records)
@@ -193,6 +301,7 @@ impl StateDB {
 	/// blockchain route has ben calculated.
 	pub fn sync_cache(&mut self, enacted: &[H256], retracted: &[H256], is_best: bool) {
 		trace!("sync_cache id = (#{:?}, {:?}), parent={:?}, best={}", self.commit_number, self.commit_hash, self.parent_hash, is_best);
+		self.db.overlay.clear();
 		let mut cache = self.account_cache.lock();
 		let mut cache = &mut *cache;
 
@@ -283,12 +392,12 @@ impl StateDB {
 
 	/// Returns an interface to HashDB.
 	pub fn as_hashdb(&self) -> &HashDB {
-		self.db.as_hashdb()
+		&self.db
 	}
 
 	/// Returns an interface to mutable HashDB.
 	pub fn as_hashdb_mut(&mut self) -> &mut HashDB {
-		self.db.as_hashdb_mut()
+		&mut self.db
 	}
 
 	/// Clone the database.
@@ -319,17 +428,51 @@ impl StateDB {
 
 	/// Check if pruning is enabled on the database.
 	pub fn is_pruned(&self) -> bool {
-		self.db.is_pruned()
+		self.db.db.is_pruned()
 	}
 
 	/// Heap size used.
 	pub fn mem_used(&self) -> usize {
-		self.db.mem_used() //TODO: + self.account_cache.lock().heap_size_of_children()
+		self.db.db.mem_used() //TODO: + self.account_cache.lock().heap_size_of_children()
+	}
+
+	/// Drops all cached accounts and trie nodes.
+	pub fn clear_cache(&self) {
+		{
+			let mut cache = self.account_cache.lock();
+			cache.accounts.clear();
+			cache.modifications.clear();
+		}
+		self.db.cache.lock().nodes.clear();
+	}
+
+	/// Approximate memory used by the trie node cache, in bytes.
+	pub fn node_cache_size(&self) -> usize {
+		self.db.cache.lock().nodes.len() * STATE_NODE_AVERAGE_SIZE
+	}
+
+	/// Approximate memory the trie node cache may use, in bytes.
+	pub fn node_cache_limit(&self) -> usize {
+		self.db.cache.lock().nodes.capacity() * STATE_NODE_AVERAGE_SIZE
+	}
+
+	/// Changes the size of the trie node cache, dropping least recently used nodes if needed.
+	pub fn set_node_cache_limit(&self, node_cache_size: usize) {
+		self.db.cache.lock().nodes.set_capacity(node_cache_size / STATE_NODE_AVERAGE_SIZE);
+	}
+
+	/// Returns trie node cache statistics.
+	pub fn node_cache_stats(&self) -> NodeCacheStats {
+		let cache = self.db.cache.lock();
+		NodeCacheStats {
+			hits: cache.hits,
+			misses: cache.misses,
+		}
 	}
 
 	/// Returns underlying `JournalDB`.
 	pub fn journal_db(&self) -> &JournalDB {
-		&*self.db
+		&*self.db.db
 	}
 
 	/// Add a local cache entry.
@@ -408,6 +551,7 @@ use util::{U256, H256, FixedHash, Address, DBTransaction};
 use tests::helpers::*;
 use state::Account;
 use util::log::init_log;
+use super::NodeCacheStats;
 
 #[test]
 fn state_db_smoke() {
@@ -476,5 +620,46 @@ fn state_db_smoke() {
 	let s = state_db.boxed_clone_canon(&h3a);
 	assert!(s.get_cached_account(&address).is_none());
 }
+
+#[test]
+fn node_cache_serves_repeated_reads() {
+	let mut state_db_result = get_temp_state_db();
+	let mut state_db = state_db_result.take();
+	let mut batch = DBTransaction::new(state_db.journal_db().backing());
+	let key = state_db.as_hashdb_mut().insert(b"node");
+	state_db.commit(&mut batch, 0, &H256::random(), None).unwrap();
+	state_db.journal_db().backing().write(batch).unwrap();
+
+	let s = state_db.boxed_clone();
+	assert_eq!(s.as_hashdb().get(&key), Some(&b"node"[..]));
+	assert_eq!(s.node_cache_stats(), NodeCacheStats { hits: 0, misses: 1 });
+
+	let s = state_db.boxed_clone();
+	assert_eq!(s.as_hashdb().get(&key), Some(&b"node"[..]));
+	assert_eq!(s.node_cache_stats(), NodeCacheStats { hits: 1, misses: 1 });
 }
 
+#[test]
+fn node_overlay_is_bounded_and_cleared_on_sync() {
+	let mut state_db_result = get_temp_state_db();
+	let mut state_db = state_db_result.take();
+	let mut batch = DBTransaction::new(state_db.journal_db().backing());
+	let key = state_db.as_hashdb_mut().insert(b"node");
+	state_db.commit(&mut batch, 0, &H256::random(), None).unwrap();
+	state_db.journal_db().backing().write(batch).unwrap();
+	assert_eq!(state_db.boxed_clone().as_hashdb().get(&key), Some(&b"node"[..]));
+
+	// cached node is kept in the overlay until the cache is synchronized
+	let mut s = state_db.boxed_clone();
+	assert_eq!(s.as_hashdb().get(&key), Some(&b"node"[..]));
+	assert!(s.db.overlay.raw(&key).is_some());
+	s.sync_cache(&[], &[], false);
+	assert!(s.db.overlay.raw(&key).is_none());
+
+	// full overlay is not extended
+	let mut s = state_db.boxed_clone();
+	s.db.overlay_limit = 0;
+	assert_eq!(s.as_hashdb().get(&key), Some(&b"node"[..]));
+	assert!(s.db.overlay.raw(&key).is_none());
+}
+}
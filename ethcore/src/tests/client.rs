@@ -14,8 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::time::Duration;
 use io::IoChannel;
-use client::{BlockChainClient, MiningBlockChainClient, Client, ClientConfig, BlockID};
+use client::{BlockChainClient, MiningBlockChainClient, Client, ClientConfig, BlockID, IpcMode, Mode};
 use ethereum;
 use block::IsBlock;
 use tests::helpers::*;
@@ -24,6 +25,10 @@ use common::*;
 use devtools::*;
 use miner::Miner;
 use rlp::{Rlp, View};
+use ethkey::KeyPair;
+use types::account_diff::Diff;
+use executive::contract_address;
+use spec::Spec;
 
 #[test]
 fn imports_from_empty() {
@@ -188,6 +193,162 @@ fn imports_block_sequence() {
 	assert!(!block.is_empty());
 }
 
+#[test]
+fn quarantines_blocks_exceeding_reorg_limit() {
+	let dir = RandomTempPath::new();
+	let spec = get_test_spec();
+	let db_config = DatabaseConfig::with_columns(::db::NUM_COLUMNS);
+	let mut config = ClientConfig::default();
+	config.max_reorg_depth = Some(2);
+
+	let client = Client::new(
+		config,
+		&spec,
+		dir.as_path(),
+		Arc::new(Miner::with_spec(&spec)),
+		IoChannel::disconnected(),
+		&db_config
+	).unwrap();
+
+	// canonical chain of 4 blocks
+	for block in get_good_dummy_block_seq(3) {
+		client.import_block(block).unwrap();
+	}
+	client.flush_queue();
+	client.import_verified_blocks();
+	let best_hash = client.chain_info().best_block_hash;
+
+	// heavier fork of 5 blocks from genesis
+	let mut parent = spec.genesis_header().hash();
+	for i in 1..6u64 {
+		let mut header = Header::new();
+		header.set_gas_limit(spec.engine.params().min_gas_limit);
+		header.set_difficulty(U256::from(i).mul(U256([0, 1, 0, 0])));
+		header.set_timestamp(i * 10 + 5);
+		header.set_number(i);
+		header.set_parent_hash(parent);
+		header.set_state_root(spec.genesis_header().state_root().clone());
+		parent = header.hash();
+		client.import_block(create_test_block(&header)).unwrap();
+	}
+	client.flush_queue();
+	client.import_verified_blocks();
+
+	assert_eq!(client.chain_info().best_block_hash, best_hash);
+	let warnings = client.chain_warnings();
+	assert_eq!(warnings.len(), 1);
+	assert_eq!(warnings[0].block_hash, parent);
+	assert_eq!(warnings[0].block_number, 5);
+	assert_eq!(warnings[0].reorg_depth, 4);
+
+	// released by the operator
+	assert!(client.release_quarantined_block(parent));
+	client.flush_queue();
+	client.import_verified_blocks();
+
+	assert_eq!(client.chain_info().best_block_hash, parent);
+	assert!(client.chain_warnings().is_empty());
+	assert!(!client.release_quarantined_block(parent));
+}
+
+#[test]
+fn prunes_ancient_bodies() {
+	let dir = RandomTempPath::new();
+	let spec = get_test_spec();
+	let db_config = DatabaseConfig::with_columns(::db::NUM_COLUMNS);
+	let mut config = ClientConfig::default();
+	config.prune_ancient_bodies = Some(2);
+
+	let client = Client::new(
+		config,
+		&spec,
+		dir.as_path(),
+		Arc::new(Miner::with_spec(&spec)),
+		IoChannel::disconnected(),
+		&db_config
+	).unwrap();
+
+	for block in get_good_dummy_block_seq(5) {
+		client.import_block(block).unwrap();
+	}
+	client.flush_queue();
+	client.import_verified_blocks();
+
+	assert_eq!(client.chain_info().best_block_number, 5);
+	assert!(client.block_header(BlockID::Number(2)).is_some());
+	assert!(client.block_body(BlockID::Number(2)).is_none());
+	assert!(client.is_pruned(BlockID::Number(2)));
+	assert!(client.block_body(BlockID::Number(3)).is_some());
+	assert!(!client.is_pruned(BlockID::Number(3)));
+	assert!(!client.is_pruned(BlockID::Number(6)));
+	assert!(client.check_integrity(64, false).is_ok());
+}
+
+#[test]
+fn calls_see_effects_of_previous_calls() {
+	use transaction::{Transaction, Action};
+
+	let client_result = generate_dummy_client(0);
+	let client = client_result.reference();
+	let sender = Address::from(10);
+	let contract = contract_address(&sender, &client.latest_nonce(&sender));
+
+	// deploys code returning 42.
+	let create = Transaction {
+		nonce: U256::zero(),
+		action: Action::Create,
+		gas: U256::from(1_000_000),
+		gas_price: U256::zero(),
+		value: U256::zero(),
+		data: "600a600c600039600a6000f3602a60005260206000f3".from_hex().unwrap(),
+	}.fake_sign(sender);
+	let call = Transaction {
+		nonce: U256::one(),
+		action: Action::Call(contract),
+		gas: U256::from(1_000_000),
+		gas_price: U256::zero(),
+		value: U256::zero(),
+		data: vec![],
+	}.fake_sign(sender);
+
+	let results = client.call_many(&[create, call.clone()], BlockID::Latest, Default::default()).unwrap();
+	assert_eq!(results.len(), 2);
+	assert_eq!(results[1].output, H256::from(42).to_vec());
+
+	// on its own the call hits an empty account.
+	let alone = client.call(&call, BlockID::Latest, Default::default()).unwrap();
+	assert!(alone.output.is_empty());
+}
+
+#[test]
+fn calls_private_contracts_with_given_code_and_storage() {
+	use std::collections::BTreeMap;
+	use transaction::{Transaction, Action};
+
+	let client_result = generate_dummy_client(0);
+	let client = client_result.reference();
+	let contract = Address::from(20);
+	// stores the first argument under 0x00, returns the value under 0x01.
+	let code = "60003560005560015460005260206000f3".from_hex().unwrap();
+	let mut storage = BTreeMap::new();
+	storage.insert(H256::from(0), H256::from(3));
+	storage.insert(H256::from(1), H256::from(7));
+	let call = Transaction {
+		nonce: U256::zero(),
+		action: Action::Call(contract),
+		gas: U256::from(1_000_000),
+		gas_price: U256::zero(),
+		value: U256::zero(),
+		data: H256::from(0).to_vec(),
+	}.fake_sign(Address::from(10));
+
+	let (executed, storage) = client.call_private(&call, code, storage).unwrap();
+
+	assert_eq!(executed.output, H256::from(7).to_vec());
+	assert_eq!(storage.into_iter().collect::<Vec<_>>(), vec![(H256::from(1), H256::from(7))]);
+	assert_eq!(client.code(&contract, BlockID::Latest), Some(None));
+}
+
 #[test]
 fn can_collect_garbage() {
 	let client_result = generate_dummy_client(100);
@@ -196,6 +357,66 @@ fn can_collect_garbage() {
 	assert!(client.blockchain_cache_info().blocks < 100 * 1024);
 }
 
+#[test]
+fn passes_integrity_check() {
+	let client_result = generate_dummy_client(20);
+	let client = client_result.reference();
+	let report = client.check_integrity(64, true);
+	assert!(report.is_ok());
+	assert_eq!(report.blocks_checked, 21);
+	assert!(report.accounts_checked > 0);
+}
+
+#[test]
+fn controls_development_chain() {
+	let client_result = generate_dummy_client_with_spec_and_data(Spec::new_test_instant, 0, 0, &[]);
+	let client = client_result.reference();
+	let address = Address::from(0xaa);
+
+	assert_eq!(client.dev_mine_blocks(2), Ok(2));
+	assert!(client.dev_mine_blocks(1025).is_err());
+	client.dev_set_balance(address.clone(), 1000.into()).unwrap();
+	assert_eq!(client.chain_info().best_block_number, 3);
+	assert_eq!(client.balance(&address, BlockID::Latest), Some(1000.into()));
+	client.dev_set_balance(address.clone(), 10.into()).unwrap();
+	assert_eq!(client.balance(&address, BlockID::Latest), Some(10.into()));
+
+	client.dev_reset().unwrap();
+	assert_eq!(client.chain_info().best_block_number, 0);
+	assert_eq!(client.balance(&address, BlockID::Latest), Some(0.into()));
+	assert_eq!(client.dev_mine_blocks(1), Ok(1));
+}
+
+#[test]
+fn rejects_development_chain_control() {
+	let client_result = generate_dummy_client(0);
+	let client = client_result.reference();
+
+	assert!(client.dev_mine_blocks(1).is_err());
+	assert!(client.dev_set_balance(Address::from(0xaa), 1000.into()).is_err());
+	assert!(client.dev_reset().is_err());
+	assert_eq!(client.chain_info().best_block_number, 0);
+}
+
+#[test]
+fn can_switch_mode() {
+	let client_result = generate_dummy_client(0);
+	let client = client_result.reference();
+	let changes = Arc::new(Mutex::new(Vec::new()));
+	let c = changes.clone();
+	client.on_mode_change(move |mode: &Mode| c.lock().push(mode.clone()));
+
+	client.set_mode(IpcMode::Off);
+	assert_eq!(client.mode(), IpcMode::Off);
+	assert!(client.is_hibernating());
+
+	client.set_mode(IpcMode::Dark(300));
+	assert_eq!(client.mode(), IpcMode::Dark(300));
+	assert!(!client.is_hibernating());
+
+	assert_eq!(*changes.lock(), vec![Mode::Off, Mode::Dark(Duration::from_secs(300))]);
+}
+
 #[test]
 #[cfg_attr(feature="dev", allow(useless_vec))]
 fn can_generate_gas_price_statistics() {
@@ -228,6 +449,57 @@ fn can_handle_long_fork() {
 	assert_eq!(2000, client.chain_info().best_block_number);
 }
 
+#[test]
+fn returns_account_diff_between_blocks() {
+	let client_result = generate_dummy_client_with_data(3, 1, &[1.into()]);
+	let client = client_result.reference();
+	let author = KeyPair::from_secret("".sha3()).unwrap().address();
+
+	let diff = client.account_diff(&author, BlockID::Number(1), BlockID::Number(3)).unwrap().unwrap();
+	assert_eq!(diff.nonce, Diff::Changed(1.into(), 3.into()));
+	assert!(client.account_diff(&author, BlockID::Number(3), BlockID::Number(3)).unwrap().is_none());
+}
+
+#[test]
+fn returns_transactions_by_address() {
+	let config = ClientConfig { address_index: true, ..Default::default() };
+	let client_result = generate_dummy_client_with_spec_config_and_data(Spec::new_null, config, 3, 2, &[1.into()]);
+	let client = client_result.reference();
+	let author = KeyPair::from_secret("".sha3()).unwrap().address();
+
+	let txs = client.address_transactions(&author, 0, 10).unwrap();
+	assert_eq!(txs.len(), 6);
+	assert_eq!(txs[0].block_number, 1);
+	assert_eq!(txs[5].block_number, 3);
+	assert_eq!(txs[5].transaction_index, 1);
+
+	let page = client.address_transactions(&author, 4, 10).unwrap();
+	assert_eq!(page.len(), 2);
+	assert_eq!(page[0].hash(), txs[4].hash());
+
+	let created = contract_address(&author, &0.into());
+	assert_eq!(client.address_transactions(&created, 0, 10).unwrap().len(), 1);
+}
+
+#[test]
+fn address_transactions_require_index() {
+	let client_result = generate_dummy_client_with_data(1, 1, &[1.into()]);
+	let client = client_result.reference();
+	assert!(client.address_transactions(&Address::default(), 0, 10).is_none());
+}
+
+#[test]
+fn resizes_caches() {
+	let client_result = generate_dummy_client(0);
+	let client = client_result.reference();
+
+	client.set_cache_limits(2 * 1024 * 1024, 1024 * 1024, 8 * 1024 * 1024);
+	let usage = client.memory_usage();
+	assert_eq!(usage.blockchain.limit, 2 * 1024 * 1024);
+	assert_eq!(usage.state.limit, 1024 * 1024);
+	assert_eq!(usage.block_queue.limit, 8 * 1024 * 1024);
+}
+
 #[test]
 fn can_mine() {
 	let dummy_blocks = get_good_dummy_block_seq(2);
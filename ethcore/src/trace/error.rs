@@ -23,7 +23,7 @@ const RESYNC_ERR: &'static str =
 To use Parity with transaction tracing, you'll need to resync with tracing.
 To do this, remove or move away your current database and restart parity. e.g.:
 
-> mv ~/.parity/906a34e69aec8c0d /tmp
+> mv ~/.parity/chains/906a34e69aec8c0d /tmp
 > parity";
 
 /// `TraceDB` errors.
@@ -23,6 +23,7 @@ mod error;
 mod executive_tracer;
 mod import;
 mod noop_tracer;
+mod transfer_tracer;
 
 pub use types::trace_types::{filter, flat, localized, trace};
 pub use types::trace_types::error::Error as TraceError;
@@ -33,6 +34,7 @@ pub use types::trace_types::trace::{VMTrace, VMOperation, VMExecutedOperation, M
 pub use types::trace_types::flat::{FlatTrace, FlatTransactionTraces, FlatBlockTraces};
 pub use self::noop_tracer::{NoopTracer, NoopVMTracer};
 pub use self::executive_tracer::{ExecutiveTracer, ExecutiveVMTracer};
+pub use self::transfer_tracer::TransferTracer;
 pub use types::trace_types::filter::{Filter, AddressesFilter};
 pub use self::import::ImportRequest;
 pub use self::localized::LocalizedTrace;
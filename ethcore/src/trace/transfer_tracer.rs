@@ -0,0 +1,119 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracer recording value transfers only.
+
+use util::{Bytes, Address, U256};
+use action_params::{ActionParams, ActionValue};
+use trace::trace::{Call, Create, Action, Res, CreateResult, CallResult, Suicide};
+use trace::{Tracer, FlatTrace, TraceError};
+
+/// Records value transfers made by calls, creates and suicides which were not reverted.
+///
+/// Unlike `ExecutiveTracer` it neither copies inputs, init code nor outputs,
+/// and does not keep trace addresses: transfers are returned as a flat list in execution order.
+#[derive(Default)]
+pub struct TransferTracer {
+	transfers: Vec<FlatTrace>,
+}
+
+fn transferred_value(params: &ActionParams) -> Option<U256> {
+	match params.value {
+		ActionValue::Transfer(value) if !value.is_zero() && params.sender != params.address => Some(value),
+		_ => None,
+	}
+}
+
+fn transfer(action: Action, result: Res) -> FlatTrace {
+	FlatTrace {
+		action: action,
+		result: result,
+		subtraces: 0,
+		trace_address: Default::default(),
+	}
+}
+
+impl Tracer for TransferTracer {
+	fn prepare_trace_call(&self, params: &ActionParams) -> Option<Call> {
+		transferred_value(params).map(|value| Call {
+			from: params.sender.clone(),
+			to: params.address.clone(),
+			value: value,
+			gas: params.gas,
+			input: Vec::new(),
+			call_type: params.call_type.clone(),
+		})
+	}
+
+	fn prepare_trace_create(&self, params: &ActionParams) -> Option<Create> {
+		transferred_value(params).map(|value| Create {
+			from: params.sender.clone(),
+			value: value,
+			gas: params.gas,
+			init: Vec::new(),
+		})
+	}
+
+	fn prepare_trace_output(&self) -> Option<Bytes> {
+		None
+	}
+
+	fn trace_call(&mut self, call: Option<Call>, gas_used: U256, _: Option<Bytes>, subs: Vec<FlatTrace>) {
+		if let Some(call) = call {
+			self.transfers.push(transfer(Action::Call(call), Res::Call(CallResult {
+				gas_used: gas_used,
+				output: Vec::new(),
+			})));
+		}
+		self.transfers.extend(subs);
+	}
+
+	fn trace_create(&mut self, create: Option<Create>, gas_used: U256, _: Option<Bytes>, address: Address, subs: Vec<FlatTrace>) {
+		if let Some(create) = create {
+			self.transfers.push(transfer(Action::Create(create), Res::Create(CreateResult {
+				gas_used: gas_used,
+				code: Vec::new(),
+				address: address,
+			})));
+		}
+		self.transfers.extend(subs);
+	}
+
+	fn trace_failed_call(&mut self, _: Option<Call>, _: Vec<FlatTrace>, _: TraceError) {
+		// everything done by a failed call is reverted, including nested transfers.
+	}
+
+	fn trace_failed_create(&mut self, _: Option<Create>, _: Vec<FlatTrace>, _: TraceError) {
+	}
+
+	fn trace_suicide(&mut self, address: Address, balance: U256, refund_address: Address) {
+		if !balance.is_zero() {
+			self.transfers.push(transfer(Action::Suicide(Suicide {
+				address: address,
+				refund_address: refund_address,
+				balance: balance,
+			}), Res::None));
+		}
+	}
+
+	fn subtracer(&self) -> Self {
+		TransferTracer::default()
+	}
+
+	fn traces(self) -> Vec<FlatTrace> {
+		self.transfers
+	}
+}
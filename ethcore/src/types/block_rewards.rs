@@ -0,0 +1,29 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Block rewards type
+
+use util::U256;
+
+/// Rewards issued by the consensus engine for a block, excluding transaction fees.
+#[derive(Debug, Default, Eq, PartialEq, Clone, Binary)]
+pub struct BlockRewards {
+	/// Reward of the block author, including the reward for including uncles.
+	pub author: U256,
+	/// Rewards of authors of the block's uncles, in order of the uncles.
+	pub uncles: Vec<U256>,
+}
@@ -25,4 +25,6 @@ pub struct CallAnalytics {
 	pub vm_tracing: bool,
 	/// Make a diff.
 	pub state_diffing: bool,
+	/// Record value transfers only, in place of the transaction trace.
+	pub transfer_tracing: bool,
 }
@@ -0,0 +1,34 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chain warning type
+
+use util::H256;
+use header::BlockNumber;
+
+/// Block which was quarantined because importing it would reorganize
+/// the chain deeper than the configured limit.
+#[derive(Debug, Eq, PartialEq, Clone, Binary)]
+pub struct ChainWarning {
+	/// Quarantined block hash.
+	pub block_hash: H256,
+	/// Quarantined block number.
+	pub block_number: BlockNumber,
+	/// Number of canonical blocks the import would have reverted.
+	pub reorg_depth: u64,
+	/// Unix timestamp of the moment the block was quarantined.
+	pub timestamp: u64,
+}
@@ -98,6 +98,9 @@ pub struct Executed {
 	pub vm_trace: Option<VMTrace>,
 	/// The state diff, if we traced it.
 	pub state_diff: Option<StateDiff>,
+	/// Whether execution was stopped by a `REVERT` instruction.
+	/// In that case `output` holds the revert payload.
+	pub reverted: bool,
 }
 
 /// Result of executing the transaction.
@@ -176,6 +179,8 @@ pub enum CallError {
 	StatePruned,
 	/// Error executing.
 	Execution(ExecutionError),
+	/// Requested block range exceeds the maximal number of blocks replayed.
+	RangeTooLarge(u64),
 }
 
 impl From<ExecutionError> for CallError {
@@ -192,6 +197,7 @@ impl fmt::Display for CallError {
 			TransactionNotFound => "Transaction couldn't be found in the chain".into(),
 			StatePruned => "Couldn't find the transaction block's state in the chain".into(),
 			Execution(ref e) => format!("{}", e),
+			RangeTooLarge(max) => format!("Block range is longer than {} blocks", max),
 		};
 
 		f.write_fmt(format_args!("Transaction execution error ({}).", msg))
@@ -0,0 +1,38 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Memory usage of caches.
+
+/// Memory used by a cache and the limit it's kept under, in bytes.
+#[derive(Debug, Default, Clone, PartialEq, Binary)]
+pub struct CacheUsage {
+	/// Bytes currently used.
+	pub used: usize,
+	/// Configured limit.
+	pub limit: usize,
+}
+
+/// Memory used by caches of the client.
+#[derive(Debug, Default, Clone, PartialEq, Binary)]
+pub struct ClientMemoryUsage {
+	/// Blockchain cache: headers, bodies, receipts and their indexes.
+	pub blockchain: CacheUsage,
+	/// State trie node cache.
+	pub state: CacheUsage,
+	/// Blocks waiting for verification and import.
+	pub block_queue: CacheUsage,
+}
@@ -0,0 +1,55 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Mode type
+
+use std::time::Duration;
+use client::Mode as ClientMode;
+
+/// Mode of the client in IPC-friendly form.
+#[derive(Debug, Eq, PartialEq, Clone, Binary)]
+pub enum IpcMode {
+	/// Always on.
+	Active,
+	/// Goes offline after given number of seconds of inactivity and wakes up after another given number of seconds.
+	Passive(u64, u64),
+	/// Goes offline after given number of seconds of inactivity.
+	Dark(u64),
+	/// Always off.
+	Off,
+}
+
+impl From<ClientMode> for IpcMode {
+	fn from(mode: ClientMode) -> Self {
+		match mode {
+			ClientMode::Active => IpcMode::Active,
+			ClientMode::Passive(timeout, alarm) => IpcMode::Passive(timeout.as_secs(), alarm.as_secs()),
+			ClientMode::Dark(timeout) => IpcMode::Dark(timeout.as_secs()),
+			ClientMode::Off => IpcMode::Off,
+		}
+	}
+}
+
+impl From<IpcMode> for ClientMode {
+	fn from(mode: IpcMode) -> Self {
+		match mode {
+			IpcMode::Active => ClientMode::Active,
+			IpcMode::Passive(timeout, alarm) => ClientMode::Passive(Duration::from_secs(timeout), Duration::from_secs(alarm)),
+			IpcMode::Dark(timeout) => ClientMode::Dark(Duration::from_secs(timeout)),
+			IpcMode::Off => ClientMode::Off,
+		}
+	}
+}
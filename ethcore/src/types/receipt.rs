@@ -91,6 +91,8 @@ pub struct RichReceipt {
 	pub gas_used: U256,
 	/// Contract address.
 	pub contract_address: Option<Address>,
+	/// The state root after executing the transaction.
+	pub state_root: H256,
 	/// Logs
 	pub logs: Vec<LogEntry>,
 }
@@ -112,7 +114,9 @@ pub struct LocalizedReceipt {
 	pub gas_used: U256,
 	/// Contract address.
 	pub contract_address: Option<Address>,
-	/// Logs
+	/// The state root after executing the transaction.
+	pub state_root: H256,
+	/// Logs (with log indices relative to the whole block).
 	pub logs: Vec<LocalizedLogEntry>,
 }
 
@@ -34,6 +34,8 @@ pub enum Error {
 	StackUnderflow,
 	/// When execution would exceed defined Stack Limit
 	OutOfStack,
+	/// `Reverted` is returned when execution hit a `REVERT` instruction.
+	Reverted,
 	/// Returned on evm internal error. Should never be ignored during development.
 	/// Likely to cause consensus issues.
 	Internal,
@@ -47,6 +49,7 @@ impl From<EvmError> for Error {
 			EvmError::BadInstruction { .. } => Error::BadInstruction,
 			EvmError::StackUnderflow { .. } => Error::StackUnderflow,
 			EvmError::OutOfStack { .. } => Error::OutOfStack,
+			EvmError::Reverted { .. } => Error::Reverted,
 			EvmError::Internal => Error::Internal,
 		}
 	}
@@ -61,6 +64,7 @@ impl fmt::Display for Error {
 			BadInstruction => "Bad instruction",
 			StackUnderflow => "Stack underflow",
 			OutOfStack => "Out of stack",
+			Reverted => "Reverted",
 			Internal => "Internal error",
 		};
 		message.fmt(f)
@@ -77,6 +81,7 @@ impl Encodable for Error {
 			StackUnderflow => 3,
 			OutOfStack => 4,
 			Internal => 5,
+			Reverted => 6,
 		};
 		s.append(&value);
 	}
@@ -93,6 +98,7 @@ impl Decodable for Error {
 			3 => Ok(StackUnderflow),
 			4 => Ok(OutOfStack),
 			5 => Ok(Internal),
+			6 => Ok(Reverted),
 			_ => Err(DecoderError::Custom("Invalid error type")),
 		}
 	}
@@ -31,6 +31,8 @@ pub struct VerificationQueueInfo {
 	pub max_mem_use: usize,
 	/// Heap memory used in bytes
 	pub mem_used: usize,
+	/// Number of threads verifying queued items
+	pub verifier_count: usize,
 }
 
 impl VerificationQueueInfo {
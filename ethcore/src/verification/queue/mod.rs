@@ -18,7 +18,7 @@
 //! Sorts them ready for blockchain insertion.
 
 use std::thread::{JoinHandle, self};
-use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::{Condvar as SCondvar, Mutex as SMutex};
 use util::*;
 use io::*;
@@ -50,6 +50,12 @@ pub struct Config {
 	/// Maximum heap memory to use.
 	/// When the limit is reached, is_full returns true.
 	pub max_mem_use: usize,
+	/// Number of threads verifying items ahead of import.
+	/// Defaults to the number of cores less two (but at least one) when not set.
+	pub verifier_count: Option<usize>,
+	/// Adjust the number of active verifiers to the backlog, using at most `verifier_count`
+	/// (or the number of cores when not set) threads.
+	pub scale_verifiers: bool,
 }
 
 impl Default for Config {
@@ -57,6 +63,8 @@ impl Default for Config {
 		Config {
 			max_queue_size: 30000,
 			max_mem_use: 50 * 1024 * 1024,
+			verifier_count: None,
+			scale_verifiers: false,
 		}
 	}
 }
@@ -96,7 +104,9 @@ pub struct VerificationQueue<K: Kind> {
 	empty: Arc<SCondvar>,
 	processing: RwLock<HashSet<H256>>,
 	max_queue_size: usize,
-	max_mem_use: usize,
+	max_mem_use: AtomicUsize,
+	scale_verifiers: bool,
+	active_verifiers: Arc<AtomicUsize>,
 }
 
 struct QueueSignal {
@@ -158,7 +168,16 @@ impl<K: Kind> VerificationQueue<K> {
 		let panic_handler = PanicHandler::new_in_arc();
 
 		let mut verifiers: Vec<JoinHandle<()>> = Vec::new();
-		let thread_count = max(::num_cpus::get(), 3) - 2;
+		let thread_count = match (config.verifier_count, config.scale_verifiers) {
+			(Some(count), _) => max(count, 1),
+			(None, true) => ::num_cpus::get(),
+			(None, false) => max(::num_cpus::get(), 3) - 2,
+		};
+		// when scaling, start with half of the threads and let `collect_garbage` adjust.
+		let active_verifiers = Arc::new(AtomicUsize::new(match config.scale_verifiers {
+			true => max(thread_count / 2, 1),
+			false => thread_count,
+		}));
 		for i in 0..thread_count {
 			let verification = verification.clone();
 			let engine = engine.clone();
@@ -167,12 +186,13 @@ impl<K: Kind> VerificationQueue<K> {
 			let empty = empty.clone();
 			let deleting = deleting.clone();
 			let panic_handler = panic_handler.clone();
+			let active_verifiers = active_verifiers.clone();
 			verifiers.push(
 				thread::Builder::new()
 				.name(format!("Verifier #{}", i))
 				.spawn(move || {
 					panic_handler.catch_panic(move || {
-						VerificationQueue::verify(verification, engine, more_to_verify, ready_signal, deleting, empty)
+						VerificationQueue::verify(i, verification, engine, more_to_verify, ready_signal, deleting, empty, active_verifiers)
 					}).unwrap()
 				})
 				.expect("Error starting block verification thread")
@@ -189,11 +209,14 @@ impl<K: Kind> VerificationQueue<K> {
 			processing: RwLock::new(HashSet::new()),
 			empty: empty.clone(),
 			max_queue_size: max(config.max_queue_size, MIN_QUEUE_LIMIT),
-			max_mem_use: max(config.max_mem_use, MIN_MEM_LIMIT),
+			max_mem_use: AtomicUsize::new(max(config.max_mem_use, MIN_MEM_LIMIT)),
+			scale_verifiers: config.scale_verifiers,
+			active_verifiers: active_verifiers,
 		}
 	}
 
-	fn verify(verification: Arc<Verification<K>>, engine: Arc<Engine>, wait: Arc<SCondvar>, ready: Arc<QueueSignal>, deleting: Arc<AtomicBool>, empty: Arc<SCondvar>) {
+	#[cfg_attr(feature="dev", allow(too_many_arguments))]
+	fn verify(id: usize, verification: Arc<Verification<K>>, engine: Arc<Engine>, wait: Arc<SCondvar>, ready: Arc<QueueSignal>, deleting: Arc<AtomicBool>, empty: Arc<SCondvar>, active: Arc<AtomicUsize>) {
 		while !deleting.load(AtomicOrdering::Acquire) {
 			{
 				let mut more_to_verify = verification.more_to_verify.lock().unwrap();
@@ -202,7 +225,8 @@ impl<K: Kind> VerificationQueue<K> {
 					empty.notify_all();
 				}
 
-				while verification.unverified.lock().is_empty() && !deleting.load(AtomicOrdering::Acquire) {
+				// threads beyond the active count sleep until the queue scales up again.
+				while (verification.unverified.lock().is_empty() || id >= active.load(AtomicOrdering::Acquire)) && !deleting.load(AtomicOrdering::Acquire) {
 					more_to_verify = wait.wait(more_to_verify).unwrap();
 				}
 
@@ -390,6 +414,11 @@ impl<K: Kind> VerificationQueue<K> {
 		result
 	}
 
+	/// Changes the memory limit of the queue. Items already queued are kept.
+	pub fn set_max_mem_use(&self, max_mem_use: usize) {
+		self.max_mem_use.store(max(max_mem_use, MIN_MEM_LIMIT), AtomicOrdering::Relaxed);
+	}
+
 	/// Get queue status.
 	pub fn queue_info(&self) -> QueueInfo {
 		let (unverified_len, unverified_bytes) = {
@@ -410,7 +439,8 @@ impl<K: Kind> VerificationQueue<K> {
 			verifying_queue_size: verifying_len,
 			verified_queue_size: verified_len,
 			max_queue_size: self.max_queue_size,
-			max_mem_use: self.max_mem_use,
+			max_mem_use: self.max_mem_use.load(AtomicOrdering::Relaxed),
+			verifier_count: self.active_verifiers.load(AtomicOrdering::Relaxed),
 			mem_used:
 				unverified_bytes
 				+ verifying_bytes
@@ -428,6 +458,34 @@ impl<K: Kind> VerificationQueue<K> {
 			self.verification.verified.lock().shrink_to_fit();
 		}
 		self.processing.write().shrink_to_fit();
+
+		if self.scale_verifiers {
+			let unverified_len = self.verification.unverified.lock().len();
+			let verified_len = self.verification.verified.lock().len();
+			let current = self.active_verifiers.load(AtomicOrdering::Acquire);
+			let target = scaled_verifiers(current, self.verifiers.len(), unverified_len, verified_len);
+			if target != current {
+				trace!(target: "verification", "Scaling verifiers from {} to {}", current, target);
+				// verifiers check the active count holding this lock, so none of them misses the wake-up.
+				let _more_to_verify = self.verification.more_to_verify.lock().unwrap();
+				self.active_verifiers.store(target, AtomicOrdering::Release);
+				if target > current {
+					self.more_to_verify.notify_all();
+				}
+			}
+		}
+	}
+}
+
+/// Number of verifiers to keep active given the sizes of the unverified and verified backlogs.
+/// Grows while verification falls behind import and shrinks once it has caught up.
+fn scaled_verifiers(current: usize, max_verifiers: usize, unverified: usize, verified: usize) -> usize {
+	if unverified > verified && current < max_verifiers {
+		current + 1
+	} else if (unverified == 0 || verified > unverified * 2) && current > 1 {
+		current - 1
+	} else {
+		current
 	}
 }
 
@@ -544,4 +602,33 @@ mod tests {
 		}
 		assert!(queue.queue_info().is_full());
 	}
+
+	#[test]
+	fn uses_configured_number_of_verifiers() {
+		let spec = get_test_spec();
+		let engine = spec.engine;
+		let mut config = Config::default();
+		config.verifier_count = Some(4);
+		let queue = BlockQueue::new(config, engine, IoChannel::disconnected());
+		assert_eq!(queue.queue_info().verifier_count, 4);
+	}
+
+	#[test]
+	fn scales_verifiers_with_backlog() {
+		let spec = get_test_spec();
+		let engine = spec.engine;
+		let mut config = Config::default();
+		config.verifier_count = Some(4);
+		config.scale_verifiers = true;
+		let queue = BlockQueue::new(config, engine, IoChannel::disconnected());
+		assert_eq!(queue.queue_info().verifier_count, 2);
+
+		// verification behind import.
+		assert_eq!(super::scaled_verifiers(2, 4, 100, 10), 3);
+		assert_eq!(super::scaled_verifiers(4, 4, 100, 10), 4);
+		// import behind verification.
+		assert_eq!(super::scaled_verifiers(3, 4, 10, 100), 2);
+		assert_eq!(super::scaled_verifiers(1, 4, 0, 0), 1);
+		assert_eq!(super::scaled_verifiers(2, 4, 10, 15), 2);
+	}
 }
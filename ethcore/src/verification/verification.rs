@@ -348,6 +348,11 @@ mod tests {
 			where F: Fn(&LogEntry) -> bool, Self: Sized {
 			unimplemented!()
 		}
+
+		fn logs_by_hash<F>(&self, _blocks: Vec<H256>, _matches: F, _limit: Option<usize>) -> Vec<LocalizedLogEntry>
+			where F: Fn(&LogEntry) -> bool, Self: Sized {
+			unimplemented!()
+		}
 	}
 
 	fn basic_test(bytes: &[u8], engine: &Engine) -> Result<(), Error> {
@@ -118,7 +118,7 @@ impl Crypto {
 
 		let mac = crypto::derive_mac(&derived_right_bits, &self.ciphertext).keccak256();
 
-		if mac != self.mac {
+		if !::rcrypto::util::fixed_time_eq(&mac, &self.mac) {
 			return Err(Error::InvalidPassword);
 		}
 
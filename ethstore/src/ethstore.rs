@@ -139,6 +139,23 @@ impl SecretStore for EthStore {
 		}
 	}
 
+	fn test_password(&self, address: &Address, password: &str) -> Result<bool, Error> {
+		let account = try!(self.get(address));
+		Ok(account.check_password(password))
+	}
+
+	fn export_account(&self, address: &Address, password: &str) -> Result<String, Error> {
+		let account = try!(self.get(address));
+		if !account.check_password(password) {
+			return Err(Error::InvalidPassword);
+		}
+
+		let keyfile: json::KeyFile = account.into();
+		let mut exported = Vec::new();
+		try!(keyfile.write(&mut exported).map_err(|e| Error::Custom(format!("{:?}", e))));
+		String::from_utf8(exported).map_err(|e| Error::Custom(format!("{:?}", e)))
+	}
+
 	fn sign(&self, address: &Address, password: &str, message: &Message) -> Result<Signature, Error> {
 		let account = try!(self.get(address));
 		account.sign(password, message)
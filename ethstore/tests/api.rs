@@ -88,6 +88,31 @@ fn secret_store_remove_account() {
 	assert!(store.remove_account(&accounts[0], "").is_err());
 }
 
+#[test]
+fn secret_store_test_password() {
+	let dir = TransientDir::create().unwrap();
+	let store = EthStore::open(Box::new(dir)).unwrap();
+	assert!(store.insert_account(random_secret(), "1").is_ok());
+	let accounts = store.accounts().unwrap();
+	assert!(store.test_password(&accounts[0], "1").unwrap());
+	assert!(!store.test_password(&accounts[0], "2").unwrap());
+}
+
+#[test]
+fn secret_store_export_account() {
+	let dir = TransientDir::create().unwrap();
+	let store = EthStore::open(Box::new(dir)).unwrap();
+	assert!(store.insert_account(random_secret(), "1").is_ok());
+	let accounts = store.accounts().unwrap();
+	assert!(store.export_account(&accounts[0], "2").is_err());
+	let exported = store.export_account(&accounts[0], "1").unwrap();
+
+	let other_dir = TransientDir::create().unwrap();
+	let other_store = EthStore::open(Box::new(other_dir)).unwrap();
+	assert_eq!(other_store.import_wallet(exported.as_bytes(), "1").unwrap(), accounts[0]);
+	assert!(other_store.sign(&accounts[0], "1", &Default::default()).is_ok());
+}
+
 fn test_path() -> &'static str {
 	match ::std::fs::metadata("ethstore") {
 		Ok(_) => "ethstore/tests/res/geth_keystore",
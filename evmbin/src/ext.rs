@@ -84,6 +84,10 @@ impl Ext for FakeExt {
 		Ok(*gas)
 	}
 
+	fn revert(self, gas: &U256, _data: &[u8]) -> evm::Result<U256> {
+		Err(evm::Error::Reverted { gas_left: *gas })
+	}
+
 	fn suicide(&mut self, _refund_address: &Address) {
 		unimplemented!();
 	}
@@ -0,0 +1,65 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dapps settings de/serialization.
+
+use std::io::{Read, Write};
+use std::collections::HashMap;
+use serde_json;
+use hash;
+
+/// Settings for a dapp.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DappsSettings {
+	/// Accounts the dapp is allowed to see.
+	pub accounts: Vec<hash::Address>,
+	/// Account the dapp uses by default. Listed first.
+	#[serde(skip_serializing_if="Option::is_none")]
+	pub default: Option<hash::Address>,
+}
+
+impl DappsSettings {
+	/// Read a hash map of dapp id -> DappsSettings.
+	pub fn read_dapps_settings<R>(reader: R) -> Result<HashMap<String, DappsSettings>, serde_json::Error> where R: Read {
+		serde_json::from_reader(reader)
+	}
+
+	/// Write a hash map of dapp id -> DappsSettings.
+	pub fn write_dapps_settings<W>(m: &HashMap<String, DappsSettings>, writer: &mut W) -> Result<(), serde_json::Error> where W: Write {
+		serde_json::to_writer(writer, m)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use misc::DappsSettings;
+
+	#[test]
+	fn dapps_settings_deserialization() {
+		let s = r#"{
+			"wallet": {
+				"accounts": ["0x0000000000000000000000000000000000000001"]
+			}
+		}"#;
+
+		let deserialized = DappsSettings::read_dapps_settings(s.as_bytes()).unwrap();
+		assert_eq!(deserialized["wallet"].accounts.len(), 1);
+
+		let serialized = serde_json::to_string(&deserialized["wallet"]).unwrap();
+		assert_eq!(serialized, r#"{"accounts":["0x0000000000000000000000000000000000000001"]}"#);
+	}
+}
@@ -17,5 +17,9 @@
 //! Misc deserialization.
 
 mod account_meta;
+mod dapps_settings;
+mod tx_permissions;
 
 pub use self::account_meta::AccountMeta;
+pub use self::dapps_settings::DappsSettings;
+pub use self::tx_permissions::TransactionPermissions;
@@ -0,0 +1,59 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction permissioning rules deserialization.
+
+use std::io::Read;
+use std::collections::BTreeMap;
+use serde_json;
+use hash::Address;
+
+/// Local transaction permissioning rules.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct TransactionPermissions {
+	/// Accounts allowed to deploy contracts. Everyone may deploy if not given.
+	pub deployers: Option<Vec<Address>>,
+	/// Recipients mapped to the only senders allowed to transact with them.
+	#[serde(rename="restrictedRecipients")]
+	pub restricted_recipients: Option<BTreeMap<Address, Vec<Address>>>,
+}
+
+impl TransactionPermissions {
+	/// Loads permissioning rules from json.
+	pub fn load<R>(reader: R) -> Result<Self, serde_json::Error> where R: Read {
+		serde_json::from_reader(reader)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use misc::TransactionPermissions;
+
+	#[test]
+	fn tx_permissions_deserialization() {
+		let s = r#"{
+			"deployers": ["0x0000000000000000000000000000000000000001"],
+			"restrictedRecipients": {
+				"0x0000000000000000000000000000000000000002": ["0x0000000000000000000000000000000000000001"]
+			}
+		}"#;
+
+		let deserialized: TransactionPermissions = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.deployers.unwrap().len(), 1);
+		assert_eq!(deserialized.restricted_recipients.unwrap().len(), 1);
+	}
+}
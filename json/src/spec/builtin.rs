@@ -16,6 +16,8 @@
 
 //! Spec builtin deserialization.
 
+use uint::Uint;
+
 /// Linear pricing.
 #[derive(Debug, PartialEq, Deserialize, Clone)]
 pub struct Linear {
@@ -40,12 +42,17 @@ pub struct Builtin {
 	pub name: String,
 	/// Builtin pricing.
 	pub pricing: Pricing,
+	/// Activation block.
+	#[serde(rename="activateAt")]
+	pub activate_at: Option<Uint>,
 }
 
 #[cfg(test)]
 mod tests {
 	use serde_json;
 	use spec::builtin::{Builtin, Pricing, Linear};
+	use util::U256;
+	use uint::Uint;
 
 	#[test]
 	fn builtin_deserialization() {
@@ -56,5 +63,17 @@ mod tests {
 		let deserialized: Builtin = serde_json::from_str(s).unwrap();
 		assert_eq!(deserialized.name, "ecrecover");
 		assert_eq!(deserialized.pricing, Pricing::Linear(Linear { base: 3000, word: 0 }));
+		assert!(deserialized.activate_at.is_none());
+	}
+
+	#[test]
+	fn activate_at() {
+		let s = r#"{
+			"name": "ecrecover",
+			"pricing": { "linear": { "base": 3000, "word": 0 } },
+			"activateAt": "0x64"
+		}"#;
+		let deserialized: Builtin = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.activate_at, Some(Uint(U256::from(100))));
 	}
 }
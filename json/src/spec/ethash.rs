@@ -46,6 +46,12 @@ pub struct EthashParams {
 	/// See main EthashParams docs.
 	#[serde(rename="frontierCompatibilityModeLimit")]
 	pub frontier_compatibility_mode_limit: Option<Uint>,
+	/// Homestead transition block. Takes precedence over `frontierCompatibilityModeLimit`.
+	#[serde(rename="homesteadTransition")]
+	pub homestead_transition: Option<Uint>,
+	/// See main EthashParams docs.
+	#[serde(rename="eip150Transition")]
+	pub eip150_transition: Option<Uint>,
 
 	/// See main EthashParams docs.
 	#[serde(rename="daoHardforkTransition")]
@@ -117,7 +123,8 @@ mod tests {
 				],
 				"difficultyHardforkTransition": "0x59d9",
 				"difficultyHardforkBoundDivisor": "0x0200",
-				"bombDefuseTransition": "0x42"
+				"bombDefuseTransition": "0x42",
+				"eip150Transition": "0x64"
 			}
 		}"#;
 
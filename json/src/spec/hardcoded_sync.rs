@@ -0,0 +1,42 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Spec hardcoded sync checkpoint deserialization.
+
+use bytes::Bytes;
+
+/// Trusted checkpoint to start syncing from.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct HardcodedSync {
+	/// RLP of the trusted header.
+	pub header: Bytes,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use bytes::Bytes;
+	use spec::HardcodedSync;
+
+	#[test]
+	fn hardcoded_sync_deserialization() {
+		let s = r#"{
+			"header": "0xc0"
+		}"#;
+		let deserialized: HardcodedSync = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.header, Bytes::new(vec![0xc0]));
+	}
+}
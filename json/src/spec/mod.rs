@@ -26,6 +26,8 @@ pub mod engine;
 pub mod state;
 pub mod ethash;
 pub mod basic_authority;
+pub mod schedule;
+pub mod hardcoded_sync;
 
 pub use self::account::Account;
 pub use self::builtin::{Builtin, Pricing, Linear};
@@ -37,3 +39,5 @@ pub use self::engine::Engine;
 pub use self::state::State;
 pub use self::ethash::{Ethash, EthashParams};
 pub use self::basic_authority::{BasicAuthority, BasicAuthorityParams};
+pub use self::schedule::GasSchedule;
+pub use self::hardcoded_sync::HardcodedSync;
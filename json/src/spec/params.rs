@@ -18,6 +18,7 @@
 
 use uint::Uint;
 use hash::H256;
+use spec::schedule::GasSchedule;
 
 /// Spec params.
 #[derive(Debug, PartialEq, Deserialize)]
@@ -43,6 +44,12 @@ pub struct Params {
 	/// Expected fork block hash.
 	#[serde(rename="forkCanonHash")]
 	pub fork_hash: Option<H256>,
+	/// Number of first block where EIP-140 (REVERT instruction) rules begin.
+	#[serde(rename="eip140Transition")]
+	pub eip140_transition: Option<Uint>,
+	/// Overrides of the EVM gas schedule.
+	#[serde(rename="gasSchedule")]
+	pub gas_schedule: Option<GasSchedule>,
 }
 
 #[cfg(test)]
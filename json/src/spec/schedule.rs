@@ -0,0 +1,137 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Spec gas schedule overrides deserialization.
+
+use uint::Uint;
+
+/// Gas schedule overrides. Every field left out keeps the value of the engine's own schedule.
+#[derive(Debug, PartialEq, Deserialize, Default, Clone)]
+pub struct GasSchedule {
+	/// Gas price for `EXP` opcode.
+	#[serde(rename="expGas")]
+	pub exp_gas: Option<Uint>,
+	/// Additional gas for `EXP` opcode for each byte of exponent.
+	#[serde(rename="expByteGas")]
+	pub exp_byte_gas: Option<Uint>,
+	/// Gas price for `SHA3` opcode.
+	#[serde(rename="sha3Gas")]
+	pub sha3_gas: Option<Uint>,
+	/// Additional gas for `SHA3` opcode for each word of hashed memory.
+	#[serde(rename="sha3WordGas")]
+	pub sha3_word_gas: Option<Uint>,
+	/// Gas price for loading from storage.
+	#[serde(rename="sloadGas")]
+	pub sload_gas: Option<Uint>,
+	/// Gas price for `BALANCE` opcode.
+	#[serde(rename="balanceGas")]
+	pub balance_gas: Option<Uint>,
+	/// Gas price for `EXTCODESIZE` opcode.
+	#[serde(rename="extcodesizeGas")]
+	pub extcodesize_gas: Option<Uint>,
+	/// Base gas price for `EXTCODECOPY` opcode.
+	#[serde(rename="extcodecopyBaseGas")]
+	pub extcodecopy_base_gas: Option<Uint>,
+	/// Gas price for setting new value to storage.
+	#[serde(rename="sstoreSetGas")]
+	pub sstore_set_gas: Option<Uint>,
+	/// Gas price for altering value in storage.
+	#[serde(rename="sstoreResetGas")]
+	pub sstore_reset_gas: Option<Uint>,
+	/// Gas refund for `SSTORE` clearing.
+	#[serde(rename="sstoreRefundGas")]
+	pub sstore_refund_gas: Option<Uint>,
+	/// Gas price for `JUMPDEST` opcode.
+	#[serde(rename="jumpdestGas")]
+	pub jumpdest_gas: Option<Uint>,
+	/// Gas price for `LOG*`.
+	#[serde(rename="logGas")]
+	pub log_gas: Option<Uint>,
+	/// Additional gas for data in `LOG*`.
+	#[serde(rename="logDataGas")]
+	pub log_data_gas: Option<Uint>,
+	/// Additional gas for each topic in `LOG*`.
+	#[serde(rename="logTopicGas")]
+	pub log_topic_gas: Option<Uint>,
+	/// Gas price for `CREATE` opcode.
+	#[serde(rename="createGas")]
+	pub create_gas: Option<Uint>,
+	/// Gas price for `*CALL*` opcodes.
+	#[serde(rename="callGas")]
+	pub call_gas: Option<Uint>,
+	/// Stipend for value transfers with `CALL|CALLCODE`.
+	#[serde(rename="callStipend")]
+	pub call_stipend: Option<Uint>,
+	/// Additional gas required for value transfer with `CALL|CALLCODE`.
+	#[serde(rename="callValueTransferGas")]
+	pub call_value_transfer_gas: Option<Uint>,
+	/// Additional gas for creating new account with `CALL|CALLCODE`.
+	#[serde(rename="callNewAccountGas")]
+	pub call_new_account_gas: Option<Uint>,
+	/// Refund for `SUICIDE`.
+	#[serde(rename="suicideRefundGas")]
+	pub suicide_refund_gas: Option<Uint>,
+	/// Gas price for `SUICIDE` opcode.
+	#[serde(rename="suicideGas")]
+	pub suicide_gas: Option<Uint>,
+	/// Additional gas for `SUICIDE` sending funds to a non-existent account.
+	#[serde(rename="suicideToNewAccountGas")]
+	pub suicide_to_new_account_cost: Option<Uint>,
+	/// Gas for each word of used memory.
+	#[serde(rename="memoryGas")]
+	pub memory_gas: Option<Uint>,
+	/// Cost for each byte of contract code deposited by `CREATE`.
+	#[serde(rename="createDataGas")]
+	pub create_data_gas: Option<Uint>,
+	/// Transaction cost.
+	#[serde(rename="txGas")]
+	pub tx_gas: Option<Uint>,
+	/// Contract creating transaction cost.
+	#[serde(rename="txCreateGas")]
+	pub tx_create_gas: Option<Uint>,
+	/// Cost for each zero byte of transaction data.
+	#[serde(rename="txDataZeroGas")]
+	pub tx_data_zero_gas: Option<Uint>,
+	/// Cost for each non-zero byte of transaction data.
+	#[serde(rename="txDataNonZeroGas")]
+	pub tx_data_non_zero_gas: Option<Uint>,
+	/// Gas price for copying each word of memory.
+	#[serde(rename="copyGas")]
+	pub copy_gas: Option<Uint>,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use spec::schedule::GasSchedule;
+	use util::U256;
+	use uint::Uint;
+
+	#[test]
+	fn gas_schedule_deserialization() {
+		let s = r#"{
+			"sloadGas": "0xc8",
+			"callStipend": "0x0",
+			"memoryGas": "0x4"
+		}"#;
+
+		let deserialized: GasSchedule = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.sload_gas, Some(Uint(U256::from(200))));
+		assert_eq!(deserialized.call_stipend, Some(Uint(U256::from(0))));
+		assert_eq!(deserialized.memory_gas, Some(Uint(U256::from(4))));
+		assert_eq!(deserialized.call_gas, None);
+	}
+}
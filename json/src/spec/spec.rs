@@ -19,7 +19,7 @@
 use std::io::Read;
 use serde_json;
 use serde_json::Error;
-use spec::{Params, Genesis, Engine, State};
+use spec::{Params, Genesis, Engine, State, HardcodedSync};
 
 /// Spec deserialization.
 #[derive(Debug, PartialEq, Deserialize)]
@@ -39,6 +39,9 @@ pub struct Spec {
 	pub accounts: State,
 	/// Boot nodes.
 	pub nodes: Option<Vec<String>>,
+	/// Trusted checkpoint to sync from.
+	#[serde(rename="hardcodedSync")]
+	pub hardcoded_sync: Option<HardcodedSync>,
 }
 
 impl Spec {
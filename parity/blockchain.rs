@@ -20,15 +20,18 @@ use std::io::{BufReader, BufRead};
 use std::time::{Instant, Duration};
 use std::thread::sleep;
 use std::sync::Arc;
+use std::path::PathBuf;
 use rustc_serialize::hex::FromHex;
 use ethcore_logger::{setup_log, Config as LogConfig};
 use io::{PanicHandler, ForwardPanic};
 use util::{ToPretty, Uint};
+use util::kvdb::{Database, DatabaseConfig};
 use rlp::PayloadInfo;
 use ethcore::service::ClientService;
 use ethcore::client::{Mode, DatabaseCompactionProfile, VMType, BlockImportError, BlockChainClient, BlockID};
 use ethcore::error::ImportError;
 use ethcore::miner::Miner;
+use ethcore::db::NUM_COLUMNS;
 use cache::CacheConfig;
 use informant::{Informant, MillisecondDuration};
 use params::{SpecType, Pruning, Switch, tracing_switch_to_bool, fatdb_switch_to_bool};
@@ -66,6 +69,9 @@ impl FromStr for DataFormat {
 pub enum BlockchainCmd {
 	Import(ImportBlockchain),
 	Export(ExportBlockchain),
+	Check(CheckBlockchain),
+	Backup(DatabaseBackup),
+	RestoreBackup(DatabaseBackup),
 }
 
 #[derive(Debug, PartialEq)]
@@ -103,10 +109,38 @@ pub struct ExportBlockchain {
 	pub to_block: BlockID,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct CheckBlockchain {
+	pub spec: SpecType,
+	pub logger_config: LogConfig,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub mode: Mode,
+	pub fat_db: Switch,
+	pub tracing: Switch,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DatabaseBackup {
+	pub spec: SpecType,
+	pub logger_config: LogConfig,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub path: String,
+}
+
 pub fn execute(cmd: BlockchainCmd) -> Result<String, String> {
 	match cmd {
 		BlockchainCmd::Import(import_cmd) => execute_import(import_cmd),
 		BlockchainCmd::Export(export_cmd) => execute_export(export_cmd),
+		BlockchainCmd::Check(check_cmd) => execute_check(check_cmd),
+		BlockchainCmd::Backup(backup_cmd) => execute_backup(backup_cmd),
+		BlockchainCmd::RestoreBackup(backup_cmd) => execute_restore_backup(backup_cmd),
 	}
 }
 
@@ -130,6 +164,7 @@ fn execute_import(cmd: ImportBlockchain) -> Result<String, String> {
 
 	// database paths
 	let db_dirs = cmd.dirs.database(genesis_hash, spec.fork_name.clone());
+	try!(db_dirs.migrate_legacy_layout(spec.network_id()));
 
 	// user defaults path
 	let user_defaults_path = db_dirs.user_defaults_path();
@@ -281,6 +316,7 @@ fn execute_export(cmd: ExportBlockchain) -> Result<String, String> {
 
 	// database paths
 	let db_dirs = cmd.dirs.database(genesis_hash, spec.fork_name.clone());
+	try!(db_dirs.migrate_legacy_layout(spec.network_id()));
 
 	// user defaults path
 	let user_defaults_path = db_dirs.user_defaults_path();
@@ -340,6 +376,150 @@ fn execute_export(cmd: ExportBlockchain) -> Result<String, String> {
 	Ok("Export completed.".into())
 }
 
+fn execute_check(cmd: CheckBlockchain) -> Result<String, String> {
+	// Setup panic handler
+	let panic_handler = PanicHandler::new_in_arc();
+
+	// Setup logging
+	let _logger = setup_log(&cmd.logger_config);
+
+	// create dirs used by parity
+	try!(cmd.dirs.create_dirs());
+
+	// load spec file
+	let spec = try!(cmd.spec.spec());
+
+	// load genesis hash
+	let genesis_hash = spec.genesis_header().hash();
+
+	// database paths
+	let db_dirs = cmd.dirs.database(genesis_hash, spec.fork_name.clone());
+	try!(db_dirs.migrate_legacy_layout(spec.network_id()));
+
+	// user defaults path
+	let user_defaults_path = db_dirs.user_defaults_path();
+
+	// load user defaults
+	let user_defaults = try!(UserDefaults::load(&user_defaults_path));
+
+	fdlimit::raise_fd_limit();
+
+	// select pruning algorithm
+	let algorithm = cmd.pruning.to_algorithm(&user_defaults);
+
+	// check if tracing is on
+	let tracing = try!(tracing_switch_to_bool(cmd.tracing, &user_defaults));
+
+	// check if fatdb is on
+	let fat_db = try!(fatdb_switch_to_bool(cmd.fat_db, &user_defaults, algorithm));
+
+	// prepare client and snapshot paths.
+	let client_path = db_dirs.client_path(algorithm);
+	let snapshot_path = db_dirs.snapshot_path();
+
+	// execute upgrades
+	try!(execute_upgrades(&db_dirs, algorithm, cmd.compaction.compaction_profile()));
+
+	// prepare client config
+	let client_config = to_client_config(&cmd.cache_config, cmd.mode, tracing, fat_db, cmd.compaction, cmd.wal, VMType::default(), "".into(), algorithm);
+
+	// opening the database already runs a quick check and tries to restore missing state
+	let service = try!(ClientService::start(
+		client_config,
+		&spec,
+		&client_path,
+		&snapshot_path,
+		&cmd.dirs.ipc_path(),
+		Arc::new(Miner::with_spec(&spec)),
+	).map_err(|e| format!("Client service error: {:?}", e)));
+
+	panic_handler.forward_from(&service);
+	let client = service.client();
+
+	info!("Checking the whole chain and state of the best block. This may take a while...");
+	let mut report = client.check_integrity(u64::max_value(), true);
+	if report.is_state_missing() {
+		match client.repair_state() {
+			Ok(count) => {
+				info!("Restored state by re-executing {} blocks.", count);
+				report = client.check_integrity(u64::max_value(), true);
+			},
+			Err(e) => warn!("Unable to restore state: {}", e),
+		}
+	}
+
+	match report.is_ok() {
+		true => Ok(format!("{}", report)),
+		false => Err(format!("{}", report)),
+	}
+}
+
+// path and configuration of the client database a backup command operates on.
+fn client_database(cmd: &DatabaseBackup) -> Result<(PathBuf, DatabaseConfig), String> {
+	// create dirs used by parity
+	try!(cmd.dirs.create_dirs());
+
+	let spec = try!(cmd.spec.spec());
+	let genesis_hash = spec.genesis_header().hash();
+	let db_dirs = cmd.dirs.database(genesis_hash, spec.fork_name.clone());
+	try!(db_dirs.migrate_legacy_layout(spec.network_id()));
+	let user_defaults = try!(UserDefaults::load(&db_dirs.user_defaults_path()));
+
+	fdlimit::raise_fd_limit();
+
+	let algorithm = cmd.pruning.to_algorithm(&user_defaults);
+	try!(execute_upgrades(&db_dirs, algorithm, cmd.compaction.compaction_profile()));
+
+	let mut db_config = DatabaseConfig::with_columns(NUM_COLUMNS);
+	db_config.compaction = cmd.compaction.compaction_profile();
+	db_config.wal = cmd.wal;
+
+	Ok((db_dirs.client_path(algorithm), db_config))
+}
+
+fn open_database(config: &DatabaseConfig, path: &PathBuf) -> Result<Database, String> {
+	Database::open(config, &path.to_string_lossy()).map_err(|e| {
+		format!("Cannot open database at {} ({}). To back up a running node use the ethcore_backupDatabase RPC method.", path.to_string_lossy(), e)
+	})
+}
+
+fn execute_backup(cmd: DatabaseBackup) -> Result<String, String> {
+	let _logger = setup_log(&cmd.logger_config);
+	let (client_path, db_config) = try!(client_database(&cmd));
+
+	let db = try!(open_database(&db_config, &client_path));
+	info!("Backing up database to {}", cmd.path);
+	try!(db.backup(&cmd.path));
+
+	Ok(format!("Database backed up to {}", cmd.path))
+}
+
+fn execute_restore_backup(cmd: DatabaseBackup) -> Result<String, String> {
+	let _logger = setup_log(&cmd.logger_config);
+	let (client_path, db_config) = try!(client_database(&cmd));
+
+	if fs::metadata(&cmd.path).is_err() {
+		return Err(format!("Backup {} does not exist", cmd.path));
+	}
+
+	let backup = try!(open_database(&db_config, &PathBuf::from(&cmd.path)));
+
+	// copy the backup first, so that it stays intact once it is moved in place.
+	let mut temp_path = client_path.clone();
+	temp_path.pop();
+	temp_path.push("restored_db");
+	let _ = fs::remove_dir_all(&temp_path);
+
+	info!("Restoring database from {}", cmd.path);
+	try!(backup.backup(&temp_path.to_string_lossy()));
+	drop(backup);
+
+	let db = try!(open_database(&db_config, &client_path));
+	try!(db.restore(&temp_path.to_string_lossy()).map_err(|e| format!("Failed to restore database: {:?}", e)));
+
+	Ok(format!("Database restored from {}", cmd.path))
+}
+
 #[cfg(test)]
 mod test {
 	use super::DataFormat;
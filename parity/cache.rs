@@ -15,12 +15,67 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp::max;
+use std::sync::Arc;
+use ethcore::client::Client;
+use ethsync::SyncProvider;
+use util::Mutex;
 
 const MIN_BC_CACHE_MB: u32 = 4;
 const MIN_DB_CACHE_MB: u32 = 2;
 const MIN_BLOCK_QUEUE_SIZE_LIMIT_MB: u32 = 16;
 const DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB: u32 = 50;
 const DEFAULT_TRACE_CACHE_SIZE: u32 = 20;
+const DEFAULT_STATE_CACHE_SIZE: u32 = 16;
+/// Shares (in percent) of the memory budget given to the block queue, state cache
+/// and blockchain cache while syncing.
+const SYNCING_SHARES: [u32; 3] = [45, 40, 15];
+/// Shares of the memory budget while serving requests.
+const SERVING_SHARES: [u32; 3] = [10, 50, 40];
+
+/// What the node spends most of its time on.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Workload {
+	/// Importing blocks.
+	Syncing,
+	/// Following the chain and answering requests.
+	Serving,
+}
+
+/// Sizes of caches resizable at runtime, in MB.
+#[derive(Debug, PartialEq)]
+pub struct Allotment {
+	pub block_queue: u32,
+	pub state: u32,
+	pub blockchain: u32,
+}
+
+/// Memory shared by caches resizable at runtime, in MB.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MemoryBudget {
+	total: u32,
+}
+
+impl MemoryBudget {
+	pub fn new(total: u32) -> Self {
+		MemoryBudget {
+			total: total,
+		}
+	}
+
+	/// Splits the budget between caches depending on the workload.
+	/// Caches never get less than their minimal sizes.
+	pub fn allot(&self, workload: Workload) -> Allotment {
+		let shares = match workload {
+			Workload::Syncing => SYNCING_SHARES,
+			Workload::Serving => SERVING_SHARES,
+		};
+		Allotment {
+			block_queue: max(self.total * shares[0] / 100, MIN_BLOCK_QUEUE_SIZE_LIMIT_MB),
+			state: self.total * shares[1] / 100,
+			blockchain: max(self.total * shares[2] / 100, MIN_BC_CACHE_MB),
+		}
+	}
+}
 
 /// Configuration for application cache sizes.
 /// All	values are represented in MB.
@@ -37,32 +92,44 @@ pub struct CacheConfig {
 	queue: u32,
 	/// Size of traces cache.
 	traces: u32,
+	/// Size of the state trie node cache.
+	state: u32,
+	/// Memory rebalanced between caches at runtime.
+	budget: Option<MemoryBudget>,
 }
 
 impl Default for CacheConfig {
 	fn default() -> Self {
-		CacheConfig::new(64, 8, DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB)
+		CacheConfig::new(64, 8, DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB, DEFAULT_STATE_CACHE_SIZE)
 	}
 }
 
 impl CacheConfig {
 	/// Creates new cache config with cumulative size equal `total`.
+	/// 7/8 of it goes to the database, the rest is a budget split between other caches
+	/// according to the workload, starting with syncing.
 	pub fn new_with_total_cache_size(total: u32) -> Self {
+		let budget = MemoryBudget::new(total - total * 7 / 8);
+		let allotment = budget.allot(Workload::Syncing);
 		CacheConfig {
 			db: total * 7 / 8,
-			blockchain: total / 8,
-			queue: DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB,
+			blockchain: allotment.blockchain,
+			queue: allotment.block_queue,
 			traces: DEFAULT_TRACE_CACHE_SIZE,
+			state: allotment.state,
+			budget: Some(budget),
 		}
 	}
 
 	/// Creates new cache config with gitven details.
-	pub fn new(db: u32, blockchain: u32, queue: u32) -> Self {
+	pub fn new(db: u32, blockchain: u32, queue: u32, state: u32) -> Self {
 		CacheConfig {
 			db: db,
 			blockchain: blockchain,
 			queue: queue,
 			traces: DEFAULT_TRACE_CACHE_SIZE,
+			state: state,
+			budget: None,
 		}
 	}
 
@@ -90,30 +157,91 @@ impl CacheConfig {
 	pub fn traces(&self) -> u32 {
 		self.traces
 	}
+
+	/// Size of the state trie node cache.
+	pub fn state(&self) -> u32 {
+		self.state
+	}
+
+	/// Memory budget rebalanced at runtime, if total cache size was given.
+	pub fn budget(&self) -> Option<MemoryBudget> {
+		self.budget
+	}
+}
+
+/// Resizes caches when the workload of the node changes.
+/// The transaction queue keeps its own limit.
+pub struct MemoryManager {
+	budget: MemoryBudget,
+	client: Arc<Client>,
+	sync: Arc<SyncProvider>,
+	workload: Mutex<Option<Workload>>,
+}
+
+impl MemoryManager {
+	pub fn new(budget: MemoryBudget, client: Arc<Client>, sync: Arc<SyncProvider>) -> Self {
+		MemoryManager {
+			budget: budget,
+			client: client,
+			sync: sync,
+			workload: Mutex::new(None),
+		}
+	}
+
+	/// Rebalances the budget if the node started or stopped syncing.
+	pub fn tick(&self) {
+		let workload = match self.sync.status().is_major_syncing() {
+			true => Workload::Syncing,
+			false => Workload::Serving,
+		};
+		let mut current = self.workload.lock();
+		if *current == Some(workload) {
+			return;
+		}
+
+		let allotment = self.budget.allot(workload);
+		let mb = 1024 * 1024;
+		self.client.set_cache_limits(allotment.blockchain as usize * mb, allotment.state as usize * mb, allotment.block_queue as usize * mb);
+		debug!(target: "cache", "Caches resized for {:?}: {:?}", workload, allotment);
+		*current = Some(workload);
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::CacheConfig;
+	use super::{CacheConfig, MemoryBudget, Workload, Allotment};
 
 	#[test]
 	fn test_cache_config_constructor() {
-		let config = CacheConfig::new_with_total_cache_size(200);
-		assert_eq!(config.db, 175);
-		assert_eq!(config.blockchain(), 25);
-		assert_eq!(config.queue(), 50);
+		let config = CacheConfig::new_with_total_cache_size(800);
+		assert_eq!(config.db, 700);
+		assert_eq!(config.blockchain(), 15);
+		assert_eq!(config.queue(), 45);
+		assert_eq!(config.state(), 40);
+		assert_eq!(config.budget(), Some(MemoryBudget::new(100)));
 	}
 
 	#[test]
 	fn test_cache_config_db_cache_sizes() {
 		let config = CacheConfig::new_with_total_cache_size(400);
 		assert_eq!(config.db, 350);
-		assert_eq!(config.db_blockchain_cache_size(), 12);
+		assert_eq!(config.db_blockchain_cache_size(), 2);
 		assert_eq!(config.db_state_cache_size(), 262);
 	}
 
+	#[test]
+	fn test_memory_budget_allotment() {
+		let budget = MemoryBudget::new(200);
+		assert_eq!(budget.allot(Workload::Syncing), Allotment { block_queue: 90, state: 80, blockchain: 30 });
+		assert_eq!(budget.allot(Workload::Serving), Allotment { block_queue: 20, state: 100, blockchain: 80 });
+		// minimal sizes are kept
+		let budget = MemoryBudget::new(20);
+		assert_eq!(budget.allot(Workload::Serving), Allotment { block_queue: 16, state: 10, blockchain: 8 });
+		assert_eq!(budget.allot(Workload::Syncing), Allotment { block_queue: 16, state: 8, blockchain: 4 });
+	}
+
 	#[test]
 	fn test_cache_config_default() {
-		assert_eq!(CacheConfig::default(), CacheConfig::new(64, 8, super::DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB));
+		assert_eq!(CacheConfig::default(), CacheConfig::new(64, 8, super::DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB, super::DEFAULT_STATE_CACHE_SIZE));
 	}
 }
@@ -21,24 +21,35 @@ usage! {
 	{
 		// Commands
 		cmd_daemon: bool,
+		cmd_stop: bool,
+		cmd_status: bool,
 		cmd_wallet: bool,
 		cmd_account: bool,
+		cmd_engine_signer: bool,
 		cmd_new: bool,
 		cmd_list: bool,
 		cmd_export: bool,
 		cmd_import: bool,
 		cmd_signer: bool,
 		cmd_new_token: bool,
+		cmd_network: bool,
+		cmd_new_key: bool,
 		cmd_snapshot: bool,
 		cmd_restore: bool,
 		cmd_ui: bool,
 		cmd_tools: bool,
 		cmd_hash: bool,
+		cmd_db: bool,
+		cmd_check: bool,
+		cmd_backup: bool,
+		cmd_evm: bool,
+		cmd_stats: bool,
 
 		// Arguments
 		arg_pid_file: String,
 		arg_file: Option<String>,
 		arg_path: Vec<String>,
+		arg_dir: String,
 
 		// Flags
 		// -- Legacy Options
@@ -67,6 +78,7 @@ usage! {
 		flag_etherbase: Option<String>,
 		flag_extradata: Option<String>,
 		flag_cache: Option<u32>,
+		flag_jitvm: bool,
 
 		// -- Miscellaneous Options
 		flag_version: bool,
@@ -74,13 +86,20 @@ usage! {
 	}
 	{
 		// -- Operating Options
-		flag_mode: String = "active", or |c: &Config| otry!(c.parity).mode.clone(),
+		flag_mode: String = "last", or |c: &Config| otry!(c.parity).mode.clone(),
 		flag_mode_timeout: u64 = 300u64, or |c: &Config| otry!(c.parity).mode_timeout.clone(),
 		flag_mode_alarm: u64 = 3600u64, or |c: &Config| otry!(c.parity).mode_alarm.clone(),
+		flag_auto_update: String = "none", or |c: &Config| otry!(c.parity).auto_update.clone(),
 		flag_chain: String = "homestead", or |c: &Config| otry!(c.parity).chain.clone(),
 		flag_db_path: String = "$HOME/.parity", or |c: &Config| otry!(c.parity).db_path.clone(),
 		flag_keys_path: String = "$HOME/.parity/keys", or |c: &Config| otry!(c.parity).keys_path.clone(),
 		flag_identity: String = "", or |c: &Config| otry!(c.parity).identity.clone(),
+		flag_max_reorg_depth: Option<u64> = None,
+			or |c: &Config| otry!(c.parity).max_reorg_depth.clone().map(Some),
+		flag_on_new_block: Option<String> = None,
+			or |c: &Config| otry!(c.parity).on_new_block.clone().map(Some),
+		flag_on_reorg: Option<String> = None,
+			or |c: &Config| otry!(c.parity).on_reorg.clone().map(Some),
 
 		// -- Account Options
 		flag_unlock: Option<String> = None,
@@ -89,6 +108,10 @@ usage! {
 			or |c: &Config| otry!(c.account).password.clone(),
 		flag_keys_iterations: u32 = 10240u32,
 			or |c: &Config| otry!(c.account).keys_iterations.clone(),
+		flag_engine_signer: Option<String> = None,
+			or |c: &Config| otry!(c.account).engine_signer.clone().map(Some),
+		flag_scheduler: bool = false,
+			or |c: &Config| otry!(c.account).scheduler.clone(),
 
 		flag_force_signer: bool = false,
 			or |c: &Config| otry!(c.signer).force.clone(),
@@ -126,6 +149,20 @@ usage! {
 			or |c: &Config| otry!(c.network).reserved_peers.clone().map(Some),
 		flag_reserved_only: bool = false,
 			or |c: &Config| otry!(c.network).reserved_only.clone(),
+		flag_node_permissions: String = "none",
+			or |c: &Config| otry!(c.network).node_permissions.clone(),
+		flag_max_upload_kbps: Option<u32> = None,
+			or |c: &Config| otry!(c.network).max_upload_kbps.clone().map(Some),
+		flag_max_download_kbps: Option<u32> = None,
+			or |c: &Config| otry!(c.network).max_download_kbps.clone().map(Some),
+		flag_max_peers_per_ip: Option<u32> = None,
+			or |c: &Config| otry!(c.network).max_peers_per_ip.clone().map(Some),
+		flag_max_peers_per_subnet: Option<u32> = None,
+			or |c: &Config| otry!(c.network).max_peers_per_subnet.clone().map(Some),
+		flag_no_tx_relay: bool = false,
+			or |c: &Config| otry!(c.network).tx_relay.map(|r| !r).clone(),
+		flag_ntp_servers: String = "none",
+			or |c: &Config| otry!(c.network).ntp_servers.as_ref().map(|vec| vec.join(",")),
 
 		// -- API and Console Options
 		// RPC
@@ -141,6 +178,12 @@ usage! {
 			or |c: &Config| otry!(c.rpc).apis.clone().map(|vec| vec.join(",")),
 		flag_jsonrpc_hosts: String = "none",
 			or |c: &Config| otry!(c.rpc).hosts.clone().map(|vec| vec.join(",")),
+		flag_jsonrpc_sync_threshold: u64 = 6u64,
+			or |c: &Config| otry!(c.rpc).sync_threshold.clone(),
+		flag_jsonrpc_max_response_size: Option<usize> = None,
+			or |c: &Config| otry!(c.rpc).max_response_size.clone().map(Some),
+		flag_rpc_threads: Option<usize> = None,
+			or |c: &Config| otry!(c.rpc).threads.clone().map(Some),
 
 		// IPC
 		flag_no_ipc: bool = false,
@@ -165,6 +208,22 @@ usage! {
 			or |c: &Config| otry!(c.dapps).user.clone().map(Some),
 		flag_dapps_pass: Option<String> = None,
 			or |c: &Config| otry!(c.dapps).pass.clone().map(Some),
+		flag_dapps_require_signatures: bool = false,
+			or |c: &Config| otry!(c.dapps).require_signatures.clone(),
+
+		// Secret Store
+		flag_secretstore: bool = false,
+			or |c: &Config| otry!(c.secretstore).enable.clone(),
+		flag_secretstore_port: u16 = 8082u16,
+			or |c: &Config| otry!(c.secretstore).port.clone(),
+		flag_secretstore_interface: String = "local",
+			or |c: &Config| otry!(c.secretstore).interface.clone(),
+		flag_secretstore_path: Option<String> = None,
+			or |c: &Config| otry!(c.secretstore).path.clone().map(Some),
+		flag_private_tx: bool = false,
+			or |c: &Config| otry!(c.secretstore).private_tx.clone(),
+		flag_private_tx_key_server: String = "127.0.0.1:8082",
+			or |c: &Config| otry!(c.secretstore).private_tx_key_server.clone(),
 
 		// -- Sealing/Mining Options
 		flag_author: Option<String> = None,
@@ -195,10 +254,26 @@ usage! {
 			or |c: &Config| otry!(c.mining).extra_data.clone().map(Some),
 		flag_tx_queue_size: usize = 2048usize,
 			or |c: &Config| otry!(c.mining).tx_queue_size.clone(),
+		flag_tx_replace_bump: usize = 10usize,
+			or |c: &Config| otry!(c.mining).tx_replace_bump.clone(),
+		flag_tx_queue_per_sender: usize = 64usize,
+			or |c: &Config| otry!(c.mining).tx_queue_per_sender.clone(),
+		flag_tx_queue_mem_limit: u32 = 4u32,
+			or |c: &Config| otry!(c.mining).tx_queue_mem_limit.clone(),
+		flag_tx_time_limit: Option<u64> = None,
+			or |c: &Config| otry!(c.mining).tx_time_limit.clone().map(Some),
+		flag_tx_queue_ban_count: u16 = 1u16,
+			or |c: &Config| otry!(c.mining).tx_queue_ban_count.clone(),
+		flag_tx_queue_ban_time: u64 = 180u64,
+			or |c: &Config| otry!(c.mining).tx_queue_ban_time.clone(),
 		flag_remove_solved: bool = false,
 			or |c: &Config| otry!(c.mining).remove_solved.clone(),
+		flag_tx_permissions: String = "none",
+			or |c: &Config| otry!(c.mining).tx_permissions.clone(),
 		flag_notify_work: Option<String> = None,
 			or |c: &Config| otry!(c.mining).notify_work.clone().map(|vec| Some(vec.join(","))),
+		flag_max_clock_drift: u64 = 10u64,
+			or |c: &Config| otry!(c.mining).max_clock_drift.clone(),
 
 		// -- Footprint Options
 		flag_tracing: String = "auto",
@@ -211,14 +286,28 @@ usage! {
 			or |c: &Config| otry!(c.footprint).cache_size_blocks.clone(),
 		flag_cache_size_queue: u32 = 50u32,
 			or |c: &Config| otry!(c.footprint).cache_size_queue.clone(),
+		flag_cache_size_state: u32 = 16u32,
+			or |c: &Config| otry!(c.footprint).cache_size_state.clone(),
 		flag_cache_size: Option<u32> = None,
 			or |c: &Config| otry!(c.footprint).cache_size.clone().map(Some),
 		flag_fast_and_loose: bool = false,
 			or |c: &Config| otry!(c.footprint).fast_and_loose.clone(),
+		flag_db_read_only: bool = false,
+			or |c: &Config| otry!(c.footprint).db_read_only.clone(),
 		flag_db_compaction: String = "ssd",
 			or |c: &Config| otry!(c.footprint).db_compaction.clone(),
 		flag_fat_db: String = "auto",
 			or |c: &Config| otry!(c.footprint).fat_db.clone(),
+		flag_address_index: bool = false,
+			or |c: &Config| otry!(c.footprint).address_index.clone(),
+		flag_prune_ancient_bodies: Option<u64> = None,
+			or |c: &Config| otry!(c.footprint).prune_ancient_bodies.clone().map(Some),
+		flag_num_verifiers: Option<usize> = None,
+			or |c: &Config| otry!(c.footprint).num_verifiers.clone().map(Some),
+		flag_scale_verifiers: bool = false,
+			or |c: &Config| otry!(c.footprint).scale_verifiers.clone(),
+		flag_io_threads: Option<usize> = None,
+			or |c: &Config| otry!(c.footprint).io_threads.clone().map(Some),
 
 		// -- Import/Export Options
 		flag_from: String = "1", or |_| None,
@@ -229,10 +318,22 @@ usage! {
 		flag_at: String = "latest", or |_| None,
 		flag_no_periodic_snapshot: bool = false,
 			or |c: &Config| otry!(c.snapshots).disable_periodic.clone(),
+		flag_snapshot_period: u64 = 10000u64,
+			or |c: &Config| otry!(c.snapshots).period.clone(),
+		flag_snapshot_retain: usize = 1usize,
+			or |c: &Config| otry!(c.snapshots).retain.clone(),
 
 		// -- Virtual Machine Options
-		flag_jitvm: bool = false,
-			or |c: &Config| otry!(c.vm).jit.clone(),
+		flag_vm: String = "interpreter",
+			or |c: &Config| otry!(c.vm).backend.clone(),
+
+		// -- EVM Stats Options
+		flag_code: Option<String> = None, or |_| None,
+		flag_input: Option<String> = None, or |_| None,
+		flag_gas: String = "10000000", or |_| None,
+		flag_gas_price: String = "0", or |_| None,
+		flag_block: u64 = 0u64, or |_| None,
+		flag_differential: bool = false, or |_| None,
 
 		// -- Miscellaneous Options
 		flag_config: String = "$HOME/.parity/config.toml", or |_| None,
@@ -242,6 +343,8 @@ usage! {
 			or |c: &Config| otry!(c.misc).log_file.clone().map(Some),
 		flag_no_color: bool = false,
 			or |c: &Config| otry!(c.misc).color.map(|c| !c).clone(),
+		flag_shutdown_timeout: u64 = 30u64,
+			or |c: &Config| otry!(c.misc).shutdown_timeout.clone(),
 	}
 }
 
@@ -255,6 +358,7 @@ struct Config {
 	rpc: Option<Rpc>,
 	ipc: Option<Ipc>,
 	dapps: Option<Dapps>,
+	secretstore: Option<SecretStore>,
 	mining: Option<Mining>,
 	footprint: Option<Footprint>,
 	snapshots: Option<Snapshots>,
@@ -267,10 +371,14 @@ struct Operating {
 	mode: Option<String>,
 	mode_timeout: Option<u64>,
 	mode_alarm: Option<u64>,
+	auto_update: Option<String>,
 	chain: Option<String>,
 	db_path: Option<String>,
 	keys_path: Option<String>,
 	identity: Option<String>,
+	max_reorg_depth: Option<u64>,
+	on_new_block: Option<String>,
+	on_reorg: Option<String>,
 }
 
 #[derive(Default, Debug, PartialEq, RustcDecodable)]
@@ -278,6 +386,8 @@ struct Account {
 	unlock: Option<Vec<String>>,
 	password: Option<Vec<String>>,
 	keys_iterations: Option<u32>,
+	engine_signer: Option<String>,
+	scheduler: Option<bool>,
 }
 
 #[derive(Default, Debug, PartialEq, RustcDecodable)]
@@ -302,6 +412,13 @@ struct Network {
 	node_key: Option<String>,
 	reserved_peers: Option<String>,
 	reserved_only: Option<bool>,
+	node_permissions: Option<String>,
+	max_upload_kbps: Option<u32>,
+	max_download_kbps: Option<u32>,
+	max_peers_per_ip: Option<u32>,
+	max_peers_per_subnet: Option<u32>,
+	tx_relay: Option<bool>,
+	ntp_servers: Option<Vec<String>>,
 }
 
 #[derive(Default, Debug, PartialEq, RustcDecodable)]
@@ -312,6 +429,9 @@ struct Rpc {
 	cors: Option<String>,
 	apis: Option<Vec<String>>,
 	hosts: Option<Vec<String>>,
+	sync_threshold: Option<u64>,
+	max_response_size: Option<usize>,
+	threads: Option<usize>,
 }
 
 #[derive(Default, Debug, PartialEq, RustcDecodable)]
@@ -330,6 +450,17 @@ struct Dapps {
 	path: Option<String>,
 	user: Option<String>,
 	pass: Option<String>,
+	require_signatures: Option<bool>,
+}
+
+#[derive(Default, Debug, PartialEq, RustcDecodable)]
+struct SecretStore {
+	enable: Option<bool>,
+	port: Option<u16>,
+	interface: Option<String>,
+	path: Option<String>,
+	private_tx: Option<bool>,
+	private_tx_key_server: Option<String>,
 }
 
 #[derive(Default, Debug, PartialEq, RustcDecodable)]
@@ -348,8 +479,16 @@ struct Mining {
 	gas_cap: Option<String>,
 	extra_data: Option<String>,
 	tx_queue_size: Option<usize>,
+	tx_replace_bump: Option<usize>,
+	tx_queue_per_sender: Option<usize>,
+	tx_queue_mem_limit: Option<u32>,
+	tx_time_limit: Option<u64>,
+	tx_queue_ban_count: Option<u16>,
+	tx_queue_ban_time: Option<u64>,
 	remove_solved: Option<bool>,
+	tx_permissions: Option<String>,
 	notify_work: Option<Vec<String>>,
+	max_clock_drift: Option<u64>,
 }
 
 #[derive(Default, Debug, PartialEq, RustcDecodable)]
@@ -357,21 +496,32 @@ struct Footprint {
 	tracing: Option<String>,
 	pruning: Option<String>,
 	fast_and_loose: Option<bool>,
+	db_read_only: Option<bool>,
 	cache_size: Option<u32>,
 	cache_size_db: Option<u32>,
 	cache_size_blocks: Option<u32>,
 	cache_size_queue: Option<u32>,
+	cache_size_state: Option<u32>,
 	db_compaction: Option<String>,
 	fat_db: Option<String>,
+	address_index: Option<bool>,
+	prune_ancient_bodies: Option<u64>,
+	num_verifiers: Option<usize>,
+	scale_verifiers: Option<bool>,
+	io_threads: Option<usize>,
 }
 
 #[derive(Default, Debug, PartialEq, RustcDecodable)]
 struct Snapshots {
 	disable_periodic: Option<bool>,
+	period: Option<u64>,
+	retain: Option<usize>,
 }
 
 #[derive(Default, Debug, PartialEq, RustcDecodable)]
 struct VM {
+	backend: Option<String>,
+	// replaced by `backend`, accepted so that existing config files still load.
 	jit: Option<bool>,
 }
 
@@ -380,13 +530,25 @@ struct Misc {
 	logging: Option<String>,
 	log_file: Option<String>,
 	color: Option<bool>,
+	shutdown_timeout: Option<u64>,
+}
+
+impl Config {
+	/// Keys of the config file which are accepted but ignored, with the keys replacing them.
+	fn ignored_keys(&self) -> Vec<(&'static str, &'static str)> {
+		let mut keys = Vec::new();
+		if self.vm.as_ref().map_or(false, |vm| vm.jit.is_some()) {
+			keys.push(("[vm] jit", "[vm] backend"));
+		}
+		keys
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::{
 		Args, ArgsError,
-		Config, Operating, Account, Signer, Network, Rpc, Ipc, Dapps, Mining, Footprint, Snapshots, VM, Misc
+		Config, Operating, Account, Signer, Network, Rpc, Ipc, Dapps, SecretStore, Mining, Footprint, Snapshots, VM, Misc
 	};
 	use toml;
 
@@ -432,38 +594,55 @@ mod tests {
 		assert_eq!(args, Args {
 			// Commands
 			cmd_daemon: false,
+			cmd_stop: false,
+			cmd_status: false,
 			cmd_wallet: false,
 			cmd_account: false,
+			cmd_engine_signer: false,
 			cmd_new: false,
 			cmd_list: false,
 			cmd_export: false,
 			cmd_import: false,
 			cmd_signer: false,
 			cmd_new_token: false,
+			cmd_network: false,
+			cmd_new_key: false,
 			cmd_snapshot: false,
 			cmd_restore: false,
 			cmd_ui: false,
 			cmd_tools: false,
 			cmd_hash: false,
+			cmd_db: false,
+			cmd_check: false,
+			cmd_backup: false,
+			cmd_evm: false,
+			cmd_stats: false,
 
 			// Arguments
 			arg_pid_file: "".into(),
 			arg_file: None,
 			arg_path: vec![],
+			arg_dir: "".into(),
 
 			// -- Operating Options
 			flag_mode: "active".into(),
 			flag_mode_timeout: 300u64,
 			flag_mode_alarm: 3600u64,
+			flag_auto_update: "none".into(),
 			flag_chain: "xyz".into(),
 			flag_db_path: "$HOME/.parity".into(),
 			flag_keys_path: "$HOME/.parity/keys".into(),
 			flag_identity: "".into(),
+			flag_max_reorg_depth: None,
+			flag_on_new_block: None,
+			flag_on_reorg: None,
 
 			// -- Account Options
 			flag_unlock: Some("0xdeadbeefcafe0000000000000000000000000000".into()),
 			flag_password: vec!["~/.safe/password.file".into()],
 			flag_keys_iterations: 10240u32,
+			flag_engine_signer: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
+			flag_scheduler: false,
 
 			flag_force_signer: false,
 			flag_no_signer: false,
@@ -484,6 +663,13 @@ mod tests {
 			flag_node_key: None,
 			flag_reserved_peers: Some("./path_to_file".into()),
 			flag_reserved_only: false,
+			flag_node_permissions: "none".into(),
+			flag_max_upload_kbps: None,
+			flag_max_download_kbps: None,
+			flag_max_peers_per_ip: None,
+			flag_max_peers_per_subnet: None,
+			flag_no_tx_relay: false,
+			flag_ntp_servers: "none".into(),
 
 			// -- API and Console Options
 			// RPC
@@ -493,6 +679,9 @@ mod tests {
 			flag_jsonrpc_cors: Some("null".into()),
 			flag_jsonrpc_apis: "web3,eth,net,personal,ethcore,traces,rpc".into(),
 			flag_jsonrpc_hosts: "none".into(),
+			flag_jsonrpc_sync_threshold: 6u64,
+			flag_jsonrpc_max_response_size: None,
+			flag_rpc_threads: None,
 
 			// IPC
 			flag_no_ipc: false,
@@ -507,6 +696,15 @@ mod tests {
 			flag_dapps_path: "$HOME/.parity/dapps".into(),
 			flag_dapps_user: Some("test_user".into()),
 			flag_dapps_pass: Some("test_pass".into()),
+			flag_dapps_require_signatures: false,
+
+			// SECRET STORE
+			flag_secretstore: false,
+			flag_secretstore_port: 8082u16,
+			flag_secretstore_interface: "local".into(),
+			flag_secretstore_path: Some("$HOME/.parity/secretstore".into()),
+			flag_private_tx: false,
+			flag_private_tx_key_server: "127.0.0.1:8082".into(),
 
 			// -- Sealing/Mining Options
 			flag_author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
@@ -523,8 +721,16 @@ mod tests {
 			flag_gas_cap: "6283184".into(),
 			flag_extra_data: Some("Parity".into()),
 			flag_tx_queue_size: 2048usize,
+			flag_tx_replace_bump: 10usize,
+			flag_tx_queue_per_sender: 64usize,
+			flag_tx_queue_mem_limit: 4u32,
+			flag_tx_time_limit: None,
+			flag_tx_queue_ban_count: 1u16,
+			flag_tx_queue_ban_time: 180u64,
 			flag_remove_solved: false,
+			flag_tx_permissions: "none".into(),
 			flag_notify_work: Some("http://localhost:3001".into()),
+			flag_max_clock_drift: 10u64,
 
 			// -- Footprint Options
 			flag_tracing: "auto".into(),
@@ -532,10 +738,17 @@ mod tests {
 			flag_cache_size_db: 64u32,
 			flag_cache_size_blocks: 8u32,
 			flag_cache_size_queue: 50u32,
+			flag_cache_size_state: 16u32,
 			flag_cache_size: Some(128),
 			flag_fast_and_loose: false,
+			flag_db_read_only: false,
 			flag_db_compaction: "ssd".into(),
 			flag_fat_db: "auto".into(),
+			flag_address_index: false,
+			flag_prune_ancient_bodies: None,
+			flag_num_verifiers: None,
+			flag_scale_verifiers: false,
+			flag_io_threads: None,
 
 			// -- Import/Export Options
 			flag_from: "1".into(),
@@ -545,9 +758,19 @@ mod tests {
 			// -- Snapshot Optons
 			flag_at: "latest".into(),
 			flag_no_periodic_snapshot: false,
+			flag_snapshot_period: 10000u64,
+			flag_snapshot_retain: 3usize,
 
 			// -- Virtual Machine Options
-			flag_jitvm: false,
+			flag_vm: "interpreter".into(),
+
+			// -- EVM Stats Options
+			flag_code: None,
+			flag_input: None,
+			flag_gas: "10000000".into(),
+			flag_gas_price: "0".into(),
+			flag_block: 0u64,
+			flag_differential: false,
 
 			// -- Legacy Options
 			flag_geth: false,
@@ -575,6 +798,7 @@ mod tests {
 			flag_etherbase: None,
 			flag_extradata: None,
 			flag_cache: None,
+			flag_jitvm: false,
 
 			// -- Miscellaneous Options
 			flag_version: false,
@@ -582,6 +806,7 @@ mod tests {
 			flag_logging: Some("own_tx=trace".into()),
 			flag_log_file: Some("/var/log/parity.log".into()),
 			flag_no_color: false,
+			flag_shutdown_timeout: 30u64,
 			flag_no_config: false,
 		});
 	}
@@ -599,6 +824,16 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn should_accept_and_ignore_legacy_jit_config() {
+		let config = Args::parse_config("[vm]\njit = true\n").unwrap();
+		assert_eq!(config.ignored_keys(), vec![("[vm] jit", "[vm] backend")]);
+
+		let args = Args::parse_with_config(&["parity"], config).unwrap();
+		assert_eq!(args.flag_vm, "interpreter".to_owned());
+		assert_eq!(args.flag_jitvm, false);
+	}
+
 	#[test]
 	fn should_deserialize_toml_file() {
 		let config: Config = toml::decode_str(include_str!("./config.toml")).unwrap();
@@ -608,15 +843,21 @@ mod tests {
 				mode: Some("dark".into()),
 				mode_timeout: Some(15u64),
 				mode_alarm: Some(10u64),
+				auto_update: None,
 				chain: Some("./chain.json".into()),
 				db_path: None,
 				keys_path: None,
 				identity: None,
+				max_reorg_depth: None,
+				on_new_block: None,
+				on_reorg: None,
 			}),
 			account: Some(Account {
 				unlock: Some(vec!["0x1".into(), "0x2".into(), "0x3".into()]),
 				password: Some(vec!["passwdfile path".into()]),
 				keys_iterations: None,
+				engine_signer: None,
+				scheduler: None,
 			}),
 			signer: Some(Signer {
 				force: None,
@@ -637,6 +878,13 @@ mod tests {
 				node_key: None,
 				reserved_peers: Some("./path/to/reserved_peers".into()),
 				reserved_only: Some(true),
+				node_permissions: None,
+				max_upload_kbps: None,
+				max_download_kbps: None,
+				max_peers_per_ip: None,
+				max_peers_per_subnet: None,
+				tx_relay: None,
+				ntp_servers: None,
 			}),
 			rpc: Some(Rpc {
 				disable: Some(true),
@@ -645,6 +893,9 @@ mod tests {
 				cors: None,
 				apis: None,
 				hosts: None,
+				sync_threshold: None,
+				max_response_size: None,
+				threads: None,
 			}),
 			ipc: Some(Ipc {
 				disable: None,
@@ -658,7 +909,16 @@ mod tests {
 				interface: None,
 				hosts: None,
 				user: Some("username".into()),
-				pass: Some("password".into())
+				pass: Some("password".into()),
+				require_signatures: None,
+			}),
+			secretstore: Some(SecretStore {
+				enable: Some(true),
+				port: Some(8082),
+				interface: None,
+				path: None,
+				private_tx: None,
+				private_tx_key_server: None,
 			}),
 			mining: Some(Mining {
 				author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
@@ -673,32 +933,51 @@ mod tests {
 				gas_floor_target: None,
 				gas_cap: None,
 				tx_queue_size: Some(2048),
+				tx_replace_bump: None,
+				tx_queue_per_sender: None,
+				tx_queue_mem_limit: None,
+				tx_time_limit: None,
+				tx_queue_ban_count: None,
+				tx_queue_ban_time: None,
 				tx_gas_limit: None,
 				extra_data: None,
 				remove_solved: None,
+				tx_permissions: None,
 				notify_work: None,
+				max_clock_drift: None,
 			}),
 			footprint: Some(Footprint {
 				tracing: Some("on".into()),
 				pruning: Some("fast".into()),
 				fast_and_loose: None,
+				db_read_only: None,
 				cache_size: None,
 				cache_size_db: Some(128),
 				cache_size_blocks: Some(16),
 				cache_size_queue: Some(100),
+				cache_size_state: None,
 				db_compaction: Some("ssd".into()),
 				fat_db: Some("off".into()),
+				address_index: None,
+				prune_ancient_bodies: None,
+				num_verifiers: None,
+				scale_verifiers: None,
+				io_threads: None,
 			}),
 			snapshots: Some(Snapshots {
 				disable_periodic: Some(true),
+				period: None,
+				retain: Some(2),
 			}),
 			vm: Some(VM {
-				jit: Some(false),
+				backend: Some("interpreter".into()),
+				jit: None,
 			}),
 			misc: Some(Misc {
 				logging: Some("own_tx=trace".into()),
 				log_file: Some("/var/log/parity.log".into()),
 				color: Some(true),
+				shutdown_timeout: None,
 			})
 		});
 	}
@@ -139,7 +139,11 @@ macro_rules! usage {
 						println!("Loading config file from {}", &config_file);
 						let mut config = String::new();
 						try!(file.read_to_string(&mut config).map_err(|e| ArgsError::Config(config_file, e)));
-						try!(Self::parse_config(&config))
+						let config = try!(Self::parse_config(&config));
+						for (key, replacement) in config.ignored_keys() {
+							println!("Option '{}' in the config file is ignored. Please use '{}' instead", key, replacement);
+						}
+						config
 					},
 					// Don't display error in case default config cannot be loaded.
 					(Err(_), false) => Config::default(),
@@ -0,0 +1,207 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Monitoring of the local clock against NTP servers and clocks of peers.
+
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use ethcore::miner::{Miner, MinerService, ClockDrift};
+use ethsync::ManageNetwork;
+use util::Mutex;
+
+/// Seconds between the NTP epoch (1900) and the UNIX epoch (1970).
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+/// How long to wait for the NTP server to respond, in seconds.
+const NTP_TIMEOUT_SEC: u64 = 5;
+/// Period between NTP queries, in seconds.
+const NTP_INTERVAL_SEC: u64 = 600;
+/// Number of queries sent to each NTP server.
+const NTP_SAMPLES_PER_SERVER: usize = 3;
+/// Minimal number of answers needed to trust the measurement.
+const NTP_MIN_SAMPLES: usize = 3;
+/// Maximal difference between the answers, in milliseconds, for the measurement to be trusted.
+const NTP_MAX_DISAGREEMENT_MS: i64 = 1000;
+/// Offset against peers, in milliseconds, above which the user is warned.
+const PEERS_MAX_DRIFT_MS: i64 = 60_000;
+
+#[derive(Default)]
+struct NtpState {
+	/// Time and result of the last successful measurement.
+	last: Option<(Instant, i64)>,
+	/// Whether servers are being queried.
+	querying: bool,
+}
+
+/// Periodically measures offset of the local clock and reports it to the miner.
+pub struct ClockMonitor {
+	ntp_servers: Vec<String>,
+	net: Arc<ManageNetwork>,
+	miner: Arc<Miner>,
+	ntp: Arc<Mutex<NtpState>>,
+	peers_off: Mutex<bool>,
+}
+
+impl ClockMonitor {
+	/// Creates new monitor querying given NTP servers. NTP is not used if there are none.
+	pub fn new(ntp_servers: Vec<String>, net: Arc<ManageNetwork>, miner: Arc<Miner>) -> Self {
+		ClockMonitor {
+			ntp_servers: ntp_servers,
+			net: net,
+			miner: miner,
+			ntp: Default::default(),
+			peers_off: Mutex::new(false),
+		}
+	}
+
+	/// Updates the measurements. NTP servers are queried on a separate thread
+	/// if the last answer is stale; until then the last answer is used.
+	pub fn tick(&self) {
+		let ntp = if self.ntp_servers.is_empty() { None } else { self.ntp_drift() };
+		let drift = ClockDrift {
+			ntp: ntp,
+			peers: self.net.peer_clock_drift().map(|secs| secs * 1000),
+		};
+
+		let peers_off = drift.peers.map_or(false, |peers| peers.abs() > PEERS_MAX_DRIFT_MS);
+		let mut was_off = self.peers_off.lock();
+		if peers_off && !*was_off {
+			warn!("Local clock seems to be off by {}s compared to peers.", drift.peers.unwrap_or(0) / 1000);
+		}
+		*was_off = peers_off;
+
+		self.miner.set_clock_drift(drift);
+	}
+
+	fn ntp_drift(&self) -> Option<i64> {
+		let mut state = self.ntp.lock();
+		let fresh = state.last.map_or(false, |(at, _)| at.elapsed() < Duration::from_secs(NTP_INTERVAL_SEC));
+		if !fresh && !state.querying {
+			let servers = self.ntp_servers.clone();
+			let ntp = self.ntp.clone();
+			let spawned = thread::Builder::new().name("NTP".into()).spawn(move || {
+				let drift = query_servers(&servers);
+				let mut state = ntp.lock();
+				state.querying = false;
+				state.last = drift.map(|drift| (Instant::now(), drift));
+			});
+			match spawned {
+				Ok(_) => state.querying = true,
+				Err(e) => warn!("Cannot start NTP query: {}", e),
+			}
+		}
+		state.last.map(|(_, drift)| drift)
+	}
+}
+
+/// Queries every server several times and returns the median offset of the local clock in milliseconds,
+/// or nothing if too few answers were received or they don't agree with each other.
+fn query_servers(servers: &[String]) -> Option<i64> {
+	let mut samples = Vec::new();
+	for server in servers {
+		for _ in 0..NTP_SAMPLES_PER_SERVER {
+			match ntp_drift(server) {
+				Ok(drift) => samples.push(drift),
+				Err(e) => debug!(target: "clock", "Unable to query NTP server {}: {}", server, e),
+			}
+		}
+	}
+	agreed_drift(samples)
+}
+
+/// Median of the measurements if there are enough of them and they agree.
+fn agreed_drift(mut samples: Vec<i64>) -> Option<i64> {
+	if samples.len() < NTP_MIN_SAMPLES {
+		debug!(target: "clock", "Only {} NTP answers received", samples.len());
+		return None;
+	}
+	samples.sort();
+	let spread = samples[samples.len() - 1] - samples[0];
+	if spread > NTP_MAX_DISAGREEMENT_MS {
+		debug!(target: "clock", "NTP answers disagree by {}ms", spread);
+		return None;
+	}
+	Some(samples[samples.len() / 2])
+}
+
+/// Milliseconds since UNIX epoch.
+fn now() -> i64 {
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::from_secs(0));
+	now.as_secs() as i64 * 1000 + now.subsec_nanos() as i64 / 1_000_000
+}
+
+/// Converts 64-bit NTP timestamp to milliseconds since UNIX epoch.
+fn ntp_timestamp(bytes: &[u8]) -> i64 {
+	let read = |b: &[u8]| b.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+	let seconds = read(&bytes[0..4]) as i64 - NTP_UNIX_OFFSET as i64;
+	let fraction = read(&bytes[4..8]);
+	seconds * 1000 + ((fraction * 1000) >> 32) as i64
+}
+
+/// Offset of the local clock given the send and receive times of the request, measured
+/// locally and by the server. Positive if the local clock is ahead.
+fn offset(sent: i64, server_received: i64, server_sent: i64, received: i64) -> i64 {
+	((sent - server_received) + (received - server_sent)) / 2
+}
+
+/// Queries the NTP server with a single SNTP request and returns offset of the local clock in milliseconds.
+fn ntp_drift(server: &str) -> Result<i64, String> {
+	let socket = try!(UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("{}", e)));
+	try!(socket.set_read_timeout(Some(Duration::from_secs(NTP_TIMEOUT_SEC))).map_err(|e| format!("{}", e)));
+
+	// leap indicator 0, version 3, client mode
+	let mut request = [0u8; 48];
+	request[0] = 0x1b;
+	let sent = now();
+	try!(socket.send_to(&request, server).map_err(|e| format!("{}", e)));
+
+	let mut response = [0u8; 48];
+	let (len, _) = try!(socket.recv_from(&mut response).map_err(|e| format!("{}", e)));
+	let received = now();
+	if len < response.len() {
+		return Err("Response too short".into());
+	}
+
+	Ok(offset(sent, ntp_timestamp(&response[32..40]), ntp_timestamp(&response[40..48]), received))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ntp_timestamp, offset, agreed_drift};
+
+	#[test]
+	fn should_decode_ntp_timestamp() {
+		// 2016-11-01 00:00:00.5 UTC
+		let bytes = [0xdb, 0xc2, 0x59, 0x80, 0x80, 0x00, 0x00, 0x00];
+		assert_eq!(ntp_timestamp(&bytes), 1_477_958_400_500);
+	}
+
+	#[test]
+	fn should_compute_offset() {
+		// local clock 2s ahead, 100ms each way
+		assert_eq!(offset(10_000, 8_100, 8_150, 10_250), 2_000);
+		// local clock 1s behind
+		assert_eq!(offset(10_000, 11_100, 11_100, 10_200), -1_000);
+	}
+
+	#[test]
+	fn should_require_agreeing_samples() {
+		assert_eq!(agreed_drift(vec![2_000, 2_100]), None);
+		assert_eq!(agreed_drift(vec![2_000, 2_100, 1_950]), Some(2_000));
+		assert_eq!(agreed_drift(vec![2_000, 2_100, 40_000]), None);
+	}
+}
@@ -24,23 +24,30 @@ use util::{Hashable, U256, Uint, Bytes, version_data, Secret, Address};
 use util::log::Colour;
 use ethsync::{NetworkConfiguration, is_valid_node_url};
 use ethcore::client::{VMType, Mode};
-use ethcore::miner::MinerOptions;
+use ethcore::snapshot::SNAPSHOT_BLOCKS;
+use ethcore::miner::{MinerOptions, Banning, TransactionPermissions, PermissionRules};
 
 use rpc::{IpcConfiguration, HttpConfiguration};
 use ethcore_rpc::NetworkSettings;
 use cache::CacheConfig;
-use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, replace_home,
-geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address};
+use helpers::{to_duration, to_mode, to_block_id, to_u256, to_bytes, to_extra_data, to_pending_set, to_price, replace_home,
+geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, build_features};
 use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType};
 use ethcore_logger::Config as LogConfig;
 use dir::Directories;
 use dapps::Configuration as DappsConfiguration;
+use secretstore::Configuration as SecretStoreConfiguration;
+use private_tx::Configuration as PrivateTxConfiguration;
 use signer::Configuration as SignerConfiguration;
 use run::RunCmd;
-use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat};
+use updater::UpdatePolicy;
+use node_filter::NodePermissions;
+use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, CheckBlockchain, DatabaseBackup, DataFormat};
 use presale::ImportWallet;
 use account::{AccountCmd, NewAccount, ImportAccounts, ImportFromGethAccounts};
 use snapshot::{self, SnapshotCommand};
+use daemon::DaemonCmd;
+use evm::EvmCmd;
 
 #[derive(Debug, PartialEq)]
 pub enum Cmd {
@@ -50,8 +57,11 @@ pub enum Cmd {
 	ImportPresaleWallet(ImportWallet),
 	Blockchain(BlockchainCmd),
 	SignerToken(String),
+	NewNodeKey(String),
 	Snapshot(SnapshotCommand),
 	Hash(Option<String>),
+	Daemon(DaemonCmd),
+	Evm(EvmCmd),
 }
 
 #[derive(Debug, PartialEq)]
@@ -73,8 +83,13 @@ impl Configuration {
 	pub fn into_command(self) -> Result<Cmd, String> {
 		let dirs = self.directories();
 		let pruning = try!(self.args.flag_pruning.parse());
+		let update_policy = try!(self.args.flag_auto_update.parse());
+		let node_permissions = try!(self.node_permissions());
 		let vm_type = try!(self.vm_type());
-		let mode = try!(to_mode(&self.args.flag_mode, self.args.flag_mode_timeout, self.args.flag_mode_alarm));
+		let mode = match self.args.flag_mode.as_ref() {
+			"last" => None,
+			mode => Some(try!(to_mode(mode, self.args.flag_mode_timeout, self.args.flag_mode_alarm))),
+		};
 		let miner_options = try!(self.miner_options());
 		let logger_config = self.logger_config();
 		let http_conf = try!(self.http_config());
@@ -87,33 +102,45 @@ impl Configuration {
 		let fat_db = try!(self.args.flag_fat_db.parse());
 		let compaction = try!(self.args.flag_db_compaction.parse());
 		let wal = !self.args.flag_fast_and_loose;
-		let enable_network = self.enable_network(&mode);
 		let geth_compatibility = self.args.flag_geth;
 		let signer_port = self.signer_port();
 		let dapps_conf = self.dapps_config();
+		let secretstore_conf = self.secretstore_config(&dirs);
+		let private_tx_conf = self.private_tx_config(&dirs);
 		let signer_conf = self.signer_config();
 		let format = try!(self.format());
 
 		let cmd = if self.args.flag_version {
 			Cmd::Version
+		} else if self.args.cmd_stop {
+			Cmd::Daemon(DaemonCmd::Stop(self.args.arg_pid_file.clone()))
+		} else if self.args.cmd_status {
+			Cmd::Daemon(DaemonCmd::Status(self.args.arg_pid_file.clone()))
 		} else if self.args.cmd_signer && self.args.cmd_new_token {
 			Cmd::SignerToken(dirs.signer)
+		} else if self.args.cmd_network && self.args.cmd_new_key {
+			Cmd::NewNodeKey(self.network_path())
 		} else if self.args.cmd_tools && self.args.cmd_hash {
 			Cmd::Hash(self.args.arg_file)
-		} else if self.args.cmd_account {
+		} else if self.args.cmd_account || self.args.cmd_engine_signer {
+			// engine signer keys are managed the same way as accounts, but in the node keys directory
+			let keys = match self.args.cmd_engine_signer {
+				true => dirs.node_keys_path().to_string_lossy().into_owned(),
+				false => dirs.keys,
+			};
 			let account_cmd = if self.args.cmd_new {
 				let new_acc = NewAccount {
 					iterations: self.args.flag_keys_iterations,
-					path: dirs.keys,
+					path: keys,
 					password_file: self.args.flag_password.first().cloned(),
 				};
 				AccountCmd::New(new_acc)
 			} else if self.args.cmd_list {
-				AccountCmd::List(dirs.keys)
+				AccountCmd::List(keys)
 			} else if self.args.cmd_import {
 				let import_acc = ImportAccounts {
 					from: self.args.arg_path.clone(),
-					to: dirs.keys,
+					to: keys,
 				};
 				AccountCmd::Import(import_acc)
 			} else {
@@ -147,7 +174,7 @@ impl Configuration {
 				pruning: pruning,
 				compaction: compaction,
 				wal: wal,
-				mode: mode,
+				mode: mode.unwrap_or_else(Mode::default),
 				tracing: tracing,
 				fat_db: fat_db,
 				vm_type: vm_type,
@@ -164,13 +191,54 @@ impl Configuration {
 				pruning: pruning,
 				compaction: compaction,
 				wal: wal,
-				mode: mode,
+				mode: mode.unwrap_or_else(Mode::default),
 				tracing: tracing,
 				fat_db: fat_db,
 				from_block: try!(to_block_id(&self.args.flag_from)),
 				to_block: try!(to_block_id(&self.args.flag_to)),
 			};
 			Cmd::Blockchain(BlockchainCmd::Export(export_cmd))
+		} else if self.args.cmd_db && self.args.cmd_check {
+			let check_cmd = CheckBlockchain {
+				spec: spec,
+				logger_config: logger_config,
+				cache_config: cache_config,
+				dirs: dirs,
+				pruning: pruning,
+				compaction: compaction,
+				wal: wal,
+				mode: mode.unwrap_or_else(Mode::default),
+				tracing: tracing,
+				fat_db: fat_db,
+			};
+			Cmd::Blockchain(BlockchainCmd::Check(check_cmd))
+		} else if self.args.cmd_db && (self.args.cmd_backup || self.args.cmd_restore) {
+			let backup_cmd = DatabaseBackup {
+				spec: spec,
+				logger_config: logger_config,
+				dirs: dirs,
+				pruning: pruning,
+				compaction: compaction,
+				wal: wal,
+				path: self.args.arg_dir.clone(),
+			};
+			match self.args.cmd_backup {
+				true => Cmd::Blockchain(BlockchainCmd::Backup(backup_cmd)),
+				false => Cmd::Blockchain(BlockchainCmd::RestoreBackup(backup_cmd)),
+			}
+		} else if self.args.cmd_evm && self.args.cmd_stats {
+			let code = try!(self.args.flag_code.as_ref().ok_or("Contract code must be given with --code.".to_owned()));
+			let evm_cmd = EvmCmd {
+				spec: spec,
+				vm_type: vm_type,
+				code: try!(to_bytes(code)),
+				input: try!(self.args.flag_input.as_ref().map_or(Ok(Vec::new()), |input| to_bytes(input))),
+				gas: try!(to_u256(&self.args.flag_gas)),
+				gas_price: try!(to_u256(&self.args.flag_gas_price)),
+				block_number: self.args.flag_block,
+				differential: self.args.flag_differential,
+			};
+			Cmd::Evm(evm_cmd)
 		} else if self.args.cmd_snapshot {
 			let snapshot_cmd = SnapshotCommand {
 				cache_config: cache_config,
@@ -178,7 +246,7 @@ impl Configuration {
 				spec: spec,
 				pruning: pruning,
 				logger_config: logger_config,
-				mode: mode,
+				mode: mode.unwrap_or_else(Mode::default),
 				tracing: tracing,
 				fat_db: fat_db,
 				compaction: compaction,
@@ -195,7 +263,7 @@ impl Configuration {
 				spec: spec,
 				pruning: pruning,
 				logger_config: logger_config,
-				mode: mode,
+				mode: mode.unwrap_or_else(Mode::default),
 				tracing: tracing,
 				fat_db: fat_db,
 				compaction: compaction,
@@ -224,6 +292,7 @@ impl Configuration {
 				ipc_conf: ipc_conf,
 				net_conf: net_conf,
 				network_id: network_id,
+				tx_relay: !self.args.flag_no_tx_relay,
 				acc_conf: try!(self.accounts_config()),
 				gas_pricer: try!(self.gas_pricer_config()),
 				miner_extras: try!(self.miner_extras()),
@@ -232,17 +301,40 @@ impl Configuration {
 				fat_db: fat_db,
 				compaction: compaction,
 				wal: wal,
+				db_read_only: self.args.flag_db_read_only,
 				vm_type: vm_type,
-				enable_network: enable_network,
+				enable_network: !self.args.flag_no_network,
 				geth_compatibility: geth_compatibility,
 				signer_port: signer_port,
 				net_settings: self.network_settings(),
 				dapps_conf: dapps_conf,
+				secretstore_conf: secretstore_conf,
+				private_tx_conf: private_tx_conf,
 				signer_conf: signer_conf,
 				ui: self.args.cmd_ui,
 				name: self.args.flag_identity,
 				custom_bootnodes: self.args.flag_bootnodes.is_some(),
 				no_periodic_snapshot: self.args.flag_no_periodic_snapshot,
+				snapshot_period: try!(self.snapshot_period()),
+				snapshot_retain: self.args.flag_snapshot_retain,
+				num_verifiers: self.args.flag_num_verifiers,
+				scale_verifiers: self.args.flag_scale_verifiers,
+				io_threads: self.args.flag_io_threads,
+				max_reorg_depth: self.args.flag_max_reorg_depth,
+				on_new_block: self.args.flag_on_new_block,
+				on_reorg: self.args.flag_on_reorg,
+				update_policy: update_policy,
+				node_permissions: node_permissions,
+				address_index: self.args.flag_address_index,
+				prune_ancient_bodies: try!(self.prune_ancient_bodies()),
+				shutdown_timeout: self.args.flag_shutdown_timeout,
+				sync_threshold: self.args.flag_jsonrpc_sync_threshold,
+				max_response_size: self.args.flag_jsonrpc_max_response_size.map(|mb| mb * 1024 * 1024),
+				scheduler: self.args.flag_scheduler,
+				ntp_servers: match self.args.flag_ntp_servers.as_str() {
+					"none" => Vec::new(),
+					servers => servers.split(',').map(Into::into).collect(),
+				},
 			};
 			Cmd::Run(run_cmd)
 		};
@@ -250,10 +342,12 @@ impl Configuration {
 		Ok(cmd)
 	}
 
-	fn enable_network(&self, mode: &Mode) -> bool {
-		match *mode {
-			Mode::Dark(_) => false,
-			_ => !self.args.flag_no_network,
+	fn prune_ancient_bodies(&self) -> Result<Option<u64>, String> {
+		// bodies are needed to take snapshots, verify uncles and revert reorganizations
+		let min = max(SNAPSHOT_BLOCKS, self.args.flag_max_reorg_depth.unwrap_or(0));
+		match self.args.flag_prune_ancient_bodies {
+			Some(keep) if keep < min => Err(format!("Invalid value for --prune-ancient-bodies: {}. At least {} most recent bodies must be kept.", keep, min)),
+			keep => Ok(keep),
 		}
 	}
 
@@ -261,7 +355,7 @@ impl Configuration {
 		if self.args.flag_jitvm {
 			VMType::jit().ok_or("Parity is built without the JIT EVM.".into())
 		} else {
-			Ok(VMType::Interpreter)
+			self.args.flag_vm.parse()
 		}
 	}
 
@@ -291,18 +385,31 @@ impl Configuration {
 	fn cache_config(&self) -> CacheConfig {
 		match self.args.flag_cache_size.or(self.args.flag_cache) {
 			Some(size) => CacheConfig::new_with_total_cache_size(size),
-			None => CacheConfig::new(self.args.flag_cache_size_db, self.args.flag_cache_size_blocks, self.args.flag_cache_size_queue),
+			None => CacheConfig::new(self.args.flag_cache_size_db, self.args.flag_cache_size_blocks, self.args.flag_cache_size_queue, self.args.flag_cache_size_state),
 		}
 	}
 
 	fn logger_config(&self) -> LogConfig {
+		let file = match self.args.flag_log_file {
+			Some(ref file) => Some(file.clone()),
+			// standard streams are closed once parity is daemonized
+			None if self.args.cmd_daemon => Some(self.default_daemon_log_file()),
+			None => None,
+		};
+
 		LogConfig {
 			mode: self.args.flag_logging.clone(),
-			color: !self.args.flag_no_color && !cfg!(windows),
-			file: self.args.flag_log_file.clone(),
+			color: !self.args.flag_no_color && !self.args.cmd_daemon && !cfg!(windows),
+			file: file,
 		}
 	}
 
+	fn default_daemon_log_file(&self) -> String {
+		let mut path = PathBuf::from(self.directories().db);
+		path.push("parity.log");
+		path.to_string_lossy().into_owned()
+	}
+
 	fn chain(&self) -> String {
 		if self.args.flag_testnet {
 			"morden".to_owned()
@@ -330,6 +437,10 @@ impl Configuration {
 			testnet: self.args.flag_testnet,
 			password_files: self.args.flag_password.clone(),
 			unlocked_accounts: try!(to_addresses(&self.args.flag_unlock)),
+			engine_signer: match self.args.flag_engine_signer {
+				Some(ref signer) => Some(try!(to_address(Some(signer.clone())))),
+				None => None,
+			},
 		};
 
 		Ok(cfg)
@@ -348,15 +459,62 @@ impl Configuration {
 				None => U256::max_value(),
 			},
 			tx_queue_size: self.args.flag_tx_queue_size,
+			tx_replace_bump: self.args.flag_tx_replace_bump,
+			tx_queue_per_sender: self.args.flag_tx_queue_per_sender,
+			tx_queue_memory_limit: match self.args.flag_tx_queue_mem_limit {
+				0 => None,
+				mb => Some(mb as usize * 1024 * 1024),
+			},
+			tx_queue_banning: match self.args.flag_tx_time_limit {
+				Some(ms) => Banning::Enabled {
+					offend_threshold: Duration::from_millis(ms),
+					min_offends: self.args.flag_tx_queue_ban_count,
+					ban_duration: Duration::from_secs(self.args.flag_tx_queue_ban_time),
+				},
+				None => Banning::Disabled,
+			},
+			tx_permissions: try!(self.tx_permissions()),
 			pending_set: try!(to_pending_set(&self.args.flag_relay_set)),
 			reseal_min_period: Duration::from_millis(self.args.flag_reseal_min_period),
 			work_queue_size: self.args.flag_work_queue_size,
 			enable_resubmission: !self.args.flag_remove_solved,
+			max_clock_drift: match self.args.flag_max_clock_drift {
+				0 => None,
+				secs => Some(Duration::from_secs(secs)),
+			},
 		};
 
 		Ok(options)
 	}
 
+	fn snapshot_period(&self) -> Result<u64, String> {
+		match self.args.flag_snapshot_period {
+			0 => Err("--snapshot-period must be greater than zero.".into()),
+			period => Ok(period),
+		}
+	}
+
+	fn tx_permissions(&self) -> Result<TransactionPermissions, String> {
+		use std::fs::File;
+
+		match self.args.flag_tx_permissions.as_str() {
+			"none" => Ok(TransactionPermissions::Unrestricted),
+			contract if contract.starts_with("0x") => to_address(Some(contract.into())).map(TransactionPermissions::Contract),
+			path => {
+				let file = try!(File::open(replace_home(path)).map_err(|e| format!("Error opening transaction permissions file: {}", e)));
+				PermissionRules::load(file).map(TransactionPermissions::Rules)
+			},
+		}
+	}
+
+	fn node_permissions(&self) -> Result<NodePermissions, String> {
+		match self.args.flag_node_permissions.as_str() {
+			"none" => Ok(NodePermissions::None),
+			contract if contract.starts_with("0x") => to_address(Some(contract.into())).map(NodePermissions::Contract),
+			path => NodePermissions::load_allowlist(path),
+		}
+	}
+
 	fn signer_config(&self) -> SignerConfiguration {
 		SignerConfiguration {
 			enabled: self.signer_enabled(),
@@ -376,6 +534,28 @@ impl Configuration {
 			user: self.args.flag_dapps_user.clone(),
 			pass: self.args.flag_dapps_pass.clone(),
 			dapps_path: self.directories().dapps,
+			settings_path: self.directories().dapps_settings_path().to_string_lossy().into_owned(),
+			require_signatures: self.args.flag_dapps_require_signatures,
+		}
+	}
+
+	fn secretstore_config(&self, dirs: &Directories) -> SecretStoreConfiguration {
+		SecretStoreConfiguration {
+			enabled: self.args.flag_secretstore,
+			interface: self.secretstore_interface(),
+			port: self.args.flag_secretstore_port,
+			data_path: match self.args.flag_secretstore_path {
+				Some(ref path) => replace_home(path),
+				None => dirs.secretstore_path().to_string_lossy().into_owned(),
+			},
+		}
+	}
+
+	fn private_tx_config(&self, dirs: &Directories) -> PrivateTxConfiguration {
+		PrivateTxConfiguration {
+			enabled: self.args.flag_private_tx,
+			key_server: self.args.flag_private_tx_key_server.clone(),
+			key_path: dirs.private_tx_path().join("key").to_string_lossy().into_owned(),
 		}
 	}
 
@@ -408,9 +588,8 @@ impl Configuration {
 
 	fn extra_data(&self) -> Result<Bytes, String> {
 		match self.args.flag_extradata.as_ref().or(self.args.flag_extra_data.as_ref()) {
-			Some(x) if x.len() <= 32 => Ok(x.as_bytes().to_owned()),
+			Some(x) => to_extra_data(x),
 			None => Ok(version_data()),
-			Some(_) => Err("Extra data must be at most 32 characters".into()),
 		}
 	}
 
@@ -456,23 +635,30 @@ impl Configuration {
 		ret.discovery_enabled = !self.args.flag_no_discovery && !self.args.flag_nodiscover;
 		ret.max_peers = self.max_peers();
 		ret.min_peers = self.min_peers();
-		let mut net_path = PathBuf::from(self.directories().db);
-		net_path.push("network");
-		let net_specific_path = net_path.join(&try!(self.network_specific_path()));
-		ret.config_path = Some(net_path.to_str().unwrap().to_owned());
+		let net_specific_path = try!(self.network_specific_path());
+		ret.config_path = Some(self.network_path());
 		ret.net_config_path = Some(net_specific_path.to_str().unwrap().to_owned());
 		ret.reserved_nodes = try!(self.init_reserved_nodes());
 		ret.allow_non_reserved = !self.args.flag_reserved_only;
+		ret.max_upload_kbps = self.args.flag_max_upload_kbps;
+		ret.max_download_kbps = self.args.flag_max_download_kbps;
+		ret.max_peers_per_ip = self.args.flag_max_peers_per_ip;
+		ret.max_peers_per_subnet = self.args.flag_max_peers_per_subnet;
 		Ok(ret)
 	}
 
+	fn network_path(&self) -> String {
+		let mut net_path = PathBuf::from(self.directories().db);
+		net_path.push("network");
+		net_path.to_str().unwrap().to_owned()
+	}
+
 	fn network_specific_path(&self) -> Result<PathBuf, String> {
 		let spec_type : SpecType = try!(self.chain().parse());
 		let spec = try!(spec_type.spec());
-		let id = try!(self.network_id());
-		let mut path = PathBuf::new();
-		path.push(format!("{}", id.unwrap_or_else(|| spec.network_id())));
-		Ok(path)
+		let id = try!(self.network_id()).unwrap_or_else(|| spec.network_id());
+		let db_dirs = self.directories().database(spec.genesis_header().hash(), spec.fork_name.clone());
+		Ok(db_dirs.network_path(id))
 	}
 
 	fn network_id(&self) -> Result<Option<U256>, String> {
@@ -530,6 +716,7 @@ impl Configuration {
 			apis: try!(self.rpc_apis().parse()),
 			hosts: self.rpc_hosts(),
 			cors: self.rpc_cors(),
+			threads: self.args.flag_rpc_threads,
 		};
 
 		Ok(conf)
@@ -543,10 +730,11 @@ impl Configuration {
 			rpc_enabled: !self.args.flag_jsonrpc_off && !self.args.flag_no_jsonrpc,
 			rpc_interface: self.args.flag_rpcaddr.clone().unwrap_or(self.args.flag_jsonrpc_interface.clone()),
 			rpc_port: self.args.flag_rpcport.unwrap_or(self.args.flag_jsonrpc_port),
+			features: build_features(),
 		}
 	}
 
-	fn directories(&self) -> Directories {
+	pub fn directories(&self) -> Directories {
 		use util::path;
 
 		let db_path = replace_home(self.args.flag_datadir.as_ref().unwrap_or(&self.args.flag_db_path));
@@ -623,6 +811,13 @@ impl Configuration {
 		}.into()
 	}
 
+	fn secretstore_interface(&self) -> String {
+		match self.args.flag_secretstore_interface.as_str() {
+			"local" => "127.0.0.1",
+			x => x,
+		}.into()
+	}
+
 	fn dapps_enabled(&self) -> bool {
 		!self.args.flag_dapps_off && !self.args.flag_no_dapps && cfg!(feature = "dapps")
 	}
@@ -648,9 +843,12 @@ mod tests {
 	use ethcore::client::{VMType, BlockID};
 	use helpers::{replace_home, default_network_config};
 	use run::RunCmd;
+	use updater::UpdatePolicy;
+	use node_filter::NodePermissions;
 	use signer::Configuration as SignerConfiguration;
-	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat};
+	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, CheckBlockchain, DatabaseBackup, DataFormat};
 	use presale::ImportWallet;
+	use evm::EvmCmd;
 	use account::{AccountCmd, NewAccount, ImportAccounts};
 	use devtools::{RandomTempPath};
 	use std::io::Write;
@@ -702,6 +900,16 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_engine_signer_import() {
+		let args = vec!["parity", "engine-signer", "import", "my_dir"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap(), Cmd::Account(AccountCmd::Import(ImportAccounts {
+			from: vec!["my_dir".into()],
+			to: replace_home("$HOME/.parity/node_keys"),
+		})));
+	}
+
 	#[test]
 	fn test_command_wallet_import() {
 		let args = vec!["parity", "wallet", "import", "my_wallet.json", "--password", "pwd"];
@@ -779,6 +987,77 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_db_check() {
+		let args = vec!["parity", "db", "check"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap(), Cmd::Blockchain(BlockchainCmd::Check(CheckBlockchain {
+			spec: Default::default(),
+			logger_config: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			compaction: Default::default(),
+			wal: true,
+			mode: Default::default(),
+			tracing: Default::default(),
+			fat_db: Default::default(),
+		})));
+	}
+
+	#[test]
+	fn test_command_db_backup_and_restore() {
+		let backup = || DatabaseBackup {
+			spec: Default::default(),
+			logger_config: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			compaction: Default::default(),
+			wal: true,
+			path: "/backups/parity".into(),
+		};
+
+		let conf = parse(&["parity", "db", "backup", "/backups/parity"]);
+		assert_eq!(conf.into_command().unwrap(), Cmd::Blockchain(BlockchainCmd::Backup(backup())));
+
+		let conf = parse(&["parity", "db", "restore", "/backups/parity"]);
+		assert_eq!(conf.into_command().unwrap(), Cmd::Blockchain(BlockchainCmd::RestoreBackup(backup())));
+	}
+
+	#[test]
+	fn test_command_evm_stats() {
+		let conf = parse(&["parity", "evm", "stats", "--code", "0x6001", "--input", "ff", "--gas-price", "0x10", "--block", "2675000"]);
+		assert_eq!(conf.into_command().unwrap(), Cmd::Evm(EvmCmd {
+			spec: Default::default(),
+			vm_type: VMType::Interpreter,
+			code: vec![0x60, 0x01],
+			input: vec![0xff],
+			gas: 10_000_000.into(),
+			gas_price: 16.into(),
+			block_number: 2_675_000,
+			differential: false,
+		}));
+	}
+
+	#[test]
+	fn test_command_daemon_stop_and_status() {
+		let conf = parse(&["parity", "stop", "/tmp/parity.pid"]);
+		assert_eq!(conf.into_command().unwrap(), Cmd::Daemon(DaemonCmd::Stop("/tmp/parity.pid".into())));
+
+		let conf = parse(&["parity", "status", "/tmp/parity.pid"]);
+		assert_eq!(conf.into_command().unwrap(), Cmd::Daemon(DaemonCmd::Status("/tmp/parity.pid".into())));
+	}
+
+	#[test]
+	fn should_log_to_file_when_daemonized() {
+		let conf = parse(&["parity", "daemon", "/tmp/parity.pid"]);
+		let expected = replace_home("$HOME/.parity/parity.log");
+		assert_eq!(conf.logger_config().file, Some(expected));
+
+		let conf = parse(&["parity", "daemon", "/tmp/parity.pid", "--log-file", "/var/log/parity.log"]);
+		assert_eq!(conf.logger_config().file, Some("/var/log/parity.log".into()));
+	}
+
 	#[test]
 	fn test_command_signer_new_token() {
 		let args = vec!["parity", "signer", "new-token"];
@@ -787,6 +1066,14 @@ mod tests {
 		assert_eq!(conf.into_command().unwrap(), Cmd::SignerToken(expected));
 	}
 
+	#[test]
+	fn test_command_network_new_key() {
+		let args = vec!["parity", "network", "new-key"];
+		let conf = parse(&args);
+		let expected = replace_home("$HOME/.parity/network");
+		assert_eq!(conf.into_command().unwrap(), Cmd::NewNodeKey(expected));
+	}
+
 	#[test]
 	fn test_run_cmd() {
 		let args = vec!["parity"];
@@ -803,6 +1090,7 @@ mod tests {
 			ipc_conf: Default::default(),
 			net_conf: default_network_config(),
 			network_id: None,
+			tx_relay: true,
 			acc_conf: Default::default(),
 			gas_pricer: Default::default(),
 			miner_extras: Default::default(),
@@ -810,18 +1098,38 @@ mod tests {
 			tracing: Default::default(),
 			compaction: Default::default(),
 			wal: true,
+			db_read_only: false,
 			vm_type: Default::default(),
 			enable_network: true,
 			geth_compatibility: false,
 			signer_port: Some(8180),
 			net_settings: Default::default(),
 			dapps_conf: Default::default(),
+			secretstore_conf: Default::default(),
+			private_tx_conf: Default::default(),
 			signer_conf: Default::default(),
 			ui: false,
 			name: "".into(),
 			custom_bootnodes: false,
 			fat_db: Default::default(),
 			no_periodic_snapshot: false,
+			snapshot_period: 10000,
+			snapshot_retain: 1,
+			num_verifiers: None,
+			scale_verifiers: false,
+			io_threads: None,
+			max_reorg_depth: None,
+			on_new_block: None,
+			on_reorg: None,
+			update_policy: UpdatePolicy::None,
+			node_permissions: NodePermissions::None,
+			address_index: false,
+			prune_ancient_bodies: None,
+			shutdown_timeout: 30,
+			sync_threshold: 6,
+			max_response_size: None,
+			scheduler: false,
+			ntp_servers: Vec::new(),
 		}));
 	}
 
@@ -840,9 +1148,72 @@ mod tests {
 			rpc_enabled: true,
 			rpc_interface: "local".to_owned(),
 			rpc_port: 8545,
+			features: build_features(),
 		});
 	}
 
+	#[test]
+	fn should_parse_bandwidth_limits() {
+		// when
+		let conf = parse(&["parity", "--max-upload-kbps", "128", "--max-download-kbps", "1024"]);
+
+		// then
+		let net = conf.net_config().unwrap();
+		assert_eq!(net.max_upload_kbps, Some(128));
+		assert_eq!(net.max_download_kbps, Some(1024));
+	}
+
+	#[test]
+	fn should_parse_peer_address_limits() {
+		// when
+		let conf = parse(&["parity", "--max-peers-per-ip", "2", "--max-peers-per-subnet", "4"]);
+
+		// then
+		let net = conf.net_config().unwrap();
+		assert_eq!(net.max_peers_per_ip, Some(2));
+		assert_eq!(net.max_peers_per_subnet, Some(4));
+	}
+
+	#[test]
+	fn should_parse_tx_permissions() {
+		// given
+		use ethcore::miner::TransactionPermissions;
+
+		// when
+		let conf0 = parse(&["parity"]);
+		let conf1 = parse(&["parity", "--tx-permissions", "0x0000000000000000000000000000000000000005"]);
+		let conf2 = parse(&["parity", "--tx-permissions", "/non/existent/permissions.json"]);
+
+		// then
+		assert_eq!(conf0.tx_permissions(), Ok(TransactionPermissions::Unrestricted));
+		assert_eq!(conf1.tx_permissions(), Ok(TransactionPermissions::Contract(5.into())));
+		assert!(conf2.tx_permissions().is_err());
+	}
+
+	#[test]
+	fn should_reject_zero_snapshot_period() {
+		// when
+		let conf0 = parse(&["parity", "--snapshot-period", "5000"]);
+		let conf1 = parse(&["parity", "--snapshot-period", "0"]);
+
+		// then
+		assert_eq!(conf0.snapshot_period(), Ok(5000));
+		assert!(conf1.snapshot_period().is_err());
+	}
+
+	#[test]
+	fn should_reject_unsafe_prune_ancient_bodies() {
+		// when
+		let conf0 = parse(&["parity", "--prune-ancient-bodies", "30000"]);
+		let conf1 = parse(&["parity", "--prune-ancient-bodies", "7"]);
+		let conf2 = parse(&["parity", "--prune-ancient-bodies", "30000", "--max-reorg-depth", "40000"]);
+
+		// then
+		assert_eq!(conf0.prune_ancient_bodies(), Ok(Some(30000)));
+		assert!(conf1.prune_ancient_bodies().is_err());
+		assert!(conf2.prune_ancient_bodies().is_err());
+	}
+
 	#[test]
 	fn should_parse_rpc_settings_with_geth_compatiblity() {
 		// given
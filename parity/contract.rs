@@ -0,0 +1,117 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Local calls of contracts with hand-encoded ABI.
+
+use util::{Address, Bytes, H256, U256, Uint, FixedHash, Hashable};
+use ethcore::client::{Client, BlockChainClient, BlockID};
+use ethcore::transaction::{Transaction, Action};
+
+/// Left-aligned `bytes32` holding given string.
+pub fn bytes32(s: &str) -> H256 {
+	let mut h = H256::new();
+	let len = ::std::cmp::min(s.len(), 32);
+	h[..len].copy_from_slice(&s.as_bytes()[..len]);
+	h
+}
+
+/// ABI-encodes a call of function with given signature taking only static arguments.
+pub fn encode(signature: &str, args: &[H256]) -> Bytes {
+	let mut data = signature.sha3()[..4].to_vec();
+	for arg in args {
+		data.extend_from_slice(arg);
+	}
+	data
+}
+
+/// Returns 32-byte word of the call output at given byte offset.
+pub fn word(out: &[u8], offset: usize) -> Result<H256, String> {
+	if out.len() < offset + 32 {
+		return Err("Unexpected contract output".into());
+	}
+	Ok(H256::from_slice(&out[offset..offset + 32]))
+}
+
+fn word_as_usize(out: &[u8], offset: usize) -> Result<usize, String> {
+	let value = U256::from(try!(word(out, offset)));
+	match value > U256::from(out.len() as u64) {
+		true => Err("Unexpected contract output".into()),
+		false => Ok(value.low_u64() as usize),
+	}
+}
+
+/// Decodes dynamic `bytes` returned by the call as the argument at given byte offset.
+pub fn bytes(out: &[u8], offset: usize) -> Result<Bytes, String> {
+	let start = try!(word_as_usize(out, offset));
+	let len = try!(word_as_usize(out, start));
+	if out.len() < start + 32 + len {
+		return Err("Unexpected contract output".into());
+	}
+	Ok(out[start + 32..start + 32 + len].to_vec())
+}
+
+/// Decodes dynamic `string` returned by the call as the argument at given byte offset.
+pub fn string(out: &[u8], offset: usize) -> Result<String, String> {
+	String::from_utf8(try!(bytes(out, offset))).map_err(|_| "Invalid string in contract output".into())
+}
+
+/// Executes a local call of the contract at given address on the latest state.
+pub fn call(client: &Client, address: Address, data: Bytes) -> Result<Bytes, String> {
+	let from = Address::default();
+	let transaction = Transaction {
+		nonce: client.latest_nonce(&from),
+		action: Action::Call(address),
+		gas: U256::from(50_000_000),
+		gas_price: U256::default(),
+		value: U256::default(),
+		data: data,
+	}.fake_sign(from);
+
+	client.call(&transaction, BlockID::Latest, Default::default())
+		.map_err(|e| format!("{:?}", e))
+		.map(|executed| executed.output)
+}
+
+/// Looks up address of the contract registered under given name.
+pub fn registry_address(client: &Client, name: &str) -> Result<Address, String> {
+	let registrar: Address = try!(client.additional_params().get("registrar")
+		.ok_or_else(|| "Registrar not defined.".to_owned())
+		.and_then(|registrar| registrar.parse().map_err(|e| format!("Invalid registrar address: {:?}", e))));
+
+	// getAddress(bytes32,string) with `name` hash and "A" as the key
+	let mut data = encode("getAddress(bytes32,string)", &[name.sha3(), H256::from(0x40), H256::from(1)]);
+	data.extend_from_slice(&bytes32("A"));
+
+	let address: Address = try!(word(&try!(call(client, registrar, data)), 0)).into();
+	match address.is_zero() {
+		true => Err(format!("{} is not registered", name)),
+		false => Ok(address),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::H256;
+	use super::{bytes32, encode};
+
+	#[test]
+	fn should_encode_static_call() {
+		let data = encode("latestInTrack(bytes32,uint8)", &[bytes32("parity"), H256::from(1)]);
+		assert_eq!(data.len(), 4 + 64);
+		assert_eq!(&data[4..10], b"parity");
+		assert_eq!(data[67], 1);
+	}
+}
@@ -0,0 +1,105 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{io, thread};
+use std::fs::{self, File};
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+/// Time `parity stop` waits for the daemon to exit.
+const STOP_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Debug, PartialEq)]
+pub enum DaemonCmd {
+	/// Stops daemon with pid stored in given file.
+	Stop(String),
+	/// Checks whether daemon with pid stored in given file is running.
+	Status(String),
+}
+
+pub fn execute(cmd: DaemonCmd) -> Result<String, String> {
+	match cmd {
+		DaemonCmd::Stop(pid_file) => stop(&pid_file),
+		DaemonCmd::Status(pid_file) => status(&pid_file),
+	}
+}
+
+fn read_pid(pid_file: &str) -> Result<i32, String> {
+	let mut content = String::new();
+	try!(File::open(pid_file)
+		.and_then(|mut f| f.read_to_string(&mut content))
+		.map_err(|e| format!("Parity is not running; cannot read pid file {}: {}", pid_file, e)));
+	content.trim().parse().map_err(|_| format!("Invalid pid file {}", pid_file))
+}
+
+fn status(pid_file: &str) -> Result<String, String> {
+	let pid = try!(read_pid(pid_file));
+	if is_running(pid) {
+		Ok(format!("Parity is running (pid {}).", pid))
+	} else {
+		Err(format!("Parity is not running; pid file {} is stale.", pid_file))
+	}
+}
+
+fn stop(pid_file: &str) -> Result<String, String> {
+	let pid = try!(read_pid(pid_file));
+	if !is_running(pid) {
+		let _ = fs::remove_file(pid_file);
+		return Err(format!("Parity is not running; removed stale pid file {}.", pid_file));
+	}
+
+	try!(terminate(pid).map_err(|e| format!("Couldn't stop parity (pid {}): {}", pid, e)));
+
+	let start = Instant::now();
+	while is_running(pid) {
+		if start.elapsed() > Duration::from_secs(STOP_TIMEOUT_SECS) {
+			return Err(format!("Parity (pid {}) did not stop in {} seconds.", pid, STOP_TIMEOUT_SECS));
+		}
+		thread::sleep(Duration::from_millis(100));
+	}
+
+	let _ = fs::remove_file(pid_file);
+	Ok(format!("Parity (pid {}) stopped.", pid))
+}
+
+#[cfg(not(windows))]
+fn is_running(pid: i32) -> bool {
+	extern crate libc;
+
+	// signal 0 only checks whether the process exists
+	let exists = unsafe { libc::kill(pid, 0) } == 0;
+	exists || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(windows))]
+fn terminate(pid: i32) -> Result<(), io::Error> {
+	extern crate libc;
+
+	match unsafe { libc::kill(pid, libc::SIGTERM) } {
+		0 => Ok(()),
+		_ => Err(io::Error::last_os_error()),
+	}
+}
+
+#[cfg(windows)]
+fn is_running(_pid: i32) -> bool {
+	false
+}
+
+#[cfg(windows)]
+fn terminate(_pid: i32) -> Result<(), io::Error> {
+	Err(io::Error::new(io::ErrorKind::Other, "daemon is not supported on windows"))
+}
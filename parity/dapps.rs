@@ -30,6 +30,8 @@ pub struct Configuration {
 	pub user: Option<String>,
 	pub pass: Option<String>,
 	pub dapps_path: String,
+	pub settings_path: String,
+	pub require_signatures: bool,
 }
 
 impl Default for Configuration {
@@ -42,6 +44,8 @@ impl Default for Configuration {
 			user: None,
 			pass: None,
 			dapps_path: replace_home("$HOME/.parity/dapps"),
+			settings_path: replace_home("$HOME/.parity/dapps_settings"),
+			require_signatures: false,
 		}
 	}
 }
@@ -72,7 +76,7 @@ pub fn new(configuration: Configuration, deps: Dependencies) -> Result<Option<We
 		(username.to_owned(), password)
 	});
 
-	Ok(Some(try!(setup_dapps_server(deps, configuration.dapps_path, &addr, configuration.hosts, auth))))
+	Ok(Some(try!(setup_dapps_server(deps, configuration.dapps_path, configuration.settings_path, configuration.require_signatures, &addr, configuration.hosts, auth))))
 }
 
 pub use self::server::WebappServer;
@@ -87,6 +91,8 @@ mod server {
 	pub fn setup_dapps_server(
 		_deps: Dependencies,
 		_dapps_path: String,
+		_settings_path: String,
+		_require_signatures: bool,
 		_url: &SocketAddr,
 		_allowed_hosts: Option<Vec<String>>,
 		_auth: Option<(String, String)>,
@@ -113,6 +119,8 @@ mod server {
 	pub fn setup_dapps_server(
 		deps: Dependencies,
 		dapps_path: String,
+		settings_path: String,
+		require_signatures: bool,
 		url: &SocketAddr,
 		allowed_hosts: Option<Vec<String>>,
 		auth: Option<(String, String)>
@@ -125,6 +133,10 @@ mod server {
 		);
 		let sync = deps.sync.clone();
 		server.with_sync_status(Arc::new(move || sync.status().is_major_syncing()));
+		let apis = deps.apis.clone();
+		server.with_health(Arc::new(move || rpc_apis::node_health(&apis)));
+		server.with_settings_path(settings_path);
+		server.with_required_signatures(require_signatures);
 		let server = rpc_apis::setup_rpc(server, deps.apis.clone(), rpc_apis::ApiSet::UnsafeContext);
 		let start_result = match auth {
 			None => {
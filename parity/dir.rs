@@ -16,7 +16,7 @@
 
 use std::fs;
 use std::path::{PathBuf, Path};
-use util::{H64, H256};
+use util::{H64, H256, U256};
 use util::journaldb::Algorithm;
 use helpers::replace_home;
 
@@ -61,6 +61,49 @@ impl Directories {
 		}
 	}
 
+	/// Get the path of keys used by the node itself, kept apart from user accounts.
+	pub fn node_keys_path(&self) -> PathBuf {
+		let mut dir = Path::new(&self.db).to_path_buf();
+		dir.push("node_keys");
+		dir
+	}
+
+	/// Get the path of settings stored by dapps, kept apart from dapps themselves.
+	pub fn dapps_settings_path(&self) -> PathBuf {
+		let mut dir = Path::new(&self.db).to_path_buf();
+		dir.push("dapps_settings");
+		dir
+	}
+
+	/// Get the path of contract ABIs used to decode calls, one `<address>.json` file per contract,
+	/// and of `wallets.json` listing code hashes of known wallet versions.
+	pub fn abis_path(&self) -> PathBuf {
+		let mut dir = Path::new(&self.db).to_path_buf();
+		dir.push("abis");
+		dir
+	}
+
+	/// Get the path of the secret store key server data.
+	pub fn secretstore_path(&self) -> PathBuf {
+		let mut dir = Path::new(&self.db).to_path_buf();
+		dir.push("secretstore");
+		dir
+	}
+
+	/// Get the path of the private transactions data.
+	pub fn private_tx_path(&self) -> PathBuf {
+		let mut dir = Path::new(&self.db).to_path_buf();
+		dir.push("private_tx");
+		dir
+	}
+
+	/// Get the path of releases downloaded by the updater.
+	pub fn updates_path(&self) -> PathBuf {
+		let mut dir = Path::new(&self.db).to_path_buf();
+		dir.push("updates");
+		dir
+	}
+
 	/// Get the ipc sockets path
 	pub fn ipc_path(&self) -> PathBuf {
 		let mut dir = Path::new(&self.db).to_path_buf();
@@ -77,9 +120,65 @@ pub struct DatabaseDirectories {
 }
 
 impl DatabaseDirectories {
+	// name of the chain directory, derived from the genesis hash and fork name.
+	fn chain_name(&self) -> String {
+		format!("{:?}{}", H64::from(self.genesis_hash), self.fork_name.as_ref().map(|f| format!("-{}", f)).unwrap_or_default())
+	}
+
+	// chain directory used before all chains were moved to `chains/`.
+	fn legacy_fork_path(&self) -> PathBuf {
+		let mut dir = Path::new(&self.path).to_path_buf();
+		dir.push(self.chain_name());
+		dir
+	}
+
+	// network data directory used before it was moved into the chain directory.
+	fn legacy_network_path(&self, network_id: U256) -> PathBuf {
+		let mut dir = Path::new(&self.path).to_path_buf();
+		dir.push("network");
+		dir.push(format!("{}", network_id));
+		dir
+	}
+
 	fn fork_path(&self) -> PathBuf {
 		let mut dir = Path::new(&self.path).to_path_buf();
-		dir.push(format!("{:?}{}", H64::from(self.genesis_hash), self.fork_name.as_ref().map(|f| format!("-{}", f)).unwrap_or_default()));
+		dir.push("chains");
+		dir.push(self.chain_name());
+		dir
+	}
+
+	/// Moves data of this chain from the legacy layout into its own chain directory.
+	/// Known nodes of the network with given id are copied rather than moved, since other
+	/// chains with the same network id used to share them.
+	pub fn migrate_legacy_layout(&self, network_id: U256) -> Result<(), String> {
+		let chain_path = self.fork_path();
+		let legacy_path = self.legacy_fork_path();
+		if legacy_path.exists() && !chain_path.exists() {
+			info!("Moving chain data from {} to {}", legacy_path.display(), chain_path.display());
+			try!(fs::create_dir_all(Path::new(&self.path).join("chains")).map_err(|e| e.to_string()));
+			try!(fs::rename(&legacy_path, &chain_path).map_err(|e| format!("Cannot move chain data to {}: {}", chain_path.display(), e)));
+		}
+
+		let network_path = self.network_path(network_id);
+		let legacy_network = self.legacy_network_path(network_id);
+		if legacy_network.exists() && !network_path.exists() {
+			try!(fs::create_dir_all(&network_path).map_err(|e| e.to_string()));
+			for entry in try!(fs::read_dir(&legacy_network).map_err(|e| e.to_string())) {
+				let entry = try!(entry.map_err(|e| e.to_string()));
+				if entry.path().is_file() {
+					try!(fs::copy(entry.path(), network_path.join(entry.file_name())).map_err(|e| e.to_string()));
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Get the path for data of the network with given id running this chain, such as known nodes.
+	pub fn network_path(&self, network_id: U256) -> PathBuf {
+		let mut dir = self.fork_path();
+		dir.push("network");
+		dir.push(format!("{}", network_id));
 		dir
 	}
 
@@ -97,6 +196,13 @@ impl DatabaseDirectories {
 		dir
 	}
 
+	/// Get the path for checkpoints of the client database used by read-only instances.
+	pub fn read_only_path(&self, pruning: Algorithm) -> PathBuf {
+		let mut dir = self.version_path(pruning);
+		dir.push("read_only");
+		dir
+	}
+
 	/// Get user defaults path
 	pub fn user_defaults_path(&self) -> PathBuf {
 		let mut dir = self.fork_path();
@@ -114,7 +220,9 @@ impl DatabaseDirectories {
 
 #[cfg(test)]
 mod tests {
+	use std::fs;
 	use super::Directories;
+	use devtools::RandomTempPath;
 	use helpers::replace_home;
 
 	#[test]
@@ -127,4 +235,27 @@ mod tests {
 		};
 		assert_eq!(expected, Directories::default());
 	}
+
+	#[test]
+	fn should_migrate_legacy_layout() {
+		let temp = RandomTempPath::create_dir();
+		let base = temp.as_path().to_owned();
+		let dirs = Directories {
+			db: base.to_str().unwrap().into(),
+			..Default::default()
+		};
+		let db_dirs = dirs.database(1.into(), None);
+		fs::create_dir_all(base.join("0000000000000000").join("snapshot")).unwrap();
+		fs::create_dir_all(base.join("network").join("3")).unwrap();
+		fs::File::create(base.join("network").join("3").join("nodes.json")).unwrap();
+
+		db_dirs.migrate_legacy_layout(3.into()).unwrap();
+
+		assert!(!base.join("0000000000000000").exists());
+		assert_eq!(db_dirs.snapshot_path(), base.join("chains").join("0000000000000000").join("snapshot"));
+		assert!(db_dirs.snapshot_path().exists());
+		assert!(db_dirs.network_path(3.into()).join("nodes.json").exists());
+		assert!(!db_dirs.network_path(1.into()).exists());
+		assert!(base.join("network").join("3").join("nodes.json").exists());
+	}
 }
@@ -0,0 +1,89 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `parity evm stats`: runs contract code on the EVM and reports what it cost.
+//!
+//! In differential mode the code is run on every available EVM, each against a fresh state,
+//! and the command fails if they disagree on the outcome.
+
+use std::collections::BTreeMap;
+use std::{env, fs};
+use serde_json::Value;
+use serde_json::ser::to_string;
+use util::{U256, H64, Bytes, FixedHash, ToPretty, Uint};
+use ethcore::client::{EvmTestClient, EvmStats, EnvInfo, VMType};
+use ethcore::spec::Spec;
+use params::SpecType;
+
+#[derive(Debug, PartialEq)]
+pub struct EvmCmd {
+	pub spec: SpecType,
+	pub vm_type: VMType,
+	pub code: Bytes,
+	pub input: Bytes,
+	pub gas: U256,
+	pub gas_price: U256,
+	pub block_number: u64,
+	pub differential: bool,
+}
+
+pub fn execute(cmd: EvmCmd) -> Result<String, String> {
+	let spec = try!(cmd.spec.spec());
+
+	let mut info = EnvInfo::default();
+	info.number = cmd.block_number;
+	info.gas_limit = cmd.gas;
+
+	let vm_types = match cmd.differential {
+		true => VMType::all(),
+		false => vec![cmd.vm_type.clone()],
+	};
+	if vm_types.len() < 2 && cmd.differential {
+		return Err("Differential execution needs another EVM to compare with. Parity is built without the JIT EVM.".into());
+	}
+
+	let mut results = Vec::new();
+	for vm_type in vm_types {
+		let stats = try!(run(&spec, vm_type.clone(), &info, &cmd));
+		results.push((vm_type, stats));
+	}
+
+	let (ref first_type, ref stats) = results[0];
+	for &(ref vm_type, ref other) in &results[1..] {
+		if other.gas_used != stats.gas_used || other.output != stats.output {
+			return Err(format!(
+				"{} and {} disagree. Gas used: {} and {}, output: 0x{} and 0x{}",
+				first_type, vm_type, stats.gas_used, other.gas_used, stats.output.to_hex(), other.output.to_hex()
+			));
+		}
+	}
+
+	let mut map = BTreeMap::new();
+	map.insert("gasUsed".to_owned(), Value::U64(stats.gas_used.low_u64()));
+	map.insert("output".to_owned(), Value::String(format!("0x{}", stats.output.to_hex())));
+	map.insert("timeNs".to_owned(), Value::U64(stats.time.as_secs() * 1_000_000_000 + stats.time.subsec_nanos() as u64));
+	Ok(to_string(&map).expect("serialization of a map cannot fail; qed"))
+}
+
+// runs the code against a fresh state of the chain, kept in a temporary directory.
+fn run(spec: &Spec, vm_type: VMType, info: &EnvInfo, cmd: &EvmCmd) -> Result<EvmStats, String> {
+	let path = env::temp_dir().join(format!("parity-evm-{}", H64::random().hex()));
+	let result = EvmTestClient::new(spec, vm_type, &path).and_then(|mut client| {
+		client.call(info, cmd.code.clone(), cmd.input.clone(), cmd.gas, cmd.gas_price).map_err(|e| format!("EVM execution failed: {}", e))
+	});
+	let _ = fs::remove_dir_all(&path);
+	result
+}
@@ -19,7 +19,7 @@ use std::io::{Write, Read, BufReader, BufRead};
 use std::time::Duration;
 use std::path::Path;
 use std::fs::File;
-use util::{clean_0x, U256, Uint, Address, path, CompactionProfile};
+use util::{clean_0x, U256, Uint, Address, Bytes, FromHex, path, CompactionProfile};
 use util::journaldb::Algorithm;
 use ethcore::client::{Mode, BlockID, VMType, DatabaseCompactionProfile, ClientConfig};
 use ethcore::miner::PendingSet;
@@ -58,7 +58,8 @@ pub fn to_mode(s: &str, timeout: u64, alarm: u64) -> Result<Mode, String> {
 		"active" => Ok(Mode::Active),
 		"passive" => Ok(Mode::Passive(Duration::from_secs(timeout), Duration::from_secs(alarm))),
 		"dark" => Ok(Mode::Dark(Duration::from_secs(timeout))),
-		_ => Err(format!("{}: Invalid address for --mode. Must be one of active, passive or dark.", s)),
+		"offline" => Ok(Mode::Off),
+		_ => Err(format!("{}: Invalid value for --mode. Must be one of active, passive, dark or offline.", s)),
 	}
 }
 
@@ -84,6 +85,27 @@ pub fn to_u256(s: &str) -> Result<U256, String> {
 	}
 }
 
+pub fn to_bytes(s: &str) -> Result<Bytes, String> {
+	clean_0x(s).from_hex().map_err(|e| format!("Invalid hex data {}: {}", s, e))
+}
+
+/// Maximal extra data size allowed by the protocol.
+pub const MAX_EXTRA_DATA_SIZE: usize = 32;
+
+/// Parses block extra data given either as `0x`-prefixed hex or as UTF-8 text.
+pub fn to_extra_data(s: &str) -> Result<Bytes, String> {
+	let data = if s.starts_with("0x") {
+		try!(s[2..].from_hex().map_err(|e| format!("Invalid hex extra data {}: {}", s, e)))
+	} else {
+		s.as_bytes().to_vec()
+	};
+
+	if data.len() > MAX_EXTRA_DATA_SIZE {
+		return Err(format!("Extra data must be at most {} bytes, got {}", MAX_EXTRA_DATA_SIZE, data.len()));
+	}
+	Ok(data)
+}
+
 pub fn to_pending_set(s: &str) -> Result<PendingSet, String> {
 	match s {
 		"cheap" => Ok(PendingSet::AlwaysQueue),
@@ -166,12 +188,24 @@ pub fn to_bootnodes(bootnodes: &Option<String>) -> Result<Vec<String>, String> {
 	}
 }
 
+/// Optional features this binary was built with.
+pub fn build_features() -> Vec<String> {
+	let features = [
+		("dapps", cfg!(feature = "dapps")),
+		("ui", cfg!(feature = "ui")),
+		("ipc", cfg!(feature = "ipc")),
+		("jit", cfg!(feature = "jit")),
+		("stratum", cfg!(feature = "stratum")),
+	];
+	features.iter().filter(|&&(_, enabled)| enabled).map(|&(name, _)| name.to_owned()).collect()
+}
+
 #[cfg(test)]
 pub fn default_network_config() -> ::ethsync::NetworkConfiguration {
 	use ethsync::NetworkConfiguration;
 	NetworkConfiguration {
 		config_path: Some(replace_home("$HOME/.parity/network")),
-		net_config_path: Some(replace_home("$HOME/.parity/network/1")),
+		net_config_path: Some(replace_home("$HOME/.parity/chains/906a34e69aec8c0d/network/1")),
 		listen_address: Some("0.0.0.0:30303".into()),
 		public_address: None,
 		udp_port: None,
@@ -183,6 +217,10 @@ pub fn default_network_config() -> ::ethsync::NetworkConfiguration {
 		min_peers: 25,
 		reserved_nodes: Vec::new(),
 		allow_non_reserved: true,
+		max_upload_kbps: None,
+		max_download_kbps: None,
+		max_peers_per_ip: None,
+		max_peers_per_subnet: None,
 	}
 }
 
@@ -209,6 +247,8 @@ pub fn to_client_config(
 	client_config.blockchain.db_cache_size = Some(cache_config.db_blockchain_cache_size() as usize);
 	// db state cache size, in megabytes
 	client_config.db_cache_size = Some(cache_config.db_state_cache_size() as usize);
+	// state trie node cache size, in bytes
+	client_config.state_cache_size = cache_config.state() as usize * mb;
 	// db queue cache size, in bytes
 	client_config.queue.max_mem_use = cache_config.queue() as usize * mb;
 	// in bytes
@@ -279,10 +319,27 @@ pub fn password_from_file<P>(path: P) -> Result<String, String> where P: AsRef<P
 	}
 }
 
+/// Returns true if given password file can be accessed by other users than the owner.
+#[cfg(unix)]
+fn is_accessible_by_others(file: &File) -> bool {
+	use std::os::unix::fs::PermissionsExt;
+
+	file.metadata().map(|metadata| metadata.permissions().mode() & 0o077 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_accessible_by_others(_file: &File) -> bool {
+	false
+}
+
 /// Reads passwords from files. Treats each line as a separate password.
+/// Warns about files accessible by other users than the owner.
 pub fn passwords_from_files(files: Vec<String>) -> Result<Vec<String>, String> {
 	let passwords = files.iter().map(|filename| {
 		let file = try!(File::open(filename).map_err(|_| format!("{} Unable to read password file. Ensure it exists and permissions are correct.", filename)));
+		if is_accessible_by_others(&file) {
+			warn!("Password file {} is accessible by other users. Restrict its permissions with `chmod 600 {}`.", filename, filename);
+		}
 		let reader = BufReader::new(&file);
 		let lines = reader.lines()
 			.map(|l| l.unwrap())
@@ -301,7 +358,7 @@ mod tests {
 	use util::{U256};
 	use ethcore::client::{Mode, BlockID};
 	use ethcore::miner::PendingSet;
-	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, password_from_file};
+	use super::{to_duration, to_mode, to_block_id, to_u256, to_extra_data, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, password_from_file, passwords_from_files, is_accessible_by_others};
 
 	#[test]
 	fn test_to_duration() {
@@ -328,6 +385,7 @@ mod tests {
 		assert_eq!(to_mode("active", 0, 0).unwrap(), Mode::Active);
 		assert_eq!(to_mode("passive", 10, 20).unwrap(), Mode::Passive(Duration::from_secs(10), Duration::from_secs(20)));
 		assert_eq!(to_mode("dark", 20, 30).unwrap(), Mode::Dark(Duration::from_secs(20)));
+		assert_eq!(to_mode("offline", 20, 30).unwrap(), Mode::Off);
 		assert!(to_mode("other", 20, 30).is_err());
 	}
 
@@ -351,6 +409,14 @@ mod tests {
 		assert!(to_u256("u").is_err())
 	}
 
+	#[test]
+	fn test_to_bytes() {
+		assert_eq!(to_bytes("0x6001").unwrap(), vec![0x60, 0x01]);
+		assert_eq!(to_bytes("6001").unwrap(), vec![0x60, 0x01]);
+		assert_eq!(to_bytes("").unwrap(), Vec::<u8>::new());
+		assert!(to_bytes("0x600").is_err());
+	}
+
 	#[test]
 	fn test_pending_set() {
 		assert_eq!(to_pending_set("cheap").unwrap(), PendingSet::AlwaysQueue);
@@ -392,6 +458,36 @@ mod tests {
 		assert_eq!(password_from_file(path).unwrap().as_bytes(), b"a bc");
 	}
 
+	#[test]
+	#[cfg(unix)]
+	fn test_passwords_file_permissions() {
+		use std::fs::{set_permissions, Permissions};
+		use std::os::unix::fs::PermissionsExt;
+
+		let path = RandomTempPath::new();
+		let mut file = File::create(path.as_path()).unwrap();
+		file.write_all(b"first\nsecond").unwrap();
+		let files = vec![path.as_str().to_owned()];
+
+		set_permissions(path.as_path(), Permissions::from_mode(0o644)).unwrap();
+		assert!(is_accessible_by_others(&File::open(path.as_path()).unwrap()));
+		assert_eq!(passwords_from_files(files.clone()).unwrap(), vec!["first".to_owned(), "second".to_owned()]);
+
+		set_permissions(path.as_path(), Permissions::from_mode(0o600)).unwrap();
+		assert!(!is_accessible_by_others(&File::open(path.as_path()).unwrap()));
+		assert_eq!(passwords_from_files(files).unwrap(), vec!["first".to_owned(), "second".to_owned()]);
+	}
+
+	#[test]
+	fn test_to_extra_data() {
+		assert_eq!(to_extra_data("Parity").unwrap(), b"Parity".to_vec());
+		assert_eq!(to_extra_data("0xcafe").unwrap(), vec![0xca, 0xfe]);
+		assert_eq!(to_extra_data("żółć").unwrap().len(), 8);
+		assert!(to_extra_data("0xcafx").is_err());
+		assert!(to_extra_data(&format!("{:33}", "a")).is_err());
+		assert!(to_extra_data(&format!("0x{:066}", 0)).is_err());
+	}
+
 	#[test]
 	#[cfg_attr(feature = "dev", allow(float_cmp))]
 	fn test_to_price() {
@@ -0,0 +1,156 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! External commands run on chain events (`--on-new-block`, `--on-reorg`).
+//!
+//! Commands are run one at a time by a single worker thread. Events arriving while
+//! the queue of pending commands is full are dropped, as are all events during major sync.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::mpsc::{self, SyncSender, Receiver, TrySendError};
+use std::thread;
+use serde_json::Value;
+use serde_json::ser::to_string;
+use util::{H256, Uint, Mutex};
+use ethcore::client::{Client, BlockChainClient, BlockID, ChainNotify};
+use ethcore::views::BlockView;
+use ethsync::SyncProvider;
+
+/// Maximal number of commands waiting to be run.
+const MAX_QUEUED_HOOKS: usize = 16;
+
+/// Runs configured shell commands when the chain head changes or the chain reorganises.
+pub struct Hooks {
+	client: Arc<Client>,
+	sync: Arc<SyncProvider>,
+	on_new_block: Option<String>,
+	on_reorg: Option<String>,
+	queue: Mutex<SyncSender<(String, String)>>,
+}
+
+impl Hooks {
+	/// Creates new hooks and starts the thread running them. Returns `None` if no command is configured.
+	pub fn new(client: Arc<Client>, sync: Arc<SyncProvider>, on_new_block: Option<String>, on_reorg: Option<String>) -> Result<Option<Self>, String> {
+		if on_new_block.is_none() && on_reorg.is_none() {
+			return Ok(None);
+		}
+
+		let (queue, commands) = mpsc::sync_channel(MAX_QUEUED_HOOKS);
+		try!(thread::Builder::new().name("Hooks".into()).spawn(move || run_queued(commands))
+			.map_err(|e| format!("Error starting hooks thread: {}", e)));
+
+		Ok(Some(Hooks {
+			client: client,
+			sync: sync,
+			on_new_block: on_new_block,
+			on_reorg: on_reorg,
+			queue: Mutex::new(queue),
+		}))
+	}
+
+	fn schedule(&self, command: &str, input: String) {
+		match self.queue.lock().try_send((command.to_owned(), input)) {
+			Ok(()) => {},
+			Err(TrySendError::Full(_)) => warn!("Too many hooks waiting to be run, skipping `{}`", command),
+			Err(TrySendError::Disconnected(_)) => warn!("Hooks thread is not running, skipping `{}`", command),
+		}
+	}
+
+	fn block_info(&self, hash: &H256) -> BTreeMap<String, Value> {
+		let mut map = BTreeMap::new();
+		map.insert("hash".into(), Value::String(format!("0x{}", hash.hex())));
+		if let Some(block) = self.client.block(BlockID::Hash(hash.clone())) {
+			let view = BlockView::new(&block);
+			let header = view.header();
+			map.insert("number".into(), Value::U64(header.number()));
+			map.insert("parentHash".into(), Value::String(format!("0x{}", header.parent_hash().hex())));
+			map.insert("timestamp".into(), Value::U64(header.timestamp()));
+			map.insert("gasUsed".into(), Value::U64(header.gas_used().low_u64()));
+			map.insert("transactions".into(), Value::U64(view.transactions_count() as u64));
+			map.insert("size".into(), Value::U64(block.len() as u64));
+		}
+		map
+	}
+}
+
+fn hashes(hashes: &[H256]) -> Value {
+	Value::Array(hashes.iter().map(|h| Value::String(format!("0x{}", h.hex()))).collect())
+}
+
+/// Runs queued commands until the hooks are dropped.
+fn run_queued(commands: Receiver<(String, String)>) {
+	for (command, input) in commands {
+		run(&command, input);
+	}
+}
+
+/// Runs `command` in a shell, writing `input` to its standard input, and waits for it to finish.
+fn run(command: &str, input: String) {
+	#[cfg(windows)]
+	let mut shell = { let mut c = Command::new("cmd"); c.arg("/C"); c };
+	#[cfg(not(windows))]
+	let mut shell = { let mut c = Command::new("sh"); c.arg("-c"); c };
+
+	let child = shell.arg(command).stdin(Stdio::piped()).spawn();
+	let mut child = match child {
+		Ok(child) => child,
+		Err(e) => {
+			warn!("Failed to run hook `{}`: {}", command, e);
+			return;
+		}
+	};
+
+	if let Some(mut stdin) = child.stdin.take() {
+		if let Err(e) = stdin.write_all(input.as_bytes()) {
+			warn!("Failed to pass block data to hook `{}`: {}", command, e);
+		}
+	}
+	match child.wait() {
+		Ok(status) if !status.success() => warn!("Hook `{}` failed: {}", command, status),
+		Err(e) => warn!("Hook `{}` failed: {}", command, e),
+		_ => {},
+	}
+}
+
+impl ChainNotify for Hooks {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, enacted: Vec<H256>, retracted: Vec<H256>, _sealed: Vec<H256>, _duration: u64) {
+		let head = match enacted.last() {
+			Some(head) => head,
+			None => return,
+		};
+
+		if self.sync.status().is_major_syncing() {
+			return;
+		}
+
+		if let Some(ref command) = self.on_new_block {
+			self.schedule(command, to_string(&self.block_info(head)).expect("serialization of a map cannot fail; qed"));
+		}
+
+		if let Some(ref command) = self.on_reorg {
+			if !retracted.is_empty() {
+				let mut map = BTreeMap::new();
+				map.insert("head".into(), Value::Object(self.block_info(head)));
+				map.insert("retracted".into(), hashes(&retracted));
+				map.insert("enacted".into(), hashes(&enacted));
+				self.schedule(command, to_string(&map).expect("serialization of a map cannot fail; qed"));
+			}
+		}
+	}
+}
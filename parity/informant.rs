@@ -18,6 +18,7 @@ extern crate ansi_term;
 use self::ansi_term::Colour::{White, Yellow, Green, Cyan, Blue};
 use self::ansi_term::Style;
 
+use std::cmp;
 use std::sync::{Arc};
 use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::time::{Instant, Duration};
@@ -98,6 +99,7 @@ impl Informant {
 		}
 
 		*self.last_tick.write() = Instant::now();
+		let peers = self.sync.as_ref().map_or_else(Vec::new, |s| s.peers());
 
 		let mut write_report = self.report.write();
 		let report = self.client.report();
@@ -126,7 +128,7 @@ impl Informant {
 				false => String::new(),
 			},
 			match (&sync_status, &network_config) {
-				(&Some(ref sync_info), &Some(ref net_config)) => format!("{}{}/{}/{} peers",
+				(&Some(ref sync_info), &Some(ref net_config)) => format!("{}{}/{}/{} peers {} req",
 					match importing {
 						true => format!("{}   ", paint(Green.bold(), format!("{:>8}", format!("#{}", sync_info.last_imported_block_number.unwrap_or(chain_info.best_block_number))))),
 						false => String::new(),
@@ -134,6 +136,7 @@ impl Informant {
 					paint(Cyan.bold(), format!("{:2}", sync_info.num_active_peers)),
 					paint(Cyan.bold(), format!("{:2}", sync_info.num_peers)),
 					paint(Cyan.bold(), format!("{:2}", sync_info.current_max_peers(net_config.min_peers, net_config.max_peers))),
+					paint(Cyan.bold(), format!("{:4}", peers.iter().fold(0, |sum, peer| sum + peer.outstanding_requests))),
 				),
 				_ => String::new(),
 			},
@@ -148,6 +151,17 @@ impl Informant {
 			)
 		);
 
+		for peer in &peers {
+			debug!(target: "sync", "Peer {} {} [{}] head {} difficulty {} {} req",
+				peer.id.as_ref().map_or("?", |id| &id[..cmp::min(id.len(), 8)]),
+				peer.client_version,
+				peer.capabilities.join(","),
+				peer.eth_head,
+				peer.eth_difficulty.map_or_else(|| "?".to_owned(), |d| format!("{}", d)),
+				peer.outstanding_requests,
+			);
+		}
+
 		*self.chain_info.write() = Some(chain_info);
 		*self.cache_info.write() = Some(cache_info);
 		*write_report = Some(report);
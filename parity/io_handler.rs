@@ -23,8 +23,11 @@ use ethcore::account_provider::AccountProvider;
 use io::{TimerToken, IoHandler, IoContext};
 
 use informant::Informant;
+use clock::ClockMonitor;
+use cache::MemoryManager;
 
 const INFO_TIMER: TimerToken = 0;
+const CLOCK_TIMER: TimerToken = 1;
 
 pub struct ClientIoHandler {
 	pub client: Arc<Client>,
@@ -32,17 +35,30 @@ pub struct ClientIoHandler {
 	pub net: Arc<ManageNetwork>,
 	pub accounts: Arc<AccountProvider>,
 	pub info: Arc<Informant>,
+	pub clock: Arc<ClockMonitor>,
+	pub memory: Option<Arc<MemoryManager>>,
 	pub shutdown: Arc<AtomicBool>
 }
 
 impl IoHandler<ClientIoMessage> for ClientIoHandler {
 	fn initialize(&self, io: &IoContext<ClientIoMessage>) {
 		io.register_timer(INFO_TIMER, 5000).expect("Error registering timer");
+		io.register_timer(CLOCK_TIMER, 60000).expect("Error registering timer");
 	}
 
 	fn timeout(&self, _io: &IoContext<ClientIoMessage>, timer: TimerToken) {
-		if timer == INFO_TIMER && !self.shutdown.load(Ordering::SeqCst) {
-			self.info.tick();
+		if self.shutdown.load(Ordering::SeqCst) {
+			return;
+		}
+		match timer {
+			INFO_TIMER => {
+				self.info.tick();
+				if let Some(ref memory) = self.memory {
+					memory.tick();
+				}
+			},
+			CLOCK_TIMER => self.clock.tick(),
+			_ => {},
 		}
 	}
 }
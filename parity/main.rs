@@ -28,6 +28,8 @@ extern crate rustc_serialize;
 extern crate ethcore_devtools as devtools;
 extern crate ethcore;
 extern crate ethsync;
+extern crate ethkey;
+extern crate ethcrypto;
 extern crate env_logger;
 extern crate ethcore_logger;
 extern crate ctrlc;
@@ -42,8 +44,8 @@ extern crate ethcore_ipc_nano as nanoipc;
 extern crate serde;
 extern crate serde_json;
 extern crate rlp;
+extern crate fetch;
 
-extern crate json_ipc_server as jsonipc;
 
 extern crate ethcore_ipc_hypervisor as hypervisor;
 extern crate ethcore_rpc;
@@ -70,6 +72,9 @@ extern crate ethcore_stratum;
 #[cfg(feature = "dapps")]
 extern crate ethcore_dapps;
 
+#[cfg(feature = "secretstore")]
+extern crate ethcore_secretstore;
+
 macro_rules! dependency {
 	($dep_ty:ident, $url:expr) => {
 		{
@@ -86,7 +91,15 @@ mod cache;
 mod upgrade;
 mod rpc;
 mod dapps;
+mod secretstore;
+mod private_tx;
 mod informant;
+mod clock;
+mod hooks;
+mod contract;
+mod updater;
+mod registry;
+mod node_filter;
 mod io_handler;
 mod cli;
 mod configuration;
@@ -103,7 +116,10 @@ mod account;
 mod blockchain;
 mod presale;
 mod snapshot;
+mod evm;
+mod read_only;
 mod run;
+mod daemon;
 #[cfg(feature="ipc")]
 mod sync;
 #[cfg(feature="ipc")]
@@ -116,9 +132,11 @@ mod stratum;
 use std::{process, env};
 use std::io::BufReader;
 use std::fs::File;
+use std::path::Path;
 use util::sha3::sha3;
 use cli::Args;
 use configuration::{Cmd, Configuration};
+use dir::Directories;
 use deprecated::find_deprecated;
 
 fn print_hash_of(maybe_file: Option<String>) -> Result<String, String> {
@@ -131,6 +149,12 @@ fn print_hash_of(maybe_file: Option<String>) -> Result<String, String> {
 	}
 }
 
+fn new_node_key(path: String) -> Result<String, String> {
+	ethsync::new_node_key(Path::new(&path))
+		.map(|id| format!("New node key generated. The enode id from the next start is {}.\nNodes listing the old id as a reserved peer need to be updated.", id.hex()))
+		.map_err(|e| format!("Error generating node key: {}", e))
+}
+
 fn execute(command: Cmd) -> Result<String, String> {
 	match command {
 		Cmd::Run(run_cmd) => {
@@ -143,7 +167,10 @@ fn execute(command: Cmd) -> Result<String, String> {
 		Cmd::ImportPresaleWallet(presale_cmd) => presale::execute(presale_cmd),
 		Cmd::Blockchain(blockchain_cmd) => blockchain::execute(blockchain_cmd),
 		Cmd::SignerToken(path) => signer::new_token(path),
+		Cmd::NewNodeKey(path) => new_node_key(path),
 		Cmd::Snapshot(snapshot_cmd) => snapshot::execute(snapshot_cmd),
+		Cmd::Evm(evm_cmd) => evm::execute(evm_cmd),
+		Cmd::Daemon(daemon_cmd) => daemon::execute(daemon_cmd),
 	}
 }
 
@@ -195,6 +222,29 @@ fn sync_main() -> bool {
 	}
 }
 
+/// Runs the release installed by the updater if it's newer than the current executable.
+/// Returns its exit code.
+fn run_latest() -> Option<i32> {
+	let args: Vec<String> = env::args().collect();
+	// arguments unknown to this version might still be valid for the newer one
+	let dirs = Configuration::parse(&args).map(|conf| conf.directories()).unwrap_or_else(|_| Directories::default());
+	let latest = match updater::latest_exe_path(&dirs.updates_path()) {
+		Some(latest) => latest,
+		None => return None,
+	};
+	if env::current_exe().ok().map_or(false, |exe| exe == latest) {
+		return None;
+	}
+
+	match process::Command::new(&latest).args(&args[1..]).status() {
+		Ok(status) => Some(status.code().unwrap_or(1)),
+		Err(e) => {
+			warn!("Failed to run {}: {}", latest.display(), e);
+			None
+		},
+	}
+}
+
 fn main() {
 	if sync_main() {
 		return;
@@ -202,6 +252,10 @@ fn main() {
 
 	if stratum_optional::probably_run() { return; }
 
+	if let Some(code) = run_latest() {
+		process::exit(code);
+	}
+
 	match start() {
 		Ok(result) => {
 			println!("{}", result);
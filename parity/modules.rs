@@ -17,7 +17,7 @@
 use std::sync::Arc;
 use ethcore::client::BlockChainClient;
 use hypervisor::Hypervisor;
-use ethsync::{SyncConfig, NetworkConfiguration, NetworkError};
+use ethsync::{SyncConfig, NetworkConfiguration, NetworkError, ConnectionFilter};
 use ethcore::snapshot::SnapshotService;
 #[cfg(not(feature="ipc"))]
 use self::no_ipc_deps::*;
@@ -124,10 +124,14 @@ pub fn sync
 		net_cfg: NetworkConfiguration,
 		_client: Arc<BlockChainClient>,
 		_snapshot_service: Arc<SnapshotService>,
+		connection_filter: Option<Arc<ConnectionFilter>>,
 		log_settings: &LogConfig,
 	)
 	-> Result<SyncModules, NetworkError>
 {
+	if connection_filter.is_some() {
+		warn!("Node permissioning is not supported when sync runs as a separate process.");
+	}
 	let mut hypervisor = hypervisor_ref.take().expect("There should be hypervisor for ipc configuration");
 	let args = sync_arguments(&hypervisor.io_path, sync_cfg, net_cfg, log_settings);
 	hypervisor = hypervisor.module(SYNC_MODULE_ID, args);
@@ -154,10 +158,11 @@ pub fn sync
 		net_cfg: NetworkConfiguration,
 		client: Arc<BlockChainClient>,
 		snapshot_service: Arc<SnapshotService>,
+		connection_filter: Option<Arc<ConnectionFilter>>,
 		_log_settings: &LogConfig,
 	)
 	-> Result<SyncModules, NetworkError>
 {
-	let eth_sync = try!(EthSync::new(sync_cfg, client, snapshot_service, net_cfg));
+	let eth_sync = try!(EthSync::new(sync_cfg, client, snapshot_service, net_cfg, connection_filter));
 	Ok((eth_sync.clone() as Arc<SyncProvider>, eth_sync.clone() as Arc<ManageNetwork>, eth_sync.clone() as Arc<ChainNotify>))
 }
@@ -0,0 +1,275 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node permissioning. Restricts peer connections to nodes in an allowlist
+//! or to nodes permitted by an on-chain permissioning contract.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::{mpsc, Arc, Weak};
+use std::thread;
+use util::{Address, H256, U256, Hashable, Mutex, clean_0x};
+use ethcore::client::{Client, BlockChainClient, BlockID, ChainNotify};
+use ethcore::transaction::{Transaction, Action};
+use ethsync::{ConnectionFilter, ConnectionDirection, NodeId};
+use helpers::replace_home;
+
+/// Node permissioning configuration.
+#[derive(Debug, PartialEq)]
+pub enum NodePermissions {
+	/// Every node may connect.
+	None,
+	/// Only nodes in the allowlist may connect.
+	AllowList(HashSet<NodeId>),
+	/// Nodes are checked by the permissioning contract at given address.
+	/// It has to implement `connectionAllowed(bytes32,bytes32,bytes32,bytes32) constant returns (bool)`,
+	/// taking the halves of our own node id followed by the halves of the connecting node id.
+	Contract(Address),
+}
+
+impl Default for NodePermissions {
+	fn default() -> Self {
+		NodePermissions::None
+	}
+}
+
+impl NodePermissions {
+	/// Loads an allowlist file with a node id or enode URL on each line.
+	pub fn load_allowlist(path: &str) -> Result<Self, String> {
+		let file = try!(File::open(replace_home(path)).map_err(|e| format!("Error opening node allowlist file: {}", e)));
+		let mut nodes = HashSet::new();
+		for line in BufReader::new(file).lines() {
+			let line = try!(line.map_err(|e| format!("Error reading node allowlist file: {}", e)));
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			nodes.insert(try!(parse_node_id(line)));
+		}
+		Ok(NodePermissions::AllowList(nodes))
+	}
+
+	/// Creates the connection filter enforcing these permissions.
+	pub fn filter(&self, client: &Arc<Client>) -> Option<Arc<ConnectionFilter>> {
+		match *self {
+			NodePermissions::None => None,
+			NodePermissions::AllowList(ref nodes) => Some(Arc::new(AllowList(nodes.clone()))),
+			NodePermissions::Contract(ref contract) => {
+				let filter = Arc::new(ContractFilter::new(client, contract.clone()));
+				client.add_notify(filter.clone());
+				Some(filter)
+			},
+		}
+	}
+}
+
+/// Parses node id given either as a hex string or as an enode URL.
+fn parse_node_id(s: &str) -> Result<NodeId, String> {
+	let id = match s.starts_with("enode://") {
+		true => s[8..].split('@').next().unwrap_or(""),
+		false => clean_0x(s),
+	};
+	id.parse().map_err(|_| format!("Invalid node id: {}", s))
+}
+
+struct AllowList(HashSet<NodeId>);
+
+impl ConnectionFilter for AllowList {
+	fn connection_allowed(&self, _own_id: &NodeId, connecting_id: &NodeId, _direction: ConnectionDirection) -> bool {
+		self.0.contains(connecting_id)
+	}
+}
+
+/// Storage root and code hash of the permissioning contract.
+type ContractState = (Option<H256>, H256);
+
+/// Answers of the permissioning contract in given state.
+#[derive(Default)]
+struct Permissions {
+	contract_state: Option<ContractState>,
+	own_id: Option<NodeId>,
+	allowed: HashMap<NodeId, bool>,
+}
+
+enum Job {
+	/// Ask about the node connecting to us.
+	Ask(NodeId, NodeId),
+	/// Ask again about all known nodes if the contract state changed.
+	Refresh,
+}
+
+/// Asks the permissioning contract on a separate thread. Nodes it hasn't been asked about yet
+/// are refused until the answer arrives. Answers are kept until the contract state changes.
+struct ContractFilter {
+	permissions: Arc<Mutex<Permissions>>,
+	jobs: Mutex<mpsc::Sender<Job>>,
+}
+
+impl ContractFilter {
+	fn new(client: &Arc<Client>, contract: Address) -> Self {
+		let permissions = Arc::new(Mutex::new(Permissions::default()));
+		let (tx, rx) = mpsc::channel();
+		let worker = Worker {
+			client: Arc::downgrade(client),
+			contract: contract,
+			permissions: permissions.clone(),
+		};
+		// the thread exits when the filter is dropped.
+		let spawned = thread::Builder::new().name("Node Filter".into()).spawn(move || worker.run(rx));
+		if let Err(e) = spawned {
+			warn!(target: "network", "Cannot start node permissioning thread: {}. All connections will be refused.", e);
+		}
+		ContractFilter {
+			permissions: permissions,
+			jobs: Mutex::new(tx),
+		}
+	}
+}
+
+impl ConnectionFilter for ContractFilter {
+	fn connection_allowed(&self, own_id: &NodeId, connecting_id: &NodeId, _direction: ConnectionDirection) -> bool {
+		if let Some(allowed) = self.permissions.lock().allowed.get(connecting_id) {
+			return *allowed;
+		}
+
+		let _ = self.jobs.lock().send(Job::Ask(own_id.clone(), connecting_id.clone()));
+		false
+	}
+}
+
+impl ChainNotify for ContractFilter {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, enacted: Vec<H256>, _retracted: Vec<H256>, _sealed: Vec<H256>, _duration: u64) {
+		if !enacted.is_empty() {
+			let _ = self.jobs.lock().send(Job::Refresh);
+		}
+	}
+}
+
+struct Worker {
+	client: Weak<Client>,
+	contract: Address,
+	permissions: Arc<Mutex<Permissions>>,
+}
+
+impl Worker {
+	fn run(self, jobs: mpsc::Receiver<Job>) {
+		for job in jobs.iter() {
+			let client = match self.client.upgrade() {
+				Some(client) => client,
+				None => return,
+			};
+			let state = client.state();
+			let contract_state = (state.storage_root(&self.contract), state.code_hash(&self.contract));
+
+			match job {
+				Job::Ask(own_id, connecting_id) => {
+					if self.permissions.lock().allowed.contains_key(&connecting_id) {
+						continue;
+					}
+					let allowed = self.ask_contract(&client, &own_id, &connecting_id);
+					let mut permissions = self.permissions.lock();
+					if permissions.contract_state.is_none() {
+						permissions.contract_state = Some(contract_state);
+					}
+					permissions.own_id = Some(own_id);
+					permissions.allowed.insert(connecting_id, allowed);
+				},
+				Job::Refresh => {
+					let (own_id, nodes) = {
+						let permissions = self.permissions.lock();
+						if permissions.contract_state.as_ref() == Some(&contract_state) {
+							continue;
+						}
+						(permissions.own_id.clone(), permissions.allowed.keys().cloned().collect::<Vec<_>>())
+					};
+					let allowed = match own_id {
+						Some(ref own_id) => nodes.into_iter()
+							.map(|node| {
+								let allowed = self.ask_contract(&client, own_id, &node);
+								(node, allowed)
+							})
+							.collect(),
+						None => HashMap::new(),
+					};
+					let mut permissions = self.permissions.lock();
+					permissions.contract_state = Some(contract_state);
+					permissions.allowed = allowed;
+				},
+			}
+		}
+	}
+
+	fn ask_contract(&self, client: &Client, own_id: &NodeId, connecting_id: &NodeId) -> bool {
+		let mut data = "connectionAllowed(bytes32,bytes32,bytes32,bytes32)".sha3()[..4].to_vec();
+		data.extend_from_slice(own_id);
+		data.extend_from_slice(connecting_id);
+
+		let from = Address::default();
+		let transaction = Transaction {
+			nonce: client.latest_nonce(&from),
+			action: Action::Call(self.contract.clone()),
+			gas: U256::from(50_000_000),
+			gas_price: U256::default(),
+			value: U256::default(),
+			data: data,
+		}.fake_sign(from);
+
+		match client.call(&transaction, BlockID::Latest, Default::default()) {
+			Ok(executed) => executed.output.len() >= 32 && executed.output[..32].iter().any(|b| *b != 0),
+			Err(e) => {
+				warn!(target: "network", "Calling node permissioning contract failed: {:?}", e);
+				false
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::File;
+	use std::io::Write;
+	use devtools::RandomTempPath;
+	use super::{NodePermissions, parse_node_id};
+
+	const NODE_ID: &'static str = "a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c";
+
+	#[test]
+	fn should_parse_node_ids() {
+		let id = parse_node_id(NODE_ID).unwrap();
+		assert_eq!(parse_node_id(&format!("0x{}", NODE_ID)).unwrap(), id);
+		assert_eq!(parse_node_id(&format!("enode://{}@22.99.55.44:7770", NODE_ID)).unwrap(), id);
+		assert!(parse_node_id("enode://1234@22.99.55.44:7770").is_err());
+	}
+
+	#[test]
+	fn should_load_allowlist() {
+		let path = RandomTempPath::new();
+		{
+			let mut file = File::create(path.as_path()).unwrap();
+			write!(file, "# consortium members\nenode://{}@22.99.55.44:7770\n\n", NODE_ID).unwrap();
+		}
+
+		let permissions = NodePermissions::load_allowlist(path.as_str()).unwrap();
+		match permissions {
+			NodePermissions::AllowList(nodes) => {
+				assert_eq!(nodes.len(), 1);
+				assert!(nodes.contains(&parse_node_id(NODE_ID).unwrap()));
+			},
+			_ => panic!("Expected allowlist"),
+		}
+	}
+}
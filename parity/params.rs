@@ -145,6 +145,7 @@ pub struct AccountsConfig {
 	pub testnet: bool,
 	pub password_files: Vec<String>,
 	pub unlocked_accounts: Vec<Address>,
+	pub engine_signer: Option<Address>,
 }
 
 impl Default for AccountsConfig {
@@ -154,6 +155,7 @@ impl Default for AccountsConfig {
 			testnet: false,
 			password_files: Vec::new(),
 			unlocked_accounts: Vec::new(),
+			engine_signer: None,
 		}
 	}
 }
@@ -0,0 +1,532 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Private transactions.
+//!
+//! A private contract lives in a public wrapper contract deployed by the consortium, which
+//! returns the encrypted private state with `state()` and replaces it with
+//! `setState(bytes,uint8,bytes32,bytes32)` if the new state is signed by one of its validators.
+//! The private state holds code and storage of the private contract and nonces of its senders.
+//! It's encrypted with the document key of the wrapper held by the secret store key server,
+//! so only parties allowed by the secret store ACL can read it.
+//!
+//! The node executes private transactions of allowed senders on the decrypted state and
+//! publishes the result, encrypted and with the node's signature of its hash, in a public
+//! transaction to the wrapper signed by the node's own key. Transactions of a contract are
+//! executed one at a time, on the state published by the previous one for as long as the
+//! publishing transaction is queued, so back-to-back transactions neither override each other
+//! nor may be replayed before the state is mined.
+
+use std::cmp;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use util::{Address, Bytes, H256, U256, Uint, FixedHash, Hashable, Mutex};
+use util::path::restrict_permissions_owner;
+use rlp::{RlpStream, Stream, UntrustedRlp, View, DecoderError};
+use rustc_serialize::hex::FromHex;
+use ethkey::{self, Generator, Random, KeyPair, Secret, Signature};
+use ethcrypto::ecies;
+use ethcore::client::{Client, BlockChainClient, BlockID, Executed};
+use ethcore::miner::{Miner, MinerService};
+use ethcore::transaction::{Transaction, SignedTransaction, Action};
+use ethcore_rpc::{PrivateTransactionService, PrivateCommitment};
+use secretstore::check_permissions;
+use contract::{call, encode, bytes};
+use dir::Directories;
+
+/// Timeout of requests to the key server.
+const KEY_SERVER_TIMEOUT_SECS: u64 = 30;
+/// Maximal size of key server responses, including headers.
+const KEY_SERVER_MAX_RESPONSE: u64 = 64 * 1024;
+/// Gas limit used to estimate gas of the transaction publishing the state.
+const PUBLISH_GAS_LIMIT: u64 = 50_000_000;
+
+/// Private transactions configuration.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Configuration {
+	/// Are private transactions enabled?
+	pub enabled: bool,
+	/// Address of the secret store key server, `host:port`.
+	pub key_server: String,
+	/// Path of the file holding the node's key.
+	pub key_path: String,
+}
+
+impl Default for Configuration {
+	fn default() -> Self {
+		Configuration {
+			enabled: false,
+			key_server: "127.0.0.1:8082".into(),
+			key_path: Directories::default().private_tx_path().join("key").to_string_lossy().into_owned(),
+		}
+	}
+}
+
+/// Private transactions dependencies.
+pub struct Dependencies {
+	/// Blockchain client executing private transactions.
+	pub client: Arc<Client>,
+	/// Miner queueing transactions publishing the state.
+	pub miner: Arc<Miner>,
+}
+
+/// Starts the private transactions service if enabled.
+pub fn start(conf: Configuration, deps: Dependencies) -> Result<Option<Arc<PrivateTransactionService>>, String> {
+	if !conf.enabled {
+		return Ok(None);
+	}
+
+	let key_pair = try!(load_or_create_key(Path::new(&conf.key_path)));
+	info!("Private transactions are signed by 0x{:?}", key_pair.address());
+	Ok(Some(Arc::new(PrivateTransactions {
+		client: Arc::downgrade(&deps.client),
+		miner: Arc::downgrade(&deps.miner),
+		key_pair: key_pair,
+		key_server: conf.key_server,
+		contracts: Mutex::new(HashMap::new()),
+		published: Mutex::new(HashMap::new()),
+	})))
+}
+
+/// Decrypted state of a private contract.
+#[derive(Debug, Default, PartialEq, Clone)]
+struct PrivateState {
+	code: Bytes,
+	storage: BTreeMap<H256, H256>,
+	nonces: BTreeMap<Address, U256>,
+}
+
+impl PrivateState {
+	fn rlp(&self) -> Bytes {
+		let mut stream = RlpStream::new_list(3);
+		stream.append(&self.code);
+		stream.begin_list(self.storage.len());
+		for (key, value) in &self.storage {
+			stream.begin_list(2).append(key).append(value);
+		}
+		stream.begin_list(self.nonces.len());
+		for (sender, nonce) in &self.nonces {
+			stream.begin_list(2).append(sender).append(nonce);
+		}
+		stream.out()
+	}
+
+	fn from_rlp(data: &[u8]) -> Result<Self, DecoderError> {
+		let rlp = UntrustedRlp::new(data);
+		let mut state = PrivateState {
+			code: try!(rlp.val_at(0)),
+			storage: BTreeMap::new(),
+			nonces: BTreeMap::new(),
+		};
+		for item in try!(rlp.at(1)).iter() {
+			state.storage.insert(try!(item.val_at(0)), try!(item.val_at(1)));
+		}
+		for item in try!(rlp.at(2)).iter() {
+			state.nonces.insert(try!(item.val_at(0)), try!(item.val_at(1)));
+		}
+		Ok(state)
+	}
+
+	fn encrypt(&self, key: &KeyPair) -> Result<Bytes, String> {
+		ecies::encrypt_single_message(key.public(), &self.rlp()).map_err(|e| format!("Cannot encrypt private state: {}", e))
+	}
+
+	fn decrypt(key: &KeyPair, encrypted: &[u8]) -> Result<Self, String> {
+		let data = try!(ecies::decrypt_single_message(key.secret(), encrypted).map_err(|e| format!("Cannot decrypt private state: {}", e)));
+		PrivateState::from_rlp(&data).map_err(|_| "Cannot decrypt private state: invalid key or state".into())
+	}
+}
+
+/// State published by the node in a transaction which is not mined yet.
+struct PendingState {
+	state: PrivateState,
+	transaction: H256,
+}
+
+/// Returns the state the next transaction of a contract is executed on: the pending state
+/// while its publishing transaction is queued, the state loaded from the chain otherwise.
+fn current_state<Q, L>(pending: &mut Option<PendingState>, is_queued: Q, load: L) -> Result<PrivateState, String> where
+	Q: Fn(&H256) -> bool,
+	L: FnOnce() -> Result<PrivateState, String>,
+{
+	if !pending.as_ref().map_or(false, |pending| is_queued(&pending.transaction)) {
+		*pending = None;
+	}
+	match *pending {
+		Some(ref pending) => Ok(pending.state.clone()),
+		None => load(),
+	}
+}
+
+/// Returns the nonce of the next transaction publishing a state, following the ones still queued.
+/// Forgets transactions which got mined or dropped from the queue.
+fn next_publish_nonce<Q>(published: &mut HashMap<H256, U256>, latest: U256, is_queued: Q) -> U256 where
+	Q: Fn(&H256) -> bool,
+{
+	*published = published.drain().filter(|&(ref hash, nonce)| nonce >= latest && is_queued(hash)).collect();
+	published.values().fold(latest, |next, nonce| cmp::max(next, *nonce + U256::one()))
+}
+
+/// Returns the nonce given sender must use for its next transaction.
+fn expected_nonce(state: &PrivateState, sender: &Address, nonce: &U256) -> Result<U256, String> {
+	let expected = state.nonces.get(sender).cloned().unwrap_or_else(U256::zero);
+	match *nonce == expected {
+		true => Ok(expected),
+		false => Err(format!("Invalid nonce: expected {}, got {}", expected, nonce)),
+	}
+}
+
+/// Executes private transactions with the node's key.
+struct PrivateTransactions {
+	client: Weak<Client>,
+	miner: Weak<Miner>,
+	key_pair: KeyPair,
+	key_server: String,
+	/// Execution locks and pending states of private contracts.
+	contracts: Mutex<HashMap<Address, Arc<Mutex<Option<PendingState>>>>>,
+	/// Nonces of queued transactions publishing the states.
+	published: Mutex<HashMap<H256, U256>>,
+}
+
+impl PrivateTransactions {
+	fn client(&self) -> Result<Arc<Client>, String> {
+		self.client.upgrade().ok_or_else(|| "Client is shutting down".into())
+	}
+
+	fn miner(&self) -> Result<Arc<Miner>, String> {
+		self.miner.upgrade().ok_or_else(|| "Client is shutting down".into())
+	}
+
+	/// Returns the lock serialising execution of given contract's transactions, guarding its pending state.
+	fn contract(&self, contract: &Address) -> Arc<Mutex<Option<PendingState>>> {
+		self.contracts.lock().entry(contract.clone()).or_insert_with(|| Arc::new(Mutex::new(None))).clone()
+	}
+
+	/// Retrieves the key the state of given contract is encrypted with, generating it if requested.
+	fn document_key(&self, contract: &Address, generate: bool) -> Result<KeyPair, String> {
+		let document = H256::from(contract);
+		let signature = try!(ethkey::sign(self.key_pair.secret(), &document).map_err(|e| format!("Cannot sign key server request: {}", e)));
+		let method = match generate {
+			true => "POST",
+			false => "GET",
+		};
+		let (status, body) = try!(key_server_request(&self.key_server, method, &format!("/{}/{}", document.hex(), signature)));
+		if status != 200 {
+			return Err(format!("Key server refused to hand out the key of 0x{:?} (status {})", contract, status));
+		}
+
+		let encrypted = try!(body.trim().from_hex().map_err(|_| "Invalid key server response".to_owned()));
+		let key = try!(ecies::decrypt_single_message(self.key_pair.secret(), &encrypted).map_err(|e| format!("Cannot decrypt document key: {}", e)));
+		if key.len() != 32 {
+			return Err("Invalid document key".into());
+		}
+		KeyPair::from_secret(Secret::from_slice(&key)).map_err(|e| format!("Invalid document key: {}", e))
+	}
+
+	/// Executes the transaction of an allowed sender on the current state of the contract it calls.
+	/// Returns the contract's key and its state after execution.
+	fn execute(&self, client: &Client, miner: &Miner, pending: &mut Option<PendingState>, transaction: &SignedTransaction, check_nonce: bool) -> Result<(KeyPair, PrivateState, Executed), String> {
+		let sender = try!(transaction.sender().map_err(|e| format!("Invalid transaction signature: {}", e)));
+		let contract = try!(private_contract(transaction));
+		if !transaction.value.is_zero() {
+			return Err("Private transaction cannot transfer value".into());
+		}
+		if !try!(check_permissions(client, &sender, &H256::from(&contract))) {
+			return Err(format!("0x{:?} is not allowed to access the private contract", sender));
+		}
+
+		let key = try!(self.document_key(&contract, false));
+		let mut state = try!(current_state(pending, |hash| miner.queued_transaction(hash).is_some(), || {
+			let encrypted = try!(call(client, contract, encode("state()", &[])).and_then(|out| bytes(&out, 0)));
+			PrivateState::decrypt(&key, &encrypted)
+		}));
+
+		let nonce = match check_nonce {
+			true => try!(expected_nonce(&state, &sender, &transaction.nonce)),
+			false => state.nonces.get(&sender).cloned().unwrap_or_else(U256::zero),
+		};
+
+		let (executed, storage) = try!(client.call_private(transaction, state.code.clone(), state.storage.clone()).map_err(|e| format!("{:?}", e)));
+		if executed.reverted {
+			return Err("Execution reverted".into());
+		}
+		state.storage = storage;
+		state.nonces.insert(sender, nonce + U256::one());
+		Ok((key, state, executed))
+	}
+
+	/// Signs the encrypted state and queues the transaction publishing it.
+	fn publish(&self, client: &Client, miner: &Miner, contract: Address, state: Bytes) -> Result<PrivateCommitment, String> {
+		let state_hash = state.sha3();
+		let signature = try!(ethkey::sign(self.key_pair.secret(), &state_hash).map_err(|e| format!("Cannot sign private state: {}", e)));
+		let address = self.key_pair.address();
+
+		let mut published = self.published.lock();
+		let nonce = next_publish_nonce(&mut published, client.latest_nonce(&address), |hash| miner.queued_transaction(hash).is_some());
+		let mut transaction = Transaction {
+			nonce: nonce,
+			action: Action::Call(contract),
+			gas: U256::from(PUBLISH_GAS_LIMIT),
+			gas_price: miner.sensible_gas_price(),
+			value: U256::zero(),
+			data: encode_set_state(&state, &signature),
+		};
+		let estimate = try!(client.call(&transaction.clone().fake_sign(address), BlockID::Latest, Default::default()).map_err(|e| format!("{:?}", e)));
+		if estimate.reverted {
+			return Err(format!("0x{:?} rejected the private state. Is 0x{:?} one of its validators?", contract, address));
+		}
+		transaction.gas = estimate.gas_used + estimate.refunded;
+
+		let transaction = transaction.sign(self.key_pair.secret());
+		let transaction_hash = transaction.hash();
+		try!(miner.import_own_transaction(client, transaction).map_err(|e| format!("Cannot queue transaction publishing the state: {}", e)));
+		published.insert(transaction_hash, nonce);
+		Ok(PrivateCommitment {
+			contract: contract,
+			state_hash: state_hash,
+			signature: signature,
+			transaction_hash: transaction_hash,
+		})
+	}
+}
+
+impl PrivateTransactionService for PrivateTransactions {
+	fn initialize(&self, contract: Address, code: Bytes) -> Result<PrivateCommitment, String> {
+		let client = try!(self.client());
+		let miner = try!(self.miner());
+		let lock = self.contract(&contract);
+		let mut pending = lock.lock();
+		let key = try!(self.document_key(&contract, true));
+		let state = PrivateState {
+			code: code,
+			..Default::default()
+		};
+		let encrypted = try!(state.encrypt(&key));
+		let commitment = try!(self.publish(&client, &miner, contract, encrypted));
+		*pending = Some(PendingState {
+			state: state,
+			transaction: commitment.transaction_hash,
+		});
+		Ok(commitment)
+	}
+
+	fn send_transaction(&self, transaction: SignedTransaction) -> Result<PrivateCommitment, String> {
+		let client = try!(self.client());
+		let miner = try!(self.miner());
+		let contract = try!(private_contract(&transaction));
+		let lock = self.contract(&contract);
+		let mut pending = lock.lock();
+		let (key, state, _) = try!(self.execute(&client, &miner, &mut pending, &transaction, true));
+		let encrypted = try!(state.encrypt(&key));
+		let commitment = try!(self.publish(&client, &miner, contract, encrypted));
+		*pending = Some(PendingState {
+			state: state,
+			transaction: commitment.transaction_hash,
+		});
+		Ok(commitment)
+	}
+
+	fn call(&self, transaction: SignedTransaction) -> Result<Bytes, String> {
+		let client = try!(self.client());
+		let miner = try!(self.miner());
+		let contract = try!(private_contract(&transaction));
+		let lock = self.contract(&contract);
+		let mut pending = lock.lock();
+		let (_, _, executed) = try!(self.execute(&client, &miner, &mut pending, &transaction, false));
+		Ok(executed.output)
+	}
+}
+
+/// Returns the private contract called by given transaction.
+fn private_contract(transaction: &SignedTransaction) -> Result<Address, String> {
+	match transaction.action {
+		Action::Call(ref address) => Ok(address.clone()),
+		Action::Create => Err("Private transaction must call a private contract".into()),
+	}
+}
+
+/// ABI-encodes `setState(bytes,uint8,bytes32,bytes32)` with the state and its signature.
+fn encode_set_state(state: &[u8], signature: &Signature) -> Bytes {
+	let mut data = encode("setState(bytes,uint8,bytes32,bytes32)", &[
+		H256::from(4 * 32),
+		H256::from(signature.v() as u64 + 27),
+		H256::from_slice(signature.r()),
+		H256::from_slice(signature.s()),
+	]);
+	data.extend_from_slice(&H256::from(state.len() as u64));
+	data.extend_from_slice(state);
+	let padded = data.len() + (32 - state.len() % 32) % 32;
+	data.resize(padded, 0);
+	data
+}
+
+/// Sends a request without a body to the key server. Returns the response status and body.
+fn key_server_request(key_server: &str, method: &str, path: &str) -> Result<(u16, String), String> {
+	let error = |e: io::Error| format!("Key server at {} is not available: {}", key_server, e);
+	let invalid = || "Invalid key server response".to_owned();
+	let mut stream = try!(TcpStream::connect(key_server).map_err(&error));
+	try!(stream.set_read_timeout(Some(Duration::from_secs(KEY_SERVER_TIMEOUT_SECS))).map_err(&error));
+	let request = format!("{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", method, path, key_server);
+	try!(stream.write_all(request.as_bytes()).map_err(&error));
+
+	let mut response = BufReader::new(stream.take(KEY_SERVER_MAX_RESPONSE));
+	let mut line = String::new();
+	try!(response.read_line(&mut line).map_err(&error));
+	let status = try!(line.split_whitespace().nth(1).and_then(|status| status.parse().ok()).ok_or_else(&invalid));
+
+	let mut content_length = None;
+	loop {
+		line.clear();
+		if try!(response.read_line(&mut line).map_err(&error)) == 0 {
+			return Err(invalid());
+		}
+		let header = line.trim();
+		if header.is_empty() {
+			break;
+		}
+		let mut parts = header.splitn(2, ':');
+		let name = parts.next().unwrap_or("");
+		if name.to_lowercase() == "content-length" {
+			content_length = parts.next().and_then(|length| length.trim().parse::<u64>().ok());
+		}
+	}
+
+	// responses without a length would be read until the server closes the connection
+	let content_length = try!(content_length.ok_or_else(&invalid));
+	let mut body = String::new();
+	try!(response.take(content_length).read_to_string(&mut body).map_err(&error));
+	if body.len() as u64 != content_length {
+		return Err(invalid());
+	}
+	Ok((status, body))
+}
+
+fn load_or_create_key(path: &Path) -> Result<KeyPair, String> {
+	let secret = match fs::File::open(path) {
+		Ok(mut file) => {
+			let mut hex = String::new();
+			try!(file.read_to_string(&mut hex).map_err(|e| format!("Cannot read private transactions key: {}", e)));
+			try!(Secret::from_str(hex.trim()).map_err(|_| format!("Invalid private transactions key in {}", path.display())))
+		},
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+			let key_pair = try!(Random.generate().map_err(|e| format!("Cannot generate private transactions key: {}", e)));
+			if let Some(dir) = path.parent() {
+				try!(fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e)));
+			}
+			let mut file = try!(fs::OpenOptions::new().write(true).create_new(true).open(path)
+				.map_err(|e| format!("Cannot create private transactions key: {}", e)));
+			if let Err(e) = restrict_permissions_owner(path) {
+				warn!("Failed to restrict permissions of {} (chmod: {})", path.display(), e);
+			}
+			try!(file.write_all(key_pair.secret().hex().as_bytes()).map_err(|e| format!("Cannot write private transactions key: {}", e)));
+			key_pair.secret().clone()
+		},
+		Err(e) => return Err(format!("Cannot open private transactions key: {}", e)),
+	};
+	KeyPair::from_secret(secret).map_err(|e| format!("Invalid private transactions key: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use util::{Address, H256, U256};
+	use ethkey::{Generator, Random, Signature};
+	use std::collections::HashMap;
+	use super::{PrivateState, PendingState, encode_set_state, current_state, next_publish_nonce, expected_nonce};
+
+	#[test]
+	fn should_encrypt_private_state() {
+		let key = Random.generate().unwrap();
+		let mut state = PrivateState::default();
+		state.code = vec![0x60, 0x00];
+		state.storage.insert(H256::from(1), H256::from(2));
+		state.nonces.insert(Address::from(3), U256::from(4));
+
+		let encrypted = state.encrypt(&key).unwrap();
+
+		assert_eq!(PrivateState::decrypt(&key, &encrypted).unwrap(), state);
+		assert!(PrivateState::decrypt(&Random.generate().unwrap(), &encrypted).is_err());
+	}
+
+	#[test]
+	fn should_encode_set_state_call() {
+		let data = encode_set_state(&[1u8; 33], &Signature::default());
+
+		assert_eq!(data.len(), 4 + 4 * 32 + 32 + 64);
+		assert_eq!(data[4 + 31], 4 * 32);
+		assert_eq!(data[4 + 63], 27);
+		assert_eq!(data[4 + 4 * 32 + 31], 33);
+		assert_eq!(&data[4 + 5 * 32..4 + 5 * 32 + 33], &[1u8; 33][..]);
+		assert!(data[4 + 5 * 32 + 33..].iter().all(|b| *b == 0));
+	}
+
+	#[test]
+	fn should_execute_back_to_back_transactions_on_pending_state() {
+		let sender = Address::from(1);
+		let mut chain_state = PrivateState::default();
+		chain_state.nonces.insert(sender, U256::from(1));
+		let mut published = HashMap::new();
+
+		// first transaction executes on the chain state
+		let state = current_state(&mut None, |_| true, || Ok(chain_state.clone())).unwrap();
+		assert_eq!(expected_nonce(&state, &sender, &U256::from(1)), Ok(U256::from(1)));
+		assert_eq!(next_publish_nonce(&mut published, U256::from(5), |_| true), U256::from(5));
+		published.insert(H256::from(10), U256::from(5));
+		let mut state = state;
+		state.nonces.insert(sender, U256::from(2));
+		let mut pending = Some(PendingState { state: state.clone(), transaction: H256::from(10) });
+
+		// second one executes on the state published by the first one while it's queued
+		let next = current_state(&mut pending, |_| true, || panic!("Pending state is used")).unwrap();
+		assert_eq!(next, state);
+		assert_eq!(expected_nonce(&next, &sender, &U256::from(2)), Ok(U256::from(2)));
+		assert_eq!(next_publish_nonce(&mut published, U256::from(5), |_| true), U256::from(6));
+	}
+
+	#[test]
+	fn should_reject_replayed_transaction() {
+		let sender = Address::from(1);
+		let mut state = PrivateState::default();
+		state.nonces.insert(sender, U256::from(1));
+		let mut pending = Some(PendingState { state: state, transaction: H256::from(10) });
+
+		let state = current_state(&mut pending, |_| true, || panic!("Pending state is used")).unwrap();
+
+		assert!(expected_nonce(&state, &sender, &U256::from(0)).is_err());
+		assert!(expected_nonce(&state, &Address::from(2), &U256::from(0)).is_ok());
+	}
+
+	#[test]
+	fn should_drop_pending_state_once_not_queued() {
+		let mut state = PrivateState::default();
+		state.code = vec![0x60, 0x00];
+		let mut pending = Some(PendingState { state: PrivateState::default(), transaction: H256::from(10) });
+		let mut published = HashMap::new();
+		published.insert(H256::from(10), U256::from(5));
+
+		let current = current_state(&mut pending, |_| false, || Ok(state.clone())).unwrap();
+
+		assert_eq!(current, state);
+		assert!(pending.is_none());
+		assert_eq!(next_publish_nonce(&mut published, U256::from(6), |_| false), U256::from(6));
+		assert!(published.is_empty());
+	}
+}
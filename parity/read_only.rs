@@ -0,0 +1,190 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Read-only access to the database of another running instance.
+//!
+//! RocksDB allows only one process to open a database, so a read-only instance works on its
+//! own checkpoint of the shared database, which hard-links the table files and never writes
+//! to the original. The checkpoint is periodically replaced by a fresh one.
+//!
+//! Every read-only instance keeps its checkpoint in a numbered directory under `read_only/`
+//! and holds a lock on the `LOCK` file inside it while running. Directories whose lock isn't
+//! held belong to instances which didn't shut down cleanly; they are removed by the next
+//! instance starting, releasing the table files the primary instance has compacted away.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
+use util::kvdb;
+use ethcore::client::{Client, BlockChainClient};
+use ethcore::snapshot::DatabaseRestore;
+
+/// Interval between refreshes of the checkpoint.
+pub const REFRESH_INTERVAL_SECS: u64 = 30;
+
+/// Checkpoint of a shared database owned by this read-only instance.
+pub struct ReadOnlyDatabase {
+	source: PathBuf,
+	root: PathBuf,
+	// held for as long as the checkpoint is in use.
+	_lock: File,
+}
+
+impl ReadOnlyDatabase {
+	/// Takes a checkpoint of the database at `source`, keeping it under `read_only_path`.
+	pub fn open(source: &Path, read_only_path: &Path) -> Result<Self, String> {
+		if !source.join("CURRENT").exists() {
+			return Err(format!("No database to share at {}. Start Parity without --db-read-only first.", source.display()));
+		}
+
+		try!(fs::create_dir_all(read_only_path).map_err(|e| format!("Cannot create {}: {}", read_only_path.display(), e)));
+		remove_abandoned(read_only_path);
+
+		let (root, lock) = try!(create_locked_dir(read_only_path));
+		let db = ReadOnlyDatabase {
+			source: source.to_path_buf(),
+			root: root,
+			_lock: lock,
+		};
+		try!(kvdb::checkpoint(&db.source.to_string_lossy(), &db.path().to_string_lossy()));
+		Ok(db)
+	}
+
+	/// Path of the checkpoint the client should be opened with.
+	pub fn path(&self) -> PathBuf {
+		self.root.join("db")
+	}
+
+	/// Replaces the client database with a fresh checkpoint of the shared database.
+	pub fn refresh(&self, client: &Client) -> Result<(), String> {
+		let next = self.root.join("next");
+		let _ = fs::remove_dir_all(&next);
+		try!(kvdb::checkpoint(&self.source.to_string_lossy(), &next.to_string_lossy()));
+		client.restore_db(&next.to_string_lossy()).map_err(|e| format!("{:?}", e))
+	}
+
+	/// Refreshes the checkpoint periodically on a separate thread, until either
+	/// the client or this database is dropped.
+	pub fn refresh_periodically(this: &Arc<Self>, client: &Arc<Client>) {
+		let db = Arc::downgrade(this);
+		let client = Arc::downgrade(client);
+		let spawned = thread::Builder::new().name("DB Refresh".into()).spawn(move || refresh_loop(db, client));
+		if let Err(e) = spawned {
+			warn!("Cannot start database refresh thread: {}. Served data will not be updated.", e);
+		}
+	}
+}
+
+impl Drop for ReadOnlyDatabase {
+	fn drop(&mut self) {
+		if let Err(e) = fs::remove_dir_all(&self.root) {
+			warn!("Cannot remove database checkpoint at {}: {}", self.root.display(), e);
+		}
+	}
+}
+
+fn refresh_loop(db: Weak<ReadOnlyDatabase>, client: Weak<Client>) {
+	loop {
+		thread::sleep(Duration::from_secs(REFRESH_INTERVAL_SECS));
+		let (db, client) = match (db.upgrade(), client.upgrade()) {
+			(Some(db), Some(client)) => (db, client),
+			_ => return,
+		};
+		match db.refresh(&client) {
+			Ok(()) => debug!(target: "db", "Refreshed read-only database at #{}", client.chain_info().best_block_number),
+			Err(e) => warn!("Cannot refresh read-only database: {}", e),
+		}
+	}
+}
+
+// creates a new numbered directory under `path` and locks it.
+fn create_locked_dir(path: &Path) -> Result<(PathBuf, File), String> {
+	for n in 0.. {
+		let dir = path.join(format!("{}", n));
+		// directory creation is atomic, so concurrently starting instances never share a directory.
+		match fs::create_dir(&dir) {
+			Ok(()) => {},
+			Err(_) if dir.exists() => continue,
+			Err(e) => return Err(format!("Cannot create {}: {}", dir.display(), e)),
+		}
+		let lock = try!(File::create(dir.join("LOCK")).map_err(|e| format!("Cannot create lock file: {}", e)));
+		if !try_lock(&lock) {
+			return Err(format!("Cannot lock {}", dir.display()));
+		}
+		return Ok((dir, lock));
+	}
+	unreachable!()
+}
+
+// removes checkpoints of instances which are no longer running.
+fn remove_abandoned(path: &Path) {
+	let entries = match fs::read_dir(path) {
+		Ok(entries) => entries,
+		Err(_) => return,
+	};
+
+	for entry in entries.filter_map(Result::ok) {
+		let dir = entry.path();
+		let abandoned = match File::open(dir.join("LOCK")) {
+			Ok(lock) => try_lock(&lock),
+			// either not yet locked by an instance starting right now, or not a checkpoint at all.
+			Err(_) => false,
+		};
+		if abandoned {
+			info!("Removing abandoned database checkpoint at {}", dir.display());
+			let _ = fs::remove_dir_all(&dir);
+		}
+	}
+}
+
+#[cfg(not(windows))]
+fn try_lock(file: &File) -> bool {
+	extern crate libc;
+	use std::os::unix::io::AsRawFd;
+
+	// released by the OS when the process exits, however it exits.
+	unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) == 0 }
+}
+
+#[cfg(windows)]
+fn try_lock(_file: &File) -> bool {
+	// files of a running instance can't be removed on windows, so removing
+	// an active checkpoint fails on its own.
+	true
+}
+
+#[cfg(test)]
+mod tests {
+	use devtools::RandomTempPath;
+	use super::{create_locked_dir, remove_abandoned};
+
+	#[test]
+	#[cfg(not(windows))]
+	fn should_remove_only_unlocked_checkpoints() {
+		let path = RandomTempPath::create_dir();
+		let (active, _lock) = create_locked_dir(path.as_path()).unwrap();
+		let (abandoned, lock) = create_locked_dir(path.as_path()).unwrap();
+		assert!(active != abandoned);
+		drop(lock);
+
+		remove_abandoned(path.as_path());
+
+		assert!(active.exists());
+		assert!(!abandoned.exists());
+	}
+}
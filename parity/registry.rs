@@ -0,0 +1,194 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Contract metadata published in the on-chain registries.
+//!
+//! Method signatures are looked up in the `signaturereg` contract and tokens
+//! in the `tokenreg` contract, both found through the registrar. ABIs of contracts
+//! are read from the local ABI store, a directory of `<address>.json` files.
+//! Code hashes of known multi-signature wallet versions are listed in `wallets.json`
+//! in the same directory.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Weak};
+use serde_json::de::from_reader;
+use util::{Address, H256, U256, Uint, FixedHash, Mutex};
+use ethcore::client::{Client, BlockChainClient};
+use ethcore_rpc::{ContractRegistry, TokenInfo};
+use ethcore_rpc::v1::call_decoder::abi_signatures;
+use contract::{call, encode, registry_address, string, word};
+
+/// Maximal number of tokens read from the token registry.
+const MAX_TOKENS: u64 = 1024;
+
+/// Looks up contract metadata using the registries of the client's chain.
+pub struct ChainRegistry {
+	client: Weak<Client>,
+	abis_path: PathBuf,
+	// token list read at the best block with given hash.
+	tokens: Mutex<Option<(H256, Vec<(Address, TokenInfo)>)>>,
+}
+
+impl ChainRegistry {
+	/// Creates new registry querying the latest state of given client and reading ABIs from `abis_path`.
+	pub fn new(client: &Arc<Client>, abis_path: PathBuf) -> Self {
+		ChainRegistry {
+			client: Arc::downgrade(client),
+			abis_path: abis_path,
+			tokens: Mutex::new(None),
+		}
+	}
+
+	fn cached_tokens(&self) -> Result<Vec<(Address, TokenInfo)>, String> {
+		let client = try!(self.client.upgrade().ok_or_else(|| "Client is gone".to_owned()));
+		let best_block = client.chain_info().best_block_hash;
+
+		let mut cache = self.tokens.lock();
+		if let Some((ref hash, ref tokens)) = *cache {
+			if *hash == best_block {
+				return Ok(tokens.clone());
+			}
+		}
+		let tokens = try!(self.list_tokens(&client));
+		*cache = Some((best_block, tokens.clone()));
+		Ok(tokens)
+	}
+
+	fn list_tokens(&self, client: &Client) -> Result<Vec<(Address, TokenInfo)>, String> {
+		let tokens = try!(registry_address(client, "tokenreg"));
+
+		let count = U256::from(try!(word(&try!(call(client, tokens, encode("tokenCount()", &[]))), 0)));
+		let count = ::std::cmp::min(count, U256::from(MAX_TOKENS)).low_u64();
+
+		let mut result = Vec::new();
+		for id in 0..count {
+			// token(uint256) returns (address addr, string tla, uint base, string name, address owner)
+			let out = try!(call(client, tokens, encode("token(uint256)", &[H256::from(id)])));
+			let address: Address = try!(word(&out, 0)).into();
+			let symbol = try!(string(&out, 32));
+			let base = U256::from(try!(word(&out, 64)));
+			// unregistered tokens are left in place with empty entries
+			if address.is_zero() || symbol.is_empty() || base.is_zero() {
+				continue;
+			}
+			result.push((address, TokenInfo {
+				symbol: symbol,
+				base: base,
+			}));
+		}
+		Ok(result)
+	}
+
+	fn read_abi(&self, address: &Address) -> Result<Option<Vec<String>>, String> {
+		let mut path = self.abis_path.clone();
+		path.push(format!("{}.json", address.hex()));
+		if !path.exists() {
+			return Ok(None);
+		}
+
+		let mut abi = String::new();
+		try!(File::open(&path).and_then(|mut f| f.read_to_string(&mut abi)).map_err(|e| format!("{}", e)));
+		abi_signatures(&abi).map(Some)
+	}
+
+	fn read_wallet_codes(&self) -> Result<Vec<H256>, String> {
+		let mut path = self.abis_path.clone();
+		path.push("wallets.json");
+		if !path.exists() {
+			return Ok(Vec::new());
+		}
+
+		let file = try!(File::open(&path).map_err(|e| format!("{}", e)));
+		let hashes: Vec<String> = try!(from_reader(file).map_err(|e| format!("{}", e)));
+		hashes.iter()
+			.map(|hash| H256::from_str(hash.trim_left_matches("0x")).map_err(|e| format!("Invalid code hash {}: {:?}", hash, e)))
+			.collect()
+	}
+
+	fn lookup_signature(&self, selector: [u8; 4]) -> Result<Option<String>, String> {
+		let client = try!(self.client.upgrade().ok_or_else(|| "Client is gone".to_owned()));
+		let signatures = try!(registry_address(&client, "signaturereg"));
+
+		// entries(bytes4) returns (string signature)
+		let mut arg = H256::new();
+		arg[..4].copy_from_slice(&selector);
+		let signature = try!(string(&try!(call(&client, signatures, encode("entries(bytes4)", &[arg]))), 0));
+		match signature.is_empty() {
+			true => Ok(None),
+			false => Ok(Some(signature)),
+		}
+	}
+
+	fn lookup_token(&self, address: &Address) -> Result<Option<TokenInfo>, String> {
+		let client = try!(self.client.upgrade().ok_or_else(|| "Client is gone".to_owned()));
+		let tokens = try!(registry_address(&client, "tokenreg"));
+
+		// fromAddress(address) returns (uint id, string tla, uint base, string name, address owner)
+		let out = try!(call(&client, tokens, encode("fromAddress(address)", &[address.clone().into()])));
+		let symbol = try!(string(&out, 32));
+		let base = U256::from(try!(word(&out, 64)));
+		match symbol.is_empty() || base.is_zero() {
+			true => Ok(None),
+			false => Ok(Some(TokenInfo {
+				symbol: symbol,
+				base: base,
+			})),
+		}
+	}
+}
+
+impl ContractRegistry for ChainRegistry {
+	fn method_signature(&self, selector: [u8; 4]) -> Option<String> {
+		self.lookup_signature(selector).unwrap_or_else(|e| {
+			trace!(target: "registry", "Error looking up method signature: {}", e);
+			None
+		})
+	}
+
+	fn token(&self, address: &Address) -> Option<TokenInfo> {
+		self.lookup_token(address).unwrap_or_else(|e| {
+			trace!(target: "registry", "Error looking up token {}: {}", address, e);
+			None
+		})
+	}
+
+	fn tokens(&self) -> Vec<(Address, TokenInfo)> {
+		self.cached_tokens().unwrap_or_else(|e| {
+			trace!(target: "registry", "Error listing tokens: {}", e);
+			Vec::new()
+		})
+	}
+
+	fn abi(&self, address: &Address) -> Option<Vec<String>> {
+		self.read_abi(address).unwrap_or_else(|e| {
+			warn!(target: "registry", "Invalid ABI of {}: {}", address, e);
+			None
+		})
+	}
+
+	fn is_wallet_code(&self, code_hash: &H256) -> bool {
+		self.read_wallet_codes()
+			.map(|hashes| hashes.contains(code_hash))
+			.unwrap_or_else(|e| {
+				warn!(target: "registry", "Invalid list of wallet versions: {}", e);
+				false
+			})
+	}
+}
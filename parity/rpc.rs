@@ -15,17 +15,17 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::fmt;
+use std::cmp::{min, max};
 use std::sync::Arc;
 use std::net::SocketAddr;
 use io::PanicHandler;
-use ethcore_rpc::{RpcServerError, RpcServer as Server};
-use jsonipc;
+use ethcore_rpc::{HttpServerError, RpcServer as Server};
 use rpc_apis;
 use rpc_apis::ApiSet;
 use helpers::parity_ipc_path;
 
-pub use jsonipc::Server as IpcServer;
-pub use ethcore_rpc::Server as HttpServer;
+pub use ethcore_rpc::IpcServer;
+pub use ethcore_rpc::HttpServer;
 
 #[derive(Debug, PartialEq)]
 pub struct HttpConfiguration {
@@ -35,6 +35,7 @@ pub struct HttpConfiguration {
 	pub apis: ApiSet,
 	pub cors: Option<Vec<String>>,
 	pub hosts: Option<Vec<String>>,
+	pub threads: Option<usize>,
 }
 
 impl Default for HttpConfiguration {
@@ -46,6 +47,7 @@ impl Default for HttpConfiguration {
 			apis: ApiSet::UnsafeContext,
 			cors: None,
 			hosts: Some(Vec::new()),
+			threads: None,
 		}
 	}
 }
@@ -89,7 +91,8 @@ pub fn new_http(conf: HttpConfiguration, deps: &Dependencies) -> Result<Option<H
 
 	let url = format!("{}:{}", conf.interface, conf.port);
 	let addr = try!(url.parse().map_err(|_| format!("Invalid JSONRPC listen host/port given: {}", url)));
-	Ok(Some(try!(setup_http_rpc_server(deps, &addr, conf.cors, conf.hosts, conf.apis))))
+	let threads = conf.threads.unwrap_or_else(|| min(max(::num_cpus::get() / 2, 2), 8));
+	Ok(Some(try!(setup_http_rpc_server(deps, &addr, conf.cors, conf.hosts, conf.apis, threads))))
 }
 
 fn setup_rpc_server(apis: ApiSet, deps: &Dependencies) -> Result<Server, String> {
@@ -102,13 +105,16 @@ pub fn setup_http_rpc_server(
 	url: &SocketAddr,
 	cors_domains: Option<Vec<String>>,
 	allowed_hosts: Option<Vec<String>>,
-	apis: ApiSet
+	apis: ApiSet,
+	threads: usize,
 ) -> Result<HttpServer, String> {
-	let server = try!(setup_rpc_server(apis, dependencies));
+	let mut server = try!(setup_rpc_server(apis, dependencies));
+	let deps = dependencies.apis.clone();
+	server.with_health(Arc::new(move || rpc_apis::node_health(&deps)));
 	let ph = dependencies.panic_handler.clone();
-	let start_result = server.start_http(url, cors_domains, allowed_hosts, ph);
+	let start_result = server.start_http(url, cors_domains, allowed_hosts, ph, threads);
 	match start_result {
-		Err(RpcServerError::IoError(err)) => Err(format!("RPC io error: {}", err)),
+		Err(HttpServerError::IoError(err)) => Err(format!("RPC io error: {}", err)),
 		Err(e) => Err(format!("RPC error: {:?}", e)),
 		Ok(server) => Ok(server),
 	}
@@ -121,9 +127,5 @@ pub fn new_ipc(conf: IpcConfiguration, deps: &Dependencies) -> Result<Option<Ipc
 
 pub fn setup_ipc_rpc_server(dependencies: &Dependencies, addr: &str, apis: ApiSet) -> Result<IpcServer, String> {
 	let server = try!(setup_rpc_server(apis, dependencies));
-	match server.start_ipc(addr) {
-		Err(jsonipc::Error::Io(io_error)) => Err(format!("RPC io error: {}", io_error)),
-		Err(any_error) => Err(format!("Rpc error: {:?}", any_error)),
-		Ok(server) => Ok(server)
-	}
+	server.start_ipc(addr, Some(dependencies.apis.address_watcher.clone()))
 }
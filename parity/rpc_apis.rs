@@ -20,11 +20,12 @@ use std::cmp::PartialEq;
 use std::str::FromStr;
 use std::sync::Arc;
 use util::RotatingLogger;
-use ethcore::miner::{Miner, ExternalMiner};
-use ethcore::client::Client;
+use ethcore::miner::{Miner, ExternalMiner, MinerService};
+use ethcore::client::{Client, BlockChainClient};
 use ethcore::account_provider::AccountProvider;
 use ethsync::{ManageNetwork, SyncProvider};
-use ethcore_rpc::{Extendable, NetworkSettings};
+use ethcore_rpc::{Extendable, NetworkSettings, ResponseCache, SnapshotStatusProvider, UpdateService, AddressWatcher, Scheduler, ContractRegistry, PrivateTransactionService};
+use ethcore_rpc::v1::types::{Health, HealthCheck};
 pub use ethcore_rpc::SignerService;
 
 
@@ -39,6 +40,7 @@ pub enum Api {
 	EthcoreSet,
 	Traces,
 	Rpc,
+	Private,
 }
 
 impl FromStr for Api {
@@ -57,6 +59,7 @@ impl FromStr for Api {
 			"ethcore_set" => Ok(EthcoreSet),
 			"traces" => Ok(Traces),
 			"rpc" => Ok(Rpc),
+			"private" => Ok(Private),
 			api => Err(format!("Unknown api: {}", api))
 		}
 	}
@@ -105,6 +108,15 @@ pub struct Dependencies {
 	pub settings: Arc<NetworkSettings>,
 	pub net_service: Arc<ManageNetwork>,
 	pub geth_compatibility: bool,
+	pub response_cache: Arc<ResponseCache>,
+	pub address_watcher: Arc<AddressWatcher>,
+	pub scheduler: Option<Arc<Scheduler<Client, Miner>>>,
+	pub updater: Arc<UpdateService>,
+	pub private_tx: Option<Arc<PrivateTransactionService>>,
+	pub contract_registry: Arc<ContractRegistry>,
+	pub snapshot: Arc<SnapshotStatusProvider>,
+	pub sync_threshold: u64,
+	pub max_response_size: Option<usize>,
 }
 
 fn to_modules(apis: &[Api]) -> BTreeMap<String, String> {
@@ -120,6 +132,7 @@ fn to_modules(apis: &[Api]) -> BTreeMap<String, String> {
 			Api::EthcoreSet => ("ethcore_set", "1.0"),
 			Api::Traces => ("traces", "1.0"),
 			Api::Rpc => ("rpc", "1.0"),
+			Api::Private => ("private", "1.0"),
 		};
 		modules.insert(name.into(), version.into());
 	}
@@ -165,6 +178,9 @@ pub fn setup_rpc<T: Extendable>(server: T, deps: Arc<Dependencies>, apis: ApiSet
 					EthClientOptions {
 						allow_pending_receipt_query: !deps.geth_compatibility,
 						send_block_number_in_get_work: !deps.geth_compatibility,
+						response_cache: Some(deps.response_cache.clone()),
+						sync_threshold: deps.sync_threshold,
+						max_response_size: deps.max_response_size,
 					}
 				);
 				server.add_delegate(client.to_delegate());
@@ -179,17 +195,17 @@ pub fn setup_rpc<T: Extendable>(server: T, deps: Arc<Dependencies>, apis: ApiSet
 				}
 			},
 			Api::Personal => {
-				server.add_delegate(PersonalClient::new(&deps.secret_store, &deps.client, &deps.miner, deps.signer_port, deps.geth_compatibility).to_delegate());
+				server.add_delegate(PersonalClient::new(&deps.secret_store, &deps.client, &deps.miner, deps.signer_port, deps.geth_compatibility, deps.scheduler.as_ref()).to_delegate());
 			},
 			Api::Signer => {
-				server.add_delegate(SignerClient::new(&deps.secret_store, &deps.client, &deps.miner, &deps.signer_service).to_delegate());
+				server.add_delegate(SignerClient::new(&deps.secret_store, &deps.client, &deps.miner, &deps.signer_service, &deps.contract_registry).to_delegate());
 			},
 			Api::Ethcore => {
 				let signer = deps.signer_port.map(|_| deps.signer_service.clone());
-				server.add_delegate(EthcoreClient::new(&deps.client, &deps.miner, &deps.sync, &deps.net_service, deps.logger.clone(), deps.settings.clone(), signer).to_delegate())
+				server.add_delegate(EthcoreClient::new(&deps.client, &deps.miner, &deps.sync, &deps.net_service, deps.logger.clone(), deps.settings.clone(), deps.external_miner.clone(), signer, &deps.snapshot, deps.contract_registry.clone()).to_delegate())
 			},
 			Api::EthcoreSet => {
-				server.add_delegate(EthcoreSetClient::new(&deps.client, &deps.miner, &deps.net_service).to_delegate())
+				server.add_delegate(EthcoreSetClient::new(&deps.client, &deps.miner, &deps.net_service, &deps.updater, &deps.secret_store).to_delegate())
 			},
 			Api::Traces => {
 				server.add_delegate(TracesClient::new(&deps.client, &deps.miner).to_delegate())
@@ -197,12 +213,42 @@ pub fn setup_rpc<T: Extendable>(server: T, deps: Arc<Dependencies>, apis: ApiSet
 			Api::Rpc => {
 				let modules = to_modules(&apis);
 				server.add_delegate(RpcClient::new(modules).to_delegate());
-			}
+			},
+			Api::Private => {
+				server.add_delegate(PrivateClient::new(deps.private_tx.as_ref()).to_delegate());
+			},
 		}
 	}
 	server
 }
 
+/// Checks whether the node has peers, is within the sync threshold
+/// of the network head and is accepting transactions.
+pub fn node_health(deps: &Dependencies) -> Health {
+	let status = deps.sync.status();
+	let best_block = deps.client.chain_info().best_block_number;
+	let highest_block = status.highest_block_number.unwrap_or(best_block);
+	let lag = highest_block.saturating_sub(best_block);
+	let miner_status = deps.miner.status();
+	let queued = miner_status.transactions_in_pending_queue + miner_status.transactions_in_future_queue;
+	let limit = deps.miner.transactions_limit();
+
+	Health {
+		peers: HealthCheck {
+			healthy: status.num_peers > 0,
+			details: format!("{} peers connected", status.num_peers),
+		},
+		sync: HealthCheck {
+			healthy: !status.is_major_syncing() && lag <= deps.sync_threshold,
+			details: format!("{} blocks behind the highest block known to peers", lag),
+		},
+		tx_queue: HealthCheck {
+			healthy: queued < limit,
+			details: format!("{} of {} transactions queued", queued, limit),
+		},
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::{Api, ApiSet};
@@ -218,6 +264,7 @@ mod test {
 		assert_eq!(Api::EthcoreSet, "ethcore_set".parse().unwrap());
 		assert_eq!(Api::Traces, "traces".parse().unwrap());
 		assert_eq!(Api::Rpc, "rpc".parse().unwrap());
+		assert_eq!(Api::Private, "private".parse().unwrap());
 		assert!("rp".parse::<Api>().is_err());
 	}
 
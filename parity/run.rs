@@ -15,43 +15,52 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::{Arc, Mutex, Condvar};
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{thread, mem, fs};
 use ctrlc::CtrlC;
 use fdlimit::raise_fd_limit;
 use ethcore_logger::{Config as LogConfig, setup_log};
-use ethcore_rpc::NetworkSettings;
+use ethcore_rpc::{NetworkSettings, ResponseCache, AddressWatcher, Scheduler};
 use ethsync::NetworkConfiguration;
 use util::{Colour, version, U256};
 use io::{MayPanic, ForwardPanic, PanicHandler};
-use ethcore::client::{Mode, DatabaseCompactionProfile, VMType, ChainNotify};
+use ethcore::client::{Client, Mode, DatabaseCompactionProfile, VMType, ChainNotify, BlockChainClient};
 use ethcore::service::ClientService;
 use ethcore::account_provider::AccountProvider;
 use ethcore::miner::{Miner, MinerService, ExternalMiner, MinerOptions};
 use ethcore::snapshot;
 use ethsync::{SyncConfig, SyncProvider};
 use informant::Informant;
+use hooks::Hooks;
+use updater::{Updater, UpdatePolicy};
+use registry::ChainRegistry;
+use node_filter::NodePermissions;
+use read_only::ReadOnlyDatabase;
 
 use rpc::{HttpServer, IpcServer, HttpConfiguration, IpcConfiguration};
 use signer::SignerServer;
 use dapps::WebappServer;
+use secretstore::KeyServer;
 use io_handler::ClientIoHandler;
+use clock::ClockMonitor;
 use params::{
 	SpecType, Pruning, AccountsConfig, GasPricerConfig, MinerExtras, Switch,
 	tracing_switch_to_bool, fatdb_switch_to_bool,
 };
 use helpers::{to_client_config, execute_upgrades, passwords_from_files};
 use dir::Directories;
-use cache::CacheConfig;
+use cache::{CacheConfig, MemoryManager};
 use user_defaults::UserDefaults;
 use dapps;
+use secretstore;
+use private_tx;
 use signer;
 use modules;
 use rpc_apis;
 use rpc;
 use url;
 
-// how often to take periodic snapshots.
-const SNAPSHOT_PERIOD: u64 = 10000;
-
 // how many blocks to wait before starting a periodic snapshot.
 const SNAPSHOT_HISTORY: u64 = 500;
 
@@ -69,25 +78,46 @@ pub struct RunCmd {
 	pub ipc_conf: IpcConfiguration,
 	pub net_conf: NetworkConfiguration,
 	pub network_id: Option<U256>,
+	pub tx_relay: bool,
 	pub acc_conf: AccountsConfig,
 	pub gas_pricer: GasPricerConfig,
 	pub miner_extras: MinerExtras,
-	pub mode: Mode,
+	pub mode: Option<Mode>,
 	pub tracing: Switch,
 	pub fat_db: Switch,
 	pub compaction: DatabaseCompactionProfile,
 	pub wal: bool,
+	pub db_read_only: bool,
 	pub vm_type: VMType,
 	pub enable_network: bool,
 	pub geth_compatibility: bool,
 	pub signer_port: Option<u16>,
 	pub net_settings: NetworkSettings,
 	pub dapps_conf: dapps::Configuration,
+	pub secretstore_conf: secretstore::Configuration,
+	pub private_tx_conf: private_tx::Configuration,
 	pub signer_conf: signer::Configuration,
 	pub ui: bool,
 	pub name: String,
 	pub custom_bootnodes: bool,
 	pub no_periodic_snapshot: bool,
+	pub snapshot_period: u64,
+	pub snapshot_retain: usize,
+	pub num_verifiers: Option<usize>,
+	pub scale_verifiers: bool,
+	pub io_threads: Option<usize>,
+	pub max_reorg_depth: Option<u64>,
+	pub on_new_block: Option<String>,
+	pub on_reorg: Option<String>,
+	pub update_policy: UpdatePolicy,
+	pub node_permissions: NodePermissions,
+	pub address_index: bool,
+	pub prune_ancient_bodies: Option<u64>,
+	pub shutdown_timeout: u64,
+	pub sync_threshold: u64,
+	pub max_response_size: Option<usize>,
+	pub scheduler: bool,
+	pub ntp_servers: Vec<String>,
 }
 
 pub fn execute(cmd: RunCmd) -> Result<(), String> {
@@ -109,8 +139,15 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 	// load genesis hash
 	let genesis_hash = spec.genesis_header().hash();
 
+	// network id, possibly overridden by the user
+	let network_id = cmd.network_id.unwrap_or_else(|| spec.network_id());
+
 	// database paths
 	let db_dirs = cmd.dirs.database(genesis_hash, spec.fork_name.clone());
+	// the database of a read-only instance is owned by another one
+	if !cmd.db_read_only {
+		try!(db_dirs.migrate_legacy_layout(network_id));
+	}
 
 	// user defaults path
 	let user_defaults_path = db_dirs.user_defaults_path();
@@ -127,16 +164,35 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 	// check if fatdb is on
 	let fat_db = try!(fatdb_switch_to_bool(cmd.fat_db, &user_defaults, algorithm));
 
+	// select mode, falling back to the one used last time
+	let mode = match cmd.mode {
+		Some(mode) => mode,
+		None => user_defaults.mode.clone(),
+	};
+
 	// prepare client and snapshot paths.
 	let client_path = db_dirs.client_path(algorithm);
 	let snapshot_path = db_dirs.snapshot_path();
 
 	// execute upgrades
-	try!(execute_upgrades(&db_dirs, algorithm, cmd.compaction.compaction_profile()));
+	if !cmd.db_read_only {
+		try!(execute_upgrades(&db_dirs, algorithm, cmd.compaction.compaction_profile()));
+	}
+
+	// read-only instances serve a checkpoint of the shared database
+	let read_only_db = match cmd.db_read_only {
+		true => {
+			let db = try!(ReadOnlyDatabase::open(&client_path, &db_dirs.read_only_path(algorithm)));
+			info!("Serving the database at {} read-only", client_path.display());
+			Some(Arc::new(db))
+		},
+		false => None,
+	};
+	let client_path = read_only_db.as_ref().map_or(client_path, |db| db.path());
 
 	// run in daemon mode
-	if let Some(pid_file) = cmd.daemon {
-		try!(daemonize(pid_file));
+	if let Some(ref pid_file) = cmd.daemon {
+		try!(daemonize(pid_file.clone()));
 	}
 
 	// display info about used pruning algorithm
@@ -160,32 +216,36 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 
 	// create sync config
 	let mut sync_config = SyncConfig::default();
-	sync_config.network_id = match cmd.network_id {
-		Some(id) => id,
-		None => spec.network_id(),
-	};
+	sync_config.network_id = network_id;
 	if spec.subprotocol_name().len() != 3 {
 		warn!("Your chain specification's subprotocol length is not 3. Ignoring.");
 	} else {
 		sync_config.subprotocol_name.clone_from_slice(spec.subprotocol_name().as_bytes());
 	}
 	sync_config.fork_block = spec.fork_block();
+	sync_config.checkpoint = spec.checkpoint();
+	sync_config.tx_relay = cmd.tx_relay;
 
 	// prepare account provider
 	let account_provider = Arc::new(try!(prepare_account_provider(&cmd.dirs, cmd.acc_conf)));
 
+	if cmd.miner_extras.extra_data.len() > spec.engine.maximum_extra_data_size() {
+		return Err(format!("Extra data must be at most {} bytes for this chain", spec.engine.maximum_extra_data_size()));
+	}
+
 	// create miner
 	let miner = Miner::new(cmd.miner_options, cmd.gas_pricer.into(), &spec, Some(account_provider.clone()));
-	miner.set_author(cmd.miner_extras.author);
+	// blocks sealed by the engine signer have to be authored by it
+	miner.set_author(account_provider.engine_signer().unwrap_or(cmd.miner_extras.author));
 	miner.set_gas_floor_target(cmd.miner_extras.gas_floor_target);
 	miner.set_gas_ceil_target(cmd.miner_extras.gas_ceil_target);
 	miner.set_extra_data(cmd.miner_extras.extra_data);
 	miner.set_transactions_limit(cmd.miner_extras.transactions_limit);
 
 	// create client config
-	let client_config = to_client_config(
+	let mut client_config = to_client_config(
 		&cmd.cache_config,
-		cmd.mode,
+		mode.clone(),
 		tracing,
 		fat_db,
 		cmd.compaction,
@@ -194,6 +254,12 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 		cmd.name,
 		algorithm,
 	);
+	client_config.queue.verifier_count = cmd.num_verifiers;
+	client_config.queue.scale_verifiers = cmd.scale_verifiers;
+	client_config.io_workers = cmd.io_threads;
+	client_config.max_reorg_depth = cmd.max_reorg_depth;
+	client_config.address_index = cmd.address_index;
+	client_config.prune_ancient_bodies = cmd.prune_ancient_bodies;
 
 	// set up bootnodes
 	let mut net_conf = cmd.net_conf;
@@ -220,28 +286,70 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 	// take handle to client
 	let client = service.client();
 	let snapshot_service = service.snapshot_service();
+	snapshot_service.set_retain(cmd.snapshot_retain);
+
+	if let Some(ref db) = read_only_db {
+		ReadOnlyDatabase::refresh_periodically(db, &client);
+	}
 
 	// create external miner
 	let external_miner = Arc::new(ExternalMiner::default());
 
 	// create sync object
 	let (sync_provider, manage_network, chain_notify) = try!(modules::sync(
-		&mut hypervisor, sync_config, net_conf.into(), client.clone(), snapshot_service, &cmd.logger_config,
+		&mut hypervisor, sync_config, net_conf.into(), client.clone(), snapshot_service.clone(), cmd.node_permissions.filter(&client), &cmd.logger_config,
 	).map_err(|e| format!("Sync error: {}", e)));
 
 	service.add_notify(chain_notify.clone());
 
-	// start network
-	if cmd.enable_network {
+	// start network unless the client starts asleep or only serves a shared database
+	let enable_network = match mode {
+		Mode::Dark(..) | Mode::Off => false,
+		_ => cmd.enable_network && !cmd.db_read_only,
+	};
+	if enable_network {
 		chain_notify.start();
 	}
 
+	// cache of block and receipt responses shared by all rpc servers
+	let response_cache = Arc::new(ResponseCache::default());
+	service.add_notify(response_cache.clone());
+
+	// addresses watched through rpc
+	let address_watcher = Arc::new(AddressWatcher::new(&(client.clone() as Arc<BlockChainClient>)));
+	service.add_notify(address_watcher.clone());
+
+	// recurring transactions signed by the node
+	let scheduler = match cmd.scheduler {
+		true => {
+			let scheduler = Arc::new(Scheduler::new(&client, &miner, &account_provider));
+			service.add_notify(scheduler.clone());
+			Some(scheduler)
+		},
+		false => None,
+	};
+
+	// checks the release registry for new versions
+	let updater = Arc::new(Updater::new(&client, &sync_provider, cmd.update_policy, cmd.dirs.updates_path()));
+	service.add_notify(updater.clone());
+
+	// executes transactions of private contracts
+	let private_tx_deps = private_tx::Dependencies {
+		client: client.clone(),
+		miner: miner.clone(),
+	};
+	let private_tx_service = try!(private_tx::start(cmd.private_tx_conf.clone(), private_tx_deps));
+
 	// set up dependencies for rpc servers
 	let signer_path = cmd.signer_conf.signer_path.clone();
+	let exchange_path = signer_path.clone();
 	let deps_for_rpc_apis = Arc::new(rpc_apis::Dependencies {
 		signer_port: cmd.signer_port,
 		signer_service: Arc::new(rpc_apis::SignerService::new(move || {
 			signer::generate_new_token(signer_path.clone()).map_err(|e| format!("{:?}", e))
+		}, move |token| {
+			signer::exchange_token(exchange_path.clone(), token).map_err(|e| format!("{:?}", e))
+				.and_then(|credential| credential.ok_or_else(|| "Unknown token".to_owned()))
 		})),
 		client: client.clone(),
 		sync: sync_provider.clone(),
@@ -253,6 +361,15 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 		settings: Arc::new(cmd.net_settings.clone()),
 		net_service: manage_network.clone(),
 		geth_compatibility: cmd.geth_compatibility,
+		response_cache: response_cache,
+		address_watcher: address_watcher,
+		scheduler: scheduler,
+		updater: updater,
+		private_tx: private_tx_service,
+		contract_registry: Arc::new(ChainRegistry::new(&client, cmd.dirs.abis_path())),
+		snapshot: snapshot_service,
+		sync_threshold: cmd.sync_threshold,
+		max_response_size: cmd.max_response_size,
 	});
 
 	let dependencies = rpc::Dependencies {
@@ -274,6 +391,13 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 	// start dapps server
 	let dapps_server = try!(dapps::new(cmd.dapps_conf.clone(), dapps_deps));
 
+	let secretstore_deps = secretstore::Dependencies {
+		client: client.clone(),
+	};
+
+	// start secret store key server
+	let secretstore_key_server = try!(secretstore::start(cmd.secretstore_conf.clone(), secretstore_deps));
+
 	let signer_deps = signer::Dependencies {
 		panic_handler: panic_handler.clone(),
 		apis: deps_for_rpc_apis.clone(),
@@ -285,18 +409,23 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 	let informant = Arc::new(Informant::new(service.client(), Some(sync_provider.clone()), Some(manage_network.clone()), cmd.logger_config.color));
 	let info_notify: Arc<ChainNotify> = informant.clone();
 	service.add_notify(info_notify);
+	if let Some(hooks) = try!(Hooks::new(service.client(), sync_provider.clone(), cmd.on_new_block.clone(), cmd.on_reorg.clone())) {
+		service.add_notify(Arc::new(hooks));
+	}
 	let io_handler = Arc::new(ClientIoHandler {
 		client: service.client(),
 		info: informant,
 		sync: sync_provider.clone(),
 		net: manage_network.clone(),
 		accounts: account_provider.clone(),
+		clock: Arc::new(ClockMonitor::new(cmd.ntp_servers.clone(), manage_network.clone(), miner.clone())),
+		memory: cmd.cache_config.budget().map(|budget| Arc::new(MemoryManager::new(budget, service.client(), sync_provider.clone()))),
 		shutdown: Default::default(),
 	});
 	service.register_io_handler(io_handler.clone()).expect("Error registering IO handler");
 
 	// the watcher must be kept alive.
-	let _watcher = match cmd.no_periodic_snapshot {
+	let _watcher = match cmd.no_periodic_snapshot || cmd.db_read_only {
 		true => None,
 		false => {
 			let sync = sync_provider.clone();
@@ -304,7 +433,7 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 				service.client(),
 				move || sync.status().is_major_syncing(),
 				service.io().channel(),
-				SNAPSHOT_PERIOD,
+				cmd.snapshot_period,
 				SNAPSHOT_HISTORY,
 			));
 
@@ -321,24 +450,59 @@ pub fn execute(cmd: RunCmd) -> Result<(), String> {
 		url::open(&format!("http://{}:{}/", cmd.dapps_conf.interface, cmd.dapps_conf.port));
 	}
 
-	// save user defaults
-	user_defaults.pruning = algorithm;
-	user_defaults.tracing = tracing;
-	try!(user_defaults.save(&user_defaults_path));
+	// save user defaults, unless they belong to another instance
+	if !cmd.db_read_only {
+		user_defaults.pruning = algorithm;
+		user_defaults.tracing = tracing;
+		user_defaults.mode = mode;
+		try!(user_defaults.save(&user_defaults_path));
+	}
+
+	// persist mode changes made at runtime
+	if !cmd.db_read_only {
+		client.on_mode_change(move |mode: &Mode| {
+			let result = UserDefaults::load(&user_defaults_path).and_then(|mut user_defaults| {
+				user_defaults.mode = mode.clone();
+				user_defaults.save(&user_defaults_path)
+			});
+			if let Err(e) = result {
+				warn!("Failed to save operating mode: {}", e);
+			}
+		});
+	}
 
 	// Handle exit
-	wait_for_exit(panic_handler, http_server, ipc_server, dapps_server, signer_server);
+	wait_for_exit(panic_handler, http_server, ipc_server, dapps_server, signer_server, secretstore_key_server);
 
 	// to make sure timer does not spawn requests while shutdown is in progress
 	io_handler.shutdown.store(true, ::std::sync::atomic::Ordering::SeqCst);
 	// just Arc is dropping here, to allow other reference release in its default time
 	drop(io_handler);
 
+	// stop accepting new blocks and transactions from peers
+	manage_network.stop_network();
+
+	// let block import in progress finish and flush buffered database writes,
+	// but don't let a stuck import keep the process alive forever
+	let timeout = Duration::from_secs(cmd.shutdown_timeout);
+	let finished = try!(prepare_client_shutdown(client.clone(), timeout));
+	if !finished {
+		// dropping the service would wait for the stuck import.
+		mem::forget(service);
+	}
+
 	// hypervisor should be shutdown first while everything still works and can be
 	// terminated gracefully
 	drop(hypervisor);
 
-	Ok(())
+	if let Some(ref pid_file) = cmd.daemon {
+		let _ = fs::remove_file(pid_file);
+	}
+
+	match finished {
+		true => Ok(()),
+		false => Err(format!("Graceful shutdown did not finish in {} seconds.", timeout.as_secs())),
+	}
 }
 
 #[cfg(not(windows))]
@@ -359,7 +523,7 @@ fn daemonize(_pid_file: String) -> Result<(), String> {
 }
 
 fn prepare_account_provider(dirs: &Directories, cfg: AccountsConfig) -> Result<AccountProvider, String> {
-	use ethcore::ethstore::EthStore;
+	use ethcore::ethstore::{EthStore, SecretStore};
 	use ethcore::ethstore::dir::DiskDirectory;
 
 	let passwords = try!(passwords_from_files(cfg.password_files));
@@ -375,15 +539,41 @@ fn prepare_account_provider(dirs: &Directories, cfg: AccountsConfig) -> Result<A
 		}
 	}
 
+	if let Some(signer) = cfg.engine_signer {
+		let path = dirs.node_keys_path();
+		let dir = Box::new(try!(DiskDirectory::create(path.clone()).map_err(|e| format!("Could not open node keys directory: {}", e))));
+		let store = try!(EthStore::open_with_iterations(dir, cfg.iterations).map_err(|e| format!("Could not open node keys directory: {}", e)));
+		let password = try!(passwords.iter()
+			.find(|p| store.test_password(&signer, p).unwrap_or(false))
+			.cloned()
+			.ok_or_else(|| format!("No password found to unlock engine signer {} in {}. Make sure valid password is present in files passed using `--password`.", signer, path.display())));
+		try!(account_service.set_engine_signer(Box::new(store), signer, password).map_err(|e| format!("Could not set engine signer: {}", e)));
+	}
+
 	Ok(account_service)
 }
 
+// Prepares the client for shutdown on a separate thread.
+// Returns false if it didn't finish within `timeout`.
+fn prepare_client_shutdown(client: Arc<Client>, timeout: Duration) -> Result<bool, String> {
+	let (done_tx, done_rx) = mpsc::channel();
+	try!(thread::Builder::new()
+		.name("Shutdown".into())
+		.spawn(move || {
+			client.prepare_shutdown();
+			let _ = done_tx.send(());
+		})
+		.map_err(|e| format!("Error starting shutdown thread: {}", e)));
+	Ok(done_rx.recv_timeout(timeout).is_ok())
+}
+
 fn wait_for_exit(
 	panic_handler: Arc<PanicHandler>,
 	_http_server: Option<HttpServer>,
 	_ipc_server: Option<IpcServer>,
 	_dapps_server: Option<WebappServer>,
-	_signer_server: Option<SignerServer>
+	_signer_server: Option<SignerServer>,
+	_secretstore_key_server: Option<KeyServer>
 	) {
 	let exit = Arc::new(Condvar::new());
 
@@ -399,4 +589,5 @@ fn wait_for_exit(
 	let mutex = Mutex::new(());
 	let _ = exit.wait(mutex.lock().unwrap());
 	info!("Finishing work, please wait...");
+	// RPC, dapps, signer and secret store servers are stopped when dropped here
 }
@@ -0,0 +1,128 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+use util::{Address, H256};
+use ethcore::client::Client;
+use contract::{call, encode, word, registry_address};
+use dir::Directories;
+
+/// Name of the permissions contract in the registrar.
+const ACL_CHECKER_CONTRACT_REGISTRY_NAME: &'static str = "secretstore_acl_checker";
+
+/// Secret store configuration
+#[derive(Debug, PartialEq, Clone)]
+pub struct Configuration {
+	/// Is secret store functionality enabled?
+	pub enabled: bool,
+	/// Interface to listen on
+	pub interface: String,
+	/// Port to listen on
+	pub port: u16,
+	/// Data directory path for secret store
+	pub data_path: String,
+}
+
+impl Default for Configuration {
+	fn default() -> Self {
+		Configuration {
+			enabled: false,
+			interface: "127.0.0.1".into(),
+			port: 8082,
+			data_path: Directories::default().secretstore_path().to_string_lossy().into_owned(),
+		}
+	}
+}
+
+/// Secret store dependencies
+pub struct Dependencies {
+	/// Blockchain client used to check document permissions
+	pub client: Arc<Client>,
+}
+
+pub fn start(conf: Configuration, deps: Dependencies) -> Result<Option<KeyServer>, String> {
+	if !conf.enabled {
+		return Ok(None);
+	}
+
+	server::start(conf, deps).map(Some)
+}
+
+/// Checks whether `requester` may access the document with `checkPermissions(address,bytes32)`
+/// of the contract registered as `secretstore_acl_checker`.
+pub fn check_permissions(client: &Client, requester: &Address, document: &H256) -> Result<bool, String> {
+	let contract = try!(registry_address(client, ACL_CHECKER_CONTRACT_REGISTRY_NAME));
+	let data = encode("checkPermissions(address,bytes32)", &[H256::from(requester), document.clone()]);
+	let out = try!(call(client, contract, data));
+	Ok(!try!(word(&out, 0)).is_zero())
+}
+
+pub use self::server::KeyServer;
+
+#[cfg(not(feature = "secretstore"))]
+mod server {
+	use super::{Configuration, Dependencies};
+
+	pub struct KeyServer;
+
+	pub fn start(_conf: Configuration, _deps: Dependencies) -> Result<KeyServer, String> {
+		Err("Your Parity version has been compiled without Secret Store support.".into())
+	}
+}
+
+#[cfg(feature = "secretstore")]
+mod server {
+	use std::fs;
+	use std::sync::Arc;
+	use util::Address;
+	use ethcore::client::Client;
+	use ethcore_secretstore::{self, AclStorage, DocumentAddress, Error, ServiceConfiguration};
+	use super::{Configuration, Dependencies, check_permissions};
+
+	/// Running key server. The listener is closed when dropped.
+	pub struct KeyServer {
+		_key_server: Box<ethcore_secretstore::KeyServer>,
+	}
+
+	pub fn start(conf: Configuration, deps: Dependencies) -> Result<KeyServer, String> {
+		try!(fs::create_dir_all(&conf.data_path).map_err(|e| format!("Error creating secret store directory {}: {}", conf.data_path, e)));
+
+		let acl_storage = Arc::new(OnChainAclStorage {
+			client: deps.client,
+		});
+		let config = ServiceConfiguration {
+			listener_address: conf.interface,
+			listener_port: conf.port,
+			data_path: conf.data_path,
+		};
+
+		let key_server = try!(ethcore_secretstore::start(acl_storage, config).map_err(|e| format!("Error starting secret store: {}", e)));
+		Ok(KeyServer {
+			_key_server: key_server,
+		})
+	}
+
+	/// Checks document permissions with the on-chain permissions contract.
+	struct OnChainAclStorage {
+		client: Arc<Client>,
+	}
+
+	impl AclStorage for OnChainAclStorage {
+		fn check(&self, requester: &Address, document: &DocumentAddress) -> Result<bool, Error> {
+			check_permissions(&self.client, requester, document).map_err(Error::Internal)
+		}
+	}
+}
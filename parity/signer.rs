@@ -83,6 +83,14 @@ pub fn generate_new_token(path: String) -> io::Result<String> {
 	Ok(code)
 }
 
+pub fn exchange_token(path: String, token: &str) -> io::Result<Option<String>> {
+	let path = codes_path(path);
+	let mut codes = try!(signer::AuthCodes::from_file(&path));
+	let credential = try!(codes.exchange(token));
+	try!(codes.to_file(&path));
+	Ok(credential.map(|credential| format!("{:?}", credential)))
+}
+
 fn do_start(conf: Configuration, deps: Dependencies) -> Result<SignerServer, String> {
 	let addr = try!(format!("{}:{}", conf.interface, conf.port)
 		.parse()
@@ -98,6 +106,7 @@ fn do_start(conf: Configuration, deps: Dependencies) -> Result<SignerServer, Str
 			info!("If you do not intend this, exit now.");
 		}
 		let server = server.skip_origin_validation(conf.skip_origin_validation);
+		let server = server.address_watcher(deps.apis.address_watcher.clone());
 		let server = rpc_apis::setup_rpc(server, deps.apis, rpc_apis::ApiSet::SafeContext);
 		server.start(addr)
 	};
@@ -133,6 +133,7 @@ impl SnapshotCommand {
 
 		// database paths
 		let db_dirs = self.dirs.database(genesis_hash, spec.fork_name.clone());
+		try!(db_dirs.migrate_legacy_layout(spec.network_id()));
 
 		// user defaults path
 		let user_defaults_path = db_dirs.user_defaults_path();
@@ -0,0 +1,435 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Client auto-updater.
+//!
+//! Releases are published in the `operations` contract found through the registrar.
+//! Binaries are looked up by their hash in the `githubhint` contract, downloaded,
+//! verified and kept in the updates directory. An installed release is run instead
+//! of the current executable once the client is restarted.
+
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Weak};
+use std::sync::atomic::AtomicBool;
+use std::thread;
+use util::{H256, U256, Uint, FixedHash, Mutex, platform};
+use util::sha3::sha3;
+use ethcore::client::{Client, BlockChainClient, ChainNotify};
+use ethsync::SyncProvider;
+use ethcore_rpc::{UpdateService, ReleaseInfo};
+use fetch::{Client as FetchClient, Fetch};
+use contract::{bytes32, call, encode, registry_address, string, word};
+
+/// Name of the client in the release registry.
+const CLIENT_ID: &'static str = "parity";
+/// Release track followed by the updater (stable).
+const RELEASE_TRACK: u64 = 1;
+/// Number of blocks between checks for a new release.
+const CHECK_PERIOD: u64 = 100;
+/// File in the updates directory holding the name of the installed release executable.
+const LATEST_FILE: &'static str = "latest";
+
+/// Which releases are installed without asking.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UpdatePolicy {
+	/// Releases are installed only on request.
+	None,
+	/// Critical releases are installed automatically.
+	Critical,
+	/// All releases are installed automatically.
+	All,
+}
+
+impl Default for UpdatePolicy {
+	fn default() -> Self {
+		UpdatePolicy::None
+	}
+}
+
+impl FromStr for UpdatePolicy {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"none" => Ok(UpdatePolicy::None),
+			"critical" => Ok(UpdatePolicy::Critical),
+			"all" => Ok(UpdatePolicy::All),
+			_ => Err(format!("{}: Invalid value for --auto-update. Must be one of all, critical or none.", s)),
+		}
+	}
+}
+
+impl UpdatePolicy {
+	fn auto_installs(&self, release: &Release) -> bool {
+		match *self {
+			UpdatePolicy::None => false,
+			UpdatePolicy::Critical => release.is_critical,
+			UpdatePolicy::All => true,
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Release {
+	/// Version packed as `major << 16 | minor << 8 | patch`.
+	version: u32,
+	is_critical: bool,
+	binary: H256,
+}
+
+impl Release {
+	fn version_string(&self) -> String {
+		format!("{}.{}.{}", self.version >> 16, (self.version >> 8) & 0xff, self.version & 0xff)
+	}
+
+	fn file_name(&self) -> String {
+		format!("parity-{}", self.version_string())
+	}
+}
+
+/// Parses version of the release from the name of its executable.
+fn version_from_file_name(name: &str) -> Option<u32> {
+	if !name.starts_with("parity-") {
+		return None;
+	}
+	let mut parts = name["parity-".len()..].split('.').map(|part| part.parse::<u8>().ok());
+	match (parts.next(), parts.next(), parts.next(), parts.next()) {
+		(Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) => Some(((major as u32) << 16) + ((minor as u32) << 8) + patch as u32),
+		_ => None,
+	}
+}
+
+#[derive(Default)]
+struct State {
+	/// Best block at the time of the last check.
+	last_check: Option<u64>,
+	/// Release being downloaded.
+	fetching: Option<Release>,
+	/// Release downloaded and waiting to be installed.
+	ready: Option<Release>,
+	/// Release installed to run after restart.
+	installed: Option<Release>,
+	/// Whether the registry is being checked.
+	polling: bool,
+}
+
+fn current_version() -> u32 {
+	let part = |s: &str| s.parse::<u32>().expect("cargo version parts are numbers; qed");
+	(part(env!("CARGO_PKG_VERSION_MAJOR")) << 16) + (part(env!("CARGO_PKG_VERSION_MINOR")) << 8) + part(env!("CARGO_PKG_VERSION_PATCH"))
+}
+
+/// Returns path of the installed release executable if it's newer than the current one.
+pub fn latest_exe_path(updates_path: &Path) -> Option<PathBuf> {
+	let mut name = String::new();
+	if File::open(updates_path.join(LATEST_FILE)).and_then(|mut f| f.read_to_string(&mut name)).is_err() {
+		return None;
+	}
+	let name = name.trim();
+	if version_from_file_name(name).map_or(true, |version| version <= current_version()) {
+		return None;
+	}
+	let path = updates_path.join(name);
+	match path.is_file() {
+		true => Some(path),
+		false => None,
+	}
+}
+
+/// Makes the client run given release after restart.
+/// The marker is replaced atomically, so an interrupted install never leaves it truncated.
+fn install(updates_path: &Path, release: &Release) -> Result<(), String> {
+	let temp_path = updates_path.join(format!("{}.tmp", LATEST_FILE));
+	{
+		let mut file = try!(File::create(&temp_path).map_err(|e| format!("{}", e)));
+		try!(file.write_all(release.file_name().as_bytes()).map_err(|e| format!("{}", e)));
+		try!(file.sync_all().map_err(|e| format!("{}", e)));
+	}
+	try!(fs::rename(&temp_path, updates_path.join(LATEST_FILE)).map_err(|e| format!("{}", e)));
+	info!("Installed Parity {}. It will be run once the client is restarted.", release.version_string());
+	Ok(())
+}
+
+fn hash_file(path: &Path) -> Result<H256, String> {
+	let file = try!(File::open(path).map_err(|e| format!("{}", e)));
+	sha3(&mut BufReader::new(file)).map_err(|e| format!("{}", e))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+	use std::os::unix::fs::PermissionsExt;
+	fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| format!("{}", e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+	Ok(())
+}
+
+/// Verifies downloaded binary and moves it to the updates directory.
+fn stage(updates_path: &Path, release: &Release, downloaded: &Path) -> Result<(), String> {
+	let hash = try!(hash_file(downloaded));
+	if hash != release.binary {
+		return Err(format!("Binary hash mismatch: expected {}, got {}", release.binary, hash));
+	}
+	try!(fs::create_dir_all(updates_path).map_err(|e| format!("{}", e)));
+	let path = updates_path.join(release.file_name());
+	try!(fs::copy(downloaded, &path).map_err(|e| format!("{}", e)));
+	make_executable(&path)
+}
+
+fn on_ready(shared: &Shared, release: Release) {
+	info!("Parity {} is ready to be installed.", release.version_string());
+	let mut state = shared.state.lock();
+	if shared.policy.auto_installs(&release) {
+		match install(&shared.updates_path, &release) {
+			Ok(()) => {
+				state.installed = Some(release);
+				return;
+			},
+			Err(e) => warn!("Failed to install Parity {}: {}", release.version_string(), e),
+		}
+	}
+	state.ready = Some(release);
+}
+
+/// Returns the latest release in the followed track.
+fn latest_release(client: &Client) -> Result<Option<Release>, String> {
+	let operations = try!(registry_address(client, "operations"));
+	let client_id = bytes32(CLIENT_ID);
+
+	let out = try!(call(client, operations, encode("latestInTrack(bytes32,uint8)", &[client_id, H256::from(RELEASE_TRACK)])));
+	let release_id = try!(word(&out, 0));
+	if release_id.is_zero() {
+		return Ok(None);
+	}
+
+	// release(bytes32,bytes32) returns (uint32 forkBlock, uint8 track, uint24 semver, bool critical)
+	let out = try!(call(client, operations, encode("release(bytes32,bytes32)", &[client_id, release_id])));
+	let version = U256::from(try!(word(&out, 64))).low_u64() as u32;
+	let is_critical = !try!(word(&out, 96)).is_zero();
+
+	let out = try!(call(client, operations, encode("checksum(bytes32,bytes32,bytes32)", &[client_id, release_id, bytes32(&platform())])));
+	let binary = try!(word(&out, 0));
+	if binary.is_zero() {
+		return Err(format!("Release {} has no binary for {}", release_id, platform()));
+	}
+
+	Ok(Some(Release {
+		version: version,
+		is_critical: is_critical,
+		binary: binary,
+	}))
+}
+
+/// Looks up download url of the binary with given hash.
+fn binary_url(client: &Client, binary: &H256) -> Result<String, String> {
+	let githubhint = try!(registry_address(client, "githubhint"));
+
+	// entries(bytes32) returns (string accountSlashRepo, bytes20 commit, address owner)
+	let out = try!(call(client, githubhint, encode("entries(bytes32)", &[binary.clone()])));
+	if !try!(word(&out, 32)).is_zero() {
+		return Err(format!("Binary {} is registered as a repository", binary));
+	}
+	match string(&out, 0) {
+		Ok(ref url) if url.is_empty() => Err(format!("Binary {} is not registered", binary)),
+		Ok(url) => Ok(url),
+		Err(_) => Err(format!("Invalid url of binary {}", binary)),
+	}
+}
+
+/// Updater state shared with the threads checking the registry and fetching binaries.
+struct Shared {
+	policy: UpdatePolicy,
+	updates_path: PathBuf,
+	fetch: Mutex<FetchClient>,
+	state: Mutex<State>,
+}
+
+impl Shared {
+	fn poll(this: &Arc<Self>, client: &Client) -> Result<(), String> {
+		let release = match try!(latest_release(client)) {
+			Some(ref release) if release.version > current_version() => release.clone(),
+			_ => return Ok(()),
+		};
+
+		{
+			let state = this.state.lock();
+			let known = Some(&release);
+			if state.fetching.as_ref() == known || state.ready.as_ref() == known || state.installed.as_ref() == known {
+				return Ok(());
+			}
+		}
+
+		// downloaded before the restart
+		let path = this.updates_path.join(release.file_name());
+		if hash_file(&path).ok() == Some(release.binary.clone()) {
+			on_ready(this, release);
+			return Ok(());
+		}
+
+		let url = try!(binary_url(client, &release.binary));
+		info!("Downloading Parity {} from {}", release.version_string(), url);
+		this.state.lock().fetching = Some(release.clone());
+
+		let shared = this.clone();
+		let fetched = release.clone();
+		let result = this.fetch.lock().request_async(&url, Arc::new(AtomicBool::new(false)), Box::new(move |result| {
+			shared.state.lock().fetching = None;
+			let staged = result.map_err(|e| format!("{:?}", e)).and_then(|downloaded| {
+				let res = stage(&shared.updates_path, &fetched, &downloaded);
+				let _ = fs::remove_file(&downloaded);
+				res
+			});
+			match staged {
+				Ok(()) => on_ready(&shared, fetched.clone()),
+				Err(e) => warn!("Failed to fetch Parity {}: {}", fetched.version_string(), e),
+			}
+		}));
+
+		if let Err(e) = result {
+			this.state.lock().fetching = None;
+			return Err(format!("{:?}", e));
+		}
+		Ok(())
+	}
+}
+
+/// Checks the release registry for new versions of the client and downloads them.
+pub struct Updater {
+	client: Weak<Client>,
+	sync: Weak<SyncProvider>,
+	shared: Arc<Shared>,
+}
+
+impl Updater {
+	/// Creates new updater installing releases to `updates_path` according to `policy`.
+	pub fn new(client: &Arc<Client>, sync: &Arc<SyncProvider>, policy: UpdatePolicy, updates_path: PathBuf) -> Self {
+		Updater {
+			client: Arc::downgrade(client),
+			sync: Arc::downgrade(sync),
+			shared: Arc::new(Shared {
+				policy: policy,
+				updates_path: updates_path,
+				fetch: Mutex::new(FetchClient::default()),
+				state: Default::default(),
+			}),
+		}
+	}
+}
+
+impl UpdateService for Updater {
+	fn upgrade_ready(&self) -> Option<ReleaseInfo> {
+		self.shared.state.lock().ready.as_ref().map(|release| ReleaseInfo {
+			version: release.version_string(),
+			is_critical: release.is_critical,
+			binary: release.binary.clone(),
+		})
+	}
+
+	fn execute_upgrade(&self) -> bool {
+		let mut state = self.shared.state.lock();
+		let release = match state.ready.take() {
+			Some(release) => release,
+			None => return false,
+		};
+		match install(&self.shared.updates_path, &release) {
+			Ok(()) => {
+				state.installed = Some(release);
+				true
+			},
+			Err(e) => {
+				warn!("Failed to install Parity {}: {}", release.version_string(), e);
+				state.ready = Some(release);
+				false
+			},
+		}
+	}
+}
+
+impl ChainNotify for Updater {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, enacted: Vec<H256>, _retracted: Vec<H256>, _sealed: Vec<H256>, _duration: u64) {
+		if enacted.is_empty() || self.sync.upgrade().map_or(true, |sync| sync.status().is_major_syncing()) {
+			return;
+		}
+		let client = match self.client.upgrade() {
+			Some(client) => client,
+			None => return,
+		};
+
+		let best_block = client.chain_info().best_block_number;
+		{
+			let mut state = self.shared.state.lock();
+			if state.polling || state.last_check.map_or(false, |last| best_block < last + CHECK_PERIOD) {
+				return;
+			}
+			state.last_check = Some(best_block);
+			state.polling = true;
+		}
+
+		// registry calls are executed on a separate thread not to hold up other notifications
+		let shared = self.shared.clone();
+		let spawned = thread::Builder::new().name("Updater".into()).spawn(move || {
+			if let Err(e) = Shared::poll(&shared, &client) {
+				debug!(target: "updater", "Release check failed: {}", e);
+			}
+			shared.state.lock().polling = false;
+		});
+		if let Err(e) = spawned {
+			warn!("Cannot start release check: {}", e);
+			self.shared.state.lock().polling = false;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::H256;
+	use super::{UpdatePolicy, Release, version_from_file_name};
+
+	#[test]
+	fn should_parse_update_policy() {
+		assert_eq!("none".parse::<UpdatePolicy>(), Ok(UpdatePolicy::None));
+		assert_eq!("critical".parse::<UpdatePolicy>(), Ok(UpdatePolicy::Critical));
+		assert_eq!("all".parse::<UpdatePolicy>(), Ok(UpdatePolicy::All));
+		assert!("some".parse::<UpdatePolicy>().is_err());
+	}
+
+	#[test]
+	fn should_auto_install_according_to_policy() {
+		let release = |critical| Release { version: 0x010401, is_critical: critical, binary: H256::from(1) };
+		assert_eq!(release(false).version_string(), "1.4.1");
+		assert!(!UpdatePolicy::None.auto_installs(&release(true)));
+		assert!(UpdatePolicy::Critical.auto_installs(&release(true)));
+		assert!(!UpdatePolicy::Critical.auto_installs(&release(false)));
+		assert!(UpdatePolicy::All.auto_installs(&release(false)));
+	}
+
+	#[test]
+	fn should_parse_version_from_file_name() {
+		assert_eq!(version_from_file_name("parity-1.4.1"), Some(0x010401));
+		assert_eq!(version_from_file_name("parity-1.4"), None);
+		assert_eq!(version_from_file_name("parity-1.4.256"), None);
+		assert_eq!(version_from_file_name("other-1.4.1"), None);
+	}
+
+	#[test]
+	fn should_not_auto_install_by_default() {
+		assert_eq!(UpdatePolicy::default(), UpdatePolicy::None);
+	}
+}
@@ -24,13 +24,16 @@ use serde::de::impls::BTreeMapVisitor;
 use serde_json::Value;
 use serde_json::de::from_reader;
 use serde_json::ser::to_string;
+use std::time::Duration;
 use util::journaldb::Algorithm;
+use ethcore::client::Mode;
 
 pub struct UserDefaults {
 	pub is_first_launch: bool,
 	pub pruning: Algorithm,
 	pub tracing: bool,
 	pub fat_db: bool,
+	pub mode: Mode,
 }
 
 impl Serialize for UserDefaults {
@@ -40,6 +43,17 @@ impl Serialize for UserDefaults {
 		map.insert("pruning".into(), Value::String(self.pruning.as_str().into()));
 		map.insert("tracing".into(), Value::Bool(self.tracing));
 		map.insert("fat_db".into(), Value::Bool(self.fat_db));
+		map.insert("mode".into(), Value::String(self.mode.to_string()));
+		match self.mode {
+			Mode::Passive(timeout, alarm) => {
+				map.insert("mode.timeout".into(), Value::U64(timeout.as_secs()));
+				map.insert("mode.alarm".into(), Value::U64(alarm.as_secs()));
+			},
+			Mode::Dark(timeout) => {
+				map.insert("mode.timeout".into(), Value::U64(timeout.as_secs()));
+			},
+			_ => {},
+		}
 		map.serialize(serializer)
 	}
 }
@@ -66,12 +80,23 @@ impl Visitor for UserDefaultsVisitor {
 		let tracing = try!(tracing.as_bool().ok_or_else(|| Error::custom("invalid tracing value")));
 		let fat_db: Value = map.remove("fat_db".into()).unwrap_or_else(|| Value::Bool(false));
 		let fat_db = try!(fat_db.as_bool().ok_or_else(|| Error::custom("invalid fat_db value")));
+		let mode: Value = map.remove("mode".into()).unwrap_or_else(|| Value::String("active".into()));
+		let timeout = map.remove("mode.timeout".into()).and_then(|v| v.as_u64()).unwrap_or(300);
+		let alarm = map.remove("mode.alarm".into()).and_then(|v| v.as_u64()).unwrap_or(3600);
+		let mode = match try!(mode.as_str().ok_or_else(|| Error::custom("invalid mode value"))) {
+			"active" => Mode::Active,
+			"passive" => Mode::Passive(Duration::from_secs(timeout), Duration::from_secs(alarm)),
+			"dark" => Mode::Dark(Duration::from_secs(timeout)),
+			"offline" => Mode::Off,
+			_ => return Err(Error::custom("invalid mode")),
+		};
 
 		let user_defaults = UserDefaults {
 			is_first_launch: false,
 			pruning: pruning,
 			tracing: tracing,
 			fat_db: fat_db,
+			mode: mode,
 		};
 
 		Ok(user_defaults)
@@ -85,6 +110,7 @@ impl Default for UserDefaults {
 			pruning: Algorithm::default(),
 			tracing: false,
 			fat_db: false,
+			mode: Mode::Active,
 		}
 	}
 }
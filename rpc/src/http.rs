@@ -0,0 +1,261 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! HTTP transport tagging every request with an id.
+//!
+//! The id is taken from the `X-Request-Id` header (or generated), logged with the request,
+//! attached to errors returned by it and echoed back in the response headers.
+//! If a health provider is given, node health is served under `/api/health`.
+
+use std::{cmp, io, thread};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use hyper::{self, header, server, Control, Decoder, Encoder, Next};
+use hyper::method::Method;
+use hyper::net::{HttpListener, HttpStream};
+use hyper::status::StatusCode;
+use hyper::uri::RequestUri;
+use serde_json;
+use jsonrpc_core::IoHandler;
+use jsonrpc_http_server::{ServerHandler, PanicHandler, AccessControlAllowOrigin};
+use v1::HealthProvider;
+use v1::request_id::{self, REQUEST_ID_HEADER};
+
+/// Path under which node health is served.
+const HEALTH_PATH: &'static str = "/api/health";
+
+/// HTTP JSON-RPC server. Stops listening once dropped.
+pub struct HttpServer {
+	servers: Vec<server::Listening>,
+}
+
+impl HttpServer {
+	/// Starts the server at given address, handling requests on `threads` threads.
+	/// Every thread runs its own event loop accepting connections on the shared socket.
+	pub fn start(
+		addr: &SocketAddr,
+		handler: Arc<IoHandler>,
+		cors_domains: Option<Vec<AccessControlAllowOrigin>>,
+		allowed_hosts: Option<Vec<String>>,
+		panic_handler: Arc<Mutex<Option<Box<Fn() -> () + Send>>>>,
+		health: Option<Arc<HealthProvider>>,
+		threads: usize,
+	) -> Result<Self, HttpServerError> {
+		let allowed_hosts = allowed_hosts.map(|mut hosts| {
+			// the server is always reachable through the address it's listening on.
+			let bind_address = format!("{}", addr);
+			hosts.push(bind_address.replace("127.0.0.1", "localhost"));
+			hosts.push(bind_address);
+			hosts
+		});
+
+		let listener = try!(HttpListener::bind(addr));
+		let mut servers = Vec::with_capacity(threads);
+		for i in 0..cmp::max(threads, 1) {
+			let (handler, cors_domains, allowed_hosts) = (handler.clone(), cors_domains.clone(), allowed_hosts.clone());
+			let (panic_handler, health) = (panic_handler.clone(), health.clone());
+			let (listening, server) = try!(hyper::Server::new(try!(listener.try_clone()))
+				.handle(move |control: Control| RequestIdHandler {
+					request_id: String::new(),
+					health: health.clone(),
+					health_response: None,
+					write_pos: 0,
+					handler: ServerHandler::new(
+						handler.clone(),
+						cors_domains.clone(),
+						allowed_hosts.clone(),
+						PanicHandler { handler: panic_handler.clone() },
+						control,
+					),
+				}));
+			try!(thread::Builder::new().name(format!("RPC HTTP #{}", i)).spawn(move || server.run()));
+			servers.push(listening);
+		}
+
+		Ok(HttpServer {
+			servers: servers,
+		})
+	}
+
+	/// Returns the address the server is listening on.
+	pub fn addr(&self) -> &SocketAddr {
+		self.servers[0].addr()
+	}
+}
+
+impl Drop for HttpServer {
+	fn drop(&mut self) {
+		for server in self.servers.drain(..) {
+			server.close();
+		}
+	}
+}
+
+/// HTTP server startup error.
+#[derive(Debug)]
+pub enum HttpServerError {
+	/// Wrapped `std::io::Error`
+	IoError(io::Error),
+	/// Other `hyper` error
+	Other(hyper::error::Error),
+}
+
+impl From<hyper::error::Error> for HttpServerError {
+	fn from(err: hyper::error::Error) -> Self {
+		match err {
+			hyper::error::Error::Io(e) => HttpServerError::IoError(e),
+			e => HttpServerError::Other(e),
+		}
+	}
+}
+
+impl From<io::Error> for HttpServerError {
+	fn from(err: io::Error) -> Self {
+		HttpServerError::IoError(err)
+	}
+}
+
+struct RequestIdHandler {
+	request_id: String,
+	health: Option<Arc<HealthProvider>>,
+	// status and body of a health check, answered without the RPC handler.
+	health_response: Option<(StatusCode, String)>,
+	write_pos: usize,
+	handler: ServerHandler,
+}
+
+impl RequestIdHandler {
+	fn check_health(&self, request: &server::Request<HttpStream>) -> Option<(StatusCode, String)> {
+		let is_health_request = *request.method() == Method::Get && match *request.uri() {
+			RequestUri::AbsolutePath(ref path) => path.split('?').next() == Some(HEALTH_PATH),
+			_ => false,
+		};
+		match (is_health_request, self.health.as_ref()) {
+			(true, Some(health)) => {
+				let health = health.health();
+				let code = match health.is_healthy() {
+					true => StatusCode::Ok,
+					false => StatusCode::ServiceUnavailable,
+				};
+				Some((code, serde_json::to_string(&health).expect("Health is always serializable; qed")))
+			},
+			_ => None,
+		}
+	}
+}
+
+impl server::Handler<HttpStream> for RequestIdHandler {
+	fn on_request(&mut self, request: server::Request<HttpStream>) -> Next {
+		let id = request.headers().get_raw(REQUEST_ID_HEADER)
+			.and_then(|list| list.get(0))
+			.and_then(|id| String::from_utf8(id.clone()).ok());
+		self.request_id = request_id::from_client(id.as_ref().map(|id| id.as_str()));
+
+		debug!(target: "rpc", "[{}] {} {}", self.request_id, request.method(), request.uri());
+		self.health_response = self.check_health(&request);
+		self.write_pos = 0;
+		if self.health_response.is_some() {
+			return Next::write();
+		}
+		let handler = &mut self.handler;
+		request_id::with_request_id(self.request_id.clone(), move || handler.on_request(request))
+	}
+
+	fn on_request_readable(&mut self, decoder: &mut Decoder<HttpStream>) -> Next {
+		if self.health_response.is_some() {
+			return Next::write();
+		}
+		let handler = &mut self.handler;
+		request_id::with_request_id(self.request_id.clone(), || handler.on_request_readable(decoder))
+	}
+
+	fn on_response(&mut self, res: &mut server::Response) -> Next {
+		let next = match self.health_response {
+			Some((code, ref body)) => {
+				res.set_status(code);
+				res.headers_mut().set(header::ContentType::json());
+				res.headers_mut().set(header::ContentLength(body.len() as u64));
+				Next::write()
+			},
+			None => self.handler.on_response(res),
+		};
+		debug!(target: "rpc", "[{}] Responding with {}", self.request_id, res.status());
+		res.headers_mut().set_raw(REQUEST_ID_HEADER, vec![self.request_id.clone().into_bytes()]);
+		next
+	}
+
+	fn on_response_writable(&mut self, encoder: &mut Encoder<HttpStream>) -> Next {
+		let body = match self.health_response {
+			Some((_, ref body)) => body.as_bytes(),
+			None => return self.handler.on_response_writable(encoder),
+		};
+		if self.write_pos == body.len() {
+			return Next::end();
+		}
+		match encoder.write(&body[self.write_pos..]) {
+			Ok(written) => {
+				self.write_pos += written;
+				Next::write()
+			},
+			Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Next::write(),
+			Err(_) => Next::end(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::{Read, Write};
+	use std::net::TcpStream;
+	use std::sync::{Arc, Mutex};
+	use jsonrpc_core::IoHandler;
+	use v1::HealthProvider;
+	use v1::types::{Health, HealthCheck};
+	use super::HttpServer;
+
+	fn health(synced: bool) -> Health {
+		Health {
+			peers: HealthCheck { healthy: true, details: "5 peers connected".into() },
+			sync: HealthCheck { healthy: synced, details: "0 blocks behind".into() },
+			tx_queue: HealthCheck { healthy: true, details: "0 of 1024 transactions queued".into() },
+		}
+	}
+
+	fn request_health(synced: bool) -> String {
+		let addr = "127.0.0.1:0".parse().unwrap();
+		let provider: Arc<HealthProvider> = Arc::new(move || health(synced));
+		let server = HttpServer::start(&addr, Arc::new(IoHandler::new()), None, None, Arc::new(Mutex::new(None)), Some(provider), 2).unwrap();
+		let addr = server.addr().clone();
+
+		let mut stream = TcpStream::connect(addr).unwrap();
+		stream.write_all(b"GET /api/health HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n").unwrap();
+		let mut response = String::new();
+		stream.read_to_string(&mut response).unwrap();
+		response
+	}
+
+	#[test]
+	fn should_serve_health_checks() {
+		let healthy = request_health(true);
+		let lagging = request_health(false);
+
+		assert!(healthy.starts_with("HTTP/1.1 200 OK"), healthy);
+		assert!(healthy.contains("X-Request-Id: "), healthy);
+		assert!(healthy.contains(r#""txQueue":{"healthy":true,"details":"0 of 1024 transactions queued"}"#), healthy);
+		assert!(lagging.starts_with("HTTP/1.1 503 Service Unavailable"), lagging);
+	}
+}
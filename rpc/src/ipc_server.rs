@@ -0,0 +1,222 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! IPC transport over a Unix domain socket.
+//!
+//! Every connection is served on its own thread and handled within an `RpcSession`,
+//! so filters and watched addresses of the connection are released once it closes.
+//! Clients send a stream of JSON requests, which is split without decoding the requests.
+
+use std::{fs, io, mem, thread};
+use std::io::{Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use jsonrpc_core::IoHandler;
+use util::Mutex;
+use v1::{RpcSession, AddressWatcher, request_id};
+
+/// Maximal size of a single request.
+const MAX_REQUEST_SIZE: usize = 16 * 1024 * 1024;
+
+/// IPC server. The socket is closed and removed once the server is dropped.
+pub struct IpcServer {
+	path: PathBuf,
+	closing: Arc<AtomicBool>,
+}
+
+impl IpcServer {
+	/// Starts listening on the socket at given path.
+	pub fn start(path: &str, handler: Arc<IoHandler>, address_watcher: Option<Arc<AddressWatcher>>) -> io::Result<Self> {
+		let path = PathBuf::from(path);
+		// socket left behind by an instance which didn't shut down cleanly
+		if path.exists() {
+			try!(fs::remove_file(&path));
+		}
+		let listener = try!(UnixListener::bind(&path));
+		let closing = Arc::new(AtomicBool::new(false));
+		{
+			let closing = closing.clone();
+			try!(thread::Builder::new().name("IPC Server".into()).spawn(move || accept(listener, handler, address_watcher, closing)));
+		}
+
+		Ok(IpcServer {
+			path: path,
+			closing: closing,
+		})
+	}
+}
+
+impl Drop for IpcServer {
+	fn drop(&mut self) {
+		self.closing.store(true, Ordering::SeqCst);
+		// wake up the accepting thread so it notices the server is closing.
+		let _ = UnixStream::connect(&self.path);
+		let _ = fs::remove_file(&self.path);
+	}
+}
+
+fn accept(listener: UnixListener, handler: Arc<IoHandler>, address_watcher: Option<Arc<AddressWatcher>>, closing: Arc<AtomicBool>) {
+	for stream in listener.incoming() {
+		if closing.load(Ordering::SeqCst) {
+			return;
+		}
+		let stream = match stream {
+			Ok(stream) => stream,
+			Err(e) => {
+				warn!(target: "rpc", "Error accepting IPC connection: {}", e);
+				continue;
+			},
+		};
+
+		let (handler, address_watcher) = (handler.clone(), address_watcher.clone());
+		let spawned = thread::Builder::new().name("IPC Connection".into()).spawn(move || serve(stream, handler, address_watcher));
+		if let Err(e) = spawned {
+			warn!(target: "rpc", "Cannot start IPC connection thread: {}", e);
+		}
+	}
+}
+
+fn serve(mut stream: UnixStream, handler: Arc<IoHandler>, address_watcher: Option<Arc<AddressWatcher>>) {
+	let out = match stream.try_clone() {
+		Ok(out) => Arc::new(Mutex::new(out)),
+		Err(e) => {
+			warn!(target: "rpc", "Cannot serve IPC connection: {}", e);
+			return;
+		},
+	};
+	let session = RpcSession::new(handler);
+	let session = match address_watcher {
+		Some(watcher) => {
+			let out = out.clone();
+			session.with_address_watcher(watcher, move |notification| send(&out, notification))
+		},
+		None => session,
+	};
+
+	let mut splitter = RequestSplitter::default();
+	let mut buf = [0u8; 4096];
+	loop {
+		let read = match stream.read(&mut buf) {
+			Ok(0) => break,
+			Ok(read) => read,
+			Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => {
+				debug!(target: "rpc", "Error reading IPC connection: {}", e);
+				break;
+			},
+		};
+
+		for request in splitter.push(&buf[..read]) {
+			match String::from_utf8(request) {
+				Ok(request) => {
+					let id = request_id::generate();
+					trace!(target: "rpc", "[{}] IPC request: {}", id, request);
+					let out = out.clone();
+					let response_id = id.clone();
+					request_id::with_request_id(id, || session.handle_request(&request, move |response| {
+						trace!(target: "rpc", "[{}] IPC response: {}", response_id, response);
+						send(&out, response)
+					}));
+				},
+				Err(_) => debug!(target: "rpc", "Ignoring IPC request which is not valid UTF-8"),
+			}
+		}
+
+		if splitter.pending() > MAX_REQUEST_SIZE {
+			warn!(target: "rpc", "Closing IPC connection sending a request larger than {} bytes", MAX_REQUEST_SIZE);
+			break;
+		}
+	}
+	// the session is dropped here, releasing the state of the connection.
+}
+
+fn send(out: &Mutex<UnixStream>, mut message: String) {
+	message.push('\n');
+	if let Err(e) = out.lock().write_all(message.as_bytes()) {
+		debug!(target: "rpc", "Error writing to IPC connection: {}", e);
+	}
+}
+
+/// Splits a stream of JSON values into single values by tracking nesting of objects and arrays.
+#[derive(Default)]
+struct RequestSplitter {
+	buffer: Vec<u8>,
+	depth: usize,
+	in_string: bool,
+	escaped: bool,
+}
+
+impl RequestSplitter {
+	/// Consumes received data, returns requests completed by it.
+	fn push(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+		let mut requests = Vec::new();
+		for &byte in data {
+			// whitespace between requests
+			if self.depth == 0 && byte != b'{' && byte != b'[' {
+				continue;
+			}
+			self.buffer.push(byte);
+
+			if self.in_string {
+				if self.escaped {
+					self.escaped = false;
+				} else if byte == b'\\' {
+					self.escaped = true;
+				} else if byte == b'"' {
+					self.in_string = false;
+				}
+				continue;
+			}
+
+			match byte {
+				b'"' => self.in_string = true,
+				b'{' | b'[' => self.depth += 1,
+				b'}' | b']' => {
+					self.depth -= 1;
+					if self.depth == 0 {
+						requests.push(mem::replace(&mut self.buffer, Vec::new()));
+					}
+				},
+				_ => {},
+			}
+		}
+		requests
+	}
+
+	/// Returns size of the request being received.
+	fn pending(&self) -> usize {
+		self.buffer.len()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::RequestSplitter;
+
+	#[test]
+	fn should_split_stream_of_requests() {
+		let mut splitter = RequestSplitter::default();
+
+		let first = splitter.push(br#"{"id":1,"params":["}\"{"]} [{"id":2}"#);
+		let second = splitter.push(br#",{"id":3}]"#);
+
+		assert_eq!(first, vec![br#"{"id":1,"params":["}\"{"]}"#.to_vec()]);
+		assert_eq!(second, vec![br#"[{"id":2},{"id":3}]"#.to_vec()]);
+		assert_eq!(splitter.pending(), 0);
+	}
+}
@@ -24,6 +24,7 @@ extern crate serde;
 extern crate serde_json;
 extern crate jsonrpc_core;
 extern crate jsonrpc_http_server;
+extern crate hyper;
 
 extern crate ethcore_io as io;
 extern crate ethcore;
@@ -32,9 +33,11 @@ extern crate ethcrypto as crypto;
 extern crate ethstore;
 extern crate ethsync;
 extern crate transient_hashmap;
+#[cfg(windows)]
 extern crate json_ipc_server as ipc;
 extern crate ethcore_ipc;
 extern crate time;
+extern crate lru_cache;
 extern crate rlp;
 extern crate fetch;
 
@@ -48,14 +51,21 @@ extern crate ethjson;
 #[cfg(test)]
 extern crate ethcore_devtools as devtools;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::net::SocketAddr;
 use io::PanicHandler;
 use self::jsonrpc_core::{IoHandler, IoDelegate};
 
-pub use jsonrpc_http_server::{ServerBuilder, Server, RpcServerError};
 pub mod v1;
-pub use v1::{SigningQueue, SignerService, ConfirmationsQueue, NetworkSettings};
+mod http;
+#[cfg(unix)]
+mod ipc_server;
+#[cfg(unix)]
+pub use ipc_server::IpcServer;
+#[cfg(windows)]
+pub use ipc::Server as IpcServer;
+pub use http::{HttpServer, HttpServerError};
+pub use v1::{SigningQueue, SignerService, ConfirmationsQueue, NetworkSettings, ResponseCache, RpcSession, SnapshotStatusProvider, UpdateService, ReleaseInfo, AddressWatcher, Scheduler, ContractRegistry, TokenInfo, HealthProvider, PrivateTransactionService, PrivateCommitment};
 
 /// An object that can be extended with `IoDelegates`
 pub trait Extendable {
@@ -66,6 +76,7 @@ pub trait Extendable {
 /// Http server.
 pub struct RpcServer {
 	handler: Arc<jsonrpc_core::io::IoHandler>,
+	health: Option<Arc<HealthProvider>>,
 }
 
 impl Extendable for RpcServer {
@@ -80,17 +91,25 @@ impl RpcServer {
 	pub fn new() -> RpcServer {
 		RpcServer {
 			handler: Arc::new(IoHandler::new()),
+			health: None,
 		}
 	}
 
+	/// Serve node health checks under `/api/health` of the http server.
+	pub fn with_health(&mut self, health: Arc<HealthProvider>) {
+		self.health = Some(health);
+	}
+
 	/// Start http server asynchronously and returns result with `Server` handle on success or an error.
+	/// Requests are handled on `threads` threads and every request is tagged with an id, see `v1::request_id`.
 	pub fn start_http(
 		&self,
 		addr: &SocketAddr,
 		cors_domains: Option<Vec<String>>,
 		allowed_hosts: Option<Vec<String>>,
 		panic_handler: Arc<PanicHandler>,
-		) -> Result<Server, RpcServerError> {
+		threads: usize,
+		) -> Result<HttpServer, HttpServerError> {
 
 		let cors_domains = cors_domains.map(|domains| {
 			domains.into_iter()
@@ -101,20 +120,26 @@ impl RpcServer {
 				})
 				.collect()
 		});
+		let on_panic: Box<Fn() -> () + Send> = Box::new(move || {
+			panic_handler.notify_all("Panic in RPC thread.".to_owned());
+		});
 
-		ServerBuilder::new(self.handler.clone())
-			.cors(cors_domains.into())
-			.allowed_hosts(allowed_hosts.into())
-			.panic_handler(move || {
-				panic_handler.notify_all("Panic in RPC thread.".to_owned());
-			})
-			.start_http(addr)
+		HttpServer::start(addr, self.handler.clone(), cors_domains, allowed_hosts, Arc::new(Mutex::new(Some(on_panic))), self.health.clone(), threads)
+	}
+
+	/// Start ipc server asynchronously and returns result with `Server` handle on success or an error.
+	/// Every connection is handled within its own `RpcSession`.
+	#[cfg(unix)]
+	pub fn start_ipc(&self, addr: &str, address_watcher: Option<Arc<AddressWatcher>>) -> Result<IpcServer, String> {
+		IpcServer::start(addr, self.handler.clone(), address_watcher).map_err(|e| format!("RPC io error: {}", e))
 	}
 
 	/// Start ipc server asynchronously and returns result with `Server` handle on success or an error.
-	pub fn start_ipc(&self, addr: &str) -> Result<ipc::Server, ipc::Error> {
-		let server = try!(ipc::Server::new(addr, &self.handler));
-		try!(server.run_async());
+	/// Named pipes are served without sessions, so addresses can't be watched over them.
+	#[cfg(windows)]
+	pub fn start_ipc(&self, addr: &str, _address_watcher: Option<Arc<AddressWatcher>>) -> Result<IpcServer, String> {
+		let server = try!(ipc::Server::new(addr, &self.handler).map_err(|e| format!("RPC error: {:?}", e)));
+		try!(server.run_async().map_err(|e| format!("RPC error: {:?}", e)));
 		Ok(server)
 	}
 }
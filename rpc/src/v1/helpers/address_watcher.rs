@@ -0,0 +1,231 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node-side lists of addresses watched by subscribers of persistent connections.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use util::{Address, H256, RwLock};
+use rlp::UntrustedRlp;
+use ethcore::client::{BlockChainClient, BlockID, ChainNotify};
+use ethcore::receipt::Receipt;
+use ethcore::transaction::Action;
+use ethcore::views::BlockView;
+use v1::types::{AddressNotification, AddressNotificationType};
+
+/// Maximal number of addresses watched by a single subscriber.
+pub const MAX_WATCHED_ADDRESSES: usize = 1024;
+
+/// Receives notifications about addresses watched by a single subscriber.
+pub type NotificationSink = Box<Fn(AddressNotification) + Send + Sync>;
+
+struct Subscriber {
+	addresses: HashSet<Address>,
+	sink: NotificationSink,
+}
+
+/// Checks blocks entering and leaving the canonical chain for transactions and logs
+/// touching watched addresses and pushes notifications about them to the subscribers
+/// watching the addresses.
+pub struct AddressWatcher {
+	client: Weak<BlockChainClient>,
+	subscribers: RwLock<HashMap<usize, Subscriber>>,
+	next_id: AtomicUsize,
+}
+
+impl AddressWatcher {
+	/// Creates new watcher without subscribers.
+	pub fn new(client: &Arc<BlockChainClient>) -> Self {
+		AddressWatcher {
+			client: Arc::downgrade(client),
+			subscribers: RwLock::new(HashMap::new()),
+			next_id: AtomicUsize::new(0),
+		}
+	}
+
+	/// Adds subscriber with an empty watch list. Returns id of the subscriber.
+	pub fn subscribe(&self, sink: NotificationSink) -> usize {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		self.subscribers.write().insert(id, Subscriber {
+			addresses: HashSet::new(),
+			sink: sink,
+		});
+		id
+	}
+
+	/// Removes subscriber together with its watch list.
+	pub fn unsubscribe(&self, id: usize) {
+		self.subscribers.write().remove(&id);
+	}
+
+	/// Adds address to the watch list of given subscriber. Returns false if it was already watched.
+	pub fn watch(&self, id: usize, address: Address) -> Result<bool, String> {
+		let mut subscribers = self.subscribers.write();
+		let subscriber = try!(subscribers.get_mut(&id).ok_or_else(|| "Unknown subscriber.".to_owned()));
+		if !subscriber.addresses.contains(&address) && subscriber.addresses.len() >= MAX_WATCHED_ADDRESSES {
+			return Err(format!("At most {} addresses can be watched.", MAX_WATCHED_ADDRESSES));
+		}
+		Ok(subscriber.addresses.insert(address))
+	}
+
+	/// Removes address from the watch list of given subscriber. Returns false if it was not watched.
+	pub fn unwatch(&self, id: usize, address: &Address) -> bool {
+		self.subscribers.write().get_mut(&id).map_or(false, |subscriber| subscriber.addresses.remove(address))
+	}
+
+	/// Returns watched addresses touched by given block.
+	fn check_block(&self, client: &BlockChainClient, hash: H256, removed: bool, watched: &HashSet<Address>) -> Vec<(Address, AddressNotification)> {
+		let bytes = match client.block(BlockID::Hash(hash.clone())) {
+			Some(bytes) => bytes,
+			None => return Vec::new(),
+		};
+		let view = BlockView::new(&bytes);
+		let number = view.header_view().number();
+		// receipts are read by hash, so they're available for retracted blocks as well.
+		let receipts = client.block_receipts(&hash)
+			.and_then(|receipts| UntrustedRlp::new(&receipts).as_val::<Vec<Receipt>>().ok())
+			.unwrap_or_else(Vec::new);
+		let notification = |notification_type, address: &Address, transaction_hash: &H256| AddressNotification {
+			notification_type: notification_type,
+			address: address.clone().into(),
+			block_hash: hash.clone().into(),
+			block_number: number.into(),
+			transaction_hash: transaction_hash.clone().into(),
+			removed: removed,
+		};
+
+		let mut touched = Vec::new();
+		for (i, t) in view.transactions().into_iter().enumerate() {
+			let transaction_hash = t.hash();
+			let mut addresses: Vec<Address> = t.sender().ok().into_iter().collect();
+			if let Action::Call(ref to) = t.action {
+				if !addresses.contains(to) {
+					addresses.push(to.clone());
+				}
+			}
+			for address in addresses.into_iter().filter(|a| watched.contains(a)) {
+				let n = notification(AddressNotificationType::Transaction, &address, &transaction_hash);
+				touched.push((address, n));
+			}
+
+			let logs = receipts.get(i).map_or(&[][..], |receipt| &receipt.logs[..]);
+			for log in logs.iter().filter(|log| watched.contains(&log.address)) {
+				touched.push((log.address.clone(), notification(AddressNotificationType::Log, &log.address, &transaction_hash)));
+			}
+		}
+		touched
+	}
+}
+
+impl ChainNotify for AddressWatcher {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, enacted: Vec<H256>, retracted: Vec<H256>, _sealed: Vec<H256>, _duration: u64) {
+		let subscribers = self.subscribers.read();
+		let watched: HashSet<Address> = subscribers.values().flat_map(|s| s.addresses.iter().cloned()).collect();
+		if watched.is_empty() {
+			return;
+		}
+
+		let client = match self.client.upgrade() {
+			Some(client) => client,
+			None => return,
+		};
+		// retracted blocks go first, so subscribers can revert them before the new branch is applied.
+		let blocks = retracted.into_iter().map(|hash| (hash, true)).chain(enacted.into_iter().map(|hash| (hash, false)));
+		for (hash, removed) in blocks {
+			for (address, notification) in self.check_block(&*client, hash, removed, &watched) {
+				for subscriber in subscribers.values().filter(|s| s.addresses.contains(&address)) {
+					(subscriber.sink)(notification.clone());
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use util::{Address, Mutex};
+	use ethcore::client::{BlockChainClient, TestBlockChainClient, EachBlockWith, ChainNotify, BlockID};
+	use ethcore::views::BlockView;
+	use v1::types::{AddressNotification, AddressNotificationType};
+	use super::{AddressWatcher, MAX_WATCHED_ADDRESSES};
+
+	fn subscribe(watcher: &AddressWatcher) -> (usize, Arc<Mutex<Vec<AddressNotification>>>) {
+		let received = Arc::new(Mutex::new(Vec::new()));
+		let sink = received.clone();
+		let id = watcher.subscribe(Box::new(move |notification| sink.lock().push(notification)));
+		(id, received)
+	}
+
+	#[test]
+	fn should_notify_only_subscribers_watching_the_address() {
+		// given
+		let client = Arc::new(TestBlockChainClient::new());
+		client.add_blocks(1, EachBlockWith::Transaction);
+		let hash = client.numbers.read()[&1].clone();
+		let sender = {
+			let block = client.block(BlockID::Number(1)).unwrap();
+			BlockView::new(&block).transactions()[0].sender().unwrap()
+		};
+		let watcher = AddressWatcher::new(&(client.clone() as Arc<BlockChainClient>));
+		let (watching, received) = subscribe(&watcher);
+		let (other, other_received) = subscribe(&watcher);
+
+		// when
+		assert_eq!(watcher.watch(watching, sender.clone()), Ok(true));
+		assert_eq!(watcher.watch(watching, sender.clone()), Ok(false));
+		assert_eq!(watcher.watch(other, Address::from(1)), Ok(true));
+		watcher.new_blocks(vec![hash.clone()], vec![], vec![hash.clone()], vec![], vec![], 0);
+
+		// then
+		assert_eq!(received.lock().len(), 1);
+		assert_eq!(received.lock()[0].notification_type, AddressNotificationType::Transaction);
+		assert_eq!(received.lock()[0].address, sender.clone().into());
+		assert!(!received.lock()[0].removed);
+		assert!(other_received.lock().is_empty());
+
+		// when
+		assert!(!watcher.unwatch(other, &sender));
+		watcher.new_blocks(vec![], vec![], vec![], vec![hash.clone()], vec![], 0);
+
+		// then
+		assert_eq!(received.lock().len(), 2);
+		assert!(received.lock()[1].removed);
+
+		// when
+		watcher.unsubscribe(watching);
+		watcher.new_blocks(vec![hash.clone()], vec![], vec![hash], vec![], vec![], 0);
+
+		// then
+		assert_eq!(received.lock().len(), 2);
+	}
+
+	#[test]
+	fn should_limit_watched_addresses() {
+		let client = Arc::new(TestBlockChainClient::new());
+		let watcher = AddressWatcher::new(&(client as Arc<BlockChainClient>));
+		let (id, _) = subscribe(&watcher);
+
+		for i in 0..MAX_WATCHED_ADDRESSES {
+			assert_eq!(watcher.watch(id, Address::from(i as u64)), Ok(true));
+		}
+
+		assert!(watcher.watch(id, Address::from(MAX_WATCHED_ADDRESSES as u64)).is_err());
+		assert_eq!(watcher.watch(id, Address::from(0)), Ok(false));
+		assert!(watcher.watch(id + 1, Address::from(0)).is_err());
+	}
+}
@@ -0,0 +1,557 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Human-readable descriptions of requests waiting for confirmation.
+
+use std::str::FromStr;
+use serde_json::{self, Value};
+use util::{Address, Bytes, H256, U256, Uint, FixedHash, Hashable};
+use rustc_serialize::hex::{ToHex, FromHex};
+use v1::helpers::{ConfirmationPayload, FilledTransactionRequest};
+use v1::types::{RequestDetails, DecodedParam, DecodedCall, TokenTransfer};
+
+/// Signature of the token transfer method.
+const TRANSFER: &'static str = "transfer(address,uint256)";
+/// Methods recognized without asking the registry.
+const KNOWN_METHODS: &'static [&'static str] = &[
+	TRANSFER,
+	"transferFrom(address,address,uint256)",
+	"approve(address,uint256)",
+];
+
+/// Token registered in the token registry.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TokenInfo {
+	/// Token symbol, e.g. `GNT`.
+	pub symbol: String,
+	/// Number of base units in a single token.
+	pub base: U256,
+}
+
+/// Contract metadata published in on-chain registries.
+pub trait ContractRegistry: Send + Sync {
+	/// Returns signature (e.g. `transfer(address,uint256)`) of the method with given selector.
+	fn method_signature(&self, selector: [u8; 4]) -> Option<String>;
+
+	/// Returns token deployed at given address.
+	fn token(&self, address: &Address) -> Option<TokenInfo>;
+
+	/// Returns all registered tokens.
+	fn tokens(&self) -> Vec<(Address, TokenInfo)>;
+
+	/// Returns signatures of methods declared in the ABI of the contract at given address.
+	fn abi(&self, address: &Address) -> Option<Vec<String>>;
+
+	/// Returns `true` if code with given hash is a known version of the multi-signature wallet.
+	fn is_wallet_code(&self, code_hash: &H256) -> bool;
+}
+
+/// Returns selector of the method with given signature.
+pub fn method_selector(signature: &str) -> [u8; 4] {
+	let hash = signature.sha3();
+	[hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Returns signature of the method called with given data.
+pub fn method_signature(data: &[u8], registry: Option<&ContractRegistry>) -> Option<String> {
+	if data.len() < 4 {
+		return None;
+	}
+	let selector = [data[0], data[1], data[2], data[3]];
+	KNOWN_METHODS.iter()
+		.find(|method| method_selector(method) == selector)
+		.map(|method| method.to_string())
+		.or_else(|| registry.and_then(|registry| registry.method_signature(selector)))
+}
+
+/// Returns signature of the method of contract at given address called with given data.
+/// Methods declared in the contract's ABI take precedence over the ones found by selector only.
+pub fn contract_method_signature(contract: &Address, data: &[u8], registry: Option<&ContractRegistry>) -> Option<String> {
+	if data.len() < 4 {
+		return None;
+	}
+	registry
+		.and_then(|registry| registry.abi(contract))
+		.and_then(|methods| methods.into_iter().find(|method| &method_selector(method)[..] == &data[..4]))
+		.or_else(|| method_signature(data, registry))
+}
+
+/// Function, constructor or event declared in JSON ABI.
+struct AbiEntry {
+	kind: String,
+	name: Option<String>,
+	inputs: Vec<String>,
+}
+
+fn abi_entries(abi: &str) -> Result<Vec<AbiEntry>, String> {
+	fn field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+		match *value {
+			Value::Object(ref object) => object.get(name),
+			_ => None,
+		}
+	}
+
+	fn string(value: Option<&Value>) -> Option<&str> {
+		match value {
+			Some(&Value::String(ref value)) => Some(value.as_str()),
+			_ => None,
+		}
+	}
+
+	let abi: Value = try!(serde_json::from_str(abi).map_err(|e| format!("Invalid ABI: {}", e)));
+	let entries = match abi {
+		Value::Array(entries) => entries,
+		_ => return Err("Invalid ABI: expected an array".into()),
+	};
+
+	entries.iter().map(|entry| {
+		let name = string(field(entry, "name")).map(|name| name.to_owned());
+		let inputs = match field(entry, "inputs") {
+			Some(&Value::Array(ref inputs)) => try!(inputs.iter()
+				.map(|input| string(field(input, "type")).map(|kind| kind.to_owned())
+					.ok_or_else(|| format!("Invalid ABI: input of {} without type", name.as_ref().map_or("constructor", |name| name.as_str()))))
+				.collect::<Result<Vec<_>, _>>()),
+			_ => Vec::new(),
+		};
+		Ok(AbiEntry {
+			// entries without type are functions
+			kind: string(field(entry, "type")).unwrap_or("function").to_owned(),
+			name: name,
+			inputs: inputs,
+		})
+	}).collect()
+}
+
+/// Returns signatures of functions declared in given JSON ABI.
+pub fn abi_signatures(abi: &str) -> Result<Vec<String>, String> {
+	let entries = try!(abi_entries(abi));
+	entries.into_iter()
+		.filter(|entry| entry.kind == "function")
+		.map(|entry| match entry.name {
+			Some(name) => Ok(format!("{}({})", name, entry.inputs.join(","))),
+			None => Err("Invalid ABI: function without name".into()),
+		})
+		.collect()
+}
+
+/// Returns types of constructor arguments declared in given JSON ABI.
+pub fn abi_constructor_inputs(abi: &str) -> Result<Vec<String>, String> {
+	let entries = try!(abi_entries(abi));
+	Ok(entries.into_iter()
+		.find(|entry| entry.kind == "constructor")
+		.map_or_else(Vec::new, |entry| entry.inputs))
+}
+
+/// Size in bits of `uintN` or `intN` type given the `N` suffix.
+fn int_bits(suffix: &str) -> Option<usize> {
+	match suffix {
+		"" => Some(256),
+		suffix => suffix.parse::<usize>().ok().and_then(|bits| match bits > 0 && bits <= 256 && bits % 8 == 0 {
+			true => Some(bits),
+			false => None,
+		}),
+	}
+}
+
+fn parse_uint(value: &Value, bits: usize) -> Option<U256> {
+	let value = match *value {
+		Value::U64(value) => Some(U256::from(value)),
+		Value::String(ref value) if value.starts_with("0x") => U256::from_str(&value[2..]).ok(),
+		Value::String(ref value) => U256::from_dec_str(value).ok(),
+		_ => None,
+	};
+	value.and_then(|value| match bits == 256 || value < U256::one() << bits {
+		true => Some(value),
+		false => None,
+	})
+}
+
+fn parse_int(value: &Value, bits: usize) -> Option<U256> {
+	let negate = |value: U256| match value.is_zero() {
+		true => value,
+		false => U256::max_value() - value + U256::one(),
+	};
+	let (negative, magnitude) = match *value {
+		Value::I64(value) if value < 0 => (true, Some(U256::from(-(value + 1) as u64) + U256::one())),
+		Value::String(ref value) if value.starts_with('-') => (true, U256::from_dec_str(&value[1..]).ok()),
+		_ => (false, parse_uint(value, 256)),
+	};
+	let limit = U256::one() << (bits - 1);
+	match magnitude {
+		Some(magnitude) if negative && magnitude <= limit => Some(negate(magnitude)),
+		Some(magnitude) if !negative && magnitude < limit => Some(magnitude),
+		_ => None,
+	}
+}
+
+fn parse_hex(value: &Value) -> Option<Bytes> {
+	match *value {
+		Value::String(ref value) if value.starts_with("0x") => value[2..].from_hex().ok(),
+		_ => None,
+	}
+}
+
+fn pad_right(data: &[u8]) -> Bytes {
+	let mut padded = data.to_vec();
+	let len = (data.len() + 31) / 32 * 32;
+	padded.resize(len, 0);
+	padded
+}
+
+/// Encodes a static argument into a single word, returns `None` for dynamic types.
+fn encode_static(kind: &str, value: &Value) -> Option<Result<H256, ()>> {
+	let word = match kind {
+		"string" | "bytes" => return None,
+		"address" => parse_hex(value).and_then(|address| match address.len() {
+			20 => Some(H256::from(Address::from_slice(&address))),
+			_ => None,
+		}),
+		"bool" => match *value {
+			Value::Bool(value) => Some(H256::from(value as u64)),
+			_ => None,
+		},
+		_ if kind.starts_with("uint") => int_bits(&kind[4..]).and_then(|bits| parse_uint(value, bits)).map(H256::from),
+		_ if kind.starts_with("int") => int_bits(&kind[3..]).and_then(|bits| parse_int(value, bits)).map(H256::from),
+		_ if kind.starts_with("bytes") => match (kind[5..].parse::<usize>(), parse_hex(value)) {
+			(Ok(len), Some(ref bytes)) if len > 0 && len <= 32 && bytes.len() == len => Some(H256::from_slice(&pad_right(bytes))),
+			_ => None,
+		},
+		_ => None,
+	};
+	Some(word.ok_or(()))
+}
+
+/// ABI-encodes given JSON arguments of given types.
+/// Supports the same types as the decoder: `address`, `bool`, `uintN`, `intN`, `bytesN`, `bytes` and `string`.
+pub fn encode_params(types: &[String], args: &[Value]) -> Result<Bytes, String> {
+	if types.len() != args.len() {
+		return Err(format!("Expected {} arguments, got {}", types.len(), args.len()));
+	}
+
+	let mut head = Vec::new();
+	let mut tail = Vec::new();
+	for (kind, arg) in types.iter().zip(args) {
+		let invalid = || format!("Invalid {} argument: {}", kind, arg);
+		match encode_static(kind, arg) {
+			Some(word) => head.extend_from_slice(&try!(word.map_err(|_| invalid()))),
+			None => {
+				let data = match (kind.as_str(), arg) {
+					("string", &Value::String(ref value)) => value.as_bytes().to_vec(),
+					("bytes", _) => try!(parse_hex(arg).ok_or_else(&invalid)),
+					_ => return Err(invalid()),
+				};
+				// dynamic arguments are placed after the head and referenced by offset
+				let offset = types.len() * 32 + tail.len();
+				head.extend_from_slice(&H256::from(offset as u64));
+				tail.extend_from_slice(&H256::from(data.len() as u64));
+				tail.extend_from_slice(&pad_right(&data));
+			},
+		}
+	}
+	head.extend(tail);
+	Ok(head)
+}
+
+/// Decodes the call of contract at given address. Returns `None` if the method is unknown.
+pub fn decode_call(contract: &Address, data: &[u8], registry: Option<&ContractRegistry>) -> Option<Result<DecodedCall, String>> {
+	contract_method_signature(contract, data, registry).map(|signature| {
+		match decode_params(&signature, data) {
+			Some(params) => Ok(DecodedCall {
+				name: signature[..signature.find('(').unwrap_or(signature.len())].to_owned(),
+				signature: signature,
+				params: params,
+			}),
+			None => Err(format!("Data doesn't match signature {}", signature)),
+		}
+	})
+}
+
+fn word(args: &[u8], offset: usize) -> Option<&[u8]> {
+	match args.len() < offset + 32 {
+		true => None,
+		false => Some(&args[offset..offset + 32]),
+	}
+}
+
+fn word_as_usize(args: &[u8], offset: usize) -> Option<usize> {
+	word(args, offset).map(|word| U256::from(word)).and_then(|value| match value > U256::from(args.len() as u64) {
+		true => None,
+		false => Some(value.low_u64() as usize),
+	})
+}
+
+fn decode_param(kind: &str, args: &[u8], head: usize) -> Option<String> {
+	let word = match word(args, head) {
+		Some(word) => word,
+		None => return None,
+	};
+
+	match kind {
+		"address" => Some(format!("0x{}", word[12..].to_hex())),
+		"bool" => Some(format!("{}", word[31] != 0)),
+		"string" | "bytes" => {
+			let offset = match word_as_usize(args, head) {
+				Some(offset) => offset,
+				None => return None,
+			};
+			let len = match word_as_usize(args, offset) {
+				Some(len) if args.len() >= offset + 32 + len => len,
+				_ => return None,
+			};
+			let content = &args[offset + 32..offset + 32 + len];
+			match kind {
+				"string" => String::from_utf8(content.to_vec()).ok(),
+				_ => Some(format!("0x{}", content.to_hex())),
+			}
+		},
+		_ if kind.starts_with("uint") => Some(format!("{}", U256::from(word))),
+		_ if kind.starts_with("int") => {
+			let value = U256::from(word);
+			match word[0] & 0x80 == 0 {
+				true => Some(format!("{}", value)),
+				false => Some(format!("-{}", U256::max_value() - value + U256::one())),
+			}
+		},
+		_ if kind.starts_with("bytes") => match kind[5..].parse::<usize>() {
+			Ok(len) if len > 0 && len <= 32 => Some(format!("0x{}", word[..len].to_hex())),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Decodes arguments of the call of the method with given signature.
+/// Returns `None` if the data doesn't match the signature or it uses unsupported types.
+pub fn decode_params(signature: &str, data: &[u8]) -> Option<Vec<DecodedParam>> {
+	let types = match (signature.find('('), signature.rfind(')')) {
+		(Some(start), Some(end)) if start < end => &signature[start + 1..end],
+		_ => return None,
+	};
+	if data.len() < 4 {
+		return None;
+	}
+
+	let args = &data[4..];
+	let types: Vec<&str> = match types.is_empty() {
+		true => Vec::new(),
+		false => types.split(',').collect(),
+	};
+	types.into_iter().enumerate().map(|(i, kind)| {
+		decode_param(kind, args, i * 32).map(|value| DecodedParam {
+			kind: kind.to_owned(),
+			value: value,
+		})
+	}).collect()
+}
+
+/// Returns number of decimal places of token with given base (expected to be a power of 10).
+pub fn decimals(base: U256) -> usize {
+	format!("{}", base).len() - 1
+}
+
+/// Formats amount of base units as a (decimal) number of whole units.
+/// `base` is expected to be a power of 10.
+pub fn format_units(value: U256, base: U256) -> String {
+	if base.is_zero() {
+		return format!("{}", value);
+	}
+	let whole = value / base;
+	let fraction = value % base;
+	if fraction.is_zero() {
+		return format!("{}", whole);
+	}
+	let fraction = format!("{:0>width$}", format!("{}", fraction), width = decimals(base));
+	format!("{}.{}", whole, fraction.trim_right_matches('0'))
+}
+
+fn ether(value: U256) -> String {
+	format_units(value, U256::from(1_000_000_000_000_000_000u64))
+}
+
+fn token_transfer(token: Address, data: &Bytes, registry: Option<&ContractRegistry>) -> Option<TokenTransfer> {
+	if data.len() < 4 + 64 {
+		return None;
+	}
+	let to = Address::from_slice(&data[4 + 12..4 + 32]);
+	let value = U256::from(&data[4 + 32..4 + 64]);
+	let info = registry.and_then(|registry| registry.token(&token));
+
+	Some(TokenTransfer {
+		token: token.into(),
+		symbol: info.as_ref().map(|info| info.symbol.clone()),
+		to: to.into(),
+		value: value.into(),
+		amount: info.map(|info| format_units(value, info.base)),
+	})
+}
+
+fn describe_transaction(request: &FilledTransactionRequest, registry: Option<&ContractRegistry>) -> RequestDetails {
+	let method = match request.to {
+		Some(ref to) => contract_method_signature(to, &request.data, registry),
+		None => None,
+	};
+	let params = method.as_ref().and_then(|method| decode_params(method, &request.data)).unwrap_or_else(Vec::new);
+	let transfer = match (request.to, method.as_ref().map(|method| method.as_str())) {
+		(Some(token), Some(TRANSFER)) => token_transfer(token, &request.data, registry),
+		_ => None,
+	};
+
+	let summary = match (request.to, &method, &transfer) {
+		(None, _, _) => "Deploy a contract".to_owned(),
+		(Some(token), _, &Some(ref transfer)) => {
+			let to: Address = transfer.to.clone().into();
+			let value: U256 = transfer.value.clone().into();
+			match (&transfer.symbol, &transfer.amount) {
+				(&Some(ref symbol), &Some(ref amount)) => format!("Send {} {} to 0x{}", amount, symbol, to.hex()),
+				_ => format!("Send {} units of token 0x{} to 0x{}", value, token.hex(), to.hex()),
+			}
+		},
+		(Some(to), _, _) if request.data.is_empty() => format!("Send {} ETH to 0x{}", ether(request.value), to.hex()),
+		(Some(to), &Some(ref method), _) => format!("Call {} on 0x{}", method, to.hex()),
+		(Some(to), &None, _) => format!("Call unknown method of 0x{}", to.hex()),
+	};
+	let summary = match request.to.is_some() && !request.data.is_empty() && !request.value.is_zero() {
+		true => format!("{} with {} ETH", summary, ether(request.value)),
+		false => summary,
+	};
+
+	RequestDetails {
+		summary: summary,
+		method: method,
+		params: params,
+		token_transfer: transfer,
+	}
+}
+
+/// Describes the request for the confirmation UI.
+pub fn describe(payload: &ConfirmationPayload, registry: Option<&ContractRegistry>) -> RequestDetails {
+	match *payload {
+		ConfirmationPayload::Transaction(ref request) | ConfirmationPayload::SignTransaction(ref request) => {
+			describe_transaction(request, registry)
+		},
+		ConfirmationPayload::Sign(address, hash) => RequestDetails {
+			summary: format!("Sign hash 0x{} with account 0x{}", hash.hex(), address.hex()),
+			method: None,
+			params: Vec::new(),
+			token_transfer: None,
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rustc_serialize::hex::{FromHex, ToHex};
+	use util::U256;
+	use v1::types::DecodedParam;
+	use serde_json::{self, Value};
+	use super::{abi_signatures, abi_constructor_inputs, encode_params, decode_params, format_units, method_signature};
+
+	fn param(kind: &str, value: &str) -> DecodedParam {
+		DecodedParam {
+			kind: kind.into(),
+			value: value.into(),
+		}
+	}
+
+	#[test]
+	fn should_decode_token_transfer() {
+		// transfer(0x...0abc, 1000)
+		let data = "a9059cbb0000000000000000000000000000000000000000000000000000000000000abc00000000000000000000000000000000000000000000000000000000000003e8".from_hex().unwrap();
+
+		let method = method_signature(&data, None).unwrap();
+
+		assert_eq!(method, "transfer(address,uint256)");
+		assert_eq!(decode_params(&method, &data), Some(vec![
+			param("address", "0x0000000000000000000000000000000000000abc"),
+			param("uint256", "1000"),
+		]));
+	}
+
+	#[test]
+	fn should_decode_dynamic_and_signed_params() {
+		let data = "00000000\
+			ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+			0000000000000000000000000000000000000000000000000000000000000040\
+			0000000000000000000000000000000000000000000000000000000000000005\
+			68656c6c6f000000000000000000000000000000000000000000000000000000".from_hex().unwrap();
+
+		assert_eq!(decode_params("f(int256,string)", &data), Some(vec![
+			param("int256", "-1"),
+			param("string", "hello"),
+		]));
+		assert_eq!(decode_params("f(int256,uint256[])", &data), None);
+		assert_eq!(decode_params("f(int256,string,bool)", &data[..68]), None);
+	}
+
+	#[test]
+	fn should_read_function_signatures_from_abi() {
+		let abi = r#"[
+			{"type":"constructor","inputs":[{"name":"owner","type":"address"}]},
+			{"type":"function","name":"setOwner","inputs":[{"name":"owner","type":"address"}],"outputs":[]},
+			{"name":"owner","inputs":[],"outputs":[{"name":"","type":"address"}]},
+			{"type":"event","name":"NewOwner","inputs":[{"name":"old","type":"address","indexed":true}]}
+		]"#;
+
+		assert_eq!(abi_signatures(abi), Ok(vec!["setOwner(address)".to_owned(), "owner()".to_owned()]));
+		assert!(abi_signatures(r#"{"type":"function"}"#).is_err());
+		assert!(abi_signatures(r#"[{"type":"function","inputs":[]}]"#).is_err());
+	}
+
+	#[test]
+	fn should_encode_constructor_arguments() {
+		let abi = r#"[{"type":"constructor","inputs":[{"name":"a","type":"int8"},{"name":"b","type":"string"},{"name":"c","type":"address"}]}]"#;
+		let types = abi_constructor_inputs(abi).unwrap();
+		let args: Vec<Value> = serde_json::from_str(r#"[-1, "hello", "0x0000000000000000000000000000000000000abc"]"#).unwrap();
+
+		let encoded = encode_params(&types, &args).unwrap();
+
+		assert_eq!(encoded.to_hex(), "\
+			ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+			0000000000000000000000000000000000000000000000000000000000000060\
+			0000000000000000000000000000000000000000000000000000000000000abc\
+			0000000000000000000000000000000000000000000000000000000000000005\
+			68656c6c6f000000000000000000000000000000000000000000000000000000");
+		assert_eq!(decode_params("f(int8,string,address)", &[&[0u8; 4][..], &encoded[..]].concat()).unwrap()[1].value, "hello");
+		assert!(encode_params(&types, &args[..2]).is_err());
+		assert!(encode_params(&["uint256".to_owned()], &[Value::Bool(true)]).is_err());
+	}
+
+	#[test]
+	fn should_reject_integers_out_of_range() {
+		let encode = |kind: &str, value: &str| encode_params(&[kind.to_owned()], &[serde_json::from_str(value).unwrap()]);
+
+		assert!(encode("uint8", "255").is_ok());
+		assert!(encode("uint8", "256").is_err());
+		assert!(encode("uint8", r#""0x100""#).is_err());
+		assert!(encode("uint", r#""115792089237316195423570985008687907853269984665640564039457584007913129639935""#).is_ok());
+		assert!(encode("int8", "127").is_ok());
+		assert!(encode("int8", "128").is_err());
+		assert!(encode("int8", "-128").is_ok());
+		assert!(encode("int8", "-129").is_err());
+		assert!(encode("int256", r#""-57896044618658097711785492504343953926634992332820282019728792003956564819968""#).is_ok());
+		assert!(encode("uint7", "1").is_err());
+		assert!(encode("int264", "1").is_err());
+	}
+
+	#[test]
+	fn should_format_units() {
+		let base = U256::from(1000);
+		assert_eq!(format_units(U256::from(10_000), base), "10");
+		assert_eq!(format_units(U256::from(10_050), base), "10.05");
+		assert_eq!(format_units(U256::from(7), base), "0.007");
+	}
+}
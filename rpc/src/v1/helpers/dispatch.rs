@@ -14,18 +14,24 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use util::{Address, H256, U256, Uint};
+use util::{Address, FixedHash, H256, U256, Uint};
+use util::sha3::Hashable;
 use util::bytes::ToPretty;
 use ethcore::miner::MinerService;
-use ethcore::client::MiningBlockChainClient;
+use ethcore::client::{MiningBlockChainClient, BlockID};
 use ethcore::transaction::{Action, SignedTransaction, Transaction};
 use ethcore::account_provider::AccountProvider;
 use jsonrpc_core::{Error, Value, to_value};
-use v1::helpers::TransactionRequest;
-use v1::types::{H256 as RpcH256, H520 as RpcH520};
+use v1::helpers::{TransactionRequest, FilledTransactionRequest, CallRequest};
+use v1::types::{H256 as RpcH256, H520 as RpcH520, Transaction as RpcTransaction};
 use v1::helpers::errors;
 
-fn prepare_transaction<C, M>(client: &C, miner: &M, request: TransactionRequest) -> Transaction where C: MiningBlockChainClient, M: MinerService {
+/// Gas available to the transaction executed to estimate its gas usage.
+const ESTIMATE_GAS_LIMIT: u64 = 50_000_000;
+/// Gas available to a call which doesn't specify it.
+const CALL_GAS_LIMIT: u64 = 50_000_000;
+
+pub fn prepare_transaction<C, M>(client: &C, miner: &M, request: TransactionRequest) -> Transaction where C: MiningBlockChainClient, M: MinerService {
 	Transaction {
 		nonce: request.nonce
 			.or_else(|| miner
@@ -41,17 +47,41 @@ fn prepare_transaction<C, M>(client: &C, miner: &M, request: TransactionRequest)
 	}
 }
 
-pub fn dispatch_transaction<C, M>(client: &C, miner: &M, signed_transaction: SignedTransaction) -> Result<RpcH256, Error>
+/// Builds the transaction executed for a call, fake-signed by its sender.
+pub fn fake_sign_call<C, M>(client: &C, miner: &M, request: CallRequest) -> SignedTransaction where C: MiningBlockChainClient, M: MinerService {
+	let from = request.from.unwrap_or_else(Address::zero);
+	Transaction {
+		nonce: request.nonce.unwrap_or_else(|| client.latest_nonce(&from)),
+		action: request.to.map_or(Action::Create, Action::Call),
+		gas: request.gas.unwrap_or_else(|| CALL_GAS_LIMIT.into()),
+		gas_price: request.gas_price.unwrap_or_else(|| default_gas_price(client, miner)),
+		value: request.value.unwrap_or_else(U256::zero),
+		data: request.data.map_or_else(Vec::new, |d| d.to_vec())
+	}.fake_sign(from)
+}
+
+pub fn dispatch_transaction<C, M>(client: &C, miner: &M, signed_transaction: SignedTransaction, local_only: bool) -> Result<RpcH256, Error>
 	where C: MiningBlockChainClient, M: MinerService {
 	let hash = RpcH256::from(signed_transaction.hash());
 
-	let import = miner.import_own_transaction(client, signed_transaction);
+	let import = match local_only {
+		true => miner.import_local_only_transaction(client, signed_transaction),
+		false => miner.import_own_transaction(client, signed_transaction),
+	};
 
 	import
 		.map_err(errors::from_transaction_error)
 		.map(|_| hash)
 }
 
+/// Returns the hash signed by `eth_sign` for given message:
+/// `sha3("\x19Ethereum Signed Message:\n" + len(message) + message)`.
+pub fn eth_data_hash(data: &[u8]) -> H256 {
+	let mut message = format!("\x19Ethereum Signed Message:\n{}", data.len()).into_bytes();
+	message.extend_from_slice(data);
+	message.sha3()
+}
+
 pub fn signature_with_password(accounts: &AccountProvider, address: Address, hash: H256, pass: String) -> Result<Value, Error> {
 	accounts.sign_with_password(address, pass, hash)
 		.map_err(errors::from_password_error)
@@ -62,6 +92,7 @@ pub fn unlock_sign_and_dispatch<C, M>(client: &C, miner: &M, request: Transactio
 	where C: MiningBlockChainClient, M: MinerService {
 
 	let address = request.from;
+	let local_only = request.local_only;
 	let signed_transaction = {
 		let t = prepare_transaction(client, miner, request);
 		let hash = t.hash();
@@ -70,12 +101,13 @@ pub fn unlock_sign_and_dispatch<C, M>(client: &C, miner: &M, request: Transactio
 	};
 
 	trace!(target: "miner", "send_transaction: dispatching tx: {}", ::rlp::encode(&signed_transaction).to_vec().pretty());
-	dispatch_transaction(&*client, &*miner, signed_transaction).map(to_value)
+	dispatch_transaction(&*client, &*miner, signed_transaction, local_only).map(to_value)
 }
 
 pub fn sign_and_dispatch<C, M>(client: &C, miner: &M, request: TransactionRequest, account_provider: &AccountProvider, address: Address) -> Result<Value, Error>
 	where C: MiningBlockChainClient, M: MinerService {
 
+	let local_only = request.local_only;
 	let signed_transaction = {
 		let t = prepare_transaction(client, miner, request);
 		let hash = t.hash();
@@ -84,7 +116,55 @@ pub fn sign_and_dispatch<C, M>(client: &C, miner: &M, request: TransactionReques
 	};
 
 	trace!(target: "miner", "send_transaction: dispatching tx: {}", ::rlp::encode(&signed_transaction).to_vec().pretty());
-	dispatch_transaction(&*client, &*miner, signed_transaction).map(to_value)
+	dispatch_transaction(&*client, &*miner, signed_transaction, local_only).map(to_value)
+}
+
+pub fn unlock_sign_transaction<C, M>(client: &C, miner: &M, request: TransactionRequest, account_provider: &AccountProvider, password: String) -> Result<Value, Error>
+	where C: MiningBlockChainClient, M: MinerService {
+
+	let address = request.from;
+	let t = prepare_transaction(client, miner, request);
+	let hash = t.hash();
+	let signature = try!(account_provider.sign_with_password(address, password, hash).map_err(errors::from_password_error));
+	Ok(to_value(&RpcTransaction::from(t.with_signature(signature))))
+}
+
+pub fn sign_transaction<C, M>(client: &C, miner: &M, request: TransactionRequest, account_provider: &AccountProvider, address: Address) -> Result<Value, Error>
+	where C: MiningBlockChainClient, M: MinerService {
+
+	let t = prepare_transaction(client, miner, request);
+	let hash = t.hash();
+	let signature = try!(account_provider.sign(address, hash).map_err(errors::from_signing_error));
+	Ok(to_value(&RpcTransaction::from(t.with_signature(signature))))
+}
+
+/// Fills in missing fields of the request without signing it.
+/// The nonce takes transactions already in the queue into account and the gas is estimated
+/// by executing the transaction on top of the latest block.
+pub fn compose_transaction<C, M>(client: &C, miner: &M, request: TransactionRequest) -> FilledTransactionRequest
+	where C: MiningBlockChainClient, M: MinerService {
+
+	let mut t = prepare_transaction(client, miner, request.clone());
+	let gas = match request.gas {
+		Some(gas) => gas,
+		None => {
+			t.gas = ESTIMATE_GAS_LIMIT.into();
+			client.call(&t.clone().fake_sign(request.from), BlockID::Latest, Default::default())
+				.map(|executed| executed.gas_used + executed.refunded)
+				.unwrap_or_else(|_| miner.sensible_gas_limit())
+		},
+	};
+
+	FilledTransactionRequest {
+		from: request.from,
+		to: request.to,
+		gas_price: t.gas_price,
+		gas: gas,
+		value: t.value,
+		data: t.data,
+		nonce: Some(t.nonce),
+		local_only: request.local_only,
+	}
 }
 
 pub fn default_gas_price<C, M>(client: &C, miner: &M) -> U256 where C: MiningBlockChainClient, M: MinerService {
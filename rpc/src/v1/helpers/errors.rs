@@ -21,9 +21,15 @@ macro_rules! rpc_unimplemented {
 }
 
 use std::fmt;
-use ethcore::error::Error as EthcoreError;
+use std::collections::BTreeMap;
+use rustc_serialize::hex::ToHex;
+use util::{U256, Uint};
+use ethcore::error::{Error as EthcoreError, CallError};
+use ethcore::client::BlockImportError;
 use ethcore::account_provider::{Error as AccountError};
 use fetch::FetchError;
+use v1::helpers::WalletError;
+use v1::helpers::request_id;
 use jsonrpc_core::{Error, ErrorCode, Value};
 
 mod codes {
@@ -34,22 +40,46 @@ mod codes {
 	pub const NO_NEW_WORK: i64 = -32003;
 	pub const UNKNOWN_ERROR: i64 = -32009;
 	pub const TRANSACTION_ERROR: i64 = -32010;
+	pub const BLOCK_IMPORT_ERROR: i64 = -32011;
+	pub const BLOCK_PRUNED: i64 = -32012;
+	pub const RESPONSE_TOO_LARGE: i64 = -32013;
+	pub const EXECUTION_ERROR: i64 = -32015;
 	pub const ACCOUNT_LOCKED: i64 = -32020;
 	pub const PASSWORD_INVALID: i64 = -32021;
 	pub const ACCOUNT_ERROR: i64 = -32023;
 	pub const SIGNER_DISABLED: i64 = -32030;
+	pub const SCHEDULER_DISABLED: i64 = -32031;
+	pub const PRIVATE_DISABLED: i64 = -32032;
 	pub const REQUEST_REJECTED: i64 = -32040;
 	pub const REQUEST_REJECTED_LIMIT: i64 = -32041;
 	pub const REQUEST_NOT_FOUND: i64 = -32042;
 	pub const COMPILATION_ERROR: i64 = -32050;
 	pub const FETCH_ERROR: i64 = -32060;
+	pub const WALLET_ERROR: i64 = -32070;
+	pub const WATCH_ERROR: i64 = -32080;
+	pub const PRIVATE_ERROR: i64 = -32090;
+}
+
+/// Attaches id of the request handled by the current thread (if any) to the error data.
+fn request_data(details: Option<Value>) -> Option<Value> {
+	let id = match request_id::current() {
+		Some(id) => id,
+		None => return details,
+	};
+
+	let mut data = BTreeMap::new();
+	data.insert("requestId".to_owned(), Value::String(id));
+	if let Some(details) = details {
+		data.insert("details".to_owned(), details);
+	}
+	Some(Value::Object(data))
 }
 
 pub fn unimplemented() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
 		message: "This request is not implemented yet. Please create an issue on Github repo.".into(),
-		data: None
+		data: request_data(None)
 	}
 }
 
@@ -57,7 +87,7 @@ pub fn request_not_found() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::REQUEST_NOT_FOUND),
 		message: "Request not found.".into(),
-		data: None,
+		data: request_data(None),
 	}
 }
 
@@ -65,7 +95,7 @@ pub fn request_rejected() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::REQUEST_REJECTED),
 		message: "Request has been rejected.".into(),
-		data: None,
+		data: request_data(None),
 	}
 }
 
@@ -73,7 +103,7 @@ pub fn request_rejected_limit() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::REQUEST_REJECTED_LIMIT),
 		message: "Request has been rejected because of queue limit.".into(),
-		data: None,
+		data: request_data(None),
 	}
 }
 
@@ -81,7 +111,7 @@ pub fn account<T: fmt::Debug>(error: &str, details: T) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::ACCOUNT_ERROR),
 		message: error.into(),
-		data: Some(Value::String(format!("{:?}", details))),
+		data: request_data(Some(Value::String(format!("{:?}", details)))),
 	}
 }
 
@@ -89,7 +119,7 @@ pub fn compilation<T: fmt::Debug>(error: T) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::COMPILATION_ERROR),
 		message: "Error while compiling code.".into(),
-		data: Some(Value::String(format!("{:?}", error))),
+		data: request_data(Some(Value::String(format!("{:?}", error)))),
 	}
 }
 
@@ -97,7 +127,7 @@ pub fn internal<T: fmt::Debug>(error: &str, data: T) -> Error {
 	Error {
 		code: ErrorCode::InternalError,
 		message: format!("Internal error occurred: {}", error),
-		data: Some(Value::String(format!("{:?}", data))),
+		data: request_data(Some(Value::String(format!("{:?}", data)))),
 	}
 }
 
@@ -105,7 +135,7 @@ pub fn invalid_params<T: fmt::Debug>(param: &str, details: T) -> Error {
 	Error {
 		code: ErrorCode::InvalidParams,
 		message: format!("Couldn't parse parameters: {}", param),
-		data: Some(Value::String(format!("{:?}", details))),
+		data: request_data(Some(Value::String(format!("{:?}", details)))),
 	}
 }
 
@@ -113,7 +143,15 @@ pub fn state_pruned() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
 		message: "This request is not supported because your node is running with state pruning. Run with --pruning=archive.".into(),
-		data: None
+		data: request_data(None)
+	}
+}
+
+pub fn dev_chain<T: fmt::Debug>(data: T) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "Development chain operation failed. It's only supported on chains using the instant seal engine.".into(),
+		data: request_data(Some(Value::String(format!("{:?}", data)))),
 	}
 }
 
@@ -121,7 +159,7 @@ pub fn no_work() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::NO_WORK),
 		message: "Still syncing.".into(),
-		data: None
+		data: request_data(None)
 	}
 }
 
@@ -129,7 +167,7 @@ pub fn no_new_work() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::NO_NEW_WORK),
 		message: "Work has not changed.".into(),
-		data: None
+		data: request_data(None)
 	}
 }
 
@@ -137,7 +175,7 @@ pub fn no_author() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::NO_AUTHOR),
 		message: "Author not configured. Run Parity with --author to configure.".into(),
-		data: None
+		data: request_data(None)
 	}
 }
 
@@ -145,7 +183,7 @@ pub fn token(e: String) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNKNOWN_ERROR),
 		message: "There was an error when saving your authorization tokens.".into(),
-		data: Some(Value::String(e)),
+		data: request_data(Some(Value::String(e))),
 	}
 }
 
@@ -153,7 +191,31 @@ pub fn signer_disabled() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::SIGNER_DISABLED),
 		message: "Trusted Signer is disabled. This API is not available.".into(),
-		data: None
+		data: request_data(None)
+	}
+}
+
+pub fn scheduler_disabled() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::SCHEDULER_DISABLED),
+		message: "Transaction scheduler is disabled. Run the node with --scheduler to use this API.".into(),
+		data: request_data(None)
+	}
+}
+
+pub fn private_disabled() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::PRIVATE_DISABLED),
+		message: "Private transactions are disabled. Run the node with --private-tx to use this API.".into(),
+		data: request_data(None)
+	}
+}
+
+pub fn private_transaction(message: String) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::PRIVATE_ERROR),
+		message: format!("Private transaction failed: {}", message),
+		data: request_data(None),
 	}
 }
 
@@ -161,7 +223,7 @@ pub fn from_fetch_error(error: FetchError) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::FETCH_ERROR),
 		message: "Error while fetching content.".into(),
-		data: Some(Value::String(format!("{:?}", error))),
+		data: request_data(Some(Value::String(format!("{:?}", error)))),
 	}
 }
 
@@ -169,7 +231,7 @@ pub fn from_signing_error(error: AccountError) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::ACCOUNT_LOCKED),
 		message: "Your account is locked. Unlock the account via CLI, personal_unlockAccount or use Trusted Signer.".into(),
-		data: Some(Value::String(format!("{:?}", error))),
+		data: request_data(Some(Value::String(format!("{:?}", error)))),
 	}
 }
 
@@ -177,7 +239,99 @@ pub fn from_password_error(error: AccountError) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::PASSWORD_INVALID),
 		message: "Account password is invalid or account does not exist.".into(),
-		data: Some(Value::String(format!("{:?}", error))),
+		data: request_data(Some(Value::String(format!("{:?}", error)))),
+	}
+}
+
+pub fn from_wallet_error(error: WalletError) -> Error {
+	let message = match error {
+		WalletError::StatePruned => return state_pruned(),
+		WalletError::NotAWallet(address) => format!("There is no wallet at 0x{}.", address.hex()),
+		WalletError::NotAnOwner(address) => format!("0x{} is not an owner of the wallet.", address.hex()),
+		WalletError::NotPending(operation) => format!("Operation 0x{} is not pending.", operation.hex()),
+		WalletError::AlreadyConfirmed(address) => format!("Operation is already confirmed by 0x{}.", address.hex()),
+	};
+	Error {
+		code: ErrorCode::ServerError(codes::WALLET_ERROR),
+		message: message,
+		data: request_data(None),
+	}
+}
+
+pub fn watch_error(message: String) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::WATCH_ERROR),
+		message: message,
+		data: request_data(None),
+	}
+}
+
+pub fn from_call_error(error: CallError) -> Error {
+	match error {
+		CallError::StatePruned => state_pruned(),
+		e => Error {
+			code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
+			message: format!("{}", e),
+			data: request_data(None),
+		},
+	}
+}
+
+/// Selector of the standard `Error(string)` revert reason.
+const REVERT_REASON_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Decodes an ABI-encoded `Error(string)` revert payload.
+fn revert_reason(output: &[u8]) -> Option<String> {
+	if output.len() < 4 + 64 || output[..4] != REVERT_REASON_SELECTOR {
+		return None;
+	}
+
+	let data = &output[4..];
+	let offset = U256::from(&data[..32]);
+	if offset > U256::from(data.len() - 32) {
+		return None;
+	}
+	let offset = offset.low_u64() as usize;
+	let len = U256::from(&data[offset..offset + 32]);
+	if len > U256::from(data.len() - offset - 32) {
+		return None;
+	}
+	let start = offset + 32;
+	String::from_utf8(data[start..start + len.low_u64() as usize].to_vec()).ok()
+}
+
+pub fn execution_reverted(output: &[u8]) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
+		message: match revert_reason(output) {
+			Some(reason) => format!("Execution reverted: {}", reason),
+			None => "Execution reverted".into(),
+		},
+		data: request_data(Some(Value::String(format!("0x{}", output.to_hex())))),
+	}
+}
+
+pub fn from_block_import_error(error: BlockImportError) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::BLOCK_IMPORT_ERROR),
+		message: format!("Block could not be imported: {}", EthcoreError::from(error)),
+		data: request_data(None),
+	}
+}
+
+pub fn block_pruned() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::BLOCK_PRUNED),
+		message: "Block is known, but its body and receipts were pruned by this node (--prune-ancient-bodies).".into(),
+		data: request_data(None),
+	}
+}
+
+pub fn response_too_large(limit: usize) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::RESPONSE_TOO_LARGE),
+		message: format!("Response is larger than the {} bytes limit. Narrow your query.", limit),
+		data: request_data(None),
 	}
 }
 
@@ -185,10 +339,14 @@ pub fn from_transaction_error(error: EthcoreError) -> Error {
 	use ethcore::error::TransactionError::*;
 
 	if let EthcoreError::Transaction(e) = error {
+		let data = match e {
+			TooCheapToReplace { minimal, got } => Some(Value::String(format!("Minimal gas price to replace: {}, got: {}", minimal, got))),
+			_ => None,
+		};
 		let msg = match e {
 			AlreadyImported => "Transaction with the same hash was already imported.".into(),
 			Old => "Transaction nonce is too low. Try incrementing the nonce.".into(),
-			TooCheapToReplace => {
+			TooCheapToReplace { .. } => {
 				"Transaction gas price is too low. There is another transaction with same nonce in the queue. Try increasing the gas price or incrementing the nonce.".into()
 			},
 			LimitReached => {
@@ -204,17 +362,21 @@ pub fn from_transaction_error(error: EthcoreError) -> Error {
 				format!("Transaction cost exceeds current gas limit. Limit: {}, got: {}. Try decreasing supplied gas.", limit, got)
 			},
 			InvalidGasLimit(_) => "Supplied gas is beyond limit.".into(),
+			SenderBanned => "Sender is temporarily banned because its transactions took too long to execute.".into(),
+			RecipientBanned => "Recipient is temporarily banned because transactions to it took too long to execute.".into(),
+			CodeBanned => "Contract code is temporarily banned because it took too long to execute.".into(),
+			NotPermitted => "Transaction is not permitted. The sender is not allowed to deploy contracts or to transact with the recipient.".into(),
 		};
 		Error {
 			code: ErrorCode::ServerError(codes::TRANSACTION_ERROR),
 			message: msg,
-			data: None,
+			data: request_data(data),
 		}
 	} else {
 		Error {
 			code: ErrorCode::ServerError(codes::UNKNOWN_ERROR),
 			message: "Unknown error when sending transaction.".into(),
-			data: Some(Value::String(format!("{:?}", error))),
+			data: request_data(Some(Value::String(format!("{:?}", error)))),
 		}
 	}
 }
@@ -20,19 +20,42 @@ pub mod auto_args;
 #[macro_use]
 pub mod errors;
 
+pub mod call_decoder;
 pub mod dispatch;
+pub mod origin;
 pub mod params;
+pub mod request_id;
+pub mod session;
 
+mod address_watcher;
+mod health;
 mod poll_manager;
 mod poll_filter;
+mod private;
 mod requests;
 mod signer;
 mod signing_queue;
 mod network_settings;
-
+mod response_cache;
+mod response_size;
+mod scheduler;
+mod snapshot;
+mod updater;
+mod wallet;
+
+pub use self::address_watcher::{AddressWatcher, NotificationSink, MAX_WATCHED_ADDRESSES};
+pub use self::health::HealthProvider;
 pub use self::poll_manager::PollManager;
 pub use self::poll_filter::{PollFilter, limit_logs};
+pub use self::private::{PrivateTransactionService, PrivateCommitment};
 pub use self::requests::{TransactionRequest, FilledTransactionRequest, ConfirmationRequest, ConfirmationPayload, CallRequest};
 pub use self::signing_queue::{ConfirmationsQueue, ConfirmationPromise, ConfirmationResult, SigningQueue, QueueEvent};
 pub use self::signer::SignerService;
 pub use self::network_settings::NetworkSettings;
+pub use self::response_cache::{ResponseCache, DEFAULT_RESPONSE_CACHE_SIZE};
+pub use self::response_size::{ensure_response_size, estimate_block_size, estimate_log_size};
+pub use self::scheduler::{Scheduler, ScheduledTransaction, ScheduleExecution, MAX_SCHEDULE_HISTORY};
+pub use self::session::RpcSession;
+pub use self::snapshot::SnapshotStatusProvider;
+pub use self::updater::{UpdateService, ReleaseInfo};
+pub use self::wallet::{Wallet, WalletError, PendingOperation, WalletTransaction};
@@ -30,6 +30,8 @@ pub struct NetworkSettings {
 	pub rpc_interface: String,
 	/// Port for JSON-RPC server
 	pub rpc_port: u16,
+	/// Optional features the client was built with
+	pub features: Vec<String>,
 }
 
 impl Default for NetworkSettings {
@@ -40,7 +42,8 @@ impl Default for NetworkSettings {
 			network_port: 30303,
 			rpc_enabled: true,
 			rpc_interface: "local".into(),
-			rpc_port: 8545
+			rpc_port: 8545,
+			features: vec![],
 		}
 	}
 }
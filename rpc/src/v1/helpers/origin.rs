@@ -0,0 +1,105 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Origin (dapp) of the RPC request being handled.
+//!
+//! Transports that are able to tell which dapp sent the request (like the dapps server)
+//! handle it within `with_origin`; methods can then use `current` to restrict what they
+//! reveal to that dapp and to attribute their side effects (e.g. signing requests) to it.
+//!
+//! The origin is only set while the transport calls into the handler, so methods have to read
+//! it before returning. Asynchronous methods must capture it on entry and pass it along,
+//! it's no longer available once the work is handed off to other threads.
+
+use std::cell::RefCell;
+use util::Address;
+use ethcore::account_provider::{AccountProvider, Error as AccountError};
+
+/// Origin of an RPC request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origin {
+	/// Request from a transport that doesn't tell dapps apart (IPC, HTTP RPC server, signer).
+	Unknown,
+	/// Request sent to the dapps server without the token of the dapp whose domain it was sent to.
+	Dapps,
+	/// Request sent with the token of given dapp to the dapp's own domain.
+	/// Its accounts and default account are only revealed to such requests.
+	Dapp(String),
+}
+
+impl Default for Origin {
+	fn default() -> Self {
+		Origin::Unknown
+	}
+}
+
+impl Origin {
+	/// Returns the dapp that sent the request (if known).
+	pub fn dapp(&self) -> Option<String> {
+		match *self {
+			Origin::Dapp(ref dapp) => Some(dapp.clone()),
+			_ => None,
+		}
+	}
+
+	/// Returns `true` if the origin can't see all accounts.
+	pub fn is_restricted(&self) -> bool {
+		*self != Origin::Unknown
+	}
+
+	/// Returns accounts from `store` that can be revealed to this origin:
+	/// all of them for transports that don't tell dapps apart, the ones exposed to a dapp
+	/// and none for dapps server requests that didn't identify the dapp.
+	pub fn accounts(&self, store: &AccountProvider) -> Result<Vec<Address>, AccountError> {
+		match *self {
+			Origin::Unknown => store.accounts(),
+			Origin::Dapps => Ok(Vec::new()),
+			Origin::Dapp(ref dapp) => store.dapps_addresses(dapp),
+		}
+	}
+}
+
+thread_local!(static ORIGIN: RefCell<Origin> = RefCell::new(Origin::Unknown));
+
+/// Runs `f` with given origin set for the current thread.
+pub fn with_origin<F, T>(origin: Origin, f: F) -> T where F: FnOnce() -> T {
+	let previous = ORIGIN.with(|o| ::std::mem::replace(&mut *o.borrow_mut(), origin));
+	let result = f();
+	ORIGIN.with(|o| *o.borrow_mut() = previous);
+	result
+}
+
+/// Returns origin of the request handled by the current thread.
+pub fn current() -> Origin {
+	ORIGIN.with(|o| o.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Origin, with_origin, current};
+
+	#[test]
+	fn should_set_origin_for_the_closure_only() {
+		assert_eq!(current(), Origin::Unknown);
+		let inner = with_origin(Origin::Dapp("wallet".into()), || {
+			let nested = with_origin(Origin::Dapps, current);
+			(current(), nested)
+		});
+		assert_eq!(inner, (Origin::Dapp("wallet".into()), Origin::Dapps));
+		assert_eq!(current(), Origin::Unknown);
+	}
+}
@@ -0,0 +1,47 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Private transactions service.
+
+use util::{Address, H256, Bytes};
+use ethkey::Signature;
+use ethcore::transaction::SignedTransaction;
+
+/// State of a private contract published by the node.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrivateCommitment {
+	/// Address of the public contract holding the encrypted state.
+	pub contract: Address,
+	/// Hash of the encrypted state.
+	pub state_hash: H256,
+	/// Node's signature of the state hash.
+	pub signature: Signature,
+	/// Hash of the public transaction publishing the state.
+	pub transaction_hash: H256,
+}
+
+/// Executes transactions of private contracts, whose code and state are kept
+/// encrypted on-chain and may only be decrypted by parties allowed by the secret store.
+pub trait PrivateTransactionService: Send + Sync {
+	/// Generates the key of a private contract and publishes its initial state with given code.
+	fn initialize(&self, contract: Address, code: Bytes) -> Result<PrivateCommitment, String>;
+
+	/// Executes the transaction privately and publishes the resulting state of the contract.
+	fn send_transaction(&self, transaction: SignedTransaction) -> Result<PrivateCommitment, String>;
+
+	/// Executes the transaction privately without publishing the resulting state. Returns the output.
+	fn call(&self, transaction: SignedTransaction) -> Result<Bytes, String>;
+}
@@ -0,0 +1,96 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Id of the RPC request being handled, correlating the request with node logs.
+//!
+//! Transports tag every request with an id (sent by the client or generated) and handle it
+//! within `with_request_id`. Transports log the id with the request and errors returned by the
+//! request carry it in their data.
+//!
+//! Same as the origin, the id is only set while the transport calls into the handler,
+//! so errors of asynchronous methods don't carry it.
+
+use std::cell::RefCell;
+use util::{H64, FixedHash};
+
+/// HTTP header carrying the request id.
+pub const REQUEST_ID_HEADER: &'static str = "X-Request-Id";
+/// Maximal length of a request id sent by the client.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+thread_local!(static REQUEST_ID: RefCell<Option<String>> = RefCell::new(None));
+
+fn is_valid(id: &str) -> bool {
+	!id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.chars().all(|c| (c as u32) >= 0x20 && (c as u32) < 0x7f)
+}
+
+/// Returns a new random request id.
+pub fn generate() -> String {
+	H64::random().hex()
+}
+
+/// Returns the id sent by the client if it's valid, a new one otherwise.
+pub fn from_client(id: Option<&str>) -> String {
+	match id.map(str::trim) {
+		Some(id) if is_valid(id) => id.to_owned(),
+		_ => generate(),
+	}
+}
+
+/// Runs `f` with given request id set for the current thread.
+pub fn with_request_id<F, T>(id: String, f: F) -> T where F: FnOnce() -> T {
+	let previous = REQUEST_ID.with(|r| ::std::mem::replace(&mut *r.borrow_mut(), Some(id)));
+	let result = f();
+	REQUEST_ID.with(|r| *r.borrow_mut() = previous);
+	result
+}
+
+/// Returns id of the request handled by the current thread.
+pub fn current() -> Option<String> {
+	REQUEST_ID.with(|r| r.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use v1::helpers::errors;
+	use super::{from_client, with_request_id, current};
+
+	#[test]
+	fn should_accept_only_valid_ids_from_client() {
+		assert_eq!(from_client(Some(" abc-1 ")), "abc-1".to_owned());
+		assert_eq!(from_client(Some("")).len(), 16);
+		assert_eq!(from_client(Some("a\nb")).len(), 16);
+		assert_eq!(from_client(None).len(), 16);
+	}
+
+	#[test]
+	fn should_set_request_id_for_the_closure_only() {
+		assert_eq!(current(), None);
+		assert_eq!(with_request_id("abc".into(), current), Some("abc".to_owned()));
+		assert_eq!(current(), None);
+	}
+
+	#[test]
+	fn should_attach_request_id_to_errors() {
+		let error = with_request_id("abc".into(), errors::request_not_found);
+		let with_details = with_request_id("abc".into(), || errors::internal("error", 5));
+
+		assert_eq!(serde_json::to_string(&error.data).unwrap(), r#"{"requestId":"abc"}"#);
+		assert_eq!(serde_json::to_string(&with_details.data).unwrap(), r#"{"details":"5","requestId":"abc"}"#);
+		assert_eq!(errors::request_not_found().data, None);
+	}
+}
@@ -33,6 +33,8 @@ pub struct TransactionRequest {
 	pub data: Option<Bytes>,
 	/// Transaction's nonce
 	pub nonce: Option<U256>,
+	/// Keep the transaction out of p2p propagation
+	pub local_only: bool,
 }
 
 /// Transaction request coming from RPC with default values filled in.
@@ -52,6 +54,8 @@ pub struct FilledTransactionRequest {
 	pub data: Bytes,
 	/// Transaction's nonce
 	pub nonce: Option<U256>,
+	/// Keep the transaction out of p2p propagation
+	pub local_only: bool,
 }
 
 impl From<FilledTransactionRequest> for TransactionRequest {
@@ -64,6 +68,7 @@ impl From<FilledTransactionRequest> for TransactionRequest {
 			value: Some(r.value),
 			data: Some(r.data),
 			nonce: r.nonce,
+			local_only: r.local_only,
 		}
 	}
 }
@@ -94,6 +99,8 @@ pub struct ConfirmationRequest {
 	pub id: U256,
 	/// Payload to confirm
 	pub payload: ConfirmationPayload,
+	/// Dapp that sent the request (if known)
+	pub origin: Option<String>,
 }
 
 /// Payload to confirm in Trusted Signer
@@ -103,4 +110,6 @@ pub enum ConfirmationPayload {
 	Transaction(FilledTransactionRequest),
 	/// Sign request
 	Sign(Address, H256),
+	/// Transaction to sign without dispatching
+	SignTransaction(FilledTransactionRequest),
 }
@@ -0,0 +1,166 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cache of block and receipt responses shared between RPC servers.
+
+use lru_cache::LruCache;
+use util::{H256, Mutex};
+use ethcore::client::ChainNotify;
+use v1::types::{Block, Receipt};
+
+/// Default number of entries kept in each of the caches.
+pub const DEFAULT_RESPONSE_CACHE_SIZE: usize = 128;
+
+/// LRU cache of `Block` and `Receipt` responses keyed by block and transaction hash.
+///
+/// Entries belonging to blocks retracted during a reorganisation are dropped.
+pub struct ResponseCache {
+	blocks: Mutex<LruCache<(H256, bool), Block>>,
+	receipts: Mutex<LruCache<H256, Receipt>>,
+}
+
+impl Default for ResponseCache {
+	fn default() -> Self {
+		ResponseCache::new(DEFAULT_RESPONSE_CACHE_SIZE)
+	}
+}
+
+impl ResponseCache {
+	/// Creates new cache holding up to `size` blocks and `size` receipts.
+	pub fn new(size: usize) -> Self {
+		ResponseCache {
+			blocks: Mutex::new(LruCache::new(size)),
+			receipts: Mutex::new(LruCache::new(size)),
+		}
+	}
+
+	/// Returns cached block with given hash.
+	pub fn block(&self, hash: &H256, include_txs: bool) -> Option<Block> {
+		self.blocks.lock().get_mut(&(hash.clone(), include_txs)).cloned()
+	}
+
+	/// Caches block response.
+	pub fn insert_block(&self, hash: H256, include_txs: bool, block: Block) {
+		self.blocks.lock().insert((hash, include_txs), block);
+	}
+
+	/// Returns cached receipt of transaction with given hash.
+	pub fn receipt(&self, hash: &H256) -> Option<Receipt> {
+		self.receipts.lock().get_mut(hash).cloned()
+	}
+
+	/// Caches receipt response. Only receipts of mined transactions should be inserted.
+	pub fn insert_receipt(&self, hash: H256, receipt: Receipt) {
+		self.receipts.lock().insert(hash, receipt);
+	}
+
+	/// Drops all entries belonging to given blocks.
+	pub fn invalidate(&self, retracted: &[H256]) {
+		if retracted.is_empty() {
+			return;
+		}
+
+		{
+			let mut blocks = self.blocks.lock();
+			for hash in retracted {
+				blocks.remove(&(hash.clone(), true));
+				blocks.remove(&(hash.clone(), false));
+			}
+		}
+
+		let mut receipts = self.receipts.lock();
+		let stale: Vec<H256> = receipts.iter()
+			.filter(|&(_, receipt)| receipt.block_hash.as_ref().map_or(false, |h| retracted.contains(&h.clone().into())))
+			.map(|(hash, _)| hash.clone())
+			.collect();
+		for hash in stale {
+			receipts.remove(&hash);
+		}
+	}
+}
+
+impl ChainNotify for ResponseCache {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, _enacted: Vec<H256>, retracted: Vec<H256>, _sealed: Vec<H256>, _duration: u64) {
+		self.invalidate(&retracted);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::H256;
+	use v1::types::{Block, BlockTransactions, Receipt};
+	use super::ResponseCache;
+
+	fn receipt(block_hash: H256) -> Receipt {
+		Receipt {
+			transaction_hash: None,
+			transaction_index: None,
+			block_hash: Some(block_hash.into()),
+			block_number: None,
+			cumulative_gas_used: Default::default(),
+			gas_used: None,
+			contract_address: None,
+			logs: vec![],
+			state_root: None,
+		}
+	}
+
+	fn block(hash: H256) -> Block {
+		Block {
+			hash: Some(hash.into()),
+			parent_hash: Default::default(),
+			uncles_hash: Default::default(),
+			author: Default::default(),
+			miner: Default::default(),
+			state_root: Default::default(),
+			transactions_root: Default::default(),
+			receipts_root: Default::default(),
+			number: None,
+			gas_used: Default::default(),
+			gas_limit: Default::default(),
+			extra_data: Default::default(),
+			logs_bloom: Default::default(),
+			timestamp: Default::default(),
+			difficulty: Default::default(),
+			total_difficulty: Default::default(),
+			seal_fields: vec![],
+			seal_info: Default::default(),
+			uncles: vec![],
+			transactions: BlockTransactions::Hashes(vec![]),
+			size: None,
+		}
+	}
+
+	#[test]
+	fn should_drop_retracted_entries() {
+		// given
+		let cache = ResponseCache::new(4);
+		cache.insert_block(H256::from(1), false, block(H256::from(1)));
+		cache.insert_block(H256::from(2), true, block(H256::from(2)));
+		cache.insert_receipt(H256::from(10), receipt(H256::from(1)));
+		cache.insert_receipt(H256::from(20), receipt(H256::from(2)));
+
+		// when
+		cache.invalidate(&[H256::from(1)]);
+
+		// then
+		assert!(cache.block(&H256::from(1), false).is_none());
+		assert!(cache.block(&H256::from(2), true).is_some());
+		assert!(cache.block(&H256::from(2), false).is_none());
+		assert!(cache.receipt(&H256::from(10)).is_none());
+		assert!(cache.receipt(&H256::from(20)).is_some());
+	}
+}
@@ -0,0 +1,76 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Response size guard.
+//!
+//! Sizes are estimated from the raw data before the RPC response is built,
+//! so oversized queries are rejected without materializing them in memory.
+
+use jsonrpc_core::Error;
+use v1::helpers::errors;
+
+/// JSON overhead of a block without its transactions (field names, header hashes, bloom).
+const BLOCK_OVERHEAD: usize = 1024;
+/// JSON overhead of a transaction hash in a block.
+const TRANSACTION_HASH_SIZE: usize = 70;
+/// JSON overhead of a full transaction object, excluding its data.
+const TRANSACTION_OVERHEAD: usize = 600;
+/// JSON overhead of a single log, excluding its data and topics.
+const LOG_OVERHEAD: usize = 400;
+/// JSON size of a single hex-encoded topic.
+const TOPIC_SIZE: usize = 70;
+
+/// Estimates the JSON size of a block given its RLP size and number of transactions.
+pub fn estimate_block_size(rlp_size: usize, transactions: usize, include_txs: bool) -> usize {
+	let per_transaction = match include_txs {
+		true => TRANSACTION_OVERHEAD,
+		false => TRANSACTION_HASH_SIZE,
+	};
+	// every byte of RLP ends up hex-encoded at least once
+	BLOCK_OVERHEAD + rlp_size * 2 + transactions * per_transaction
+}
+
+/// Estimates the JSON size of a single log given its data length and number of topics.
+pub fn estimate_log_size(data_size: usize, topics: usize) -> usize {
+	LOG_OVERHEAD + data_size * 2 + topics * TOPIC_SIZE
+}
+
+/// Checks that the estimated response size fits in `limit` bytes.
+pub fn ensure_response_size(estimate: usize, limit: Option<usize>) -> Result<(), Error> {
+	match limit {
+		Some(limit) if estimate > limit => Err(errors::response_too_large(limit)),
+		_ => Ok(()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ensure_response_size, estimate_block_size, estimate_log_size};
+
+	#[test]
+	fn should_reject_responses_over_limit() {
+		assert!(ensure_response_size(200, None).is_ok());
+		assert!(ensure_response_size(200, Some(200)).is_ok());
+		assert!(ensure_response_size(201, Some(200)).is_err());
+	}
+
+	#[test]
+	fn should_estimate_full_transactions_as_larger() {
+		assert!(estimate_block_size(1000, 10, true) > estimate_block_size(1000, 10, false));
+		assert!(estimate_log_size(32, 2) > estimate_log_size(0, 0));
+	}
+}
@@ -0,0 +1,227 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recurring transactions signed and sent by the node.
+//!
+//! Schedules live in memory only: they are lost when the node stops and have to be registered
+//! again after a restart. Passwords of the senders are kept in memory together with
+//! the schedules, so they are never written to disk.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::{Arc, Weak, mpsc};
+use std::thread;
+use util::{H256, U256, Mutex};
+use ethcore::account_provider::AccountProvider;
+use ethcore::client::{MiningBlockChainClient, ChainNotify};
+use ethcore::header::BlockNumber;
+use ethcore::miner::MinerService;
+use v1::helpers::TransactionRequest;
+use v1::helpers::dispatch::{prepare_transaction, dispatch_transaction, default_gas_price};
+
+/// Number of past executions remembered for every scheduled transaction.
+pub const MAX_SCHEDULE_HISTORY: usize = 32;
+
+/// Single execution of a scheduled transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleExecution {
+	/// Best block number at the time of the execution.
+	pub block_number: BlockNumber,
+	/// Hash of the sent transaction or the reason it was not sent.
+	pub result: Result<H256, String>,
+}
+
+/// Transaction sent by the node every `interval` blocks.
+#[derive(Clone)]
+pub struct ScheduledTransaction {
+	/// Template of the transaction. Nonce and gas price are filled in on every execution.
+	pub request: TransactionRequest,
+	/// Number of blocks between executions.
+	pub interval: u64,
+	/// Executions are skipped while the gas price is above this limit.
+	pub max_gas_price: U256,
+	/// Block from which the transaction is due again.
+	pub next_block: BlockNumber,
+	/// Most recent executions, oldest first.
+	pub history: VecDeque<ScheduleExecution>,
+	password: String,
+}
+
+type Schedules = Arc<Mutex<BTreeMap<u64, ScheduledTransaction>>>;
+
+enum Job {
+	/// Execute scheduled transaction with given id at given block.
+	Execute(u64, BlockNumber, ScheduledTransaction),
+	/// Report back once all previous jobs are done.
+	#[cfg(test)]
+	Flush(mpsc::Sender<()>),
+}
+
+/// Signs and sends registered transactions once they are due, as new blocks are imported.
+/// Transactions are signed on a separate thread, so block import notifications are not held up.
+pub struct Scheduler<C, M> where C: MiningBlockChainClient, M: MinerService {
+	client: Weak<C>,
+	schedules: Schedules,
+	last_id: Mutex<u64>,
+	jobs: Mutex<mpsc::Sender<Job>>,
+	_marker: PhantomData<M>,
+}
+
+impl<C: 'static, M: 'static> Scheduler<C, M> where C: MiningBlockChainClient, M: MinerService {
+	/// Creates new scheduler without any transactions.
+	pub fn new(client: &Arc<C>, miner: &Arc<M>, accounts: &Arc<AccountProvider>) -> Self {
+		let schedules = Arc::new(Mutex::new(BTreeMap::new()));
+		let (jobs, receiver) = mpsc::channel();
+		let worker = Worker {
+			client: Arc::downgrade(client),
+			miner: Arc::downgrade(miner),
+			accounts: Arc::downgrade(accounts),
+			schedules: schedules.clone(),
+		};
+		// the worker stops once the scheduler is dropped.
+		let spawned = thread::Builder::new().name("Scheduler".into()).spawn(move || {
+			while let Ok(job) = receiver.recv() {
+				worker.run(job);
+			}
+		});
+		if let Err(e) = spawned {
+			warn!(target: "scheduler", "Cannot start scheduler thread: {}. Scheduled transactions won't be sent.", e);
+		}
+
+		Scheduler {
+			client: Arc::downgrade(client),
+			schedules: schedules,
+			last_id: Mutex::new(0),
+			jobs: Mutex::new(jobs),
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<C, M> Scheduler<C, M> where C: MiningBlockChainClient, M: MinerService {
+	/// Registers transaction to be sent every `interval` blocks, starting with the next block.
+	/// The password of `request.from` is kept in memory and used to sign every execution.
+	/// Returns id of the scheduled transaction.
+	pub fn add(&self, request: TransactionRequest, password: String, interval: u64, max_gas_price: U256) -> u64 {
+		let next_block = self.client.upgrade().map_or(0, |client| client.chain_info().best_block_number + 1);
+		let mut last_id = self.last_id.lock();
+		*last_id += 1;
+		self.schedules.lock().insert(*last_id, ScheduledTransaction {
+			request: request,
+			interval: interval,
+			max_gas_price: max_gas_price,
+			next_block: next_block,
+			history: VecDeque::new(),
+			password: password,
+		});
+		*last_id
+	}
+
+	/// Removes scheduled transaction. Returns false if there was no transaction with given id.
+	pub fn remove(&self, id: u64) -> bool {
+		self.schedules.lock().remove(&id).is_some()
+	}
+
+	/// Returns all scheduled transactions ordered by id.
+	pub fn schedules(&self) -> Vec<(u64, ScheduledTransaction)> {
+		self.schedules.lock().iter().map(|(id, s)| (*id, s.clone())).collect()
+	}
+
+	/// Blocks until all due transactions are executed.
+	#[cfg(test)]
+	pub fn flush(&self) {
+		let (done, wait) = mpsc::channel();
+		self.jobs.lock().send(Job::Flush(done)).expect("Worker runs as long as the scheduler exists; qed");
+		wait.recv().expect("Worker reports back once the job is done; qed");
+	}
+}
+
+struct Worker<C, M> where C: MiningBlockChainClient, M: MinerService {
+	client: Weak<C>,
+	miner: Weak<M>,
+	accounts: Weak<AccountProvider>,
+	schedules: Schedules,
+}
+
+impl<C, M> Worker<C, M> where C: MiningBlockChainClient, M: MinerService {
+	fn run(&self, job: Job) {
+		match job {
+			Job::Execute(id, number, schedule) => {
+				let result = match (self.client.upgrade(), self.miner.upgrade(), self.accounts.upgrade()) {
+					(Some(client), Some(miner), Some(accounts)) => Self::execute(&*client, &*miner, &*accounts, &schedule),
+					_ => return,
+				};
+				trace!(target: "scheduler", "Scheduled transaction {} executed at block {}: {:?}", id, number, result);
+
+				// the schedule might have been removed in the meantime.
+				if let Some(schedule) = self.schedules.lock().get_mut(&id) {
+					if schedule.history.len() == MAX_SCHEDULE_HISTORY {
+						schedule.history.pop_front();
+					}
+					schedule.history.push_back(ScheduleExecution {
+						block_number: number,
+						result: result,
+					});
+				}
+			},
+			#[cfg(test)]
+			Job::Flush(done) => {
+				let _ = done.send(());
+			},
+		}
+	}
+
+	fn execute(client: &C, miner: &M, accounts: &AccountProvider, schedule: &ScheduledTransaction) -> Result<H256, String> {
+		let gas_price = schedule.request.gas_price.unwrap_or_else(|| default_gas_price(client, miner));
+		if gas_price > schedule.max_gas_price {
+			return Err(format!("Gas price {} is above the limit of {}.", gas_price, schedule.max_gas_price));
+		}
+
+		let mut request = schedule.request.clone();
+		request.gas_price = Some(gas_price);
+		request.nonce = None;
+		let t = prepare_transaction(client, miner, request);
+		let hash = t.hash();
+		let signature = try!(accounts.sign_with_password(schedule.request.from, schedule.password.clone(), hash)
+			.map_err(|e| format!("Could not sign the transaction: {:?}", e)));
+
+		dispatch_transaction(client, miner, t.with_signature(signature), schedule.request.local_only)
+			.map(Into::into)
+			.map_err(|e| e.message)
+	}
+}
+
+impl<C, M> ChainNotify for Scheduler<C, M> where C: MiningBlockChainClient, M: MinerService {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, enacted: Vec<H256>, _retracted: Vec<H256>, _sealed: Vec<H256>, _duration: u64) {
+		if enacted.is_empty() {
+			return;
+		}
+
+		let client = match self.client.upgrade() {
+			Some(client) => client,
+			None => return,
+		};
+
+		let number = client.chain_info().best_block_number;
+		let jobs = self.jobs.lock();
+		for (id, schedule) in self.schedules.lock().iter_mut().filter(|&(_, ref s)| s.next_block <= number) {
+			schedule.next_block = number + schedule.interval;
+			if jobs.send(Job::Execute(*id, number, schedule.clone())).is_err() {
+				warn!(target: "scheduler", "Scheduler thread is not running. Scheduled transaction {} won't be sent.", id);
+			}
+		}
+	}
+}
@@ -0,0 +1,278 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-connection RPC session state.
+//!
+//! Transports keeping persistent connections (signer WebSockets, IPC) handle requests of
+//! every connection within its `RpcSession`. Methods creating server-side state owned by the
+//! connection (filters, watched addresses) record it in the session of the request being handled,
+//! and the state is released once the connection closes and its session is dropped.
+//!
+//! Same as the origin, the session is only set while the transport calls into the handler,
+//! so methods have to record their state before returning.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::Arc;
+use serde_json;
+use jsonrpc_core::IoHandler;
+use util::{Address, Mutex};
+use v1::helpers::AddressWatcher;
+
+/// Method removing server-side filters.
+const UNINSTALL_METHOD: &'static str = "eth_uninstallFilter";
+/// Method of notifications about watched addresses pushed to the client.
+const NOTIFICATION_METHOD: &'static str = "ethcore_addressNotification";
+
+struct AddressSubscription {
+	watcher: Arc<AddressWatcher>,
+	id: usize,
+}
+
+struct SessionState {
+	filters: Mutex<HashSet<usize>>,
+	addresses: Option<AddressSubscription>,
+}
+
+thread_local!(static SESSION: RefCell<Option<Arc<SessionState>>> = RefCell::new(None));
+
+/// State of a single connection to a persistent transport (e.g. WebSockets).
+///
+/// Remembers filters installed over the connection and uninstalls those
+/// the client did not remove itself once the session is dropped.
+/// Addresses watched over the connection are pushed to the client as notifications
+/// and forgotten together with the session.
+pub struct RpcSession {
+	handler: Arc<IoHandler>,
+	state: Arc<SessionState>,
+}
+
+impl RpcSession {
+	/// Creates new session handling requests with given handler.
+	pub fn new(handler: Arc<IoHandler>) -> Self {
+		RpcSession {
+			handler: handler,
+			state: Arc::new(SessionState {
+				filters: Mutex::new(HashSet::new()),
+				addresses: None,
+			}),
+		}
+	}
+
+	/// Enables watching addresses over the session.
+	/// Notifications about watched addresses are passed to `notify`.
+	pub fn with_address_watcher<F>(mut self, watcher: Arc<AddressWatcher>, notify: F) -> Self where F: Fn(String) + Send + Sync + 'static {
+		let id = watcher.subscribe(Box::new(move |notification| {
+			notify(format!(r#"{{"jsonrpc":"2.0","method":"{}","params":[{}]}}"#, NOTIFICATION_METHOD, serde_json::to_string(&notification).expect("Notifications are always serializable; qed")));
+		}));
+		Arc::get_mut(&mut self.state).expect("State is shared only while handling requests; qed").addresses = Some(AddressSubscription {
+			watcher: watcher,
+			id: id,
+		});
+		self
+	}
+
+	/// Handles request and passes the response (if any) to `on_response`.
+	pub fn handle_request<F>(&self, request: &str, on_response: F) where F: Fn(String) + Send + 'static {
+		let previous = SESSION.with(|s| ::std::mem::replace(&mut *s.borrow_mut(), Some(self.state.clone())));
+		let response = self.handler.handle_request(request);
+		SESSION.with(|s| *s.borrow_mut() = previous);
+
+		if let Some(async) = response {
+			async.on_result(move |response| on_response(response));
+		}
+	}
+
+	/// Returns number of filters installed over this session.
+	pub fn filters_count(&self) -> usize {
+		self.state.filters.lock().len()
+	}
+}
+
+impl Drop for RpcSession {
+	fn drop(&mut self) {
+		if let Some(ref subscription) = self.state.addresses {
+			subscription.watcher.unsubscribe(subscription.id);
+		}
+
+		let filters: Vec<_> = self.state.filters.lock().drain().collect();
+		for id in filters {
+			trace!(target: "rpc", "Uninstalling filter {} of a closed session", id);
+			let request = format!(r#"{{"jsonrpc":"2.0","method":"{}","params":["0x{:x}"],"id":0}}"#, UNINSTALL_METHOD, id);
+			self.handler.handle_request_sync(&request);
+		}
+	}
+}
+
+fn with_current<F, T>(f: F) -> Option<T> where F: FnOnce(&SessionState) -> T {
+	SESSION.with(|s| s.borrow().as_ref().map(|state| f(&*state)))
+}
+
+/// Records the filter as owned by the session of the request handled by the current thread.
+pub fn filter_installed(id: usize) {
+	with_current(|state| state.filters.lock().insert(id));
+}
+
+/// Forgets the filter removed by the request handled by the current thread.
+pub fn filter_uninstalled(id: usize) {
+	with_current(|state| state.filters.lock().remove(&id));
+}
+
+/// Adds address to the watch list of the session of the request handled by the current thread.
+/// Returns false if it was already watched.
+pub fn watch_address(address: Address) -> Result<bool, String> {
+	with_current(|state| state.addresses.as_ref().map(|subscription| subscription.watcher.watch(subscription.id, address)))
+		.and_then(|result| result)
+		.unwrap_or_else(|| Err("Addresses can only be watched over persistent connections.".into()))
+}
+
+/// Removes address from the watch list of the session of the request handled by the current thread.
+/// Returns false if it was not watched.
+pub fn unwatch_address(address: &Address) -> bool {
+	with_current(|state| state.addresses.as_ref().map_or(false, |subscription| subscription.watcher.unwatch(subscription.id, address)))
+		.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use jsonrpc_core::*;
+	use util::Mutex;
+	use ethcore::client::{BlockChainClient, TestBlockChainClient, EachBlockWith, ChainNotify, BlockID};
+	use ethcore::views::BlockView;
+	use v1::helpers::AddressWatcher;
+	use v1::types::H160;
+	use super::{RpcSession, filter_installed, filter_uninstalled, watch_address};
+
+	#[derive(Default)]
+	struct Filters {
+		uninstalled: Mutex<Vec<String>>,
+	}
+
+	impl Filters {
+		fn new_filter(&self, _params: Params) -> Result<Value, Error> {
+			filter_installed(1);
+			Ok(Value::String("0x1".into()))
+		}
+
+		fn uninstall_filter(&self, params: Params) -> Result<Value, Error> {
+			from_params::<(String, )>(params).map(|(id, )| {
+				filter_uninstalled(usize::from_str_radix(&id[2..], 16).unwrap());
+				self.uninstalled.lock().push(id);
+				Value::Bool(true)
+			})
+		}
+
+		fn watch_address(&self, params: Params) -> Result<Value, Error> {
+			from_params::<(H160, )>(params).and_then(|(address, )| {
+				watch_address(address.into()).map(Value::Bool).map_err(|_| Error::internal_error())
+			})
+		}
+	}
+
+	fn handler(filters: Arc<Filters>) -> Arc<IoHandler> {
+		let io = IoHandler::new();
+		let mut delegate = IoDelegate::new(filters);
+		delegate.add_method("eth_newBlockFilter", Filters::new_filter);
+		delegate.add_method("eth_uninstallFilter", Filters::uninstall_filter);
+		delegate.add_method("ethcore_watchAddress", Filters::watch_address);
+		io.add_delegate(delegate);
+		Arc::new(io)
+	}
+
+	#[test]
+	fn should_uninstall_filters_when_dropped() {
+		// given
+		let filters = Arc::new(Filters::default());
+		let session = RpcSession::new(handler(filters.clone()));
+
+		// when
+		session.handle_request(r#"{"jsonrpc":"2.0","method":"eth_newBlockFilter","params":[],"id":1}"#, |_| {});
+		assert_eq!(session.filters_count(), 1);
+		drop(session);
+
+		// then
+		assert_eq!(*filters.uninstalled.lock(), vec!["0x1".to_owned()]);
+	}
+
+	#[test]
+	fn should_forget_filters_uninstalled_by_client() {
+		// given
+		let filters = Arc::new(Filters::default());
+		let session = RpcSession::new(handler(filters.clone()));
+		session.handle_request(r#"{"jsonrpc":"2.0","method":"eth_newBlockFilter","params":[],"id":1}"#, |_| {});
+
+		// when
+		session.handle_request(r#"{"jsonrpc":"2.0","method":"eth_uninstallFilter","params":["0x1"],"id":2}"#, |_| {});
+		drop(session);
+
+		// then
+		assert_eq!(filters.uninstalled.lock().len(), 1);
+	}
+
+	#[test]
+	fn should_not_watch_addresses_outside_of_session() {
+		let filters = Arc::new(Filters::default());
+		let io = handler(filters);
+
+		let response = io.handle_request_sync(r#"{"jsonrpc":"2.0","method":"ethcore_watchAddress","params":["0x0000000000000000000000000000000000000001"],"id":1}"#);
+
+		assert!(response.unwrap().contains("error"));
+	}
+
+	#[test]
+	fn should_push_notifications_about_addresses_watched_over_session() {
+		// given
+		let client = Arc::new(TestBlockChainClient::new());
+		client.add_blocks(1, EachBlockWith::Transaction);
+		let hash = client.numbers.read()[&1].clone();
+		let sender = {
+			let block = client.block(BlockID::Number(1)).unwrap();
+			BlockView::new(&block).transactions()[0].sender().unwrap()
+		};
+		let watcher = Arc::new(AddressWatcher::new(&(client.clone() as Arc<BlockChainClient>)));
+		let pushed = Arc::new(Mutex::new(Vec::new()));
+		let session = {
+			let pushed = pushed.clone();
+			RpcSession::new(handler(Arc::new(Filters::default())))
+				.with_address_watcher(watcher.clone(), move |notification| pushed.lock().push(notification))
+		};
+		let responses = Arc::new(Mutex::new(Vec::new()));
+		let request = format!(r#"[{{"jsonrpc":"2.0","method":"ethcore_watchAddress","params":["0x{:?}"],"id":1}},{{"jsonrpc":"2.0","method":"eth_newBlockFilter","params":[],"id":2}}]"#, sender);
+
+		// when
+		{
+			let responses = responses.clone();
+			session.handle_request(&request, move |response| responses.lock().push(response));
+		}
+		watcher.new_blocks(vec![hash.clone()], vec![], vec![hash.clone()], vec![], vec![], 0);
+
+		// then
+		assert_eq!(responses.lock().len(), 1);
+		assert!(responses.lock()[0].contains(r#""result":true"#), "{:?}", *responses.lock());
+		assert!(responses.lock()[0].contains(r#""result":"0x1""#), "{:?}", *responses.lock());
+		assert_eq!(session.filters_count(), 1);
+		assert_eq!(pushed.lock().len(), 1);
+		assert!(pushed.lock()[0].starts_with(r#"{"jsonrpc":"2.0","method":"ethcore_addressNotification","params":[{"#), "{:?}", *pushed.lock());
+
+		// when
+		drop(session);
+		watcher.new_blocks(vec![hash.clone()], vec![], vec![hash], vec![], vec![], 0);
+
+		// then
+		assert_eq!(pushed.lock().len(), 1);
+	}
+}
@@ -22,16 +22,20 @@ use v1::helpers::signing_queue::{ConfirmationsQueue};
 pub struct SignerService {
 	queue: Arc<ConfirmationsQueue>,
 	generate_new_token: Box<Fn() -> Result<String, String> + Send + Sync + 'static>,
+	exchange_token: Box<Fn(&str) -> Result<String, String> + Send + Sync + 'static>,
 }
 
 impl SignerService {
 
-	/// Creates new Signer Service given function to generate new tokens.
-	pub fn new<F>(new_token: F) -> Self
-		where F: Fn() -> Result<String, String> + Send + Sync + 'static {
+	/// Creates new Signer Service given functions to generate new tokens
+	/// and to exchange them for persistent credentials.
+	pub fn new<F, E>(new_token: F, exchange_token: E) -> Self
+		where F: Fn() -> Result<String, String> + Send + Sync + 'static,
+			E: Fn(&str) -> Result<String, String> + Send + Sync + 'static {
 		SignerService {
 			queue: Arc::new(ConfirmationsQueue::default()),
 			generate_new_token: Box::new(new_token),
+			exchange_token: Box::new(exchange_token),
 		}
 	}
 
@@ -40,6 +44,11 @@ impl SignerService {
 		(self.generate_new_token)()
 	}
 
+	/// Exchanges one-time token for a persistent credential.
+	pub fn exchange_token(&self, token: &str) -> Result<String, String> {
+		(self.exchange_token)(token)
+	}
+
 	/// Returns a reference to `ConfirmationsQueue`
 	pub fn queue(&self) -> Arc<ConfirmationsQueue> {
 		self.queue.clone()
@@ -48,7 +57,10 @@ impl SignerService {
 	#[cfg(test)]
 	/// Creates new Signer Service for tests.
 	pub fn new_test() -> Self {
-		SignerService::new(|| Ok("new_token".into()))
+		SignerService::new(|| Ok("new_token".into()), |token| match token {
+			"new_token" => Ok("credential".into()),
+			_ => Err("Unknown token".into()),
+		})
 	}
 }
 
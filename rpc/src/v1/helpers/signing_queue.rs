@@ -61,9 +61,9 @@ const QUEUE_LIMIT: usize = 50;
 
 /// A queue of transactions awaiting to be confirmed and signed.
 pub trait SigningQueue: Send + Sync {
-	/// Add new request to the queue.
+	/// Add new request sent by given dapp (if known) to the queue.
 	/// Returns a `ConfirmationPromise` that can be used to await for resolution of given request.
-	fn add_request(&self, request: ConfirmationPayload) -> Result<ConfirmationPromise, QueueAddError>;
+	fn add_request(&self, request: ConfirmationPayload, origin: Option<String>) -> Result<ConfirmationPromise, QueueAddError>;
 
 	/// Removes a request from the queue.
 	/// Notifies possible token holders that request was rejected.
@@ -246,7 +246,7 @@ impl Drop for ConfirmationsQueue {
 }
 
 impl SigningQueue for ConfirmationsQueue {
-	fn add_request(&self, request: ConfirmationPayload) -> Result<ConfirmationPromise, QueueAddError> {
+	fn add_request(&self, request: ConfirmationPayload, origin: Option<String>) -> Result<ConfirmationPromise, QueueAddError> {
 		if self.len() > QUEUE_LIMIT {
 			return Err(QueueAddError::LimitReached);
 		}
@@ -269,6 +269,7 @@ impl SigningQueue for ConfirmationsQueue {
 				request: ConfirmationRequest {
 					id: id,
 					payload: request,
+					origin: origin,
 				},
 			});
 			queue.get(&id).map(|token| token.as_promise()).expect("Token was just inserted.")
@@ -328,6 +329,7 @@ mod test {
 			value: 10_000_000.into(),
 			data: vec![],
 			nonce: None,
+			local_only: false,
 		})
 	}
 
@@ -340,7 +342,7 @@ mod test {
 		// when
 		let q = queue.clone();
 		let handle = thread::spawn(move || {
-			let v = q.add_request(request).unwrap();
+			let v = q.add_request(request, None).unwrap();
 			let (tx, rx) = mpsc::channel();
 			v.wait_for_result(move |res| {
 				tx.send(res).unwrap();
@@ -375,7 +377,7 @@ mod test {
 				*v = Some(notification);
 			}).expect("Should be closed nicely.")
 		});
-		queue.add_request(request).unwrap();
+		queue.add_request(request, None).unwrap();
 		queue.finish();
 
 		// then
@@ -391,7 +393,7 @@ mod test {
 		let request = request();
 
 		// when
-		queue.add_request(request.clone()).unwrap();
+		queue.add_request(request.clone(), None).unwrap();
 		let all = queue.requests();
 
 		// then
@@ -400,4 +402,20 @@ mod test {
 		assert_eq!(el.id, U256::from(1));
 		assert_eq!(el.payload, request);
 	}
+
+	#[test]
+	fn should_attribute_request_to_origin() {
+		// given
+		let queue = ConfirmationsQueue::default();
+
+		// when
+		queue.add_request(request(), Some("wallet".into())).unwrap();
+		queue.add_request(request(), None).unwrap();
+		let all = queue.requests();
+
+		// then
+		assert_eq!(all.len(), 2);
+		assert_eq!(all[0].origin, Some("wallet".into()));
+		assert_eq!(all[1].origin, None);
+	}
 }
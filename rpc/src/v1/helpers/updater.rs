@@ -0,0 +1,40 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Client auto-update service.
+
+use util::H256;
+
+/// Release of the client downloaded and ready to be installed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReleaseInfo {
+	/// Release version, e.g. `1.4.1`.
+	pub version: String,
+	/// Is it a critical release?
+	pub is_critical: bool,
+	/// Hash of the release binary.
+	pub binary: H256,
+}
+
+/// Controls the client auto-updater.
+pub trait UpdateService: Send + Sync {
+	/// Returns the release waiting to be installed if any.
+	fn upgrade_ready(&self) -> Option<ReleaseInfo>;
+
+	/// Installs the release waiting to be installed, so that it's run once the client restarts.
+	/// Returns false if there is no such release or it can't be installed.
+	fn execute_upgrade(&self) -> bool;
+}
@@ -0,0 +1,323 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage layout of the standard multi-signature wallet contract.
+//!
+//! The wallet keeps its state in the following slots:
+//! `m_required` (0), `m_numOwners` (1), `m_owners` (2 - 257), `m_ownerIndex` (258),
+//! `m_pending` (259), `m_pendingIndex` (260), daily limit (261 - 263) and `m_txs` (264).
+//! Only contracts whose code is a known version of the wallet are read.
+
+use std::cmp;
+use util::{Address, Bytes, H256, U256, Uint, FixedHash, Hashable};
+use ethcore::client::{BlockChainClient, BlockID};
+use v1::helpers::call_decoder::ContractRegistry;
+
+const REQUIRED_SLOT: u64 = 0;
+const NUM_OWNERS_SLOT: u64 = 1;
+const OWNERS_SLOT: u64 = 2;
+const OWNER_INDEX_SLOT: u64 = 258;
+const PENDING_SLOT: u64 = 259;
+const PENDING_INDEX_SLOT: u64 = 260;
+const TXS_SLOT: u64 = 264;
+/// Maximal number of owners supported by the wallet.
+const MAX_OWNERS: u64 = 250;
+/// Maximal number of pending operation slots read.
+const MAX_PENDING: u64 = 1024;
+/// Maximal length of transaction data read.
+const MAX_DATA: usize = 64 * 1024;
+
+/// Wallet state lookup error.
+#[derive(Debug, PartialEq)]
+pub enum WalletError {
+	/// State of the latest block is not available.
+	StatePruned,
+	/// There is no wallet at given address.
+	NotAWallet(Address),
+	/// Given address is not an owner of the wallet.
+	NotAnOwner(Address),
+	/// There is no such pending operation.
+	NotPending(H256),
+	/// The owner has already confirmed the operation.
+	AlreadyConfirmed(Address),
+}
+
+/// Transaction executed once the operation is confirmed.
+#[derive(Debug, PartialEq)]
+pub struct WalletTransaction {
+	/// Recipient
+	pub to: Address,
+	/// Value
+	pub value: U256,
+	/// Data
+	pub data: Bytes,
+}
+
+/// Operation waiting for confirmations of the wallet owners.
+#[derive(Debug, PartialEq)]
+pub struct PendingOperation {
+	/// Operation hash
+	pub operation: H256,
+	/// Number of confirmations still needed
+	pub yet_needed: U256,
+	/// Owners who already confirmed the operation
+	pub confirmed_by: Vec<Address>,
+	/// Transaction of the operation (none for owner and limit changes)
+	pub transaction: Option<WalletTransaction>,
+}
+
+fn slot(n: u64) -> H256 {
+	H256::from(n)
+}
+
+fn offset(base: &H256, offset: u64) -> H256 {
+	(U256::from(base.clone()) + U256::from(offset)).into()
+}
+
+/// Position of the `mapping` value with given key.
+fn mapping_slot(key: &H256, mapping: u64) -> H256 {
+	let mut data = key.to_vec();
+	data.extend_from_slice(&slot(mapping));
+	data.sha3()
+}
+
+/// Reads the wallet storage at the latest block.
+pub struct Wallet<'a, C: 'a + ?Sized> {
+	client: &'a C,
+	address: Address,
+}
+
+impl<'a, C: 'a + ?Sized> Wallet<'a, C> where C: BlockChainClient {
+	/// Creates new reader of the wallet at given address.
+	/// Fails if the contract code is not a wallet version known to the registry.
+	pub fn new(client: &'a C, registry: &ContractRegistry, address: Address) -> Result<Self, WalletError> {
+		let code = try!(client.code(&address, BlockID::Latest).ok_or(WalletError::StatePruned));
+		if !code.map_or(false, |code| registry.is_wallet_code(&code.sha3())) {
+			return Err(WalletError::NotAWallet(address));
+		}
+
+		let wallet = Wallet {
+			client: client,
+			address: address,
+		};
+		let required = try!(wallet.uint(&slot(REQUIRED_SLOT)));
+		let owners = try!(wallet.uint(&slot(NUM_OWNERS_SLOT)));
+		if required.is_zero() || owners.is_zero() || owners > U256::from(MAX_OWNERS) {
+			return Err(WalletError::NotAWallet(address));
+		}
+		Ok(wallet)
+	}
+
+	fn storage(&self, position: &H256) -> Result<H256, WalletError> {
+		self.client.storage_at(&self.address, position, BlockID::Latest).ok_or(WalletError::StatePruned)
+	}
+
+	fn uint(&self, position: &H256) -> Result<U256, WalletError> {
+		self.storage(position).map(U256::from)
+	}
+
+	fn bytes(&self, position: &H256) -> Result<Bytes, WalletError> {
+		let value = try!(self.storage(position));
+		// short arrays are kept in the slot along with twice their length
+		if value[31] & 1 == 0 {
+			let len = cmp::min(value[31] as usize / 2, 31);
+			return Ok(value[..len].to_vec());
+		}
+
+		let len = (U256::from(value) - U256::one()) / U256::from(2);
+		let len = cmp::min(len, U256::from(MAX_DATA)).low_u64() as usize;
+		let start = position.sha3();
+		let mut data = Vec::with_capacity(len);
+		let mut i = 0;
+		while data.len() < len {
+			let word = try!(self.storage(&offset(&start, i)));
+			let remaining = len - data.len();
+			data.extend_from_slice(&word[..cmp::min(remaining, 32)]);
+			i += 1;
+		}
+		Ok(data)
+	}
+
+	/// Returns owners of the wallet, the index in the list is their index in the wallet decreased by one.
+	pub fn owners(&self) -> Result<Vec<Address>, WalletError> {
+		let count = try!(self.uint(&slot(NUM_OWNERS_SLOT))).low_u64();
+		(1..count + 1)
+			.map(|index| self.storage(&slot(OWNERS_SLOT + index)).map(Address::from))
+			.collect()
+	}
+
+	/// Returns index of given owner (starting from 1) or error if it's not an owner.
+	pub fn owner_index(&self, owner: &Address) -> Result<u64, WalletError> {
+		let index = try!(self.uint(&mapping_slot(&H256::from(owner.clone()), OWNER_INDEX_SLOT)));
+		match index.is_zero() || index > U256::from(MAX_OWNERS) {
+			true => Err(WalletError::NotAnOwner(owner.clone())),
+			false => Ok(index.low_u64()),
+		}
+	}
+
+	/// Returns the pending operation with given hash.
+	pub fn pending_operation(&self, operation: &H256) -> Result<PendingOperation, WalletError> {
+		let owners = try!(self.owners());
+		self.pending_operation_of(operation, &owners)
+	}
+
+	// reads the operation given the current owners of the wallet.
+	fn pending_operation_of(&self, operation: &H256, owners: &[Address]) -> Result<PendingOperation, WalletError> {
+		let pending = mapping_slot(operation, PENDING_SLOT);
+		let yet_needed = try!(self.uint(&pending));
+		if yet_needed.is_zero() {
+			return Err(WalletError::NotPending(operation.clone()));
+		}
+		let owners_done = try!(self.uint(&offset(&pending, 1)));
+		let confirmed_by = owners.iter()
+			.enumerate()
+			.filter(|&(index, _)| owners_done.bit(index + 1))
+			.map(|(_, owner)| owner.clone())
+			.collect();
+
+		let transaction = mapping_slot(operation, TXS_SLOT);
+		let to: Address = try!(self.storage(&transaction)).into();
+		let transaction = match to.is_zero() {
+			true => None,
+			false => Some(WalletTransaction {
+				to: to,
+				value: try!(self.uint(&offset(&transaction, 1))),
+				data: try!(self.bytes(&offset(&transaction, 2))),
+			}),
+		};
+
+		Ok(PendingOperation {
+			operation: operation.clone(),
+			yet_needed: yet_needed,
+			confirmed_by: confirmed_by,
+			transaction: transaction,
+		})
+	}
+
+	/// Returns all operations waiting for confirmations.
+	pub fn pending_operations(&self) -> Result<Vec<PendingOperation>, WalletError> {
+		let count = try!(self.uint(&slot(PENDING_INDEX_SLOT)));
+		let count = cmp::min(count, U256::from(MAX_PENDING)).low_u64();
+		let start = slot(PENDING_INDEX_SLOT).sha3();
+		let owners = try!(self.owners());
+
+		let mut operations = Vec::new();
+		for i in 0..count {
+			// confirmed operations are removed from the index leaving empty entries
+			let operation = try!(self.storage(&offset(&start, i)));
+			if operation.is_zero() {
+				continue;
+			}
+			match self.pending_operation_of(&operation, &owners) {
+				Ok(pending) => operations.push(pending),
+				Err(WalletError::NotPending(_)) => {},
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(operations)
+	}
+
+	/// Checks that given owner can confirm the operation.
+	pub fn can_confirm(&self, owner: &Address, operation: &H256) -> Result<(), WalletError> {
+		let index = try!(self.owner_index(owner));
+		let pending = mapping_slot(operation, PENDING_SLOT);
+		if try!(self.uint(&pending)).is_zero() {
+			return Err(WalletError::NotPending(operation.clone()));
+		}
+		match try!(self.uint(&offset(&pending, 1))).bit(index as usize) {
+			true => Err(WalletError::AlreadyConfirmed(owner.clone())),
+			false => Ok(()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::{Address, H256, U256, Hashable};
+	use ethcore::client::TestBlockChainClient;
+	use v1::tests::helpers::TestRegistry;
+	use super::{Wallet, WalletError, WalletTransaction, mapping_slot, offset, slot};
+
+	const WALLET_CODE: &'static [u8] = &[0x60, 0x00];
+
+	fn registry() -> TestRegistry {
+		let mut registry = TestRegistry::default();
+		registry.wallet_codes.insert(WALLET_CODE.sha3());
+		registry
+	}
+
+	#[test]
+	fn should_decode_pending_operation() {
+		// given
+		let client = TestBlockChainClient::new();
+		let wallet = Address::from(0x1000);
+		let operation = H256::from(0xabcd);
+		let set = |position: H256, value: H256| client.set_storage(wallet.clone(), position, value);
+
+		client.set_code(wallet.clone(), WALLET_CODE.to_vec());
+		set(slot(0), H256::from(2));
+		set(slot(1), H256::from(2));
+		set(slot(3), H256::from(Address::from(1)));
+		set(slot(4), H256::from(Address::from(2)));
+		set(mapping_slot(&H256::from(Address::from(1)), 258), H256::from(1));
+		set(mapping_slot(&H256::from(Address::from(2)), 258), H256::from(2));
+		let pending = mapping_slot(&operation, 259);
+		set(pending.clone(), H256::from(1));
+		set(offset(&pending, 1), H256::from(0b100));
+		set(slot(260), H256::from(2));
+		set(offset(&slot(260).sha3(), 1), operation.clone());
+		let tx = mapping_slot(&operation, 264);
+		set(tx.clone(), H256::from(Address::from(5)));
+		set(offset(&tx, 1), H256::from(100));
+		// 2-byte data stored in place
+		let mut data = H256::from(4);
+		data[0] = 0x12;
+		data[1] = 0x34;
+		set(offset(&tx, 2), data);
+
+		// when
+		let registry = registry();
+		let wallet = Wallet::new(&client, &registry, wallet).unwrap();
+		let operations = wallet.pending_operations().unwrap();
+
+		// then
+		assert_eq!(operations.len(), 1);
+		assert_eq!(operations[0].operation, operation);
+		assert_eq!(operations[0].yet_needed, U256::from(1));
+		assert_eq!(operations[0].confirmed_by, vec![Address::from(2)]);
+		assert_eq!(operations[0].transaction, Some(WalletTransaction {
+			to: Address::from(5),
+			value: U256::from(100),
+			data: vec![0x12, 0x34],
+		}));
+		assert_eq!(wallet.can_confirm(&Address::from(1), &operation), Ok(()));
+		assert_eq!(wallet.can_confirm(&Address::from(2), &operation), Err(WalletError::AlreadyConfirmed(Address::from(2))));
+		assert_eq!(wallet.can_confirm(&Address::from(3), &operation), Err(WalletError::NotAnOwner(Address::from(3))));
+	}
+
+	#[test]
+	fn should_reject_contracts_which_are_not_wallets() {
+		let client = TestBlockChainClient::new();
+		let registry = registry();
+		// wallet-like storage but unknown code
+		client.set_code(Address::from(6), vec![0x60, 0x01]);
+		client.set_storage(Address::from(6), slot(0), H256::from(1));
+		client.set_storage(Address::from(6), slot(1), H256::from(1));
+
+		assert_eq!(Wallet::new(&client, &registry, Address::from(5)).err(), Some(WalletError::NotAWallet(Address::from(5))));
+		assert_eq!(Wallet::new(&client, &registry, Address::from(6)).err(), Some(WalletError::NotAWallet(Address::from(6))));
+	}
+}
@@ -18,14 +18,15 @@
 
 extern crate ethash;
 
+use std::cmp;
 use std::io::{Write};
 use std::process::{Command, Stdio};
 use std::thread;
 use std::time::{Instant, Duration};
-use std::sync::{Arc, Weak};
+use std::sync::{Arc, Weak, mpsc};
 use time::get_time;
 use ethsync::{SyncProvider, SyncState};
-use ethcore::miner::{MinerService, ExternalMinerService};
+use ethcore::miner::{MinerService, ExternalMinerService, ShareResult};
 use jsonrpc_core::*;
 use util::{H256, Address, FixedHash, U256, H64, Uint};
 use util::sha3::*;
@@ -34,21 +35,22 @@ use rlp::{self, UntrustedRlp, View};
 use ethcore::account_provider::AccountProvider;
 use ethcore::client::{MiningBlockChainClient, BlockID, TransactionID, UncleID};
 use ethcore::header::{Header as BlockHeader, BlockNumber as EthBlockNumber};
-use ethcore::block::IsBlock;
+use ethcore::block::{IsBlock, Block as EthBlock};
 use ethcore::views::*;
 use ethcore::ethereum::Ethash;
-use ethcore::transaction::{Transaction as EthTransaction, SignedTransaction, Action};
+use ethcore::transaction::SignedTransaction;
 use ethcore::log_entry::LogEntry;
+use ethcore::error::Error as EthcoreError;
 use ethcore::filter::Filter as EthcoreFilter;
 use self::ethash::SeedHashCompute;
 use v1::traits::Eth;
 use v1::types::{
 	Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, QueueStatus, CallRequest, Index, Filter, Log, Receipt, Work,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
-use v1::helpers::{CallRequest as CRequest, errors, limit_logs};
-use v1::helpers::dispatch::{default_gas_price, dispatch_transaction};
+use v1::helpers::{CallRequest as CRequest, ResponseCache, errors, limit_logs, ensure_response_size, estimate_block_size, estimate_log_size, origin};
+use v1::helpers::dispatch::{default_gas_price, dispatch_transaction, fake_sign_call};
 use v1::helpers::auto_args::Trailing;
 
 /// Eth RPC options
@@ -57,6 +59,12 @@ pub struct EthClientOptions {
 	pub allow_pending_receipt_query: bool,
 	/// Send additional block number when asking for work
 	pub send_block_number_in_get_work: bool,
+	/// Cache of block and receipt responses shared with other servers
+	pub response_cache: Option<Arc<ResponseCache>>,
+	/// `eth_syncing` reports no sync in progress when we are at most that many blocks behind
+	pub sync_threshold: u64,
+	/// Maximal size in bytes of block and log responses. Unlimited if none.
+	pub max_response_size: Option<usize>,
 }
 
 impl Default for EthClientOptions {
@@ -64,6 +72,9 @@ impl Default for EthClientOptions {
 		EthClientOptions {
 			allow_pending_receipt_query: true,
 			send_block_number_in_get_work: true,
+			response_cache: None,
+			sync_threshold: 6,
+			max_response_size: None,
 		}
 	}
 }
@@ -80,7 +91,7 @@ pub struct EthClient<C, S: ?Sized, M, EM> where
 	accounts: Weak<AccountProvider>,
 	miner: Weak<M>,
 	external_miner: Arc<EM>,
-	seed_compute: Mutex<SeedHashCompute>,
+	seed_compute: Arc<Mutex<SeedHashCompute>>,
 	options: EthClientOptions,
 }
 
@@ -99,16 +110,54 @@ impl<C, S: ?Sized, M, EM> EthClient<C, S, M, EM> where
 			miner: Arc::downgrade(miner),
 			accounts: Arc::downgrade(accounts),
 			external_miner: em.clone(),
-			seed_compute: Mutex::new(SeedHashCompute::new()),
+			seed_compute: Arc::new(Mutex::new(SeedHashCompute::new())),
 			options: options,
 		}
 	}
 
 	fn block(&self, id: BlockID, include_txs: bool) -> Result<Option<Block>, Error> {
 		let client = take_weak!(self.client);
-		match (client.block(id.clone()), client.block_total_difficulty(id)) {
+		// pending block is not part of the chain yet, so it's never cached
+		let cache = match id {
+			BlockID::Pending => {
+				let best_block = client.chain_info().best_block_number;
+				if let Some(block) = take_weak!(self.miner).pending_block(best_block) {
+					return self.pending_block(block, include_txs).map(Some);
+				}
+				None
+			},
+			_ => self.options.response_cache.as_ref().and_then(|cache| client.block_hash(id.clone()).map(|hash| (cache, hash))),
+		};
+		if let Some((cache, hash)) = cache {
+			if let Some(block) = cache.block(&hash, include_txs) {
+				let size = block.size.map_or(0, |size| {
+					let size: U256 = size.into();
+					size.low_u64() as usize
+				});
+				let transactions = match block.transactions {
+					BlockTransactions::Full(ref txs) => txs.len(),
+					BlockTransactions::Hashes(ref hashes) => hashes.len(),
+				};
+				try!(ensure_response_size(estimate_block_size(size, transactions, include_txs), self.options.max_response_size));
+				return Ok(Some(block));
+			}
+			let block = try!(self.block_uncached(BlockID::Hash(hash.clone()), include_txs));
+			if let Some(ref block) = block {
+				cache.insert_block(hash, include_txs, block.clone());
+			}
+			return Ok(block);
+		}
+
+		self.block_uncached(id, include_txs)
+	}
+
+	fn block_uncached(&self, id: BlockID, include_txs: bool) -> Result<Option<Block>, Error> {
+		let client = take_weak!(self.client);
+		match (client.block(id.clone()), client.block_total_difficulty(id.clone())) {
 			(Some(bytes), Some(total_difficulty)) => {
 				let block_view = BlockView::new(&bytes);
+				let estimate = estimate_block_size(bytes.len(), block_view.transactions_count(), include_txs);
+				try!(ensure_response_size(estimate, self.options.max_response_size));
 				let view = block_view.header_view();
 				let block = Block {
 					hash: Some(view.sha3().into()),
@@ -128,6 +177,7 @@ impl<C, S: ?Sized, M, EM> EthClient<C, S, M, EM> where
 					difficulty: view.difficulty().into(),
 					total_difficulty: total_difficulty.into(),
 					seal_fields: view.seal().into_iter().map(|f| rlp::decode(&f)).map(Bytes::new).collect(),
+					seal_info: client.block_extra_info(id).unwrap_or_default(),
 					uncles: block_view.uncle_hashes().into_iter().map(Into::into).collect(),
 					transactions: match include_txs {
 						true => BlockTransactions::Full(block_view.localized_transactions().into_iter().map(Into::into).collect()),
@@ -137,22 +187,72 @@ impl<C, S: ?Sized, M, EM> EthClient<C, S, M, EM> where
 				};
 				Ok(Some(block))
 			},
-			_ => Ok(None)
+			_ => self.found_or_pruned(None, id),
+		}
+	}
+
+	/// Converts the block being sealed by the miner. It has no hash or seal until it's mined,
+	/// and its transactions aren't assigned to any block yet.
+	fn pending_block(&self, block: EthBlock, include_txs: bool) -> Result<Block, Error> {
+		let client = take_weak!(self.client);
+		let size = {
+			let mut rlp = rlp::RlpStream::new_list(3);
+			rlp.append(&block.header).append(&block.transactions).append(&block.uncles);
+			rlp.out().len()
+		};
+		try!(ensure_response_size(estimate_block_size(size, block.transactions.len(), include_txs), self.options.max_response_size));
+		let header = block.header;
+		let parent_difficulty = client.block_total_difficulty(BlockID::Hash(header.parent_hash().clone())).unwrap_or_default();
+		Ok(Block {
+			hash: None,
+			size: Some(size.into()),
+			parent_hash: header.parent_hash().clone().into(),
+			uncles_hash: header.uncles_hash().clone().into(),
+			author: header.author().clone().into(),
+			miner: header.author().clone().into(),
+			state_root: header.state_root().clone().into(),
+			transactions_root: header.transactions_root().clone().into(),
+			receipts_root: header.receipts_root().clone().into(),
+			number: Some(header.number().into()),
+			gas_used: header.gas_used().clone().into(),
+			gas_limit: header.gas_limit().clone().into(),
+			logs_bloom: header.log_bloom().clone().into(),
+			timestamp: header.timestamp().into(),
+			difficulty: header.difficulty().clone().into(),
+			total_difficulty: (parent_difficulty + *header.difficulty()).into(),
+			seal_fields: Vec::new(),
+			seal_info: Default::default(),
+			uncles: block.uncles.iter().map(|uncle| uncle.hash().into()).collect(),
+			transactions: match include_txs {
+				true => BlockTransactions::Full(block.transactions.into_iter().map(Into::into).collect()),
+				false => BlockTransactions::Hashes(block.transactions.iter().map(|t| t.hash().into()).collect()),
+			},
+			extra_data: Bytes::new(header.extra_data().clone()),
+		})
+	}
+
+	/// Reports a missing value of a known block whose body was pruned as an error.
+	fn found_or_pruned<T>(&self, value: Option<T>, id: BlockID) -> Result<Option<T>, Error> {
+		match value {
+			None if take_weak!(self.client).is_pruned(id) => Err(errors::block_pruned()),
+			value => Ok(value),
 		}
 	}
 
 	fn transaction(&self, id: TransactionID) -> Result<Option<Transaction>, Error> {
-		match take_weak!(self.client).transaction(id) {
+		let client = take_weak!(self.client);
+		match client.transaction(id.clone()) {
 			Some(t) => Ok(Some(Transaction::from(t))),
+			None if client.is_transaction_pruned(id) => Err(errors::block_pruned()),
 			None => Ok(None),
 		}
 	}
 
 	fn uncle(&self, id: UncleID) -> Result<Option<Block>, Error> {
 		let client = take_weak!(self.client);
-		let uncle: BlockHeader = match client.uncle(id) {
-			Some(rlp) => rlp::decode(&rlp),
-			None => { return Ok(None); }
+		let (uncle, size): (BlockHeader, usize) = match client.uncle(id.clone()) {
+			Some(rlp) => (rlp::decode(&rlp), rlp.len()),
+			None => { return self.found_or_pruned(None, id.block); }
 		};
 		let parent_difficulty = match client.block_total_difficulty(BlockID::Hash(uncle.parent_hash().clone())) {
 			Some(difficulty) => difficulty,
@@ -161,7 +261,7 @@ impl<C, S: ?Sized, M, EM> EthClient<C, S, M, EM> where
 
 		let block = Block {
 			hash: Some(uncle.hash().into()),
-			size: None,
+			size: Some(size.into()),
 			parent_hash: uncle.parent_hash().clone().into(),
 			uncles_hash: uncle.uncles_hash().clone().into(),
 			author: uncle.author().clone().into(),
@@ -178,6 +278,7 @@ impl<C, S: ?Sized, M, EM> EthClient<C, S, M, EM> where
 			receipts_root: uncle.receipts_root().clone().into(),
 			extra_data: uncle.extra_data().clone().into(),
 			seal_fields: uncle.seal().clone().into_iter().map(|f| rlp::decode(&f)).map(Bytes::new).collect(),
+			seal_info: client.uncle_extra_info(id).unwrap_or_default(),
 			uncles: vec![],
 			transactions: BlockTransactions::Hashes(vec![]),
 		};
@@ -185,16 +286,7 @@ impl<C, S: ?Sized, M, EM> EthClient<C, S, M, EM> where
 	}
 
 	fn sign_call(&self, request: CRequest) -> Result<SignedTransaction, Error> {
-		let (client, miner) = (take_weak!(self.client), take_weak!(self.miner));
-		let from = request.from.unwrap_or(Address::zero());
-		Ok(EthTransaction {
-			nonce: request.nonce.unwrap_or_else(|| client.latest_nonce(&from)),
-			action: request.to.map_or(Action::Create, Action::Call),
-			gas: request.gas.unwrap_or(U256::from(50_000_000)),
-			gas_price: request.gas_price.unwrap_or_else(|| default_gas_price(&*client, &*miner)),
-			value: request.value.unwrap_or_else(U256::zero),
-			data: request.data.map_or_else(Vec::new, |d| d.to_vec())
-		}.fake_sign(from))
+		Ok(fake_sign_call(&*take_weak!(self.client), &*take_weak!(self.miner), request))
 	}
 }
 
@@ -218,6 +310,36 @@ pub fn pending_logs<M>(miner: &M, best_block: EthBlockNumber, filter: &EthcoreFi
 }
 
 const MAX_QUEUE_SIZE_TO_MINE_ON: usize = 4;	// because uncles go back 6.
+const MAX_WORK_CHANGE_WAIT_MS: u64 = 60_000;
+
+/// Describes the current sealing work of the miner.
+fn work_package<C, M>(client: &C, miner: &M, seed_compute: &Mutex<SeedHashCompute>, no_new_work_timeout: u64, send_block_number: bool) -> Result<Work, Error>
+	where C: MiningBlockChainClient, M: MinerService {
+	miner.map_sealing_work(client, |b| {
+		let pow_hash = b.hash();
+		let target = Ethash::difficulty_to_boundary(b.block().header().difficulty());
+		let seed_hash = seed_compute.lock().get_seedhash(b.block().header().number());
+
+		if no_new_work_timeout > 0 && b.block().header().timestamp() + no_new_work_timeout < get_time().sec as u64 {
+			Err(errors::no_new_work())
+		} else if send_block_number {
+			let block_number = b.block().header().number();
+			Ok(Work {
+				pow_hash: pow_hash.into(),
+				seed_hash: seed_hash.into(),
+				target: target.into(),
+				number: Some(block_number),
+			})
+		} else {
+			Ok(Work {
+				pow_hash: pow_hash.into(),
+				seed_hash: seed_hash.into(),
+				target: target.into(),
+				number: None
+			})
+		}
+	}).unwrap_or(Err(Error::internal_error()))	// no work found.
+}
 
 impl<C, S: ?Sized, M, EM> EthClient<C, S, M, EM> where
 	C: MiningBlockChainClient + 'static,
@@ -258,15 +380,21 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 		match status.state {
 			SyncState::Idle => Ok(SyncStatus::None),
 			SyncState::Waiting | SyncState::Blocks | SyncState::NewBlocks | SyncState::ChainHead
-				| SyncState::SnapshotManifest | SyncState::SnapshotData | SyncState::SnapshotWaiting => {
+				| SyncState::SnapshotManifest | SyncState::CheckpointHeaders | SyncState::SnapshotData | SyncState::SnapshotWaiting => {
 				let current_block = U256::from(take_weak!(self.client).chain_info().best_block_number);
 				let highest_block = U256::from(status.highest_block_number.unwrap_or(status.start_block_number));
 
-				if highest_block > current_block + U256::from(6) {
+				if highest_block > current_block + U256::from(self.options.sync_threshold) {
+					let is_warping = match status.state {
+						SyncState::SnapshotManifest | SyncState::CheckpointHeaders | SyncState::SnapshotData | SyncState::SnapshotWaiting => true,
+						_ => false,
+					};
 					let info = SyncInfo {
 						starting_block: status.start_block_number.into(),
 						current_block: current_block.into(),
 						highest_block: highest_block.into(),
+						warp_chunks_amount: if is_warping { Some(U256::from(status.num_snapshot_chunks).into()) } else { None },
+						warp_chunks_processed: if is_warping { Some(U256::from(status.snapshot_chunks_done).into()) } else { None },
 					};
 					Ok(SyncStatus::Info(info))
 				} else {
@@ -305,7 +433,7 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 		try!(self.active());
 
 		let store = take_weak!(self.accounts);
-		let accounts = try!(store.accounts().map_err(|e| errors::internal("Could not fetch accounts.", e)));
+		let accounts = try!(origin::current().accounts(&*store).map_err(|e| errors::internal("Could not fetch accounts.", e)));
 		Ok(accounts.into_iter().map(Into::into).collect())
 	}
 
@@ -356,10 +484,10 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 
 	fn block_transaction_count_by_hash(&self, hash: RpcH256) -> Result<Option<RpcU256>, Error> {
 		try!(self.active());
-		Ok(
-			take_weak!(self.client).block(BlockID::Hash(hash.into()))
-				.map(|bytes| BlockView::new(&bytes).transactions_count().into())
-		)
+		let id = BlockID::Hash(hash.into());
+		let count = take_weak!(self.client).block(id.clone())
+			.map(|bytes| BlockView::new(&bytes).transactions_count().into());
+		self.found_or_pruned(count, id)
 	}
 
 	fn block_transaction_count_by_number(&self, num: BlockNumber) -> Result<Option<RpcU256>, Error> {
@@ -369,20 +497,22 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 			BlockNumber::Pending => Ok(Some(
 				take_weak!(self.miner).status().transactions_in_pending_block.into()
 			)),
-			_ => Ok(
-				take_weak!(self.client).block(num.into())
-					.map(|bytes| BlockView::new(&bytes).transactions_count().into())
-				)
+			_ => {
+				let id: BlockID = num.into();
+				let count = take_weak!(self.client).block(id.clone())
+					.map(|bytes| BlockView::new(&bytes).transactions_count().into());
+				self.found_or_pruned(count, id)
+			}
 		}
 	}
 
 	fn block_uncles_count_by_hash(&self, hash: RpcH256) -> Result<Option<RpcU256>, Error> {
 		try!(self.active());
 
-		Ok(
-			take_weak!(self.client).block(BlockID::Hash(hash.into()))
-				.map(|bytes| BlockView::new(&bytes).uncles_count().into())
-		)
+		let id = BlockID::Hash(hash.into());
+		let count = take_weak!(self.client).block(id.clone())
+			.map(|bytes| BlockView::new(&bytes).uncles_count().into());
+		self.found_or_pruned(count, id)
 	}
 
 	fn block_uncles_count_by_number(&self, num: BlockNumber) -> Result<Option<RpcU256>, Error> {
@@ -390,10 +520,12 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 
 		match num {
 			BlockNumber::Pending => Ok(Some(0.into())),
-			_ => Ok(
-				take_weak!(self.client).block(num.into())
-					.map(|bytes| BlockView::new(&bytes).uncles_count().into())
-			),
+			_ => {
+				let id: BlockID = num.into();
+				let count = take_weak!(self.client).block(id.clone())
+					.map(|bytes| BlockView::new(&bytes).uncles_count().into());
+				self.found_or_pruned(count, id)
+			},
 		}
 	}
 
@@ -427,7 +559,13 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 		let hash: H256 = hash.into();
 		let miner = take_weak!(self.miner);
 		let client = take_weak!(self.client);
-		Ok(try!(self.transaction(TransactionID::Hash(hash))).or_else(|| miner.transaction(client.chain_info().best_block_number, &hash).map(Into::into)))
+		let queued = |hash: &H256| {
+			miner.transaction(client.chain_info().best_block_number, hash)
+				.map(|t| (t, QueueStatus::Pending))
+				.or_else(|| miner.queued_transaction(hash).map(|(t, status)| (t, status.into())))
+				.map(|(t, status)| Transaction { queue_status: Some(status), ..t.into() })
+		};
+		Ok(try!(self.transaction(TransactionID::Hash(hash))).or_else(|| queued(&hash)))
 	}
 
 	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, index: Index) -> Result<Option<Transaction>, Error> {
@@ -451,9 +589,18 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 		match (miner.pending_receipt(best_block, &hash), self.options.allow_pending_receipt_query) {
 			(Some(receipt), true) => Ok(Some(receipt.into())),
 			_ => {
+				if let Some(receipt) = self.options.response_cache.as_ref().and_then(|cache| cache.receipt(&hash)) {
+					return Ok(Some(receipt));
+				}
 				let client = take_weak!(self.client);
-				let receipt = client.transaction_receipt(TransactionID::Hash(hash));
-				Ok(receipt.map(Into::into))
+				let receipt: Option<Receipt> = client.transaction_receipt(TransactionID::Hash(hash.clone())).map(Into::into);
+				if receipt.is_none() && client.is_transaction_pruned(TransactionID::Hash(hash.clone())) {
+					return Err(errors::block_pruned());
+				}
+				if let (Some(cache), Some(receipt)) = (self.options.response_cache.as_ref(), receipt.as_ref()) {
+					cache.insert_receipt(hash, receipt.clone());
+				}
+				Ok(receipt)
 			}
 		}
 	}
@@ -482,10 +629,19 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 	}
 
 	fn logs(&self, filter: Filter) -> Result<Vec<Log>, Error> {
+		if filter.is_ambiguous() {
+			return Err(errors::invalid_params("Filter", "blockHash can't be used together with fromBlock or toBlock"));
+		}
+
 		let include_pending = filter.to_block == Some(BlockNumber::Pending);
 		let filter: EthcoreFilter = filter.into();
-		let mut logs = take_weak!(self.client).logs(filter.clone())
-			.into_iter()
+		let logs = take_weak!(self.client).logs(filter.clone());
+		let skip = filter.limit.map_or(0, |limit| logs.len().saturating_sub(limit));
+		let estimate = logs.iter()
+			.skip(skip)
+			.fold(0, |acc, log| acc + estimate_log_size(log.entry.data.len(), log.entry.topics.len()));
+		try!(ensure_response_size(estimate, self.options.max_response_size));
+		let mut logs = logs.into_iter()
 			.map(From::from)
 			.collect::<Vec<Log>>();
 
@@ -495,9 +651,7 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 			logs.extend(pending);
 		}
 
-		let logs = limit_logs(logs, filter.limit);
-
-		Ok(logs)
+		Ok(limit_logs(logs, filter.limit))
 	}
 
 	fn work(&self, no_new_work_timeout: Trailing<u64>) -> Result<Work, Error> {
@@ -526,33 +680,39 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 			warn!(target: "miner", "Cannot give work package - no author is configured. Use --author to configure!");
 			return Err(errors::no_author())
 		}
-		miner.map_sealing_work(&*client, |b| {
-			let pow_hash = b.hash();
-			let target = Ethash::difficulty_to_boundary(b.block().header().difficulty());
-			let seed_hash = self.seed_compute.lock().get_seedhash(b.block().header().number());
-
-			if no_new_work_timeout > 0 && b.block().header().timestamp() + no_new_work_timeout < get_time().sec as u64 {
-				Err(errors::no_new_work())
-			} else if self.options.send_block_number_in_get_work {
-				let block_number = b.block().header().number();
-				Ok(Work {
-					pow_hash: pow_hash.into(),
-					seed_hash: seed_hash.into(),
-					target: target.into(),
-					number: Some(block_number),
-				})
-			} else {
-				Ok(Work {
-					pow_hash: pow_hash.into(),
-					seed_hash: seed_hash.into(),
-					target: target.into(),
-					number: None
-				})
+		work_package(&*client, &*miner, &self.seed_compute, no_new_work_timeout, self.options.send_block_number_in_get_work)
+	}
+
+	fn work_change(&self, ready: Ready<Work>, pow_hash: RpcH256, timeout: u64) {
+		let work = self.work(Trailing(0));
+		if work.as_ref().map_or(true, |work| work.pow_hash != pow_hash) {
+			return ready.ready(work);
+		}
+		let miner = match self.miner.upgrade() {
+			Some(miner) => miner,
+			None => return ready.ready(Err(Error::internal_error())),
+		};
+
+		let (client, weak_miner) = (self.client.clone(), self.miner.clone());
+		let (seed_compute, send_block_number) = (self.seed_compute.clone(), self.options.send_block_number_in_get_work);
+		let (tx, rx) = mpsc::channel();
+		tx.send(ready).expect("Receiver is moved to the callback below.");
+
+		// the call above enabled sealing, so any change is going to be announced.
+		let timeout = Duration::from_millis(cmp::min(timeout, MAX_WORK_CHANGE_WAIT_MS));
+		miner.on_work_change(&pow_hash.into(), timeout, Box::new(move |_: bool| {
+			// the callback is invoked once, so ready is always there.
+			if let Ok(ready) = rx.try_recv() {
+				let work = match (client.upgrade(), weak_miner.upgrade()) {
+					(Some(client), Some(miner)) => work_package(&*client, &*miner, &seed_compute, 0, send_block_number),
+					_ => Err(Error::internal_error()),
+				};
+				ready.ready(work);
 			}
-		}).unwrap_or(Err(Error::internal_error()))	// no work found.
+		}));
 	}
 
-	fn submit_work(&self, nonce: RpcH64, pow_hash: RpcH256, mix_hash: RpcH256) -> Result<bool, Error> {
+	fn submit_work(&self, nonce: RpcH64, pow_hash: RpcH256, mix_hash: RpcH256, worker: Trailing<RpcH256>) -> Result<bool, Error> {
 		try!(self.active());
 
 		let nonce: H64 = nonce.into();
@@ -563,7 +723,13 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 		let miner = take_weak!(self.miner);
 		let client = take_weak!(self.client);
 		let seal = vec![rlp::encode(&mix_hash).to_vec(), rlp::encode(&nonce).to_vec()];
-		Ok(miner.submit_seal(&*client, pow_hash, seal).is_ok())
+		let result = miner.submit_seal(&*client, pow_hash, seal);
+		self.external_miner.submit_share(worker.0.into(), match result {
+			Ok(_) => ShareResult::Accepted,
+			Err(EthcoreError::PowHashInvalid) => ShareResult::Stale,
+			Err(_) => ShareResult::Invalid,
+		});
+		Ok(result.is_ok())
 	}
 
 	fn submit_hashrate(&self, rate: RpcU256, id: RpcH256) -> Result<bool, Error> {
@@ -577,7 +743,7 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 
 		let raw_transaction = raw.to_vec();
 		match UntrustedRlp::new(&raw_transaction).as_val() {
-			Ok(signed_transaction) => dispatch_transaction(&*take_weak!(self.client), &*take_weak!(self.miner), signed_transaction),
+			Ok(signed_transaction) => dispatch_transaction(&*take_weak!(self.client), &*take_weak!(self.miner), signed_transaction, false),
 			Err(_) => Ok(RpcH256::from(H256::from(0))),
 		}
 	}
@@ -593,7 +759,11 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 			num => take_weak!(self.client).call(&signed, num.into(), Default::default()),
 		};
 
-		Ok(r.map(|e| Bytes(e.output)).unwrap_or(Bytes::new(vec![])))
+		match r {
+			Ok(ref e) if e.reverted => Err(errors::execution_reverted(&e.output)),
+			Ok(e) => Ok(Bytes(e.output)),
+			Err(_) => Ok(Bytes::new(vec![])),
+		}
 	}
 
 	fn estimate_gas(&self, request: CallRequest, num: Trailing<BlockNumber>) -> Result<RpcU256, Error> {
@@ -606,7 +776,11 @@ impl<C, S: ?Sized, M, EM> Eth for EthClient<C, S, M, EM> where
 			num => take_weak!(self.client).call(&signed, num.into(), Default::default()),
 		};
 
-		Ok(RpcU256::from(r.map(|res| res.gas_used + res.refunded).unwrap_or(From::from(0))))
+		match r {
+			Ok(ref res) if res.reverted => Err(errors::execution_reverted(&res.output)),
+			Ok(res) => Ok(RpcU256::from(res.gas_used + res.refunded)),
+			Err(_) => Ok(RpcU256::from(0)),
+		}
 	}
 
 	fn compile_lll(&self, _: String) -> Result<Bytes, Error> {
@@ -25,7 +25,7 @@ use ethcore::client::{BlockChainClient, BlockID};
 use util::Mutex;
 use v1::traits::EthFilter;
 use v1::types::{BlockNumber, Index, Filter, FilterChanges, Log, H256 as RpcH256, U256 as RpcU256};
-use v1::helpers::{PollFilter, PollManager, limit_logs};
+use v1::helpers::{PollFilter, PollManager, errors, limit_logs, session};
 use v1::impls::eth::pending_logs;
 
 /// Eth filter rpc implementation.
@@ -63,9 +63,13 @@ impl<C, M> EthFilter for EthFilterClient<C, M>
 {
 	fn new_filter(&self, filter: Filter) -> Result<RpcU256, Error> {
 		try!(self.active());
+		if filter.block_hash.is_some() {
+			return Err(errors::invalid_params("Filter", "blockHash is only supported by eth_getLogs"));
+		}
 		let mut polls = self.polls.lock();
 		let block_number = take_weak!(self.client).chain_info().best_block_number;
 		let id = polls.create_poll(PollFilter::Logs(block_number, Default::default(), filter));
+		session::filter_installed(id);
 		Ok(id.into())
 	}
 
@@ -74,6 +78,7 @@ impl<C, M> EthFilter for EthFilterClient<C, M>
 
 		let mut polls = self.polls.lock();
 		let id = polls.create_poll(PollFilter::Block(take_weak!(self.client).chain_info().best_block_number));
+		session::filter_installed(id);
 		Ok(id.into())
 	}
 
@@ -84,6 +89,7 @@ impl<C, M> EthFilter for EthFilterClient<C, M>
 		let best_block = take_weak!(self.client).chain_info().best_block_number;
 		let pending_transactions = take_weak!(self.miner).pending_transactions_hashes(best_block);
 		let id = polls.create_poll(PollFilter::PendingTransaction(pending_transactions));
+		session::filter_installed(id);
 		Ok(id.into())
 	}
 
@@ -210,6 +216,7 @@ impl<C, M> EthFilter for EthFilterClient<C, M>
 		try!(self.active());
 
 		self.polls.lock().remove_poll(&index.value());
+		session::filter_uninstalled(index.value());
 		Ok(true)
 	}
 }
@@ -16,15 +16,19 @@
 
 //! Eth Signing RPC implementation.
 
+use std::collections::BTreeMap;
 use std::sync::{Arc, Weak};
 use jsonrpc_core::*;
+use serde_json;
 use ethcore::miner::MinerService;
-use ethcore::client::MiningBlockChainClient;
-use util::{U256, Address, H256, Mutex};
+use ethcore::client::{MiningBlockChainClient, TransactionID};
+use util::{U256, H256, Address, Mutex};
 use transient_hashmap::TransientHashMap;
 use ethcore::account_provider::AccountProvider;
-use v1::helpers::{errors, SigningQueue, ConfirmationPromise, ConfirmationResult, ConfirmationPayload, TransactionRequest as TRequest, FilledTransactionRequest as FilledRequest, SignerService};
-use v1::helpers::dispatch::{default_gas_price, sign_and_dispatch};
+use ethcore::contract_address;
+use v1::helpers::{call_decoder, errors, origin, SigningQueue, ConfirmationPromise, ConfirmationResult, ConfirmationPayload, TransactionRequest as TRequest, FilledTransactionRequest as FilledRequest, SignerService};
+use v1::helpers::origin::Origin;
+use v1::helpers::dispatch::{default_gas_price, sign_and_dispatch, sign_transaction, eth_data_hash};
 use v1::traits::EthSigning;
 use v1::types::{TransactionRequest, H160 as RpcH160, H256 as RpcH256, H520 as RpcH520, U256 as RpcU256, Bytes as RpcBytes};
 
@@ -38,9 +42,43 @@ fn fill_optional_fields<C, M>(request: TRequest, client: &C, miner: &M) -> Fille
 		gas: request.gas.unwrap_or_else(|| miner.sensible_gas_limit()),
 		value: request.value.unwrap_or_else(|| 0.into()),
 		data: request.data.unwrap_or_else(Vec::new),
+		local_only: request.local_only,
 	}
 }
 
+/// Parses `ethcore_deployContract` parameters into the contract creation request with ABI-encoded
+/// constructor arguments.
+fn deployment_request(params: Params) -> Result<TRequest, Error> {
+	from_params::<(String, RpcBytes, Vec<Value>, TransactionRequest)>(params).and_then(|(abi, code, args, options)| {
+		let types = try!(call_decoder::abi_constructor_inputs(&abi).map_err(|e| errors::invalid_params("abi", e)));
+		let args = try!(call_decoder::encode_params(&types, &args).map_err(|e| errors::invalid_params("constructorArgs", e)));
+
+		let mut request: TRequest = options.into();
+		let mut data = code.to_vec();
+		data.extend(args);
+		request.to = None;
+		request.data = Some(data);
+		Ok(request)
+	})
+}
+
+/// Returns hash of the deployment transaction together with the address of the created contract.
+/// The address is computed from the nonce the transaction was actually signed with.
+fn deployed_contract<C, M>(client: &Weak<C>, miner: &Weak<M>, sender: &Address, hash: Value) -> Result<Value, Error>
+	where C: MiningBlockChainClient, M: MinerService {
+	let (client, miner) = (take_weak!(client), take_weak!(miner));
+	let transaction_hash: H256 = try!(serde_json::from_value::<RpcH256>(hash.clone()).map_err(|_| Error::internal_error())).into();
+	let nonce = match miner.transaction(client.chain_info().best_block_number, &transaction_hash) {
+		Some(t) => t.nonce,
+		None => try!(client.transaction(TransactionID::Hash(transaction_hash)).map(|t| t.nonce).ok_or_else(Error::internal_error)),
+	};
+
+	let mut result = BTreeMap::new();
+	result.insert("contractAddress".to_owned(), to_value(&RpcH160::from(contract_address(sender, &nonce))));
+	result.insert("transactionHash".to_owned(), hash);
+	Ok(Value::Object(result))
+}
+
 /// Implementation of functions that require signing when no trusted signer is used.
 pub struct EthSigningQueueClient<C, M> where C: MiningBlockChainClient, M: MinerService {
 	signer: Weak<SignerService>,
@@ -76,10 +114,10 @@ impl<C, M> EthSigningQueueClient<C, M> where C: MiningBlockChainClient, M: Miner
 		Ok(())
 	}
 
-	fn dispatch_sign(&self, params: Params) -> Result<DispatchResult, Error> {
-		from_params::<(RpcH160, RpcH256)>(params).and_then(|(address, msg)| {
+	fn dispatch_sign(&self, params: Params, origin: Origin) -> Result<DispatchResult, Error> {
+		from_params::<(RpcH160, RpcBytes)>(params).and_then(|(address, data)| {
 			let address: Address = address.into();
-			let msg: H256 = msg.into();
+			let msg = eth_data_hash(&data.0);
 
 			let accounts = take_weak!(self.accounts);
 			if accounts.is_unlocked(address) {
@@ -87,30 +125,57 @@ impl<C, M> EthSigningQueueClient<C, M> where C: MiningBlockChainClient, M: Miner
 			}
 
 			let signer = take_weak!(self.signer);
-			signer.add_request(ConfirmationPayload::Sign(address, msg))
+			signer.add_request(ConfirmationPayload::Sign(address, msg), origin.dapp())
 				.map(DispatchResult::Promise)
 				.map_err(|_| errors::request_rejected_limit())
 		})
 	}
 
-	fn dispatch_transaction(&self, params: Params) -> Result<DispatchResult, Error> {
+	fn dispatch_transaction(&self, params: Params, origin: Origin, sign_only: bool) -> Result<DispatchResult, Error> {
 		from_params::<(TransactionRequest, )>(params)
-			.and_then(|(request, )| {
-				let request: TRequest = request.into();
-				let accounts = take_weak!(self.accounts);
-				let (client, miner) = (take_weak!(self.client), take_weak!(self.miner));
-
-				if accounts.is_unlocked(request.from) {
-					let sender = request.from;
-					return sign_and_dispatch(&*client, &*miner, request, &*accounts, sender).map(DispatchResult::Value);
-				}
-
-				let signer = take_weak!(self.signer);
-				let request = fill_optional_fields(request, &*client, &*miner);
-				signer.add_request(ConfirmationPayload::Transaction(request))
-					.map(DispatchResult::Promise)
-					.map_err(|_| errors::request_rejected_limit())
+			.and_then(|(request, )| self.dispatch_request(request.into(), origin, sign_only))
+	}
+
+	fn dispatch_request(&self, request: TRequest, origin: Origin, sign_only: bool) -> Result<DispatchResult, Error> {
+		let accounts = take_weak!(self.accounts);
+		let (client, miner) = (take_weak!(self.client), take_weak!(self.miner));
+
+		if accounts.is_unlocked(request.from) {
+			let sender = request.from;
+			let result = match sign_only {
+				true => sign_transaction(&*client, &*miner, request, &*accounts, sender),
+				false => sign_and_dispatch(&*client, &*miner, request, &*accounts, sender),
+			};
+			return result.map(DispatchResult::Value);
+		}
+
+		let signer = take_weak!(self.signer);
+		let request = fill_optional_fields(request, &*client, &*miner);
+		let payload = match sign_only {
+			true => ConfirmationPayload::SignTransaction(request),
+			false => ConfirmationPayload::Transaction(request),
+		};
+		signer.add_request(payload, origin.dapp())
+			.map(DispatchResult::Promise)
+			.map_err(|_| errors::request_rejected_limit())
+	}
+
+	fn dispatch_deployment(&self, params: Params, origin: Origin) -> Result<(DispatchResult, Address), Error> {
+		let request = try!(deployment_request(params));
+		let sender = request.from;
+		self.dispatch_request(request, origin, false).map(|result| (result, sender))
+	}
+}
+
+fn dispatch_ready(res: Result<DispatchResult, Error>, ready: Ready) {
+	match res {
+		Ok(DispatchResult::Promise(promise)) => {
+			promise.wait_for_result(move |result| {
+				ready.ready(result.unwrap_or_else(|| Err(errors::request_rejected())))
 			})
+		},
+		Ok(DispatchResult::Value(v)) => ready.ready(Ok(v)),
+		Err(e) => ready.ready(Err(e)),
 	}
 }
 
@@ -119,21 +184,12 @@ impl<C, M> EthSigning for EthSigningQueueClient<C, M>
 {
 
 	fn sign(&self, params: Params, ready: Ready) {
-		let res = self.active().and_then(|_| self.dispatch_sign(params));
-		match res {
-			Ok(DispatchResult::Promise(promise)) => {
-				promise.wait_for_result(move |result| {
-					ready.ready(result.unwrap_or_else(|| Err(errors::request_rejected())))
-				})
-			},
-			Ok(DispatchResult::Value(v)) => ready.ready(Ok(v)),
-			Err(e) => ready.ready(Err(e)),
-		}
+		dispatch_ready(self.active().and_then(|_| self.dispatch_sign(params, origin::current())), ready)
 	}
 
 	fn post_sign(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
-		self.dispatch_sign(params).map(|result| match result {
+		self.dispatch_sign(params, origin::current()).map(|result| match result {
 			DispatchResult::Value(v) => v,
 			DispatchResult::Promise(promise) => {
 				let id = promise.id();
@@ -144,21 +200,29 @@ impl<C, M> EthSigning for EthSigningQueueClient<C, M>
 	}
 
 	fn send_transaction(&self, params: Params, ready: Ready) {
-		let res = self.active().and_then(|_| self.dispatch_transaction(params));
-		match res {
-			Ok(DispatchResult::Promise(promise)) => {
+		dispatch_ready(self.active().and_then(|_| self.dispatch_transaction(params, origin::current(), false)), ready)
+	}
+
+	fn sign_transaction(&self, params: Params, ready: Ready) {
+		dispatch_ready(self.active().and_then(|_| self.dispatch_transaction(params, origin::current(), true)), ready)
+	}
+
+	fn deploy_contract(&self, params: Params, ready: Ready) {
+		match self.active().and_then(|_| self.dispatch_deployment(params, origin::current())) {
+			Ok((DispatchResult::Promise(promise), sender)) => {
+				let (client, miner) = (self.client.clone(), self.miner.clone());
 				promise.wait_for_result(move |result| {
-					ready.ready(result.unwrap_or_else(|| Err(errors::request_rejected())))
+					ready.ready(result.unwrap_or_else(|| Err(errors::request_rejected())).and_then(|hash| deployed_contract(&client, &miner, &sender, hash)))
 				})
 			},
-			Ok(DispatchResult::Value(v)) => ready.ready(Ok(v)),
+			Ok((DispatchResult::Value(hash), sender)) => ready.ready(deployed_contract(&self.client, &self.miner, &sender, hash)),
 			Err(e) => ready.ready(Err(e)),
 		}
 	}
 
 	fn post_transaction(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
-		self.dispatch_transaction(params).map(|result| match result {
+		self.dispatch_transaction(params, origin::current(), false).map(|result| match result {
 			DispatchResult::Value(v) => v,
 			DispatchResult::Promise(promise) => {
 				let id = promise.id();
@@ -230,10 +294,10 @@ impl<C, M> EthSigning for EthSigningUnsafeClient<C, M> where
 
 	fn sign(&self, params: Params, ready: Ready) {
 		ready.ready(self.active()
-			.and_then(|_| from_params::<(RpcH160, RpcH256)>(params))
-			.and_then(|(address, msg)| {
+			.and_then(|_| from_params::<(RpcH160, RpcBytes)>(params))
+			.and_then(|(address, data)| {
 				let address: Address = address.into();
-				let msg: H256 = msg.into();
+				let msg = eth_data_hash(&data.0);
 				Ok(to_value(&take_weak!(self.accounts).sign(address, msg).ok().map_or_else(RpcH520::default, Into::into)))
 			}))
 	}
@@ -248,6 +312,26 @@ impl<C, M> EthSigning for EthSigningUnsafeClient<C, M> where
 			}))
 	}
 
+	fn sign_transaction(&self, params: Params, ready: Ready) {
+		ready.ready(self.active()
+			.and_then(|_| from_params::<(TransactionRequest, )>(params))
+			.and_then(|(request, )| {
+				let request: TRequest = request.into();
+				let sender = request.from;
+				sign_transaction(&*take_weak!(self.client), &*take_weak!(self.miner), request, &*take_weak!(self.accounts), sender)
+			}))
+	}
+
+	fn deploy_contract(&self, params: Params, ready: Ready) {
+		ready.ready(self.active()
+			.and_then(|_| {
+				let request = try!(deployment_request(params));
+				let sender = request.from;
+				let hash = try!(sign_and_dispatch(&*take_weak!(self.client), &*take_weak!(self.miner), request, &*take_weak!(self.accounts), sender));
+				deployed_contract(&self.client, &self.miner, &sender, hash)
+			}))
+	}
+
 	fn decrypt_message(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
 		from_params::<(RpcH160, RpcBytes)>(params).and_then(|(address, ciphertext)| {
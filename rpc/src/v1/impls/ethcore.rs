@@ -18,24 +18,42 @@
 use std::{fs, io};
 use std::sync::{mpsc, Arc, Weak};
 use std::str::FromStr;
+use std::collections::{BTreeMap, HashSet};
 
-use util::{RotatingLogger, Address, Mutex, sha3};
-use util::misc::version_data;
+use util::{RotatingLogger, Address, Mutex, Uint, H256 as EthH256, H520 as EthH520, U256 as EthU256, sha3};
+use util::misc::{version_data, sha, commit_date};
 
 use crypto::ecies;
 use fetch::{Client as FetchClient, Fetch};
-use ethkey::{Brain, Generator};
+use ethkey::{Brain, Generator, recover, public_to_address};
 use ethstore::random_phrase;
 use ethsync::{SyncProvider, ManageNetwork};
-use ethcore::miner::MinerService;
-use ethcore::client::{MiningBlockChainClient};
-use ethcore::ids::BlockID;
+use ethcore::miner::{MinerService, ExternalMinerService};
+use ethcore::client::{MiningBlockChainClient, IpcMode, BlockID, TransactionID, CallAnalytics};
+use ethcore::views::{BlockView, HeaderView};
+use ethcore::error::CallError;
+use ethcore::filter::Filter as EthcoreFilter;
+use ethcore::contract_address;
 
 use jsonrpc_core::Error;
 use v1::traits::Ethcore;
-use v1::types::{Bytes, U256, H160, H256, H512, Peers, Transaction, RpcSettings};
-use v1::helpers::{errors, SigningQueue, SignerService, NetworkSettings};
-use v1::helpers::auto_args::Ready;
+use v1::types::{Bytes, U256, H160, H256, H512, H520, BlockNumber, CallRequest, Filter, AccountDiff, Peers, Transaction, TransactionRequest, RpcSettings, BlockQueueStatus, NetStats, PeerPropagation, ChainWarning, ChainStatus, WorkerStatistics, SealingStatistics, PeerTraffic, VersionInfo, SnapshotStatus, Transfer, InclusionEstimate, DecodedCall, TokenBalance, WalletOperation, BlockEconomics, TransactionFee, UncleStatistics, NodeHealth, MemoryStats};
+use v1::helpers::{errors, SigningQueue, SignerService, NetworkSettings, SnapshotStatusProvider, Wallet, CallRequest as CRequest, TransactionRequest as TRequest};
+use v1::helpers::auto_args::{Ready, Trailing};
+use v1::helpers::call_decoder::{self, ContractRegistry};
+use v1::helpers::session;
+use v1::helpers::dispatch::{eth_data_hash, compose_transaction, fake_sign_call};
+
+/// Number of transactions returned by `ethcore_transactionsByAddress`.
+const ADDRESS_TRANSACTIONS_PAGE_SIZE: u64 = 50;
+/// Number of latest blocks sampled by `ethcore_estimateInclusion`.
+const INCLUSION_ESTIMATE_SAMPLE: u64 = 20;
+/// Number of latest blocks sampled by `ethcore_uncleStatistics`.
+const UNCLE_STATISTICS_WINDOW: u64 = 100;
+/// Maximal number of calls executed by a single `ethcore_call`.
+const MAX_CALLS: usize = 32;
+/// Gas available to each `balanceOf` call made by `ethcore_tokenBalances`.
+const TOKEN_BALANCE_GAS: u64 = 100_000;
 
 /// Ethcore implementation.
 pub struct EthcoreClient<C, M, S: ?Sized, F=FetchClient> where
@@ -48,9 +66,12 @@ pub struct EthcoreClient<C, M, S: ?Sized, F=FetchClient> where
 	miner: Weak<M>,
 	sync: Weak<S>,
 	net: Weak<ManageNetwork>,
+	external_miner: Arc<ExternalMinerService>,
 	logger: Arc<RotatingLogger>,
 	settings: Arc<NetworkSettings>,
 	signer: Option<Arc<SignerService>>,
+	snapshot: Weak<SnapshotStatusProvider>,
+	registry: Arc<ContractRegistry>,
 	fetch: Mutex<F>
 }
 
@@ -66,9 +87,12 @@ impl<C, M, S: ?Sized> EthcoreClient<C, M, S> where
 		net: &Arc<ManageNetwork>,
 		logger: Arc<RotatingLogger>,
 		settings: Arc<NetworkSettings>,
-		signer: Option<Arc<SignerService>>
+		external_miner: Arc<ExternalMinerService>,
+		signer: Option<Arc<SignerService>>,
+		snapshot: &Arc<SnapshotStatusProvider>,
+		registry: Arc<ContractRegistry>,
 	) -> Self {
-		Self::with_fetch(client, miner, sync, net, logger, settings, signer)
+		Self::with_fetch(client, miner, sync, net, logger, settings, external_miner, signer, snapshot, registry)
 	}
 }
 
@@ -86,16 +110,22 @@ impl<C, M, S: ?Sized, F> EthcoreClient<C, M, S, F> where
 		net: &Arc<ManageNetwork>,
 		logger: Arc<RotatingLogger>,
 		settings: Arc<NetworkSettings>,
-		signer: Option<Arc<SignerService>>
+		external_miner: Arc<ExternalMinerService>,
+		signer: Option<Arc<SignerService>>,
+		snapshot: &Arc<SnapshotStatusProvider>,
+		registry: Arc<ContractRegistry>,
 		) -> Self {
 		EthcoreClient {
 			client: Arc::downgrade(client),
 			miner: Arc::downgrade(miner),
 			sync: Arc::downgrade(sync),
 			net: Arc::downgrade(net),
+			external_miner: external_miner,
 			logger: logger,
 			settings: settings,
 			signer: signer,
+			snapshot: Arc::downgrade(snapshot),
+			registry: registry,
 			fetch: Mutex::new(F::default()),
 		}
 	}
@@ -165,13 +195,15 @@ impl<C, M, S: ?Sized, F> Ethcore for EthcoreClient<C, M, S, F> where
 	fn net_peers(&self) -> Result<Peers, Error> {
 		try!(self.active());
 
-		let sync_status = take_weak!(self.sync).status();
+		let sync = take_weak!(self.sync);
+		let sync_status = sync.status();
 		let net_config = take_weak!(self.net).network_config();
 
 		Ok(Peers {
 			active: sync_status.num_active_peers,
 			connected: sync_status.num_peers,
 			max: sync_status.current_max_peers(net_config.min_peers, net_config.max_peers),
+			peers: sync.peers().into_iter().map(Into::into).collect(),
 		})
 	}
 
@@ -187,6 +219,18 @@ impl<C, M, S: ?Sized, F> Ethcore for EthcoreClient<C, M, S, F> where
 		Ok(self.settings.name.clone())
 	}
 
+	fn version_info(&self) -> Result<VersionInfo, Error> {
+		try!(self.active());
+
+		Ok(VersionInfo {
+			version: env!("CARGO_PKG_VERSION").into(),
+			hash: sha().into(),
+			date: commit_date().into(),
+			features: self.settings.features.clone(),
+			forks: take_weak!(self.client).forks(),
+		})
+	}
+
 	fn registry_address(&self) -> Result<Option<H160>, Error> {
 		try!(self.active());
 
@@ -223,6 +267,64 @@ impl<C, M, S: ?Sized, F> Ethcore for EthcoreClient<C, M, S, F> where
 		}
 	}
 
+	fn estimate_inclusion(&self, gas_price: U256) -> Result<Option<InclusionEstimate>, Error> {
+		try!(self.active());
+
+		let client = take_weak!(self.client);
+		let miner = take_weak!(self.miner);
+		let gas_price: EthU256 = gas_price.into();
+		if gas_price < miner.minimal_gas_price() {
+			return Ok(None);
+		}
+
+		// queued transactions paying at least as much are mined first
+		let queued_ahead = miner.all_transactions().into_iter()
+			.filter(|t| t.gas_price >= gas_price)
+			.fold(EthU256::zero(), |gas, t| gas + t.gas);
+
+		// gas in recent blocks taken by transactions outbidding ours, approximated
+		// as the share of such transactions in the block's gas used
+		let best_block = client.chain_info().best_block_number;
+		let first_block = best_block.saturating_sub(INCLUSION_ESTIMATE_SAMPLE - 1);
+		let mut gas_limit = EthU256::zero();
+		let mut outbidding = EthU256::zero();
+		let mut timestamps = Vec::new();
+		for number in first_block..(best_block + 1) {
+			let bytes = match client.block(BlockID::Number(number)) {
+				Some(bytes) => bytes,
+				None => continue,
+			};
+			let block = BlockView::new(&bytes);
+			let header = block.header_view();
+			let transactions = block.transactions();
+			let above = transactions.iter().filter(|t| t.gas_price >= gas_price).count();
+			if !transactions.is_empty() {
+				outbidding = outbidding + header.gas_used() * EthU256::from(above) / EthU256::from(transactions.len());
+			}
+			gas_limit = gas_limit + header.gas_limit();
+			timestamps.push(header.timestamp());
+		}
+
+		if timestamps.is_empty() || outbidding >= gas_limit {
+			return Ok(None);
+		}
+		let room = (gas_limit - outbidding) / EthU256::from(timestamps.len());
+		if room.is_zero() {
+			return Ok(None);
+		}
+		let blocks = queued_ahead / room + EthU256::one();
+
+		let block_time = match timestamps.len() {
+			0 | 1 => None,
+			n => Some((timestamps[n - 1].saturating_sub(timestamps[0])) / (n as u64 - 1)),
+		};
+
+		Ok(Some(InclusionEstimate {
+			blocks: blocks.into(),
+			seconds: block_time.map(|time| (blocks * EthU256::from(time)).into()),
+		}))
+	}
+
 	fn unsigned_transactions_count(&self) -> Result<usize, Error> {
 		try!(self.active());
 
@@ -244,19 +346,49 @@ impl<C, M, S: ?Sized, F> Ethcore for EthcoreClient<C, M, S, F> where
 		Ok(Brain::new(phrase).generate().unwrap().address().into())
 	}
 
-	fn list_accounts(&self) -> Result<Option<Vec<H160>>, Error> {
+	fn compute_contract_address(&self, sender: H160, nonce: U256) -> Result<H160, Error> {
+		try!(self.active());
+
+		Ok(contract_address(&sender.into(), &nonce.into()).into())
+	}
+
+	fn list_accounts(&self, block: Trailing<BlockNumber>) -> Result<Option<Vec<H160>>, Error> {
 		try!(self.active());
 
 		Ok(take_weak!(self.client)
-			.list_accounts(BlockID::Latest)
+			.list_accounts(block.0.into())
 			.map(|a| a.into_iter().map(Into::into).collect()))
 	}
 
-	fn list_storage_keys(&self, _address: H160) -> Result<Option<Vec<H256>>, Error> {
+	fn list_storage_keys(&self, address: H160, block: Trailing<BlockNumber>) -> Result<Option<Vec<H256>>, Error> {
 		try!(self.active());
 
-		// TODO: implement this
-		Ok(None)
+		Ok(take_weak!(self.client)
+			.list_storage(block.0.into(), &address.into())
+			.map(|s| s.into_iter().map(Into::into).collect()))
+	}
+
+	fn state_diff(&self, address: H160, from: BlockNumber, to: BlockNumber) -> Result<Option<AccountDiff>, Error> {
+		try!(self.active());
+
+		take_weak!(self.client).account_diff(&address.into(), from.into(), to.into())
+			.map(|diff| diff.map(Into::into))
+			.map_err(errors::from_call_error)
+	}
+
+	fn transactions_by_address(&self, address: H160, page: u64) -> Result<Option<Vec<Transaction>>, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.client)
+			.address_transactions(&address.into(), page.saturating_mul(ADDRESS_TRANSACTIONS_PAGE_SIZE), ADDRESS_TRANSACTIONS_PAGE_SIZE)
+			.map(|txs| txs.into_iter().map(Into::into).collect()))
+	}
+
+	fn blocks_with_logs(&self, filter: Filter) -> Result<Vec<U256>, Error> {
+		try!(self.active());
+
+		let filter: EthcoreFilter = filter.into();
+		Ok(take_weak!(self.client).blocks_with_filter(&filter).into_iter().map(Into::into).collect())
 	}
 
 	fn encrypt_message(&self, key: H512, phrase: Bytes) -> Result<Bytes, Error> {
@@ -267,12 +399,392 @@ impl<C, M, S: ?Sized, F> Ethcore for EthcoreClient<C, M, S, F> where
 			.map(Into::into)
 	}
 
+	fn ec_recover(&self, data: Bytes, signature: H520) -> Result<H160, Error> {
+		try!(self.active());
+
+		let hash = eth_data_hash(&data.0);
+		let signature: EthH520 = signature.into();
+		recover(&signature.into(), &hash)
+			.map(|public| public_to_address(&public).into())
+			.map_err(|e| errors::invalid_params("signature", e))
+	}
+
+	fn compose_transaction(&self, request: TransactionRequest) -> Result<TransactionRequest, Error> {
+		try!(self.active());
+
+		let (client, miner) = (take_weak!(self.client), take_weak!(self.miner));
+		Ok(compose_transaction(&*client, &*miner, request.into()).into())
+	}
+
+	fn raw_block_by_number(&self, number: BlockNumber) -> Result<Option<Bytes>, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.client).block(number.into()).map(Into::into))
+	}
+
+	fn raw_transaction(&self, hash: H256) -> Result<Option<Bytes>, Error> {
+		try!(self.active());
+
+		let hash = hash.into();
+		let (client, miner) = (take_weak!(self.client), take_weak!(self.miner));
+		if let Some(t) = client.transaction(TransactionID::Hash(hash)) {
+			return Ok(Some(::rlp::encode(&t.signed).to_vec().into()));
+		}
+		let queued = miner.transaction(client.chain_info().best_block_number, &hash)
+			.or_else(|| miner.queued_transaction(&hash).map(|(t, _)| t));
+		Ok(queued.map(|t| ::rlp::encode(&t).to_vec().into()))
+	}
+
+	fn block_economics(&self, number: BlockNumber) -> Result<Option<BlockEconomics>, Error> {
+		try!(self.active());
+
+		let client = take_weak!(self.client);
+		let bytes = match client.block(number.into()) {
+			Some(bytes) => bytes,
+			None => return Ok(None),
+		};
+		let block = BlockView::new(&bytes);
+		let header = block.header_view();
+		let hash = header.hash();
+		let rewards = client.block_rewards(BlockID::Hash(hash)).unwrap_or_else(Default::default);
+
+		let mut fees = EthU256::zero();
+		let mut transactions = Vec::new();
+		for (index, t) in block.transactions().into_iter().enumerate() {
+			let receipt = try!(client.transaction_receipt(TransactionID::Location(BlockID::Hash(hash), index))
+				.ok_or_else(|| errors::internal("Missing transaction receipt", t.hash())));
+			let fee = receipt.gas_used * t.gas_price;
+			fees = fees + fee;
+			transactions.push(TransactionFee {
+				hash: t.hash().into(),
+				gas_used: receipt.gas_used.into(),
+				gas_price: t.gas_price.into(),
+				fee: fee.into(),
+			});
+		}
+
+		let (gas_used, gas_limit) = (header.gas_used(), header.gas_limit());
+		let gas_utilization = match gas_limit.is_zero() {
+			true => 0.0,
+			false => gas_used.low_u64() as f64 * 100.0 / gas_limit.low_u64() as f64,
+		};
+
+		Ok(Some(BlockEconomics {
+			number: header.number().into(),
+			hash: hash.into(),
+			gas_used: gas_used.into(),
+			gas_limit: gas_limit.into(),
+			gas_utilization: gas_utilization,
+			fees: fees.into(),
+			block_reward: rewards.author.into(),
+			miner_reward: (rewards.author + fees).into(),
+			uncle_rewards: rewards.uncles.into_iter().map(Into::into).collect(),
+			transactions: transactions,
+		}))
+	}
+
 	fn pending_transactions(&self) -> Result<Vec<Transaction>, Error> {
 		try!(self.active());
 
 		Ok(take_weak!(self.miner).all_transactions().into_iter().map(Into::into).collect::<Vec<_>>())
 	}
 
+	fn next_nonce(&self, address: H160) -> Result<U256, Error> {
+		try!(self.active());
+
+		let address: Address = address.into();
+		let miner = take_weak!(self.miner);
+		let client = take_weak!(self.client);
+
+		Ok(miner.next_nonce(&*client, &address).into())
+	}
+
+	fn node_status(&self) -> Result<String, Error> {
+		// intentionally doesn't call `active()` - querying status shouldn't wake the node up.
+		match take_weak!(self.client).is_hibernating() {
+			true => Ok("hibernating".into()),
+			false => Ok("awake".into()),
+		}
+	}
+
+	fn block_queue_status(&self) -> Result<BlockQueueStatus, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.client).queue_info().into())
+	}
+
+	fn node_table(&self) -> Result<Vec<String>, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.net).node_table())
+	}
+
+	fn net_stats(&self) -> Result<NetStats, Error> {
+		try!(self.active());
+
+		let sync = take_weak!(self.sync);
+		let mut stats: NetStats = take_weak!(self.net).traffic_stats().into();
+		stats.transaction_bytes_saved = sync.status().transaction_bytes_saved;
+		stats.block_propagation = sync.peers().into_iter()
+			.filter_map(|peer| peer.block_propagation_delay.map(|delay| PeerPropagation {
+				id: peer.id,
+				announcements: peer.block_announcements,
+				average_delay: delay,
+			}))
+			.collect();
+		stats.block_propagation.sort_by_key(|peer| peer.average_delay);
+		Ok(stats)
+	}
+
+	fn net_stats_peers(&self, offset: u64, limit: u64) -> Result<Vec<PeerTraffic>, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.net).traffic_stats().peers.into_iter()
+			.skip(offset as usize)
+			.take(limit as usize)
+			.map(Into::into)
+			.collect())
+	}
+
+	fn worker_statistics(&self) -> Result<BTreeMap<String, WorkerStatistics>, Error> {
+		try!(self.active());
+
+		Ok(self.external_miner.worker_statistics().into_iter()
+			.map(|(id, stats)| (format!("0x{}", id.hex()), stats.into()))
+			.collect())
+	}
+
+	fn node_health(&self) -> Result<NodeHealth, Error> {
+		try!(self.active());
+
+		let status = take_weak!(self.sync).status();
+		let drift = take_weak!(self.miner).clock_drift();
+		Ok(NodeHealth {
+			peers: status.num_peers,
+			syncing: status.is_major_syncing(),
+			ntp_clock_drift: drift.ntp,
+			peer_clock_drift: drift.peers,
+		})
+	}
+
+	fn memory_stats(&self) -> Result<MemoryStats, Error> {
+		try!(self.active());
+
+		let usage = take_weak!(self.client).memory_usage();
+		Ok(MemoryStats {
+			blockchain: usage.blockchain.into(),
+			state: usage.state.into(),
+			block_queue: usage.block_queue.into(),
+			transaction_queue: take_weak!(self.miner).transaction_queue_memory().into(),
+		})
+	}
+
+	fn uncle_statistics(&self) -> Result<UncleStatistics, Error> {
+		try!(self.active());
+
+		let client = take_weak!(self.client);
+		let miner = take_weak!(self.miner);
+		let best_block = client.chain_info().best_block_number;
+		let first_block = best_block.saturating_sub(UNCLE_STATISTICS_WINDOW - 1);
+
+		let mut blocks = 0u64;
+		let mut distance = 0u64;
+		let mut uncles = HashSet::new();
+		for number in first_block..(best_block + 1) {
+			let bytes = match client.block(BlockID::Number(number)) {
+				Some(bytes) => bytes,
+				None => continue,
+			};
+			blocks += 1;
+			for uncle in BlockView::new(&bytes).uncles() {
+				distance += number.saturating_sub(uncle.number());
+				uncles.insert(uncle.hash());
+			}
+		}
+
+		// own blocks which are known but no longer canonical
+		let mut sealed_blocks = 0u64;
+		let mut orphans = Vec::new();
+		for hash in miner.sealed_blocks() {
+			let number = match client.block_header(BlockID::Hash(hash.clone())) {
+				Some(header) => HeaderView::new(&header).number(),
+				None => continue,
+			};
+			if number < first_block || number > best_block {
+				continue;
+			}
+			sealed_blocks += 1;
+			if client.block_hash(BlockID::Number(number)).as_ref() != Some(&hash) {
+				orphans.push(hash);
+			}
+		}
+		let orphans_included = orphans.iter().filter(|hash| uncles.contains(*hash)).count();
+
+		Ok(UncleStatistics {
+			from_block: first_block.into(),
+			to_block: best_block.into(),
+			uncles: (uncles.len() as u64).into(),
+			uncle_rate: match blocks {
+				0 => 0.0,
+				blocks => uncles.len() as f64 / blocks as f64,
+			},
+			average_uncle_distance: match uncles.len() {
+				0 => 0.0,
+				count => distance as f64 / count as f64,
+			},
+			sealed_blocks: sealed_blocks.into(),
+			orphaned_blocks: (orphans.len() as u64).into(),
+			orphans_included: (orphans_included as u64).into(),
+		})
+	}
+
+	fn sealing_statistics(&self) -> Result<SealingStatistics, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.miner).sealing_statistics().into())
+	}
+
+	fn chain_status(&self) -> Result<ChainStatus, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.sync).chain_status().into())
+	}
+
+	fn chain_warnings(&self) -> Result<Vec<ChainWarning>, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.client).chain_warnings().into_iter().map(Into::into).collect())
+	}
+
+	fn mode(&self) -> Result<String, Error> {
+		// intentionally doesn't call `active()` - querying mode shouldn't wake the node up.
+		Ok(match take_weak!(self.client).mode() {
+			IpcMode::Off => "offline",
+			IpcMode::Dark(..) => "dark",
+			IpcMode::Passive(..) => "passive",
+			IpcMode::Active => "active",
+		}.into())
+	}
+
+	fn snapshot_status(&self) -> Result<SnapshotStatus, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.snapshot).creation_status().into())
+	}
+
+	fn call(&self, requests: Vec<CallRequest>, num: Trailing<BlockNumber>) -> Result<Vec<Bytes>, Error> {
+		try!(self.active());
+		if requests.len() > MAX_CALLS {
+			return Err(errors::invalid_params("requests", format!("At most {} calls can be executed at once.", MAX_CALLS)));
+		}
+
+		let (client, miner) = (take_weak!(self.client), take_weak!(self.miner));
+		let signed: Vec<_> = requests.into_iter()
+			.map(|request| fake_sign_call(&*client, &*miner, CallRequest::into(request)))
+			.collect();
+
+		client.call_many(&signed, num.0.into(), Default::default())
+			.map(|executed| executed.into_iter().map(|e| Bytes::new(e.output)).collect())
+			.map_err(errors::from_call_error)
+	}
+
+	fn transaction_transfers(&self, hash: H256) -> Result<Option<Vec<Transfer>>, Error> {
+		try!(self.active());
+
+		let analytics = CallAnalytics { transfer_tracing: true, ..Default::default() };
+		match take_weak!(self.client).replay(TransactionID::Hash(hash.into()), analytics) {
+			Ok(e) => Ok(Some(e.trace.into_iter().map(Into::into).collect())),
+			Err(CallError::TransactionNotFound) => Ok(None),
+			Err(e) => Err(errors::from_call_error(e)),
+		}
+	}
+
+	fn decode_call_data(&self, address: H160, data: Bytes) -> Result<Option<DecodedCall>, Error> {
+		try!(self.active());
+
+		let address: Address = address.into();
+		match call_decoder::decode_call(&address, &data.to_vec(), Some(&*self.registry)) {
+			Some(Ok(call)) => Ok(Some(call)),
+			Some(Err(e)) => Err(errors::invalid_params("data", e)),
+			None => Ok(None),
+		}
+	}
+
+	fn token_balances(&self, address: H160) -> Result<Vec<TokenBalance>, Error> {
+		try!(self.active());
+
+		let owner: Address = address.into();
+		let mut data = call_decoder::method_selector("balanceOf(address)").to_vec();
+		data.extend_from_slice(&EthH256::from(owner));
+
+		let (client, miner) = (take_weak!(self.client), take_weak!(self.miner));
+		let tokens = self.registry.tokens();
+		let calls: Vec<_> = tokens.iter()
+			.map(|&(ref token, _)| fake_sign_call(&*client, &*miner, CRequest {
+				to: Some(token.clone()),
+				gas: Some(TOKEN_BALANCE_GAS.into()),
+				data: Some(data.clone()),
+				..Default::default()
+			}))
+			.collect();
+
+		let executed = try!(client.call_many(&calls, BlockID::Latest, Default::default())
+			.map_err(errors::from_call_error));
+
+		Ok(tokens.into_iter().zip(executed)
+			// skip tokens not implementing `balanceOf`
+			.filter(|&(_, ref executed)| !executed.reverted && executed.output.len() >= 32)
+			.map(|((token, info), executed)| TokenBalance {
+				token: token.into(),
+				symbol: info.symbol,
+				decimals: call_decoder::decimals(info.base) as u64,
+				balance: EthU256::from(&executed.output[..32]).into(),
+			})
+			.collect())
+	}
+
+	fn wallet_pending_operations(&self, address: H160) -> Result<Vec<WalletOperation>, Error> {
+		try!(self.active());
+
+		let client = take_weak!(self.client);
+		Wallet::new(&*client, &*self.registry, address.into())
+			.and_then(|wallet| wallet.pending_operations())
+			.map(|operations| operations.into_iter().map(Into::into).collect())
+			.map_err(errors::from_wallet_error)
+	}
+
+	fn confirm_wallet_operation(&self, address: H160, owner: H160, operation: H256) -> Result<TransactionRequest, Error> {
+		try!(self.active());
+
+		let (client, miner) = (take_weak!(self.client), take_weak!(self.miner));
+		let (address, owner, operation) = (address.into(), owner.into(), operation.into());
+		try!(Wallet::new(&*client, &*self.registry, address)
+			.and_then(|wallet| wallet.can_confirm(&owner, &operation))
+			.map_err(errors::from_wallet_error));
+
+		let mut data = call_decoder::method_selector("confirm(bytes32)").to_vec();
+		data.extend_from_slice(&operation);
+		let request = TRequest {
+			from: owner,
+			to: Some(address),
+			data: Some(data),
+			..Default::default()
+		};
+		Ok(compose_transaction(&*client, &*miner, request).into())
+	}
+
+	fn watch_address(&self, address: H160) -> Result<bool, Error> {
+		try!(self.active());
+
+		session::watch_address(address.into()).map_err(errors::watch_error)
+	}
+
+	fn unwatch_address(&self, address: H160) -> Result<bool, Error> {
+		try!(self.active());
+
+		Ok(session::unwatch_address(&address.into()))
+	}
+
 	fn hash_content(&self, ready: Ready<H256>, url: String) {
 		let res = self.active();
 
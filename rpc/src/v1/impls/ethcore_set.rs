@@ -17,12 +17,20 @@
 /// Ethcore-specific rpc interface for operations altering the settings.
 use std::sync::{Arc, Weak};
 use jsonrpc_core::*;
+use rlp::{UntrustedRlp, View};
+use ethcore::block::Block;
+use ethcore::account_provider::AccountProvider;
 use ethcore::miner::MinerService;
-use ethcore::client::MiningBlockChainClient;
+use ethcore::client::{MiningBlockChainClient, IpcMode};
 use ethsync::ManageNetwork;
-use v1::helpers::errors;
+use v1::helpers::{errors, UpdateService};
 use v1::traits::EthcoreSet;
-use v1::types::{Bytes, H160, U256};
+use v1::types::{Bytes, H160, H256, U256, ReleaseInfo};
+
+/// Inactivity timeout used when switching to `passive` or `dark` mode from a mode without one.
+const DEFAULT_MODE_TIMEOUT: u64 = 300;
+/// Wake up alarm used when switching to `passive` mode from a mode without one.
+const DEFAULT_MODE_ALARM: u64 = 3600;
 
 /// Ethcore-specific rpc interface for operations altering the settings.
 pub struct EthcoreSetClient<C, M> where
@@ -32,17 +40,21 @@ pub struct EthcoreSetClient<C, M> where
 	client: Weak<C>,
 	miner: Weak<M>,
 	net: Weak<ManageNetwork>,
+	updater: Weak<UpdateService>,
+	accounts: Weak<AccountProvider>,
 }
 
 impl<C, M> EthcoreSetClient<C, M> where
 	C: MiningBlockChainClient,
 	M: MinerService {
 	/// Creates new `EthcoreSetClient`.
-	pub fn new(client: &Arc<C>, miner: &Arc<M>, net: &Arc<ManageNetwork>) -> Self {
+	pub fn new(client: &Arc<C>, miner: &Arc<M>, net: &Arc<ManageNetwork>, updater: &Arc<UpdateService>, accounts: &Arc<AccountProvider>) -> Self {
 		EthcoreSetClient {
 			client: Arc::downgrade(client),
 			miner: Arc::downgrade(miner),
 			net: Arc::downgrade(net),
+			updater: Arc::downgrade(updater),
+			accounts: Arc::downgrade(accounts),
 		}
 	}
 
@@ -81,7 +93,12 @@ impl<C, M> EthcoreSet for EthcoreSetClient<C, M> where
 	fn set_extra_data(&self, extra_data: Bytes) -> Result<bool, Error> {
 		try!(self.active());
 
-		take_weak!(self.miner).set_extra_data(extra_data.to_vec());
+		let miner = take_weak!(self.miner);
+		let extra_data = extra_data.to_vec();
+		if extra_data.len() > miner.maximum_extra_data_size() {
+			return Err(errors::invalid_params("extra_data", format!("Extra data must be at most {} bytes", miner.maximum_extra_data_size())));
+		}
+		miner.set_extra_data(extra_data);
 		Ok(true)
 	}
 
@@ -147,4 +164,113 @@ impl<C, M> EthcoreSet for EthcoreSetClient<C, M> where
 		take_weak!(self.net).stop_network();
 		Ok(true)
 	}
+
+	fn set_mode(&self, mode: String) -> Result<bool, Error> {
+		// intentionally doesn't call `active()` - switching to `offline` shouldn't wake the node up.
+		let client = take_weak!(self.client);
+		let (timeout, alarm) = match client.mode() {
+			IpcMode::Passive(timeout, alarm) => (timeout, alarm),
+			IpcMode::Dark(timeout) => (timeout, DEFAULT_MODE_ALARM),
+			_ => (DEFAULT_MODE_TIMEOUT, DEFAULT_MODE_ALARM),
+		};
+		let mode = match mode.as_str() {
+			"offline" => IpcMode::Off,
+			"dark" => IpcMode::Dark(timeout),
+			"passive" => IpcMode::Passive(timeout, alarm),
+			"active" => IpcMode::Active,
+			_ => return Err(errors::invalid_params("mode", mode.clone())),
+		};
+		client.set_mode(mode);
+		Ok(true)
+	}
+
+	fn release_quarantined_block(&self, hash: H256) -> Result<bool, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.client).release_quarantined_block(hash.into()))
+	}
+
+	fn upgrade_ready(&self) -> Result<Option<ReleaseInfo>, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.updater).upgrade_ready().map(Into::into))
+	}
+
+	fn execute_upgrade(&self) -> Result<bool, Error> {
+		try!(self.active());
+
+		Ok(take_weak!(self.updater).execute_upgrade())
+	}
+
+	fn backup_database(&self, path: String) -> Result<bool, Error> {
+		try!(self.active());
+
+		try!(take_weak!(self.client).backup_database(path).map_err(|e| errors::internal("Database backup failed", e)));
+		Ok(true)
+	}
+
+	fn submit_block(&self, block: Bytes) -> Result<H256, Error> {
+		try!(self.active());
+
+		let block = block.to_vec();
+		// the queue expects well-formed blocks.
+		try!(UntrustedRlp::new(&block).as_val::<Block>().map_err(|e| errors::invalid_params("Block is not valid RLP", e)));
+		take_weak!(self.client).import_block(block).map(Into::into).map_err(errors::from_block_import_error)
+	}
+
+	fn dev_mine_blocks(&self, count: u64) -> Result<U256, Error> {
+		try!(self.active());
+
+		take_weak!(self.client).dev_mine_blocks(count).map(Into::into).map_err(errors::dev_chain)
+	}
+
+	fn dev_set_balance(&self, address: H160, balance: U256) -> Result<bool, Error> {
+		try!(self.active());
+
+		try!(take_weak!(self.client).dev_set_balance(address.into(), balance.into()).map_err(errors::dev_chain));
+		Ok(true)
+	}
+
+	fn dev_reset(&self) -> Result<bool, Error> {
+		try!(self.active());
+
+		try!(take_weak!(self.client).dev_reset().map_err(errors::dev_chain));
+		Ok(true)
+	}
+
+	fn set_dapp_addresses(&self, dapp: String, addresses: Vec<H160>) -> Result<bool, Error> {
+		try!(self.active());
+
+		let store = take_weak!(self.accounts);
+		try!(store.set_dapps_addresses(dapp, addresses.into_iter().map(Into::into).collect())
+			.map_err(|e| errors::account("Could not set dapp addresses.", e)));
+		Ok(true)
+	}
+
+	fn dapp_addresses(&self, dapp: String) -> Result<Vec<H160>, Error> {
+		try!(self.active());
+
+		let store = take_weak!(self.accounts);
+		let addresses = try!(store.dapps_addresses(&dapp)
+			.map_err(|e| errors::account("Could not fetch dapp addresses.", e)));
+		Ok(addresses.into_iter().map(Into::into).collect())
+	}
+
+	fn set_dapp_default_address(&self, dapp: String, address: H160) -> Result<bool, Error> {
+		try!(self.active());
+
+		let store = take_weak!(self.accounts);
+		try!(store.set_dapps_default_address(dapp, address.into())
+			.map_err(|e| errors::account("Could not set dapp default address.", e)));
+		Ok(true)
+	}
+
+	fn dapp_default_address(&self, dapp: String) -> Result<Option<H160>, Error> {
+		try!(self.active());
+
+		let store = take_weak!(self.accounts);
+		let address = try!(store.dapps_default_address(&dapp)
+			.map_err(|e| errors::account("Could not fetch dapp default address.", e)));
+		Ok(address.map(Into::into))
+	}
 }
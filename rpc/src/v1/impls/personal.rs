@@ -16,16 +16,19 @@
 
 //! Account management (personal) rpc implementation
 use std::sync::{Arc, Weak};
-use std::collections::{BTreeMap};
-use util::{Address};
+use std::collections::BTreeMap;
+use util::{Address, U256, Uint, Hashable};
 use jsonrpc_core::*;
+use serde_json;
 use ethkey::{Brain, Generator};
 use v1::traits::Personal;
-use v1::types::{H160 as RpcH160, TransactionRequest};
-use v1::helpers::{errors, TransactionRequest as TRequest};
+use v1::types::{H160 as RpcH160, H256 as RpcH256, H520 as RpcH520, U256 as RpcU256, Bytes as RpcBytes, TransactionRequest, ScheduledTransaction};
+use v1::helpers::{errors, origin, Scheduler, TransactionRequest as TRequest};
 use v1::helpers::params::expect_no_params;
 use v1::helpers::dispatch::unlock_sign_and_dispatch;
+use ethstore::Error as SSError;
 use ethcore::account_provider::AccountProvider;
+use ethjson::misc::AccountMeta;
 use ethcore::client::MiningBlockChainClient;
 use ethcore::miner::MinerService;
 
@@ -36,17 +39,19 @@ pub struct PersonalClient<C, M> where C: MiningBlockChainClient, M: MinerService
 	miner: Weak<M>,
 	signer_port: Option<u16>,
 	allow_perm_unlock: bool,
+	scheduler: Option<Weak<Scheduler<C, M>>>,
 }
 
 impl<C, M> PersonalClient<C, M> where C: MiningBlockChainClient, M: MinerService {
 	/// Creates new PersonalClient
-	pub fn new(store: &Arc<AccountProvider>, client: &Arc<C>, miner: &Arc<M>, signer_port: Option<u16>, allow_perm_unlock: bool) -> Self {
+	pub fn new(store: &Arc<AccountProvider>, client: &Arc<C>, miner: &Arc<M>, signer_port: Option<u16>, allow_perm_unlock: bool, scheduler: Option<&Arc<Scheduler<C, M>>>) -> Self {
 		PersonalClient {
 			accounts: Arc::downgrade(store),
 			client: Arc::downgrade(client),
 			miner: Arc::downgrade(miner),
 			signer_port: signer_port,
 			allow_perm_unlock: allow_perm_unlock,
+			scheduler: scheduler.map(Arc::downgrade),
 		}
 	}
 
@@ -55,6 +60,25 @@ impl<C, M> PersonalClient<C, M> where C: MiningBlockChainClient, M: MinerService
 		take_weak!(self.client).keep_alive();
 		Ok(())
 	}
+
+	/// Info of accounts and address book entries; dapps only get the accounts exposed to them.
+	fn visible_accounts_info(&self, store: &AccountProvider) -> Result<BTreeMap<Address, AccountMeta>, Error> {
+		let info = try!(store.accounts_info().map_err(|e| errors::account("Could not fetch account info.", e)));
+		let origin = origin::current();
+		if origin.is_restricted() {
+			let visible = try!(origin.accounts(store).map_err(|e| errors::account("Could not fetch accounts.", e)));
+			return Ok(info.into_iter().filter(|&(ref address, _)| visible.contains(address)).collect());
+		}
+		let other = store.addresses_info().expect("addresses_info always returns Ok; qed");
+		Ok(info.into_iter().chain(other.into_iter()).collect())
+	}
+
+	fn scheduler(&self) -> Result<Arc<Scheduler<C, M>>, Error> {
+		match self.scheduler {
+			Some(ref scheduler) => Ok(take_weak!(scheduler)),
+			None => Err(errors::scheduler_disabled()),
+		}
+	}
 }
 
 impl<C: 'static, M: 'static> Personal for PersonalClient<C, M> where C: MiningBlockChainClient, M: MinerService {
@@ -73,7 +97,7 @@ impl<C: 'static, M: 'static> Personal for PersonalClient<C, M> where C: MiningBl
 		try!(expect_no_params(params));
 
 		let store = take_weak!(self.accounts);
-		let accounts = try!(store.accounts().map_err(|e| errors::internal("Could not fetch accounts.", e)));
+		let accounts = try!(origin::current().accounts(&*store).map_err(|e| errors::internal("Could not fetch accounts.", e)));
 		Ok(to_value(&accounts.into_iter().map(Into::into).collect::<Vec<RpcH160>>()))
 	}
 
@@ -103,6 +127,19 @@ impl<C: 'static, M: 'static> Personal for PersonalClient<C, M> where C: MiningBl
 		)
 	}
 
+	fn new_account_from_secret(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		from_params::<(RpcH256, String, )>(params).and_then(
+			|(secret, pass, )| {
+				let store = take_weak!(self.accounts);
+				match store.insert_account(secret.into(), &pass) {
+					Ok(address) => Ok(to_value(&RpcH160::from(address))),
+					Err(e) => Err(errors::account("Could not create account.", e)),
+				}
+			}
+		)
+	}
+
 	fn new_account_from_wallet(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
 		from_params::<(String, String, )>(params).and_then(
@@ -135,6 +172,38 @@ impl<C: 'static, M: 'static> Personal for PersonalClient<C, M> where C: MiningBl
 			})
 	}
 
+	fn test_password(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		from_params::<(RpcH160, String)>(params).and_then(|(account, password)| {
+			let account: Address = account.into();
+			let store = take_weak!(self.accounts);
+			store.test_password(&account, &password)
+				.map(|valid| Value::Bool(valid))
+				.map_err(|e| errors::account("Could not fetch account.", e))
+		})
+	}
+
+	fn change_password(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		from_params::<(RpcH160, String, String)>(params).and_then(|(account, password, new_password)| {
+			let account: Address = account.into();
+			let store = take_weak!(self.accounts);
+			store.change_password(&account, password, new_password)
+				.map(|_| Value::Bool(true))
+				.map_err(|e| errors::account("Could not change password.", e))
+		})
+	}
+
+	fn export_account(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		from_params::<(RpcH160, String)>(params).and_then(|(account, password)| {
+			let account: Address = account.into();
+			let store = take_weak!(self.accounts);
+			let exported = try!(store.export_account(&account, password).map_err(|e| errors::account("Could not export account.", e)));
+			serde_json::from_str::<Value>(&exported).map_err(|e| errors::internal("Invalid key file.", e))
+		})
+	}
+
 	fn sign_and_send_transaction(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
 		from_params::<(TransactionRequest, String)>(params)
@@ -146,6 +215,16 @@ impl<C: 'static, M: 'static> Personal for PersonalClient<C, M> where C: MiningBl
 			})
 	}
 
+	fn sign_message(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		from_params::<(RpcH160, String, RpcBytes)>(params).and_then(|(account, password, data)| {
+			let account: Address = account.into();
+			take_weak!(self.accounts).sign_with_password(account, password, data.0.sha3())
+				.map(|signature| to_value(&RpcH520::from(signature)))
+				.map_err(errors::from_password_error)
+		})
+	}
+
 	fn set_account_name(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
 		let store = take_weak!(self.accounts);
@@ -168,11 +247,13 @@ impl<C: 'static, M: 'static> Personal for PersonalClient<C, M> where C: MiningBl
 
 	fn accounts_info(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
-		try!(expect_no_params(params));
+		let (offset, limit) = match params {
+			Params::None => (0, usize::max_value()),
+			params => try!(from_params::<(u64, u64)>(params).map(|(offset, limit)| (offset as usize, limit as usize))),
+		};
 		let store = take_weak!(self.accounts);
-		let info = try!(store.accounts_info().map_err(|e| errors::account("Could not fetch account info.", e)));
-		let other = store.addresses_info().expect("addresses_info always returns Ok; qed");
-		Ok(Value::Object(info.into_iter().chain(other.into_iter()).map(|(a, v)| {
+		let all = try!(self.visible_accounts_info(&*store));
+		Ok(Value::Object(all.into_iter().skip(offset).take(limit).map(|(a, v)| {
 			let m = map![
 				"name".to_owned() => to_value(&v.name),
 				"meta".to_owned() => to_value(&v.meta),
@@ -186,6 +267,14 @@ impl<C: 'static, M: 'static> Personal for PersonalClient<C, M> where C: MiningBl
 		}).collect::<BTreeMap<_, _>>()))
 	}
 
+	fn accounts_count(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		try!(expect_no_params(params));
+		let store = take_weak!(self.accounts);
+		let count = try!(self.visible_accounts_info(&*store)).len();
+		Ok(to_value(&count))
+	}
+
 	fn geth_accounts(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
 		try!(expect_no_params(params));
@@ -202,4 +291,42 @@ impl<C: 'static, M: 'static> Personal for PersonalClient<C, M> where C: MiningBl
 			).into_iter().map(Into::into).collect::<Vec<RpcH160>>()))
 		})
 	}
+
+	fn schedule_transaction(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		let scheduler = try!(self.scheduler());
+		from_params::<(TransactionRequest, String, RpcU256, RpcU256)>(params).and_then(|(request, password, interval, max_gas_price)| {
+			let request: TRequest = request.into();
+			let interval: U256 = interval.into();
+			if interval.is_zero() || interval > U256::from(u64::max_value()) {
+				return Err(errors::invalid_params("interval", interval));
+			}
+
+			let store = take_weak!(self.accounts);
+			match store.test_password(&request.from, &password) {
+				Ok(true) => {},
+				Ok(false) => return Err(errors::from_password_error(SSError::InvalidPassword.into())),
+				Err(e) => return Err(errors::from_password_error(e)),
+			}
+
+			let id = scheduler.add(request, password, interval.low_u64(), max_gas_price.into());
+			Ok(to_value(&RpcU256::from(id)))
+		})
+	}
+
+	fn cancel_scheduled_transaction(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		let scheduler = try!(self.scheduler());
+		from_params::<(RpcU256,)>(params).and_then(|(id,)| {
+			let id: U256 = id.into();
+			Ok(Value::Bool(scheduler.remove(id.low_u64())))
+		})
+	}
+
+	fn scheduled_transactions(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		try!(expect_no_params(params));
+		let scheduler = try!(self.scheduler());
+		Ok(to_value(&scheduler.schedules().into_iter().map(Into::into).collect::<Vec<ScheduledTransaction>>()))
+	}
 }
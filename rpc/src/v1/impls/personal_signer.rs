@@ -25,7 +25,8 @@ use v1::traits::PersonalSigner;
 use v1::types::{TransactionModification, ConfirmationRequest, U256};
 use v1::helpers::{errors, SignerService, SigningQueue, ConfirmationPayload};
 use v1::helpers::params::expect_no_params;
-use v1::helpers::dispatch::{unlock_sign_and_dispatch, signature_with_password};
+use v1::helpers::call_decoder::{self, ContractRegistry};
+use v1::helpers::dispatch::{unlock_sign_and_dispatch, unlock_sign_transaction, signature_with_password};
 
 /// Transactions confirmation (personal) rpc implementation.
 pub struct SignerClient<C, M> where C: MiningBlockChainClient, M: MinerService {
@@ -33,6 +34,7 @@ pub struct SignerClient<C, M> where C: MiningBlockChainClient, M: MinerService {
 	accounts: Weak<AccountProvider>,
 	client: Weak<C>,
 	miner: Weak<M>,
+	registry: Weak<ContractRegistry>,
 }
 
 impl<C: 'static, M: 'static> SignerClient<C, M> where C: MiningBlockChainClient, M: MinerService {
@@ -43,12 +45,14 @@ impl<C: 'static, M: 'static> SignerClient<C, M> where C: MiningBlockChainClient,
 		client: &Arc<C>,
 		miner: &Arc<M>,
 		signer: &Arc<SignerService>,
+		registry: &Arc<ContractRegistry>,
 	) -> Self {
 		SignerClient {
 			signer: Arc::downgrade(signer),
 			accounts: Arc::downgrade(store),
 			client: Arc::downgrade(client),
 			miner: Arc::downgrade(miner),
+			registry: Arc::downgrade(registry),
 		}
 	}
 
@@ -65,7 +69,14 @@ impl<C: 'static, M: 'static> PersonalSigner for SignerClient<C, M> where C: Mini
 		try!(self.active());
 		try!(expect_no_params(params));
 		let signer = take_weak!(self.signer);
-		Ok(to_value(&signer.requests().into_iter().map(From::from).collect::<Vec<ConfirmationRequest>>()))
+		let registry = take_weak!(self.registry);
+		let requests = signer.requests().into_iter().map(|request| {
+			let details = call_decoder::describe(&request.payload, Some(&*registry));
+			let mut request = ConfirmationRequest::from(request);
+			request.details = Some(details);
+			request
+		}).collect::<Vec<_>>();
+		Ok(to_value(&requests))
 	}
 
 	fn confirm_request(&self, params: Params) -> Result<Value, Error> {
@@ -92,7 +103,15 @@ impl<C: 'static, M: 'static> PersonalSigner for SignerClient<C, M> where C: Mini
 						},
 						ConfirmationPayload::Sign(address, hash) => {
 							signature_with_password(&*accounts, address, hash, pass)
-						}
+						},
+						ConfirmationPayload::SignTransaction(mut request) => {
+							// apply modification
+							if let Some(gas_price) = modification.gas_price {
+								request.gas_price = gas_price.into();
+							}
+
+							unlock_sign_transaction(&*client, &*miner, request.into(), &*accounts, pass)
+						},
 					};
 					if let Ok(ref response) = result {
 						signer.request_confirmed(id, Ok(response.clone()));
@@ -122,5 +141,15 @@ impl<C: 'static, M: 'static> PersonalSigner for SignerClient<C, M> where C: Mini
 			.map(|token| to_value(&token))
 			.map_err(|e| errors::token(e))
 	}
+
+	fn exchange_token(&self, params: Params) -> Result<Value, Error> {
+		try!(self.active());
+		from_params::<(String, )>(params).and_then(|(token, )| {
+			let signer = take_weak!(self.signer);
+			signer.exchange_token(&token)
+				.map(|credential| to_value(&credential))
+				.map_err(|e| errors::token(e))
+		})
+	}
 }
 
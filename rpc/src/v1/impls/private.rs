@@ -0,0 +1,76 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Private transactions rpc implementation.
+
+use std::sync::{Arc, Weak};
+use jsonrpc_core::*;
+use rlp::{UntrustedRlp, View};
+use ethcore::transaction::SignedTransaction;
+use v1::helpers::{errors, PrivateTransactionService};
+use v1::traits::Private;
+use v1::types::{Bytes, H160, PrivateCommitment};
+
+/// Private transactions rpc implementation.
+pub struct PrivateClient {
+	service: Option<Weak<PrivateTransactionService>>,
+}
+
+impl PrivateClient {
+	/// Creates new `PrivateClient`. Private transactions are disabled if there is no service.
+	pub fn new(service: Option<&Arc<PrivateTransactionService>>) -> Self {
+		PrivateClient {
+			service: service.map(Arc::downgrade),
+		}
+	}
+
+	fn service(&self) -> Result<Arc<PrivateTransactionService>, Error> {
+		match self.service {
+			Some(ref service) => Ok(take_weak!(service)),
+			None => Err(errors::private_disabled()),
+		}
+	}
+}
+
+fn decode_transaction(raw: Bytes) -> Result<SignedTransaction, Error> {
+	let raw = raw.to_vec();
+	UntrustedRlp::new(&raw).as_val().map_err(|e| errors::invalid_params("transaction", e))
+}
+
+impl Private for PrivateClient {
+	fn initialize(&self, contract: H160, code: Bytes) -> Result<PrivateCommitment, Error> {
+		let service = try!(self.service());
+		service.initialize(contract.into(), code.to_vec())
+			.map(Into::into)
+			.map_err(errors::private_transaction)
+	}
+
+	fn send_transaction(&self, raw: Bytes) -> Result<PrivateCommitment, Error> {
+		let service = try!(self.service());
+		let transaction = try!(decode_transaction(raw));
+		service.send_transaction(transaction)
+			.map(Into::into)
+			.map_err(errors::private_transaction)
+	}
+
+	fn call(&self, raw: Bytes) -> Result<Bytes, Error> {
+		let service = try!(self.service());
+		let transaction = try!(decode_transaction(raw));
+		service.call(transaction)
+			.map(Bytes::new)
+			.map_err(errors::private_transaction)
+	}
+}
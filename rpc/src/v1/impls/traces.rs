@@ -20,6 +20,7 @@ use std::sync::{Weak, Arc};
 use jsonrpc_core::*;
 use rlp::{UntrustedRlp, View};
 use ethcore::client::{BlockChainClient, CallAnalytics, TransactionID, TraceId};
+use ethcore::error::CallError;
 use ethcore::miner::MinerService;
 use ethcore::transaction::{Transaction as EthTransaction, SignedTransaction, Action};
 use v1::traits::Traces;
@@ -27,11 +28,15 @@ use v1::helpers::{errors, CallRequest as CRequest};
 use v1::helpers::params::from_params_default_third;
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, H256};
 
+/// Trace types which can be requested with `trace_replayTransaction`.
+const TRACE_TYPES: &'static [&'static str] = &["trace", "vmTrace", "stateDiff"];
+
 fn to_call_analytics(flags: Vec<String>) -> CallAnalytics {
 	CallAnalytics {
 		transaction_tracing: flags.contains(&("trace".to_owned())),
 		vm_tracing: flags.contains(&("vmTrace".to_owned())),
 		state_diffing: flags.contains(&("stateDiff".to_owned())),
+		transfer_tracing: false,
 	}
 }
 
@@ -151,11 +156,15 @@ impl<C, M> Traces for TracesClient<C, M> where C: BlockChainClient + 'static, M:
 
 	fn replay_transaction(&self, params: Params) -> Result<Value, Error> {
 		try!(self.active());
-		from_params::<(H256, _)>(params)
+		from_params::<(H256, Vec<String>)>(params)
 			.and_then(|(transaction_hash, flags)| {
+				if let Some(flag) = flags.iter().find(|f| !TRACE_TYPES.contains(&f.as_str())) {
+					return Err(errors::invalid_params("Unknown trace type", flag));
+				}
 				match take_weak!(self.client).replay(TransactionID::Hash(transaction_hash.into()), to_call_analytics(flags)) {
 					Ok(e) => Ok(to_value(&TraceResults::from(e))),
-					_ => Ok(Value::Null),
+					Err(CallError::TransactionNotFound) => Ok(Value::Null),
+					Err(e) => Err(errors::from_call_error(e)),
 				}
 			})
 	}
@@ -26,6 +26,8 @@ pub mod traits;
 pub mod tests;
 pub mod types;
 
-pub use self::traits::{Web3, Eth, EthFilter, EthSigning, Personal, PersonalSigner, Net, Ethcore, EthcoreSet, Traces, Rpc};
+pub use self::traits::{Web3, Eth, EthFilter, EthSigning, Personal, PersonalSigner, Net, Ethcore, EthcoreSet, Traces, Rpc, Private};
 pub use self::impls::*;
-pub use self::helpers::{SigningQueue, SignerService, ConfirmationsQueue, NetworkSettings};
+pub use self::helpers::{SigningQueue, SignerService, ConfirmationsQueue, NetworkSettings, ResponseCache, RpcSession, SnapshotStatusProvider, UpdateService, ReleaseInfo, AddressWatcher, Scheduler, HealthProvider, PrivateTransactionService, PrivateCommitment};
+pub use self::helpers::call_decoder::{ContractRegistry, TokenInfo};
+pub use self::helpers::{origin, call_decoder, request_id};
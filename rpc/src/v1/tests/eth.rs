@@ -24,7 +24,7 @@ use ethcore::spec::{Genesis, Spec};
 use ethcore::block::Block;
 use ethcore::views::BlockView;
 use ethcore::ethereum;
-use ethcore::miner::{MinerOptions, GasPricer, MinerService, ExternalMiner, Miner, PendingSet};
+use ethcore::miner::{MinerOptions, Banning, GasPricer, MinerService, ExternalMiner, Miner, PendingSet, TransactionPermissions};
 use ethcore::account_provider::AccountProvider;
 use devtools::RandomTempPath;
 use util::Hashable;
@@ -57,11 +57,17 @@ fn miner_service(spec: &Spec, accounts: Arc<AccountProvider>) -> Arc<Miner> {
 			reseal_on_external_tx: true,
 			reseal_on_own_tx: true,
 			tx_queue_size: 1024,
+			tx_replace_bump: 10,
+			tx_queue_per_sender: 64,
+			tx_queue_memory_limit: None,
+			tx_queue_banning: Banning::Disabled,
+			tx_permissions: TransactionPermissions::Unrestricted,
 			tx_gas_limit: !U256::zero(),
 			pending_set: PendingSet::SealingOrElseQueue,
 			reseal_min_period: Duration::from_secs(0),
 			work_queue_size: 50,
 			enable_resubmission: true,
+			max_clock_drift: None,
 		},
 		GasPricer::new_fixed(20_000_000_000u64.into()),
 		&spec,
@@ -16,15 +16,16 @@
 
 //! Test implementation of miner service.
 
+use std::time::Duration;
 use util::{Address, H256, Bytes, U256, FixedHash, Uint};
 use util::standard::*;
 use ethcore::error::{Error, CallError};
-use ethcore::client::{MiningBlockChainClient, Executed, CallAnalytics};
-use ethcore::block::{ClosedBlock, IsBlock};
+use ethcore::client::{MiningBlockChainClient, Executed, CallAnalytics, CacheUsage};
+use ethcore::block::{Block, ClosedBlock, IsBlock};
 use ethcore::header::BlockNumber;
 use ethcore::transaction::SignedTransaction;
 use ethcore::receipt::{Receipt, RichReceipt};
-use ethcore::miner::{MinerService, MinerStatus, TransactionImportResult};
+use ethcore::miner::{MinerService, MinerStatus, SealingStatistics, ClockDrift, TransactionImportResult, QueueStatus};
 
 /// Test miner service.
 pub struct TestMinerService {
@@ -34,10 +35,27 @@ pub struct TestMinerService {
 	pub latest_closed_block: Mutex<Option<ClosedBlock>>,
 	/// Pre-existed pending transactions
 	pub pending_transactions: Mutex<HashMap<H256, SignedTransaction>>,
+	/// Pre-existed future transactions
+	pub future_transactions: Mutex<HashMap<H256, SignedTransaction>>,
 	/// Pre-existed pending receipts
 	pub pending_receipts: Mutex<BTreeMap<H256, Receipt>>,
 	/// Last nonces.
 	pub last_nonces: RwLock<HashMap<Address, U256>>,
+	/// Transactions imported as local-only.
+	pub local_only_transactions: RwLock<HashSet<H256>>,
+	/// Block production timings.
+	pub sealing_statistics: RwLock<SealingStatistics>,
+	/// Hashes of blocks reported as sealed by this node.
+	pub sealed_blocks: RwLock<Vec<H256>>,
+	/// Last measured clock offsets.
+	pub clock_drift: RwLock<ClockDrift>,
+	/// Queue status reported by `status`.
+	pub queue_status: RwLock<MinerStatus>,
+	/// Number of the pending block. When set, pending transactions and receipts are only reported
+	/// while it's above the best block, as the real miner does. Otherwise they're always reported.
+	pub pending_block_number: RwLock<Option<BlockNumber>>,
+	/// Block being sealed, visible under the same rules as pending transactions.
+	pub pending_block: Mutex<Option<Block>>,
 
 	min_gas_price: RwLock<U256>,
 	gas_range_target: RwLock<(U256, U256)>,
@@ -53,8 +71,20 @@ impl Default for TestMinerService {
 			imported_transactions: Mutex::new(Vec::new()),
 			latest_closed_block: Mutex::new(None),
 			pending_transactions: Mutex::new(HashMap::new()),
+			future_transactions: Mutex::new(HashMap::new()),
 			pending_receipts: Mutex::new(BTreeMap::new()),
 			last_nonces: RwLock::new(HashMap::new()),
+			local_only_transactions: RwLock::new(HashSet::new()),
+			sealing_statistics: RwLock::new(SealingStatistics::default()),
+			sealed_blocks: RwLock::new(Vec::new()),
+			clock_drift: RwLock::new(ClockDrift::default()),
+			queue_status: RwLock::new(MinerStatus {
+				transactions_in_pending_queue: 0,
+				transactions_in_future_queue: 0,
+				transactions_in_pending_block: 1,
+			}),
+			pending_block_number: RwLock::new(None),
+			pending_block: Mutex::new(None),
 			min_gas_price: RwLock::new(U256::from(20_000_000)),
 			gas_range_target: RwLock::new((U256::from(12345), U256::from(54321))),
 			author: RwLock::new(Address::zero()),
@@ -65,17 +95,49 @@ impl Default for TestMinerService {
 	}
 }
 
+impl TestMinerService {
+	/// Adds `transaction` to the pending block, with `receipt` as the result of its execution.
+	pub fn add_pending_transaction(&self, transaction: SignedTransaction, receipt: Receipt) {
+		let hash = transaction.hash();
+		self.pending_transactions.lock().insert(hash.clone(), transaction);
+		self.pending_receipts.lock().insert(hash, receipt);
+	}
+
+	fn is_pending_visible(&self, best_block: BlockNumber) -> bool {
+		self.pending_block_number.read().map_or(true, |number| number > best_block)
+	}
+}
+
 impl MinerService for TestMinerService {
 
 	/// Returns miner's status.
 	fn status(&self) -> MinerStatus {
-		MinerStatus {
-			transactions_in_pending_queue: 0,
-			transactions_in_future_queue: 0,
-			transactions_in_pending_block: 1
+		self.queue_status.read().clone()
+	}
+
+	fn sealing_statistics(&self) -> SealingStatistics {
+		self.sealing_statistics.read().clone()
+	}
+
+	fn sealed_blocks(&self) -> Vec<H256> {
+		self.sealed_blocks.read().clone()
+	}
+
+	fn clock_drift(&self) -> ClockDrift {
+		self.clock_drift.read().clone()
+	}
+
+	fn transaction_queue_memory(&self) -> CacheUsage {
+		CacheUsage {
+			used: 1024,
+			limit: 4 * 1024 * 1024,
 		}
 	}
 
+	fn set_clock_drift(&self, drift: ClockDrift) {
+		*self.clock_drift.write() = drift;
+	}
+
 	fn set_author(&self, author: Address) {
 		*self.author.write() = author;
 	}
@@ -84,6 +146,10 @@ impl MinerService for TestMinerService {
 		*self.extra_data.write() = extra_data;
 	}
 
+	fn maximum_extra_data_size(&self) -> usize {
+		32
+	}
+
 	/// Set the lower gas limit we wish to target when sealing a new block.
 	fn set_gas_floor_target(&self, target: U256) {
 		self.gas_range_target.write().0 = target;
@@ -162,9 +228,23 @@ impl MinerService for TestMinerService {
 		Ok(TransactionImportResult::Current)
 	}
 
+	/// Imports transactions to transaction queue and marks them as local-only.
+	fn import_local_only_transaction(&self, chain: &MiningBlockChainClient, transaction: SignedTransaction) ->
+		Result<TransactionImportResult, Error> {
+		self.local_only_transactions.write().insert(transaction.hash());
+		self.import_own_transaction(chain, transaction)
+	}
+
+	fn is_local_only_transaction(&self, hash: &H256) -> bool {
+		self.local_only_transactions.read().contains(hash)
+	}
+
 	/// Returns hashes of transactions currently in pending
-	fn pending_transactions_hashes(&self, _best_block: BlockNumber) -> Vec<H256> {
-		vec![]
+	fn pending_transactions_hashes(&self, best_block: BlockNumber) -> Vec<H256> {
+		match self.is_pending_visible(best_block) {
+			true => self.pending_transactions.lock().keys().cloned().collect(),
+			false => vec![],
+		}
 	}
 
 	/// Removes all transactions from the queue and restart mining operation.
@@ -187,44 +267,75 @@ impl MinerService for TestMinerService {
 		Some(f(&open_block.close()))
 	}
 
-	fn transaction(&self, _best_block: BlockNumber, hash: &H256) -> Option<SignedTransaction> {
-		self.pending_transactions.lock().get(hash).cloned()
+	fn transaction(&self, best_block: BlockNumber, hash: &H256) -> Option<SignedTransaction> {
+		match self.is_pending_visible(best_block) {
+			true => self.pending_transactions.lock().get(hash).cloned(),
+			false => None,
+		}
+	}
+
+	fn queued_transaction(&self, hash: &H256) -> Option<(SignedTransaction, QueueStatus)> {
+		self.pending_transactions.lock().get(hash).cloned().map(|t| (t, QueueStatus::Current))
+			.or_else(|| self.future_transactions.lock().get(hash).cloned().map(|t| (t, QueueStatus::Future)))
 	}
 
 	fn all_transactions(&self) -> Vec<SignedTransaction> {
 		self.pending_transactions.lock().values().cloned().collect()
 	}
 
-	fn pending_transactions(&self, _best_block: BlockNumber) -> Vec<SignedTransaction> {
-		self.pending_transactions.lock().values().cloned().collect()
+	fn pending_block(&self, best_block: BlockNumber) -> Option<Block> {
+		match self.is_pending_visible(best_block) {
+			true => self.pending_block.lock().clone(),
+			false => None,
+		}
 	}
 
-	fn pending_receipt(&self, _best_block: BlockNumber, hash: &H256) -> Option<RichReceipt> {
+	fn pending_transactions(&self, best_block: BlockNumber) -> Vec<SignedTransaction> {
+		match self.is_pending_visible(best_block) {
+			true => self.pending_transactions.lock().values().cloned().collect(),
+			false => vec![],
+		}
+	}
+
+	fn pending_receipt(&self, best_block: BlockNumber, hash: &H256) -> Option<RichReceipt> {
 		// Not much point implementing this since the logic is complex and the only thing it relies on is pending_receipts, which is already tested.
-		self.pending_receipts(0).get(hash).map(|r|
+		self.pending_receipts(best_block).get(hash).map(|r|
 			RichReceipt {
-				transaction_hash: Default::default(),
+				transaction_hash: hash.clone(),
 				transaction_index: Default::default(),
 				cumulative_gas_used: r.gas_used.clone(),
 				gas_used: r.gas_used.clone(),
 				contract_address: None,
+				state_root: r.state_root.clone(),
 				logs: r.logs.clone(),
 			}
 		)
 	}
 
-	fn pending_receipts(&self, _best_block: BlockNumber) -> BTreeMap<H256, Receipt> {
-		self.pending_receipts.lock().clone()
+	fn pending_receipts(&self, best_block: BlockNumber) -> BTreeMap<H256, Receipt> {
+		match self.is_pending_visible(best_block) {
+			true => self.pending_receipts.lock().clone(),
+			false => BTreeMap::new(),
+		}
 	}
 
 	fn last_nonce(&self, address: &Address) -> Option<U256> {
 		self.last_nonces.read().get(address).cloned()
 	}
 
+	fn next_nonce(&self, chain: &MiningBlockChainClient, address: &Address) -> U256 {
+		let state_nonce = chain.latest_nonce(address);
+		self.last_nonce(address).map_or(state_nonce, |nonce| cmp::max(state_nonce, nonce + U256::one()))
+	}
+
 	fn is_sealing(&self) -> bool {
 		false
 	}
 
+	fn on_work_change(&self, _pow_hash: &H256, _timeout: Duration, on_change: Box<Fn(bool) + Send>) {
+		on_change(false);
+	}
+
 	/// Submit `seal` as a valid solution for the header of `pow_hash`.
 	/// Will check the seal, but not actually insert the block into the chain.
 	fn submit_seal(&self, _chain: &MiningBlockChainClient, _pow_hash: H256, _seal: Vec<Bytes>) -> Result<(), Error> {
@@ -19,7 +19,15 @@
 mod sync_provider;
 mod miner_service;
 mod fetch;
+mod snapshot_service;
+mod updater;
+mod registry;
+mod private;
 
 pub use self::sync_provider::{Config, TestSyncProvider};
 pub use self::miner_service::TestMinerService;
 pub use self::fetch::TestFetch;
+pub use self::snapshot_service::TestSnapshotService;
+pub use self::updater::TestUpdater;
+pub use self::registry::TestRegistry;
+pub use self::private::TestPrivateService;
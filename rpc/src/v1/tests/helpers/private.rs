@@ -0,0 +1,62 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test implementation of private transactions service.
+
+use util::{Address, H256, Bytes, Mutex};
+use ethkey::Signature;
+use ethcore::transaction::{SignedTransaction, Action};
+use v1::helpers::{PrivateTransactionService, PrivateCommitment};
+
+/// Test private transactions service, executing transactions by echoing their data.
+#[derive(Default)]
+pub struct TestPrivateService {
+	/// Transactions sent.
+	pub sent: Mutex<Vec<SignedTransaction>>,
+}
+
+fn commitment(contract: Address) -> PrivateCommitment {
+	PrivateCommitment {
+		contract: contract,
+		state_hash: H256::from(1),
+		signature: Signature::default(),
+		transaction_hash: H256::from(2),
+	}
+}
+
+fn contract(transaction: &SignedTransaction) -> Result<Address, String> {
+	match transaction.action {
+		Action::Call(ref address) => Ok(address.clone()),
+		Action::Create => Err("Not a contract call".into()),
+	}
+}
+
+impl PrivateTransactionService for TestPrivateService {
+	fn initialize(&self, contract: Address, _code: Bytes) -> Result<PrivateCommitment, String> {
+		Ok(commitment(contract))
+	}
+
+	fn send_transaction(&self, transaction: SignedTransaction) -> Result<PrivateCommitment, String> {
+		let contract = try!(contract(&transaction));
+		self.sent.lock().push(transaction);
+		Ok(commitment(contract))
+	}
+
+	fn call(&self, transaction: SignedTransaction) -> Result<Bytes, String> {
+		try!(contract(&transaction));
+		Ok(transaction.data.clone())
+	}
+}
@@ -0,0 +1,59 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Test implementation of contract registry.
+
+use std::collections::{HashMap, HashSet};
+use util::{Address, H256};
+use v1::helpers::call_decoder::{ContractRegistry, TokenInfo};
+
+/// Test contract registry.
+#[derive(Default)]
+pub struct TestRegistry {
+	/// Method signatures by selector.
+	pub signatures: HashMap<[u8; 4], String>,
+	/// Registered tokens.
+	pub tokens: HashMap<Address, TokenInfo>,
+	/// Method signatures from ABIs of contracts.
+	pub abis: HashMap<Address, Vec<String>>,
+	/// Code hashes of known wallet versions.
+	pub wallet_codes: HashSet<H256>,
+}
+
+impl ContractRegistry for TestRegistry {
+	fn method_signature(&self, selector: [u8; 4]) -> Option<String> {
+		self.signatures.get(&selector).cloned()
+	}
+
+	fn token(&self, address: &Address) -> Option<TokenInfo> {
+		self.tokens.get(address).cloned()
+	}
+
+	fn tokens(&self) -> Vec<(Address, TokenInfo)> {
+		let mut tokens: Vec<_> = self.tokens.iter().map(|(address, token)| (address.clone(), token.clone())).collect();
+		tokens.sort_by(|a, b| a.0.cmp(&b.0));
+		tokens
+	}
+
+	fn abi(&self, address: &Address) -> Option<Vec<String>> {
+		self.abis.get(address).cloned()
+	}
+
+	fn is_wallet_code(&self, code_hash: &H256) -> bool {
+		self.wallet_codes.contains(code_hash)
+	}
+}
@@ -0,0 +1,34 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test implementation of snapshot status provider.
+
+use ethcore::snapshot::CreationStatus;
+use util::Mutex;
+use v1::helpers::SnapshotStatusProvider;
+
+/// Test snapshot status provider.
+#[derive(Default)]
+pub struct TestSnapshotService {
+	/// Status to report.
+	pub status: Mutex<CreationStatus>,
+}
+
+impl SnapshotStatusProvider for TestSnapshotService {
+	fn creation_status(&self) -> CreationStatus {
+		self.status.lock().clone()
+	}
+}
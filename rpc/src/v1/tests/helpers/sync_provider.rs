@@ -16,8 +16,9 @@
 
 //! Test implementation of SyncProvider.
 
-use util::{RwLock, U256};
-use ethsync::{SyncProvider, SyncStatus, SyncState};
+use util::{RwLock, U256, H256};
+use ethcore::header::BlockNumber;
+use ethsync::{SyncProvider, SyncStatus, SyncState, ChainStatus, PeerInfo};
 
 /// TestSyncProvider config.
 pub struct Config {
@@ -31,6 +32,8 @@ pub struct Config {
 pub struct TestSyncProvider {
 	/// Sync status.
 	pub status: RwLock<SyncStatus>,
+	/// Chain status.
+	pub chain_status: RwLock<ChainStatus>,
 }
 
 impl TestSyncProvider {
@@ -51,14 +54,41 @@ impl TestSyncProvider {
 				mem_used: 0,
 				num_snapshot_chunks: 0,
 				snapshot_chunks_done: 0,
+				transaction_bytes_saved: 0,
 			}),
+			chain_status: RwLock::new(ChainStatus::default()),
 		}
 	}
+
+	/// Switches the sync to `state`, heading for `highest_block`.
+	pub fn set_state(&self, state: SyncState, highest_block: Option<BlockNumber>) {
+		let mut status = self.status.write();
+		status.state = state;
+		status.highest_block_number = highest_block;
+	}
 }
 
 impl SyncProvider for TestSyncProvider {
 	fn status(&self) -> SyncStatus {
 		self.status.read().clone()
 	}
+
+	fn chain_status(&self) -> ChainStatus {
+		self.chain_status.read().clone()
+	}
+
+	fn peers(&self) -> Vec<PeerInfo> {
+		vec![PeerInfo {
+			id: Some("node1".into()),
+			client_version: "Parity/v1.4.0".into(),
+			capabilities: vec!["eth/63".into()],
+			eth_version: 63,
+			eth_head: H256::from(1),
+			eth_difficulty: Some(40.into()),
+			outstanding_requests: 0,
+			block_announcements: 12,
+			block_propagation_delay: Some(250),
+		}]
+	}
 }
 
@@ -0,0 +1,59 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test implementation of update service.
+
+use util::{H256, Mutex};
+use v1::helpers::{UpdateService, ReleaseInfo};
+
+/// Test update service.
+#[derive(Default)]
+pub struct TestUpdater {
+	/// Release waiting to be installed.
+	pub ready: Mutex<Option<ReleaseInfo>>,
+	/// Installed release.
+	pub installed: Mutex<Option<ReleaseInfo>>,
+}
+
+impl TestUpdater {
+	/// Creates updater with given release ready to be installed.
+	pub fn with_release(version: &str, is_critical: bool) -> Self {
+		TestUpdater {
+			ready: Mutex::new(Some(ReleaseInfo {
+				version: version.into(),
+				is_critical: is_critical,
+				binary: H256::from(5),
+			})),
+			installed: Mutex::new(None),
+		}
+	}
+}
+
+impl UpdateService for TestUpdater {
+	fn upgrade_ready(&self) -> Option<ReleaseInfo> {
+		self.ready.lock().clone()
+	}
+
+	fn execute_upgrade(&self) -> bool {
+		match self.ready.lock().take() {
+			Some(release) => {
+				*self.installed.lock() = Some(release);
+				true
+			},
+			None => false,
+		}
+	}
+}
@@ -29,7 +29,9 @@ use ethcore::miner::{ExternalMiner, MinerService};
 use ethsync::SyncState;
 use v1::{Eth, EthClient, EthClientOptions, EthFilter, EthFilterClient, EthSigning, EthSigningUnsafeClient};
 use v1::tests::helpers::{TestSyncProvider, Config, TestMinerService};
-use rustc_serialize::hex::ToHex;
+use v1::helpers::dispatch::eth_data_hash;
+use v1::helpers::origin::{self, Origin};
+use rustc_serialize::hex::{ToHex, FromHex};
 use time::get_time;
 
 fn blockchain_client() -> Arc<TestBlockChainClient> {
@@ -124,7 +126,7 @@ fn rpc_eth_syncing() {
 		}
 	}
 
-	let true_res = r#"{"jsonrpc":"2.0","result":{"currentBlock":"0x3e8","highestBlock":"0x9c4","startingBlock":"0x0"},"id":1}"#;
+	let true_res = r#"{"jsonrpc":"2.0","result":{"currentBlock":"0x3e8","highestBlock":"0x9c4","startingBlock":"0x0","warpChunksAmount":null,"warpChunksProcessed":null},"id":1}"#;
 	assert_eq!(tester.io.handle_request_sync(request), Some(true_res.to_owned()));
 
 	{
@@ -138,6 +140,52 @@ fn rpc_eth_syncing() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(false_res.to_owned()));
 }
 
+#[test]
+fn rpc_eth_syncing_warp_and_threshold() {
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_syncing", "params": [], "id": 1}"#;
+
+	let tester = EthTester::default();
+	{
+		let mut status = tester.sync.status.write();
+		status.state = SyncState::SnapshotData;
+		status.highest_block_number = Some(2500);
+		status.num_snapshot_chunks = 10;
+		status.snapshot_chunks_done = 3;
+	}
+
+	let warp_res = r#"{"jsonrpc":"2.0","result":{"currentBlock":"0x0","highestBlock":"0x9c4","startingBlock":"0x0","warpChunksAmount":"0xa","warpChunksProcessed":"0x3"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(warp_res.to_owned()));
+
+	let tester = EthTester::new_with_options(EthClientOptions {
+		sync_threshold: 3000,
+		..Default::default()
+	});
+	{
+		let mut status = tester.sync.status.write();
+		status.state = SyncState::Blocks;
+		status.highest_block_number = Some(2500);
+	}
+
+	let false_res = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(false_res.to_owned()));
+}
+
+#[test]
+fn rpc_eth_syncing_scripted_state() {
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_syncing", "params": [], "id": 1}"#;
+
+	let tester = EthTester::default();
+	tester.sync.set_state(SyncState::Blocks, Some(2500));
+
+	let true_res = r#"{"jsonrpc":"2.0","result":{"currentBlock":"0x0","highestBlock":"0x9c4","startingBlock":"0x0","warpChunksAmount":null,"warpChunksProcessed":null},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(true_res.to_owned()));
+
+	tester.sync.set_state(SyncState::Idle, None);
+
+	let false_res = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(false_res.to_owned()));
+}
+
 #[test]
 fn rpc_eth_hashrate() {
 	let tester = EthTester::default();
@@ -264,7 +312,7 @@ fn rpc_eth_sign() {
 	let account = tester.accounts_provider.new_account("abcd").unwrap();
 	tester.accounts_provider.unlock_account_permanently(account, "abcd".into()).unwrap();
 	let message = H256::from("0x0cc175b9c0f1b6a831c399e26977266192eb5ffee6ae2fec3ad71c777531578f");
-	let signed = tester.accounts_provider.sign(account, message).unwrap();
+	let signed = tester.accounts_provider.sign(account, eth_data_hash(&message)).unwrap();
 
 	let req = r#"{
 		"jsonrpc": "2.0",
@@ -331,6 +379,25 @@ fn rpc_eth_accounts() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_accounts_for_dapp() {
+	let tester = EthTester::default();
+	let address = tester.accounts_provider.new_account("").unwrap();
+	tester.accounts_provider.new_account("").unwrap();
+	tester.accounts_provider.set_dapps_addresses("wallet".into(), vec![address]).unwrap();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_accounts", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[""#.to_owned() + &format!("0x{:?}", address) + r#""],"id":1}"#;
+	assert_eq!(origin::with_origin(Origin::Dapp("wallet".into()), || tester.io.handle_request_sync(request)), Some(response.to_owned()));
+
+	// nothing exposed to other dapps
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(origin::with_origin(Origin::Dapp("home".into()), || tester.io.handle_request_sync(request)), Some(response.to_owned()));
+
+	// nor to requests to dapps server that didn't identify the dapp
+	assert_eq!(origin::with_origin(Origin::Dapps, || tester.io.handle_request_sync(request)), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_eth_block_number() {
 	let tester = EthTester::default();
@@ -457,7 +524,7 @@ fn rpc_eth_pending_transaction_by_hash() {
 		tester.miner.pending_transactions.lock().insert(H256::zero(), tx);
 	}
 
-	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"creates":null,"from":"0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e","gas":"0x5208","gasPrice":"0x1","hash":"0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31","input":"0x","nonce":"0x0","publicKey":"0x7ae46da747962c2ee46825839c1ef9298e3bd2e70ca2938495c3693a485ec3eaa8f196327881090ff64cf4fbb0a48485d4f83098e189ed3b7a87d5941b59f789","raw":"0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","to":"0x095e7baea6a6c7c4c2dfeb977efac326af552d87","transactionIndex":null,"value":"0xa"},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"creates":null,"from":"0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e","gas":"0x5208","gasPrice":"0x1","hash":"0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31","input":"0x","nonce":"0x0","publicKey":"0x7ae46da747962c2ee46825839c1ef9298e3bd2e70ca2938495c3693a485ec3eaa8f196327881090ff64cf4fbb0a48485d4f83098e189ed3b7a87d5941b59f789","queueStatus":"pending","raw":"0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","to":"0x095e7baea6a6c7c4c2dfeb977efac326af552d87","transactionIndex":null,"value":"0xa"},"id":1}"#;
 	let request = r#"{
 		"jsonrpc": "2.0",
 		"method": "eth_getTransactionByHash",
@@ -467,6 +534,26 @@ fn rpc_eth_pending_transaction_by_hash() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_future_transaction_by_hash() {
+	use util::*;
+	use ethcore::transaction::*;
+
+	let tester = EthTester::default();
+	{
+		let tx: SignedTransaction = ::rlp::decode(&FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap());
+		tester.miner.future_transactions.lock().insert(H256::zero(), tx);
+	}
+
+	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"creates":null,"from":"0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e","gas":"0x5208","gasPrice":"0x1","hash":"0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31","input":"0x","nonce":"0x0","publicKey":"0x7ae46da747962c2ee46825839c1ef9298e3bd2e70ca2938495c3693a485ec3eaa8f196327881090ff64cf4fbb0a48485d4f83098e189ed3b7a87d5941b59f789","queueStatus":"future","raw":"0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","to":"0x095e7baea6a6c7c4c2dfeb977efac326af552d87","transactionIndex":null,"value":"0xa"},"id":1}"#;
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getTransactionByHash",
+		"params": ["0x0000000000000000000000000000000000000000000000000000000000000000"],
+		"id": 1
+	}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
 
 #[test]
 fn rpc_eth_uncle_count_by_block_hash() {
@@ -494,6 +581,70 @@ fn rpc_eth_uncle_count_by_block_number() {
 	assert_eq!(EthTester::default().io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_pruned_block_is_distinguished_from_unknown() {
+	let tester = EthTester::default();
+	let genesis = tester.client.numbers.read()[&0].clone();
+	tester.client.pruned.write().insert(genesis);
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getBlockTransactionCountByNumber",
+		"params": ["0x0"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32012,"message":"Block is known, but its body and receipts were pruned by this node (--prune-ancient-bodies).","data":null},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getBlockTransactionCountByNumber",
+		"params": ["0x5"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_eth_pending_block_by_number() {
+	use ethcore::block::Block;
+	use ethcore::header::Header;
+
+	let tester = EthTester::default();
+	let mut header = Header::default();
+	header.set_number(1);
+	*tester.miner.pending_block.lock() = Some(Block { header: header, transactions: vec![], uncles: vec![] });
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getBlockByNumber",
+		"params": ["pending", false],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"author":"0x0000000000000000000000000000000000000000","difficulty":"0x0","extraData":"0x","gasLimit":"0x0","gasUsed":"0x0","hash":null,"logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","miner":"0x0000000000000000000000000000000000000000","number":"0x1","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","receiptsRoot":"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421","sealFields":[],"sha3Uncles":"0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347","size":"0x1cb","stateRoot":"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421","timestamp":"0x0","totalDifficulty":"0x0","transactions":[],"transactionsRoot":"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421","uncles":[]},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_eth_rejects_too_large_responses() {
+	let tester = EthTester::new_with_options(EthClientOptions {
+		max_response_size: Some(64),
+		..Default::default()
+	});
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getBlockByNumber",
+		"params": ["latest", true],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32013,"message":"Response is larger than the 64 bytes limit. Narrow your query.","data":null},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_eth_code() {
 	let tester = EthTester::default();
@@ -524,6 +675,7 @@ fn rpc_eth_call_latest() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		reverted: false,
 	}));
 
 	let request = r#"{
@@ -559,6 +711,7 @@ fn rpc_eth_call() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		reverted: false,
 	}));
 
 	let request = r#"{
@@ -594,6 +747,7 @@ fn rpc_eth_call_default_block() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		reverted: false,
 	}));
 
 	let request = r#"{
@@ -628,6 +782,7 @@ fn rpc_eth_estimate_gas() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		reverted: false,
 	}));
 
 	let request = r#"{
@@ -663,6 +818,7 @@ fn rpc_eth_estimate_gas_default_block() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		reverted: false,
 	}));
 
 	let request = r#"{
@@ -683,6 +839,40 @@ fn rpc_eth_estimate_gas_default_block() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_call_and_estimate_gas_return_revert_reason() {
+	let tester = EthTester::default();
+	tester.client.set_execution_result(Ok(Executed {
+		gas: U256::zero(),
+		gas_used: U256::from(0x5208),
+		refunded: U256::zero(),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: "08c379a00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000a4e6f7420656e6f75676800000000000000000000000000000000000000000000".from_hex().unwrap(),
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		reverted: true,
+	}));
+
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32015,"message":"Execution reverted: Not enough","data":"0x08c379a00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000a4e6f7420656e6f75676800000000000000000000000000000000000000000000"},"id":1}"#;
+	for method in &["eth_call", "eth_estimateGas"] {
+		let request = r#"{
+			"jsonrpc": "2.0",
+			"method": ""#.to_owned() + method + r#"",
+			"params": [{
+				"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+				"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567"
+			},
+			"latest"],
+			"id": 1
+		}"#;
+
+		assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+	}
+}
+
 #[test]
 fn rpc_eth_send_transaction() {
 	let tester = EthTester::default();
@@ -820,6 +1010,7 @@ fn rpc_eth_transaction_receipt() {
 		cumulative_gas_used: U256::from(0x20),
 		gas_used: U256::from(0x10),
 		contract_address: None,
+		state_root: H256::from(10),
 		logs: vec![LocalizedLogEntry {
 			entry: LogEntry {
 				address: Address::from_str("33990122638b9132ca29c723bdf037f1a891a70c").unwrap(),
@@ -847,7 +1038,7 @@ fn rpc_eth_transaction_receipt() {
 		"params": ["0xb903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238"],
 		"id": 1
 	}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","contractAddress":null,"cumulativeGasUsed":"0x20","gasUsed":"0x10","logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","data":"0x","logIndex":"0x1","topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","type":"mined"}],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0"},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","contractAddress":null,"cumulativeGasUsed":"0x20","gasUsed":"0x10","logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","data":"0x","logIndex":"0x1","topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","type":"mined"}],"root":"0x000000000000000000000000000000000000000000000000000000000000000a","transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0"},"id":1}"#;
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -867,6 +1058,36 @@ fn rpc_eth_transaction_receipt_null() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_pending_receipt_during_reorg() {
+	use util::*;
+	use ethcore::receipt::Receipt;
+	use ethcore::transaction::*;
+
+	let tester = EthTester::default();
+	tester.client.add_blocks(2, EachBlockWith::Nothing);
+	{
+		let tx: SignedTransaction = ::rlp::decode(&FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap());
+		tester.miner.add_pending_transaction(tx, Receipt::new(H256::from(10), U256::from(0x10), vec![]));
+		*tester.miner.pending_block_number.write() = Some(3);
+	}
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getTransactionReceipt",
+		"params": ["0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31"],
+		"id": 1
+	}"#;
+	let pending = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"contractAddress":null,"cumulativeGasUsed":"0x10","gasUsed":"0x10","logs":[],"root":"0x000000000000000000000000000000000000000000000000000000000000000a","transactionHash":"0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31","transactionIndex":"0x0"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(pending.to_owned()));
+
+	// a longer fork takes over before the pending block is rebuilt; its stale receipt mustn't be served.
+	tester.client.reorg(1, 2, EachBlockWith::Uncle);
+
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 // These tests are incorrect: their output is undefined as long as eth_getCompilers is [].
 // Will ignore for now, but should probably be replaced by more substantial tests which check
 // the output of eth_getCompilers to determine whether to test. CI systems can then be preinstalled
@@ -934,6 +1155,9 @@ fn rpc_get_work_should_not_return_block_number() {
 	let eth_tester = EthTester::new_with_options(EthClientOptions {
 		allow_pending_receipt_query: true,
 		send_block_number_in_get_work: false,
+		response_cache: None,
+		sync_threshold: 6,
+		max_response_size: None,
 	});
 	eth_tester.miner.set_author(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap());
 
@@ -943,6 +1167,21 @@ fn rpc_get_work_should_not_return_block_number() {
 	assert_eq!(eth_tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_get_work_change() {
+	let eth_tester = EthTester::default();
+	eth_tester.miner.set_author(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap());
+	let response = r#"{"jsonrpc":"2.0","result":["0x3bbe93f74e7b97ae00784aeff8819c5cb600dd87e8b282a5d3446f3f871f0347","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000800000000000000000000000000000000000000000000000000000000000","0x1"],"id":1}"#;
+
+	// work differs from the one the miner has, returned straight away.
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getWorkChange", "params": ["0x0000000000000000000000000000000000000000000000000000000000000000", 60000], "id": 1}"#;
+	assert_eq!(eth_tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	// work stays the same, the current one is returned after the timeout.
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getWorkChange", "params": ["0x3bbe93f74e7b97ae00784aeff8819c5cb600dd87e8b282a5d3446f3f871f0347", 10], "id": 1}"#;
+	assert_eq!(eth_tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_get_work_should_timeout() {
 	let eth_tester = EthTester::default();
@@ -19,12 +19,14 @@ use std::sync::Arc;
 use jsonrpc_core::{IoHandler, to_value};
 use v1::impls::EthSigningQueueClient;
 use v1::traits::EthSigning;
-use v1::helpers::{SignerService, SigningQueue};
-use v1::types::{H256 as RpcH256, H520 as RpcH520};
+use v1::helpers::{SignerService, SigningQueue, ConfirmationPayload};
+use v1::helpers::dispatch::eth_data_hash;
+use v1::types::{H256 as RpcH256, H520 as RpcH520, Transaction as RpcTransaction};
 use v1::tests::helpers::TestMinerService;
 use util::{Address, FixedHash, Uint, U256, H256, H520};
 use ethcore::account_provider::AccountProvider;
 use ethcore::client::TestBlockChainClient;
+use ethcore::miner::MinerService;
 use ethcore::transaction::{Transaction, Action};
 
 struct EthSigningTester {
@@ -178,7 +180,7 @@ fn should_sign_if_account_is_unlocked() {
 	let acc = tester.accounts.new_account("test").unwrap();
 	tester.accounts.unlock_account_permanently(acc, "test".into()).unwrap();
 
-	let signature = tester.accounts.sign(acc, hash).unwrap();
+	let signature = tester.accounts.sign(acc, eth_data_hash(&hash)).unwrap();
 
 	// when
 	let request = r#"{
@@ -227,6 +229,94 @@ fn should_add_transaction_to_queue() {
 	}));
 }
 
+#[test]
+fn should_add_contract_deployment_to_queue() {
+	use ethcore::contract_address;
+	use ethkey::{Generator, Random};
+
+	// given
+	let tester = eth_signing();
+	let address = Address::from(1);
+	tester.miner.last_nonces.write().insert(address, U256::from(4));
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "ethcore_deployContract",
+		"params": [
+			"[{\"type\":\"constructor\",\"inputs\":[{\"name\":\"limit\",\"type\":\"uint256\"}]}]",
+			"0x6060",
+			[16],
+			{
+				"from": "0x0000000000000000000000000000000000000001",
+				"gas": "0x76c0",
+				"gasPrice": "0x9184e72a000"
+			}
+		],
+		"id": 1
+	}"#;
+
+	// then
+	let async_result = tester.io.handle_request(&request).unwrap();
+	let requests = tester.signer.requests();
+	assert_eq!(requests.len(), 1);
+	match requests[0].payload {
+		ConfirmationPayload::Transaction(ref request) => {
+			let mut data = vec![0x60, 0x60];
+			data.extend_from_slice(&H256::from(16));
+			assert_eq!(request.to, None);
+			assert_eq!(request.nonce, None);
+			assert_eq!(request.data, data);
+		},
+		_ => panic!("Expected transaction request"),
+	}
+
+	// another transaction of the sender is sent before the deployment is confirmed
+	let t = Transaction {
+		nonce: U256::from(6),
+		gas_price: U256::from(0x9184e72a000u64),
+		gas: U256::from(0x76c0),
+		action: Action::Create,
+		value: U256::zero(),
+		data: vec![0x60, 0x60],
+	}.sign(Random.generate().unwrap().secret());
+	let hash = t.hash();
+	tester.miner.pending_transactions.lock().insert(hash, t);
+	let contract = contract_address(&address, &U256::from(6));
+	let response = r#"{"jsonrpc":"2.0","result":{"contractAddress":""#.to_owned() + format!("0x{:?}", contract).as_ref()
+		+ r#"","transactionHash":""# + format!("0x{:?}", hash).as_ref() + r#""},"id":1}"#;
+
+	// respond
+	tester.signer.request_confirmed(U256::from(1), Ok(to_value(&RpcH256::from(hash))));
+	assert!(async_result.on_result(move |res| {
+		assert_eq!(res, response);
+	}));
+}
+
+#[test]
+fn should_reject_deployment_with_invalid_constructor_arguments() {
+	// given
+	let tester = eth_signing();
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "ethcore_deployContract",
+		"params": [
+			"[{\"type\":\"constructor\",\"inputs\":[{\"name\":\"limit\",\"type\":\"uint256\"}]}]",
+			"0x6060",
+			[],
+			{ "from": "0x0000000000000000000000000000000000000001" }
+		],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: constructorArgs","data":"\"Expected 1 arguments, got 0\""},"id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+	assert_eq!(tester.signer.requests().len(), 0);
+}
+
 #[test]
 fn should_dispatch_transaction_if_account_is_unlock() {
 	// given
@@ -263,3 +353,112 @@ fn should_dispatch_transaction_if_account_is_unlock() {
 	// then
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
 }
+
+#[test]
+fn should_dispatch_local_only_transaction_if_account_is_unlocked() {
+	// given
+	let tester = eth_signing();
+	let acc = tester.accounts.new_account("test").unwrap();
+	tester.accounts.unlock_account_permanently(acc, "test".into()).unwrap();
+
+	let t = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::from(0x9184e72a000u64),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(0x9184e72au64),
+		data: vec![]
+	};
+	let signature = tester.accounts.sign(acc, t.hash()).unwrap();
+	let t = t.with_signature(signature);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_postTransaction",
+		"params": [{
+			"from": ""#.to_owned() + format!("0x{:?}", acc).as_ref() + r#"",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"gas": "0x76c0",
+			"gasPrice": "0x9184e72a000",
+			"value": "0x9184e72a",
+			"localOnly": true
+		}],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:?}", t.hash()).as_ref() + r#"","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+	assert!(tester.miner.is_local_only_transaction(&t.hash()));
+}
+
+#[test]
+fn should_sign_transaction_without_dispatching_if_account_is_unlocked() {
+	// given
+	let tester = eth_signing();
+	let acc = tester.accounts.new_account("test").unwrap();
+	tester.accounts.unlock_account_permanently(acc, "test".into()).unwrap();
+
+	let t = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::from(0x9184e72a000u64),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(0x9184e72au64),
+		data: vec![]
+	};
+	let signature = tester.accounts.sign(acc, t.hash()).unwrap();
+	let t = t.with_signature(signature);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "ethcore_signTransaction",
+		"params": [{
+			"from": ""#.to_owned() + format!("0x{:?}", acc).as_ref() + r#"",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"gas": "0x76c0",
+			"gasPrice": "0x9184e72a000",
+			"value": "0x9184e72a"
+		}],
+		"id": 1
+	}"#;
+	let result = ::serde_json::to_string(&to_value(&RpcTransaction::from(t))).unwrap();
+	let response = r#"{"jsonrpc":"2.0","result":"#.to_owned() + &result + r#","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+	assert_eq!(tester.miner.imported_transactions.lock().len(), 0);
+}
+
+#[test]
+fn should_add_sign_transaction_to_queue() {
+	// given
+	let tester = eth_signing();
+	let address = Address::random();
+	assert_eq!(tester.signer.requests().len(), 0);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "ethcore_signTransaction",
+		"params": [{
+			"from": ""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"gas": "0x76c0",
+			"gasPrice": "0x9184e72a000",
+			"value": "0x9184e72a"
+		}],
+		"id": 1
+	}"#;
+
+	// then
+	let _async_result = tester.io.handle_request(&request).unwrap();
+	let requests = tester.signer.requests();
+	assert_eq!(requests.len(), 1);
+	match requests[0].payload {
+		ConfirmationPayload::SignTransaction(ref request) => assert_eq!(request.from, address),
+		_ => panic!("Expected transaction signing request."),
+	}
+}
@@ -15,15 +15,18 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
+use std::time::Duration;
 use util::log::RotatingLogger;
-use util::U256;
+use util::{Address, U256, H256, Hashable};
 use ethsync::ManageNetwork;
-use ethcore::client::{TestBlockChainClient};
+use ethcore::client::{BlockChainClient, TestBlockChainClient, EachBlockWith, ChainWarning, Executed};
+use ethcore::miner::{ExternalMiner, ExternalMinerService, ShareResult, MinerService, ClockDrift};
 
 use jsonrpc_core::IoHandler;
 use v1::{Ethcore, EthcoreClient};
-use v1::helpers::{SignerService, NetworkSettings};
-use v1::tests::helpers::{TestSyncProvider, Config, TestMinerService, TestFetch};
+use v1::helpers::{SignerService, NetworkSettings, SnapshotStatusProvider};
+use v1::helpers::call_decoder::{ContractRegistry, TokenInfo, method_selector};
+use v1::tests::helpers::{TestSyncProvider, Config, TestMinerService, TestFetch, TestSnapshotService, TestRegistry};
 use super::manage_network::TestManageNetwork;
 
 fn miner_service() -> Arc<TestMinerService> {
@@ -53,6 +56,7 @@ fn settings() -> Arc<NetworkSettings> {
 		rpc_enabled: true,
 		rpc_interface: "all".to_owned(),
 		rpc_port: 8545,
+		features: vec!["dapps".to_owned()],
 	})
 }
 
@@ -60,6 +64,25 @@ fn network_service() -> Arc<ManageNetwork> {
 	Arc::new(TestManageNetwork)
 }
 
+fn external_miner() -> Arc<ExternalMiner> {
+	Arc::new(ExternalMiner::default())
+}
+
+fn snapshot_service() -> Arc<SnapshotStatusProvider> {
+	Arc::new(TestSnapshotService::default())
+}
+
+const WALLET_CODE: &'static [u8] = &[0x60, 0x00];
+
+fn registry() -> Arc<ContractRegistry> {
+	let mut registry = TestRegistry::default();
+	registry.wallet_codes.insert(WALLET_CODE.sha3());
+	registry.abis.insert(Address::from(0x1234), vec!["setOwner(address)".into()]);
+	registry.tokens.insert(Address::from(0x10), TokenInfo { symbol: "AAA".into(), base: U256::from(1000) });
+	registry.tokens.insert(Address::from(0x20), TokenInfo { symbol: "BBB".into(), base: U256::from(1) });
+	Arc::new(registry)
+}
+
 type TestEthcoreClient = EthcoreClient<TestBlockChainClient, TestMinerService, TestSyncProvider, TestFetch>;
 
 fn ethcore_client(
@@ -68,7 +91,7 @@ fn ethcore_client(
 	sync: &Arc<TestSyncProvider>,
 	net: &Arc<ManageNetwork>)
 	-> TestEthcoreClient {
-	EthcoreClient::with_fetch(client, miner, sync, net, logger(), settings(), None)
+	EthcoreClient::with_fetch(client, miner, sync, net, logger(), settings(), external_miner(), None, &snapshot_service(), registry())
 }
 
 #[test]
@@ -143,7 +166,7 @@ fn rpc_ethcore_dev_logs() {
 	let logger = logger();
 	logger.append("a".to_owned());
 	logger.append("b".to_owned());
-	let ethcore: TestEthcoreClient = EthcoreClient::with_fetch(&client, &miner, &sync, &net, logger.clone(), settings(), None);
+	let ethcore: TestEthcoreClient = EthcoreClient::with_fetch(&client, &miner, &sync, &net, logger.clone(), settings(), external_miner(), None, &snapshot_service(), registry());
 	let io = IoHandler::new();
 	io.add_delegate(ethcore.to_delegate());
 
@@ -208,7 +231,7 @@ fn rpc_ethcore_net_peers() {
 	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
 
 	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_netPeers", "params":[], "id": 1}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"active":0,"connected":120,"max":50},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"active":0,"connected":120,"max":50,"peers":[{"capabilities":["eth/63"],"clientVersion":"Parity/v1.4.0","difficulty":"0x28","ethVersion":63,"head":"0x0000000000000000000000000000000000000000000000000000000000000001","id":"node1","outstandingRequests":0}]},"id":1}"#;
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -258,6 +281,24 @@ fn rpc_ethcore_node_name() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_ethcore_version_info() {
+	use util::misc::{sha, commit_date};
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_versionInfo", "params":[], "id": 1}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":{{"date":"{}","features":["dapps"],"forks":{{}},"hash":"{}","version":"{}"}},"id":1}}"#,
+		commit_date(), sha(), env!("CARGO_PKG_VERSION"));
+
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
+
 #[test]
 fn rpc_ethcore_unsigned_transactions_count() {
 	let miner = miner_service();
@@ -266,7 +307,7 @@ fn rpc_ethcore_unsigned_transactions_count() {
 	let net = network_service();
 	let io = IoHandler::new();
 	let signer = Arc::new(SignerService::new_test());
-	let ethcore: TestEthcoreClient = EthcoreClient::with_fetch(&client, &miner, &sync, &net, logger(), settings(), Some(signer));
+	let ethcore: TestEthcoreClient = EthcoreClient::with_fetch(&client, &miner, &sync, &net, logger(), settings(), external_miner(), Some(signer), &snapshot_service(), registry());
 	io.add_delegate(ethcore.to_delegate());
 
 	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_unsignedTransactionsCount", "params":[], "id": 1}"#;
@@ -319,3 +360,752 @@ fn rpc_ethcore_pending_transactions() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_ethcore_next_nonce() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let address = Address::from(1);
+	client.set_nonce(address.clone(), U256::from(5));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_nextNonce", "params":["0x0000000000000000000000000000000000000001"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x5","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	miner.last_nonces.write().insert(address, U256::from(7));
+	let response = r#"{"jsonrpc":"2.0","result":"0x8","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_node_table() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_nodeTable", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":["enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770"],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_net_stats() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+	sync.status.write().transaction_bytes_saved = 4096;
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_netStats", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockPropagation":[{"announcements":12,"averageDelay":250,"id":"node1"}],"bytesReceived":2048,"bytesSent":1024,"downloadThrottled":false,"peers":[{"bytesReceived":2048,"bytesSent":1024,"clientVersion":"Parity/v1.4.0","id":null}],"receiveRate":200,"sendRate":100,"transactionBytesSaved":4096,"uploadThrottled":true},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_net_stats_peers() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_netStatsPeers", "params":[0, 10], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"bytesReceived":2048,"bytesSent":1024,"clientVersion":"Parity/v1.4.0","id":null}],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_netStatsPeers", "params":[1, 10], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_list_storage_keys_without_fat_db() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_listAccounts", "params":["latest"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_listStorageKeys", "params":["0x0000000000000000000000000000000000000001", "0x10"], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_transactions_by_address_without_index() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_transactionsByAddress", "params":["0x0000000000000000000000000000000000000001", 0], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_blocks_with_logs() {
+	let miner = miner_service();
+	let client = client_service();
+	client.add_blocks(3, EachBlockWith::Nothing);
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_blocksWithLogs", "params":[{"fromBlock": "0x1", "toBlock": "latest"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":["0x1","0x2","0x3"],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_state_diff() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_stateDiff", "params":["0x0000000000000000000000000000000000000001", "0x0", "latest"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_node_status() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_nodeStatus", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"awake","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_mode() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_mode", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"active","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_snapshot_status() {
+	use ethcore::snapshot::CreationStatus;
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let snapshot = Arc::new(TestSnapshotService::default());
+	*snapshot.status.lock() = CreationStatus {
+		in_progress: Some(30000),
+		accounts: 100,
+		blocks: 20,
+		size: 4096,
+		snapshots: vec![20000, 10000],
+	};
+	let ethcore: TestEthcoreClient = EthcoreClient::with_fetch(&client, &miner, &sync, &net, logger(), settings(), external_miner(), None, &(snapshot.clone() as Arc<SnapshotStatusProvider>), registry());
+	let io = IoHandler::new();
+	io.add_delegate(ethcore.to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_snapshotStatus", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"accounts":100,"blocks":20,"inProgress":"0x7530","size":4096,"snapshots":["0x4e20","0x2710"]},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_worker_statistics() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let external_miner = external_miner();
+	external_miner.submit_hashrate(U256::from(16), H256::from(1));
+	external_miner.submit_share(H256::from(1), ShareResult::Accepted);
+	external_miner.submit_share(H256::from(1), ShareResult::Stale);
+	let ethcore: TestEthcoreClient = EthcoreClient::with_fetch(&client, &miner, &sync, &net, logger(), settings(), external_miner, None, &snapshot_service(), registry());
+	let io = IoHandler::new();
+	io.add_delegate(ethcore.to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_workerStatistics", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"0x0000000000000000000000000000000000000000000000000000000000000001":{"accepted":1,"hashrate":"0x10","invalid":0,"stale":1}},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_sealing_statistics() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	{
+		let mut stats = miner.sealing_statistics.write();
+		stats.execution.note(Duration::from_millis(3));
+		stats.execution.note(Duration::from_millis(1));
+	}
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_sealingStatistics", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"execution":{"average":2000,"count":2,"last":1000,"max":3000},"import":{"average":0,"count":0,"last":0,"max":0},"sealCheck":{"average":0,"count":0,"last":0,"max":0},"transactionSelection":{"average":0,"count":0,"last":0,"max":0},"workPreparation":{"average":0,"count":0,"last":0,"max":0}},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_chain_status() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	{
+		let mut status = sync.chain_status.write();
+		status.agreeing_peers = 3;
+		status.diverging_peers = 2;
+		status.diverging_block = Some(90);
+		status.split = true;
+	}
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_chainStatus", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"agreeingPeers":3,"divergingBlock":"0x5a","divergingPeers":2,"split":true,"unknownPeers":0},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_chain_warnings() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.chain_warnings.write().push(ChainWarning {
+		block_hash: 5.into(),
+		block_number: 5,
+		reorg_depth: 4,
+		timestamp: 16,
+	});
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_chainWarnings", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000005","blockNumber":"0x5","reorgDepth":4,"timestamp":16}],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_block_queue_status() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_blockQueueStatus", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"maxMemUse":0,"maxQueueSize":0,"memUsed":0,"unverifiedQueueSize":0,"verifiedQueueSize":0,"verifierCount":0,"verifyingQueueSize":0},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_ec_recover() {
+	use ethkey::{Random, Generator, sign};
+	use v1::helpers::dispatch::eth_data_hash;
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let keypair = Random.generate().unwrap();
+	let signature = sign(keypair.secret(), &eth_data_hash(b"hello")).unwrap();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_ecRecover", "params":["0x68656c6c6f", ""#.to_owned() + &format!("0x{}", signature) + r#""], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + &format!("0x{:?}", keypair.address()) + r#"","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(&request), Some(response));
+}
+
+#[test]
+fn rpc_ethcore_compose_transaction() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.set_execution_result(Ok(Executed {
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::from(0x5),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		reverted: false,
+	}));
+	miner.last_nonces.write().insert(Address::from(1), U256::from(7));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_composeTransaction", "params":[{
+		"from": "0x0000000000000000000000000000000000000001",
+		"to": "0x0000000000000000000000000000000000000002",
+		"gasPrice": "0x9184e72a000",
+		"value": "0x9184e72a"
+	}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"data":"0x","from":"0x0000000000000000000000000000000000000001","gas":"0xff35","gasPrice":"0x9184e72a000","nonce":"0x8","to":"0x0000000000000000000000000000000000000002","value":"0x9184e72a"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert!(miner.imported_transactions.lock().is_empty());
+}
+
+#[test]
+fn rpc_ethcore_call() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.set_execution_result(Ok(Executed {
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::from(0x5),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![0x12, 0x34, 0xff],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		reverted: false,
+	}));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_call", "params":[[{
+		"from": "0x0000000000000000000000000000000000000001",
+		"to": "0x0000000000000000000000000000000000000002",
+		"value": "0x9184e72a"
+	}, {
+		"from": "0x0000000000000000000000000000000000000002",
+		"to": "0x0000000000000000000000000000000000000003",
+		"data": "0x01"
+	}], "latest"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":["0x1234ff","0x1234ff"],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_call_rejects_too_many_calls() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let calls = vec![r#"{"to": "0x0000000000000000000000000000000000000002"}"#; 33].join(",");
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "ethcore_call", "params":[[{}], "latest"], "id": 1}}"#, calls);
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: requests","data":"\"At most 32 calls can be executed at once.\""},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_transaction_transfers() {
+	use ethcore::trace::FlatTrace;
+	use ethcore::trace::trace::{Action, Res, Suicide};
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.set_execution_result(Ok(Executed {
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::zero(),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![],
+		trace: vec![FlatTrace {
+			action: Action::Suicide(Suicide {
+				address: Address::from(5),
+				refund_address: Address::from(10),
+				balance: U256::from(100),
+			}),
+			result: Res::None,
+			subtraces: 0,
+			trace_address: Default::default(),
+		}],
+		vm_trace: None,
+		state_diff: None,
+		reverted: false,
+	}));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_transactionTransfers", "params":["0x0000000000000000000000000000000000000000000000000000000000000001"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"from":"0x0000000000000000000000000000000000000005","to":"0x000000000000000000000000000000000000000a","type":"suicide","value":"0x64"}],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_raw_block_and_transaction() {
+	use rustc_serialize::hex::{FromHex, ToHex};
+	use ethcore::client::{BlockChainClient, BlockID};
+	use ethcore::transaction::SignedTransaction;
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.add_blocks(1, EachBlockWith::Nothing);
+	let block = client.block(BlockID::Number(1)).unwrap();
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_getRawBlockByNumber", "params":["0x1"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x"#.to_owned() + &block.to_hex() + r#"","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response));
+
+	let raw = "f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804";
+	let tx: SignedTransaction = ::rlp::decode(&raw.from_hex().unwrap());
+	miner.pending_transactions.lock().insert(H256::from(5), tx);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_getRawTransaction", "params":["0x0000000000000000000000000000000000000000000000000000000000000005"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x"#.to_owned() + raw + r#"","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_getRawTransaction", "params":["0x0000000000000000000000000000000000000000000000000000000000000006"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_estimate_inclusion() {
+	use ethkey::{Generator, Random};
+	use ethcore::transaction::{Transaction, Action};
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.add_blocks(2, EachBlockWith::Transaction);
+	for _ in 0..3 {
+		let keypair = Random.generate().unwrap();
+		let tx = Transaction {
+			action: Action::Call(Address::from(1)),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(400_000),
+			gas_price: U256::from(20_000_000_000u64),
+			nonce: U256::zero(),
+		}.sign(keypair.secret());
+		miner.pending_transactions.lock().insert(tx.hash(), tx);
+	}
+
+	// waits behind the queued transactions paying the same price
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_estimateInclusion", "params": ["0x4a817c800"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blocks":"0x2","seconds":"0x0"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	// outbids the queue
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_estimateInclusion", "params": ["0x6fc23ac00"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blocks":"0x1","seconds":"0x0"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	// below the minimal gas price
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_estimateInclusion", "params": ["0x1"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_decode_call_data() {
+	use rustc_serialize::hex::ToHex;
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let mut data = method_selector("setOwner(address)").to_vec();
+	data.extend_from_slice(&H256::from(Address::from(5)));
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "ethcore_decodeCallData", "params": ["0x0000000000000000000000000000000000001234", "0x{}"], "id": 1}}"#, data.to_hex());
+	let response = r#"{"jsonrpc":"2.0","result":{"name":"setOwner","params":[{"type":"address","value":"0x0000000000000000000000000000000000000005"}],"signature":"setOwner(address)"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+
+	// the method is known only to the contract with ABI
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "ethcore_decodeCallData", "params": ["0x0000000000000000000000000000000000000005", "0x{}"], "id": 1}}"#, data.to_hex());
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+
+	// too short for the signature
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "ethcore_decodeCallData", "params": ["0x0000000000000000000000000000000000001234", "0x{}"], "id": 1}}"#, data[..20].to_hex());
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: data","data":"\"Data doesn't match signature setOwner(address)\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_token_balances() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.set_execution_result(Ok(Executed {
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::from(0x5),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: H256::from(1500).to_vec(),
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		reverted: false,
+	}));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_tokenBalances", "params": ["0x0000000000000000000000000000000000000001"], "id": 1}"#;
+	let response = concat!(
+		r#"{"jsonrpc":"2.0","result":["#,
+		r#"{"balance":"0x5dc","decimals":3,"symbol":"AAA","token":"0x0000000000000000000000000000000000000010"},"#,
+		r#"{"balance":"0x5dc","decimals":0,"symbol":"BBB","token":"0x0000000000000000000000000000000000000020"}"#,
+		r#"],"id":1}"#
+	);
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_wallet_pending_operations() {
+	use util::Uint;
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let mapping_slot = |key: H256, slot: u64| {
+		let mut data = key.to_vec();
+		data.extend_from_slice(&H256::from(slot));
+		data.sha3()
+	};
+	let wallet = Address::from(0x1000);
+	let operation = H256::from(0xabcd);
+	// 2 of 2 wallet with a single operation confirmed by the first owner
+	client.set_code(wallet, WALLET_CODE.to_vec());
+	client.set_storage(wallet, H256::from(0), H256::from(2));
+	client.set_storage(wallet, H256::from(1), H256::from(2));
+	client.set_storage(wallet, H256::from(3), H256::from(Address::from(1)));
+	client.set_storage(wallet, H256::from(4), H256::from(Address::from(2)));
+	client.set_storage(wallet, mapping_slot(H256::from(Address::from(1)), 258), H256::from(1));
+	client.set_storage(wallet, mapping_slot(H256::from(Address::from(2)), 258), H256::from(2));
+	client.set_storage(wallet, H256::from(260), H256::from(1));
+	client.set_storage(wallet, H256::from(260).sha3(), operation);
+	let pending = mapping_slot(operation, 259);
+	client.set_storage(wallet, pending, H256::from(1));
+	client.set_storage(wallet, (U256::from(pending) + U256::one()).into(), H256::from(0b10));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_walletPendingOperations", "params": ["0x0000000000000000000000000000000000001000"], "id": 1}"#;
+	let response = concat!(
+		r#"{"jsonrpc":"2.0","result":[{"confirmedBy":["0x0000000000000000000000000000000000000001"],"#,
+		r#""operation":"0x000000000000000000000000000000000000000000000000000000000000abcd","transaction":null,"yetNeeded":"0x1"}],"id":1}"#
+	);
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_confirmWalletOperation", "params": ["0x0000000000000000000000000000000000001000", "0x0000000000000000000000000000000000000001", "0x000000000000000000000000000000000000000000000000000000000000abcd"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32070,"message":"Operation is already confirmed by 0x0000000000000000000000000000000000000001.","data":null},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_walletPendingOperations", "params": ["0x0000000000000000000000000000000000000005"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32070,"message":"There is no wallet at 0x0000000000000000000000000000000000000005.","data":null},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_compute_contract_address() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_computeContractAddress", "params": ["0x0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6", "0x58"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x3f09c73a5ed19289fb9bdc72f1742566df146f56","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_block_economics() {
+	use ethcore::client::{BlockID, TransactionID};
+	use ethcore::receipt::LocalizedReceipt;
+	use ethcore::views::BlockView;
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.add_blocks(1, EachBlockWith::Transaction);
+	let hash = client.block_hash(BlockID::Number(1)).unwrap();
+	let transaction = BlockView::new(&client.block(BlockID::Number(1)).unwrap()).transactions()[0].hash();
+	client.set_transaction_receipt(TransactionID::Location(BlockID::Hash(hash), 0), LocalizedReceipt {
+		transaction_hash: transaction,
+		transaction_index: 0,
+		block_hash: hash,
+		block_number: 1,
+		cumulative_gas_used: U256::from(21_000),
+		gas_used: U256::from(21_000),
+		contract_address: None,
+		state_root: H256::zero(),
+		logs: vec![],
+	});
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_blockEconomics", "params": ["0x1"], "id": 1}"#;
+	let response = format!(
+		r#"{{"jsonrpc":"2.0","result":{{"blockReward":"0x0","fees":"0x5208","gasLimit":"0xf4240","gasUsed":"0x0","gasUtilization":0.0,"hash":"0x{:?}","minerReward":"0x5208","number":"0x1","transactions":[{{"fee":"0x5208","gasPrice":"0x1","gasUsed":"0x5208","hash":"0x{:?}"}}],"uncleRewards":[]}},"id":1}}"#,
+		hash, transaction
+	);
+	assert_eq!(io.handle_request_sync(request), Some(response));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_blockEconomics", "params": ["0x5"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_uncle_statistics() {
+	use rlp::{RlpStream, NULL_RLP};
+	use ethcore::client::BlockID;
+	use ethcore::header::Header;
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	client.add_blocks(3, EachBlockWith::Uncle);
+	// block sealed by us which lost against block #3, which included it as an uncle
+	let mut orphan = Header::new();
+	orphan.set_difficulty(3.into());
+	orphan.set_parent_hash(client.block_hash(BlockID::Number(2)).unwrap());
+	orphan.set_number(3);
+	let mut rlp = RlpStream::new_list(3);
+	rlp.append(&orphan);
+	rlp.append_raw(&NULL_RLP, 1);
+	rlp.append_raw(&NULL_RLP, 1);
+	client.import_block(rlp.out()).unwrap();
+	*miner.sealed_blocks.write() = vec![client.block_hash(BlockID::Number(3)).unwrap(), orphan.hash(), H256::from(5)];
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_uncleStatistics", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"averageUncleDistance":0.0,"fromBlock":"0x0","orphanedBlocks":"0x1","orphansIncluded":"0x1","sealedBlocks":"0x2","toBlock":"0x3","uncleRate":0.75,"uncles":"0x3"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_node_health() {
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_nodeHealth", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"ntpClockDrift":null,"peerClockDrift":null,"peers":120,"syncing":false},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	miner.set_clock_drift(ClockDrift { ntp: Some(-1500), peers: Some(-2000) });
+	let response = r#"{"jsonrpc":"2.0","result":{"ntpClockDrift":-1500,"peerClockDrift":-2000,"peers":120,"syncing":false},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_memory_stats() {
+	use ethcore::client::{CacheUsage, ClientMemoryUsage};
+
+	let miner = miner_service();
+	let client = client_service();
+	let sync = sync_provider();
+	let net = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_client(&client, &miner, &sync, &net).to_delegate());
+
+	*client.memory_usage.write() = ClientMemoryUsage {
+		blockchain: CacheUsage { used: 100, limit: 200 },
+		state: CacheUsage { used: 300, limit: 400 },
+		block_queue: CacheUsage { used: 0, limit: 500 },
+	};
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_memoryStats", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockQueue":{"limit":500,"used":0},"blockchain":{"limit":200,"used":100},"state":{"limit":400,"used":300},"transactionQueue":{"limit":4194304,"used":1024}},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -17,12 +17,13 @@
 use std::sync::Arc;
 use std::str::FromStr;
 use jsonrpc_core::IoHandler;
-use v1::{EthcoreSet, EthcoreSetClient};
+use v1::{EthcoreSet, EthcoreSetClient, UpdateService};
 use ethcore::miner::MinerService;
-use ethcore::client::TestBlockChainClient;
-use v1::tests::helpers::TestMinerService;
+use ethcore::account_provider::AccountProvider;
+use ethcore::client::{TestBlockChainClient, BlockChainClient, EachBlockWith, BlockID, IpcMode, ChainWarning};
+use v1::tests::helpers::{TestMinerService, TestUpdater};
 use util::{U256, Address};
-use rustc_serialize::hex::FromHex;
+use rustc_serialize::hex::{FromHex, ToHex};
 use super::manage_network::TestManageNetwork;
 use ethsync::ManageNetwork;
 
@@ -39,7 +40,9 @@ fn network_service() -> Arc<TestManageNetwork> {
 }
 
 fn ethcore_set_client(client: &Arc<TestBlockChainClient>, miner: &Arc<TestMinerService>, net: &Arc<TestManageNetwork>) -> EthcoreSetClient<TestBlockChainClient, TestMinerService> {
-	EthcoreSetClient::new(client, miner, &(net.clone() as Arc<ManageNetwork>))
+	let updater = Arc::new(TestUpdater::default()) as Arc<UpdateService>;
+	let accounts = Arc::new(AccountProvider::transient_provider());
+	EthcoreSetClient::new(client, miner, &(net.clone() as Arc<ManageNetwork>), &updater, &accounts)
 }
 
 #[test]
@@ -87,6 +90,21 @@ fn rpc_ethcore_set_extra_data() {
 	assert_eq!(miner.extra_data(), "cd1722f3947def4cf144679da39c4c32bdc35681".from_hex().unwrap());
 }
 
+#[test]
+fn rpc_ethcore_set_extra_data_rejects_oversized() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_set_client(&client, &miner, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_setExtraData", "params":["0x000000000000000000000000000000000000000000000000000000000000000000"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: extra_data","data":"\"Extra data must be at most 32 bytes\""},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(miner.extra_data(), vec![1, 2, 3, 4]);
+}
+
 #[test]
 fn rpc_ethcore_set_author() {
 	let miner = miner_service();
@@ -102,6 +120,30 @@ fn rpc_ethcore_set_author() {
 	assert_eq!(miner.author(), Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap());
 }
 
+#[test]
+fn rpc_ethcore_release_quarantined_block() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_set_client(&client, &miner, &network).to_delegate());
+
+	client.chain_warnings.write().push(ChainWarning {
+		block_hash: 5.into(),
+		block_number: 5,
+		reorg_depth: 4,
+		timestamp: 16,
+	});
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_releaseQuarantinedBlock", "params":["0x0000000000000000000000000000000000000000000000000000000000000005"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert!(client.chain_warnings().is_empty());
+
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_ethcore_set_transactions_limit() {
 	let miner = miner_service();
@@ -115,4 +157,164 @@ fn rpc_ethcore_set_transactions_limit() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 	assert_eq!(miner.transactions_limit(), 10_240_240);
-}
\ No newline at end of file
+}
+
+#[test]
+fn rpc_ethcore_backup_database() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_set_client(&client, &miner, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_backupDatabase", "params":["/backups/parity"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(*client.backups.read(), vec!["/backups/parity".to_owned()]);
+}
+
+#[test]
+fn rpc_ethcore_dev_chain_control() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_set_client(&client, &miner, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_devMineBlocks", "params":[3], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x3","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(client.chain_info().best_block_number, 3);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_devSetBalance", "params":["0x00000000000000000000000000000000000000aa", "0xde0b6b3a7640000"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(client.balances.read()[&Address::from(0xaa)], U256::from(1_000_000_000_000_000_000u64));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_devReset", "params":[], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(client.chain_info().best_block_number, 0);
+	assert!(client.balances.read().is_empty());
+}
+
+#[test]
+fn rpc_ethcore_submit_block() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_set_client(&client, &miner, &network).to_delegate());
+
+	let relay = TestBlockChainClient::default();
+	relay.add_blocks(1, EachBlockWith::Uncle);
+	let block = relay.block(BlockID::Latest).unwrap();
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "ethcore_submitBlock", "params":["0x{}"], "id": 1}}"#, block.to_hex());
+	let response = format!(r#"{{"jsonrpc":"2.0","result":"0x{:?}","id":1}}"#, relay.chain_info().best_block_hash);
+	assert_eq!(io.handle_request_sync(&request), Some(response));
+	assert_eq!(client.chain_info().best_block_hash, relay.chain_info().best_block_hash);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_submitBlock", "params":["0x00"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: Block is not valid RLP","data":"RlpIncorrectListLen"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_set_mode() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let io = IoHandler::new();
+	io.add_delegate(ethcore_set_client(&client, &miner, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_setMode", "params":["passive"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(client.mode(), IpcMode::Passive(300, 3600));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_setMode", "params":["offline"], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(client.mode(), IpcMode::Off);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_setMode", "params":["sleepy"], "id": 1}"#;
+	assert!(io.handle_request_sync(request).unwrap().contains("error"));
+	assert_eq!(client.mode(), IpcMode::Off);
+}
+
+#[test]
+fn rpc_ethcore_upgrade() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service() as Arc<ManageNetwork>;
+	let updater = Arc::new(TestUpdater::with_release("1.4.1", true));
+	let accounts = Arc::new(AccountProvider::transient_provider());
+	let io = IoHandler::new();
+	io.add_delegate(EthcoreSetClient::new(&client, &miner, &network, &(updater.clone() as Arc<UpdateService>), &accounts).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_upgradeReady", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"binary":"0x0000000000000000000000000000000000000000000000000000000000000005","isCritical":true,"version":"1.4.1"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_executeUpgrade", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(updater.installed.lock().as_ref().map(|r| r.version.clone()), Some("1.4.1".to_owned()));
+
+	// nothing left to install
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_upgradeReady", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_executeUpgrade", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_ethcore_set_dapp_addresses() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service() as Arc<ManageNetwork>;
+	let updater = Arc::new(TestUpdater::default()) as Arc<UpdateService>;
+	let accounts = Arc::new(AccountProvider::transient_provider());
+	let address = accounts.new_account("").unwrap();
+	let io = IoHandler::new();
+	io.add_delegate(EthcoreSetClient::new(&client, &miner, &network, &updater, &accounts).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_setDappAddresses", "params":["wallet", [""#.to_owned() + &format!("0x{:?}", address) + r#"", "0x0000000000000000000000000000000000000005"]], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+
+	// accounts not in the store are not exposed.
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_dappAddresses", "params":["wallet"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[""#.to_owned() + &format!("0x{:?}", address) + r#""],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert!(accounts.dapps_addresses("home").unwrap().is_empty());
+}
+
+#[test]
+fn rpc_ethcore_set_dapp_default_address() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service() as Arc<ManageNetwork>;
+	let updater = Arc::new(TestUpdater::default()) as Arc<UpdateService>;
+	let accounts = Arc::new(AccountProvider::transient_provider());
+	let address = accounts.new_account("").unwrap();
+	let io = IoHandler::new();
+	io.add_delegate(EthcoreSetClient::new(&client, &miner, &network, &updater, &accounts).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_dappDefaultAddress", "params":["wallet"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_setDappDefaultAddress", "params":["wallet", ""#.to_owned() + &format!("0x{:?}", address) + r#""], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "ethcore_dappDefaultAddress", "params":["wallet"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + &format!("0x{:?}", address) + r#"","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(accounts.dapps_addresses("wallet").unwrap(), vec![address]);
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethsync::{ManageNetwork, NetworkConfiguration};
+use ethsync::{ManageNetwork, NetworkConfiguration, TrafficStats, PeerTrafficStats};
 
 pub struct TestManageNetwork;
 
@@ -27,4 +27,22 @@ impl ManageNetwork for TestManageNetwork {
 	fn start_network(&self) {}
 	fn stop_network(&self) {}
 	fn network_config(&self) -> NetworkConfiguration { NetworkConfiguration::new_local() }
+	fn node_table(&self) -> Vec<String> { vec!["enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770".into()] }
+	fn traffic_stats(&self) -> TrafficStats {
+		TrafficStats {
+			bytes_received: 2048,
+			bytes_sent: 1024,
+			receive_rate: 200,
+			send_rate: 100,
+			download_throttled: false,
+			upload_throttled: true,
+			peers: vec![PeerTrafficStats {
+				id: None,
+				client_version: "Parity/v1.4.0".into(),
+				bytes_received: 2048,
+				bytes_sent: 1024,
+			}],
+		}
+	}
+	fn peer_clock_drift(&self) -> Option<i64> { Some(-2) }
 }
@@ -17,20 +17,20 @@
 use std::sync::Arc;
 use std::str::FromStr;
 use jsonrpc_core::IoHandler;
-use util::{U256, Uint, Address};
+use util::{U256, Uint, Address, Hashable};
 use ethcore::account_provider::AccountProvider;
-use v1::{PersonalClient, Personal};
+use v1::{PersonalClient, Personal, Scheduler};
 use v1::tests::helpers::TestMinerService;
-use ethcore::client::TestBlockChainClient;
+use v1::helpers::origin::{self, Origin};
+use ethcore::client::{TestBlockChainClient, BlockChainClient, EachBlockWith, ChainNotify};
 use ethcore::transaction::{Action, Transaction};
 
 struct PersonalTester {
 	accounts: Arc<AccountProvider>,
 	io: IoHandler,
 	miner: Arc<TestMinerService>,
-	// these unused fields are necessary to keep the data alive
-	// as the handler has only weak pointers.
-	_client: Arc<TestBlockChainClient>,
+	client: Arc<TestBlockChainClient>,
+	scheduler: Arc<Scheduler<TestBlockChainClient, TestMinerService>>,
 }
 
 fn blockchain_client() -> Arc<TestBlockChainClient> {
@@ -50,7 +50,8 @@ fn setup(signer: Option<u16>) -> PersonalTester {
 	let accounts = accounts_provider();
 	let client = blockchain_client();
 	let miner = miner_service();
-	let personal = PersonalClient::new(&accounts, &client, &miner, signer, false);
+	let scheduler = Arc::new(Scheduler::new(&client, &miner, &accounts));
+	let personal = PersonalClient::new(&accounts, &client, &miner, signer, false, Some(&scheduler));
 
 	let io = IoHandler::new();
 	io.add_delegate(personal.to_delegate());
@@ -59,7 +60,8 @@ fn setup(signer: Option<u16>) -> PersonalTester {
 		accounts: accounts,
 		io: io,
 		miner: miner,
-		_client: client,
+		client: client,
+		scheduler: scheduler,
 	};
 
 	tester
@@ -103,6 +105,25 @@ fn accounts() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn accounts_for_dapps() {
+	let tester = setup(None);
+	let address = tester.accounts.new_account("").unwrap();
+	tester.accounts.new_account("").unwrap();
+	tester.accounts.set_dapps_addresses("wallet".into(), vec![address]).unwrap();
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_listAccounts", "params": [], "id": 1}"#;
+
+	let response = r#"{"jsonrpc":"2.0","result":[""#.to_owned() + &format!("0x{:?}", address) + r#""],"id":1}"#;
+	assert_eq!(origin::with_origin(Origin::Dapp("wallet".into()), || tester.io.handle_request_sync(request)), Some(response.to_owned()));
+
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(origin::with_origin(Origin::Dapps, || tester.io.handle_request_sync(request)), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_accountsCount", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":1,"id":1}"#;
+	assert_eq!(origin::with_origin(Origin::Dapp("wallet".into()), || tester.io.handle_request_sync(request)), Some(response.to_owned()));
+}
+
 #[test]
 fn new_account() {
 	let tester = setup(None);
@@ -118,6 +139,16 @@ fn new_account() {
 	assert_eq!(res, Some(response));
 }
 
+#[test]
+fn new_account_from_secret() {
+	let tester = setup(None);
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_newAccountFromSecret", "params": ["0x0000000000000000000000000000000000000000000000000000000000000001", "pass"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x7e5f4552091a69125d5dfcb7b8c2659029395bdf","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(tester.accounts.accounts().unwrap(), vec![Address::from_str("7e5f4552091a69125d5dfcb7b8c2659029395bdf").unwrap()]);
+}
+
 #[test]
 fn should_be_able_to_get_account_info() {
 	let tester = setup(None);
@@ -136,6 +167,24 @@ fn should_be_able_to_get_account_info() {
 	assert_eq!(res, Some(response));
 }
 
+#[test]
+fn should_page_accounts_info() {
+	let tester = setup(None);
+	tester.accounts.new_account("").unwrap();
+	tester.accounts.new_account("").unwrap();
+	let mut accounts = tester.accounts.accounts().unwrap();
+	accounts.sort();
+	let uuid = tester.accounts.accounts_info().unwrap().get(&accounts[1]).unwrap().uuid.as_ref().unwrap().clone();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_accountsCount", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":2,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_accountsInfo", "params": [1, 5], "id": 1}"#;
+	let response = format!("{{\"jsonrpc\":\"2.0\",\"result\":{{\"0x{}\":{{\"meta\":\"{{}}\",\"name\":\"{}\",\"uuid\":\"{}\"}}}},\"id\":1}}", accounts[1].hex(), uuid, uuid);
+	assert_eq!(tester.io.handle_request_sync(request), Some(response));
+}
+
 #[test]
 fn should_be_able_to_set_name() {
 	let tester = setup(None);
@@ -252,3 +301,95 @@ fn sign_and_send_transaction() {
 
 	assert_eq!(tester.io.handle_request_sync(request.as_ref()), Some(response));
 }
+
+#[test]
+fn should_sign_message_without_prefix_given_password() {
+	let tester = setup(None);
+	let address = tester.accounts.new_account("password123").unwrap();
+	let sign = |password: &str| r#"{"jsonrpc": "2.0", "method": "personal_signMessage", "params": [""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"", ""# + password + r#"", "0x68656c6c6f"], "id": 1}"#;
+
+	let response = tester.io.handle_request_sync(&sign("wrong")).unwrap();
+	assert!(response.contains("\"error\""));
+
+	tester.accounts.unlock_account_temporarily(address, "password123".into()).unwrap();
+	let signature = tester.accounts.sign(address, b"hello".sha3()).unwrap();
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{}", signature).as_ref() + r#"","id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&sign("password123")), Some(response));
+}
+
+#[test]
+fn should_test_and_change_password() {
+	let tester = setup(None);
+	let address = tester.accounts.new_account("old").unwrap();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_testPassword", "params": [""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"", "new"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_changePassword", "params": [""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"", "old", "new"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+
+	assert!(!tester.accounts.test_password(&address, "old").unwrap());
+	assert!(tester.accounts.test_password(&address, "new").unwrap());
+}
+
+#[test]
+fn should_export_account() {
+	let tester = setup(None);
+	let address = tester.accounts.new_account("password").unwrap();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_exportAccount", "params": [""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"", "wrong"], "id": 1}"#;
+	let response = tester.io.handle_request_sync(&request).unwrap();
+	assert!(response.contains("\"error\""));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_exportAccount", "params": [""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"", "password"], "id": 1}"#;
+	let response = tester.io.handle_request_sync(&request).unwrap();
+	assert!(response.contains(&format!("\"address\":\"{:?}\"", address)));
+	assert!(response.contains("\"crypto\""));
+}
+
+#[test]
+fn should_schedule_transaction() {
+	let tester = setup(None);
+	let address = tester.accounts.new_account("password123").unwrap();
+	let schedule = |password: &str| r#"{
+		"jsonrpc": "2.0",
+		"method": "personal_scheduleTransaction",
+		"params": [{
+			"from": ""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"gas": "0x76c0",
+			"value": "0x9184e72a"
+		}, ""# + password + r#"", "0x2", "0x4a817c800"],
+		"id": 1
+	}"#;
+
+	// wrong password is rejected
+	let response = tester.io.handle_request_sync(&schedule("wrong")).unwrap();
+	assert!(response.contains("\"error\""));
+
+	let response = r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&schedule("password123")), Some(response.to_owned()));
+
+	// the transaction is due with the next block
+	tester.client.add_blocks(1, EachBlockWith::Nothing);
+	let best = tester.client.chain_info().best_block_hash;
+	tester.scheduler.new_blocks(vec![best], vec![], vec![best], vec![], vec![], 0);
+	tester.scheduler.flush();
+	assert_eq!(tester.miner.imported_transactions.lock().len(), 1);
+	let t = tester.miner.imported_transactions.lock()[0].clone();
+	assert_eq!(t.sender().unwrap(), address);
+	assert_eq!(t.value, U256::from(0x9184e72au64));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_scheduledTransactions", "params": [], "id": 1}"#;
+	let response = tester.io.handle_request_sync(request).unwrap();
+	assert!(response.contains(r#""id":"0x1""#));
+	assert!(response.contains(r#""nextBlock":"0x3""#));
+	assert!(response.contains(&format!(r#""transactionHash":"0x{:?}""#, t.hash())));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "personal_cancelScheduledTransaction", "params": ["0x1"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.replace("true", "false")));
+}
@@ -16,14 +16,17 @@
 
 use std::sync::Arc;
 use std::str::FromStr;
-use jsonrpc_core::IoHandler;
+use rustc_serialize::hex::FromHex;
+use jsonrpc_core::{IoHandler, to_value};
 use util::{U256, Uint, Address};
 use ethcore::account_provider::AccountProvider;
 use ethcore::client::TestBlockChainClient;
 use ethcore::transaction::{Transaction, Action};
 use v1::{SignerClient, PersonalSigner};
-use v1::tests::helpers::TestMinerService;
+use v1::tests::helpers::{TestMinerService, TestRegistry};
 use v1::helpers::{SigningQueue, SignerService, FilledTransactionRequest, ConfirmationPayload};
+use v1::helpers::call_decoder::{ContractRegistry, TokenInfo};
+use v1::types::Transaction as RpcTransaction;
 
 struct PersonalSignerTester {
 	signer: Arc<SignerService>,
@@ -33,6 +36,7 @@ struct PersonalSignerTester {
 	// these unused fields are necessary to keep the data alive
 	// as the handler has only weak pointers.
 	_client: Arc<TestBlockChainClient>,
+	_registry: Arc<ContractRegistry>,
 }
 
 fn blockchain_client() -> Arc<TestBlockChainClient> {
@@ -53,9 +57,12 @@ fn signer_tester() -> PersonalSignerTester {
 	let accounts = accounts_provider();
 	let client = blockchain_client();
 	let miner = miner_service();
+	let mut registry = TestRegistry::default();
+	registry.tokens.insert(Address::from(0xdead), TokenInfo { symbol: "TKN".into(), base: U256::from(1000) });
+	let registry: Arc<ContractRegistry> = Arc::new(registry);
 
 	let io = IoHandler::new();
-	io.add_delegate(SignerClient::new(&accounts, &client, &miner, &signer).to_delegate());
+	io.add_delegate(SignerClient::new(&accounts, &client, &miner, &signer, &registry).to_delegate());
 
 	PersonalSignerTester {
 		signer: signer,
@@ -63,6 +70,7 @@ fn signer_tester() -> PersonalSignerTester {
 		io: io,
 		miner: miner,
 		_client: client,
+		_registry: registry,
 	}
 }
 
@@ -79,15 +87,18 @@ fn should_return_list_of_items_to_confirm() {
 		value: U256::from(1),
 		data: vec![],
 		nonce: None,
-	})).unwrap();
-	tester.signer.add_request(ConfirmationPayload::Sign(1.into(), 5.into())).unwrap();
+		local_only: false,
+	}), None).unwrap();
+	tester.signer.add_request(ConfirmationPayload::Sign(1.into(), 5.into()), None).unwrap();
 
 	// when
 	let request = r#"{"jsonrpc":"2.0","method":"personal_requestsToConfirm","params":[],"id":1}"#;
 	let response = concat!(
 		r#"{"jsonrpc":"2.0","result":["#,
-		r#"{"id":"0x1","payload":{"transaction":{"data":"0x","from":"0x0000000000000000000000000000000000000001","gas":"0x989680","gasPrice":"0x2710","nonce":null,"to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","value":"0x1"}}},"#,
-		r#"{"id":"0x2","payload":{"sign":{"address":"0x0000000000000000000000000000000000000001","hash":"0x0000000000000000000000000000000000000000000000000000000000000005"}}}"#,
+		r#"{"details":{"method":null,"params":[],"summary":"Send 0.000000000000000001 ETH to 0xd46e8dd67c5d32be8058bb8eb970870f07244567","tokenTransfer":null},"#,
+		r#""id":"0x1","payload":{"transaction":{"data":"0x","from":"0x0000000000000000000000000000000000000001","gas":"0x989680","gasPrice":"0x2710","nonce":null,"to":"0xd46e8dd67c5d32be8058bb8eb970870f07244567","value":"0x1"}}},"#,
+		r#"{"details":{"method":null,"params":[],"summary":"Sign hash 0x0000000000000000000000000000000000000000000000000000000000000005 with account 0x0000000000000000000000000000000000000001","tokenTransfer":null},"#,
+		r#""id":"0x2","payload":{"sign":{"address":"0x0000000000000000000000000000000000000001","hash":"0x0000000000000000000000000000000000000000000000000000000000000005"}}}"#,
 		r#"],"id":1}"#
 	);
 
@@ -95,6 +106,37 @@ fn should_return_list_of_items_to_confirm() {
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
 }
 
+#[test]
+fn should_describe_token_transfer() {
+	// given
+	let tester = signer_tester();
+	let data = "a9059cbb0000000000000000000000000000000000000000000000000000000000000abc00000000000000000000000000000000000000000000000000000000000005dc";
+	tester.signer.add_request(ConfirmationPayload::Transaction(FilledTransactionRequest {
+		from: Address::from(1),
+		to: Some(Address::from(0xdead)),
+		gas_price: U256::from(10_000),
+		gas: U256::from(10_000_000),
+		value: U256::zero(),
+		data: data.from_hex().unwrap(),
+		nonce: None,
+		local_only: false,
+	}), None).unwrap();
+
+	// when
+	let request = r#"{"jsonrpc":"2.0","method":"personal_requestsToConfirm","params":[],"id":1}"#;
+	let response = concat!(
+		r#"{"jsonrpc":"2.0","result":[{"details":{"method":"transfer(address,uint256)","#,
+		r#""params":[{"type":"address","value":"0x0000000000000000000000000000000000000abc"},{"type":"uint256","value":"1500"}],"#,
+		r#""summary":"Send 1.5 TKN to 0x0000000000000000000000000000000000000abc","#,
+		r#""tokenTransfer":{"amount":"1.5","symbol":"TKN","to":"0x0000000000000000000000000000000000000abc","token":"0x000000000000000000000000000000000000dead","value":"0x5dc"}},"#,
+		r#""id":"0x1","payload":{"transaction":{"data":"0xa9059cbb0000000000000000000000000000000000000000000000000000000000000abc00000000000000000000000000000000000000000000000000000000000005dc","#,
+		r#""from":"0x0000000000000000000000000000000000000001","gas":"0x989680","gasPrice":"0x2710","nonce":null,"to":"0x000000000000000000000000000000000000dead","value":"0x0"}}}],"id":1}"#
+	);
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
 
 #[test]
 fn should_reject_transaction_from_queue_without_dispatching() {
@@ -108,7 +150,8 @@ fn should_reject_transaction_from_queue_without_dispatching() {
 		value: U256::from(1),
 		data: vec![],
 		nonce: None,
-	})).unwrap();
+		local_only: false,
+	}), None).unwrap();
 	assert_eq!(tester.signer.requests().len(), 1);
 
 	// when
@@ -133,7 +176,8 @@ fn should_not_remove_transaction_if_password_is_invalid() {
 		value: U256::from(1),
 		data: vec![],
 		nonce: None,
-	})).unwrap();
+		local_only: false,
+	}), None).unwrap();
 	assert_eq!(tester.signer.requests().len(), 1);
 
 	// when
@@ -149,7 +193,7 @@ fn should_not_remove_transaction_if_password_is_invalid() {
 fn should_not_remove_sign_if_password_is_invalid() {
 	// given
 	let tester = signer_tester();
-	tester.signer.add_request(ConfirmationPayload::Sign(0.into(), 5.into())).unwrap();
+	tester.signer.add_request(ConfirmationPayload::Sign(0.into(), 5.into()), None).unwrap();
 	assert_eq!(tester.signer.requests().len(), 1);
 
 	// when
@@ -175,7 +219,8 @@ fn should_confirm_transaction_and_dispatch() {
 		value: U256::from(1),
 		data: vec![],
 		nonce: None,
-	})).unwrap();
+		local_only: false,
+	}), None).unwrap();
 
 	let t = Transaction {
 		nonce: U256::zero(),
@@ -206,6 +251,51 @@ fn should_confirm_transaction_and_dispatch() {
 	assert_eq!(tester.miner.imported_transactions.lock().len(), 1);
 }
 
+#[test]
+fn should_confirm_transaction_signing_without_dispatching() {
+	// given
+	let tester = signer_tester();
+	let address = tester.accounts.new_account("test").unwrap();
+	let recipient = Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap();
+	tester.signer.add_request(ConfirmationPayload::SignTransaction(FilledTransactionRequest {
+		from: address,
+		to: Some(recipient),
+		gas_price: U256::from(10_000),
+		gas: U256::from(10_000_000),
+		value: U256::from(1),
+		data: vec![],
+		nonce: None,
+		local_only: false,
+	}), None).unwrap();
+
+	let t = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::from(0x1000),
+		gas: U256::from(10_000_000),
+		action: Action::Call(recipient),
+		value: U256::from(0x1),
+		data: vec![]
+	};
+	tester.accounts.unlock_account_temporarily(address, "test".into()).unwrap();
+	let signature = tester.accounts.sign(address, t.hash()).unwrap();
+	let t = t.with_signature(signature);
+
+	// when
+	let request = r#"{
+		"jsonrpc":"2.0",
+		"method":"personal_confirmRequest",
+		"params":["0x1", {"gasPrice":"0x1000"}, "test"],
+		"id":1
+	}"#;
+	let result = ::serde_json::to_string(&to_value(&RpcTransaction::from(t))).unwrap();
+	let response = r#"{"jsonrpc":"2.0","result":"#.to_owned() + &result + r#","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+	assert_eq!(tester.signer.requests().len(), 0);
+	assert_eq!(tester.miner.imported_transactions.lock().len(), 0);
+}
+
 #[test]
 fn should_generate_new_token() {
 	// given
@@ -223,3 +313,21 @@ fn should_generate_new_token() {
 	// then
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
 }
+
+#[test]
+fn should_exchange_token_for_credential() {
+	// given
+	let tester = signer_tester();
+
+	// when
+	let request = r#"{
+		"jsonrpc":"2.0",
+		"method":"personal_exchangeAuthorizationToken",
+		"params":["new_token"],
+		"id":1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"credential","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
@@ -0,0 +1,94 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+use jsonrpc_core::IoHandler;
+use util::{U256, Address};
+use ethkey::{Generator, Random};
+use ethcore::transaction::{Transaction, Action};
+use rustc_serialize::hex::ToHex;
+use v1::{Private, PrivateClient, PrivateTransactionService};
+use v1::tests::helpers::TestPrivateService;
+
+fn raw_transaction(action: Action) -> String {
+	let t = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::zero(),
+		gas: U256::from(0x76c0),
+		action: action,
+		value: U256::zero(),
+		data: vec![0x12, 0x34],
+	}.sign(Random.generate().unwrap().secret());
+	::rlp::encode(&t).to_vec().to_hex()
+}
+
+fn private_io(service: &Arc<TestPrivateService>) -> IoHandler {
+	let service = service.clone() as Arc<PrivateTransactionService>;
+	let io = IoHandler::new();
+	io.add_delegate(PrivateClient::new(Some(&service)).to_delegate());
+	io
+}
+
+#[test]
+fn rpc_private_send_transaction() {
+	let service = Arc::new(TestPrivateService::default());
+	let io = private_io(&service);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "private_sendTransaction", "params":["0x"#.to_owned()
+		+ &raw_transaction(Action::Call(Address::from(5))) + r#""], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"contract":"0x0000000000000000000000000000000000000005","stateHash":"0x0000000000000000000000000000000000000000000000000000000000000001","signature":"0x"#.to_owned()
+		+ &[0u8; 65].to_hex() + r#"","transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000002"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(&request), Some(response));
+	assert_eq!(service.sent.lock().len(), 1);
+}
+
+#[test]
+fn rpc_private_call() {
+	let service = Arc::new(TestPrivateService::default());
+	let io = private_io(&service);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "private_call", "params":["0x"#.to_owned()
+		+ &raw_transaction(Action::Call(Address::from(5))) + r#""], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x1234","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+	assert!(service.sent.lock().is_empty());
+}
+
+#[test]
+fn rpc_private_call_fails_for_contract_creation() {
+	let service = Arc::new(TestPrivateService::default());
+	let io = private_io(&service);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "private_call", "params":["0x"#.to_owned()
+		+ &raw_transaction(Action::Create) + r#""], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32090,"message":"Private transaction failed: Not a contract call","data":null},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_private_disabled() {
+	let io = IoHandler::new();
+	io.add_delegate(PrivateClient::new(None).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "private_call", "params":["0x"#.to_owned()
+		+ &raw_transaction(Action::Call(Address::from(5))) + r#""], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32032,"message":"Private transactions are disabled. Run the node with --private-tx to use this API.","data":null},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+}
@@ -0,0 +1,55 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+use jsonrpc_core::IoHandler;
+use ethcore::client::TestBlockChainClient;
+use ethcore::error::CallError;
+use v1::{Traces, TracesClient};
+use v1::tests::helpers::TestMinerService;
+
+fn io(client: &Arc<TestBlockChainClient>) -> IoHandler {
+	let miner = Arc::new(TestMinerService::default());
+	let io = IoHandler::new();
+	io.add_delegate(TracesClient::new(client, &miner).to_delegate());
+	io
+}
+
+#[test]
+fn rpc_trace_replay_transaction_rejects_unknown_trace_type() {
+	let client = Arc::new(TestBlockChainClient::default());
+	let io = io(&client);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "trace_replayTransaction", "params":["0x0000000000000000000000000000000000000000000000000000000000000005", ["trace", "foo"]], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: Unknown trace type","data":"\"foo\""},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_trace_replay_transaction_reports_pruned_state() {
+	let client = Arc::new(TestBlockChainClient::default());
+	let io = io(&client);
+	let request = r#"{"jsonrpc": "2.0", "method": "trace_replayTransaction", "params":["0x0000000000000000000000000000000000000000000000000000000000000005", ["stateDiff"]], "id": 1}"#;
+
+	client.set_execution_result(Err(CallError::TransactionNotFound));
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	client.set_execution_result(Err(CallError::StatePruned));
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive.","data":null},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
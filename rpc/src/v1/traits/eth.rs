@@ -159,9 +159,15 @@ build_rpc_trait! {
 		#[rpc(name = "eth_getWork")]
 		fn work(&self, Trailing<u64>) -> Result<Work, Error>;
 
+		/// Like `eth_getWork`, but blocks until the work differs from the given pow hash
+		/// or the timeout (in milliseconds, at most a minute) passes.
+		#[rpc(async, name = "eth_getWorkChange")]
+		fn work_change(&self, Ready<Work>, H256, u64);
+
 		/// Used for submitting a proof-of-work solution.
+		/// Optional fourth parameter is the miner id used with `eth_submitHashrate`.
 		#[rpc(name = "eth_submitWork")]
-		fn submit_work(&self, H64, H256, H256) -> Result<bool, Error>;
+		fn submit_work(&self, H64, H256, H256, Trailing<H256>) -> Result<bool, Error>;
 
 		/// Used for submitting mining hashrate.
 		#[rpc(name = "eth_submitHashrate")]
@@ -202,6 +208,7 @@ build_rpc_trait! {
 /// Signing methods implementation relying on unlocked accounts.
 pub trait EthSigning: Sized + Send + Sync + 'static {
 	/// Signs the data with given address signature.
+	/// The signed hash is `sha3("\x19Ethereum Signed Message:\n" + len(data) + data)`.
 	fn sign(&self, _: Params, _: Ready);
 
 	/// Posts sign request asynchronously.
@@ -218,6 +225,10 @@ pub trait EthSigning: Sized + Send + Sync + 'static {
 	/// Will return a transaction ID for later use with check_transaction.
 	fn post_transaction(&self, _: Params) -> Result<Value, Error>;
 
+	/// Signs transaction without dispatching it to the network.
+	/// Returns the signed transaction together with its RLP encoding.
+	fn sign_transaction(&self, _: Params, _: Ready);
+
 	/// Checks the progress of a previously posted request (transaction/sign).
 	/// Should be given a valid send_transaction ID.
 	/// Returns the transaction hash, the zero hash (not yet available),
@@ -229,6 +240,11 @@ pub trait EthSigning: Sized + Send + Sync + 'static {
 	/// First parameter is the address with which it is encrypted, second is the ciphertext.
 	fn decrypt_message(&self, _: Params) -> Result<Value, Error>;
 
+	/// Deploys a contract. Parameters are the contract's JSON ABI, its bytecode, constructor arguments
+	/// and the transaction options (like in `eth_sendTransaction`, `to` and `data` are ignored).
+	/// Returns the transaction hash and the address of the created contract.
+	fn deploy_contract(&self, _: Params, _: Ready);
+
 	/// Should be used to convert object to io delegate.
 	fn to_delegate(self) -> IoDelegate<Self> {
 		let mut delegate = IoDelegate::new(Arc::new(self));
@@ -238,6 +254,8 @@ pub trait EthSigning: Sized + Send + Sync + 'static {
 		delegate.add_method("eth_postTransaction", EthSigning::post_transaction);
 		delegate.add_method("eth_checkRequest", EthSigning::check_request);
 		delegate.add_method("ethcore_decryptMessage", EthSigning::decrypt_message);
+		delegate.add_async_method("ethcore_signTransaction", EthSigning::sign_transaction);
+		delegate.add_async_method("ethcore_deployContract", EthSigning::deploy_contract);
 		delegate
 	}
 }
@@ -15,10 +15,11 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Ethcore-specific rpc interface.
+use std::collections::BTreeMap;
 use jsonrpc_core::Error;
 
-use v1::helpers::auto_args::{Wrap, WrapAsync, Ready};
-use v1::types::{H160, H256, H512, U256, Bytes, Peers, Transaction, RpcSettings};
+use v1::helpers::auto_args::{Wrap, WrapAsync, Ready, Trailing};
+use v1::types::{H160, H256, H512, H520, U256, Bytes, BlockNumber, CallRequest, Filter, AccountDiff, Peers, Transaction, TransactionRequest, RpcSettings, BlockQueueStatus, NetStats, ChainWarning, ChainStatus, WorkerStatistics, SealingStatistics, PeerTraffic, VersionInfo, SnapshotStatus, Transfer, InclusionEstimate, DecodedCall, TokenBalance, WalletOperation, BlockEconomics, UncleStatistics, NodeHealth, MemoryStats};
 
 build_rpc_trait! {
 	/// Ethcore-specific rpc interface.
@@ -71,6 +72,10 @@ build_rpc_trait! {
 		#[rpc(name = "ethcore_nodeName")]
 		fn node_name(&self) -> Result<String, Error>;
 
+		/// Returns version, build information and consensus forks supported by the client.
+		#[rpc(name = "ethcore_versionInfo")]
+		fn version_info(&self) -> Result<VersionInfo, Error>;
+
 		/// Returns default extra data
 		#[rpc(name = "ethcore_defaultExtraData")]
 		fn default_extra_data(&self) -> Result<Bytes, Error>;
@@ -79,6 +84,13 @@ build_rpc_trait! {
 		#[rpc(name = "ethcore_gasPriceStatistics")]
 		fn gas_price_statistics(&self) -> Result<Vec<U256>, Error>;
 
+		/// Predicts how many blocks a transaction with given gas price waits before being mined,
+		/// from the gas of queued transactions paying at least as much and the room left in recent
+		/// blocks by transactions outbidding it. Returns null if the price is below the node's
+		/// minimal gas price or recent blocks were filled by higher paying transactions.
+		#[rpc(name = "ethcore_estimateInclusion")]
+		fn estimate_inclusion(&self, U256) -> Result<Option<InclusionEstimate>, Error>;
+
 		/// Returns number of unsigned transactions waiting in the signer queue (if signer enabled)
 		/// Returns error when signer is disabled
 		#[rpc(name = "ethcore_unsignedTransactionsCount")]
@@ -92,28 +104,174 @@ build_rpc_trait! {
 		#[rpc(name = "ethcore_phraseToAddress")]
 		fn phrase_to_address(&self, String) -> Result<H160, Error>;
 
+		/// Returns address of the contract created by given sender (first parameter) with given nonce (second parameter).
+		#[rpc(name = "ethcore_computeContractAddress")]
+		fn compute_contract_address(&self, H160, U256) -> Result<H160, Error>;
+
 		/// Returns the value of the registrar for this network.
 		#[rpc(name = "ethcore_registryAddress")]
 		fn registry_address(&self) -> Result<Option<H160>, Error>;
 
-		/// Returns all addresses if Fat DB is enabled (`--fat-db`), or null if not.
+		/// Returns all addresses at given block (latest by default) if Fat DB is enabled (`--fat-db`), or null if not.
 		#[rpc(name = "ethcore_listAccounts")]
-		fn list_accounts(&self) -> Result<Option<Vec<H160>>, Error>;
+		fn list_accounts(&self, Trailing<BlockNumber>) -> Result<Option<Vec<H160>>, Error>;
 
-		/// Returns all storage keys of the given address (first parameter) if Fat DB is enabled (`--fat-db`),
-		/// or null if not.
+		/// Returns all storage keys of the given address (first parameter) at given block (latest by default)
+		/// if Fat DB is enabled (`--fat-db`), or null if not.
 		#[rpc(name = "ethcore_listStorageKeys")]
-		fn list_storage_keys(&self, H160) -> Result<Option<Vec<H256>>, Error>;
+		fn list_storage_keys(&self, H160, Trailing<BlockNumber>) -> Result<Option<Vec<H256>>, Error>;
+
+		/// Returns balance, nonce, code and storage changes of the given address (first parameter)
+		/// between the second and third block, or null if nothing changed. Without Fat DB
+		/// at most 256 intervening blocks are replayed to find changed storage.
+		#[rpc(name = "ethcore_stateDiff")]
+		fn state_diff(&self, H160, BlockNumber, BlockNumber) -> Result<Option<AccountDiff>, Error>;
+
+		/// Returns given page (second parameter) of transactions sent from or to the given address,
+		/// oldest first, if the address index is enabled (`--address-index`), or null if not.
+		#[rpc(name = "ethcore_transactionsByAddress")]
+		fn transactions_by_address(&self, H160, u64) -> Result<Option<Vec<Transaction>>, Error>;
+
+		/// Returns numbers of blocks whose log blooms match the given filter. Blocks are candidates only,
+		/// logs have to be fetched and matched separately.
+		#[rpc(name = "ethcore_blocksWithLogs")]
+		fn blocks_with_logs(&self, Filter) -> Result<Vec<U256>, Error>;
 
 		/// Encrypt some data with a public key under ECIES.
 		/// First parameter is the 512-byte destination public key, second is the message.
 		#[rpc(name = "ethcore_encryptMessage")]
 		fn encrypt_message(&self, H512, Bytes) -> Result<Bytes, Error>;
 
+		/// Returns the address which produced given `eth_sign` signature (second parameter) of the message (first parameter).
+		#[rpc(name = "ethcore_ecRecover")]
+		fn ec_recover(&self, Bytes, H520) -> Result<H160, Error>;
+
+		/// Fills in nonce, gas and gas price of the transaction request, without signing it.
+		#[rpc(name = "ethcore_composeTransaction")]
+		fn compose_transaction(&self, TransactionRequest) -> Result<TransactionRequest, Error>;
+
+		/// Returns RLP of the block with given number.
+		#[rpc(name = "ethcore_getRawBlockByNumber")]
+		fn raw_block_by_number(&self, BlockNumber) -> Result<Option<Bytes>, Error>;
+
+		/// Returns RLP of the signed transaction with given hash, either mined or still in the queue.
+		#[rpc(name = "ethcore_getRawTransaction")]
+		fn raw_transaction(&self, H256) -> Result<Option<Bytes>, Error>;
+
+		/// Returns fees paid by transactions of the block with given number, rewards issued by the engine
+		/// for the block and its uncles and the block's gas utilization, or null if the block is not found.
+		#[rpc(name = "ethcore_blockEconomics")]
+		fn block_economics(&self, BlockNumber) -> Result<Option<BlockEconomics>, Error>;
+
 		/// Returns all pending transactions from transaction queue.
 		#[rpc(name = "ethcore_pendingTransactions")]
 		fn pending_transactions(&self) -> Result<Vec<Transaction>, Error>;
 
+		/// Returns next nonce that can be used by given address, taking transactions
+		/// waiting in the queue (both pending and future) into account.
+		#[rpc(name = "ethcore_nextNonce")]
+		fn next_nonce(&self, H160) -> Result<U256, Error>;
+
+		/// Returns node status: `awake` or `hibernating` (after being inactive for the configured
+		/// mode timeout). Unlike other calls it does not wake the node up.
+		#[rpc(name = "ethcore_nodeStatus")]
+		fn node_status(&self) -> Result<String, Error>;
+
+		/// Returns sizes of the block verification queue stages and number of verifier threads.
+		#[rpc(name = "ethcore_blockQueueStatus")]
+		fn block_queue_status(&self) -> Result<BlockQueueStatus, Error>;
+
+		/// Returns URLs of nodes in the node table, most reliable first.
+		#[rpc(name = "ethcore_nodeTable")]
+		fn node_table(&self) -> Result<Vec<String>, Error>;
+
+		/// Returns network traffic totals, transfer rates and per-peer byte counts.
+		#[rpc(name = "ethcore_netStats")]
+		fn net_stats(&self) -> Result<NetStats, Error>;
+
+		/// Returns per-peer traffic of at most `limit` (second parameter) peers starting at `offset` (first parameter).
+		#[rpc(name = "ethcore_netStatsPeers")]
+		fn net_stats_peers(&self, u64, u64) -> Result<Vec<PeerTraffic>, Error>;
+
+		/// Returns solution statistics and hashrate of external miners, keyed by miner id.
+		#[rpc(name = "ethcore_workerStatistics")]
+		fn worker_statistics(&self) -> Result<BTreeMap<String, WorkerStatistics>, Error>;
+
+		/// Returns timing breakdown of the block production (in microseconds).
+		#[rpc(name = "ethcore_sealingStatistics")]
+		fn sealing_statistics(&self) -> Result<SealingStatistics, Error>;
+
+		/// Returns number of peers, sync state and last measured offsets of the local clock.
+		#[rpc(name = "ethcore_nodeHealth")]
+		fn node_health(&self) -> Result<NodeHealth, Error>;
+
+		/// Returns memory used by caches of the node and their current limits.
+		#[rpc(name = "ethcore_memoryStats")]
+		fn memory_stats(&self) -> Result<MemoryStats, Error>;
+
+		/// Returns uncle rate and distance over recent blocks together with the number of blocks
+		/// sealed by this node in that window which were orphaned.
+		#[rpc(name = "ethcore_uncleStatistics")]
+		fn uncle_statistics(&self) -> Result<UncleStatistics, Error>;
+
+		/// Returns how many peers follow our chain and whether a chain split is suspected.
+		#[rpc(name = "ethcore_chainStatus")]
+		fn chain_status(&self) -> Result<ChainStatus, Error>;
+
+		/// Returns blocks quarantined because importing them would revert too many blocks.
+		#[rpc(name = "ethcore_chainWarnings")]
+		fn chain_warnings(&self) -> Result<Vec<ChainWarning>, Error>;
+
+		/// Returns current operating mode: `active`, `passive`, `dark` or `offline`.
+		#[rpc(name = "ethcore_mode")]
+		fn mode(&self) -> Result<String, Error>;
+
+		/// Returns the snapshot being taken (if any) and the snapshots kept on disk.
+		#[rpc(name = "ethcore_snapshotStatus")]
+		fn snapshot_status(&self) -> Result<SnapshotStatus, Error>;
+
+		/// Executes the calls one after another on a single copy of the state at given block,
+		/// each one seeing the effects of the previous ones. Returns the output of every call.
+		/// At most 32 calls are accepted at once.
+		#[rpc(name = "ethcore_call")]
+		fn call(&self, Vec<CallRequest>, Trailing<BlockNumber>) -> Result<Vec<Bytes>, Error>;
+
+		/// Replays given transaction and returns all value transfers it made,
+		/// including ones made by internal calls, contract creations and suicides.
+		#[rpc(name = "ethcore_transactionTransfers")]
+		fn transaction_transfers(&self, H256) -> Result<Option<Vec<Transfer>>, Error>;
+
+		/// Decodes call data (second parameter) of the contract at given address using the contract's ABI
+		/// from the local ABI store or method signatures from the on-chain registry.
+		/// Returns null if the method is unknown.
+		#[rpc(name = "ethcore_decodeCallData")]
+		fn decode_call_data(&self, H160, Bytes) -> Result<Option<DecodedCall>, Error>;
+
+		/// Returns balances of the given address in all tokens from the token registry at the latest block.
+		#[rpc(name = "ethcore_tokenBalances")]
+		fn token_balances(&self, H160) -> Result<Vec<TokenBalance>, Error>;
+
+		/// Returns operations of the multi-signature wallet at given address waiting for confirmations,
+		/// decoded from the wallet's storage at the latest block. Only known versions of the wallet code are accepted.
+		#[rpc(name = "ethcore_walletPendingOperations")]
+		fn wallet_pending_operations(&self, H160) -> Result<Vec<WalletOperation>, Error>;
+
+		/// Composes a transaction confirming given operation (third parameter) of the wallet (first parameter)
+		/// by the owner (second parameter). Fails if the owner can't confirm the operation.
+		/// The transaction has to be sent with `eth_sendTransaction`.
+		#[rpc(name = "ethcore_confirmWalletOperation")]
+		fn confirm_wallet_operation(&self, H160, H160, H256) -> Result<TransactionRequest, Error>;
+
+		/// Adds address to the watch list of the connection (signer WebSocket or IPC).
+		/// Transactions and logs touching the address are pushed as `ethcore_addressNotification`.
+		/// Returns false if the address was already watched.
+		#[rpc(name = "ethcore_watchAddress")]
+		fn watch_address(&self, H160) -> Result<bool, Error>;
+
+		/// Removes address from the watch list of the connection. Returns false if it was not watched.
+		#[rpc(name = "ethcore_unwatchAddress")]
+		fn unwatch_address(&self, H160) -> Result<bool, Error>;
+
 		/// Hash a file content under given URL.
 		#[rpc(async, name = "ethcore_hashContent")]
 		fn hash_content(&self, Ready<H256>, String);
@@ -19,7 +19,7 @@
 use jsonrpc_core::Error;
 
 use v1::helpers::auto_args::Wrap;
-use v1::types::{Bytes, H160, U256};
+use v1::types::{Bytes, H160, H256, U256, ReleaseInfo};
 
 build_rpc_trait! {
 	/// Ethcore-specific rpc interface for operations altering the settings.
@@ -75,5 +75,62 @@ build_rpc_trait! {
 		/// Stop the network.
 		#[rpc(name = "ethcore_stopNetwork")]
 		fn stop_network(&self) -> Result<bool, Error>;
+
+		/// Set the operating mode: `active`, `passive`, `dark` or `offline`.
+		#[rpc(name = "ethcore_setMode")]
+		fn set_mode(&self, String) -> Result<bool, Error>;
+
+		/// Imports a block quarantined by the reorganization depth limit, together with its descendants.
+		/// Returns false if no such block is held.
+		#[rpc(name = "ethcore_releaseQuarantinedBlock")]
+		fn release_quarantined_block(&self, H256) -> Result<bool, Error>;
+
+		/// Returns the release downloaded and waiting to be installed if any.
+		#[rpc(name = "ethcore_upgradeReady")]
+		fn upgrade_ready(&self) -> Result<Option<ReleaseInfo>, Error>;
+
+		/// Installs the release waiting to be installed. It's run when the client restarts.
+		#[rpc(name = "ethcore_executeUpgrade")]
+		fn execute_upgrade(&self) -> Result<bool, Error>;
+
+		/// Starts writing a point-in-time copy of the database to given directory on the node's host.
+		/// Returns once the backup is started; its outcome is reported in the node's logs.
+		#[rpc(name = "ethcore_backupDatabase")]
+		fn backup_database(&self, String) -> Result<bool, Error>;
+
+		/// Queues an externally sealed block for import. It's fully verified and, once imported,
+		/// propagated to peers like any other block. Returns the block hash.
+		#[rpc(name = "ethcore_submitBlock")]
+		fn submit_block(&self, Bytes) -> Result<H256, Error>;
+
+		/// Seals given number of empty blocks (at most 1024) on a development chain. Returns the new best block number.
+		#[rpc(name = "ethcore_devMineBlocks")]
+		fn dev_mine_blocks(&self, u64) -> Result<U256, Error>;
+
+		/// Sets the balance of an account on a development chain by sealing a block.
+		#[rpc(name = "ethcore_devSetBalance")]
+		fn dev_set_balance(&self, H160, U256) -> Result<bool, Error>;
+
+		/// Resets a development chain to its genesis block.
+		#[rpc(name = "ethcore_devReset")]
+		fn dev_reset(&self) -> Result<bool, Error>;
+
+		/// Sets the accounts returned by `eth_accounts` to requests of given dapp.
+		/// Replaces accounts previously exposed to it.
+		#[rpc(name = "ethcore_setDappAddresses")]
+		fn set_dapp_addresses(&self, String, Vec<H160>) -> Result<bool, Error>;
+
+		/// Returns the accounts exposed to given dapp.
+		#[rpc(name = "ethcore_dappAddresses")]
+		fn dapp_addresses(&self, String) -> Result<Vec<H160>, Error>;
+
+		/// Sets the account given dapp uses by default. It's listed first by `eth_accounts`
+		/// and exposed to the dapp if it wasn't yet.
+		#[rpc(name = "ethcore_setDappDefaultAddress")]
+		fn set_dapp_default_address(&self, String, H160) -> Result<bool, Error>;
+
+		/// Returns the account given dapp uses by default, if any is exposed to it.
+		#[rpc(name = "ethcore_dappDefaultAddress")]
+		fn dapp_default_address(&self, String) -> Result<Option<H160>, Error>;
 	}
 }
\ No newline at end of file
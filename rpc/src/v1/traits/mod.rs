@@ -22,6 +22,7 @@ pub mod net;
 pub mod personal;
 pub mod ethcore;
 pub mod ethcore_set;
+pub mod private;
 pub mod traces;
 pub mod rpc;
 
@@ -31,6 +32,7 @@ pub use self::net::Net;
 pub use self::personal::{Personal, PersonalSigner};
 pub use self::ethcore::Ethcore;
 pub use self::ethcore_set::EthcoreSet;
+pub use self::private::Private;
 pub use self::traces::Traces;
 pub use self::rpc::Rpc;
 
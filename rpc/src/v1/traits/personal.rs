@@ -32,6 +32,10 @@ pub trait Personal: Sized + Send + Sync + 'static {
 	/// Second parameter is password for the new account.
 	fn new_account_from_phrase(&self, _: Params) -> Result<Value, Error>;
 
+	/// Creates new account from the given secret key.
+	/// Second parameter is password for the new account.
+	fn new_account_from_secret(&self, _: Params) -> Result<Value, Error>;
+
 	/// Creates new account from the given JSON wallet.
 	/// Second parameter is password for the wallet and the new account.
 	fn new_account_from_wallet(&self, params: Params) -> Result<Value, Error>;
@@ -39,9 +43,22 @@ pub trait Personal: Sized + Send + Sync + 'static {
 	/// Unlocks specified account for use (can only be one unlocked account at one moment)
 	fn unlock_account(&self, _: Params) -> Result<Value, Error>;
 
+	/// Returns `true` if given password unlocks the account.
+	fn test_password(&self, _: Params) -> Result<Value, Error>;
+
+	/// Changes account password. Params are the account, current and new password.
+	fn change_password(&self, _: Params) -> Result<Value, Error>;
+
+	/// Returns the encrypted JSON key file of the account, given its password.
+	fn export_account(&self, _: Params) -> Result<Value, Error>;
+
 	/// Sends transaction and signs it in single call. The account is not unlocked in such case.
 	fn sign_and_send_transaction(&self, _: Params) -> Result<Value, Error>;
 
+	/// Signs `sha3(data)` without prefixing the message. Params are the account, its password and the data.
+	/// Unlike `eth_sign` the signed hash might be a hash of a transaction, so the password is always required.
+	fn sign_message(&self, _: Params) -> Result<Value, Error>;
+
 	/// Returns `true` if Trusted Signer is enabled, `false` otherwise.
 	fn signer_enabled(&self, _: Params) -> Result<Value, Error>;
 
@@ -51,15 +68,31 @@ pub trait Personal: Sized + Send + Sync + 'static {
 	/// Set an account's metadata string.
 	fn set_account_meta(&self, _: Params) -> Result<Value, Error>;
 
-	/// Returns accounts information.
+	/// Returns accounts information, ordered by address.
+	/// Optional parameters are offset and maximal number of returned accounts.
 	fn accounts_info(&self, _: Params) -> Result<Value, Error>;
 
+	/// Returns number of accounts listed by `personal_accountsInfo`.
+	fn accounts_count(&self, _: Params) -> Result<Value, Error>;
+
 	/// Returns the accounts available for importing from Geth.
 	fn geth_accounts(&self, _: Params) -> Result<Value, Error>;
 
 	/// Imports a number of Geth accounts, with the list provided as the argument.
 	fn import_geth_accounts(&self, _: Params) -> Result<Value, Error>;
 
+	/// Registers a transaction the node signs and sends every given number of blocks.
+	/// Params are the transaction template, the sender's password, the interval in blocks
+	/// and the maximal gas price. Returns id of the scheduled transaction.
+	/// Schedules are kept in memory only and don't survive a restart of the node.
+	fn schedule_transaction(&self, _: Params) -> Result<Value, Error>;
+
+	/// Removes scheduled transaction with given id.
+	fn cancel_scheduled_transaction(&self, _: Params) -> Result<Value, Error>;
+
+	/// Returns scheduled transactions together with their recent executions.
+	fn scheduled_transactions(&self, _: Params) -> Result<Value, Error>;
+
 	/// Should be used to convert object to io delegate.
 	fn to_delegate(self) -> IoDelegate<Self> {
 		let mut delegate = IoDelegate::new(Arc::new(self));
@@ -67,14 +100,23 @@ pub trait Personal: Sized + Send + Sync + 'static {
 		delegate.add_method("personal_listAccounts", Personal::accounts);
 		delegate.add_method("personal_newAccount", Personal::new_account);
 		delegate.add_method("personal_newAccountFromPhrase", Personal::new_account_from_phrase);
+		delegate.add_method("personal_newAccountFromSecret", Personal::new_account_from_secret);
 		delegate.add_method("personal_newAccountFromWallet", Personal::new_account_from_wallet);
 		delegate.add_method("personal_unlockAccount", Personal::unlock_account);
+		delegate.add_method("personal_testPassword", Personal::test_password);
+		delegate.add_method("personal_changePassword", Personal::change_password);
+		delegate.add_method("personal_exportAccount", Personal::export_account);
 		delegate.add_method("personal_signAndSendTransaction", Personal::sign_and_send_transaction);
+		delegate.add_method("personal_signMessage", Personal::sign_message);
 		delegate.add_method("personal_setAccountName", Personal::set_account_name);
 		delegate.add_method("personal_setAccountMeta", Personal::set_account_meta);
 		delegate.add_method("personal_accountsInfo", Personal::accounts_info);
+		delegate.add_method("personal_accountsCount", Personal::accounts_count);
 		delegate.add_method("personal_listGethAccounts", Personal::geth_accounts);
 		delegate.add_method("personal_importGethAccounts", Personal::import_geth_accounts);
+		delegate.add_method("personal_scheduleTransaction", Personal::schedule_transaction);
+		delegate.add_method("personal_cancelScheduledTransaction", Personal::cancel_scheduled_transaction);
+		delegate.add_method("personal_scheduledTransactions", Personal::scheduled_transactions);
 
 		delegate
 	}
@@ -95,6 +137,9 @@ pub trait PersonalSigner: Sized + Send + Sync + 'static {
 	/// Generates new authorization token.
 	fn generate_token(&self, _: Params) -> Result<Value, Error>;
 
+	/// Exchanges one-time authorization token for a persistent credential.
+	fn exchange_token(&self, _: Params) -> Result<Value, Error>;
+
 	/// Should be used to convert object to io delegate.
 	fn to_delegate(self) -> IoDelegate<Self> {
 		let mut delegate = IoDelegate::new(Arc::new(self));
@@ -102,6 +147,7 @@ pub trait PersonalSigner: Sized + Send + Sync + 'static {
 		delegate.add_method("personal_confirmRequest", PersonalSigner::confirm_request);
 		delegate.add_method("personal_rejectRequest", PersonalSigner::reject_request);
 		delegate.add_method("personal_generateAuthorizationToken", PersonalSigner::generate_token);
+		delegate.add_method("personal_exchangeAuthorizationToken", PersonalSigner::exchange_token);
 		delegate
 	}
 }
@@ -0,0 +1,42 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Private transactions rpc interface.
+
+use jsonrpc_core::Error;
+
+use v1::helpers::auto_args::Wrap;
+use v1::types::{Bytes, H160, PrivateCommitment};
+
+build_rpc_trait! {
+	/// Private transactions rpc interface.
+	pub trait Private {
+		/// Generates the key of a private contract held by the contract at given address
+		/// and publishes its initial state with given code.
+		#[rpc(name = "private_initialize")]
+		fn initialize(&self, H160, Bytes) -> Result<PrivateCommitment, Error>;
+
+		/// Executes the signed transaction on the decrypted state of the private contract
+		/// and publishes the resulting state, encrypted and signed by the node.
+		#[rpc(name = "private_sendTransaction")]
+		fn send_transaction(&self, Bytes) -> Result<PrivateCommitment, Error>;
+
+		/// Executes the signed transaction on the decrypted state of the private contract
+		/// without publishing the resulting state. Returns the output.
+		#[rpc(name = "private_call")]
+		fn call(&self, Bytes) -> Result<Bytes, Error>;
+	}
+}
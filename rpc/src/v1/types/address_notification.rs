@@ -0,0 +1,49 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use v1::types::{H160, H256, U256};
+
+/// What touched the watched address.
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub enum AddressNotificationType {
+	/// Transaction sent from or to the address
+	#[serde(rename="transaction")]
+	Transaction,
+	/// Log emitted by the address
+	#[serde(rename="log")]
+	Log,
+}
+
+/// Watched address touched by a block entering or leaving the canonical chain.
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct AddressNotification {
+	/// Notification type
+	#[serde(rename="type")]
+	pub notification_type: AddressNotificationType,
+	/// Watched address
+	pub address: H160,
+	/// Block hash
+	#[serde(rename="blockHash")]
+	pub block_hash: H256,
+	/// Block number
+	#[serde(rename="blockNumber")]
+	pub block_number: U256,
+	/// Hash of the transaction which touched the address
+	#[serde(rename="transactionHash")]
+	pub transaction_hash: H256,
+	/// Whether the block was retracted from the canonical chain
+	pub removed: bool,
+}
@@ -14,11 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
 use serde::{Serialize, Serializer};
 use v1::types::{Bytes, Transaction, H160, H256, H2048, U256};
 
 /// Block Transactions
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BlockTransactions {
 	/// Only hashes
 	Hashes(Vec<H256>),
@@ -37,7 +38,7 @@ impl Serialize for BlockTransactions {
 }
 
 /// Block representation
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Block {
 	/// Hash of the block
 	pub hash: Option<H256>,
@@ -85,6 +86,9 @@ pub struct Block {
 	/// Seal fields
 	#[serde(rename="sealFields")]
 	pub seal_fields: Vec<Bytes>,
+	/// Engine-specific interpretation of the seal fields
+	#[serde(rename="sealInfo", skip_serializing_if="BTreeMap::is_empty")]
+	pub seal_info: BTreeMap<String, String>,
 	/// Uncles' hashes
 	pub uncles: Vec<H256>,
 	/// Transactions
@@ -95,6 +99,7 @@ pub struct Block {
 
 #[cfg(test)]
 mod tests {
+	use std::collections::BTreeMap;
 	use serde_json;
 	use v1::types::{Transaction, H160, H256, H2048, Bytes, U256};
 	use super::{Block, BlockTransactions};
@@ -130,6 +135,7 @@ mod tests {
 			difficulty: U256::default(),
 			total_difficulty: U256::default(),
 			seal_fields: vec![Bytes::default(), Bytes::default()],
+			seal_info: BTreeMap::new(),
 			uncles: vec![],
 			transactions: BlockTransactions::Hashes(vec![].into()),
 			size: Some(69.into()),
@@ -138,4 +144,36 @@ mod tests {
 		let serialized = serde_json::to_string(&block).unwrap();
 		assert_eq!(serialized, r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000000","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","sha3Uncles":"0x0000000000000000000000000000000000000000000000000000000000000000","author":"0x0000000000000000000000000000000000000000","miner":"0x0000000000000000000000000000000000000000","stateRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","receiptsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","number":"0x0","gasUsed":"0x0","gasLimit":"0x0","extraData":"0x","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","timestamp":"0x0","difficulty":"0x0","totalDifficulty":"0x0","sealFields":["0x","0x"],"uncles":[],"transactions":[],"size":"0x45"}"#);
 	}
+
+	#[test]
+	fn test_serialize_block_with_seal_info() {
+		let mut seal_info = BTreeMap::new();
+		seal_info.insert("signer".to_owned(), "0x0000000000000000000000000000000000000000".to_owned());
+		let block = Block {
+			hash: None,
+			parent_hash: H256::default(),
+			uncles_hash: H256::default(),
+			author: H160::default(),
+			miner: H160::default(),
+			state_root: H256::default(),
+			transactions_root: H256::default(),
+			receipts_root: H256::default(),
+			number: None,
+			gas_used: U256::default(),
+			gas_limit: U256::default(),
+			extra_data: Bytes::default(),
+			logs_bloom: H2048::default(),
+			timestamp: U256::default(),
+			difficulty: U256::default(),
+			total_difficulty: U256::default(),
+			seal_fields: vec![],
+			seal_info: seal_info,
+			uncles: vec![],
+			transactions: BlockTransactions::Hashes(vec![].into()),
+			size: None,
+		};
+
+		let serialized = serde_json::to_string(&block).unwrap();
+		assert!(serialized.contains(r#""sealFields":[],"sealInfo":{"signer":"0x0000000000000000000000000000000000000000"},"uncles":[]"#));
+	}
 }
@@ -0,0 +1,64 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use v1::types::{H256, U256};
+
+/// Fee paid by a transaction in the block.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TransactionFee {
+	/// Transaction hash
+	pub hash: H256,
+	/// Gas used by the transaction
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+	/// Gas price
+	#[serde(rename="gasPrice")]
+	pub gas_price: U256,
+	/// Fee paid to the block author
+	pub fee: U256,
+}
+
+/// Fees and rewards of a block.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BlockEconomics {
+	/// Block number
+	pub number: U256,
+	/// Block hash
+	pub hash: H256,
+	/// Gas used by all transactions
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+	/// Gas limit
+	#[serde(rename="gasLimit")]
+	pub gas_limit: U256,
+	/// Used gas as a percentage of the gas limit
+	#[serde(rename="gasUtilization")]
+	pub gas_utilization: f64,
+	/// Sum of transaction fees
+	pub fees: U256,
+	/// Reward issued to the block author, including the reward for uncles
+	#[serde(rename="blockReward")]
+	pub block_reward: U256,
+	/// Total income of the block author: block reward and fees
+	#[serde(rename="minerReward")]
+	pub miner_reward: U256,
+	/// Rewards issued to authors of uncles
+	#[serde(rename="uncleRewards")]
+	pub uncle_rewards: Vec<U256>,
+	/// Fees of all transactions
+	pub transactions: Vec<TransactionFee>,
+}
@@ -0,0 +1,45 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethcore::client::ChainWarning as EthChainWarning;
+use v1::types::{H256, U256};
+
+/// Block quarantined by the reorganization depth limit.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ChainWarning {
+	/// Block hash
+	#[serde(rename="blockHash")]
+	pub block_hash: H256,
+	/// Block number
+	#[serde(rename="blockNumber")]
+	pub block_number: U256,
+	/// Number of canonical blocks the reorganization would revert
+	#[serde(rename="reorgDepth")]
+	pub reorg_depth: u64,
+	/// Time the block was quarantined at
+	pub timestamp: u64,
+}
+
+impl From<EthChainWarning> for ChainWarning {
+	fn from(w: EthChainWarning) -> Self {
+		ChainWarning {
+			block_hash: w.block_hash.into(),
+			block_number: w.block_number.into(),
+			reorg_depth: w.reorg_depth,
+			timestamp: w.timestamp,
+		}
+	}
+}
@@ -27,6 +27,12 @@ pub struct ConfirmationRequest {
 	pub id: U256,
 	/// Payload
 	pub payload: ConfirmationPayload,
+	/// Dapp that sent the request
+	#[serde(skip_serializing_if="Option::is_none")]
+	pub origin: Option<String>,
+	/// Human-readable description of the request
+	#[serde(skip_serializing_if="Option::is_none")]
+	pub details: Option<RequestDetails>,
 }
 
 impl From<helpers::ConfirmationRequest> for ConfirmationRequest {
@@ -34,10 +40,51 @@ impl From<helpers::ConfirmationRequest> for ConfirmationRequest {
 		ConfirmationRequest {
 			id: c.id.into(),
 			payload: c.payload.into(),
+			origin: c.origin,
+			details: None,
 		}
 	}
 }
 
+/// Human-readable description of the request
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct RequestDetails {
+	/// Short summary, e.g. `Send 10 GNT to 0x...`
+	pub summary: String,
+	/// Signature of the called method (if known)
+	pub method: Option<String>,
+	/// Decoded arguments of the called method
+	pub params: Vec<DecodedParam>,
+	/// Details of the token transfer
+	#[serde(rename="tokenTransfer")]
+	pub token_transfer: Option<TokenTransfer>,
+}
+
+/// Decoded argument of a method call
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct DecodedParam {
+	/// ABI type of the argument
+	#[serde(rename="type")]
+	pub kind: String,
+	/// Value of the argument
+	pub value: String,
+}
+
+/// Token transfer
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+pub struct TokenTransfer {
+	/// Token contract
+	pub token: H160,
+	/// Token symbol (if registered)
+	pub symbol: Option<String>,
+	/// Recipient
+	pub to: H160,
+	/// Transferred value in base units
+	pub value: U256,
+	/// Transferred value in whole tokens (if token is registered)
+	pub amount: Option<String>,
+}
+
 /// Sign request
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
 pub struct SignRequest {
@@ -56,6 +103,9 @@ pub enum ConfirmationPayload {
 	/// Signature
 	#[serde(rename="sign")]
 	Sign(SignRequest),
+	/// Transaction to sign without dispatching
+	#[serde(rename="signTransaction")]
+	SignTransaction(TransactionRequest),
 }
 
 impl From<helpers::ConfirmationPayload> for ConfirmationPayload {
@@ -66,6 +116,7 @@ impl From<helpers::ConfirmationPayload> for ConfirmationPayload {
 				address: address.into(),
 				hash: hash.into(),
 			}),
+			helpers::ConfirmationPayload::SignTransaction(t) => ConfirmationPayload::SignTransaction(t.into()),
 		}
 	}
 }
@@ -92,11 +143,12 @@ mod tests {
 		let request = helpers::ConfirmationRequest {
 			id: 15.into(),
 			payload: helpers::ConfirmationPayload::Sign(1.into(), 5.into()),
+			origin: Some("wallet".into()),
 		};
 
 		// when
 		let res = serde_json::to_string(&ConfirmationRequest::from(request));
-		let expected = r#"{"id":"0xf","payload":{"sign":{"address":"0x0000000000000000000000000000000000000001","hash":"0x0000000000000000000000000000000000000000000000000000000000000005"}}}"#;
+		let expected = r#"{"id":"0xf","payload":{"sign":{"address":"0x0000000000000000000000000000000000000001","hash":"0x0000000000000000000000000000000000000000000000000000000000000005"}},"origin":"wallet"}"#;
 
 		// then
 		assert_eq!(res.unwrap(), expected.to_owned());
@@ -115,7 +167,9 @@ mod tests {
 				value: 100_000.into(),
 				data: vec![1, 2, 3],
 				nonce: Some(1.into()),
+				local_only: false,
 			}),
+			origin: None,
 		};
 
 		// when
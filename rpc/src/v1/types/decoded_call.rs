@@ -0,0 +1,29 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use v1::types::DecodedParam;
+
+/// Call of a contract method decoded using its ABI or the signature registry.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DecodedCall {
+	/// Method name
+	pub name: String,
+	/// Full method signature, e.g. `transfer(address,uint256)`
+	pub signature: String,
+	/// Decoded arguments
+	pub params: Vec<DecodedParam>,
+}
@@ -62,6 +62,9 @@ pub struct Filter {
 	/// To Block
 	#[serde(rename="toBlock")]
 	pub to_block: Option<BlockNumber>,
+	/// Block hash. Can't be used together with `from_block` and `to_block`.
+	#[serde(rename="blockHash")]
+	pub block_hash: Option<H256>,
 	/// Address
 	pub address: Option<FilterAddress>,
 	/// Topics
@@ -70,11 +73,29 @@ pub struct Filter {
 	pub limit: Option<usize>,
 }
 
+impl Filter {
+	/// Returns true if both the block hash and a block range are given.
+	pub fn is_ambiguous(&self) -> bool {
+		self.block_hash.is_some() && (self.from_block.is_some() || self.to_block.is_some())
+	}
+}
+
 impl Into<EthFilter> for Filter {
 	fn into(self) -> EthFilter {
+		let (from_block, to_block) = match self.block_hash {
+			Some(hash) => {
+				let id = BlockID::Hash(hash.into());
+				(id.clone(), id)
+			},
+			None => (
+				self.from_block.map_or_else(|| BlockID::Latest, Into::into),
+				self.to_block.map_or_else(|| BlockID::Latest, Into::into),
+			),
+		};
+
 		EthFilter {
-			from_block: self.from_block.map_or_else(|| BlockID::Latest, Into::into),
-			to_block: self.to_block.map_or_else(|| BlockID::Latest, Into::into),
+			from_block: from_block,
+			to_block: to_block,
 			address: self.address.and_then(|address| match address {
 				VariadicValue::Null => None,
 				VariadicValue::Single(a) => Some(vec![a.into()]),
@@ -151,6 +172,7 @@ mod tests {
 		assert_eq!(deserialized, Filter {
 			from_block: Some(BlockNumber::Earliest),
 			to_block: Some(BlockNumber::Latest),
+			block_hash: None,
 			address: None,
 			topics: None,
 			limit: None,
@@ -162,6 +184,7 @@ mod tests {
 		let filter = Filter {
 			from_block: Some(BlockNumber::Earliest),
 			to_block: Some(BlockNumber::Latest),
+			block_hash: None,
 			address: Some(VariadicValue::Multiple(vec![])),
 			topics: Some(vec![
 				VariadicValue::Null,
@@ -185,4 +208,19 @@ mod tests {
 			limit: None,
 		});
 	}
+
+	#[test]
+	fn filter_conversion_with_block_hash() {
+		let s = r#"{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000005"}"#;
+		let filter: Filter = serde_json::from_str(s).unwrap();
+		assert!(!filter.is_ambiguous());
+
+		let eth_filter: EthFilter = filter.into();
+		assert_eq!(eth_filter.from_block, BlockID::Hash(5.into()));
+		assert_eq!(eth_filter.to_block, BlockID::Hash(5.into()));
+
+		let s = r#"{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000005","fromBlock":"latest"}"#;
+		let filter: Filter = serde_json::from_str(s).unwrap();
+		assert!(filter.is_ambiguous());
+	}
 }
@@ -0,0 +1,43 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Result of a single node health check.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HealthCheck {
+	/// Whether the check passed.
+	pub healthy: bool,
+	/// Human-readable details of the check.
+	pub details: String,
+}
+
+/// Node health reported by `/api/health`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Health {
+	/// Node is connected to some peers.
+	pub peers: HealthCheck,
+	/// Node is close enough to the head of the chain.
+	pub sync: HealthCheck,
+	/// Transaction queue is accepting new transactions.
+	#[serde(rename="txQueue")]
+	pub tx_queue: HealthCheck,
+}
+
+impl Health {
+	/// Returns true if all the checks have passed.
+	pub fn is_healthy(&self) -> bool {
+		self.peers.healthy && self.sync.healthy && self.tx_queue.healthy
+	}
+}
@@ -0,0 +1,51 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use ethcore::client::CacheUsage as EthCacheUsage;
+
+/// Memory used by a cache and its limit, in bytes.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct CacheUsage {
+	/// Used bytes
+	pub used: usize,
+	/// Limit
+	pub limit: usize,
+}
+
+impl From<EthCacheUsage> for CacheUsage {
+	fn from(usage: EthCacheUsage) -> Self {
+		CacheUsage {
+			used: usage.used,
+			limit: usage.limit,
+		}
+	}
+}
+
+/// Memory used by caches of the node.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct MemoryStats {
+	/// Blockchain cache
+	pub blockchain: CacheUsage,
+	/// State trie node cache
+	pub state: CacheUsage,
+	/// Block queue
+	#[serde(rename="blockQueue")]
+	pub block_queue: CacheUsage,
+	/// Transaction queue
+	#[serde(rename="transactionQueue")]
+	pub transaction_queue: CacheUsage,
+}
@@ -0,0 +1,31 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+/// Overview of the node's connectivity and clock.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct NodeHealth {
+	/// Number of connected peers
+	pub peers: usize,
+	/// Whether the node is catching up with the chain
+	pub syncing: bool,
+	/// Offset of the local clock against NTP in milliseconds, positive if the local clock is ahead
+	#[serde(rename="ntpClockDrift")]
+	pub ntp_clock_drift: Option<i64>,
+	/// Estimated offset of the local clock against clocks of peers in milliseconds
+	#[serde(rename="peerClockDrift")]
+	pub peer_clock_drift: Option<i64>,
+}
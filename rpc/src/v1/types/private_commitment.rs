@@ -0,0 +1,44 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use v1::helpers::PrivateCommitment as HelperPrivateCommitment;
+use v1::types::{H160, H256, H520};
+
+/// State of a private contract published by the node.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct PrivateCommitment {
+	/// Address of the contract holding the encrypted state
+	pub contract: H160,
+	/// Hash of the encrypted state
+	#[serde(rename="stateHash")]
+	pub state_hash: H256,
+	/// Node's signature of the state hash
+	pub signature: H520,
+	/// Hash of the transaction publishing the state
+	#[serde(rename="transactionHash")]
+	pub transaction_hash: H256,
+}
+
+impl From<HelperPrivateCommitment> for PrivateCommitment {
+	fn from(c: HelperPrivateCommitment) -> Self {
+		PrivateCommitment {
+			contract: c.contract.into(),
+			state_hash: c.state_hash.into(),
+			signature: c.signature.into(),
+			transaction_hash: c.transaction_hash.into(),
+		}
+	}
+}
@@ -18,7 +18,7 @@ use v1::types::{Log, H160, H256, U256};
 use ethcore::receipt::{Receipt as EthReceipt, RichReceipt, LocalizedReceipt};
 
 /// Receipt
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Receipt {
 	/// Transaction Hash
 	#[serde(rename="transactionHash")]
@@ -43,6 +43,9 @@ pub struct Receipt {
 	pub contract_address: Option<H160>,
 	/// Logs
 	pub logs: Vec<Log>,
+	/// State root after executing the transaction
+	#[serde(rename="root")]
+	pub state_root: Option<H256>,
 }
 
 impl From<LocalizedReceipt> for Receipt {
@@ -56,21 +59,30 @@ impl From<LocalizedReceipt> for Receipt {
 			gas_used: Some(r.gas_used.into()),
 			contract_address: r.contract_address.map(Into::into),
 			logs: r.logs.into_iter().map(Into::into).collect(),
+			state_root: Some(r.state_root.into()),
 		}
 	}
 }
 
 impl From<RichReceipt> for Receipt {
 	fn from(r: RichReceipt) -> Self {
+		let transaction_hash: H256 = r.transaction_hash.into();
+		let transaction_index: U256 = r.transaction_index.into();
 		Receipt {
-			transaction_hash: Some(r.transaction_hash.into()),
-			transaction_index: Some(r.transaction_index.into()),
+			transaction_hash: Some(transaction_hash.clone()),
+			transaction_index: Some(transaction_index.clone()),
 			block_hash: None,
 			block_number: None,
 			cumulative_gas_used: r.cumulative_gas_used.into(),
 			gas_used: Some(r.gas_used.into()),
 			contract_address: r.contract_address.map(Into::into),
-			logs: r.logs.into_iter().map(Into::into).collect(),
+			logs: r.logs.into_iter().map(|l| {
+				let mut log = Log::from(l);
+				log.transaction_hash = Some(transaction_hash.clone());
+				log.transaction_index = Some(transaction_index.clone());
+				log
+			}).collect(),
+			state_root: Some(r.state_root.into()),
 		}
 	}
 }
@@ -86,6 +98,7 @@ impl From<EthReceipt> for Receipt {
 			gas_used: None,
 			contract_address: None,
 			logs: r.logs.into_iter().map(Into::into).collect(),
+			state_root: Some(r.state_root.into()),
 		}
 	}
 }
@@ -98,7 +111,7 @@ mod tests {
 
 	#[test]
 	fn receipt_serialization() {
-		let s = r#"{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","cumulativeGasUsed":"0x20","gasUsed":"0x10","contractAddress":null,"logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"data":"0x","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","logIndex":"0x1","type":"mined"}]}"#;
+		let s = r#"{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","cumulativeGasUsed":"0x20","gasUsed":"0x10","contractAddress":null,"logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"data":"0x","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","logIndex":"0x1","type":"mined"}],"root":"0x000000000000000000000000000000000000000000000000000000000000000a"}"#;
 
 		let receipt = Receipt {
 			transaction_hash: Some(H256::from(0)),
@@ -121,7 +134,8 @@ mod tests {
 				transaction_index: Some(U256::default()),
 				log_index: Some(U256::from(1)),
 				log_type: "mined".to_owned(),
-			}]
+			}],
+			state_root: Some(H256::from(10)),
 		};
 
 		let serialized = serde_json::to_string(&receipt).unwrap();
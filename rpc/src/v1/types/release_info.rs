@@ -0,0 +1,40 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use v1::helpers::ReleaseInfo as HelperReleaseInfo;
+use v1::types::H256;
+
+/// Release of the client ready to be installed.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ReleaseInfo {
+	/// Release version
+	pub version: String,
+	/// Is it a critical release
+	#[serde(rename="isCritical")]
+	pub is_critical: bool,
+	/// Hash of the release binary
+	pub binary: H256,
+}
+
+impl From<HelperReleaseInfo> for ReleaseInfo {
+	fn from(r: HelperReleaseInfo) -> Self {
+		ReleaseInfo {
+			version: r.version,
+			is_critical: r.is_critical,
+			binary: r.binary.into(),
+		}
+	}
+}
@@ -0,0 +1,89 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use v1::helpers::{ScheduledTransaction as Schedule, ScheduleExecution as Execution};
+use v1::types::{Bytes, H160, H256, U256};
+
+/// Execution of a scheduled transaction.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ScheduleExecution {
+	/// Best block number at the time of the execution
+	#[serde(rename="blockNumber")]
+	pub block_number: U256,
+	/// Hash of the sent transaction
+	#[serde(rename="transactionHash")]
+	pub transaction_hash: Option<H256>,
+	/// Reason the transaction was not sent
+	pub error: Option<String>,
+}
+
+impl From<Execution> for ScheduleExecution {
+	fn from(e: Execution) -> Self {
+		let (hash, error) = match e.result {
+			Ok(hash) => (Some(hash.into()), None),
+			Err(error) => (None, Some(error)),
+		};
+		ScheduleExecution {
+			block_number: e.block_number.into(),
+			transaction_hash: hash,
+			error: error,
+		}
+	}
+}
+
+/// Transaction sent by the node every `interval` blocks.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ScheduledTransaction {
+	/// Id of the scheduled transaction
+	pub id: U256,
+	/// Sender
+	pub from: H160,
+	/// Recipient
+	pub to: Option<H160>,
+	/// Value sent with every transaction
+	pub value: Option<U256>,
+	/// Data sent with every transaction
+	pub data: Option<Bytes>,
+	/// Gas provided with every transaction
+	pub gas: Option<U256>,
+	/// Number of blocks between executions
+	pub interval: U256,
+	/// Executions are skipped while the gas price is above this limit
+	#[serde(rename="maxGasPrice")]
+	pub max_gas_price: U256,
+	/// Block from which the transaction is due again
+	#[serde(rename="nextBlock")]
+	pub next_block: U256,
+	/// Most recent executions, oldest first
+	pub history: Vec<ScheduleExecution>,
+}
+
+impl From<(u64, Schedule)> for ScheduledTransaction {
+	fn from((id, s): (u64, Schedule)) -> Self {
+		ScheduledTransaction {
+			id: id.into(),
+			from: s.request.from.into(),
+			to: s.request.to.map(Into::into),
+			value: s.request.value.map(Into::into),
+			data: s.request.data.map(Into::into),
+			gas: s.request.gas.map(Into::into),
+			interval: s.interval.into(),
+			max_gas_price: s.max_gas_price.into(),
+			next_block: s.next_block.into(),
+			history: s.history.into_iter().map(Into::into).collect(),
+		}
+	}
+}
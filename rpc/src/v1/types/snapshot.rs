@@ -0,0 +1,46 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethcore::snapshot::CreationStatus;
+use v1::types::U256;
+
+/// Status of snapshots created by the node.
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct SnapshotStatus {
+	/// Number of the block a snapshot is currently being taken at
+	#[serde(rename="inProgress")]
+	pub in_progress: Option<U256>,
+	/// Accounts written by the snapshot in progress
+	pub accounts: usize,
+	/// Blocks written by the snapshot in progress
+	pub blocks: usize,
+	/// Bytes written by the snapshot in progress
+	pub size: usize,
+	/// Block numbers of snapshots kept on disk, most recent first
+	pub snapshots: Vec<U256>,
+}
+
+impl From<CreationStatus> for SnapshotStatus {
+	fn from(status: CreationStatus) -> Self {
+		SnapshotStatus {
+			in_progress: status.in_progress.map(Into::into),
+			accounts: status.accounts,
+			blocks: status.blocks,
+			size: status.size,
+			snapshots: status.snapshots.into_iter().map(Into::into).collect(),
+		}
+	}
+}
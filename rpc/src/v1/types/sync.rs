@@ -15,7 +15,9 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use serde::{Serialize, Serializer};
-use v1::types::U256;
+use ethcore::verification_queue_info::VerificationQueueInfo as BlockQueueInfo;
+use ethsync::{TrafficStats, PeerTrafficStats, ChainStatus as EthChainStatus, PeerInfo as EthPeerInfo};
+use v1::types::{H256, U256};
 
 /// Sync info
 #[derive(Default, Debug, Serialize, PartialEq)]
@@ -29,6 +31,12 @@ pub struct SyncInfo {
 	/// Highest block seen so far
 	#[serde(rename="highestBlock")]
 	pub highest_block: U256,
+	/// Total number of snapshot chunks while warp syncing
+	#[serde(rename="warpChunksAmount")]
+	pub warp_chunks_amount: Option<U256>,
+	/// Number of snapshot chunks processed while warp syncing
+	#[serde(rename="warpChunksProcessed")]
+	pub warp_chunks_processed: Option<U256>,
 }
 
 /// Peers info
@@ -40,6 +48,201 @@ pub struct Peers {
 	pub connected: usize,
 	/// Max number of peers
 	pub max: u32,
+	/// Sync details of connected peers
+	pub peers: Vec<PeerInfo>,
+}
+
+/// Sync details of a connected peer
+#[derive(Debug, Serialize, PartialEq)]
+pub struct PeerInfo {
+	/// Peer node id
+	pub id: Option<String>,
+	/// Peer client version
+	#[serde(rename="clientVersion")]
+	pub client_version: String,
+	/// Protocols agreed with the peer
+	pub capabilities: Vec<String>,
+	/// Peer eth protocol version
+	#[serde(rename="ethVersion")]
+	pub eth_version: u32,
+	/// Peer best block hash
+	pub head: H256,
+	/// Peer total difficulty if known
+	pub difficulty: Option<U256>,
+	/// Number of items requested from the peer and not delivered yet
+	#[serde(rename="outstandingRequests")]
+	pub outstanding_requests: usize,
+}
+
+impl From<EthPeerInfo> for PeerInfo {
+	fn from(info: EthPeerInfo) -> Self {
+		PeerInfo {
+			id: info.id,
+			client_version: info.client_version,
+			capabilities: info.capabilities,
+			eth_version: info.eth_version,
+			head: info.eth_head.into(),
+			difficulty: info.eth_difficulty.map(Into::into),
+			outstanding_requests: info.outstanding_requests,
+		}
+	}
+}
+
+/// Block queue status
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct BlockQueueStatus {
+	/// Number of blocks waiting for verification
+	#[serde(rename="unverifiedQueueSize")]
+	pub unverified_queue_size: usize,
+	/// Number of blocks being verified
+	#[serde(rename="verifyingQueueSize")]
+	pub verifying_queue_size: usize,
+	/// Number of verified blocks waiting for import
+	#[serde(rename="verifiedQueueSize")]
+	pub verified_queue_size: usize,
+	/// Maximum number of blocks in the queue
+	#[serde(rename="maxQueueSize")]
+	pub max_queue_size: usize,
+	/// Heap memory used by the queue in bytes
+	#[serde(rename="memUsed")]
+	pub mem_used: usize,
+	/// Maximum heap memory the queue may use in bytes
+	#[serde(rename="maxMemUse")]
+	pub max_mem_use: usize,
+	/// Number of verifier threads
+	#[serde(rename="verifierCount")]
+	pub verifier_count: usize,
+}
+
+impl From<BlockQueueInfo> for BlockQueueStatus {
+	fn from(info: BlockQueueInfo) -> Self {
+		BlockQueueStatus {
+			unverified_queue_size: info.unverified_queue_size,
+			verifying_queue_size: info.verifying_queue_size,
+			verified_queue_size: info.verified_queue_size,
+			max_queue_size: info.max_queue_size,
+			mem_used: info.mem_used,
+			max_mem_use: info.max_mem_use,
+			verifier_count: info.verifier_count,
+		}
+	}
+}
+
+/// Traffic of a single peer
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct PeerTraffic {
+	/// Peer node id
+	pub id: Option<String>,
+	/// Peer client version
+	#[serde(rename="clientVersion")]
+	pub client_version: String,
+	/// Bytes received from the peer
+	#[serde(rename="bytesReceived")]
+	pub bytes_received: u64,
+	/// Bytes sent to the peer
+	#[serde(rename="bytesSent")]
+	pub bytes_sent: u64,
+}
+
+impl From<PeerTrafficStats> for PeerTraffic {
+	fn from(stats: PeerTrafficStats) -> Self {
+		PeerTraffic {
+			id: stats.id,
+			client_version: stats.client_version,
+			bytes_received: stats.bytes_received,
+			bytes_sent: stats.bytes_sent,
+		}
+	}
+}
+
+/// How fast a peer announces new blocks
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct PeerPropagation {
+	/// Peer node id
+	pub id: Option<String>,
+	/// Number of new blocks announced by the peer
+	pub announcements: u64,
+	/// Average delay in milliseconds after the first announcement of a block by any peer
+	#[serde(rename="averageDelay")]
+	pub average_delay: u64,
+}
+
+/// Network traffic statistics
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct NetStats {
+	/// Total bytes received
+	#[serde(rename="bytesReceived")]
+	pub bytes_received: u64,
+	/// Total bytes sent
+	#[serde(rename="bytesSent")]
+	pub bytes_sent: u64,
+	/// Download rate in bytes per second
+	#[serde(rename="receiveRate")]
+	pub receive_rate: u64,
+	/// Upload rate in bytes per second
+	#[serde(rename="sendRate")]
+	pub send_rate: u64,
+	/// Download bandwidth cap is reached
+	#[serde(rename="downloadThrottled")]
+	pub download_throttled: bool,
+	/// Upload bandwidth cap is reached
+	#[serde(rename="uploadThrottled")]
+	pub upload_throttled: bool,
+	/// Per-peer traffic
+	pub peers: Vec<PeerTraffic>,
+	/// Bytes saved by announcing transaction hashes instead of sending transactions
+	#[serde(rename="transactionBytesSaved")]
+	pub transaction_bytes_saved: u64,
+	/// Peers which announced new blocks, fastest first. Whole new blocks are relayed to them first.
+	#[serde(rename="blockPropagation")]
+	pub block_propagation: Vec<PeerPropagation>,
+}
+
+impl From<TrafficStats> for NetStats {
+	fn from(stats: TrafficStats) -> Self {
+		NetStats {
+			bytes_received: stats.bytes_received,
+			bytes_sent: stats.bytes_sent,
+			receive_rate: stats.receive_rate,
+			send_rate: stats.send_rate,
+			download_throttled: stats.download_throttled,
+			upload_throttled: stats.upload_throttled,
+			peers: stats.peers.into_iter().map(Into::into).collect(),
+			transaction_bytes_saved: 0,
+			block_propagation: Vec::new(),
+		}
+	}
+}
+
+/// Comparison of the chain followed by peers with ours
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct ChainStatus {
+	/// Number of peers following our chain
+	#[serde(rename="agreeingPeers")]
+	pub agreeing_peers: usize,
+	/// Number of peers following a different chain
+	#[serde(rename="divergingPeers")]
+	pub diverging_peers: usize,
+	/// Number of peers which are ahead of us or whose best block is unknown
+	#[serde(rename="unknownPeers")]
+	pub unknown_peers: usize,
+	/// Highest block number at which peers diverge from our chain
+	#[serde(rename="divergingBlock")]
+	pub diverging_block: Option<U256>,
+	/// Significant part of peers has been following a different chain for a while
+	pub split: bool,
+}
+
+impl From<EthChainStatus> for ChainStatus {
+	fn from(status: EthChainStatus) -> Self {
+		ChainStatus {
+			agreeing_peers: status.agreeing_peers,
+			diverging_peers: status.diverging_peers,
+			unknown_peers: status.unknown_peers,
+			diverging_block: status.diverging_block.map(Into::into),
+			split: status.split,
+		}
+	}
 }
 
 /// Sync status
@@ -64,20 +267,27 @@ impl Serialize for SyncStatus {
 #[cfg(test)]
 mod tests {
 	use serde_json;
-	use super::{SyncInfo, SyncStatus, Peers};
+	use super::{SyncInfo, SyncStatus, Peers, BlockQueueStatus};
 
 	#[test]
 	fn test_serialize_sync_info() {
 		let t = SyncInfo::default();
 		let serialized = serde_json::to_string(&t).unwrap();
-		assert_eq!(serialized, r#"{"startingBlock":"0x0","currentBlock":"0x0","highestBlock":"0x0"}"#);
+		assert_eq!(serialized, r#"{"startingBlock":"0x0","currentBlock":"0x0","highestBlock":"0x0","warpChunksAmount":null,"warpChunksProcessed":null}"#);
 	}
 
 	#[test]
 	fn test_serialize_peers() {
 		let t = Peers::default();
 		let serialized = serde_json::to_string(&t).unwrap();
-		assert_eq!(serialized, r#"{"active":0,"connected":0,"max":0}"#);
+		assert_eq!(serialized, r#"{"active":0,"connected":0,"max":0,"peers":[]}"#);
+	}
+
+	#[test]
+	fn test_serialize_block_queue_status() {
+		let t = BlockQueueStatus::default();
+		let serialized = serde_json::to_string(&t).unwrap();
+		assert_eq!(serialized, r#"{"unverifiedQueueSize":0,"verifyingQueueSize":0,"verifiedQueueSize":0,"maxQueueSize":0,"memUsed":0,"maxMemUse":0,"verifierCount":0}"#);
 	}
 
 	#[test]
@@ -88,6 +298,6 @@ mod tests {
 
 		let t = SyncStatus::Info(SyncInfo::default());
 		let serialized = serde_json::to_string(&t).unwrap();
-		assert_eq!(serialized, r#"{"startingBlock":"0x0","currentBlock":"0x0","highestBlock":"0x0"}"#);
+		assert_eq!(serialized, r#"{"startingBlock":"0x0","currentBlock":"0x0","highestBlock":"0x0","warpChunksAmount":null,"warpChunksProcessed":null}"#);
 	}
 }
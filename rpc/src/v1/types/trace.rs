@@ -581,6 +581,7 @@ mod tests {
 			trace: vec![],
 			vm_trace: None,
 			state_diff: None,
+			reverted: false,
 		};
 		let serialized = serde_json::to_string(&r).unwrap();
 		assert_eq!(serialized, r#"{"output":"0x60","trace":[],"vmTrace":null,"stateDiff":null}"#);
@@ -14,12 +14,14 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use serde::{Serialize, Serializer};
 use ethcore::contract_address;
+use ethcore::miner::QueueStatus as EthQueueStatus;
 use ethcore::transaction::{LocalizedTransaction, Action, SignedTransaction};
 use v1::types::{Bytes, H160, H256, U256, H512};
 
 /// Transaction
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct Transaction {
 	/// Hash
 	pub hash: H256,
@@ -54,6 +56,37 @@ pub struct Transaction {
 	/// Public key of the signer.
 	#[serde(rename="publicKey")]
 	pub public_key: Option<H512>,
+	/// Status of the transaction in the queue (only for transactions not yet included in a block)
+	#[serde(rename="queueStatus", skip_serializing_if="Option::is_none")]
+	pub queue_status: Option<QueueStatus>,
+}
+
+/// Status of a transaction which is not yet included in a block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QueueStatus {
+	/// Transaction is ready to be included in a block
+	Pending,
+	/// Transaction is waiting for transactions with lower nonces (nonce gap)
+	Future,
+}
+
+impl Serialize for QueueStatus {
+	fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+	where S: Serializer {
+		match *self {
+			QueueStatus::Pending => "pending".serialize(serializer),
+			QueueStatus::Future => "future".serialize(serializer),
+		}
+	}
+}
+
+impl From<EthQueueStatus> for QueueStatus {
+	fn from(s: EthQueueStatus) -> Self {
+		match s {
+			EthQueueStatus::Current => QueueStatus::Pending,
+			EthQueueStatus::Future => QueueStatus::Future,
+		}
+	}
 }
 
 impl From<LocalizedTransaction> for Transaction {
@@ -79,6 +112,7 @@ impl From<LocalizedTransaction> for Transaction {
 			},
 			raw: ::rlp::encode(&t.signed).to_vec().into(),
 			public_key: t.public_key().ok().map(Into::into),
+			queue_status: None,
 		}
 	}
 }
@@ -106,13 +140,14 @@ impl From<SignedTransaction> for Transaction {
 			},
 			raw: ::rlp::encode(&t).to_vec().into(),
 			public_key: t.public_key().ok().map(Into::into),
+			queue_status: None,
 		}
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::Transaction;
+	use super::{Transaction, QueueStatus};
 	use serde_json;
 
 	#[test]
@@ -121,5 +156,14 @@ mod tests {
 		let serialized = serde_json::to_string(&t).unwrap();
 		assert_eq!(serialized, r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0","blockHash":null,"blockNumber":null,"transactionIndex":null,"from":"0x0000000000000000000000000000000000000000","to":null,"value":"0x0","gasPrice":"0x0","gas":"0x0","input":"0x","creates":null,"raw":"0x","publicKey":null}"#);
 	}
-}
 
+	#[test]
+	fn test_transaction_with_queue_status_serialize() {
+		let t = Transaction {
+			queue_status: Some(QueueStatus::Future),
+			..Default::default()
+		};
+		let serialized = serde_json::to_string(&t).unwrap();
+		assert_eq!(serialized, r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0","blockHash":null,"blockNumber":null,"transactionIndex":null,"from":"0x0000000000000000000000000000000000000000","to":null,"value":"0x0","gasPrice":"0x0","gas":"0x0","input":"0x","creates":null,"raw":"0x","publicKey":null,"queueStatus":"future"}"#);
+	}
+}
@@ -37,6 +37,9 @@ pub struct TransactionRequest {
 	pub data: Option<Bytes>,
 	/// Transaction's nonce
 	pub nonce: Option<U256>,
+	/// Never propagate the transaction to peers, only include it in blocks sealed by this node
+	#[serde(rename="localOnly", skip_serializing_if="Option::is_none")]
+	pub local_only: Option<bool>,
 }
 
 impl From<helpers::TransactionRequest> for TransactionRequest {
@@ -49,6 +52,7 @@ impl From<helpers::TransactionRequest> for TransactionRequest {
 			value: r.value.map(Into::into),
 			data: r.data.map(Into::into),
 			nonce: r.nonce.map(Into::into),
+			local_only: if r.local_only { Some(true) } else { None },
 		}
 	}
 }
@@ -63,6 +67,7 @@ impl From<helpers::FilledTransactionRequest> for TransactionRequest {
 			value: Some(r.value.into()),
 			data: Some(r.data.into()),
 			nonce: r.nonce.map(Into::into),
+			local_only: if r.local_only { Some(true) } else { None },
 		}
 	}
 }
@@ -77,6 +82,7 @@ impl Into<helpers::TransactionRequest> for TransactionRequest {
 			value: self.value.map(Into::into),
 			data: self.data.map(Into::into),
 			nonce: self.nonce.map(Into::into),
+			local_only: self.local_only.unwrap_or(false),
 		}
 	}
 }
@@ -111,6 +117,7 @@ mod tests {
 			value: Some(U256::from(3)),
 			data: Some(vec![0x12, 0x34, 0x56].into()),
 			nonce: Some(U256::from(4)),
+			local_only: None,
 		});
 	}
 
@@ -133,7 +140,8 @@ mod tests {
 			gas: Some(U256::from_str("76c0").unwrap()),
 			value: Some(U256::from_str("9184e72a").unwrap()),
 			data: Some("d46e8dd67c5d32be8d46e8dd67c5d32be8058bb8eb970870f072445675058bb8eb970870f072445675".from_hex().unwrap().into()),
-			nonce: None
+			nonce: None,
+			local_only: None,
 		});
 	}
 
@@ -150,6 +158,7 @@ mod tests {
 			value: None,
 			data: None,
 			nonce: None,
+			local_only: None,
 		});
 	}
 
@@ -173,6 +182,7 @@ mod tests {
 			value: None,
 			data: Some(vec![0x85, 0x95, 0xba, 0xb1].into()),
 			nonce: None,
+			local_only: None,
 		});
 	}
 
@@ -0,0 +1,94 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethcore::trace::FlatTrace;
+use ethcore::trace::trace::{Action, Res};
+use v1::types::{H160, U256};
+
+/// Kind of the value transfer.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum TransferType {
+	/// Value sent with a message call
+	#[serde(rename="call")]
+	Call,
+	/// Endowment of a newly created contract
+	#[serde(rename="create")]
+	Create,
+	/// Balance refunded by a suicided contract
+	#[serde(rename="suicide")]
+	Suicide,
+}
+
+/// Value transfer made by a transaction, including internal ones.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Transfer {
+	/// Transfer type
+	#[serde(rename="type")]
+	pub transfer_type: TransferType,
+	/// Sender
+	pub from: H160,
+	/// Recipient
+	pub to: H160,
+	/// Transferred value
+	pub value: U256,
+}
+
+impl From<FlatTrace> for Transfer {
+	fn from(t: FlatTrace) -> Self {
+		match (t.action, t.result) {
+			(Action::Call(call), _) => Transfer {
+				transfer_type: TransferType::Call,
+				from: call.from.into(),
+				to: call.to.into(),
+				value: call.value.into(),
+			},
+			(Action::Create(create), result) => Transfer {
+				transfer_type: TransferType::Create,
+				from: create.from.into(),
+				to: match result {
+					Res::Create(result) => result.address.into(),
+					_ => Default::default(),
+				},
+				value: create.value.into(),
+			},
+			(Action::Suicide(suicide), _) => Transfer {
+				transfer_type: TransferType::Suicide,
+				from: suicide.address.into(),
+				to: suicide.refund_address.into(),
+				value: suicide.balance.into(),
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::{Transfer, TransferType};
+
+	#[test]
+	fn transfer_serialization() {
+		let t = Transfer {
+			transfer_type: TransferType::Suicide,
+			from: 5.into(),
+			to: 10.into(),
+			value: 100.into(),
+		};
+
+		let serialized = serde_json::to_string(&t).unwrap();
+		assert_eq!(serialized, r#"{"type":"suicide","from":"0x0000000000000000000000000000000000000005","to":"0x000000000000000000000000000000000000000a","value":"0x64"}"#);
+	}
+}
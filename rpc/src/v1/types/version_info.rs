@@ -0,0 +1,33 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+/// Version and capabilities of the client.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct VersionInfo {
+	/// Semantic version
+	pub version: String,
+	/// Commit hash the client was built from
+	pub hash: String,
+	/// Date of the commit
+	pub date: String,
+	/// Optional features the client was built with
+	pub features: Vec<String>,
+	/// Consensus forks supported by the client with their transition blocks on the current chain
+	/// (`null` if not scheduled)
+	pub forks: BTreeMap<String, Option<u64>>,
+}
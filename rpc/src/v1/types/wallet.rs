@@ -0,0 +1,60 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use v1::types::{Bytes, H160, H256, U256};
+use v1::helpers;
+
+/// Transaction executed once the wallet operation is confirmed.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct WalletTransaction {
+	/// Recipient
+	pub to: H160,
+	/// Value
+	pub value: U256,
+	/// Data
+	pub data: Bytes,
+}
+
+/// Multi-signature wallet operation waiting for confirmations.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct WalletOperation {
+	/// Operation hash
+	pub operation: H256,
+	/// Number of confirmations still needed
+	#[serde(rename="yetNeeded")]
+	pub yet_needed: U256,
+	/// Owners who already confirmed the operation
+	#[serde(rename="confirmedBy")]
+	pub confirmed_by: Vec<H160>,
+	/// Transaction of the operation (null for owner and limit changes)
+	pub transaction: Option<WalletTransaction>,
+}
+
+impl From<helpers::PendingOperation> for WalletOperation {
+	fn from(op: helpers::PendingOperation) -> Self {
+		WalletOperation {
+			operation: op.operation.into(),
+			yet_needed: op.yet_needed.into(),
+			confirmed_by: op.confirmed_by.into_iter().map(Into::into).collect(),
+			transaction: op.transaction.map(|tx| WalletTransaction {
+				to: tx.to.into(),
+				value: tx.value.into(),
+				data: tx.data.into(),
+			}),
+		}
+	}
+}
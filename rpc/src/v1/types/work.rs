@@ -14,9 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::time::Duration;
 use super::{H256, U256};
 
 use serde::{Serialize, Serializer};
+use ethcore::miner::WorkerStatistics as EthWorkerStatistics;
+use ethcore::miner::{Timing as EthTiming, SealingStatistics as EthSealingStatistics};
 
 /// The result of an `eth_getWork` call: it differs based on an option
 /// whether to send the block number.
@@ -41,3 +44,113 @@ impl Serialize for Work {
 	}
 }
 
+
+/// Solutions submitted by single worker.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct WorkerStatistics {
+	/// Number of accepted solutions.
+	pub accepted: u64,
+	/// Number of solutions for outdated work.
+	pub stale: u64,
+	/// Number of rejected solutions.
+	pub invalid: u64,
+	/// Last reported hashrate.
+	pub hashrate: U256,
+}
+
+impl From<EthWorkerStatistics> for WorkerStatistics {
+	fn from(stats: EthWorkerStatistics) -> Self {
+		WorkerStatistics {
+			accepted: stats.accepted,
+			stale: stats.stale,
+			invalid: stats.invalid,
+			hashrate: stats.hashrate.into(),
+		}
+	}
+}
+
+fn micros(d: Duration) -> u64 {
+	d.as_secs() * 1_000_000 + d.subsec_nanos() as u64 / 1_000
+}
+
+/// Duration of a block production step, in microseconds.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct Timing {
+	/// Duration of the most recent run.
+	pub last: u64,
+	/// Longest run.
+	pub max: u64,
+	/// Average run.
+	pub average: u64,
+	/// Number of runs.
+	pub count: u64,
+}
+
+impl From<EthTiming> for Timing {
+	fn from(t: EthTiming) -> Self {
+		Timing {
+			last: micros(t.last),
+			max: micros(t.max),
+			average: micros(t.average()),
+			count: t.count,
+		}
+	}
+}
+
+/// Timing breakdown of the block production.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct SealingStatistics {
+	/// Picking transactions from the queue and opening the block.
+	#[serde(rename="transactionSelection")]
+	pub transaction_selection: Timing,
+	/// Executing transactions and closing the block.
+	pub execution: Timing,
+	/// Preparing the work package on request.
+	#[serde(rename="workPreparation")]
+	pub work_preparation: Timing,
+	/// Verifying the seal.
+	#[serde(rename="sealCheck")]
+	pub seal_check: Timing,
+	/// Importing the sealed block.
+	pub import: Timing,
+}
+
+impl From<EthSealingStatistics> for SealingStatistics {
+	fn from(stats: EthSealingStatistics) -> Self {
+		SealingStatistics {
+			transaction_selection: stats.transaction_selection.into(),
+			execution: stats.execution.into(),
+			work_preparation: stats.work_preparation.into(),
+			seal_check: stats.seal_check.into(),
+			import: stats.import.into(),
+		}
+	}
+}
+
+/// Uncles included in recent blocks and blocks sealed by this node that didn't make it into the chain.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct UncleStatistics {
+	/// First block of the window.
+	#[serde(rename="fromBlock")]
+	pub from_block: U256,
+	/// Last block of the window.
+	#[serde(rename="toBlock")]
+	pub to_block: U256,
+	/// Number of uncles included in the window.
+	pub uncles: U256,
+	/// Average number of uncles per block.
+	#[serde(rename="uncleRate")]
+	pub uncle_rate: f64,
+	/// Average difference between the number of the including block and the uncle.
+	#[serde(rename="averageUncleDistance")]
+	pub average_uncle_distance: f64,
+	/// Blocks sealed by this node within the window.
+	#[serde(rename="sealedBlocks")]
+	pub sealed_blocks: U256,
+	/// Blocks sealed by this node within the window that are not part of the canonical chain.
+	#[serde(rename="orphanedBlocks")]
+	pub orphaned_blocks: U256,
+	/// Orphaned blocks of this node that were included as uncles.
+	#[serde(rename="orphansIncluded")]
+	pub orphans_included: U256,
+}
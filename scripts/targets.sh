@@ -7,6 +7,7 @@ export TARGETS="
 	-p ethcore-bigint\
 	-p ethcore-dapps \
 	-p ethcore-rpc \
+	-p ethcore-secretstore \
 	-p ethcore-signer \
 	-p ethcore-util \
 	-p ethcore-network \
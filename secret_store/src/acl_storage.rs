@@ -0,0 +1,50 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use util::Address;
+use types::{Error, DocumentAddress};
+
+/// Storage of document access permissions.
+pub trait AclStorage: Send + Sync {
+	/// Returns true if `requester` may retrieve key of the document.
+	fn check(&self, requester: &Address, document: &DocumentAddress) -> Result<bool, Error>;
+}
+
+#[cfg(test)]
+pub mod tests {
+	use std::collections::HashSet;
+	use util::{Address, Mutex};
+	use types::{Error, DocumentAddress};
+	use super::AclStorage;
+
+	/// ACL storage allowing everything except explicitly prohibited pairs.
+	#[derive(Default)]
+	pub struct DummyAclStorage {
+		prohibited: Mutex<HashSet<(Address, DocumentAddress)>>,
+	}
+
+	impl DummyAclStorage {
+		pub fn prohibit(&self, requester: Address, document: DocumentAddress) {
+			self.prohibited.lock().insert((requester, document));
+		}
+	}
+
+	impl AclStorage for DummyAclStorage {
+		fn check(&self, requester: &Address, document: &DocumentAddress) -> Result<bool, Error> {
+			Ok(!self.prohibited.lock().contains(&(requester.clone(), document.clone())))
+		}
+	}
+}
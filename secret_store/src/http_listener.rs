@@ -0,0 +1,170 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+use hyper::server::{Server as HttpServer, Handler as HttpHandler, Request as HttpRequest, Response as HttpResponse, Listening as HttpListening};
+use hyper::method::Method as HttpMethod;
+use hyper::status::StatusCode as HttpStatusCode;
+use hyper::uri::RequestUri;
+use rustc_serialize::hex::ToHex;
+use traits::KeyServer;
+use types::{Error, ServiceConfiguration, DocumentAddress, DocumentEncryptedKey, RequestSignature};
+
+/// Key server http listener.
+///
+/// `POST /{document}/{signature}` generates key of the document,
+/// `GET /{document}/{signature}` retrieves it. Both return the key
+/// encrypted with the requester's public key, hex-encoded.
+pub struct KeyServerHttpListener<T: KeyServer + 'static> {
+	http_server: HttpListening,
+	key_server: Arc<T>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Request {
+	Invalid,
+	GenerateDocumentKey(DocumentAddress, RequestSignature),
+	GetDocumentKey(DocumentAddress, RequestSignature),
+}
+
+struct KeyServerHttpHandler<T: KeyServer + 'static> {
+	key_server: Arc<T>,
+}
+
+impl<T> KeyServerHttpListener<T> where T: KeyServer + 'static {
+	/// Starts listening on the configured address.
+	pub fn start(config: &ServiceConfiguration, key_server: T) -> Result<Self, Error> {
+		let key_server = Arc::new(key_server);
+		let handler = KeyServerHttpHandler {
+			key_server: key_server.clone(),
+		};
+
+		let listener_addr = format!("{}:{}", config.listener_address, config.listener_port);
+		let http_server = try!(HttpServer::http(listener_addr.as_str())
+			.and_then(|server| server.handle(handler))
+			.map_err(|e| Error::Internal(format!("Error starting key server listener on {}: {}", listener_addr, e))));
+
+		Ok(KeyServerHttpListener {
+			http_server: http_server,
+			key_server: key_server,
+		})
+	}
+}
+
+impl<T> KeyServer for KeyServerHttpListener<T> where T: KeyServer + 'static {
+	fn generate_document_key(&self, signature: &RequestSignature, document: &DocumentAddress) -> Result<DocumentEncryptedKey, Error> {
+		self.key_server.generate_document_key(signature, document)
+	}
+
+	fn document_key(&self, signature: &RequestSignature, document: &DocumentAddress) -> Result<DocumentEncryptedKey, Error> {
+		self.key_server.document_key(signature, document)
+	}
+}
+
+impl<T> Drop for KeyServerHttpListener<T> where T: KeyServer + 'static {
+	fn drop(&mut self) {
+		let _ = self.http_server.close();
+	}
+}
+
+impl<T> HttpHandler for KeyServerHttpHandler<T> where T: KeyServer + 'static {
+	fn handle(&self, req: HttpRequest, mut res: HttpResponse) {
+		let path = match req.uri {
+			RequestUri::AbsolutePath(ref path) => path.clone(),
+			_ => {
+				*res.status_mut() = HttpStatusCode::NotFound;
+				return;
+			},
+		};
+
+		let result = match parse_request(&req.method, &path) {
+			Request::GenerateDocumentKey(document, signature) => self.key_server.generate_document_key(&signature, &document),
+			Request::GetDocumentKey(document, signature) => self.key_server.document_key(&signature, &document),
+			Request::Invalid => {
+				warn!(target: "secretstore", "Ignoring invalid {}-request {}", req.method, path);
+				*res.status_mut() = HttpStatusCode::BadRequest;
+				return;
+			},
+		};
+
+		match result {
+			Ok(key) => {
+				if let Err(err) = res.send(key.to_hex().as_bytes()) {
+					warn!(target: "secretstore", "Error sending response to {}-request {}: {}", req.method, path, err);
+				}
+			},
+			Err(err) => {
+				warn!(target: "secretstore", "{}-request {} failed: {}", req.method, path, err);
+				*res.status_mut() = match err {
+					Error::BadSignature => HttpStatusCode::BadRequest,
+					Error::AccessDenied => HttpStatusCode::Forbidden,
+					Error::DocumentNotFound => HttpStatusCode::NotFound,
+					Error::DocumentExists => HttpStatusCode::Conflict,
+					Error::Database(_) | Error::Internal(_) => HttpStatusCode::InternalServerError,
+				};
+			},
+		}
+	}
+}
+
+fn parse_request(method: &HttpMethod, path: &str) -> Request {
+	let path: Vec<&str> = path.trim_left_matches('/').split('/').collect();
+	if path.len() != 2 {
+		return Request::Invalid;
+	}
+
+	let (document, signature) = match (path[0].parse(), path[1].parse()) {
+		(Ok(document), Ok(signature)) => (document, signature),
+		_ => return Request::Invalid,
+	};
+
+	match *method {
+		HttpMethod::Post => Request::GenerateDocumentKey(document, signature),
+		HttpMethod::Get => Request::GetDocumentKey(document, signature),
+		_ => Request::Invalid,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use hyper::method::Method as HttpMethod;
+	use util::H256;
+	use ethkey::Signature;
+	use super::{parse_request, Request};
+
+	#[test]
+	fn should_parse_key_requests() {
+		let document = "0000000000000000000000000000000000000000000000000000000000000001";
+		let signature = "a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01";
+		let path = format!("/{}/{}", document, signature);
+
+		match parse_request(&HttpMethod::Post, &path) {
+			Request::GenerateDocumentKey(d, s) => {
+				assert_eq!(d, H256::from(1));
+				assert_eq!(s, signature.parse::<Signature>().unwrap());
+			},
+			request => panic!("Unexpected request: {:?}", request),
+		}
+		match parse_request(&HttpMethod::Get, &path) {
+			Request::GetDocumentKey(d, _) => assert_eq!(d, H256::from(1)),
+			request => panic!("Unexpected request: {:?}", request),
+		}
+
+		assert_eq!(parse_request(&HttpMethod::Delete, &path), Request::Invalid);
+		assert_eq!(parse_request(&HttpMethod::Get, &format!("/{}", document)), Request::Invalid);
+		assert_eq!(parse_request(&HttpMethod::Get, &format!("/{}/0x01", document)), Request::Invalid);
+	}
+}
@@ -0,0 +1,127 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+use ethkey::{self, Generator, Random, Public};
+use ethcrypto;
+use acl_storage::AclStorage;
+use key_storage::KeyStorage;
+use traits::KeyServer;
+use types::{Error, DocumentAddress, DocumentKey, DocumentEncryptedKey, RequestSignature};
+
+/// Key server serving keys from the key storage.
+pub struct KeyServerImpl {
+	acl_storage: Arc<AclStorage>,
+	key_storage: Arc<KeyStorage>,
+}
+
+impl KeyServerImpl {
+	/// Creates new key server.
+	pub fn new(acl_storage: Arc<AclStorage>, key_storage: Arc<KeyStorage>) -> Self {
+		KeyServerImpl {
+			acl_storage: acl_storage,
+			key_storage: key_storage,
+		}
+	}
+}
+
+impl KeyServer for KeyServerImpl {
+	fn generate_document_key(&self, signature: &RequestSignature, document: &DocumentAddress) -> Result<DocumentEncryptedKey, Error> {
+		let public = try!(ethkey::recover(signature, document).map_err(|_| Error::BadSignature));
+		// only parties granted access to the document may create its key,
+		// so nobody can take over ids of documents they have no business with
+		if !try!(self.acl_storage.check(&ethkey::public_to_address(&public), document)) {
+			return Err(Error::AccessDenied);
+		}
+
+		// replacing the key would make documents encrypted with it unreadable
+		match self.key_storage.get(document) {
+			Ok(_) => return Err(Error::DocumentExists),
+			Err(Error::DocumentNotFound) => {},
+			Err(err) => return Err(err),
+		}
+
+		let key: DocumentKey = try!(Random.generate()).secret().to_vec();
+		try!(self.key_storage.insert(document.clone(), key.clone()));
+		encrypt_document_key(&public, &key)
+	}
+
+	fn document_key(&self, signature: &RequestSignature, document: &DocumentAddress) -> Result<DocumentEncryptedKey, Error> {
+		let public = try!(ethkey::recover(signature, document).map_err(|_| Error::BadSignature));
+		if !try!(self.acl_storage.check(&ethkey::public_to_address(&public), document)) {
+			return Err(Error::AccessDenied);
+		}
+
+		let key = try!(self.key_storage.get(document));
+		encrypt_document_key(&public, &key)
+	}
+}
+
+fn encrypt_document_key(public: &Public, key: &DocumentKey) -> Result<DocumentEncryptedKey, Error> {
+	Ok(try!(ethcrypto::ecies::encrypt_single_message(public, key)))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use ethkey::{self, Generator, Random};
+	use ethcrypto;
+	use util::H256;
+	use acl_storage::tests::DummyAclStorage;
+	use key_storage::tests::DummyKeyStorage;
+	use traits::KeyServer;
+	use types::Error;
+	use super::KeyServerImpl;
+
+	#[test]
+	fn document_key_is_generated_once_and_encrypted_for_requester() {
+		let acl_storage = Arc::new(DummyAclStorage::default());
+		let key_server = KeyServerImpl::new(acl_storage.clone(), Arc::new(DummyKeyStorage::default()));
+		let author = Random.generate().unwrap();
+		let reader = Random.generate().unwrap();
+		let stranger = Random.generate().unwrap();
+		let document = H256::from(42);
+		acl_storage.prohibit(stranger.address(), document.clone());
+
+		// unknown document
+		let signature = ethkey::sign(reader.secret(), &document).unwrap();
+		assert_eq!(key_server.document_key(&signature, &document), Err(Error::DocumentNotFound));
+
+		let signature = ethkey::sign(author.secret(), &document).unwrap();
+		let encrypted = key_server.generate_document_key(&signature, &document).unwrap();
+		let key = ethcrypto::ecies::decrypt_single_message(author.secret(), &encrypted).unwrap();
+		assert_eq!(key_server.generate_document_key(&signature, &document), Err(Error::DocumentExists));
+
+		let signature = ethkey::sign(reader.secret(), &document).unwrap();
+		let encrypted = key_server.document_key(&signature, &document).unwrap();
+		assert_eq!(ethcrypto::ecies::decrypt_single_message(reader.secret(), &encrypted).unwrap(), key);
+
+		let signature = ethkey::sign(stranger.secret(), &document).unwrap();
+		assert_eq!(key_server.document_key(&signature, &document), Err(Error::AccessDenied));
+	}
+
+	#[test]
+	fn document_key_is_only_generated_for_permitted_parties() {
+		let acl_storage = Arc::new(DummyAclStorage::default());
+		let key_server = KeyServerImpl::new(acl_storage.clone(), Arc::new(DummyKeyStorage::default()));
+		let stranger = Random.generate().unwrap();
+		let document = H256::from(42);
+		acl_storage.prohibit(stranger.address(), document.clone());
+
+		let signature = ethkey::sign(stranger.secret(), &document).unwrap();
+		assert_eq!(key_server.generate_document_key(&signature, &document), Err(Error::AccessDenied));
+	}
+}
@@ -0,0 +1,162 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use util::Database;
+use util::path::restrict_permissions_owner;
+use ethkey::{Generator, Random, KeyPair, Secret};
+use ethcrypto;
+use types::{Error, ServiceConfiguration, DocumentAddress, DocumentKey};
+
+/// Name of the file holding the key document keys are encrypted with, within the data directory.
+const STORAGE_KEY_FILE: &'static str = "key";
+/// Name of the database directory, within the data directory.
+const DATABASE_DIR: &'static str = "db";
+
+/// Storage of document keys.
+pub trait KeyStorage: Send + Sync {
+	/// Stores key of the document.
+	fn insert(&self, document: DocumentAddress, key: DocumentKey) -> Result<(), Error>;
+	/// Returns key of the document.
+	fn get(&self, document: &DocumentAddress) -> Result<DocumentKey, Error>;
+}
+
+/// Key storage backed by a database. Document keys are stored encrypted with
+/// the storage key, which is kept in a separate file readable only by its owner.
+pub struct PersistentKeyStorage {
+	db: Database,
+	key_pair: KeyPair,
+}
+
+impl PersistentKeyStorage {
+	/// Opens the database in configured directory, creating it and the storage key if needed.
+	pub fn new(config: &ServiceConfiguration) -> Result<Self, Error> {
+		let path = Path::new(&config.data_path);
+		let key_pair = try!(load_or_create_key(&path.join(STORAGE_KEY_FILE)));
+		let db = try!(Database::open_default(&path.join(DATABASE_DIR).to_string_lossy()).map_err(Error::Database));
+		Ok(PersistentKeyStorage {
+			db: db,
+			key_pair: key_pair,
+		})
+	}
+}
+
+impl KeyStorage for PersistentKeyStorage {
+	fn insert(&self, document: DocumentAddress, key: DocumentKey) -> Result<(), Error> {
+		let encrypted = try!(ethcrypto::ecies::encrypt_single_message(self.key_pair.public(), &key));
+		let mut batch = self.db.transaction();
+		batch.put(None, &document, &encrypted);
+		self.db.write(batch).map_err(Error::Database)
+	}
+
+	fn get(&self, document: &DocumentAddress) -> Result<DocumentKey, Error> {
+		let encrypted = try!(self.db.get(None, document)
+			.map_err(Error::Database)
+			.and_then(|key| key.ok_or(Error::DocumentNotFound)));
+		Ok(try!(ethcrypto::ecies::decrypt_single_message(self.key_pair.secret(), &encrypted)))
+	}
+}
+
+fn load_or_create_key(path: &Path) -> Result<KeyPair, Error> {
+	let secret = match fs::File::open(path) {
+		Ok(mut file) => {
+			let mut hex = String::new();
+			try!(file.read_to_string(&mut hex).map_err(|e| Error::Internal(format!("Cannot read storage key: {}", e))));
+			try!(Secret::from_str(hex.trim()).map_err(|_| Error::Internal(format!("Invalid storage key in {}", path.display()))))
+		},
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+			let key_pair = try!(Random.generate());
+			let mut file = try!(fs::OpenOptions::new().write(true).create_new(true).open(path)
+				.map_err(|e| Error::Internal(format!("Cannot create storage key: {}", e))));
+			if let Err(e) = restrict_permissions_owner(path) {
+				warn!(target: "secretstore", "Failed to restrict permissions of {} (chmod: {})", path.display(), e);
+			}
+			try!(file.write_all(key_pair.secret().hex().as_bytes()).map_err(|e| Error::Internal(format!("Cannot write storage key: {}", e))));
+			key_pair.secret().clone()
+		},
+		Err(e) => return Err(Error::Internal(format!("Cannot open storage key: {}", e))),
+	};
+	Ok(try!(KeyPair::from_secret(secret)))
+}
+
+#[cfg(test)]
+pub mod tests {
+	use std::collections::HashMap;
+	use devtools::RandomTempPath;
+	use std::fs;
+	use util::{H256, RwLock};
+	use types::{Error, ServiceConfiguration, DocumentAddress, DocumentKey};
+	use super::{KeyStorage, PersistentKeyStorage};
+
+	/// In-memory key storage.
+	#[derive(Default)]
+	pub struct DummyKeyStorage {
+		keys: RwLock<HashMap<DocumentAddress, DocumentKey>>,
+	}
+
+	impl KeyStorage for DummyKeyStorage {
+		fn insert(&self, document: DocumentAddress, key: DocumentKey) -> Result<(), Error> {
+			self.keys.write().insert(document, key);
+			Ok(())
+		}
+
+		fn get(&self, document: &DocumentAddress) -> Result<DocumentKey, Error> {
+			self.keys.read().get(document).cloned().ok_or(Error::DocumentNotFound)
+		}
+	}
+
+	#[test]
+	fn persistent_key_storage_keeps_keys_after_reopening() {
+		let path = RandomTempPath::create_dir();
+		let config = ServiceConfiguration {
+			listener_address: "127.0.0.1".into(),
+			listener_port: 8082,
+			data_path: path.as_str().to_owned(),
+		};
+
+		let document1 = H256::from(1);
+		let document2 = H256::from(2);
+		let key = vec![1, 2, 3];
+		{
+			let storage = PersistentKeyStorage::new(&config).unwrap();
+			storage.insert(document1.clone(), key.clone()).unwrap();
+		}
+
+		let storage = PersistentKeyStorage::new(&config).unwrap();
+		assert_eq!(storage.get(&document1), Ok(key.clone()));
+		assert_eq!(storage.get(&document2), Err(Error::DocumentNotFound));
+
+		// keys are not stored in plain text
+		assert!(storage.db.get(None, &document1).unwrap().unwrap() != key);
+	}
+
+	#[test]
+	fn persistent_key_storage_fails_with_unreadable_storage_key() {
+		let path = RandomTempPath::create_dir();
+		let config = ServiceConfiguration {
+			listener_address: "127.0.0.1".into(),
+			listener_port: 8082,
+			data_path: path.as_str().to_owned(),
+		};
+		fs::create_dir(path.as_path().join("key")).unwrap();
+
+		assert!(PersistentKeyStorage::new(&config).is_err());
+		assert!(path.as_path().join("key").is_dir());
+	}
+}
@@ -0,0 +1,59 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Secret store: key server holding document keys of private-data dapps.
+//!
+//! A document key is generated on request of the document author and handed out
+//! only to parties allowed by the ACL storage, which normally is an on-chain contract.
+//! Every request carries the requester's signature of the document id. The public key
+//! recovered from it is checked against the ACL and used to encrypt the returned key,
+//! so nobody but the requester can read it.
+//!
+//! Keys are held by a single key server, encrypted at rest with a key stored apart from
+//! the database. It's not a distributed secret store: the key server sees every document
+//! key, and there is no threshold key generation among a set of key servers.
+
+#[macro_use]
+extern crate log;
+extern crate hyper;
+extern crate rustc_serialize;
+extern crate ethcore_util as util;
+extern crate ethkey;
+extern crate ethcrypto;
+
+#[cfg(test)]
+extern crate ethcore_devtools as devtools;
+
+mod types;
+mod traits;
+mod acl_storage;
+mod key_storage;
+mod key_server;
+mod http_listener;
+
+pub use types::{Error, DocumentAddress, DocumentKey, DocumentEncryptedKey, RequestSignature, ServiceConfiguration};
+pub use traits::KeyServer;
+pub use acl_storage::AclStorage;
+
+use std::sync::Arc;
+
+/// Opens the key storage and starts the key server http listener.
+pub fn start(acl_storage: Arc<AclStorage>, config: ServiceConfiguration) -> Result<Box<KeyServer>, Error> {
+	let key_storage = Arc::new(try!(key_storage::PersistentKeyStorage::new(&config)));
+	let key_server = key_server::KeyServerImpl::new(acl_storage, key_storage);
+	let listener = try!(http_listener::KeyServerHttpListener::start(&config, key_server));
+	Ok(Box::new(listener))
+}
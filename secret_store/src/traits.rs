@@ -0,0 +1,28 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use types::{Error, DocumentAddress, DocumentEncryptedKey, RequestSignature};
+
+/// Key server.
+pub trait KeyServer: Send + Sync {
+	/// Generates and stores key of the document. The document author signs the request
+	/// and receives the key encrypted with their public key. The author must be allowed
+	/// to access the document by the ACL storage.
+	fn generate_document_key(&self, signature: &RequestSignature, document: &DocumentAddress) -> Result<DocumentEncryptedKey, Error>;
+	/// Returns key of the document encrypted with the requester's public key,
+	/// if the requester is allowed to access the document.
+	fn document_key(&self, signature: &RequestSignature, document: &DocumentAddress) -> Result<DocumentEncryptedKey, Error>;
+}
@@ -0,0 +1,82 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+use ethkey;
+use ethcrypto;
+use util::{H256, Bytes};
+
+/// Id of the document the key belongs to.
+pub type DocumentAddress = H256;
+/// Document key.
+pub type DocumentKey = Bytes;
+/// Document key encrypted with the requester's public key.
+pub type DocumentEncryptedKey = Bytes;
+/// Requester's signature of the document id.
+pub type RequestSignature = ethkey::Signature;
+
+/// Key server error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+	/// Requester's public key could not be recovered from the signature.
+	BadSignature,
+	/// Requester is not allowed to access the document.
+	AccessDenied,
+	/// There is no key for the document.
+	DocumentNotFound,
+	/// Key for the document has already been generated.
+	DocumentExists,
+	/// Key storage error.
+	Database(String),
+	/// Other errors.
+	Internal(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+		match *self {
+			Error::BadSignature => write!(f, "Bad signature"),
+			Error::AccessDenied => write!(f, "Access denied"),
+			Error::DocumentNotFound => write!(f, "Document not found"),
+			Error::DocumentExists => write!(f, "Document key already generated"),
+			Error::Database(ref msg) => write!(f, "Database error: {}", msg),
+			Error::Internal(ref msg) => write!(f, "Internal error: {}", msg),
+		}
+	}
+}
+
+impl From<ethkey::Error> for Error {
+	fn from(err: ethkey::Error) -> Self {
+		Error::Internal(err.to_string())
+	}
+}
+
+impl From<ethcrypto::Error> for Error {
+	fn from(err: ethcrypto::Error) -> Self {
+		Error::Internal(err.to_string())
+	}
+}
+
+/// Key server configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceConfiguration {
+	/// Interface to listen on.
+	pub listener_address: String,
+	/// Port to listen on.
+	pub listener_port: u16,
+	/// Path of the key storage database.
+	pub data_path: String,
+}
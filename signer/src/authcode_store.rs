@@ -20,8 +20,9 @@ use std::io;
 use std::io::{Read, Write};
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 use std::time;
-use util::{H256, Hashable};
+use util::{H256, Hashable, FixedHash};
 
 /// Providing current time in seconds
 pub trait TimeProvider {
@@ -49,10 +50,16 @@ impl TimeProvider for DefaultTimeProvider {
 const TIME_THRESHOLD: u64 = 7;
 const TOKEN_LENGTH: usize = 16;
 const INITIAL_TOKEN: &'static str = "initial";
+/// Prefix of lines holding hashes of persistent credentials.
+const CREDENTIAL_PREFIX: &'static str = "credential:";
 
-/// Manages authorization codes for `SignerUIs`
+/// Manages authorization codes for `SignerUIs`.
+///
+/// Codes are one-time pairing tokens which `SignerUIs` exchange for persistent credentials.
+/// Only hashes of the credentials are stored.
 pub struct AuthCodes<T: TimeProvider = DefaultTimeProvider> {
 	codes: Vec<String>,
+	credentials: Vec<H256>,
 	now: T,
 }
 
@@ -70,11 +77,16 @@ impl AuthCodes<DefaultTimeProvider> {
 			}
 		};
 		let codes = content.lines()
-			.filter(|f| f.len() >= TOKEN_LENGTH)
+			.filter(|f| f.len() >= TOKEN_LENGTH && !f.starts_with(CREDENTIAL_PREFIX))
 			.map(String::from)
 			.collect();
+		let credentials = content.lines()
+			.filter(|f| f.starts_with(CREDENTIAL_PREFIX))
+			.filter_map(|f| H256::from_str(&f[CREDENTIAL_PREFIX.len()..]).ok())
+			.collect();
 		Ok(AuthCodes {
 			codes: codes,
+			credentials: credentials,
 			now: DefaultTimeProvider::default(),
 		})
 	}
@@ -86,7 +98,11 @@ impl<T: TimeProvider> AuthCodes<T> {
 	/// Writes all `AuthCodes` to a disk.
 	pub fn to_file(&self, file: &Path) -> io::Result<()> {
 		let mut file = try!(fs::File::create(file));
-		let content = self.codes.join("\n");
+		let content = self.codes.iter()
+			.cloned()
+			.chain(self.credentials.iter().map(|hash| format!("{}{:?}", CREDENTIAL_PREFIX, hash)))
+			.collect::<Vec<_>>()
+			.join("\n");
 		file.write_all(content.as_bytes())
 	}
 
@@ -94,6 +110,7 @@ impl<T: TimeProvider> AuthCodes<T> {
 	pub fn new(codes: Vec<String>, now: T) -> Self {
 		AuthCodes {
 			codes: codes,
+			credentials: Vec::new(),
 			now: now,
 		}
 	}
@@ -107,6 +124,11 @@ impl<T: TimeProvider> AuthCodes<T> {
 			return false;
 		}
 
+		// Check if it's a persistent credential.
+		if self.credentials.contains(&hash.sha3()) {
+			return true;
+		}
+
 		let as_token = |code| format!("{}:{}", code, time).sha3();
 
 		// Check if it's the initial token.
@@ -138,9 +160,27 @@ impl<T: TimeProvider> AuthCodes<T> {
 		Ok(readable_code)
 	}
 
-	/// Returns true if there are no tokens in this store
+	/// Exchanges one-time code (as returned by `generate_new`) for a new persistent credential.
+	/// The code is removed from the store. Returns `None` if the code is unknown.
+	pub fn exchange(&mut self, code: &str) -> io::Result<Option<H256>> {
+		let code = code.replace("-", "");
+		let position = match self.codes.iter().position(|c| *c == code) {
+			Some(position) => position,
+			None => return Ok(None),
+		};
+
+		let mut rng = try!(OsRng::new());
+		let mut credential = H256::new();
+		rng.fill_bytes(&mut credential);
+		self.codes.remove(position);
+		self.credentials.push(credential.sha3());
+		trace!(target: "signer", "Authentication token exchanged for a credential.");
+		Ok(Some(credential))
+	}
+
+	/// Returns true if there are no tokens nor credentials in this store
 	pub fn is_empty(&self) -> bool {
-		self.codes.is_empty()
+		self.codes.is_empty() && self.credentials.is_empty()
 	}
 }
 
@@ -215,7 +255,19 @@ mod tests {
 		assert_eq!(res1, false);
 		assert_eq!(res2, false);
 	}
+	#[test]
+	fn should_exchange_code_for_credential() {
+		// given
+		let code = "23521352asdfasdfadf";
+		let time = 99;
+		let mut codes = AuthCodes::new(vec![code.into()], || 100);
 
-}
-
+		// when
+		let credential = codes.exchange("2352-1352-asdfasdfadf").unwrap().unwrap();
 
+		// then
+		assert_eq!(codes.is_valid(&credential, time), true);
+		assert_eq!(codes.is_valid(&generate_hash(code, time), time), false);
+		assert_eq!(codes.exchange(code).unwrap(), None);
+	}
+}
@@ -26,7 +26,7 @@ use std::sync::Arc;
 use std::net::SocketAddr;
 use io::{PanicHandler, OnPanicListener, MayPanic};
 use jsonrpc_core::{IoHandler, IoDelegate};
-use rpc::{Extendable, ConfirmationsQueue};
+use rpc::{Extendable, ConfirmationsQueue, AddressWatcher};
 
 mod session;
 
@@ -54,6 +54,7 @@ pub struct ServerBuilder {
 	handler: Arc<IoHandler>,
 	authcodes_path: PathBuf,
 	skip_origin_validation: bool,
+	address_watcher: Option<Arc<AddressWatcher>>,
 }
 
 impl Extendable for ServerBuilder {
@@ -70,6 +71,7 @@ impl ServerBuilder {
 			handler: Arc::new(IoHandler::new()),
 			authcodes_path: authcodes_path,
 			skip_origin_validation: false,
+			address_watcher: None,
 		}
 	}
 
@@ -80,10 +82,16 @@ impl ServerBuilder {
 		self
 	}
 
+	/// Lets clients watch addresses and pushes notifications about them over their connections.
+	pub fn address_watcher(mut self, watcher: Arc<AddressWatcher>) -> Self {
+		self.address_watcher = Some(watcher);
+		self
+	}
+
 	/// Starts a new `WebSocket` server in separate thread.
 	/// Returns a `Server` handle which closes the server when droped.
 	pub fn start(self, addr: SocketAddr) -> Result<Server, ServerError> {
-		Server::start(addr, self.handler, self.queue, self.authcodes_path, self.skip_origin_validation)
+		Server::start(addr, self.handler, self.queue, self.authcodes_path, self.skip_origin_validation, self.address_watcher)
 	}
 }
 
@@ -104,7 +112,14 @@ impl Server {
 
 	/// Starts a new `WebSocket` server in separate thread.
 	/// Returns a `Server` handle which closes the server when droped.
-	fn start(addr: SocketAddr, handler: Arc<IoHandler>, queue: Arc<ConfirmationsQueue>, authcodes_path: PathBuf, skip_origin_validation: bool) -> Result<Server, ServerError> {
+	fn start(
+		addr: SocketAddr,
+		handler: Arc<IoHandler>,
+		queue: Arc<ConfirmationsQueue>,
+		authcodes_path: PathBuf,
+		skip_origin_validation: bool,
+		address_watcher: Option<Arc<AddressWatcher>>,
+	) -> Result<Server, ServerError> {
 		let config = {
 			let mut config = ws::Settings::default();
 			// accept only handshakes beginning with GET
@@ -117,7 +132,7 @@ impl Server {
 		// Create WebSocket
 		let origin = format!("{}", addr);
 		let ws = try!(ws::Builder::new().with_settings(config).build(
-			session::Factory::new(handler, origin, authcodes_path, skip_origin_validation)
+			session::Factory::new(handler, origin, authcodes_path, skip_origin_validation, address_watcher)
 		));
 
 		let panic_handler = PanicHandler::new_in_arc();
@@ -22,6 +22,7 @@ use std::path::{PathBuf, Path};
 use std::sync::Arc;
 use std::str::FromStr;
 use jsonrpc_core::IoHandler;
+use rpc::{RpcSession, AddressWatcher};
 use util::{H256, Mutex, version};
 
 #[cfg(feature = "ui")]
@@ -106,7 +107,7 @@ pub struct Session {
 	skip_origin_validation: bool,
 	self_origin: String,
 	authcodes_path: PathBuf,
-	handler: Arc<IoHandler>,
+	rpc: RpcSession,
 }
 
 impl ws::Handler for Session {
@@ -163,15 +164,13 @@ impl ws::Handler for Session {
 
 	fn on_message(&mut self, msg: ws::Message) -> ws::Result<()> {
 		let req = try!(msg.as_text());
-		if let Some(async) = self.handler.handle_request(req) {
-			let out = self.out.clone();
-			async.on_result(move |result| {
-				let res = out.lock().send(result);
-				if let Err(e) = res {
-					warn!(target: "signer", "Error while sending response: {:?}", e);
-				}
-			});
-		}
+		let out = self.out.clone();
+		self.rpc.handle_request(req, move |result| {
+			let res = out.lock().send(result);
+			if let Err(e) = res {
+				warn!(target: "signer", "Error while sending response: {:?}", e);
+			}
+		});
 		Ok(())
 	}
 }
@@ -181,15 +180,17 @@ pub struct Factory {
 	skip_origin_validation: bool,
 	self_origin: String,
 	authcodes_path: PathBuf,
+	address_watcher: Option<Arc<AddressWatcher>>,
 }
 
 impl Factory {
-	pub fn new(handler: Arc<IoHandler>, self_origin: String, authcodes_path: PathBuf, skip_origin_validation: bool) -> Self {
+	pub fn new(handler: Arc<IoHandler>, self_origin: String, authcodes_path: PathBuf, skip_origin_validation: bool, address_watcher: Option<Arc<AddressWatcher>>) -> Self {
 		Factory {
 			handler: handler,
 			skip_origin_validation: skip_origin_validation,
 			self_origin: self_origin,
 			authcodes_path: authcodes_path,
+			address_watcher: address_watcher,
 		}
 	}
 }
@@ -198,9 +199,23 @@ impl ws::Factory for Factory {
 	type Handler = Session;
 
 	fn connection_made(&mut self, sender: ws::Sender) -> Self::Handler {
+		let out = Arc::new(Mutex::new(sender));
+		let rpc = RpcSession::new(self.handler.clone());
+		let rpc = match self.address_watcher {
+			Some(ref watcher) => {
+				let out = out.clone();
+				rpc.with_address_watcher(watcher.clone(), move |notification| {
+					if let Err(e) = out.lock().send(notification) {
+						warn!(target: "signer", "Error while sending notification: {:?}", e);
+					}
+				})
+			},
+			None => rpc,
+		};
+
 		Session {
-			out: Arc::new(Mutex::new(sender)),
-			handler: self.handler.clone(),
+			out: out,
+			rpc: rpc,
 			skip_origin_validation: self.skip_origin_validation,
 			self_origin: self.self_origin.clone(),
 			authcodes_path: self.authcodes_path.clone(),
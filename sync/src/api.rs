@@ -16,7 +16,7 @@
 
 use std::sync::Arc;
 use std::str;
-use network::{NetworkProtocolHandler, NetworkService, NetworkContext, PeerId,
+use network::{NetworkProtocolHandler, NetworkService, NetworkContext, PeerId, ConnectionFilter,
 	NetworkConfiguration as BasicNetworkConfiguration, NonReservedPeerMode, NetworkError};
 use util::{U256, H256};
 use io::{TimerToken};
@@ -41,6 +41,10 @@ pub struct SyncConfig {
 	pub subprotocol_name: [u8; 3],
 	/// Fork block to check
 	pub fork_block: Option<(BlockNumber, H256)>,
+	/// Trusted checkpoint block; snapshots taken before it are not used
+	pub checkpoint: Option<(BlockNumber, H256)>,
+	/// Relay transactions received from other peers
+	pub tx_relay: bool,
 }
 
 impl Default for SyncConfig {
@@ -50,6 +54,8 @@ impl Default for SyncConfig {
 			network_id: U256::from(1),
 			subprotocol_name: *b"eth",
 			fork_block: None,
+			checkpoint: None,
+			tx_relay: true,
 		}
 	}
 }
@@ -61,6 +67,12 @@ binary_fixed_size!(SyncStatus);
 pub trait SyncProvider: Send + Sync {
 	/// Get sync status
 	fn status(&self) -> SyncStatus;
+
+	/// Get comparison of the chain followed by peers with our chain
+	fn chain_status(&self) -> ChainStatus;
+
+	/// Get sync details of connected peers
+	fn peers(&self) -> Vec<PeerInfo>;
 }
 
 /// Ethereum network protocol handler
@@ -74,10 +86,11 @@ pub struct EthSync {
 }
 
 impl EthSync {
-	/// Creates and register protocol with the network service
-	pub fn new(config: SyncConfig, chain: Arc<BlockChainClient>, snapshot_service: Arc<SnapshotService>, network_config: NetworkConfiguration) -> Result<Arc<EthSync>, NetworkError> {
+	/// Creates and register protocol with the network service.
+	/// Connections are checked against `connection_filter` if given.
+	pub fn new(config: SyncConfig, chain: Arc<BlockChainClient>, snapshot_service: Arc<SnapshotService>, network_config: NetworkConfiguration, connection_filter: Option<Arc<ConnectionFilter>>) -> Result<Arc<EthSync>, NetworkError> {
 		let chain_sync = ChainSync::new(config, &*chain);
-		let service = try!(NetworkService::new(try!(network_config.into_basic())));
+		let service = try!(NetworkService::new(try!(network_config.into_basic()), connection_filter));
 		let sync = Arc::new(EthSync{
 			network: service,
 			handler: Arc::new(SyncProtocolHandler { sync: RwLock::new(chain_sync), chain: chain, snapshot_service: snapshot_service }),
@@ -94,6 +107,17 @@ impl SyncProvider for EthSync {
 	fn status(&self) -> SyncStatus {
 		self.handler.sync.write().status()
 	}
+
+	fn chain_status(&self) -> ChainStatus {
+		self.handler.sync.read().chain_status()
+	}
+
+	fn peers(&self) -> Vec<PeerInfo> {
+		self.network.with_context_eval(self.subprotocol_name, |context| {
+			let sync_io = NetSyncIo::new(context, &*self.handler.chain, &*self.handler.snapshot_service);
+			self.handler.sync.read().peers(&sync_io)
+		}).unwrap_or_else(Vec::new)
+	}
 }
 
 struct SyncProtocolHandler {
@@ -150,6 +174,13 @@ impl ChainNotify for EthSync {
 		});
 	}
 
+	fn block_sealed(&self, block: Vec<u8>, total_difficulty: U256) {
+		self.network.with_context(self.subprotocol_name, |context| {
+			let mut sync_io = NetSyncIo::new(context, &*self.handler.chain, &*self.handler.snapshot_service);
+			self.handler.sync.write().propagate_sealed_block(&mut sync_io, &block, &total_difficulty);
+		});
+	}
+
 	fn start(&self) {
 		self.network.start().unwrap_or_else(|e| warn!("Error starting network: {:?}", e));
 		self.network.register_protocol(self.handler.clone(), self.subprotocol_name, &[62u8, 63u8, 64u8])
@@ -180,6 +211,12 @@ pub trait ManageNetwork : Send + Sync {
 	fn stop_network(&self);
 	/// Query the current configuration of the network
 	fn network_config(&self) -> NetworkConfiguration;
+	/// Returns URLs of nodes in the node table, most reliable first
+	fn node_table(&self) -> Vec<String>;
+	/// Returns network traffic totals, current rates and per-peer byte counts
+	fn traffic_stats(&self) -> TrafficStats;
+	/// Returns estimated offset of the local clock against clocks of peers, in seconds
+	fn peer_clock_drift(&self) -> Option<i64>;
 }
 
 
@@ -216,6 +253,102 @@ impl ManageNetwork for EthSync {
 	fn network_config(&self) -> NetworkConfiguration {
 		NetworkConfiguration::from(self.network.config().clone())
 	}
+
+	fn node_table(&self) -> Vec<String> {
+		self.network.node_table()
+	}
+
+	fn traffic_stats(&self) -> TrafficStats {
+		let stats = self.network.stats();
+		TrafficStats {
+			bytes_received: stats.recv() as u64,
+			bytes_sent: stats.send() as u64,
+			receive_rate: stats.recv_rate() as u64,
+			send_rate: stats.send_rate() as u64,
+			download_throttled: stats.is_download_throttled(),
+			upload_throttled: stats.is_upload_throttled(),
+			peers: self.network.peer_traffic().into_iter().map(|p| PeerTrafficStats {
+				id: p.id.map(|id| id.hex()),
+				client_version: p.client_version,
+				bytes_received: p.bytes_received as u64,
+				bytes_sent: p.bytes_sent as u64,
+			}).collect(),
+		}
+	}
+
+	fn peer_clock_drift(&self) -> Option<i64> {
+		self.network.peer_clock_drift()
+	}
+}
+
+/// Comparison of the best blocks advertised by peers with our chain
+#[derive(Binary, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChainStatus {
+	/// Number of peers whose best block is in our canonical chain
+	pub agreeing_peers: usize,
+	/// Number of peers whose best block is not in our canonical chain at the same height
+	pub diverging_peers: usize,
+	/// Number of peers whose best block is ahead of ours or unknown
+	pub unknown_peers: usize,
+	/// Highest block number at which a peer diverges from our chain
+	pub diverging_block: Option<BlockNumber>,
+	/// Significant part of peers has been following a different chain for a while
+	pub split: bool,
+}
+
+/// Sync details of a connected peer
+#[derive(Binary, Debug, Clone, PartialEq, Eq)]
+pub struct PeerInfo {
+	/// Peer node id, hex encoded
+	pub id: Option<String>,
+	/// Peer client version
+	pub client_version: String,
+	/// Protocols agreed with the peer, e.g. `eth/63`
+	pub capabilities: Vec<String>,
+	/// Peer eth protocol version
+	pub eth_version: u32,
+	/// Peer best block hash
+	pub eth_head: H256,
+	/// Peer total difficulty if known
+	pub eth_difficulty: Option<U256>,
+	/// Number of items (headers, bodies or snapshot chunks) requested from the peer and not delivered yet
+	pub outstanding_requests: usize,
+	/// Number of new blocks announced by the peer
+	pub block_announcements: u64,
+	/// Average delay in milliseconds between a new block being first announced by any peer and this peer announcing it
+	pub block_propagation_delay: Option<u64>,
+}
+
+/// Traffic counters of a single connected peer
+#[derive(Binary, Debug, Clone, PartialEq, Eq)]
+pub struct PeerTrafficStats {
+	/// Peer node id, hex encoded
+	pub id: Option<String>,
+	/// Peer client version
+	pub client_version: String,
+	/// Bytes received from the peer
+	pub bytes_received: u64,
+	/// Bytes sent to the peer
+	pub bytes_sent: u64,
+}
+
+/// Network traffic statistics
+#[derive(Binary, Debug, Clone, PartialEq, Eq)]
+pub struct TrafficStats {
+	/// Total bytes received
+	pub bytes_received: u64,
+	/// Total bytes sent
+	pub bytes_sent: u64,
+	/// Current download rate in bytes per second
+	pub receive_rate: u64,
+	/// Current upload rate in bytes per second
+	pub send_rate: u64,
+	/// Whether download is currently capped
+	pub download_throttled: bool,
+	/// Whether upload is currently capped
+	pub upload_throttled: bool,
+	/// Per-peer traffic
+	pub peers: Vec<PeerTrafficStats>,
 }
 
 #[derive(Binary, Debug, Clone, PartialEq, Eq)]
@@ -247,6 +380,14 @@ pub struct NetworkConfiguration {
 	pub reserved_nodes: Vec<String>,
 	/// The non-reserved peer mode.
 	pub allow_non_reserved: bool,
+	/// Upload bandwidth cap in kilobytes per second
+	pub max_upload_kbps: Option<u32>,
+	/// Download bandwidth cap in kilobytes per second
+	pub max_download_kbps: Option<u32>,
+	/// Max number of non-reserved peers from a single IP address
+	pub max_peers_per_ip: Option<u32>,
+	/// Max number of non-reserved peers from a single subnet
+	pub max_peers_per_subnet: Option<u32>,
 }
 
 impl NetworkConfiguration {
@@ -284,6 +425,10 @@ impl NetworkConfiguration {
 			min_peers: self.min_peers,
 			reserved_nodes: self.reserved_nodes,
 			non_reserved_mode: if self.allow_non_reserved { NonReservedPeerMode::Accept } else { NonReservedPeerMode::Deny },
+			max_upload_kbps: self.max_upload_kbps,
+			max_download_kbps: self.max_download_kbps,
+			max_peers_per_ip: self.max_peers_per_ip,
+			max_peers_per_subnet: self.max_peers_per_subnet,
 		})
 	}
 }
@@ -304,6 +449,10 @@ impl From<BasicNetworkConfiguration> for NetworkConfiguration {
 			min_peers: other.min_peers,
 			reserved_nodes: other.reserved_nodes,
 			allow_non_reserved: match other.non_reserved_mode { NonReservedPeerMode::Accept => true, _ => false } ,
+			max_upload_kbps: other.max_upload_kbps,
+			max_download_kbps: other.max_download_kbps,
+			max_peers_per_ip: other.max_peers_per_ip,
+			max_peers_per_subnet: other.max_peers_per_subnet,
 		}
 	}
 }
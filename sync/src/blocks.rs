@@ -17,7 +17,7 @@
 use util::*;
 use rlp::*;
 use network::NetworkError;
-use ethcore::header::{ Header as BlockHeader};
+use ethcore::header::{ Header as BlockHeader, BlockNumber};
 
 known_heap_size!(0, HeaderId);
 
@@ -25,6 +25,7 @@ known_heap_size!(0, HeaderId);
 struct SyncBlock {
 	header: Bytes,
 	body: Option<Bytes>,
+	number: BlockNumber,
 }
 
 impl HeapSizeOf for SyncBlock {
@@ -116,8 +117,9 @@ impl BlockCollection {
 		inserted
 	}
 
-	/// Returns a set of block hashes that require a body download. The returned set is marked as being downloaded.
-	pub fn needed_bodies(&mut self, count: usize, _ignore_downloading: bool) -> Vec<H256> {
+	/// Returns a set of block hashes that require a body download, skipping blocks below `from`.
+	/// The returned set is marked as being downloaded.
+	pub fn needed_bodies(&mut self, count: usize, _ignore_downloading: bool, from: BlockNumber) -> Vec<H256> {
 		if self.head.is_none() {
 			return Vec::new();
 		}
@@ -127,7 +129,7 @@ impl BlockCollection {
 			head = self.parents.get(&head.unwrap()).cloned();
 			if let Some(head) = head {
 				match self.blocks.get(&head) {
-					Some(block) if block.body.is_none() && !self.downloading_bodies.contains(&head) => {
+					Some(block) if block.body.is_none() && block.number >= from && !self.downloading_bodies.contains(&head) => {
 						self.downloading_bodies.insert(head.clone());
 						needed_bodies.push(head.clone());
 					}
@@ -139,7 +141,7 @@ impl BlockCollection {
 			if needed_bodies.len() >= count {
 				break;
 			}
-			if !self.downloading_bodies.contains(h) {
+			if !self.downloading_bodies.contains(h) && self.blocks.get(h).map_or(false, |b| b.number >= from) {
 				needed_bodies.push(h.clone());
 				self.downloading_bodies.insert(h.clone());
 			}
@@ -235,6 +237,21 @@ impl BlockCollection {
 			+ self.downloading_bodies.heap_size_of_children()
 	}
 
+	/// Check if any headers are being downloaded.
+	pub fn is_downloading_headers(&self) -> bool {
+		!self.downloading_headers.is_empty()
+	}
+
+	/// Number of blocks with a downloaded header still waiting for the body.
+	pub fn missing_bodies(&self) -> usize {
+		self.blocks.values().filter(|b| b.body.is_none()).count()
+	}
+
+	/// Number of a block with a downloaded header.
+	pub fn block_number(&self, hash: &H256) -> Option<BlockNumber> {
+		self.blocks.get(hash).map(|b| b.number)
+	}
+
 	/// Check if given block hash is marked as being downloaded.
 	pub fn is_downloading(&self, hash: &H256) -> bool {
 		self.downloading_headers.contains(hash) || self.downloading_bodies.contains(hash)
@@ -289,6 +306,7 @@ impl BlockCollection {
 		let mut block = SyncBlock {
 			header: header,
 			body: None,
+			number: info.number(),
 		};
 		let header_id = HeaderId {
 			transactions_root: info.transactions_root().clone(),
@@ -387,7 +405,7 @@ mod test {
 		bc.reset_to(heads);
 		assert!(!bc.is_empty());
 		assert_eq!(hashes[0], bc.heads[0]);
-		assert!(bc.needed_bodies(1, false).is_empty());
+		assert!(bc.needed_bodies(1, false, 0).is_empty());
 		assert!(!bc.contains(&hashes[0]));
 		assert!(!bc.is_downloading(&hashes[0]));
 
@@ -426,6 +444,24 @@ mod test {
 		assert!(bc.is_empty());
 	}
 
+	#[test]
+	fn needed_bodies_from_number() {
+		let mut bc = BlockCollection::new();
+		let client = TestBlockChainClient::new();
+		let nblocks = 10;
+		client.add_blocks(nblocks, EachBlockWith::Uncle);
+		let blocks: Vec<_> = (0 .. nblocks).map(|i| (&client as &BlockChainClient).block(BlockID::Number(i as BlockNumber)).unwrap()).collect();
+		let headers: Vec<_> = blocks.iter().map(|b| Rlp::new(b).at(0).as_raw().to_vec()).collect();
+		let hashes: Vec<_> = headers.iter().map(|h| HeaderView::new(h).sha3()).collect();
+		bc.reset_to(vec![hashes[0].clone()]);
+		bc.insert_headers(headers[0..6].to_vec());
+		assert_eq!(bc.block_number(&hashes[4]), Some(4));
+
+		assert_eq!(bc.needed_bodies(10, false, 3), hashes[3..6].to_vec());
+		assert_eq!(bc.needed_bodies(10, false, 0), hashes[1..3].to_vec());
+		assert!(bc.needed_bodies(10, false, 0).is_empty());
+	}
+
 	#[test]
 	fn insert_headers_with_gap() {
 		let mut bc = BlockCollection::new();
@@ -99,9 +99,11 @@ use ethcore::block::Block;
 use ethcore::snapshot::{ManifestData, RestorationStatus};
 use sync_io::SyncIo;
 use time;
-use super::SyncConfig;
+use super::{SyncConfig, ChainStatus};
+use api::PeerInfo as PeerInfoDigest;
 use blocks::BlockCollection;
 use snapshot::{Snapshot, ChunkType};
+use rolling_bloom::RollingBloom;
 use rand::{thread_rng, Rng};
 
 known_heap_size!(0, PeerInfo);
@@ -115,6 +117,10 @@ const MAX_RECEIPTS_TO_SEND: usize = 1024;
 const MAX_RECEIPTS_HEADERS_TO_SEND: usize = 256;
 const MAX_HEADERS_TO_REQUEST: usize = 128;
 const MAX_BODIES_TO_REQUEST: usize = 128;
+/// Headers are downloaded ahead of bodies until that many blocks wait for their bodies.
+const MAX_HEADERS_AHEAD: usize = 4 * MAX_BODIES_TO_REQUEST;
+/// Peer is deactivated after that many body requests in a row answered with nothing.
+const MAX_EMPTY_BODY_REPLIES: usize = 3;
 const MIN_PEERS_PROPAGATION: usize = 4;
 const MAX_PEERS_PROPAGATION: usize = 128;
 const MAX_PEER_LAG_PROPAGATION: BlockNumber = 20;
@@ -122,7 +128,20 @@ const SUBCHAIN_SIZE: usize = 256;
 const MAX_ROUND_PARENTS: usize = 32;
 const MAX_NEW_HASHES: usize = 64;
 const MAX_TX_TO_IMPORT: usize = 512;
+const MAX_TRANSACTIONS_TO_PROPAGATE: usize = 256;
+const KNOWN_TRANSACTIONS_PER_PEER: usize = 2048;
+const KNOWN_NETWORK_TRANSACTIONS: usize = 16384;
 const MAX_NEW_BLOCK_AGE: BlockNumber = 20;
+/// Maximal number of transactions requested from announcing peers at once.
+const MAX_REQUESTED_TRANSACTIONS: usize = 4096;
+/// Maximal number of peers remembered to retry a transaction request with.
+const MAX_TRANSACTION_ANNOUNCERS: usize = 8;
+const CHAIN_SPLIT_MIN_PEERS: usize = 2;
+const CHAIN_SPLIT_PEERS_PERCENT: usize = 25;
+/// Blocks first announced longer ago (in seconds) are no longer used to measure peer announcement delays.
+const BLOCK_ANNOUNCEMENT_WINDOW_SEC: f64 = 60f64;
+/// Weight of the latest measurement in the average announcement delay of a peer.
+const BLOCK_ANNOUNCEMENT_DELAY_WEIGHT: f64 = 0.2;
 
 const STATUS_PACKET: u8 = 0x00;
 const NEW_BLOCK_HASHES_PACKET: u8 = 0x01;
@@ -132,6 +151,8 @@ const BLOCK_HEADERS_PACKET: u8 = 0x04;
 const GET_BLOCK_BODIES_PACKET: u8 = 0x05;
 const BLOCK_BODIES_PACKET: u8 = 0x06;
 const NEW_BLOCK_PACKET: u8 = 0x07;
+const NEW_TRANSACTION_HASHES_PACKET: u8 = 0x08;
+const GET_TRANSACTIONS_PACKET: u8 = 0x09;
 
 const GET_NODE_DATA_PACKET: u8 = 0x0d;
 const NODE_DATA_PACKET: u8 = 0x0e;
@@ -147,12 +168,16 @@ const BODIES_TIMEOUT_SEC: f64 = 5f64;
 const FORK_HEADER_TIMEOUT_SEC: f64 = 3f64;
 const SNAPSHOT_MANIFEST_TIMEOUT_SEC: f64 = 3f64;
 const SNAPSHOT_DATA_TIMEOUT_SEC: f64 = 10f64;
+const TRANSACTIONS_TIMEOUT_SEC: f64 = 5f64;
+const CHAIN_SPLIT_TIMEOUT_SEC: f64 = 60f64;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 /// Sync state
 pub enum SyncState {
 	/// Waiting for pv64 peers to start snapshot syncing
 	SnapshotManifest,
+	/// Downloading headers linking the snapshot block to the trusted checkpoint
+	CheckpointHeaders,
 	/// Downloading snapshot data
 	SnapshotData,
 	/// Waiting for snapshot restoration to complete
@@ -182,7 +207,7 @@ pub struct SyncStatus {
 	pub start_block_number: BlockNumber,
 	/// Last fully downloaded and imported block number (if any).
 	pub last_imported_block_number: Option<BlockNumber>,
-	/// Highest block number in the download queue (if any).
+	/// Highest block number known to the majority of peers, or in the download queue if peers heights are unknown (if any).
 	pub highest_block_number: Option<BlockNumber>,
 	/// Total number of blocks for the sync process.
 	pub blocks_total: BlockNumber,
@@ -198,6 +223,8 @@ pub struct SyncStatus {
 	pub num_snapshot_chunks: usize,
 	/// Snapshot chunks downloaded
 	pub snapshot_chunks_done: usize,
+	/// Bytes not sent thanks to announcing transaction hashes instead of bodies.
+	pub transaction_bytes_saved: u64,
 }
 
 impl SyncStatus {
@@ -226,6 +253,7 @@ enum PeerAsking {
 	Heads,
 	SnapshotManifest,
 	SnapshotData,
+	CheckpointHeaders,
 }
 
 #[derive(Clone, Eq, PartialEq)]
@@ -265,6 +293,8 @@ struct PeerInfo {
 	ask_time: f64,
 	/// Holds a set of transactions recently sent to this peer to avoid spamming.
 	last_sent_transactions: HashSet<H256>,
+	/// Transactions recently received from this peer. These are never sent back.
+	known_transactions: RollingBloom,
 	/// Pending request is expired and result should be ignored
 	expired: bool,
 	/// Peer fork confirmation status
@@ -273,6 +303,17 @@ struct PeerInfo {
 	snapshot_hash: Option<H256>,
 	/// Best snapshot block number
 	snapshot_number: Option<BlockNumber>,
+	/// Average delay (in seconds) between a new block being first announced by any peer and this peer announcing it
+	block_announcement_delay: Option<f64>,
+	/// Number of new blocks announced by this peer
+	block_announcements: u64,
+	/// Lowest block number this peer is asked for bodies. Raised when the peer answers a body request
+	/// with nothing, so peers that keep only headers or only recent bodies are still used for the rest.
+	bodies_from: BlockNumber,
+	/// Number of body requests in a row answered with nothing
+	empty_body_replies: usize,
+	/// Peer gets transaction hashes and asks for the transactions it misses
+	transaction_hashes: bool,
 }
 
 impl PeerInfo {
@@ -285,6 +326,26 @@ impl PeerInfo {
 	}
 }
 
+/// Announced transaction requested from a peer.
+struct TransactionRequest {
+	/// Peer the transaction is requested from.
+	peer: PeerId,
+	/// Time of the request.
+	ask_time: f64,
+	/// Other peers which announced the transaction, asked in turn if the request times out.
+	announcers: Vec<PeerId>,
+}
+
+/// Snapshot waiting for its block to be linked to the trusted checkpoint.
+struct CheckpointLink {
+	/// Manifest of the snapshot.
+	manifest: ManifestData,
+	/// Hash of the manifest.
+	manifest_hash: H256,
+	/// Number and hash of the last header linked to the checkpoint.
+	last: (BlockNumber, H256),
+}
+
 /// Blockchain sync handler.
 /// See module documentation for more details.
 pub struct ChainSync {
@@ -318,8 +379,30 @@ pub struct ChainSync {
 	network_id: U256,
 	/// Optional fork block to check
 	fork_block: Option<(BlockNumber, H256)>,
+	/// Optional trusted checkpoint. Snapshots taken before it are ignored.
+	checkpoint: Option<(BlockNumber, H256)>,
+	/// Snapshot which block is being linked to the checkpoint.
+	checkpoint_link: Option<CheckpointLink>,
 	/// Snapshot downloader.
 	snapshot: Snapshot,
+	/// Block download is paused because the download bandwidth cap has been reached.
+	download_throttled: bool,
+	/// Relay transactions received from other peers.
+	tx_relay: bool,
+	/// Transactions recently received from the network.
+	network_transactions: RollingBloom,
+	/// Last comparison of peer chains with ours.
+	chain_status: ChainStatus,
+	/// Time when peers started diverging from our chain.
+	diverging_since: Option<f64>,
+	/// Locally sealed blocks sent to peers before they were imported.
+	announced_sealed: HashSet<H256>,
+	/// Bytes not sent thanks to announcing transaction hashes instead of bodies.
+	transaction_bytes_saved: u64,
+	/// Time new blocks were first announced and peers which announced them since.
+	block_announcements: HashMap<H256, (f64, HashSet<PeerId>)>,
+	/// Announced transactions being requested from peers.
+	requested_transactions: HashMap<H256, TransactionRequest>,
 }
 
 type RlpResponseResult = Result<Option<(PacketId, RlpStream)>, PacketDecodeError>;
@@ -343,26 +426,44 @@ impl ChainSync {
 			round_parents: VecDeque::new(),
 			_max_download_ahead_blocks: max(MAX_HEADERS_TO_REQUEST, config.max_download_ahead_blocks),
 			network_id: config.network_id,
-			fork_block: config.fork_block,
+			// A peer having the checkpoint also has every block before it,
+			// so confirm the checkpoint instead of the fork block when it is later.
+			fork_block: match (config.fork_block, config.checkpoint) {
+				(Some(fork), Some(checkpoint)) if checkpoint.0 > fork.0 => Some(checkpoint),
+				(fork, checkpoint) => fork.or(checkpoint),
+			},
+			checkpoint: config.checkpoint,
+			checkpoint_link: None,
 			snapshot: Snapshot::new(),
+			download_throttled: false,
+			tx_relay: config.tx_relay,
+			network_transactions: RollingBloom::new(KNOWN_NETWORK_TRANSACTIONS),
+			chain_status: ChainStatus::default(),
+			diverging_since: None,
+			announced_sealed: HashSet::new(),
+			transaction_bytes_saved: 0,
+			block_announcements: HashMap::new(),
+			requested_transactions: HashMap::new(),
 		}
 	}
 
 	/// @returns Synchonization status
 	pub fn status(&self) -> SyncStatus {
+		let highest_block = self.peers_highest_block().or(self.highest_block);
 		SyncStatus {
 			state: self.state.clone(),
 			protocol_version: if self.state == SyncState::SnapshotData { 64 } else { 63 },
 			network_id: self.network_id,
 			start_block_number: self.starting_block,
 			last_imported_block_number: Some(self.last_imported_block),
-			highest_block_number: self.highest_block.map(|n| max(n, self.last_imported_block)),
+			highest_block_number: highest_block.map(|n| max(n, self.last_imported_block)),
 			blocks_received: if self.last_imported_block > self.starting_block { self.last_imported_block - self.starting_block } else { 0 },
-			blocks_total: match self.highest_block { Some(x) if x > self.starting_block => x - self.starting_block, _ => 0 },
+			blocks_total: match highest_block { Some(x) if x > self.starting_block => x - self.starting_block, _ => 0 },
 			num_peers: self.peers.values().filter(|p| p.is_allowed()).count(),
 			num_active_peers: self.peers.values().filter(|p| p.is_allowed() && p.asking != PeerAsking::Nothing).count(),
 			num_snapshot_chunks: self.snapshot.total_chunks(),
 			snapshot_chunks_done: self.snapshot.done_chunks(),
+			transaction_bytes_saved: self.transaction_bytes_saved,
 			mem_used:
 				self.blocks.heap_size()
 				+ self.peers.heap_size_of_children()
@@ -370,6 +471,46 @@ impl ChainSync {
 		}
 	}
 
+	/// Returns sync details of connected peers.
+	pub fn peers(&self, io: &SyncIo) -> Vec<PeerInfoDigest> {
+		self.peers.iter().map(|(&peer_id, peer)| {
+			let session = io.peer_session_info(peer_id);
+			PeerInfoDigest {
+				id: session.as_ref().and_then(|s| s.id.as_ref()).map(|id| id.hex()),
+				client_version: session.as_ref().map_or_else(|| io.peer_info(peer_id), |s| s.client_version.clone()),
+				capabilities: session.as_ref().map_or_else(Vec::new, |s| s.capability_names()),
+				eth_version: peer.protocol_version,
+				eth_head: peer.latest_hash.clone(),
+				eth_difficulty: peer.difficulty,
+				outstanding_requests: match peer.asking {
+					PeerAsking::Nothing => 0,
+					_ => max(peer.asking_blocks.len(), 1),
+				},
+				block_announcements: peer.block_announcements,
+				block_propagation_delay: peer.block_announcement_delay.map(|delay| (delay * 1000f64) as u64),
+			}
+		}).collect()
+	}
+
+	/// Median of the best block numbers advertised by peers. A single peer
+	/// announcing a bogus height can't move it.
+	fn peers_highest_block(&self) -> Option<BlockNumber> {
+		let mut heights: Vec<BlockNumber> = self.peers.values()
+			.filter(|p| p.is_allowed())
+			.filter_map(|p| p.latest_number)
+			.collect();
+		if heights.is_empty() {
+			return None;
+		}
+		heights.sort();
+		Some(heights[heights.len() / 2])
+	}
+
+	/// Returns comparison of the chain followed by peers with ours
+	pub fn chain_status(&self) -> ChainStatus {
+		self.chain_status.clone()
+	}
+
 	/// Abort all sync activity
 	pub fn abort(&mut self, io: &mut SyncIo) {
 		self.restart(io);
@@ -381,6 +522,7 @@ impl ChainSync {
 	fn reset(&mut self, io: &mut SyncIo) {
 		self.blocks.clear();
 		self.snapshot.clear();
+		self.checkpoint_link = None;
 		if self.state == SyncState::SnapshotData {
 			debug!(target:"sync", "Aborting snapshot restore");
 			io.snapshot_service().abort_restore();
@@ -450,11 +592,17 @@ impl ChainSync {
 			asking_hash: None,
 			ask_time: 0f64,
 			last_sent_transactions: HashSet::new(),
+			known_transactions: RollingBloom::new(KNOWN_TRANSACTIONS_PER_PEER),
 			expired: false,
 			confirmation: if self.fork_block.is_none() { ForkConfirmation::Confirmed } else { ForkConfirmation::Unconfirmed },
 			asking_snapshot_data: None,
-			snapshot_hash: if protocol_version == 64 { Some(try!(r.val_at(5))) } else { None },
-			snapshot_number: if protocol_version == 64 { Some(try!(r.val_at(6))) } else { None },
+			snapshot_hash: if protocol_version >= 64 { Some(try!(r.val_at(5))) } else { None },
+			snapshot_number: if protocol_version >= 64 { Some(try!(r.val_at(6))) } else { None },
+			block_announcement_delay: None,
+			block_announcements: 0,
+			bodies_from: 0,
+			empty_body_replies: 0,
+			transaction_hashes: protocol_version >= 64 && r.item_count() > 7 && try!(r.val_at(7)),
 		};
 
 		trace!(target: "sync", "New peer {} (protocol: {}, network: {:?}, difficulty: {:?}, latest:{}, genesis:{})", peer_id, peer.protocol_version, peer.network_id, peer.difficulty, peer.latest_hash, peer.genesis);
@@ -518,6 +666,9 @@ impl ChainSync {
 			self.sync_peer(io, peer_id, false);
 			return Ok(());
 		}
+		if self.peers.get(&peer_id).map_or(false, |p| p.asking == PeerAsking::CheckpointHeaders) {
+			return self.on_checkpoint_headers(io, peer_id, r);
+		}
 
 		self.clear_peer_download(peer_id);
 		let expected_asking = if self.state == SyncState::ChainHead { PeerAsking::Heads } else { PeerAsking::BlockHeaders };
@@ -551,6 +702,11 @@ impl ChainSync {
 		for i in 0..item_count {
 			let info: BlockHeader = try!(r.val_at(i));
 			let number = BlockNumber::from(info.number());
+			if let Some(ref mut peer) = self.peers.get_mut(&peer_id) {
+				if peer.latest_hash == info.hash() {
+					peer.latest_number = Some(number);
+				}
+			}
 			// Check if any of the headers matches the hash we requested
 			if !valid_response {
 				if let Some(expected) = expected_hash {
@@ -627,14 +783,96 @@ impl ChainSync {
 		Ok(())
 	}
 
+	/// Called by peer once it has headers following the checkpoint.
+	/// Snapshot restoration starts once the snapshot block is linked to the checkpoint.
+	fn on_checkpoint_headers(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		self.reset_peer_asking(peer_id, PeerAsking::CheckpointHeaders);
+		let mut link = match (self.state == SyncState::CheckpointHeaders, self.checkpoint_link.take()) {
+			(true, Some(link)) => link,
+			_ => {
+				trace!(target: "sync", "{}: Ignored unexpected checkpoint headers", peer_id);
+				self.continue_sync(io);
+				return Ok(());
+			},
+		};
+
+		let item_count = r.item_count();
+		let mut linked = item_count > 0;
+		for i in 0..item_count {
+			let header: BlockHeader = try!(r.val_at(i));
+			if header.number() != link.last.0 + 1 || *header.parent_hash() != link.last.1 {
+				linked = false;
+				break;
+			}
+			link.last = (header.number(), header.hash());
+			if link.last.0 == link.manifest.block_number {
+				break;
+			}
+		}
+
+		if !linked {
+			trace!(target: "sync", "{}: No headers following #{} of the checkpoint chain", peer_id, link.last.0);
+			self.checkpoint_link = Some(link);
+			// try the other peers
+			match item_count {
+				0 => self.deactivate_peer(io, peer_id),
+				_ => io.disable_peer(peer_id),
+			}
+			self.continue_sync(io);
+			return Ok(());
+		}
+
+		if link.last.0 < link.manifest.block_number {
+			trace!(target: "sync", "{}: Linked #{} to the checkpoint", peer_id, link.last.0);
+			self.checkpoint_link = Some(link);
+		} else if link.last.1 == link.manifest.block_hash {
+			trace!(target: "sync", "{}: Linked snapshot at #{} to the checkpoint", peer_id, link.last.0);
+			self.begin_snapshot_restore(io, link.manifest, &link.manifest_hash);
+		} else {
+			trace!(target: "sync", "Snapshot at #{} does not follow the checkpoint", link.last.0);
+			for peer in self.peers.values_mut().filter(|p| p.snapshot_hash == Some(link.manifest_hash)) {
+				peer.snapshot_number = None;
+				peer.snapshot_hash = None;
+			}
+			self.state = SyncState::Idle;
+		}
+
+		// give a task to the same peer first.
+		self.sync_peer(io, peer_id, false);
+		// give tasks to other peers
+		self.continue_sync(io);
+		Ok(())
+	}
+
 	/// Called by peer once it has new block bodies
 	fn on_peer_block_bodies(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		let requested = match self.peers.get(&peer_id) {
+			Some(peer) if peer.asking == PeerAsking::BlockBodies => peer.asking_blocks.clone(),
+			_ => Vec::new(),
+		};
 		self.clear_peer_download(peer_id);
 		self.reset_peer_asking(peer_id, PeerAsking::BlockBodies);
 		let item_count = r.item_count();
 		trace!(target: "sync", "{} -> BlockBodies ({} entries)", peer_id, item_count);
 		if item_count == 0 {
-			self.deactivate_peer(io, peer_id);
+			// The peer might not keep bodies this old. Keep it for headers and newer bodies,
+			// unless it keeps answering with nothing.
+			let highest_requested = requested.iter().filter_map(|h| self.blocks.block_number(h)).max();
+			let exhausted = self.peers.get_mut(&peer_id).map_or(true, |peer| {
+				peer.empty_body_replies += 1;
+				peer.empty_body_replies >= MAX_EMPTY_BODY_REPLIES
+			});
+			match (highest_requested, exhausted) {
+				(Some(number), false) => {
+					trace!(target: "sync", "{}: No bodies up to #{}", peer_id, number);
+					let peer = self.peers.get_mut(&peer_id).expect("Peer has been found above; qed");
+					peer.bodies_from = max(peer.bodies_from, number + 1);
+				},
+				_ => {
+					trace!(target: "sync", "{}: Deactivating peer for giving no block bodies", peer_id);
+					self.deactivate_peer(io, peer_id);
+				},
+			}
 		}
 		else if self.state != SyncState::Blocks && self.state != SyncState::NewBlocks && self.state != SyncState::Waiting {
 			trace!(target: "sync", "Ignored unexpected block bodies");
@@ -644,6 +882,9 @@ impl ChainSync {
 		}
 		else
 		{
+			if let Some(peer) = self.peers.get_mut(&peer_id) {
+				peer.empty_body_replies = 0;
+			}
 			let mut bodies = Vec::with_capacity(item_count);
 			for i in 0..item_count {
 				bodies.push(try!(r.at(i)).as_raw().to_vec());
@@ -679,6 +920,8 @@ impl ChainSync {
 			peer.latest_hash = header.hash();
 			peer.latest_number = Some(header.number());
 		}
+		let is_new = io.chain().block_status(BlockID::Hash(h.clone())) == BlockStatus::Unknown;
+		self.note_block_announcement(peer_id, &h, is_new);
 		if self.last_imported_block > header.number() && self.last_imported_block - header.number() > MAX_NEW_BLOCK_AGE {
 			trace!(target: "sync", "Ignored ancient new block {:?}", h);
 			io.disable_peer(peer_id);
@@ -759,7 +1002,9 @@ impl ChainSync {
 				io.disable_peer(peer_id);
 				continue;
 			}
-			match io.chain().block_status(BlockID::Hash(hash.clone())) {
+			let status = io.chain().block_status(BlockID::Hash(hash.clone()));
+			self.note_block_announcement(peer_id, &hash, status == BlockStatus::Unknown);
+			match status {
 				BlockStatus::InChain  => {
 					trace!(target: "sync", "New block hash already in chain {:?}", hash);
 				},
@@ -815,9 +1060,30 @@ impl ChainSync {
 			}
 			Ok(manifest) => manifest,
 		};
-		self.snapshot.reset_to(&manifest, &manifest_rlp.as_raw().sha3());
-		io.snapshot_service().begin_restore(manifest);
-		self.state = SyncState::SnapshotData;
+		let manifest_hash = manifest_rlp.as_raw().sha3();
+		match self.checkpoint {
+			Some((number, hash)) if manifest.block_number < number || (manifest.block_number == number && manifest.block_hash != hash) => {
+				trace!(target: "sync", "{}: Ignored manifest at #{} not following the checkpoint", peer_id, manifest.block_number);
+				if let Some(ref mut peer) = self.peers.get_mut(&peer_id) {
+					peer.snapshot_number = None;
+					peer.snapshot_hash = None;
+				}
+				self.state = SyncState::Idle;
+				self.continue_sync(io);
+				return Ok(());
+			},
+			Some((number, hash)) if manifest.block_number > number => {
+				// download headers from the checkpoint up to the snapshot block first
+				trace!(target: "sync", "{}: Linking manifest at #{} to the checkpoint", peer_id, manifest.block_number);
+				self.checkpoint_link = Some(CheckpointLink {
+					manifest: manifest,
+					manifest_hash: manifest_hash,
+					last: (number, hash),
+				});
+				self.state = SyncState::CheckpointHeaders;
+			},
+			_ => self.begin_snapshot_restore(io, manifest, &manifest_hash),
+		}
 
 		// give a task to the same peer first.
 		self.sync_peer(io, peer_id, false);
@@ -826,6 +1092,13 @@ impl ChainSync {
 		Ok(())
 	}
 
+	/// Starts restoring the snapshot with given manifest.
+	fn begin_snapshot_restore(&mut self, io: &mut SyncIo, manifest: ManifestData, manifest_hash: &H256) {
+		self.snapshot.reset_to(&manifest, manifest_hash);
+		io.snapshot_service().begin_restore(manifest);
+		self.state = SyncState::SnapshotData;
+	}
+
 	/// Called when snapshot data is downloaded from a peer.
 	fn on_snapshot_data(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
 		if !self.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
@@ -905,6 +1178,11 @@ impl ChainSync {
 
 	/// Resume downloading
 	fn continue_sync(&mut self, io: &mut SyncIo) {
+		if io.is_download_throttled() {
+			trace!(target: "sync", "Download bandwidth cap reached, pausing requests");
+			self.download_throttled = true;
+			return;
+		}
 		let mut peers: Vec<(PeerId, U256, u32)> = self.peers.iter().filter_map(|(k, p)|
 			if p.can_sync() { Some((*k, p.difficulty.unwrap_or_else(U256::zero), p.protocol_version)) } else { None }).collect();
 		thread_rng().shuffle(&mut peers); //TODO: sort by rating
@@ -959,12 +1237,13 @@ impl ChainSync {
 		let chain_info = io.chain().chain_info();
 		let td = chain_info.pending_total_difficulty;
 		let syncing_difficulty = max(self.syncing_difficulty, td);
+		let min_snapshot_number = self.checkpoint.map_or(1, |(number, _)| max(number, 1));
 
 		if force || self.state == SyncState::NewBlocks || peer_difficulty.map_or(true, |pd| pd > syncing_difficulty) {
 			match self.state {
 				SyncState::Idle => {
 					// check if we can start snapshot sync with this peer
-					if peer_snapshot_number.unwrap_or(0) > 0 && chain_info.best_block_number == 0 {
+					if peer_snapshot_number.unwrap_or(0) >= min_snapshot_number && chain_info.best_block_number == 0 {
 						self.start_snapshot_sync(io, peer_id);
 					} else {
 						if self.last_imported_block < chain_info.best_block_number {
@@ -994,6 +1273,12 @@ impl ChainSync {
 						self.request_snapshot_data(io, peer_id);
 					}
 				},
+				SyncState::CheckpointHeaders => {
+					// headers are linked one batch after another
+					if !self.peers.values().any(|p| p.asking == PeerAsking::CheckpointHeaders) {
+						self.request_checkpoint_headers(io, peer_id);
+					}
+				},
 				SyncState::SnapshotManifest | //already downloading from other peer
 					SyncState::Waiting | SyncState::SnapshotWaiting => ()
 			}
@@ -1039,18 +1324,34 @@ impl ChainSync {
 			return;
 		}
 
-		// check to see if we need to download any block bodies first
-		let needed_bodies = self.blocks.needed_bodies(MAX_BODIES_TO_REQUEST, ignore_others);
+		// headers are downloaded first: one peer keeps fetching headers ahead
+		// while the others fetch bodies of the headers already downloaded.
+		if !self.blocks.is_downloading_headers() && self.blocks.missing_bodies() < MAX_HEADERS_AHEAD
+			&& self.request_headers(io, peer_id, ignore_others) {
+			return;
+		}
+
+		// peers without the bodies are given headers to download instead
+		let bodies_from = self.peers.get(&peer_id).map_or(0, |p| p.bodies_from);
+		let needed_bodies = self.blocks.needed_bodies(MAX_BODIES_TO_REQUEST, ignore_others, bodies_from);
 		if !needed_bodies.is_empty() {
 			replace(&mut self.peers.get_mut(&peer_id).unwrap().asking_blocks, needed_bodies.clone());
 			self.request_bodies(io, peer_id, needed_bodies);
 			return;
 		}
 
-		// find subchain to download
-		if let Some((h, count)) = self.blocks.needed_headers(MAX_HEADERS_TO_REQUEST, ignore_others) {
-			replace(&mut self.peers.get_mut(&peer_id).unwrap().asking_blocks, vec![h.clone()]);
-			self.request_headers_by_hash(io, peer_id, &h, count, 0, false, PeerAsking::BlockHeaders);
+		self.request_headers(io, peer_id, ignore_others);
+	}
+
+	/// Find a subchain to download headers of. Returns `true` if the peer has been asked for headers.
+	fn request_headers(&mut self, io: &mut SyncIo, peer_id: PeerId, ignore_others: bool) -> bool {
+		match self.blocks.needed_headers(MAX_HEADERS_TO_REQUEST, ignore_others) {
+			Some((h, count)) => {
+				replace(&mut self.peers.get_mut(&peer_id).unwrap().asking_blocks, vec![h.clone()]);
+				self.request_headers_by_hash(io, peer_id, &h, count, 0, false, PeerAsking::BlockHeaders);
+				true
+			},
+			None => false,
 		}
 	}
 
@@ -1184,6 +1485,15 @@ impl ChainSync {
 		self.send_request(sync, peer_id, asking, GET_BLOCK_HEADERS_PACKET, rlp.out());
 	}
 
+	/// Request next headers following the last one linked to the checkpoint.
+	fn request_checkpoint_headers(&mut self, sync: &mut SyncIo, peer_id: PeerId) {
+		let (from, count) = match self.checkpoint_link {
+			Some(ref link) => (link.last.0 + 1, min(MAX_HEADERS_TO_REQUEST as BlockNumber, link.manifest.block_number - link.last.0) as usize),
+			None => return,
+		};
+		self.request_headers_by_number(sync, peer_id, from, count, 0, false, PeerAsking::CheckpointHeaders);
+	}
+
 	/// Request snapshot manifest from a peer.
 	fn request_snapshot_manifest(&mut self, sync: &mut SyncIo, peer_id: PeerId) {
 		trace!(target: "sync", "{} <- GetSnapshotManifest", peer_id);
@@ -1262,16 +1572,120 @@ impl ChainSync {
 		let mut transactions = Vec::with_capacity(item_count);
 		for i in 0 .. item_count {
 			let tx = try!(r.at(i)).as_raw().to_vec();
+			let hash = tx.sha3();
+			if let Some(peer) = self.peers.get_mut(&peer_id) {
+				peer.known_transactions.insert(&hash);
+			}
+			self.network_transactions.insert(&hash);
+			self.requested_transactions.remove(&hash);
 			transactions.push(tx);
 		}
 		io.chain().queue_transactions(transactions);
 		Ok(())
 	}
 
+	/// Called when peer announces transaction hashes. Asks for the transactions we don't have.
+	fn on_peer_new_transaction_hashes(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		// accepting transactions once only fully synced
+		if !io.is_chain_queue_empty() {
+			return Ok(());
+		}
+
+		let item_count = min(r.item_count(), MAX_TX_TO_IMPORT);
+		trace!(target: "sync", "{} -> NewTransactionHashes ({} entries)", peer_id, item_count);
+		let tick = time::precise_time_s();
+		let mut missing = Vec::new();
+		for i in 0 .. item_count {
+			let hash: H256 = try!(r.val_at(i));
+			if let Some(peer) = self.peers.get_mut(&peer_id) {
+				peer.known_transactions.insert(&hash);
+			}
+			if let Some(request) = self.requested_transactions.get_mut(&hash) {
+				// already requested, keep the peer to retry with
+				if request.peer != peer_id && !request.announcers.contains(&peer_id) && request.announcers.len() < MAX_TRANSACTION_ANNOUNCERS {
+					request.announcers.push(peer_id);
+				}
+				continue;
+			}
+			if self.requested_transactions.len() >= MAX_REQUESTED_TRANSACTIONS {
+				break;
+			}
+			if !self.network_transactions.contains(&hash) && io.chain().queued_transaction(&hash).is_none() {
+				self.requested_transactions.insert(hash.clone(), TransactionRequest {
+					peer: peer_id,
+					ask_time: tick,
+					announcers: Vec::new(),
+				});
+				missing.push(hash);
+			}
+		}
+		self.request_transactions(io, peer_id, &missing);
+		Ok(())
+	}
+
+	/// Request transactions with given hashes from a peer.
+	fn request_transactions(&mut self, io: &mut SyncIo, peer_id: PeerId, hashes: &[H256]) {
+		if hashes.is_empty() {
+			return;
+		}
+		let mut packet = RlpStream::new_list(hashes.len());
+		for hash in hashes {
+			packet.append(hash);
+		}
+		self.send_packet(io, peer_id, GET_TRANSACTIONS_PACKET, packet.out());
+	}
+
+	/// Asks other announcers for transactions not received in time.
+	fn retry_transaction_requests(&mut self, io: &mut SyncIo, tick: f64) {
+		let mut retries: HashMap<PeerId, Vec<H256>> = HashMap::new();
+		let mut expired = Vec::new();
+		for (hash, request) in self.requested_transactions.iter_mut().filter(|&(_, ref r)| tick - r.ask_time > TRANSACTIONS_TIMEOUT_SEC) {
+			let peers = &self.peers;
+			request.announcers.retain(|p| peers.contains_key(p));
+			if request.announcers.is_empty() {
+				expired.push(hash.clone());
+				continue;
+			}
+			request.peer = request.announcers.remove(0);
+			request.ask_time = tick;
+			retries.entry(request.peer).or_insert_with(Vec::new).push(hash.clone());
+		}
+		for hash in expired {
+			trace!(target: "sync", "Transaction {} request timed out", hash);
+			self.requested_transactions.remove(&hash);
+		}
+		for (peer_id, hashes) in retries {
+			trace!(target: "sync", "Requesting {} transactions from {} after timeout", hashes.len(), peer_id);
+			self.request_transactions(io, peer_id, &hashes);
+		}
+	}
+
+	/// Called when peer asks for transactions it has been announced.
+	fn on_peer_get_transactions(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		let item_count = min(r.item_count(), MAX_TRANSACTIONS_TO_PROPAGATE);
+		let mut transactions = Vec::new();
+		for i in 0 .. item_count {
+			let hash: H256 = try!(r.val_at(i));
+			if let Some(tx) = io.chain().queued_transaction(&hash) {
+				transactions.push(tx);
+			}
+		}
+		trace!(target: "sync", "{} -> GetTransactions: returning {} of {} entries", peer_id, transactions.len(), item_count);
+
+		let mut packet = RlpStream::new_list(transactions.len());
+		for tx in &transactions {
+			packet.append(tx);
+		}
+		let packet = packet.out();
+		self.transaction_bytes_saved = self.transaction_bytes_saved.saturating_sub(packet.len() as u64);
+		self.send_packet(io, peer_id, TRANSACTIONS_PACKET, packet);
+		Ok(())
+	}
+
 	/// Send Status message
 	fn send_status(&mut self, io: &mut SyncIo, peer: PeerId) -> Result<(), NetworkError> {
 		let pv64 = io.eth_protocol_version(peer) >= 64;
-		let mut packet = RlpStream::new_list(if pv64 { 7 } else { 5 });
+		let mut packet = RlpStream::new_list(if pv64 { 8 } else { 5 });
 		let chain = io.chain().chain_info();
 		packet.append(&(io.eth_protocol_version(peer) as u32));
 		packet.append(&self.network_id);
@@ -1284,6 +1698,8 @@ impl ChainSync {
 			let manifest_hash = manifest.map_or(H256::new(), |m| m.into_rlp().sha3());
 			packet.append(&manifest_hash);
 			packet.append(&block_number);
+			// peers ignoring the item keep getting full transactions
+			packet.append(&true);
 		}
 		io.respond(STATUS_PACKET, packet.out())
 	}
@@ -1524,6 +1940,8 @@ impl ChainSync {
 		let result = match packet_id {
 			STATUS_PACKET => self.on_peer_status(io, peer, &rlp),
 			TRANSACTIONS_PACKET => self.on_peer_transactions(io, peer, &rlp),
+			NEW_TRANSACTION_HASHES_PACKET => self.on_peer_new_transaction_hashes(io, peer, &rlp),
+			GET_TRANSACTIONS_PACKET => self.on_peer_get_transactions(io, peer, &rlp),
 			BLOCK_HEADERS_PACKET => self.on_peer_block_headers(io, peer, &rlp),
 			BLOCK_BODIES_PACKET => self.on_peer_block_bodies(io, peer, &rlp),
 			NEW_BLOCK_PACKET => self.on_peer_new_block(io, peer, &rlp),
@@ -1546,7 +1964,7 @@ impl ChainSync {
 		let mut aborting = Vec::new();
 		for (peer_id, peer) in &self.peers {
 			let timeout = match peer.asking {
-				PeerAsking::BlockHeaders | PeerAsking::Heads => (tick - peer.ask_time) > HEADERS_TIMEOUT_SEC,
+				PeerAsking::BlockHeaders | PeerAsking::Heads | PeerAsking::CheckpointHeaders => (tick - peer.ask_time) > HEADERS_TIMEOUT_SEC,
 				PeerAsking::BlockBodies => (tick - peer.ask_time) > BODIES_TIMEOUT_SEC,
 				PeerAsking::Nothing => false,
 				PeerAsking::ForkHeader => (tick - peer.ask_time) > FORK_HEADER_TIMEOUT_SEC,
@@ -1562,6 +1980,62 @@ impl ChainSync {
 		for p in aborting {
 			self.on_peer_aborting(io, p);
 		}
+
+		let expired: Vec<H256> = self.block_announcements.iter()
+			.filter(|&(_, &(first_seen, _))| tick - first_seen > BLOCK_ANNOUNCEMENT_WINDOW_SEC)
+			.map(|(hash, _)| hash.clone())
+			.collect();
+		for hash in expired {
+			self.block_announcements.remove(&hash);
+		}
+
+		self.retry_transaction_requests(io, tick);
+	}
+
+	/// Updates the average delay of the peer in announcing new blocks.
+	/// `is_new` tells whether the block was unknown before this announcement.
+	fn note_block_announcement(&mut self, peer_id: PeerId, hash: &H256, is_new: bool) {
+		use std::collections::hash_map::Entry;
+		let now = time::precise_time_s();
+		let first_seen = match self.block_announcements.entry(hash.clone()) {
+			Entry::Occupied(mut entry) => {
+				if !entry.get_mut().1.insert(peer_id) {
+					return;
+				}
+				entry.get().0
+			},
+			Entry::Vacant(entry) => {
+				if !is_new {
+					return;
+				}
+				let mut announced_by = HashSet::new();
+				announced_by.insert(peer_id);
+				entry.insert((now, announced_by));
+				now
+			},
+		};
+
+		if let Some(peer) = self.peers.get_mut(&peer_id) {
+			let delay = now - first_seen;
+			peer.block_announcement_delay = Some(match peer.block_announcement_delay {
+				Some(average) => average + (delay - average) * BLOCK_ANNOUNCEMENT_DELAY_WEIGHT,
+				None => delay,
+			});
+			peer.block_announcements += 1;
+		}
+	}
+
+	/// Orders peers by their average delay in announcing new blocks, fastest first.
+	/// Peers which haven't announced any blocks yet go last, keeping their relative order.
+	fn sort_by_announcement_delay(&self, peers: &mut [(PeerId, BlockNumber)]) {
+		use std::cmp::Ordering;
+		let delay = |peer_id: &PeerId| self.peers.get(peer_id).and_then(|p| p.block_announcement_delay);
+		peers.sort_by(|&(a, _), &(b, _)| match (delay(&a), delay(&b)) {
+			(Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(None, None) => Ordering::Equal,
+		});
 	}
 
 	fn check_resume(&mut self, io: &mut SyncIo) {
@@ -1636,7 +2110,10 @@ impl ChainSync {
 			.collect::<Vec<_>>()
 	}
 
-	fn select_random_lagging_peers(&mut self, peers: &[(PeerId, BlockNumber)]) -> Vec<(PeerId, BlockNumber)> {
+	/// Selects peers to send the whole new block to. Half of them are the peers fastest
+	/// in announcing new blocks, the rest is random so that the block reaches all parts
+	/// of the network and new peers get a chance to prove themselves.
+	fn select_lagging_peers(&mut self, peers: &[(PeerId, BlockNumber)]) -> Vec<(PeerId, BlockNumber)> {
 		use rand::Rng;
 		// take sqrt(x) peers
 		let mut peers = peers.to_vec();
@@ -1644,7 +2121,11 @@ impl ChainSync {
 		count = min(count, MAX_PEERS_PROPAGATION);
 		count = max(count, MIN_PEERS_PROPAGATION);
 		::rand::thread_rng().shuffle(&mut peers);
-		peers.truncate(count);
+		self.sort_by_announcement_delay(&mut peers);
+		let fastest = min((count + 1) / 2, peers.len());
+		let mut rest = peers.split_off(fastest);
+		::rand::thread_rng().shuffle(&mut rest);
+		peers.extend(rest.into_iter().take(count - fastest));
 		peers
 	}
 
@@ -1658,6 +2139,9 @@ impl ChainSync {
 				self.send_packet(io, peer_id, NEW_BLOCK_PACKET, rlp);
 			} else {
 				for h in sealed {
+					if self.announced_sealed.contains(h) {
+						continue;
+					}
 					let rlp =  ChainSync::create_new_block_rlp(io.chain(), h);
 					self.send_packet(io, peer_id, NEW_BLOCK_PACKET, rlp);
 				}
@@ -1705,17 +2189,31 @@ impl ChainSync {
 			return 0;
 		}
 
-		let transactions = io.chain().pending_transactions();
+		// Transactions will be sent once upload bandwidth is available again.
+		if io.is_upload_throttled() {
+			trace!(target: "sync", "Upload bandwidth cap reached, delaying transaction propagation");
+			return 0;
+		}
+
+		let mut transactions = io.chain().pending_transactions();
+		if !self.tx_relay {
+			// only our own transactions are propagated
+			let network_transactions = &self.network_transactions;
+			transactions.retain(|tx| !network_transactions.contains(&tx.hash()));
+		}
 		if transactions.is_empty() {
 			return 0;
 		}
 
-		let all_transactions_hashes = transactions.iter().map(|tx| tx.hash()).collect::<HashSet<H256>>();
+		let transactions_hashes = transactions.iter().map(|tx| tx.hash()).collect::<Vec<H256>>();
+		let all_transactions_hashes = transactions_hashes.iter().cloned().collect::<HashSet<H256>>();
 		let all_transactions_rlp = {
 			let mut packet = RlpStream::new_list(transactions.len());
 			for tx in &transactions { packet.append(tx); }
 			packet.out()
 		};
+		let transactions_sizes = transactions.iter().map(|tx| (tx.hash(), ::rlp::encode(tx).len())).collect::<HashMap<H256, usize>>();
+		let mut bytes_saved = 0u64;
 
 		// sqrt(x)/x scaled to max u32
 		let fraction = (self.peers.len() as f64).powf(-0.5).mul(u32::max_value() as f64).round() as u32;
@@ -1725,18 +2223,38 @@ impl ChainSync {
 			.filter(|_| small || ::rand::random::<u32>() < fraction)
 			.take(MAX_PEERS_PROPAGATION)
 			.filter_map(|(peer_id, mut peer_info)| {
-				// Send all transactions
-				if peer_info.last_sent_transactions.is_empty() {
-					peer_info.last_sent_transactions = all_transactions_hashes.clone();
-					return Some((*peer_id, all_transactions_rlp.clone()));
-				}
-
-				// Get hashes of all transactions to send to this peer
-				let to_send = all_transactions_hashes.difference(&peer_info.last_sent_transactions).cloned().collect::<HashSet<_>>();
+				// Get hashes of transactions to send to this peer, skipping the ones it already has
+				let to_send = transactions_hashes.iter()
+					.filter(|hash| !peer_info.last_sent_transactions.contains(*hash) && !peer_info.known_transactions.contains(*hash))
+					.take(MAX_TRANSACTIONS_TO_PROPAGATE)
+					.cloned()
+					.collect::<HashSet<_>>();
 				if to_send.is_empty() {
 					return None;
 				}
 
+				// Only announce hashes, the peer asks for the transactions it's missing
+				if peer_info.transaction_hashes {
+					let mut packet = RlpStream::new_list(to_send.len());
+					for hash in &to_send {
+						packet.append(hash);
+						bytes_saved += transactions_sizes[hash] as u64;
+					}
+					let packet = packet.out();
+					bytes_saved = bytes_saved.saturating_sub(packet.len() as u64);
+					peer_info.last_sent_transactions = all_transactions_hashes.intersection(&peer_info.last_sent_transactions)
+						.chain(to_send.iter())
+						.cloned()
+						.collect();
+					return Some((*peer_id, NEW_TRANSACTION_HASHES_PACKET, packet));
+				}
+
+				// Send all transactions
+				if to_send.len() == transactions.len() {
+					peer_info.last_sent_transactions = all_transactions_hashes.clone();
+					return Some((*peer_id, TRANSACTIONS_PACKET, all_transactions_rlp.clone()));
+				}
+
 				// Construct RLP
 				let mut packet = RlpStream::new_list(to_send.len());
 				for tx in &transactions {
@@ -1745,16 +2263,21 @@ impl ChainSync {
 					}
 				}
 
-				peer_info.last_sent_transactions = all_transactions_hashes.clone();
-				Some((*peer_id, packet.out()))
+				// Remaining transactions are sent on the next round
+				peer_info.last_sent_transactions = all_transactions_hashes.intersection(&peer_info.last_sent_transactions)
+					.chain(to_send.iter())
+					.cloned()
+					.collect();
+				Some((*peer_id, TRANSACTIONS_PACKET, packet.out()))
 			})
 			.collect::<Vec<_>>();
+		self.transaction_bytes_saved += bytes_saved;
 
 		// Send RLPs
 		let sent = lucky_peers.len();
 		if sent > 0 {
-			for (peer_id, rlp) in lucky_peers.into_iter() {
-				self.send_packet(io, peer_id, TRANSACTIONS_PACKET, rlp);
+			for (peer_id, packet_id, rlp) in lucky_peers.into_iter() {
+				self.send_packet(io, peer_id, packet_id, rlp);
 			}
 
 			trace!(target: "sync", "Sent up to {} transactions to {} peers.", transactions.len(), sent);
@@ -1762,14 +2285,40 @@ impl ChainSync {
 		sent
 	}
 
+	/// Sends a locally sealed block to all peers while it's still being imported.
+	pub fn propagate_sealed_block(&mut self, io: &mut SyncIo, block: &[u8], total_difficulty: &U256) {
+		if !io.is_chain_queue_empty() {
+			return;
+		}
+		let hash = BlockView::new(block).hash();
+		let mut rlp_stream = RlpStream::new_list(2);
+		rlp_stream.append_raw(block, 1);
+		rlp_stream.append(total_difficulty);
+		let rlp = rlp_stream.out();
+		let mut peers: Vec<(PeerId, BlockNumber)> = self.peers.iter().filter(|&(_, p)| p.is_allowed()).map(|(id, p)| (*id, p.latest_number.unwrap_or(0))).collect();
+		self.sort_by_announcement_delay(&mut peers);
+		for &(peer_id, _) in &peers {
+			self.send_packet(io, peer_id, NEW_BLOCK_PACKET, rlp.clone());
+		}
+		trace!(target: "sync", "Sent sealed block {} to {} peers ahead of its import", hash, peers.len());
+		self.announced_sealed.insert(hash);
+	}
+
 	fn propagate_latest_blocks(&mut self, io: &mut SyncIo, sealed: &[H256]) {
 		let chain_info = io.chain().chain_info();
 		if (((chain_info.best_block_number as i64) - (self.last_sent_block_number as i64)).abs() as BlockNumber) < MAX_PEER_LAG_PROPAGATION {
 			let mut peers = self.get_lagging_peers(&chain_info, io);
+			// fastest relaying peers are served first
+			self.sort_by_announcement_delay(&mut peers);
 			if sealed.is_empty() {
 				let hashes = self.propagate_new_hashes(&chain_info, io, &peers);
-				peers = self.select_random_lagging_peers(&peers);
-				let blocks = self.propagate_blocks(&chain_info, io, sealed, &peers);
+				// Only announce hashes when upload is capped; peers can fetch the blocks themselves.
+				let blocks = if io.is_upload_throttled() {
+					0
+				} else {
+					peers = self.select_lagging_peers(&peers);
+					self.propagate_blocks(&chain_info, io, sealed, &peers)
+				};
 				if blocks != 0 || hashes != 0 {
 					trace!(target: "sync", "Sent latest {} blocks and {} hashes to peers.", blocks, hashes);
 				}
@@ -1783,9 +2332,57 @@ impl ChainSync {
 		self.last_sent_block_number = chain_info.best_block_number;
 	}
 
+	/// Compare best blocks of the peers with our chain and warn if a significant part of them
+	/// keeps following a different one.
+	fn check_chain_split(&mut self, io: &SyncIo) {
+		let best_number = io.chain().chain_info().best_block_number;
+		let mut status = ChainStatus::default();
+		for peer in self.peers.values_mut().filter(|p| p.is_allowed()) {
+			if peer.latest_number.is_none() {
+				if let Some(header) = io.chain().block_header(BlockID::Hash(peer.latest_hash.clone())) {
+					peer.latest_number = Some(HeaderView::new(&header).number());
+				}
+			}
+			match peer.latest_number {
+				Some(number) if number <= best_number => {
+					if io.chain().block_hash(BlockID::Number(number)).map_or(false, |h| h == peer.latest_hash) {
+						status.agreeing_peers += 1;
+					} else {
+						status.diverging_peers += 1;
+						status.diverging_block = Some(max(number, status.diverging_block.unwrap_or(0)));
+					}
+				},
+				_ => status.unknown_peers += 1,
+			}
+		}
+
+		let compared = status.agreeing_peers + status.diverging_peers;
+		let diverging = status.diverging_peers >= CHAIN_SPLIT_MIN_PEERS && status.diverging_peers * 100 >= compared * CHAIN_SPLIT_PEERS_PERCENT;
+		let now = time::precise_time_s();
+		if !diverging {
+			self.diverging_since = None;
+		} else if self.diverging_since.is_none() {
+			self.diverging_since = Some(now);
+		}
+		// short lived forks are resolved within a few blocks; only report persistent ones
+		status.split = self.diverging_since.map_or(false, |since| now - since >= CHAIN_SPLIT_TIMEOUT_SEC);
+		if status.split && !self.chain_status.split {
+			warn!(target: "sync", "Possible chain split: {} of {} peers follow a different chain at block #{}", status.diverging_peers, compared, status.diverging_block.unwrap_or(0));
+		} else if !status.split && self.chain_status.split {
+			info!(target: "sync", "Chain split resolved: {} of {} peers follow our chain", status.agreeing_peers, compared);
+		}
+		self.chain_status = status;
+	}
+
 	/// Maintain other peers. Send out any new blocks and transactions
 	pub fn maintain_sync(&mut self, io: &mut SyncIo) {
 		self.check_resume(io);
+		self.check_chain_split(io);
+		if self.download_throttled && !io.is_download_throttled() {
+			trace!(target: "sync", "Download bandwidth available, resuming requests");
+			self.download_throttled = false;
+			self.continue_sync(io);
+		}
 	}
 
 	/// called when block is imported to chain - propagates the blocks and updates transactions sent to peers
@@ -1793,6 +2390,9 @@ impl ChainSync {
 		if io.is_chain_queue_empty() {
 			self.propagate_latest_blocks(io, sealed);
 		}
+		for h in sealed.iter().chain(invalid) {
+			self.announced_sealed.remove(h);
+		}
 		if !invalid.is_empty() {
 			trace!(target: "sync", "Bad blocks in the queue, restarting");
 			self.restart_on_bad_block(io);
@@ -1811,7 +2411,8 @@ mod tests {
 	use ::SyncConfig;
 	use util::*;
 	use rlp::*;
-	use super::{PeerInfo, PeerAsking};
+	use super::{PeerInfo, PeerAsking, CheckpointLink};
+	use ethcore::snapshot::ManifestData;
 	use ethcore::views::BlockView;
 	use ethcore::header::*;
 	use ethcore::client::*;
@@ -2008,15 +2609,88 @@ mod tests {
 				asking_hash: None,
 				ask_time: 0f64,
 				last_sent_transactions: HashSet::new(),
+				known_transactions: RollingBloom::new(KNOWN_TRANSACTIONS_PER_PEER),
 				expired: false,
 				confirmation: super::ForkConfirmation::Confirmed,
 				snapshot_number: None,
 				snapshot_hash: None,
 				asking_snapshot_data: None,
+				block_announcement_delay: None,
+				block_announcements: 0,
+				bodies_from: 0,
+				empty_body_replies: 0,
+				transaction_hashes: false,
 			});
 		sync
 	}
 
+	#[test]
+	fn ignores_snapshots_before_checkpoint() {
+		for &(snapshot_number, expect_snapshot) in &[(500, false), (1200, true)] {
+			let mut client = TestBlockChainClient::new();
+			let mut queue = VecDeque::new();
+			let mut config = SyncConfig::default();
+			config.checkpoint = Some((1000, H256::from(1)));
+			let mut sync = ChainSync::new(config, &client);
+			let mut peer = dummy_sync_with_peer(H256::from(2), &client).peers.remove(&0).unwrap();
+			peer.snapshot_number = Some(snapshot_number);
+			peer.snapshot_hash = Some(H256::from(3));
+			sync.peers.insert(0, peer);
+			sync.active_peers.insert(0);
+			let ss = TestSnapshotService::new();
+			let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+
+			sync.sync_peer(&mut io, 0, false);
+
+			assert_eq!(sync.state == SyncState::SnapshotManifest, expect_snapshot);
+		}
+	}
+
+	#[test]
+	fn links_snapshot_to_checkpoint() {
+		let checkpoint = get_dummy_block(1000, H256::from(1));
+		let checkpoint_hash = UntrustedRlp::new(&checkpoint).at(0).unwrap().as_raw().sha3();
+		let first = get_dummy_block(1001, checkpoint_hash.clone());
+		let first_hash = UntrustedRlp::new(&first).at(0).unwrap().as_raw().sha3();
+		let second = get_dummy_block(1002, first_hash.clone());
+		let second_hash = UntrustedRlp::new(&second).at(0).unwrap().as_raw().sha3();
+
+		for &(snapshot_hash, expect_restore) in &[(second_hash, true), (H256::from(2), false)] {
+			let mut client = TestBlockChainClient::new();
+			let mut queue = VecDeque::new();
+			let mut config = SyncConfig::default();
+			config.checkpoint = Some((1000, checkpoint_hash.clone()));
+			let mut sync = ChainSync::new(config, &client);
+			let mut peer = dummy_sync_with_peer(H256::from(2), &client).peers.remove(&0).unwrap();
+			peer.asking = PeerAsking::CheckpointHeaders;
+			sync.peers.insert(0, peer);
+			sync.active_peers.insert(0);
+			sync.state = SyncState::CheckpointHeaders;
+			sync.checkpoint_link = Some(CheckpointLink {
+				manifest: ManifestData {
+					state_hashes: Vec::new(),
+					block_hashes: Vec::new(),
+					state_root: H256::zero(),
+					block_number: 1002,
+					block_hash: snapshot_hash,
+				},
+				manifest_hash: H256::from(3),
+				last: (1000, checkpoint_hash.clone()),
+			});
+			let ss = TestSnapshotService::new();
+			let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+
+			let mut headers = RlpStream::new_list(2);
+			headers.append_raw(UntrustedRlp::new(&first).at(0).unwrap().as_raw(), 1);
+			headers.append_raw(UntrustedRlp::new(&second).at(0).unwrap().as_raw(), 1);
+			let headers = headers.out();
+			sync.on_peer_block_headers(&mut io, 0, &UntrustedRlp::new(&headers)).unwrap();
+
+			assert_eq!(sync.state == SyncState::SnapshotData, expect_restore);
+			assert!(sync.checkpoint_link.is_none());
+		}
+	}
+
 	#[test]
 	fn finds_lagging_peers() {
 		let mut client = TestBlockChainClient::new();
@@ -2111,6 +2785,54 @@ mod tests {
 		assert_eq!(0x07, io.queue[0].packet_id);
 	}
 
+	#[test]
+	fn reports_peer_details() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Nothing);
+		let latest = client.block_hash_delta_minus(1);
+		let mut sync = dummy_sync_with_peer(latest.clone(), &client);
+		{
+			let peer = sync.peers.get_mut(&0).unwrap();
+			peer.asking = PeerAsking::BlockBodies;
+			peer.asking_blocks = vec![H256::from(1), H256::from(2)];
+		}
+		let mut queue = VecDeque::new();
+		let ss = TestSnapshotService::new();
+		let io = TestIo::new(&mut client, &ss, &mut queue, None);
+
+		let peers = sync.peers(&io);
+		assert_eq!(peers.len(), 1);
+		assert_eq!(peers[0].eth_head, latest);
+		assert_eq!(peers[0].outstanding_requests, 2);
+		assert!(peers[0].capabilities.is_empty());
+	}
+
+	#[test]
+	fn sends_sealed_block_once_if_sent_before_import() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let mut queue = VecDeque::new();
+		let hash = client.block_hash(BlockID::Number(99)).unwrap();
+		let block = client.block(BlockID::Hash(hash.clone())).unwrap();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(5), &client);
+		let chain_info = client.chain_info();
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+		sync.propagate_sealed_block(&mut io, &block, &chain_info.total_difficulty);
+		let peers = sync.get_lagging_peers(&chain_info, &io);
+		let peer_count = sync.propagate_blocks(&chain_info, &mut io, &[hash.clone()], &peers);
+
+		// 1 message should be send
+		assert_eq!(1, io.queue.len());
+		// 1 peer should be updated
+		assert_eq!(1, peer_count);
+		// NEW_BLOCK_PACKET
+		assert_eq!(0x07, io.queue[0].packet_id);
+
+		sync.chain_new_blocks(&mut io, &[hash.clone()], &[], &[], &[], &[hash.clone()]);
+		assert!(sync.announced_sealed.is_empty());
+	}
+
 	#[test]
 	fn propagates_transactions() {
 		let mut client = TestBlockChainClient::new();
@@ -2133,6 +2855,194 @@ mod tests {
 		assert_eq!(0x02, io.queue[0].packet_id);
 	}
 
+	#[test]
+	fn announces_transaction_hashes_to_capable_peers() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		client.insert_transaction_to_queue();
+		let hash = client.pending_transactions()[0].hash();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		sync.peers.get_mut(&0).unwrap().transaction_hashes = true;
+		let mut queue = VecDeque::new();
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+		let peer_count = sync.propagate_new_transactions(&mut io);
+
+		assert_eq!(1, peer_count);
+		// NEW_TRANSACTION_HASHES_PACKET
+		assert_eq!(0x08, io.queue[0].packet_id);
+		assert_eq!(UntrustedRlp::new(&io.queue[0].data).as_val::<Vec<H256>>().unwrap(), vec![hash.clone()]);
+		let saved = sync.status().transaction_bytes_saved;
+		assert!(saved > 0);
+
+		// peer asks for the transaction
+		let mut request = RlpStream::new_list(1);
+		request.append(&hash);
+		sync.on_packet(&mut io, 0, GET_TRANSACTIONS_PACKET, &request.out());
+
+		// TRANSACTIONS_PACKET
+		assert_eq!(0x02, io.queue[1].packet_id);
+		assert_eq!(UntrustedRlp::new(&io.queue[1].data).item_count(), 1);
+		assert!(sync.status().transaction_bytes_saved < saved);
+	}
+
+	#[test]
+	fn requests_announced_transactions_once() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		let mut queue = VecDeque::new();
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+		let mut announcement = RlpStream::new_list(1);
+		announcement.append(&H256::from(1));
+		let announcement = announcement.out();
+
+		sync.on_packet(&mut io, 0, NEW_TRANSACTION_HASHES_PACKET, &announcement);
+		sync.on_packet(&mut io, 0, NEW_TRANSACTION_HASHES_PACKET, &announcement);
+
+		assert_eq!(1, io.queue.len());
+		// GET_TRANSACTIONS_PACKET
+		assert_eq!(0x09, io.queue[0].packet_id);
+		assert_eq!(io.queue[0].data, announcement);
+	}
+
+	#[test]
+	fn requests_transactions_from_other_announcers_after_timeout() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		let peer = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client).peers.remove(&0).unwrap();
+		sync.peers.insert(1, peer);
+		let mut queue = VecDeque::new();
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+		let mut announcement = RlpStream::new_list(1);
+		announcement.append(&H256::from(1));
+		let announcement = announcement.out();
+
+		sync.on_packet(&mut io, 0, NEW_TRANSACTION_HASHES_PACKET, &announcement);
+		sync.on_packet(&mut io, 1, NEW_TRANSACTION_HASHES_PACKET, &announcement);
+		assert_eq!(1, io.queue.len());
+		assert_eq!(0, io.queue[0].recipient);
+
+		sync.requested_transactions.get_mut(&H256::from(1)).unwrap().ask_time -= TRANSACTIONS_TIMEOUT_SEC + 1f64;
+		sync.maintain_peers(&mut io);
+
+		assert_eq!(2, io.queue.len());
+		assert_eq!(1, io.queue[1].recipient);
+		assert_eq!(0x09, io.queue[1].packet_id);
+		assert_eq!(io.queue[1].data, announcement);
+	}
+
+	#[test]
+	fn does_not_propagate_transactions_when_upload_throttled() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		client.insert_transaction_to_queue();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		let mut queue = VecDeque::new();
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+		io.upload_throttled = true;
+		let peer_count = sync.propagate_new_transactions(&mut io);
+		io.upload_throttled = false;
+		let peer_count2 = sync.propagate_new_transactions(&mut io);
+
+		// transactions are sent only once the cap is lifted
+		assert_eq!(0, peer_count);
+		assert_eq!(1, peer_count2);
+		assert_eq!(1, io.queue.len());
+	}
+
+	#[test]
+	fn does_not_send_transactions_back_to_origin_peer() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		client.insert_transaction_to_queue();
+		let tx_hash = client.pending_transactions()[0].hash();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		sync.peers.get_mut(&0).unwrap().known_transactions.insert(&tx_hash);
+		let mut queue = VecDeque::new();
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+		let peer_count = sync.propagate_new_transactions(&mut io);
+
+		assert_eq!(0, peer_count);
+		assert_eq!(0, io.queue.len());
+	}
+
+	#[test]
+	fn does_not_relay_network_transactions_when_disabled() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		client.insert_transaction_to_queue();
+		let tx_hash = client.pending_transactions()[0].hash();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		sync.tx_relay = false;
+		sync.network_transactions.insert(&tx_hash);
+		let mut queue = VecDeque::new();
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+		let peer_count = sync.propagate_new_transactions(&mut io);
+
+		assert_eq!(0, peer_count);
+		assert_eq!(0, io.queue.len());
+	}
+
+	#[test]
+	fn detects_peers_following_different_chain() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(10), &client);
+		for id in 1..3 {
+			let mut peer = sync.peers.get(&0).unwrap().clone();
+			peer.latest_hash = H256::from(id);
+			peer.latest_number = Some(90);
+			sync.peers.insert(id as PeerId, peer);
+		}
+		let mut queue = VecDeque::new();
+		let ss = TestSnapshotService::new();
+		let io = TestIo::new(&mut client, &ss, &mut queue, None);
+
+		sync.check_chain_split(&io);
+		let status = sync.chain_status();
+		assert_eq!(1, status.agreeing_peers);
+		assert_eq!(2, status.diverging_peers);
+		assert_eq!(Some(90), status.diverging_block);
+		assert!(!status.split);
+
+		// divergence persists
+		sync.diverging_since = Some(0f64);
+		sync.check_chain_split(&io);
+		assert!(sync.chain_status().split);
+
+		// diverging peers catch up with our chain
+		for id in 1..3 {
+			sync.peers.get_mut(&id).unwrap().latest_hash = io.chain().block_hash(BlockID::Number(90)).unwrap();
+		}
+		sync.check_chain_split(&io);
+		assert_eq!(3, sync.chain_status().agreeing_peers);
+		assert!(!sync.chain_status().split);
+		assert!(sync.diverging_since.is_none());
+	}
+
+	#[test]
+	fn reports_median_of_peers_best_blocks() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(10), &client);
+		sync.highest_block = Some(10_000_000);
+		assert_eq!(Some(10_000_000), sync.status().highest_block_number);
+
+		for (id, number) in vec![(0, 120), (1, 10_000_000), (2, 130)] {
+			let mut peer = sync.peers.get(&0).unwrap().clone();
+			peer.latest_number = Some(number);
+			sync.peers.insert(id as PeerId, peer);
+		}
+		assert_eq!(Some(130), sync.status().highest_block_number);
+	}
+
 	#[test]
 	fn propagates_transactions_again_after_new_block() {
 		let mut client = TestBlockChainClient::new();
@@ -2229,6 +3139,123 @@ mod tests {
 		assert!(result.is_ok());
 	}
 
+	#[test]
+	fn ranks_peers_by_block_announcement_delay() {
+		let client = TestBlockChainClient::new();
+		let mut sync = dummy_sync_with_peer(H256::zero(), &client);
+		let peer = sync.peers[&0].clone();
+		sync.peers.insert(1, peer.clone());
+		sync.peers.insert(2, peer);
+		let hash = H256::from(1);
+
+		// blocks we already have are not measured
+		sync.note_block_announcement(1, &hash, false);
+		assert_eq!(sync.peers[&1].block_announcements, 0);
+
+		// peer 1 is the first to announce the block
+		sync.note_block_announcement(1, &hash, true);
+		assert_eq!(sync.peers[&1].block_announcement_delay, Some(0f64));
+
+		// peer 0 announces it two seconds later, only once
+		sync.block_announcements.get_mut(&hash).unwrap().0 -= 2f64;
+		sync.note_block_announcement(0, &hash, false);
+		sync.note_block_announcement(0, &hash, false);
+		assert_eq!(sync.peers[&0].block_announcements, 1);
+		assert!(sync.peers[&0].block_announcement_delay.unwrap() >= 2f64);
+
+		// peer 2 hasn't announced anything yet
+		let mut peers = vec![(2, 0), (0, 0), (1, 0)];
+		sync.sort_by_announcement_delay(&mut peers);
+		assert_eq!(peers, vec![(1, 0), (0, 0), (2, 0)]);
+	}
+
+	#[test]
+	fn keeps_peer_without_old_bodies_for_newer_ones() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Uncle);
+		let mut queue = VecDeque::new();
+		let mut sync = dummy_sync_with_peer(H256::random(), &client);
+		let headers: Vec<Bytes> = (1..6).map(|n| Rlp::new(&client.block(BlockID::Number(n)).unwrap()).at(0).as_raw().to_vec()).collect();
+		let hashes: Vec<H256> = (1..6).map(|n| client.block_hash(BlockID::Number(n)).unwrap()).collect();
+		sync.blocks.reset_to(vec![hashes[0].clone()]);
+		sync.blocks.insert_headers(headers);
+		sync.state = SyncState::Blocks;
+		sync.active_peers.insert(0);
+		// headers are being downloaded by another peer
+		assert!(sync.blocks.needed_headers(MAX_HEADERS_TO_REQUEST, false).is_some());
+		{
+			let peer = sync.peers.get_mut(&0).unwrap();
+			peer.asking = PeerAsking::BlockBodies;
+			peer.asking_blocks = hashes[2..4].to_vec();
+		}
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+
+		let empty = RlpStream::new_list(0).out();
+		sync.on_peer_block_bodies(&mut io, 0, &UntrustedRlp::new(&empty)).unwrap();
+
+		// blocks #3 and #4 are skipped from now on, the peer is asked for #5 instead
+		assert_eq!(sync.peers[&0].bodies_from, 5);
+		assert_eq!(sync.peers[&0].asking, PeerAsking::BlockBodies);
+		assert_eq!(sync.peers[&0].asking_blocks, vec![hashes[4].clone()]);
+	}
+
+	#[test]
+	fn deactivates_peer_giving_no_bodies_repeatedly() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Uncle);
+		let mut queue = VecDeque::new();
+		let mut sync = dummy_sync_with_peer(H256::random(), &client);
+		let peer = sync.peers[&0].clone();
+		sync.peers.insert(1, peer);
+		let headers: Vec<Bytes> = (1..6).map(|n| Rlp::new(&client.block(BlockID::Number(n)).unwrap()).at(0).as_raw().to_vec()).collect();
+		let hashes: Vec<H256> = (1..6).map(|n| client.block_hash(BlockID::Number(n)).unwrap()).collect();
+		sync.blocks.reset_to(vec![hashes[0].clone()]);
+		sync.blocks.insert_headers(headers);
+		sync.state = SyncState::Blocks;
+		sync.active_peers.insert(0);
+		sync.active_peers.insert(1);
+		{
+			let peer = sync.peers.get_mut(&0).unwrap();
+			peer.empty_body_replies = MAX_EMPTY_BODY_REPLIES - 1;
+			peer.asking = PeerAsking::BlockBodies;
+			peer.asking_blocks = hashes[0..2].to_vec();
+		}
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+
+		let empty = RlpStream::new_list(0).out();
+		sync.on_peer_block_bodies(&mut io, 0, &UntrustedRlp::new(&empty)).unwrap();
+
+		assert!(!sync.active_peers.contains(&0));
+		assert!(sync.active_peers.contains(&1));
+	}
+
+	#[test]
+	fn downloads_headers_ahead_while_other_peers_fetch_bodies() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Uncle);
+		let mut queue = VecDeque::new();
+		let mut sync = dummy_sync_with_peer(H256::random(), &client);
+		let peer = sync.peers[&0].clone();
+		sync.peers.insert(1, peer);
+		let headers: Vec<Bytes> = (1..6).map(|n| Rlp::new(&client.block(BlockID::Number(n)).unwrap()).at(0).as_raw().to_vec()).collect();
+		let hashes: Vec<H256> = (1..6).map(|n| client.block_hash(BlockID::Number(n)).unwrap()).collect();
+		sync.blocks.reset_to(vec![hashes[0].clone()]);
+		sync.blocks.insert_headers(headers);
+		sync.state = SyncState::Blocks;
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &mut queue, None);
+
+		sync.request_blocks(&mut io, 0, false);
+		sync.request_blocks(&mut io, 1, false);
+
+		assert_eq!(sync.peers[&0].asking, PeerAsking::BlockHeaders);
+		assert_eq!(sync.peers[&0].asking_blocks, vec![hashes[4].clone()]);
+		assert_eq!(sync.peers[&1].asking, PeerAsking::BlockBodies);
+		assert!(!sync.peers[&1].asking_blocks.is_empty());
+	}
+
 	#[test]
 	fn handles_peer_new_block_empty() {
 		let mut client = TestBlockChainClient::new();
@@ -50,6 +50,7 @@ mod chain;
 mod blocks;
 mod sync_io;
 mod snapshot;
+mod rolling_bloom;
 
 #[cfg(test)]
 mod tests;
@@ -60,9 +61,9 @@ mod api {
 }
 
 pub use api::{EthSync, SyncProvider, SyncClient, NetworkManagerClient, ManageNetwork, SyncConfig,
-	ServiceConfiguration, NetworkConfiguration};
+	ServiceConfiguration, NetworkConfiguration, TrafficStats, PeerTrafficStats, ChainStatus, PeerInfo};
 pub use chain::{SyncStatus, SyncState};
-pub use network::{is_valid_node_url, NonReservedPeerMode, NetworkError};
+pub use network::{is_valid_node_url, new_node_key, NonReservedPeerMode, NetworkError, ConnectionFilter, ConnectionDirection, NodeId};
 
 /// IPC interfaces
 #[cfg(feature="ipc")]
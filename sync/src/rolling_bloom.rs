@@ -0,0 +1,126 @@
+// Copyright 2015, 2016 Ethcore (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rolling bloom filter for recently seen hashes.
+
+use std::mem;
+use util::H256;
+
+/// Number of bits set for each inserted hash.
+const BITS_PER_ITEM: usize = 3;
+/// Bitmap bits allocated per item of a generation. Gives about 0.5% false positive rate when full.
+const BITMAP_BITS_PER_ITEM: usize = 16;
+
+/// Bloom filter of hashes which keeps two generations of `generation_size` items.
+/// Once the current generation fills up, the previous one is dropped, so only
+/// the most recent `2 * generation_size` hashes are remembered.
+/// Hashes are assumed to be uniformly distributed and are used as bit indexes directly.
+#[derive(Clone)]
+pub struct RollingBloom {
+	current: Vec<u64>,
+	previous: Vec<u64>,
+	items: usize,
+	generation_size: usize,
+}
+
+impl RollingBloom {
+	/// Create a new filter holding at least `generation_size` most recent items.
+	pub fn new(generation_size: usize) -> RollingBloom {
+		let words = (generation_size * BITMAP_BITS_PER_ITEM + 63) / 64;
+		RollingBloom {
+			current: vec![0u64; words],
+			previous: vec![0u64; words],
+			items: 0,
+			generation_size: generation_size,
+		}
+	}
+
+	/// Remember given hash.
+	pub fn insert(&mut self, hash: &H256) {
+		if self.items >= self.generation_size {
+			let words = self.current.len();
+			self.previous = mem::replace(&mut self.current, vec![0u64; words]);
+			self.items = 0;
+		}
+		let bits = self.bits(hash);
+		for bit in &bits {
+			self.current[bit / 64] |= 1u64 << (bit % 64);
+		}
+		self.items += 1;
+	}
+
+	/// Check if given hash has been seen recently. May return false positives.
+	pub fn contains(&self, hash: &H256) -> bool {
+		let bits = self.bits(hash);
+		let has = |bitmap: &[u64]| bits.iter().all(|bit| bitmap[bit / 64] & (1u64 << (bit % 64)) != 0);
+		has(&self.current) || has(&self.previous)
+	}
+
+	fn bits(&self, hash: &H256) -> [usize; BITS_PER_ITEM] {
+		let bitmap_bits = (self.current.len() * 64) as u64;
+		let mut bits = [0usize; BITS_PER_ITEM];
+		for (i, bit) in bits.iter_mut().enumerate() {
+			let word = hash[i * 8 .. (i + 1) * 8].iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+			*bit = (word % bitmap_bits) as usize;
+		}
+		bits
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::{H256, FixedHash};
+	use super::RollingBloom;
+
+	// hash setting given bits of a 64-bit bitmap
+	fn hash(bits: [u8; 3]) -> H256 {
+		let mut h = H256::new();
+		h[7] = bits[0];
+		h[15] = bits[1];
+		h[23] = bits[2];
+		h
+	}
+
+	#[test]
+	fn should_remember_inserted_hashes() {
+		let mut bloom = RollingBloom::new(16);
+		let hashes: Vec<H256> = (0..16).map(|_| H256::random()).collect();
+		for h in &hashes {
+			bloom.insert(h);
+		}
+		assert!(hashes.iter().all(|h| bloom.contains(h)));
+	}
+
+	#[test]
+	fn should_forget_old_generations() {
+		let mut bloom = RollingBloom::new(4);
+		let old = hash([1, 2, 3]);
+		bloom.insert(&old);
+		for i in 0..3 {
+			bloom.insert(&hash([4 + i, 4 + i, 4 + i]));
+		}
+		let recent: Vec<H256> = (0..4).map(|i| hash([10 + i, 20 + i, 30 + i])).collect();
+		for h in &recent {
+			bloom.insert(h);
+		}
+		assert!(bloom.contains(&old));
+
+		// third generation pushes out the first one
+		bloom.insert(&hash([40, 41, 42]));
+		assert!(recent.iter().all(|h| bloom.contains(h)));
+		assert!(!bloom.contains(&old));
+	}
+}
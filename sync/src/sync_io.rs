@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use network::{NetworkContext, PeerId, PacketId, NetworkError};
+use network::{NetworkContext, PeerId, PacketId, NetworkError, SessionInfo};
 use ethcore::client::BlockChainClient;
 use ethcore::snapshot::SnapshotService;
 
@@ -38,6 +38,10 @@ pub trait SyncIo {
 	fn peer_info(&self, peer_id: PeerId) -> String {
 		peer_id.to_string()
 	}
+	/// Returns information on the peer's session if it's connected
+	fn peer_session_info(&self, _peer_id: PeerId) -> Option<SessionInfo> {
+		None
+	}
 	/// Maximum mutuallt supported ETH protocol version
 	fn eth_protocol_version(&self, peer_id: PeerId) -> u8;
 	/// Returns if the chain block queue empty
@@ -46,6 +50,14 @@ pub trait SyncIo {
 	}
 	/// Check if the session is expired
 	fn is_expired(&self) -> bool;
+	/// Returns true if the upload bandwidth cap has been reached.
+	fn is_upload_throttled(&self) -> bool {
+		false
+	}
+	/// Returns true if the download bandwidth cap has been reached.
+	fn is_download_throttled(&self) -> bool {
+		false
+	}
 }
 
 /// Wraps `NetworkContext` and the blockchain client
@@ -95,6 +107,10 @@ impl<'s, 'h> SyncIo for NetSyncIo<'s, 'h> {
 		self.network.peer_info(peer_id)
 	}
 
+	fn peer_session_info(&self, peer_id: PeerId) -> Option<SessionInfo> {
+		self.network.session_info(peer_id)
+	}
+
 	fn is_expired(&self) -> bool {
 		self.network.is_expired()
 	}
@@ -102,6 +118,14 @@ impl<'s, 'h> SyncIo for NetSyncIo<'s, 'h> {
 	fn eth_protocol_version(&self, peer_id: PeerId) -> u8 {
 		self.network.protocol_version(peer_id, self.network.subprotocol_name()).unwrap_or(0)
 	}
+
+	fn is_upload_throttled(&self) -> bool {
+		self.network.is_upload_throttled()
+	}
+
+	fn is_download_throttled(&self) -> bool {
+		self.network.is_download_throttled()
+	}
 }
 
 
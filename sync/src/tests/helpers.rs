@@ -29,6 +29,7 @@ pub struct TestIo<'p> {
 	pub snapshot_service: &'p TestSnapshotService,
 	pub queue: &'p mut VecDeque<TestPacket>,
 	pub sender: Option<PeerId>,
+	pub upload_throttled: bool,
 }
 
 impl<'p> TestIo<'p> {
@@ -37,7 +38,8 @@ impl<'p> TestIo<'p> {
 			chain: chain,
 			snapshot_service: ss,
 			queue: queue,
-			sender: sender
+			sender: sender,
+			upload_throttled: false,
 		}
 	}
 }
@@ -82,6 +84,10 @@ impl<'p> SyncIo for TestIo<'p> {
 	fn eth_protocol_version(&self, _peer: PeerId) -> u8 {
 		64
 	}
+
+	fn is_upload_throttled(&self) -> bool {
+		self.upload_throttled
+	}
 }
 
 pub struct TestPacket {
@@ -168,4 +168,11 @@ mod tests {
 		service.register_handler(Arc::new(MyHandler)).unwrap();
 	}
 
+	#[test]
+	fn test_service_with_workers() {
+		let service = IoService::<MyMessage>::start_with_workers(1).expect("Error creating network service");
+		service.register_handler(Arc::new(MyHandler)).unwrap();
+		service.send_message(MyMessage { data: 5 }).unwrap();
+	}
+
 }
@@ -36,6 +36,8 @@ pub type HandlerId = usize;
 /// Maximum number of tokens a handler can use
 pub const TOKENS_PER_HANDLER: usize = 16384;
 const MAX_HANDLERS: usize = 8;
+/// Number of worker threads used by `IoService::start`
+const DEFAULT_WORKERS: usize = 4;
 
 /// Messages used to communicate with the event loop from other threads.
 #[derive(Clone)]
@@ -172,10 +174,9 @@ pub struct IoManager<Message> where Message: Send + Sync {
 }
 
 impl<Message> IoManager<Message> where Message: Send + Sync + Clone + 'static {
-	/// Creates a new instance and registers it with the event loop.
-	pub fn start(panic_handler: Arc<PanicHandler>, event_loop: &mut EventLoop<IoManager<Message>>) -> Result<(), IoError> {
+	/// Creates a new instance with `num_workers` worker threads and registers it with the event loop.
+	pub fn start(panic_handler: Arc<PanicHandler>, event_loop: &mut EventLoop<IoManager<Message>>, num_workers: usize) -> Result<(), IoError> {
 		let (worker, stealer) = chase_lev::deque();
-		let num_workers = 4;
 		let work_ready_mutex =  Arc::new(SMutex::new(()));
 		let work_ready = Arc::new(SCondvar::new());
 		let workers = (0..num_workers).map(|i|
@@ -359,6 +360,12 @@ impl<Message> MayPanic for IoService<Message> where Message: Send + Sync + Clone
 impl<Message> IoService<Message> where Message: Send + Sync + Clone + 'static {
 	/// Starts IO event loop
 	pub fn start() -> Result<IoService<Message>, IoError> {
+		IoService::start_with_workers(DEFAULT_WORKERS)
+	}
+
+	/// Starts IO event loop with given number of worker threads (at least one).
+	pub fn start_with_workers(num_workers: usize) -> Result<IoService<Message>, IoError> {
+		let num_workers = ::std::cmp::max(num_workers, 1);
 		let panic_handler = PanicHandler::new_in_arc();
 		let mut config = EventLoopConfig::new();
 		config.messages_per_tick(1024);
@@ -368,7 +375,7 @@ impl<Message> IoService<Message> where Message: Send + Sync + Clone + 'static {
 		let thread = thread::spawn(move || {
 			let p = panic.clone();
 			panic.catch_panic(move || {
-				IoManager::<Message>::start(p, &mut event_loop).unwrap();
+				IoManager::<Message>::start(p, &mut event_loop, num_workers).unwrap();
 			}).unwrap()
 		});
 		Ok(IoService {
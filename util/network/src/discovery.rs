@@ -46,6 +46,8 @@ const PACKET_FIND_NODE: u8 = 3;
 const PACKET_NEIGHBOURS: u8 = 4;
 
 const PING_TIMEOUT_MS: u64 = 300;
+const EXPIRY_TIME_SEC: u32 = 60; // Packets we send expire after this many seconds
+const CLOCK_SAMPLES: usize = 64; // Number of recent peer clock samples kept
 const MAX_NODES_PING: usize = 32; // Max nodes to add/ping at once
 
 #[derive(Clone, Debug)]
@@ -95,6 +97,7 @@ pub struct Discovery {
 	send_queue: VecDeque<Datagramm>,
 	check_timestamps: bool,
 	adding_nodes: Vec<NodeEntry>,
+	clock_samples: VecDeque<i64>,
 }
 
 pub struct TableUpdates {
@@ -118,6 +121,7 @@ impl Discovery {
 			send_queue: VecDeque::new(),
 			check_timestamps: true,
 			adding_nodes: Vec::new(),
+			clock_samples: VecDeque::new(),
 		}
 	}
 
@@ -247,7 +251,7 @@ impl Discovery {
 		for i in 0 .. source.item_count() {
 			rlp.append_raw(source.at(i).as_raw(), 1);
 		}
-		let timestamp = time::get_time().sec as u32 + 60;
+		let timestamp = time::get_time().sec as u32 + EXPIRY_TIME_SEC;
 		rlp.append(&timestamp);
 
 		let bytes = rlp.drain();
@@ -386,11 +390,32 @@ impl Discovery {
 		Ok(())
 	}
 
+	/// Estimates the peer's clock from the expiration of its ping, assuming it uses the same expiration time as we do.
+	fn note_clock_sample(&mut self, timestamp: u64) {
+		if timestamp > ::std::u32::MAX as u64 {
+			return;
+		}
+		let drift = time::get_time().sec - (timestamp as i64 - EXPIRY_TIME_SEC as i64);
+		if self.clock_samples.len() == CLOCK_SAMPLES {
+			self.clock_samples.pop_front();
+		}
+		self.clock_samples.push_back(drift);
+	}
+
+	/// Median offset of the local clock against clocks of recently pinging peers, in seconds.
+	/// Positive if the local clock is ahead. Only accurate to the differences of expiration times used by peers.
+	pub fn clock_drift(&self) -> Option<i64> {
+		let mut samples: Vec<_> = self.clock_samples.iter().cloned().collect();
+		samples.sort();
+		samples.get(samples.len() / 2).cloned()
+	}
+
 	fn on_ping(&mut self, rlp: &UntrustedRlp, node: &NodeId, from: &SocketAddr) -> Result<Option<TableUpdates>, NetworkError> {
 		trace!(target: "discovery", "Got Ping from {:?}", &from);
 		let source = try!(NodeEndpoint::from_rlp(&try!(rlp.at(1))));
 		let dest = try!(NodeEndpoint::from_rlp(&try!(rlp.at(2))));
 		let timestamp: u64 = try!(rlp.val_at(3));
+		self.note_clock_sample(timestamp);
 		try!(self.check_timestamp(timestamp));
 		let mut added_map = HashMap::new();
 		let entry = NodeEntry { id: node.clone(), endpoint: source.clone() };
@@ -605,6 +630,21 @@ mod tests {
 		assert!(removed > 0);
 	}
 
+	#[test]
+	fn estimates_clock_drift_of_peers() {
+		let key = Random.generate().unwrap();
+		let ep = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40448").unwrap(), udp_port: 40448 };
+		let mut discovery = Discovery::new(&key, ep.address.clone(), ep.clone(), 0);
+		assert_eq!(discovery.clock_drift(), None);
+
+		let now = time::get_time().sec as u64;
+		for offset in &[0u64, 300, 300] {
+			discovery.note_clock_sample(now + EXPIRY_TIME_SEC as u64 + offset);
+		}
+		let drift = discovery.clock_drift().unwrap();
+		assert!(drift <= -299 && drift >= -300);
+	}
+
 	#[test]
 	fn find_nearest_saturated() {
 		use super::*;
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::net::SocketAddr;
+use std::net::{SocketAddr, IpAddr};
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -24,6 +24,8 @@ use std::cmp::min;
 use std::path::{Path, PathBuf};
 use std::io::{Read, Write};
 use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
 use ethkey::{KeyPair, Secret, Random, Generator};
 use mio::*;
 use mio::tcp::*;
@@ -31,14 +33,16 @@ use util::hash::*;
 use util::Hashable;
 use util::version;
 use rlp::*;
-use session::{Session, SessionData};
+use session::{Session, SessionData, SessionInfo};
 use error::*;
 use io::*;
 use {NetworkProtocolHandler, NonReservedPeerMode, PROTOCOL_VERSION};
+use connection_filter::{ConnectionFilter, ConnectionDirection};
 use node_table::*;
-use stats::NetworkStats;
+use stats::{NetworkStats, PeerTraffic};
 use discovery::{Discovery, TableUpdates, NodeEntry};
-use ip_utils::{map_external_address, select_public_address};
+use ip_utils::{map_external_address, renew_mapping, select_public_address, subnet, NatMapping, NAT_LEASE_SECS};
+#[cfg(not(unix))]
 use util::path::restrict_permissions_owner;
 use parking_lot::{Mutex, RwLock};
 
@@ -78,6 +82,14 @@ pub struct NetworkConfiguration {
 	pub reserved_nodes: Vec<String>,
 	/// The non-reserved peer mode.
 	pub non_reserved_mode: NonReservedPeerMode,
+	/// Upload rate in kilobytes per second above which block and transaction propagation is throttled.
+	pub max_upload_kbps: Option<u32>,
+	/// Download rate in kilobytes per second above which new sync requests are postponed.
+	pub max_download_kbps: Option<u32>,
+	/// Maximum number of non-reserved peers connected from a single IP address.
+	pub max_peers_per_ip: Option<u32>,
+	/// Maximum number of non-reserved peers connected from a single /24 (IPv4) or /48 (IPv6) subnet.
+	pub max_peers_per_subnet: Option<u32>,
 }
 
 impl Default for NetworkConfiguration {
@@ -103,6 +115,10 @@ impl NetworkConfiguration {
 			max_peers: 50,
 			reserved_nodes: Vec::new(),
 			non_reserved_mode: NonReservedPeerMode::Accept,
+			max_upload_kbps: None,
+			max_download_kbps: None,
+			max_peers_per_ip: None,
+			max_peers_per_subnet: None,
 		}
 	}
 
@@ -168,6 +184,8 @@ pub enum NetworkIoMessage {
 	DisablePeer(PeerId),
 	/// Network has been started with the host as the given enode.
 	NetworkStarted(String),
+	/// NAT port mapping lease has been renewed.
+	NatMappingRenewed(NatMapping),
 }
 
 /// Local (temporary) peer session ID.
@@ -198,6 +216,7 @@ pub struct NetworkContext<'s> {
 	session: Option<SharedSession>,
 	session_id: Option<StreamToken>,
 	_reserved_peers: &'s HashSet<NodeId>,
+	stats: &'s NetworkStats,
 }
 
 impl<'s> NetworkContext<'s> {
@@ -205,7 +224,7 @@ impl<'s> NetworkContext<'s> {
 	fn new(io: &'s IoContext<NetworkIoMessage>,
 		protocol: ProtocolId,
 		session: Option<SharedSession>, sessions: Arc<RwLock<Slab<SharedSession>>>,
-		reserved_peers: &'s HashSet<NodeId>) -> NetworkContext<'s> {
+		reserved_peers: &'s HashSet<NodeId>, stats: &'s NetworkStats) -> NetworkContext<'s> {
 		let id = session.as_ref().map(|s| s.lock().token());
 		NetworkContext {
 			io: io,
@@ -214,6 +233,7 @@ impl<'s> NetworkContext<'s> {
 			session: session,
 			sessions: sessions,
 			_reserved_peers: reserved_peers,
+			stats: stats,
 		}
 	}
 
@@ -283,6 +303,11 @@ impl<'s> NetworkContext<'s> {
 		"unknown".to_owned()
 	}
 
+	/// Returns session information of the peer.
+	pub fn session_info(&self, peer: PeerId) -> Option<SessionInfo> {
+		self.resolve_session(peer).map(|s| s.lock().info.clone())
+	}
+
 	/// Returns max version for a given protocol.
 	pub fn protocol_version(&self, peer: PeerId, protocol: ProtocolId) -> Option<u8> {
 		let session = self.resolve_session(peer);
@@ -291,6 +316,16 @@ impl<'s> NetworkContext<'s> {
 
 	/// Returns this object's subprotocol name.
 	pub fn subprotocol_name(&self) -> ProtocolId { self.protocol }
+
+	/// Returns true if the upload rate exceeds configured limit and non-essential packets should not be sent.
+	pub fn is_upload_throttled(&self) -> bool {
+		self.stats.is_upload_throttled()
+	}
+
+	/// Returns true if the download rate exceeds configured limit and new data should not be requested.
+	pub fn is_download_throttled(&self) -> bool {
+		self.stats.is_download_throttled()
+	}
 }
 
 /// Shared host information
@@ -353,11 +388,14 @@ pub struct Host {
 	reserved_nodes: RwLock<HashSet<NodeId>>,
 	num_sessions: AtomicUsize,
 	stopping: AtomicBool,
+	nat_mapping: Mutex<Option<NatMapping>>,
+	last_rate_update: Mutex<Instant>,
+	filter: Option<Arc<ConnectionFilter>>,
 }
 
 impl Host {
 	/// Create a new instance
-	pub fn new(config: NetworkConfiguration, stats: Arc<NetworkStats>) -> Result<Host, NetworkError> {
+	pub fn new(config: NetworkConfiguration, stats: Arc<NetworkStats>, filter: Option<Arc<ConnectionFilter>>) -> Result<Host, NetworkError> {
 		trace!(target: "host", "Creating new Host object");
 
 		let mut listen_address = match config.listen_address {
@@ -372,7 +410,9 @@ impl Host {
 				.map_or_else(|| {
 				let key = Random.generate().unwrap();
 				if let Some(path) = config.config_path.clone() {
-					save_key(Path::new(&path), key.secret());
+					if let Err(e) = save_key(Path::new(&path), key.secret()) {
+						warn!("Error saving key file: {:?}", e);
+					}
 				}
 				key
 			},
@@ -410,6 +450,9 @@ impl Host {
 			reserved_nodes: RwLock::new(HashSet::new()),
 			num_sessions: AtomicUsize::new(0),
 			stopping: AtomicBool::new(false),
+			nat_mapping: Mutex::new(None),
+			last_rate_update: Mutex::new(Instant::now()),
+			filter: filter,
 		};
 
 		for n in boot_nodes {
@@ -497,6 +540,16 @@ impl Host {
 		self.info.read().public_endpoint.as_ref().map(|e| format!("{}", Node::new(self.info.read().id().clone(), e.clone())))
 	}
 
+	/// Returns URLs of known nodes, most reliable first.
+	pub fn node_table(&self) -> Vec<String> {
+		self.nodes.read().urls()
+	}
+
+	/// Returns estimated offset of the local clock against clocks of peers, in seconds.
+	pub fn peer_clock_drift(&self) -> Option<i64> {
+		self.discovery.lock().as_ref().and_then(|d| d.clock_drift())
+	}
+
 	pub fn local_url(&self) -> String {
 		let r = format!("{}", Node::new(self.info.read().id().clone(), self.info.read().local_endpoint.clone()));
 		println!("{}", r);
@@ -531,8 +584,10 @@ impl Host {
 				let public_endpoint = NodeEndpoint { address: public_address, udp_port: local_endpoint.udp_port };
 				if self.info.read().config.nat_enabled {
 					match map_external_address(&local_endpoint) {
-						Some(endpoint) => {
-							info!("NAT mapped to external address {}", endpoint.address);
+						Some(mapping) => {
+							info!("NAT mapped to external address {} using {:?}", mapping.external.address, mapping.method);
+							let endpoint = mapping.external.clone();
+							*self.nat_mapping.lock() = Some(mapping);
 							endpoint
 						},
 						None => public_endpoint
@@ -569,15 +624,81 @@ impl Host {
 			io.register_timer(DISCOVERY_ROUND, 300).expect("Error registering discovery timer");
 		}
 		try!(io.register_timer(NODE_TABLE, 300_000));
+		if let Some(mapping) = self.nat_mapping.lock().clone() {
+			Self::renew_nat_mapping_periodically(mapping, io.channel());
+		}
 		try!(io.register_stream(TCP_ACCEPT));
 		Ok(())
 	}
 
+	/// Renews the mapping lease on a separate thread, since talking to the gateway blocks.
+	/// The thread exits once the IO service is gone.
+	fn renew_nat_mapping_periodically(mut mapping: NatMapping, channel: IoChannel<NetworkIoMessage>) {
+		let spawned = thread::Builder::new().name("NAT Renewal".into()).spawn(move || loop {
+			// renew well before the lease expires
+			thread::sleep(Duration::from_secs(NAT_LEASE_SECS as u64 / 2));
+			match renew_mapping(&mapping) {
+				Some(renewed) => {
+					mapping = renewed.clone();
+					if channel.send(NetworkIoMessage::NatMappingRenewed(renewed)).is_err() {
+						return;
+					}
+				},
+				None => warn!("Failed to renew NAT port mapping for {}", mapping.external.address),
+			}
+		});
+		if let Err(e) = spawned {
+			warn!("Cannot start NAT renewal thread: {}. The port mapping will expire.", e);
+		}
+	}
+
+	fn update_nat_mapping(&self, renewed: NatMapping) {
+		let previous = ::std::mem::replace(&mut *self.nat_mapping.lock(), Some(renewed.clone()));
+		let changed = previous.map_or(true, |previous|
+			renewed.external.address != previous.external.address || renewed.external.udp_port != previous.external.udp_port);
+		if changed {
+			warn!("NAT mapping changed to external address {}", renewed.external.address);
+			self.info.write().public_endpoint = Some(renewed.external);
+		}
+	}
+
 	fn maintain_network(&self, io: &IoContext<NetworkIoMessage>) {
+		self.update_rates();
 		self.keep_alive(io);
 		self.connect_peers(io);
 	}
 
+	fn update_rates(&self) {
+		let elapsed = {
+			let mut last_update = self.last_rate_update.lock();
+			let elapsed = last_update.elapsed();
+			*last_update = Instant::now();
+			elapsed
+		};
+		let elapsed_ms = elapsed.as_secs() * 1000 + elapsed.subsec_nanos() as u64 / 1_000_000;
+		let (max_upload, max_download) = {
+			let info = self.info.read();
+			(info.config.max_upload_kbps, info.config.max_download_kbps)
+		};
+		self.stats.update_rates(elapsed_ms, max_upload.map(|kbps| kbps as usize * 1024), max_download.map(|kbps| kbps as usize * 1024));
+	}
+
+	/// Returns traffic of connected peers.
+	pub fn peer_traffic(&self) -> Vec<PeerTraffic> {
+		let sessions = self.sessions.read();
+		let traffic = sessions.iter()
+			.map(|s| s.lock())
+			.filter(|s| s.is_ready())
+			.map(|s| PeerTraffic {
+				id: s.id().cloned(),
+				client_version: s.info.client_version.clone(),
+				bytes_sent: s.info.bytes_sent,
+				bytes_received: s.info.bytes_received,
+			})
+			.collect();
+		traffic
+	}
+
 	fn have_session(&self, id: &NodeId) -> bool {
 		self.sessions.read().iter().any(|e| e.lock().info.id == Some(id.clone()))
 	}
@@ -643,7 +764,7 @@ impl Host {
 		// iterate over all nodes, reserved ones coming first.
 		// if we are pinned to only reserved nodes, ignore all others.
 		let nodes = reserved_nodes.iter().cloned().chain(if !pin {
-			self.nodes.read().nodes()
+			self.connection_candidates()
 		} else {
 			Vec::new()
 		});
@@ -657,6 +778,47 @@ impl Host {
 		debug!(target: "network", "Connecting peers: {} sessions, {} pending, {} started", self.session_count(), self.handshake_count(), started);
 	}
 
+	/// Remote IP addresses of all sessions, including the ones still handshaking.
+	fn session_addresses(&self, exclude: Option<StreamToken>) -> Vec<IpAddr> {
+		self.sessions.read().iter()
+			.map(|e| e.lock())
+			.filter(|s| Some(s.token()) != exclude)
+			.filter_map(|s| s.remote_addr().ok().map(|a| a.ip()))
+			.collect()
+	}
+
+	fn address_limits(&self) -> (Option<u32>, Option<u32>) {
+		let info = self.info.read();
+		(info.config.max_peers_per_ip, info.config.max_peers_per_subnet)
+	}
+
+	/// Known non-reserved nodes to connect to. Nodes at addresses that already hold as many sessions
+	/// as the per IP or per subnet limits allow are skipped. The rest are ordered so that nodes from
+	/// less represented subnets come first, spreading peer slots over as many networks as possible.
+	fn connection_candidates(&self) -> Vec<NodeId> {
+		let (max_per_ip, max_per_subnet) = self.address_limits();
+		let addresses = self.session_addresses(None);
+		let nodes = self.nodes.read();
+		let mut candidates: Vec<(usize, NodeId)> = nodes.nodes().into_iter()
+			.filter_map(|id| nodes.get(&id).map(|n| (n.endpoint.address.ip(), id)))
+			.filter(|&(ref ip, _)| !address_limit_reached(ip, &addresses, max_per_ip, max_per_subnet))
+			.map(|(ip, id)| {
+				let net = subnet(&ip);
+				(addresses.iter().filter(|a| subnet(a) == net).count(), id)
+			})
+			.collect();
+		// stable sort keeps the failure ordering within each group
+		candidates.sort_by(|a, b| a.0.cmp(&b.0));
+		candidates.into_iter().map(|(_, id)| id).collect()
+	}
+
+	fn connection_allowed(&self, id: &NodeId, direction: ConnectionDirection) -> bool {
+		match self.filter {
+			Some(ref filter) => filter.connection_allowed(self.info.read().id(), id, direction),
+			None => true,
+		}
+	}
+
 	#[cfg_attr(feature="dev", allow(single_match))]
 	fn connect_peer(&self, id: &NodeId, io: &IoContext<NetworkIoMessage>) {
 		if self.have_session(id)
@@ -668,6 +830,10 @@ impl Host {
 			trace!(target: "network", "Aborted connect. Node already connecting.");
 			return;
 		}
+		if !self.connection_allowed(id, ConnectionDirection::Outbound) {
+			trace!(target: "network", "Aborted connect. Node {} is not allowed by the connection filter.", id);
+			return;
+		}
 
 		let socket = {
 			let address = {
@@ -759,6 +925,7 @@ impl Host {
 		let mut ready_data: Vec<ProtocolId> = Vec::new();
 		let mut packet_data: Vec<(ProtocolId, PacketId, Vec<u8>)> = Vec::new();
 		let mut kill = false;
+		let mut inbound_address = None;
 		let session = { self.sessions.read().get(token).cloned() };
 		if let Some(session) = session.clone() {
 			let mut s = session.lock();
@@ -779,6 +946,12 @@ impl Host {
 					},
 					Ok(SessionData::Ready) => {
 						self.num_sessions.fetch_add(1, AtomicOrdering::SeqCst);
+						let direction = if s.info.originated { ConnectionDirection::Outbound } else { ConnectionDirection::Inbound };
+						if !self.connection_allowed(s.id().expect("Ready session always has id; qed"), direction) {
+							trace!(target: "network", "Node {:?} is not allowed by the connection filter", s.id());
+							s.disconnect(io, DisconnectReason::UnexpectedIdentity);
+							return;
+						}
 						if !s.info.originated {
 							let session_count = self.session_count();
 							let (max_peers, reserved_only) = {
@@ -794,6 +967,10 @@ impl Host {
 								}
 							}
 
+							if !self.reserved_nodes.read().contains(s.id().unwrap()) {
+								inbound_address = s.remote_addr().ok().map(|a| a.ip());
+							}
+
 							// Add it no node table
 							if let Ok(address) = s.remote_addr() {
 								let entry = NodeEntry { id: s.id().unwrap().clone(), endpoint: NodeEndpoint { address: address, udp_port: address.port() } };
@@ -828,17 +1005,28 @@ impl Host {
 		if kill {
 			self.kill_connection(token, io, true);
 		}
+		// Checked with the session unlocked since other sessions have to be locked to get their addresses.
+		if let Some(address) = inbound_address {
+			let (max_per_ip, max_per_subnet) = self.address_limits();
+			if !kill && address_limit_reached(&address, &self.session_addresses(Some(token)), max_per_ip, max_per_subnet) {
+				trace!(target: "network", "Too many peers from {}, disconnecting {}", address, token);
+				if let Some(ref session) = session {
+					session.lock().disconnect(io, DisconnectReason::TooManyPeers);
+				}
+				return;
+			}
+		}
 		let handlers = self.handlers.read();
 		for p in ready_data {
 			let h = handlers.get(&p).unwrap().clone();
 			self.stats.inc_sessions();
 			let reserved = self.reserved_nodes.read();
-			h.connected(&NetworkContext::new(io, p, session.clone(), self.sessions.clone(), &reserved), &token);
+			h.connected(&NetworkContext::new(io, p, session.clone(), self.sessions.clone(), &reserved, &self.stats), &token);
 		}
 		for (p, packet_id, data) in packet_data {
 			let h = handlers.get(&p).unwrap().clone();
 			let reserved = self.reserved_nodes.read();
-			h.read(&NetworkContext::new(io, p, session.clone(), self.sessions.clone(), &reserved), &token, packet_id, &data[1..]);
+			h.read(&NetworkContext::new(io, p, session.clone(), self.sessions.clone(), &reserved, &self.stats), &token, packet_id, &data[1..]);
 		}
 	}
 
@@ -880,7 +1068,7 @@ impl Host {
 		for p in to_disconnect {
 			let h = self.handlers.read().get(&p).unwrap().clone();
 			let reserved = self.reserved_nodes.read();
-			h.disconnected(&NetworkContext::new(io, p, expired_session.clone(), self.sessions.clone(), &reserved), &token);
+			h.disconnected(&NetworkContext::new(io, p, expired_session.clone(), self.sessions.clone(), &reserved, &self.stats), &token);
 		}
 		if deregister {
 			io.deregister_stream(token).unwrap_or_else(|e| debug!("Error deregistering stream: {:?}", e));
@@ -910,9 +1098,16 @@ impl Host {
 	pub fn with_context<F>(&self, protocol: ProtocolId, io: &IoContext<NetworkIoMessage>, action: F) where F: Fn(&NetworkContext) {
 		let reserved = { self.reserved_nodes.read() };
 
-		let context = NetworkContext::new(io, protocol, None, self.sessions.clone(), &reserved);
+		let context = NetworkContext::new(io, protocol, None, self.sessions.clone(), &reserved, &self.stats);
 		action(&context);
 	}
+
+	pub fn with_context_eval<F, T>(&self, protocol: ProtocolId, io: &IoContext<NetworkIoMessage>, action: F) -> T where F: Fn(&NetworkContext) -> T {
+		let reserved = { self.reserved_nodes.read() };
+
+		let context = NetworkContext::new(io, protocol, None, self.sessions.clone(), &reserved, &self.stats);
+		action(&context)
+	}
 }
 
 impl IoHandler<NetworkIoMessage> for Host {
@@ -981,14 +1176,17 @@ impl IoHandler<NetworkIoMessage> for Host {
 			},
 			NODE_TABLE => {
 				trace!(target: "network", "Refreshing node table");
-				self.nodes.write().clear_useless();
+				let mut nodes = self.nodes.write();
+				nodes.clear_useless();
+				// persist discovered nodes in case we don't shut down cleanly
+				nodes.save();
 			},
 			_ => match self.timers.read().get(&token).cloned() {
 				Some(timer) => match self.handlers.read().get(&timer.protocol).cloned() {
 					None => { warn!(target: "network", "No handler found for protocol: {:?}", timer.protocol) },
 					Some(h) => {
 						let reserved = self.reserved_nodes.read();
-						h.timeout(&NetworkContext::new(io, timer.protocol, None, self.sessions.clone(), &reserved), timer.token);
+						h.timeout(&NetworkContext::new(io, timer.protocol, None, self.sessions.clone(), &reserved, &self.stats), timer.token);
 					}
 				},
 				None => { warn!("Unknown timer token: {}", token); } // timer is not registerd through us
@@ -1008,7 +1206,7 @@ impl IoHandler<NetworkIoMessage> for Host {
 			} => {
 				let h = handler.clone();
 				let reserved = self.reserved_nodes.read();
-				h.initialize(&NetworkContext::new(io, *protocol, None, self.sessions.clone(), &reserved));
+				h.initialize(&NetworkContext::new(io, *protocol, None, self.sessions.clone(), &reserved, &self.stats));
 				self.handlers.write().insert(*protocol, h);
 				let mut info = self.info.write();
 				for v in versions {
@@ -1051,6 +1249,7 @@ impl IoHandler<NetworkIoMessage> for Host {
 			},
 			NetworkIoMessage::InitPublicInterface =>
 				self.init_public_interface(io).unwrap_or_else(|e| warn!("Error initializing public interface: {:?}", e)),
+			NetworkIoMessage::NatMappingRenewed(ref mapping) => self.update_nat_mapping(mapping.clone()),
 			_ => {}	// ignore others.
 		}
 	}
@@ -1098,27 +1297,43 @@ impl IoHandler<NetworkIoMessage> for Host {
 	}
 }
 
-fn save_key(path: &Path, key: &Secret) {
+/// Generates a new node key and stores it in the network configuration directory at `path`,
+/// replacing the existing one. Returns the new node id, used from the next start of the node.
+pub fn new_node_key(path: &Path) -> Result<NodeId, NetworkError> {
+	let key = try!(Random.generate());
+	try!(save_key(path, key.secret()));
+	Ok(key.public().clone())
+}
+
+/// Writes the key to a temporary file readable only by the owner and moves it over the
+/// previous one, so that an interrupted rotation never leaves a truncated key behind.
+fn save_key(path: &Path, key: &Secret) -> ::std::io::Result<()> {
 	let mut path_buf = PathBuf::from(path);
-	if let Err(e) = fs::create_dir_all(path_buf.as_path()) {
-		warn!("Error creating key directory: {:?}", e);
-		return;
-	};
+	try!(fs::create_dir_all(path_buf.as_path()));
+	let temp_path = path_buf.join("key.tmp");
 	path_buf.push("key");
-	let path = path_buf.as_path();
-	let mut file = match fs::File::create(&path) {
-		Ok(file) => file,
-		Err(e) => {
-			warn!("Error creating key file: {:?}", e);
-			return;
-		}
-	};
-	if let Err(e) = restrict_permissions_owner(path) {
-		warn!(target: "network", "Failed to modify permissions of the file (chmod: {})", e);
-	}
-	if let Err(e) = file.write(&key.hex().into_bytes()) {
-		warn!("Error writing key file: {:?}", e);
+	// a stale temporary file might have been created with other permissions
+	let _ = fs::remove_file(&temp_path);
+	{
+		let mut file = try!(create_owner_only(&temp_path));
+		try!(file.write_all(&key.hex().into_bytes()));
+		try!(file.sync_all());
 	}
+	fs::rename(&temp_path, &path_buf)
+}
+
+#[cfg(unix)]
+fn create_owner_only(path: &Path) -> ::std::io::Result<fs::File> {
+	use std::os::unix::fs::OpenOptionsExt;
+	fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)
+}
+
+#[cfg(not(unix))]
+fn create_owner_only(path: &Path) -> ::std::io::Result<fs::File> {
+	let file = try!(fs::OpenOptions::new().write(true).create_new(true).open(path));
+	// chmod sets errno on failure
+	try!(restrict_permissions_owner(path).map_err(|_| ::std::io::Error::last_os_error()));
+	Ok(file)
 }
 
 fn load_key(path: &Path) -> Option<Secret> {
@@ -1148,22 +1363,70 @@ fn load_key(path: &Path) -> Option<Secret> {
 	}
 }
 
+/// Returns `true` if another peer from `ip` would exceed the per IP or per subnet limit,
+/// given the addresses of the already connected peers.
+fn address_limit_reached(ip: &IpAddr, addresses: &[IpAddr], max_per_ip: Option<u32>, max_per_subnet: Option<u32>) -> bool {
+	let ip_limit_reached = max_per_ip.map_or(false, |max| addresses.iter().filter(|a| *a == ip).count() >= max as usize);
+	let subnet_limit_reached = max_per_subnet.map_or(false, |max| {
+		let net = subnet(ip);
+		addresses.iter().filter(|a| subnet(a) == net).count() >= max as usize
+	});
+	ip_limit_reached || subnet_limit_reached
+}
+
 #[test]
 fn key_save_load() {
 	use ::devtools::RandomTempPath;
 	let temp_path = RandomTempPath::create_dir();
 	let key = H256::random();
-	save_key(temp_path.as_path(), &key);
+	save_key(temp_path.as_path(), &key).unwrap();
 	let r = load_key(temp_path.as_path());
 	assert_eq!(key, r.unwrap());
 }
 
+#[cfg(unix)]
+#[test]
+fn key_is_saved_readable_only_by_owner() {
+	use std::os::unix::fs::PermissionsExt;
+	use ::devtools::RandomTempPath;
+	let temp_path = RandomTempPath::create_dir();
+	save_key(temp_path.as_path(), &H256::random()).unwrap();
+	let key = H256::random();
+	save_key(temp_path.as_path(), &key).unwrap();
+	let metadata = fs::metadata(temp_path.as_path().join("key")).unwrap();
+	assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+	assert!(!temp_path.as_path().join("key.tmp").exists());
+	assert_eq!(load_key(temp_path.as_path()), Some(key));
+}
+
+#[test]
+fn new_node_key_replaces_saved_key() {
+	use ::devtools::RandomTempPath;
+	let temp_path = RandomTempPath::create_dir();
+	save_key(temp_path.as_path(), &H256::random()).unwrap();
+	let id = new_node_key(temp_path.as_path()).unwrap();
+	let key = KeyPair::from_secret(load_key(temp_path.as_path()).unwrap()).unwrap();
+	assert_eq!(key.public(), &id);
+}
+
+
+#[test]
+fn limits_peers_per_address() {
+	let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+	let connected = vec![ip("203.0.113.1"), ip("203.0.113.1"), ip("203.0.113.2"), ip("198.51.100.1")];
+
+	assert!(!address_limit_reached(&ip("203.0.113.1"), &connected, None, None));
+	assert!(address_limit_reached(&ip("203.0.113.1"), &connected, Some(2), None));
+	assert!(!address_limit_reached(&ip("203.0.113.2"), &connected, Some(2), None));
+	assert!(address_limit_reached(&ip("203.0.113.9"), &connected, Some(2), Some(3)));
+	assert!(!address_limit_reached(&ip("198.51.100.9"), &connected, Some(2), Some(3)));
+}
 
 #[test]
 fn host_client_url() {
 	let mut config = NetworkConfiguration::new();
 	let key = "6f7b0d801bc7b5ce7bbd930b84fd0369b3eb25d09be58d64ba811091046f3aa2".into();
 	config.use_secret = Some(key);
-	let host: Host = Host::new(config, Arc::new(NetworkStats::new())).unwrap();
+	let host: Host = Host::new(config, Arc::new(NetworkStats::new()), None).unwrap();
 	assert!(host.local_url().starts_with("enode://101b3ef5a4ea7a1c7928e24c4c75fd053c235d7b80c22ae5c03d145d0ac7396e2a4ffff9adee3133a7b05044a5cee08115fd65145e5165d646bde371010d803c@"));
 }
@@ -16,8 +16,9 @@
 
 // Based on original work by David Levy https://raw.githubusercontent.com/dlevy47/rust-interfaces
 
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
-use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, UdpSocket};
+use std::io::{self, Read};
+use std::fs::File;
 use igd::{PortMappingProtocol, search_gateway_from_timeout};
 use std::time::Duration;
 use node_table::{NodeEndpoint};
@@ -136,6 +137,21 @@ fn get_if_addrs() -> io::Result<Vec<IpAddr>> {
 	Ok(Vec::new())
 }
 
+/// Returns the network a peer address belongs to when limiting peers per subnet:
+/// the /24 for IPv4 and the /48 for IPv6 addresses.
+pub fn subnet(ip: &IpAddr) -> IpAddr {
+	match *ip {
+		IpAddr::V4(ref ip) => {
+			let octets = ip.octets();
+			IpAddr::V4(Ipv4Addr::new(octets[0], octets[1], octets[2], 0))
+		},
+		IpAddr::V6(ref ip) => {
+			let segments = ip.segments();
+			IpAddr::V6(Ipv6Addr::new(segments[0], segments[1], segments[2], 0, 0, 0, 0, 0))
+		},
+	}
+}
+
 /// Select the best available public address
 pub fn select_public_address(port: u16) -> SocketAddr {
 	match get_if_addrs() {
@@ -163,39 +179,200 @@ pub fn select_public_address(port: u16) -> SocketAddr {
 	SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), port))
 }
 
-pub fn map_external_address(local: &NodeEndpoint) -> Option<NodeEndpoint> {
+/// Lease requested for port mappings, in seconds. Mappings are renewed before they expire.
+pub const NAT_LEASE_SECS: u32 = 3600;
+
+const NATPMP_PORT: u16 = 5351;
+const NATPMP_OP_MAP_UDP: u8 = 1;
+const NATPMP_OP_MAP_TCP: u8 = 2;
+
+/// Protocol used to map the external ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NatMethod {
+	/// Universal Plug and Play Internet Gateway Device.
+	Upnp,
+	/// NAT Port Mapping Protocol.
+	NatPmp,
+}
+
+/// Port mapping established on the gateway.
+#[derive(Debug, Clone)]
+pub struct NatMapping {
+	/// Protocol used to establish the mapping.
+	pub method: NatMethod,
+	/// Local endpoint the ports are mapped to.
+	pub local: NodeEndpoint,
+	/// External endpoint.
+	pub external: NodeEndpoint,
+}
+
+/// Maps local TCP and UDP ports on the gateway using UPnP, falling back to NAT-PMP.
+pub fn map_external_address(local: &NodeEndpoint) -> Option<NatMapping> {
 	if let SocketAddr::V4(ref local_addr) = local.address {
-		match search_gateway_from_timeout(local_addr.ip().clone(), Duration::new(5, 0)) {
-			Err(ref err) => debug!("Gateway search error: {}", err),
-			Ok(gateway) => {
-				match gateway.get_external_ip() {
-					Err(ref err) => {
-						debug!("IP request error: {}", err);
-					},
-					Ok(external_addr) => {
-						match gateway.add_any_port(PortMappingProtocol::TCP, SocketAddrV4::new(local_addr.ip().clone(), local_addr.port()), 0, "Parity Node/TCP") {
-							Err(ref err) => {
-								debug!("Port mapping error: {}", err);
-							},
-							Ok(tcp_port) => {
-								match gateway.add_any_port(PortMappingProtocol::UDP, SocketAddrV4::new(local_addr.ip().clone(), local.udp_port), 0, "Parity Node/UDP") {
-									Err(ref err) => {
-										debug!("Port mapping error: {}", err);
-									},
-									Ok(udp_port) => {
-										return Some(NodeEndpoint { address: SocketAddr::V4(SocketAddrV4::new(external_addr, tcp_port)), udp_port: udp_port });
-									},
-								}
-							},
-						}
-					},
-				}
-			},
+		let upnp = map_upnp(local_addr, local.udp_port, None).map(|external| (NatMethod::Upnp, external));
+		let mapped = upnp.or_else(|| map_natpmp(local_addr, local.udp_port, None).map(|external| (NatMethod::NatPmp, external)));
+		if let Some((method, external)) = mapped {
+			return Some(NatMapping { method: method, local: local.clone(), external: external });
 		}
 	}
 	None
 }
 
+/// Renews the lease of existing mapping. Returns updated mapping, external address may have changed.
+pub fn renew_mapping(mapping: &NatMapping) -> Option<NatMapping> {
+	if let SocketAddr::V4(ref local_addr) = mapping.local.address {
+		let external = match mapping.method {
+			NatMethod::Upnp => map_upnp(local_addr, mapping.local.udp_port, Some(&mapping.external)),
+			NatMethod::NatPmp => map_natpmp(local_addr, mapping.local.udp_port, Some(&mapping.external)),
+		};
+		return external.map(|external| NatMapping { method: mapping.method, local: mapping.local.clone(), external: external });
+	}
+	None
+}
+
+fn map_upnp(local_addr: &SocketAddrV4, local_udp_port: u16, current: Option<&NodeEndpoint>) -> Option<NodeEndpoint> {
+	let gateway = match search_gateway_from_timeout(local_addr.ip().clone(), Duration::new(5, 0)) {
+		Ok(gateway) => gateway,
+		Err(ref err) => {
+			debug!("Gateway search error: {}", err);
+			return None;
+		},
+	};
+	let external_addr = match gateway.get_external_ip() {
+		Ok(external_addr) => external_addr,
+		Err(ref err) => {
+			debug!("IP request error: {}", err);
+			return None;
+		},
+	};
+
+	let local_tcp = local_addr.clone();
+	let local_udp = SocketAddrV4::new(local_addr.ip().clone(), local_udp_port);
+	let ports = match current {
+		Some(current) => gateway.add_port(PortMappingProtocol::TCP, current.address.port(), local_tcp, NAT_LEASE_SECS, "Parity Node/TCP")
+			.and_then(|_| gateway.add_port(PortMappingProtocol::UDP, current.udp_port, local_udp, NAT_LEASE_SECS, "Parity Node/UDP"))
+			.map(|_| (current.address.port(), current.udp_port))
+			.map_err(|e| e.to_string()),
+		None => gateway.add_any_port(PortMappingProtocol::TCP, local_tcp, NAT_LEASE_SECS, "Parity Node/TCP")
+			.and_then(|tcp_port| gateway.add_any_port(PortMappingProtocol::UDP, local_udp, NAT_LEASE_SECS, "Parity Node/UDP").map(|udp_port| (tcp_port, udp_port)))
+			.map_err(|e| e.to_string()),
+	};
+
+	match ports {
+		Ok((tcp_port, udp_port)) => Some(NodeEndpoint { address: SocketAddr::V4(SocketAddrV4::new(external_addr, tcp_port)), udp_port: udp_port }),
+		Err(err) => {
+			debug!("Port mapping error: {}", err);
+			None
+		},
+	}
+}
+
+fn map_natpmp(local_addr: &SocketAddrV4, local_udp_port: u16, current: Option<&NodeEndpoint>) -> Option<NodeEndpoint> {
+	let gateway = match default_gateway(local_addr.ip()) {
+		Some(gateway) => gateway,
+		None => {
+			debug!("NAT-PMP: no default gateway found");
+			return None;
+		},
+	};
+	let socket = match UdpSocket::bind("0.0.0.0:0") {
+		Ok(socket) => socket,
+		Err(ref err) => {
+			debug!("NAT-PMP: socket error: {}", err);
+			return None;
+		},
+	};
+	let gateway = SocketAddr::V4(SocketAddrV4::new(gateway, NATPMP_PORT));
+
+	let external_ip = match natpmp_request(&socket, &gateway, &[0, 0]).and_then(|r| parse_natpmp_address_response(&r)) {
+		Some(ip) => ip,
+		None => {
+			debug!("NAT-PMP: external address request failed");
+			return None;
+		},
+	};
+	let (tcp_port, udp_port) = current.map_or((local_addr.port(), local_udp_port), |c| (c.address.port(), c.udp_port));
+	let tcp_request = natpmp_map_request(NATPMP_OP_MAP_TCP, local_addr.port(), tcp_port, NAT_LEASE_SECS);
+	let udp_request = natpmp_map_request(NATPMP_OP_MAP_UDP, local_udp_port, udp_port, NAT_LEASE_SECS);
+	let tcp_port = natpmp_request(&socket, &gateway, &tcp_request).and_then(|r| parse_natpmp_map_response(&r, NATPMP_OP_MAP_TCP));
+	let udp_port = natpmp_request(&socket, &gateway, &udp_request).and_then(|r| parse_natpmp_map_response(&r, NATPMP_OP_MAP_UDP));
+	match (tcp_port, udp_port) {
+		(Some(tcp_port), Some(udp_port)) => Some(NodeEndpoint { address: SocketAddr::V4(SocketAddrV4::new(external_ip, tcp_port)), udp_port: udp_port }),
+		_ => {
+			debug!("NAT-PMP: port mapping request failed");
+			None
+		},
+	}
+}
+
+/// Sends NAT-PMP request retrying with increasing timeout as described in RFC 6886.
+fn natpmp_request(socket: &UdpSocket, gateway: &SocketAddr, request: &[u8]) -> Option<Vec<u8>> {
+	let mut buf = [0u8; 16];
+	for attempt in 0..3 {
+		if socket.set_read_timeout(Some(Duration::from_millis(250 << attempt))).is_err() {
+			return None;
+		}
+		if let Err(ref err) = socket.send_to(request, gateway) {
+			debug!("NAT-PMP: send error: {}", err);
+			return None;
+		}
+		match socket.recv_from(&mut buf) {
+			Ok((len, ref from)) if from == gateway => return Some(buf[..len].to_vec()),
+			_ => {},
+		}
+	}
+	None
+}
+
+fn natpmp_map_request(opcode: u8, internal_port: u16, external_port: u16, lease: u32) -> [u8; 12] {
+	[
+		0, opcode, 0, 0,
+		(internal_port >> 8) as u8, internal_port as u8,
+		(external_port >> 8) as u8, external_port as u8,
+		(lease >> 24) as u8, (lease >> 16) as u8, (lease >> 8) as u8, lease as u8,
+	]
+}
+
+fn parse_natpmp_address_response(response: &[u8]) -> Option<Ipv4Addr> {
+	// version, opcode, result code, seconds since epoch, external address
+	if response.len() < 12 || response[0] != 0 || response[1] != 128 || response[2] != 0 || response[3] != 0 {
+		return None;
+	}
+	Some(Ipv4Addr::new(response[8], response[9], response[10], response[11]))
+}
+
+fn parse_natpmp_map_response(response: &[u8], opcode: u8) -> Option<u16> {
+	// version, opcode, result code, seconds since epoch, internal port, mapped external port, lease
+	if response.len() < 16 || response[0] != 0 || response[1] != 128 + opcode || response[2] != 0 || response[3] != 0 {
+		return None;
+	}
+	Some(((response[10] as u16) << 8) | response[11] as u16)
+}
+
+/// Returns default IPv4 gateway from the routing table, or the first address of the local subnet.
+fn default_gateway(local: &Ipv4Addr) -> Option<Ipv4Addr> {
+	let mut routes = String::new();
+	let from_table = File::open("/proc/net/route").and_then(|mut f| f.read_to_string(&mut routes)).ok()
+		.and_then(|_| parse_default_route(&routes));
+	from_table.or_else(|| {
+		let octets = local.octets();
+		match local.is_unspecified_s() {
+			true => None,
+			false => Some(Ipv4Addr::new(octets[0], octets[1], octets[2], 1)),
+		}
+	})
+}
+
+fn parse_default_route(routes: &str) -> Option<Ipv4Addr> {
+	// Iface Destination Gateway ... with addresses in little endian hex
+	routes.lines().skip(1)
+		.map(|line| line.split_whitespace().collect::<Vec<_>>())
+		.filter(|fields| fields.len() > 2 && fields[1] == "00000000")
+		.filter_map(|fields| u32::from_str_radix(fields[2], 16).ok())
+		.find(|gateway| *gateway != 0)
+		.map(|gateway| Ipv4Addr::new(gateway as u8, (gateway >> 8) as u8, (gateway >> 16) as u8, (gateway >> 24) as u8))
+}
+
 #[test]
 fn can_select_public_address() {
 	let pub_address = select_public_address(40477);
@@ -209,6 +386,49 @@ fn can_map_external_address_or_fail() {
 	let _ = map_external_address(&NodeEndpoint { address: pub_address, udp_port: 40478 });
 }
 
+#[test]
+fn should_encode_natpmp_map_request() {
+	assert_eq!(natpmp_map_request(NATPMP_OP_MAP_TCP, 30303, 30304, 3600), [0, 2, 0, 0, 0x76, 0x5f, 0x76, 0x60, 0, 0, 0x0e, 0x10]);
+}
+
+#[test]
+fn should_parse_natpmp_responses() {
+	let address = [0, 128, 0, 0, 0, 0, 1, 0, 203, 0, 113, 7];
+	assert_eq!(parse_natpmp_address_response(&address), Some(Ipv4Addr::new(203, 0, 113, 7)));
+
+	let map = [0, 130, 0, 0, 0, 0, 1, 0, 0x76, 0x5f, 0x76, 0x60, 0, 0, 0x0e, 0x10];
+	assert_eq!(parse_natpmp_map_response(&map, NATPMP_OP_MAP_TCP), Some(30304));
+	assert_eq!(parse_natpmp_map_response(&map, NATPMP_OP_MAP_UDP), None);
+
+	// non-zero result code
+	let refused = [0, 130, 0, 2, 0, 0, 1, 0, 0x76, 0x5f, 0x76, 0x60, 0, 0, 0x0e, 0x10];
+	assert_eq!(parse_natpmp_map_response(&refused, NATPMP_OP_MAP_TCP), None);
+}
+
+#[test]
+fn should_parse_default_route() {
+	let routes = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+		eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+		eth0\t00000000\t0100A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+	assert_eq!(parse_default_route(routes), Some(Ipv4Addr::new(192, 168, 0, 1)));
+	assert_eq!(parse_default_route("Iface\tDestination\tGateway\n"), None);
+}
+
+#[test]
+fn should_group_addresses_by_subnet() {
+	let a: IpAddr = "203.0.113.7".parse().unwrap();
+	let b: IpAddr = "203.0.113.250".parse().unwrap();
+	let c: IpAddr = "203.0.114.7".parse().unwrap();
+	assert_eq!(subnet(&a), subnet(&b));
+	assert!(subnet(&a) != subnet(&c));
+	assert_eq!(subnet(&a), "203.0.113.0".parse::<IpAddr>().unwrap());
+
+	let d: IpAddr = "2001:db8:1:2::1".parse().unwrap();
+	let e: IpAddr = "2001:db8:1:3::1".parse().unwrap();
+	assert_eq!(subnet(&d), subnet(&e));
+	assert_eq!(subnet(&d), "2001:db8:1::".parse::<IpAddr>().unwrap());
+}
+
 #[test]
 fn ipv4_properties() {
 
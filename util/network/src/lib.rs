@@ -44,7 +44,7 @@
 //! }
 //!
 //! fn main () {
-//! 	let mut service = NetworkService::new(NetworkConfiguration::new_local()).expect("Error creating network service");
+//! 	let mut service = NetworkService::new(NetworkConfiguration::new_local(), None).expect("Error creating network service");
 //! 	service.register_protocol(Arc::new(MyHandler), *b"myp", &[1u8]);
 //! 	service.start().expect("Error starting service");
 //!
@@ -87,6 +87,7 @@ mod error;
 mod node_table;
 mod stats;
 mod ip_utils;
+mod connection_filter;
 
 #[cfg(test)]
 mod tests;
@@ -94,14 +95,16 @@ mod tests;
 pub use host::PeerId;
 pub use host::PacketId;
 pub use host::NetworkContext;
+pub use session::SessionInfo;
 pub use service::NetworkService;
 pub use host::NetworkIoMessage;
 pub use error::NetworkError;
-pub use host::NetworkConfiguration;
-pub use stats::NetworkStats;
+pub use host::{NetworkConfiguration, new_node_key};
+pub use stats::{NetworkStats, PeerTraffic};
 
 use io::TimerToken;
-pub use node_table::is_valid_node_url;
+pub use node_table::{is_valid_node_url, NodeId};
+pub use connection_filter::{ConnectionFilter, ConnectionDirection};
 
 const PROTOCOL_VERSION: u32 = 4;
 
@@ -225,12 +225,22 @@ impl NodeTable {
 		refs.iter().map(|n| n.id.clone()).collect()
 	}
 
+	/// Returns node URLs sorted by number of failures
+	pub fn urls(&self) -> Vec<String> {
+		self.nodes().iter().filter_map(|id| self.nodes.get(id)).map(|n| n.to_string()).collect()
+	}
+
 	/// Unordered list of all entries
 	pub fn unordered_entries(&self) -> Vec<NodeEntry> {
 		// preserve failure counter
 		self.nodes.values().map(|n| NodeEntry { endpoint: n.endpoint.clone(), id: n.id.clone() }).collect()
 	}
 
+	/// Get particular node
+	pub fn get(&self, id: &NodeId) -> Option<&Node> {
+		self.nodes.get(id)
+	}
+
 	/// Get particular node
 	pub fn get_mut(&mut self, id: &NodeId) -> Option<&mut Node> {
 		self.nodes.get_mut(id)
@@ -266,7 +276,8 @@ impl NodeTable {
 		self.useless_nodes.clear();
 	}
 
-	fn save(&self) {
+	/// Writes the table to disk, it's loaded back on restart.
+	pub fn save(&self) {
 		if let Some(ref path) = self.path {
 			let mut path_buf = PathBuf::from(path);
 			if let Err(e) = fs::create_dir_all(path_buf.as_path()) {
@@ -409,6 +420,23 @@ mod tests {
 		assert_eq!(r[0][..], id3[..]);
 		assert_eq!(r[1][..], id2[..]);
 		assert_eq!(r[2][..], id1[..]);
+
+		let urls = table.urls();
+		assert_eq!(urls.len(), 3);
+		assert!(urls[0].starts_with("enode://c979fb57"));
+		assert!(urls[2].starts_with("enode://a979fb57"));
+	}
+
+	#[test]
+	fn table_save_without_drop() {
+		let temp_path = RandomTempPath::create_dir();
+		let path = Some(temp_path.as_path().to_str().unwrap().to_owned());
+		let node = Node::from_str("enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
+		let mut table = NodeTable::new(path.clone());
+		table.add_node(node);
+		table.save();
+
+		assert_eq!(NodeTable::new(path).nodes().len(), 1);
 	}
 
 	#[test]
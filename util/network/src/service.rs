@@ -15,9 +15,10 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use {NetworkProtocolHandler, NetworkConfiguration, NonReservedPeerMode};
+use connection_filter::ConnectionFilter;
 use error::NetworkError;
 use host::{Host, NetworkContext, NetworkIoMessage, ProtocolId};
-use stats::NetworkStats;
+use stats::{NetworkStats, PeerTraffic};
 use io::*;
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -49,11 +50,12 @@ pub struct NetworkService {
 	panic_handler: Arc<PanicHandler>,
 	host_handler: Arc<HostHandler>,
 	config: NetworkConfiguration,
+	filter: Option<Arc<ConnectionFilter>>,
 }
 
 impl NetworkService {
 	/// Starts IO event loop
-	pub fn new(config: NetworkConfiguration) -> Result<NetworkService, NetworkError> {
+	pub fn new(config: NetworkConfiguration, filter: Option<Arc<ConnectionFilter>>) -> Result<NetworkService, NetworkError> {
 		let host_handler = Arc::new(HostHandler { public_url: RwLock::new(None) });
 		let panic_handler = PanicHandler::new_in_arc();
 		let io_service = try!(IoService::<NetworkIoMessage>::start());
@@ -69,6 +71,7 @@ impl NetworkService {
 			host: RwLock::new(None),
 			config: config,
 			host_handler: host_handler,
+			filter: filter,
 		})
 	}
 
@@ -114,11 +117,30 @@ impl NetworkService {
 		host.as_ref().map(|h| h.local_url())
 	}
 
+	/// Returns traffic of connected peers. Empty if the network is not started.
+	pub fn peer_traffic(&self) -> Vec<PeerTraffic> {
+		let host = self.host.read();
+		host.as_ref().map_or_else(Vec::new, |h| h.peer_traffic())
+	}
+
+	/// Returns URLs of nodes in the node table, most reliable first. Empty if the network is not started.
+	pub fn node_table(&self) -> Vec<String> {
+		let host = self.host.read();
+		host.as_ref().map_or_else(Vec::new, |h| h.node_table())
+	}
+
+	/// Returns estimated offset of the local clock against clocks of peers found by discovery, in seconds.
+	/// Positive if the local clock is ahead. None if the network is not started or no peer pinged us yet.
+	pub fn peer_clock_drift(&self) -> Option<i64> {
+		let host = self.host.read();
+		host.as_ref().and_then(|h| h.peer_clock_drift())
+	}
+
 	/// Start network IO
 	pub fn start(&self) -> Result<(), NetworkError> {
 		let mut host = self.host.write();
 		if host.is_none() {
-			let h = Arc::new(try!(Host::new(self.config.clone(), self.stats.clone())));
+			let h = Arc::new(try!(Host::new(self.config.clone(), self.stats.clone(), self.filter.clone())));
 			try!(self.io_service.register_handler(h.clone()));
 			*host = Some(h);
 		}
@@ -178,6 +200,13 @@ impl NetworkService {
 			host.with_context(protocol, &io, action);
 		};
 	}
+
+	/// Evaluates function in the network context. Returns `None` if the network isn't started.
+	pub fn with_context_eval<F, T>(&self, protocol: ProtocolId, action: F) -> Option<T> where F: Fn(&NetworkContext) -> T {
+		let io = IoContext::new(self.io_service.channel(), 0);
+		let host = self.host.read();
+		host.as_ref().map(|host| host.with_context_eval(protocol, &io, action))
+	}
 }
 
 impl MayPanic for NetworkService {
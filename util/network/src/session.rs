@@ -72,6 +72,7 @@ pub enum SessionData {
 }
 
 /// Shared session information
+#[derive(Debug, Clone)]
 pub struct SessionInfo {
 	/// Peer public key
 	pub id: Option<NodeId>,
@@ -85,6 +86,17 @@ pub struct SessionInfo {
 	pub ping_ms: Option<u64>,
 	/// True if this session was originated by us.
 	pub originated: bool,
+	/// Total size of packets sent to the peer.
+	pub bytes_sent: usize,
+	/// Total size of packets received from the peer.
+	pub bytes_received: usize,
+}
+
+impl SessionInfo {
+	/// Protocols agreed with the peer, as `name/version` strings.
+	pub fn capability_names(&self) -> Vec<String> {
+		self.capabilities.iter().map(|c| format!("{}/{}", String::from_utf8_lossy(&c.protocol), c.version)).collect()
+	}
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -109,7 +121,7 @@ impl Decodable for PeerCapabilityInfo {
 	}
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct SessionCapabilityInfo {
 	pub protocol: [u8; 3],
 	pub version: u8,
@@ -145,6 +157,8 @@ impl Session {
 				capabilities: Vec::new(),
 				ping_ms: None,
 				originated: originated,
+				bytes_sent: 0,
+				bytes_received: 0,
 			},
 			ping_time_ns: 0,
 			pong_time_ns: None,
@@ -328,6 +342,7 @@ impl Session {
 		if packet.data.len() < 2 {
 			return Err(From::from(NetworkError::BadProtocol));
 		}
+		self.info.bytes_received += packet.data.len();
 		let packet_id = packet.data[0];
 		if packet_id != PACKET_HELLO && packet_id != PACKET_DISCONNECT && !self.had_hello {
 			return Err(From::from(NetworkError::BadProtocol));
@@ -482,7 +497,9 @@ impl Session {
 				warn!(target:"network", "Unexpected send request");
 			},
 			State::Session(ref mut s) => {
-				try!(s.send_packet(io, &rlp.out()))
+				let data = rlp.out();
+				self.info.bytes_sent += data.len();
+				try!(s.send_packet(io, &data))
 			},
 		}
 		Ok(())
@@ -16,6 +16,7 @@
 
 //! Network Statistics
 use std::sync::atomic::*;
+use node_table::NodeId;
 
 /// Network statistics structure
 #[derive(Default, Debug)]
@@ -26,6 +27,31 @@ pub struct NetworkStats {
 	send: AtomicUsize,
 	/// Total number of sessions created
 	sessions: AtomicUsize,
+	/// Bytes received per second, measured over the last update period
+	recv_rate: AtomicUsize,
+	/// Bytes sent per second, measured over the last update period
+	send_rate: AtomicUsize,
+	/// Bytes received at the last update
+	last_recv: AtomicUsize,
+	/// Bytes sent at the last update
+	last_send: AtomicUsize,
+	/// Upload rate exceeds configured limit
+	upload_throttled: AtomicBool,
+	/// Download rate exceeds configured limit
+	download_throttled: AtomicBool,
+}
+
+/// Traffic of a single peer session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerTraffic {
+	/// Peer public key
+	pub id: Option<NodeId>,
+	/// Peer client ID
+	pub client_version: String,
+	/// Bytes sent to the peer
+	pub bytes_sent: usize,
+	/// Bytes received from the peer
+	pub bytes_received: usize,
 }
 
 impl NetworkStats {
@@ -65,12 +91,68 @@ impl NetworkStats {
 		self.sessions.load(Ordering::Relaxed)
 	}
 
+	/// Get bytes sent per second.
+	#[inline]
+	pub fn send_rate(&self) -> usize {
+		self.send_rate.load(Ordering::Relaxed)
+	}
+
+	/// Get bytes received per second.
+	#[inline]
+	pub fn recv_rate(&self) -> usize {
+		self.recv_rate.load(Ordering::Relaxed)
+	}
+
+	/// Returns true if the upload rate exceeds configured limit.
+	#[inline]
+	pub fn is_upload_throttled(&self) -> bool {
+		self.upload_throttled.load(Ordering::Relaxed)
+	}
+
+	/// Returns true if the download rate exceeds configured limit.
+	#[inline]
+	pub fn is_download_throttled(&self) -> bool {
+		self.download_throttled.load(Ordering::Relaxed)
+	}
+
+	/// Recalculate transfer rates from the traffic since the last update `elapsed_ms` milliseconds ago
+	/// and check them against given limits in bytes per second.
+	pub fn update_rates(&self, elapsed_ms: u64, max_send_rate: Option<usize>, max_recv_rate: Option<usize>) {
+		let elapsed_ms = ::std::cmp::max(elapsed_ms, 1) as usize;
+		let send = self.send();
+		let recv = self.recv();
+		let send_rate = (send - self.last_send.swap(send, Ordering::Relaxed)) * 1000 / elapsed_ms;
+		let recv_rate = (recv - self.last_recv.swap(recv, Ordering::Relaxed)) * 1000 / elapsed_ms;
+		self.send_rate.store(send_rate, Ordering::Relaxed);
+		self.recv_rate.store(recv_rate, Ordering::Relaxed);
+		self.upload_throttled.store(max_send_rate.map_or(false, |max| send_rate > max), Ordering::Relaxed);
+		self.download_throttled.store(max_recv_rate.map_or(false, |max| recv_rate > max), Ordering::Relaxed);
+	}
+
 	/// Create a new empty instance.
 	pub fn new() -> NetworkStats {
-		NetworkStats {
-			recv: AtomicUsize::new(0),
-			send: AtomicUsize::new(0),
-			sessions: AtomicUsize::new(0),
-		}
+		NetworkStats::default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::NetworkStats;
+
+	#[test]
+	fn should_calculate_rates_and_throttle() {
+		let stats = NetworkStats::new();
+		stats.inc_send(4000);
+		stats.inc_recv(1000);
+		stats.update_rates(2000, Some(1024), Some(1024));
+		assert_eq!(stats.send_rate(), 2000);
+		assert_eq!(stats.recv_rate(), 500);
+		assert!(stats.is_upload_throttled());
+		assert!(!stats.is_download_throttled());
+
+		stats.update_rates(1000, Some(1024), Some(1024));
+		assert_eq!(stats.send_rate(), 0);
+		assert!(!stats.is_upload_throttled());
+		assert_eq!(stats.send(), 4000);
 	}
 }
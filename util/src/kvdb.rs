@@ -27,6 +27,8 @@ use rocksdb::{DB, Writable, WriteBatch, WriteOptions, IteratorMode, DBIterator,
 
 const DB_BACKGROUND_FLUSHES: i32 = 2;
 const DB_BACKGROUND_COMPACTIONS: i32 = 2;
+/// Number of key-value pairs written at once when copying a database.
+const BACKUP_BATCH_SIZE: usize = 1024;
 
 /// Write transaction. Batches a sequence of put/delete operations for efficiency.
 pub struct DBTransaction {
@@ -459,12 +461,90 @@ impl Database {
 		}
 	}
 
+	/// Get database iterator for flushed data starting at the first key not lesser than `prefix`.
+	/// Callers should stop once keys no longer start with the prefix.
+	pub fn iter_from_prefix(&self, col: Option<u32>, prefix: &[u8]) -> Option<DatabaseIterator> {
+		match *self.db.read() {
+			Some(DBAndColumns { ref db, ref cfs }) => {
+				let iter = col.map_or_else(|| db.iterator(IteratorMode::From(prefix, Direction::Forward)),
+					|c| db.iterator_cf(cfs[c as usize], IteratorMode::From(prefix, Direction::Forward)).unwrap());
+				Some(DatabaseIterator { iter: iter })
+			},
+			None => None,
+		}
+	}
+
 	/// Close the database
 	fn close(&self) {
 		*self.db.write() = None;
 		self.overlay.write().clear();
 	}
 
+	/// Create a point-in-time copy of the database at given path, which must not exist yet.
+	/// This may be called while the database is in use.
+	pub fn backup(&self, path: &str) -> Result<(), String> {
+		if fs::metadata(path).is_ok() {
+			return Err(format!("Backup destination {} already exists", path));
+		}
+
+		try!(self.flush());
+
+		if self.config.wal {
+			// keeps the database from being closed or restored while the checkpoint is taken.
+			let db = self.db.read();
+			if db.is_none() {
+				return Err("Database is closed".into());
+			}
+			return checkpoint(&self.path, path);
+		}
+
+		// without a write-ahead log the most recent writes only live in memory,
+		// so the data has to be copied through the database itself.
+		self.copy_to(path)
+	}
+
+	// copies every key of the database into a new database at `path`.
+	fn copy_to(&self, path: &str) -> Result<(), String> {
+		let iterators: Vec<(Option<u32>, DBIterator)> = {
+			// no writes can happen while the lock is held, so every column is seen at the same point.
+			let db = self.db.write();
+			match *db {
+				Some(DBAndColumns { ref db, ref cfs }) => match self.config.columns {
+					None => vec![(None, db.iterator(IteratorMode::Start))],
+					Some(columns) => try!((0..columns)
+						.map(|c| db.iterator_cf(cfs[c as usize], IteratorMode::Start).map(|iter| (Some(c), iter)))
+						.collect()),
+				},
+				None => return Err("Database is closed".into()),
+			}
+		};
+
+		let mut config = self.config.clone();
+		config.wal = true;
+		let backup = try!(Database::open(&config, path));
+
+		for (col, iter) in iterators {
+			let mut transaction = backup.transaction();
+			let mut pending = 0;
+			for (key, value) in iter {
+				transaction.put(col, &key, &value);
+				pending += 1;
+				if pending == BACKUP_BATCH_SIZE {
+					try!(backup.write(mem::replace(&mut transaction, backup.transaction())));
+					pending = 0;
+				}
+			}
+			try!(backup.write(transaction));
+		}
+
+		Ok(())
+	}
+
+	/// The path this database is stored at.
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
 	/// Restore the database from a copy at given path.
 	pub fn restore(&self, new_db: &str) -> Result<(), UtilError> {
 		self.close();
@@ -506,6 +586,95 @@ impl Database {
 	}
 }
 
+// name and length of the manifest the database at `path` currently uses.
+fn current_manifest(path: &Path) -> io::Result<(String, u64)> {
+	let mut current = String::new();
+	try!(try!(fs::File::open(path.join("CURRENT"))).read_to_string(&mut current));
+	let manifest = current.trim().to_owned();
+	let len = try!(fs::metadata(path.join(&manifest))).len();
+	Ok((manifest, len))
+}
+
+// copies the first `len` bytes of `from` to a new file at `to`.
+fn copy_prefix(from: &Path, to: &Path, len: u64) -> io::Result<()> {
+	let source = try!(fs::File::open(from));
+	let mut dest = try!(fs::File::create(to));
+	try!(io::copy(&mut source.take(len), &mut dest));
+	dest.sync_all()
+}
+
+// links (or copies when linking is not possible) the files making up the database at `source`.
+// returns `false` when a file disappeared in the meantime.
+fn link_files(source: &Path, dest: &Path, manifest: &str, manifest_len: u64) -> io::Result<bool> {
+	try!(fs::create_dir_all(dest));
+	try!(copy_prefix(&source.join(manifest), &dest.join(manifest), manifest_len));
+
+	for entry in try!(fs::read_dir(source)) {
+		let entry = try!(entry);
+		let name = entry.file_name().to_string_lossy().into_owned();
+		let from = entry.path();
+		let to = dest.join(&name);
+		let result = if name.ends_with(".sst") {
+			// table files are never modified once written.
+			fs::hard_link(&from, &to).or_else(|_| fs::copy(&from, &to).map(|_| ()))
+		} else if name.ends_with(".log") || name.starts_with("OPTIONS-") || name == "IDENTITY" {
+			fs::copy(&from, &to).map(|_| ())
+		} else {
+			// CURRENT is written last, the lock and info logs belong to the running instance.
+			continue;
+		};
+		match result {
+			Ok(()) => {},
+			Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+			Err(e) => return Err(e),
+		}
+	}
+
+	let mut current = try!(fs::File::create(dest.join("CURRENT")));
+	try!(current.write_all(format!("{}\n", manifest).as_bytes()));
+	try!(current.sync_all());
+	Ok(true)
+}
+
+/// Creates a consistent checkpoint of the RocksDB database at `source` in `dest`, which must not exist yet.
+///
+/// Table files are hard-linked, so the checkpoint takes little time and space when `dest`
+/// is on the same filesystem; the manifest and write-ahead logs are copied.
+/// The database may be written to meanwhile, even by another process: a compaction or flush
+/// changes the manifest, in which case the checkpoint is taken again.
+pub fn checkpoint(source: &str, dest: &str) -> Result<(), String> {
+	const MAX_ATTEMPTS: usize = 16;
+
+	let source = Path::new(source);
+	let dest = Path::new(dest);
+	if fs::metadata(dest).is_ok() {
+		return Err(format!("Checkpoint destination {} already exists", dest.display()));
+	}
+
+	for _ in 0..MAX_ATTEMPTS {
+		let (manifest, len) = try!(current_manifest(source).map_err(|e| format!("Cannot read database manifest: {}", e)));
+		let linked = link_files(source, dest, &manifest, len);
+		let unchanged = match current_manifest(source) {
+			Ok((new_manifest, new_len)) => new_manifest == manifest && new_len == len,
+			Err(_) => false,
+		};
+
+		match linked {
+			Ok(true) if unchanged => return Ok(()),
+			Ok(_) => {},
+			Err(e) => {
+				let _ = fs::remove_dir_all(dest);
+				return Err(format!("Cannot create checkpoint at {}: {}", dest.display(), e));
+			},
+		}
+
+		trace!(target: "db", "Database at {} changed while taking checkpoint, retrying", source.display());
+		try!(fs::remove_dir_all(dest).map_err(|e| format!("Cannot remove incomplete checkpoint: {}", e)));
+	}
+
+	Err(format!("Database at {} is changing too fast to take a checkpoint", source.display()))
+}
+
 #[cfg(test)]
 mod tests {
 	use hash::*;
@@ -566,6 +735,39 @@ mod tests {
 		assert_eq!(&*db.get(None, &key1).unwrap().unwrap(), b"horse");
 	}
 
+	#[test]
+	fn backup() {
+		let path = RandomTempPath::create_dir();
+		let config = DatabaseConfig::with_columns(Some(2));
+		let db = Database::open(&config, path.as_path().to_str().unwrap()).unwrap();
+
+		let mut batch = db.transaction();
+		batch.put(Some(0), b"cat", b"meow");
+		batch.put(Some(1), b"dog", b"woof");
+		db.write(batch).unwrap();
+
+		let mut batch = db.transaction();
+		batch.put(Some(1), b"cow", b"moo");
+		db.write_buffered(batch);
+
+		let backup_path = RandomTempPath::new();
+		let backup_path = backup_path.as_path().to_str().unwrap();
+		db.backup(backup_path).unwrap();
+
+		// later writes don't make it into the copy.
+		let mut batch = db.transaction();
+		batch.put(Some(0), b"fox", b"?");
+		db.write(batch).unwrap();
+
+		let backup = Database::open(&config, backup_path).unwrap();
+		assert_eq!(&*backup.get(Some(0), b"cat").unwrap().unwrap(), b"meow");
+		assert_eq!(&*backup.get(Some(1), b"dog").unwrap().unwrap(), b"woof");
+		assert_eq!(&*backup.get(Some(1), b"cow").unwrap().unwrap(), b"moo");
+		assert!(backup.get(Some(0), b"fox").unwrap().is_none());
+
+		assert!(db.backup(backup_path).is_err());
+	}
+
 	#[test]
 	fn kvdb() {
 		let path = RandomTempPath::create_dir();
@@ -19,8 +19,9 @@
 mod tests;
 
 use std::collections::BTreeMap;
-use std::fs;
+use std::fs::{self, File};
 use std::fmt;
+use std::io::{Read, Write, ErrorKind};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -169,6 +170,10 @@ fn database_path(path: &Path) -> PathBuf {
 	temp_path
 }
 
+/// Name of the file recording the last completed step of an interrupted migration.
+const CHECKPOINT_FILE_NAME: &'static str = "migration_checkpoint";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum TempIndex {
 	One,
 	Two,
@@ -193,6 +198,59 @@ impl TempIndex {
 
 		buf
 	}
+
+	fn from_str(s: &str) -> Option<Self> {
+		match s {
+			"1" => Some(TempIndex::One),
+			"2" => Some(TempIndex::Two),
+			_ => None,
+		}
+	}
+
+	fn as_str(&self) -> &'static str {
+		match *self {
+			TempIndex::One => "1",
+			TempIndex::Two => "2",
+		}
+	}
+}
+
+fn checkpoint_path(db_root: &Path) -> PathBuf {
+	let mut buf = db_root.to_owned();
+	buf.push(CHECKPOINT_FILE_NAME);
+	buf
+}
+
+/// Reads the version reached by an interrupted migration of `source` and
+/// the temporary database holding it.
+fn read_checkpoint(db_root: &Path, source: &str) -> Option<(u32, TempIndex)> {
+	let mut s = String::new();
+	if File::open(checkpoint_path(db_root)).and_then(|mut f| f.read_to_string(&mut s)).is_err() {
+		return None;
+	}
+
+	let parts: Vec<_> = s.trim().split(' ').collect();
+	match parts.len() {
+		3 if parts[0] == source => match (parts[1].parse().ok(), TempIndex::from_str(parts[2])) {
+			(Some(version), Some(idx)) => Some((version, idx)),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Records that `source` has been migrated to `version` in the given temporary database.
+fn write_checkpoint(db_root: &Path, source: &str, version: u32, idx: TempIndex) -> Result<(), Error> {
+	let mut file = try!(File::create(checkpoint_path(db_root)));
+	try!(file.write_all(format!("{} {} {}", source, version, idx.as_str()).as_bytes()));
+	Ok(())
+}
+
+fn clear_checkpoint(db_root: &Path) -> Result<(), Error> {
+	match fs::remove_file(checkpoint_path(db_root)) {
+		Err(ref e) if e.kind() != ErrorKind::NotFound => Err(Error::Custom(format!("{}", e))),
+		_ => Ok(()),
+	}
 }
 
 /// Manages database migration.
@@ -225,11 +283,33 @@ impl Manager {
 
 	/// Performs migration in order, starting with a source path, migrating between two temporary databases,
 	/// and producing a path where the final migration lives.
+	///
+	/// Every completed step is checkpointed, so if the process is interrupted the next
+	/// execution for the same source resumes from the last completed step.
 	pub fn execute(&mut self, old_path: &Path, version: u32) -> Result<PathBuf, Error> {
 		let config = self.config.clone();
+		let db_root = database_path(old_path);
+		let source = try!(old_path.file_name().and_then(|n| n.to_str()).ok_or(Error::MigrationImpossible)).to_owned();
+
+		// look for a previously interrupted run we can pick up from.
+		let resume = match read_checkpoint(&db_root, &source) {
+			Some((reached, idx)) if reached > version && idx.path(&db_root).exists() => Some((reached, idx)),
+			Some(_) => {
+				try!(clear_checkpoint(&db_root));
+				None
+			},
+			None => None,
+		};
+
+		let version = resume.map_or(version, |(reached, _)| reached);
 		let migrations = self.migrations_from(version);
 		trace!(target: "migration", "Total migrations to execute for version {}: {}", version, migrations.len());
-		if migrations.is_empty() { return Err(Error::MigrationImpossible) };
+		if migrations.is_empty() {
+			return match resume {
+				Some((_, idx)) => Ok(idx.path(&db_root)),
+				None => Err(Error::MigrationImpossible),
+			};
+		}
 
 		let columns = migrations.iter().nth(0).and_then(|m| m.pre_columns());
 
@@ -242,21 +322,32 @@ impl Manager {
 			wal: true,
 		};
 
-		let db_root = database_path(old_path);
-		let mut temp_idx = TempIndex::One;
+		let (start_path, mut temp_idx) = match resume {
+			Some((reached, idx)) => {
+				flushln!("Resuming interrupted migration of {} from version {}", source, reached);
+				let mut next = idx;
+				next.swap();
+				(idx.path(&db_root), next)
+			},
+			// start with the old db.
+			None => (old_path.to_owned(), TempIndex::One),
+		};
 		let mut temp_path = temp_idx.path(&db_root);
 
-		// start with the old db.
-		let old_path_str = try!(old_path.to_str().ok_or(Error::MigrationImpossible));
-		let mut cur_db = Arc::new(try!(Database::open(&db_config, old_path_str).map_err(Error::Custom)));
+		let start_path_str = try!(start_path.to_str().ok_or(Error::MigrationImpossible));
+		let mut cur_db = Arc::new(try!(Database::open(&db_config, start_path_str).map_err(Error::Custom)));
+
+		let total = migrations.len();
+		for (step, migration) in migrations.into_iter().enumerate() {
+			flush!("Migrating {} to version {} ({}/{})", source, migration.version(), step + 1, total);
 
-		for migration in migrations {
 			// Change number of columns in new db
 			let current_columns = db_config.columns;
 			db_config.columns = migration.columns();
 
-			// open the target temporary database.
+			// open the target temporary database, discarding leftovers of an interrupted step.
 			temp_path = temp_idx.path(&db_root);
+			let _ = fs::remove_dir_all(&temp_path);
 			let temp_path_str = try!(temp_path.to_str().ok_or(Error::MigrationImpossible));
 			let mut new_db = try!(Database::open(&db_config, temp_path_str).map_err(Error::Custom));
 
@@ -271,6 +362,11 @@ impl Manager {
 					}
 				}
 			}
+			flushln!(" done");
+
+			// record the completed step before the previous database is removed.
+			try!(write_checkpoint(&db_root, &source, migration.version(), temp_idx));
+
 			// next iteration, we will migrate from this db into the other temp.
 			cur_db = Arc::new(new_db);
 			temp_idx.swap();
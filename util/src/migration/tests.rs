@@ -82,6 +82,18 @@ impl SimpleMigration for Migration1 {
 	}
 }
 
+struct Interrupted(u32);
+
+impl Migration for Interrupted {
+	fn columns(&self) -> Option<u32> { None }
+
+	fn version(&self) -> u32 { self.0 }
+
+	fn migrate(&mut self, _source: Arc<Database>, _config: &Config, _dest: &mut Database, _col: Option<u32>) -> Result<(), Error> {
+		Err(Error::Custom("interrupted".into()))
+	}
+}
+
 struct AddsColumn;
 
 impl Migration for AddsColumn {
@@ -203,6 +215,27 @@ fn noop_and_second_migration() {
 	verify_migration(&end_path, expected);
 }
 
+#[test]
+fn resumes_interrupted_migration() {
+	let dir = RandomTempPath::create_dir();
+	let db_path = db_path(dir.as_path());
+	make_db(&db_path, map![vec![] => vec![], vec![1] => vec![1]]);
+	let expected = map![vec![0x11] => vec![], vec![1, 0x11] => vec![]];
+
+	let mut manager = Manager::new(Config::default());
+	manager.add_migration(Migration0).unwrap();
+	manager.add_migration(Interrupted(2)).unwrap();
+	assert!(manager.execute(&db_path, 0).is_err());
+
+	// the first step has completed already, so it must not run again.
+	let mut manager = Manager::new(Config::default());
+	manager.add_migration(Interrupted(1)).unwrap();
+	manager.add_migration(Migration1).unwrap();
+	let end_path = manager.execute(&db_path, 0).unwrap();
+
+	verify_migration(&end_path, expected);
+}
+
 #[test]
 fn is_migration_needed() {
 	let mut manager = Manager::new(Config::default());
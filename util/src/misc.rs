@@ -43,6 +43,13 @@ pub fn version() -> String {
 	format!("Parity/v{}-unstable{}{}{}{}/{}-{}{}{}/rustc{}", env!("CARGO_PKG_VERSION"), sha3_dash, sha3, date_dash, commit_date, Target::arch(), Target::os(), env_dash, env, rustc_version())
 }
 
+/// Get the platform identifier of this build, e.g. `x86_64-linux-gnu`.
+pub fn platform() -> String {
+	let env = Target::env();
+	let env_dash = if env.is_empty() { "" } else { "-" };
+	format!("{}-{}{}{}", Target::arch(), Target::os(), env_dash, env)
+}
+
 /// Get the standard version data for this software.
 pub fn version_data() -> Bytes {
 	let mut s = RlpStream::new_list(4);